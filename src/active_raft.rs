@@ -11,6 +11,48 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// Raft election timeout range as a number of ticks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ElectionTicks {
+    min: Option<usize>,
+    max: Option<usize>,
+}
+
+impl ElectionTicks {
+    /// Create election timeouts, using raft defaults for the ones not set
+    ///
+    /// ### Arguments
+    ///
+    /// * `min` - Minimum election timeout in ticks
+    /// * `max` - Maximum election timeout in ticks
+    pub fn new(min: Option<usize>, max: Option<usize>) -> Self {
+        Self { min, max }
+    }
+
+    /// Minimum election timeout in ticks
+    pub fn min(&self) -> usize {
+        self.min
+            .unwrap_or_else(|| raft::Config::default().election_tick)
+    }
+
+    /// Maximum election timeout in ticks
+    pub fn max(&self) -> usize {
+        self.max.unwrap_or_else(|| 2 * self.min())
+    }
+
+    /// Check elections cannot time out before a heartbeat is due,
+    /// and the timeout range is not empty.
+    pub fn validate(self) -> Result<Self, &'static str> {
+        if self.min() <= raft::Config::default().heartbeat_tick {
+            return Err("Raft election timeout must be larger than the heartbeat interval");
+        }
+        if self.max() <= self.min() {
+            return Err("Raft maximum election timeout must be larger than the minimum");
+        }
+        Ok(self)
+    }
+}
+
 /// Provide RAFT loop and in/out channels to interact with it.
 ///
 pub struct ActiveRaft {
@@ -33,6 +75,8 @@ pub struct ActiveRaft {
     raft_peers_to_connect: Vec<SocketAddr>,
     /// Collection of the peer expected to be connected.
     raft_peer_addrs: Vec<SocketAddr>,
+    /// Index of the last snapshot sent to the running RaftNode.
+    last_snapshot_idx: Option<u64>,
 }
 
 impl ActiveRaft {
@@ -42,6 +86,7 @@ impl ActiveRaft {
         node_specs: &[NodeSpec],
         use_raft: bool,
         tick_timeout_duration: Duration,
+        election_ticks: ElectionTicks,
         raft_db: SimpleDb,
     ) -> Self {
         let peers: Vec<u64> = (0..node_specs.len()).map(|idx| idx as u64 + 1).collect();
@@ -60,6 +105,9 @@ impl ActiveRaft {
                 peers,
                 max_size_per_msg: 4096,
                 max_inflight_msgs: 256,
+                election_tick: election_ticks.min(),
+                min_election_tick: election_ticks.min(),
+                max_election_tick: election_ticks.max(),
                 tag: format!("[id={peer_id}]"),
                 ..Default::default()
             },
@@ -92,6 +140,7 @@ impl ActiveRaft {
             peer_addr,
             raft_peers_to_connect,
             raft_peer_addrs,
+            last_snapshot_idx: None,
         }
     }
 
@@ -121,6 +170,11 @@ impl ActiveRaft {
         self.raft_peer_addrs.iter()
     }
 
+    /// Index of the last snapshot sent to the raft loop
+    pub fn last_snapshot_idx(&self) -> Option<u64> {
+        self.last_snapshot_idx
+    }
+
     /// Blocks & waits for a next event from a peer.
     pub fn raft_loop(&self) -> impl Future<Output = ()> {
         let raft_node = self.raft_node.clone();
@@ -203,6 +257,51 @@ impl ActiveRaft {
             self.cmd_tx
                 .send(RaftCmd::Snapshot { idx, data, backup })
                 .unwrap();
+            self.last_snapshot_idx = Some(idx);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn election_ticks_validation() {
+        //
+        // Arrange
+        //
+        let heartbeat = raft::Config::default().heartbeat_tick;
+        let default_ticks = ElectionTicks::default();
+        let valid_ticks = ElectionTicks::new(Some(heartbeat + 1), Some(heartbeat + 5));
+        let below_tick = ElectionTicks::new(Some(0), None);
+        let below_heartbeat = ElectionTicks::new(Some(heartbeat), None);
+        let empty_range = ElectionTicks::new(Some(heartbeat + 5), Some(heartbeat + 5));
+
+        //
+        // Act
+        //
+        let results: Vec<_> = vec![
+            default_ticks,
+            valid_ticks,
+            below_tick,
+            below_heartbeat,
+            empty_range,
+        ]
+        .into_iter()
+        .map(|ticks| ticks.validate().is_ok())
+        .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(results, vec![true, true, false, false, false]);
+        assert_eq!(
+            (default_ticks.min(), default_ticks.max()),
+            (
+                raft::Config::default().election_tick,
+                2 * default_ticks.min()
+            )
+        );
+    }
+}
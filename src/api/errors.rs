@@ -12,8 +12,10 @@ pub enum ApiErrorType {
     Generic(String),
     InvalidPassphrase,
     BlankPassphrase,
+    WeakPassphrase(String),
     InvalidRequestBody,
     CannotParseAddress,
+    InvalidAddress(String),
     CannotAccessWallet,
     CannotAccessUserNode,
     CannotAccessMinerNode,
@@ -22,11 +24,19 @@ pub enum ApiErrorType {
     CannotSaveAddressesToWallet,
     CannotFetchBalance,
     NoDataFoundForKey,
+    NodeNotReady(String),
+    NodeBusy(String),
+    NodeOverloaded(String),
+    DbAccess(String),
+    WalletDecrypt(String),
+    CommsInject(String),
+    ThreadedCallFailed(String),
     InternalError,
     Unauthorized,
     MethodNotFound,
     MethodNotAllowed,
     BadRequest,
+    BatchTooLarge(usize),
 }
 
 impl ApiError {
@@ -40,6 +50,42 @@ impl ApiError {
     }
 }
 
+impl ApiErrorType {
+    /// HTTP status code to reply with when this error is not otherwise specified
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiErrorType::InvalidPassphrase | ApiErrorType::Unauthorized => {
+                StatusCode::UNAUTHORIZED
+            }
+            ApiErrorType::BlankPassphrase
+            | ApiErrorType::WeakPassphrase(_)
+            | ApiErrorType::InvalidRequestBody
+            | ApiErrorType::CannotParseAddress
+            | ApiErrorType::InvalidAddress(_)
+            | ApiErrorType::BadRequest
+            | ApiErrorType::BatchTooLarge(_) => StatusCode::BAD_REQUEST,
+            ApiErrorType::NoDataFoundForKey | ApiErrorType::MethodNotFound => StatusCode::NOT_FOUND,
+            ApiErrorType::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ApiErrorType::NodeNotReady(_)
+            | ApiErrorType::NodeBusy(_)
+            | ApiErrorType::NodeOverloaded(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiErrorType::Generic(_)
+            | ApiErrorType::CannotAccessWallet
+            | ApiErrorType::CannotAccessUserNode
+            | ApiErrorType::CannotAccessMinerNode
+            | ApiErrorType::CannotAccessComputeNode
+            | ApiErrorType::CannotAccessPeerUserNode
+            | ApiErrorType::CannotSaveAddressesToWallet
+            | ApiErrorType::CannotFetchBalance
+            | ApiErrorType::DbAccess(_)
+            | ApiErrorType::WalletDecrypt(_)
+            | ApiErrorType::CommsInject(_)
+            | ApiErrorType::ThreadedCallFailed(_)
+            | ApiErrorType::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
 impl std::fmt::Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.message)
@@ -54,8 +100,10 @@ impl std::fmt::Display for ApiErrorType {
             ApiErrorType::Generic(message) => write!(f, "Generic error: {message}"),
             ApiErrorType::InvalidPassphrase => write!(f, "Invalid passphrase"),
             ApiErrorType::BlankPassphrase => write!(f, "New passphrase cannot be blank"),
+            ApiErrorType::WeakPassphrase(reason) => write!(f, "Weak passphrase: {reason}"),
             ApiErrorType::InvalidRequestBody => write!(f, "Invalid request body"),
             ApiErrorType::CannotParseAddress => write!(f, "Cannot parse address"),
+            ApiErrorType::InvalidAddress(reason) => write!(f, "Invalid address: {reason}"),
             ApiErrorType::CannotAccessWallet => write!(f, "Cannot access wallet"),
             ApiErrorType::CannotAccessUserNode => write!(f, "Cannot access user node"),
             ApiErrorType::CannotAccessMinerNode => write!(f, "Cannot access miner node"),
@@ -66,11 +114,23 @@ impl std::fmt::Display for ApiErrorType {
             }
             ApiErrorType::CannotFetchBalance => write!(f, "Cannot fetch balance"),
             ApiErrorType::NoDataFoundForKey => write!(f, "No data found for key"),
+            ApiErrorType::NodeNotReady(reason) => write!(f, "Node not ready: {reason}"),
+            ApiErrorType::NodeBusy(reason) => write!(f, "Node busy: {reason}"),
+            ApiErrorType::NodeOverloaded(reason) => write!(f, "Node overloaded: {reason}"),
+            ApiErrorType::DbAccess(reason) => write!(f, "Database access error: {reason}"),
+            ApiErrorType::WalletDecrypt(reason) => write!(f, "Cannot decrypt wallet: {reason}"),
+            ApiErrorType::CommsInject(reason) => {
+                write!(f, "Cannot inject event into node: {reason}")
+            }
+            ApiErrorType::ThreadedCallFailed(reason) => {
+                write!(f, "Threaded call failed: {reason}")
+            }
             ApiErrorType::InternalError => write!(f, "Internal Error"),
             ApiErrorType::Unauthorized => write!(f, "Unauthorized"),
             ApiErrorType::MethodNotFound => write!(f, "Method not found"),
             ApiErrorType::MethodNotAllowed => write!(f, "Method not allowed"),
             ApiErrorType::BadRequest => write!(f, "Bad request"),
+            ApiErrorType::BatchTooLarge(max) => write!(f, "Batch too large: at most {max} items"),
         }
     }
 }
@@ -3,39 +3,85 @@ use crate::api::responses::{
     json_embed, json_embed_block, json_embed_transaction, json_serialize_embed, APIAsset,
     APICreateResponseContent, CallResponse, JsonReply,
 };
-use crate::api::utils::map_string_err;
+use crate::api::utils::{map_api_err, map_string_err};
 use crate::comms_handler::Node;
 use crate::compute::ComputeError;
 use crate::configurations::ComputeNodeSharedConfig;
-use crate::constants::LAST_BLOCK_HASH_KEY;
-use crate::db_utils::SimpleDb;
+use crate::constants::{DB_VERSION_KEY, LAST_BLOCK_HASH_KEY, NETWORK_VERSION_SERIALIZED};
+use crate::db_utils::{DbMetrics, SimpleDb, DB_COL_DEFAULT};
 use crate::interfaces::{
     node_type_as_str, AddressesWithOutPoints, BlockchainItem, BlockchainItemMeta,
-    BlockchainItemType, ComputeApi, DebugData, DruidPool, MineApiRequest, MineRequest, NodeType,
-    OutPointData, StoredSerializingBlock, UserApiRequest, UserRequest, UtxoFetchType,
+    BlockchainItemType, ComputeApi, ComputeApiRequest, DebugData, DruidPool, MineApiRequest,
+    MineRequest, NodeType, OutPointData, StoredSerializingBlock, UserApiRequest, UserRequest,
+    UtxoFetchType,
 };
 use crate::miner::{BlockPoWReceived, CurrentBlockWithMutex};
-use crate::storage::{get_stored_value_from_db, indexed_block_hash_key};
-use crate::threaded_call::{self, ThreadedCallSender};
-use crate::utils::{decode_pub_key, decode_signature, StringError};
+use crate::raft_util::RaftProposalMetricsInfo;
+use crate::storage::{
+    get_address_transactions, get_stored_value_from_db, indexed_block_hash_key, utxo_set_at_block,
+    verify_stored_chain, UtxoCheckpoints, ADDRESS_TRANSACTIONS_MAX_LIMIT,
+    BLOCKCHAIN_ENTRIES_MAX_KEYS,
+};
+use crate::threaded_call::{self, ThreadedCallError, ThreadedCallSender};
+use crate::tracked_utxo::{TrackedUtxoBalance, UTXO_PAGE_MAX_LIMIT, UTXO_SNAPSHOT_CHUNK_SIZE};
+use crate::utils::{
+    decode_pub_key, decode_signature, reload_log_filter, validate_address, LocalEvent,
+    LocalEventSender, LogFilterHandle, NodeReadiness, PreLaunchStatus, StorageRaftStatus,
+    StringError,
+};
 use crate::wallet::{AddressStore, AddressStoreHex, WalletDb, WalletDbError};
 use crate::Response;
+use bytes::Bytes;
+use futures::stream;
 use naom::constants::D_DISPLAY_PLACES;
 use naom::crypto::sign_ed25519::PublicKey;
 use naom::primitives::asset::{Asset, ReceiptAsset, TokenAmount};
+use naom::primitives::block::BlockHeader;
 use naom::primitives::druid::DdeValues;
 use naom::primitives::transaction::{DrsTxHashSpec, OutPoint, Transaction, TxIn, TxOut};
 use naom::script::lang::Script;
 use naom::utils::transaction_utils::{construct_address_for, construct_tx_hash};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
 use std::str;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use tracing::{debug, error};
-use warp::hyper::StatusCode;
+use warp::http::header::{HeaderValue, CONTENT_TYPE};
+use warp::hyper::{Body, StatusCode};
+
+/// Paths of the constructed API routes, excluding disabled routes
+#[derive(Debug, Clone, Default)]
+pub struct DbgPaths {
+    paths: Vec<&'static str>,
+    disabled: BTreeSet<String>,
+}
+
+impl DbgPaths {
+    /// Create paths where the given routes will be disabled
+    pub fn new(disabled: BTreeSet<String>) -> Self {
+        Self {
+            paths: Vec::new(),
+            disabled,
+        }
+    }
 
-pub type DbgPaths = Vec<&'static str>;
+    /// Add the route path if enabled, returning whether it is enabled
+    pub fn push(&mut self, path: &'static str) -> bool {
+        let enabled = !self.disabled.contains(path);
+        if enabled {
+            self.paths.push(path);
+        }
+        enabled
+    }
+
+    /// Paths of the enabled routes
+    pub fn into_paths(self) -> Vec<&'static str> {
+        self.paths
+    }
+}
 
 /// Data entry from the blockchain
 #[derive(Debug, Serialize, Deserialize)]
@@ -64,6 +110,183 @@ struct WalletInfo {
     addresses: AddressesWithOutPoints,
 }
 
+/// Software and protocol versions of a node to be returned to requester
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VersionInfo {
+    software_version: String,
+    network_version: String,
+    db_version: Option<String>,
+}
+
+/// Progress of a pre-launch node to be returned to requester
+#[derive(Debug, Clone, Serialize)]
+struct PreLaunchStatusInfo {
+    fetched_dbs: Vec<&'static str>,
+    complete: bool,
+}
+
+/// Database size and raft proposal metrics of a node to be returned to requester
+#[derive(Debug, Clone, Serialize)]
+struct NodeMetrics {
+    #[serde(flatten)]
+    db: DbMetrics,
+    raft: RaftProposalMetricsInfo,
+}
+
+/// Stored block consensus progress of a storage node to be returned to requester
+#[derive(Debug, Clone, Serialize)]
+struct StorageRaftStatusInfo {
+    stored_block_num: Option<u64>,
+    compute_block_num: Option<u64>,
+    lag: Option<u64>,
+}
+
+/// Confirmation depth of a transaction to be returned to requester
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TxConfirmations {
+    found: bool,
+    confirmations: u64,
+}
+
+/// UTXO set entry streamed as a line of newline-delimited JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UtxoSnapshotEntry {
+    out_point: OutPoint,
+    tx_out: TxOut,
+}
+
+/// Filter received from client to page through UTXO set addresses
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct UtxoAddressesFilter {
+    pub prefix: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Filter received from client to page through the transactions affecting an address
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct AddressTransactionsFilter {
+    pub address: String,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// Address received from client to page through its `OutPoint`s, starting after the cursor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressUtxoPageRequest {
+    pub address: String,
+    pub limit: Option<usize>,
+    pub cursor: Option<OutPoint>,
+}
+
+/// Block fields a client may keep in a projection
+const BLOCK_PROJECTION_FIELDS: &[&str] = &["header", "transactions"];
+
+/// Projection received from client to trim the fields of returned blocks
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct BlockProjection {
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub tx_hashes_only: bool,
+}
+
+/// Block projection received from client as query parameters, with comma separated fields
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct BlockProjectionQuery {
+    pub fields: Option<String>,
+    pub tx_hashes_only: Option<bool>,
+}
+
+impl From<BlockProjectionQuery> for BlockProjection {
+    fn from(query: BlockProjectionQuery) -> Self {
+        Self {
+            fields: query
+                .fields
+                .map(|fields| fields.split(',').map(|f| f.trim().to_owned()).collect()),
+            tx_hashes_only: query.tx_hashes_only.unwrap_or_default(),
+        }
+    }
+}
+
+impl BlockProjection {
+    /// Whether the stored block is to be returned untrimmed
+    fn is_full(&self) -> bool {
+        self.fields.is_none() && !self.tx_hashes_only
+    }
+
+    /// Block fields to keep, rejecting unknown fields
+    fn kept_fields(&self) -> Result<Vec<&str>, ApiErrorType> {
+        let fields: Vec<&str> = match (&self.fields, self.tx_hashes_only) {
+            (Some(_), true) => {
+                return Err(ApiErrorType::Generic(
+                    "Block fields cannot be combined with tx_hashes_only".to_owned(),
+                ))
+            }
+            (_, true) => vec!["transactions"],
+            (Some(fields), false) => fields.iter().map(|f| f.as_str()).collect(),
+            (None, false) => BLOCK_PROJECTION_FIELDS.to_vec(),
+        };
+
+        let unknown = fields
+            .iter()
+            .find(|f| !BLOCK_PROJECTION_FIELDS.contains(*f));
+        match unknown {
+            Some(f) => Err(ApiErrorType::Generic(format!("Unknown block field: {f}"))),
+            None => Ok(fields),
+        }
+    }
+}
+
+/// Last block hash known to a client long-polling for a newer block
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct LatestBlockLongPollQuery {
+    pub last_hash: Option<String>,
+}
+
+/// Block numbers received from client, optionally with a projection of the block fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlockNumsRequest {
+    Nums(Vec<u64>),
+    Projected {
+        nums: Vec<u64>,
+        #[serde(flatten)]
+        projection: BlockProjection,
+    },
+}
+
+impl BlockNumsRequest {
+    /// Split into the requested block numbers and projection
+    fn into_parts(self) -> (Vec<u64>, BlockProjection) {
+        match self {
+            Self::Nums(nums) => (nums, Default::default()),
+            Self::Projected { nums, projection } => (nums, projection),
+        }
+    }
+}
+
+/// Stored block re-serialized with only the projected fields
+#[derive(Debug, Serialize)]
+struct ProjectedStoredBlock<'a> {
+    block: ProjectedBlock<'a>,
+}
+
+/// Block re-serialized with only the projected fields
+#[derive(Debug, Serialize)]
+struct ProjectedBlock<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header: Option<&'a BlockHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transactions: Option<&'a Vec<String>>,
+}
+
+/// Balance including transactions pending in the pool to be returned to requester
+#[derive(Debug, Clone, Serialize)]
+struct BalanceWithPending {
+    confirmed: TrackedUtxoBalance,
+    available: TrackedUtxoBalance,
+}
+
 /// Public key addresses received from client
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicKeyAddresses {
@@ -144,6 +367,12 @@ pub struct ChangePassphraseData {
     pub new_passphrase: String,
 }
 
+/// Struct received from an external worker submitting a block PoW nonce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitPowData {
+    pub nonce: Vec<u8>,
+}
+
 /// Struct received from client to construct address
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct AddressConstructData {
@@ -158,12 +387,34 @@ pub struct FetchPendingData {
     pub druid_list: Vec<String>,
 }
 
+/// Struct received from client to cancel its participation in a pending DRUID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelDruidData {
+    pub druid: String,
+    pub tx_hash: String,
+    pub expiry: u64,
+    pub public_key: String,
+    pub signature: String,
+}
+
 /// Struct received from client to fetch pending
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchPendingtResult {
     pub pending_transactions: DruidPool,
 }
 
+/// Struct received from client to schedule a coordinated shutdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoordinatedShutdownData {
+    pub at_block: u64,
+}
+
+/// Struct received from client to change the log filter directives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevelData {
+    pub log_level: String,
+}
+
 //======= GET HANDLERS =======//
 
 /// Gets the state of the connected wallet and returns it.
@@ -179,7 +430,7 @@ pub async fn get_wallet_info(
 
     let mut fund_store = match wallet_db.get_fund_store_err() {
         Ok(fund) => fund,
-        Err(_) => return r.into_err_internal(ApiErrorType::CannotAccessWallet),
+        Err(e) => return r.into_api_err(ApiErrorType::DbAccess(e.to_string())),
     };
 
     let mut addresses = AddressesWithOutPoints::new();
@@ -235,7 +486,7 @@ pub async fn get_export_keypairs(
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
     let r = CallResponse::new(route, &call_id);
-    let known_addr = wallet_db.get_known_addresses();
+    let known_addr = wallet_db.get_all_addresses();
     let mut addresses = BTreeMap::new();
 
     for addr in known_addr {
@@ -264,13 +515,108 @@ pub async fn get_payment_address(
     )
 }
 
-/// Gets the latest block information
+/// Gets the latest block information, optionally trimmed to the projected fields
+///
+/// The reply carries an ETag derived from the block hash, and is empty with
+/// 304 Not Modified when the client's `If-None-Match` already matches it
 pub async fn get_latest_block(
+    db: Arc<Mutex<SimpleDb>>,
+    projection: BlockProjection,
+    if_none_match: Option<String>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let fields = match projection.kept_fields() {
+        Ok(fields) => fields,
+        Err(e) => return r.into_err_bad_req(e),
+    };
+    let item = match get_stored_value_from_db(db, LAST_BLOCK_HASH_KEY) {
+        Some(item) => item,
+        None => return r.into_err(StatusCode::NO_CONTENT, ApiErrorType::NoDataFoundForKey),
+    };
+
+    let etag = block_etag(&item.key, &projection, &fields);
+    if let Some(if_none_match) = if_none_match {
+        if etag_matches(&if_none_match, &etag) {
+            let reply = JsonReply::new(Vec::new()).with_code(StatusCode::NOT_MODIFIED);
+            return Ok(reply.with_etag(etag));
+        }
+    }
+
+    let json_content = if projection.is_full() {
+        json_embed(&[&item.data_json])
+    } else {
+        match bincode::deserialize::<StoredSerializingBlock>(&item.data) {
+            Ok(block) => json_serialize_embed(project_stored_block(&block, &fields)),
+            Err(_) => return r.into_err(StatusCode::NO_CONTENT, ApiErrorType::NoDataFoundForKey),
+        }
+    };
+
+    r.into_ok("Database item(s) successfully retrieved", json_content)
+        .map(|reply| reply.with_etag(etag))
+}
+
+/// Gets the latest block information once it differs from the client's last known block
+///
+/// The request is held open until a new block is stored, replying empty with
+/// 304 Not Modified if none is stored before the timeout.
+/// Rejected as overloaded when all the pending long-poll permits are taken.
+pub async fn get_latest_block_longpoll(
+    db: Arc<Mutex<SimpleDb>>,
+    status: StorageRaftStatus,
+    pending: Arc<Semaphore>,
+    query: LatestBlockLongPollQuery,
+    timeout: Duration,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let _permit = match pending.try_acquire_owned() {
+        Ok(permit) => permit,
+        Err(_) => {
+            let reason = "Too many pending long-polls".to_owned();
+            return r.into_api_err(ApiErrorType::NodeOverloaded(reason));
+        }
+    };
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    // Subscribe before reading the stored block so none is missed in between
+    let mut block_stored = status.subscribe_block_stored();
+    loop {
+        let item = get_stored_value_from_db(db.clone(), LAST_BLOCK_HASH_KEY);
+        let last_hash = query.last_hash.as_ref().map(|hash| hash.as_bytes());
+        if let Some(item) = item.filter(|item| Some(&item.key[..]) != last_hash) {
+            let etag = block_etag(&item.key, &Default::default(), &[]);
+            return r
+                .into_ok(
+                    "Database item(s) successfully retrieved",
+                    json_embed(&[&item.data_json]),
+                )
+                .map(|reply| reply.with_etag(etag));
+        }
+
+        let stored = tokio::time::timeout_at(deadline, block_stored.changed()).await;
+        if !matches!(stored, Ok(Ok(()))) {
+            return Ok(JsonReply::new(Vec::new()).with_code(StatusCode::NOT_MODIFIED));
+        }
+    }
+}
+
+/// Verifies the stored chain from genesis to head, reporting the first inconsistency
+pub async fn get_verify_chain(
     db: Arc<Mutex<SimpleDb>>,
     route: &'static str,
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
-    get_json_reply_stored_value_from_db(db, LAST_BLOCK_HASH_KEY, false, call_id, route)
+    let r = CallResponse::new(route, &call_id);
+    let verification = verify_stored_chain(db).await;
+    let reason = if verification.inconsistency.is_some() {
+        "Stored chain inconsistency found"
+    } else {
+        "Stored chain successfully verified"
+    };
+    r.into_ok(reason, json_serialize_embed(verification))
 }
 
 /// Gets the debug info for a specified node type
@@ -297,14 +643,14 @@ pub async fn get_debug_data(
             let aux_peers = aux.get_peer_list().await;
             DebugData {
                 node_type: format!("{node_type}/{aux_type}"),
-                node_api: debug_paths,
+                node_api: debug_paths.into_paths(),
                 node_peers: [node_peers, aux_peers].concat(),
                 routes_pow,
             }
         }
         None => DebugData {
             node_type: node_type.to_owned(),
-            node_api: debug_paths,
+            node_api: debug_paths.into_paths(),
             node_peers,
             routes_pow,
         },
@@ -330,20 +676,44 @@ pub async fn get_current_mining_block(
 }
 
 /// Get all addresses for unspent tokens on the UTXO set
+/// Optionally only the addresses matching a prefix, up to a limit, if a filter is provided
 pub async fn get_utxo_addresses(
     mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    body: Bytes,
     route: &'static str,
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
     let r = CallResponse::new(route, &call_id);
 
+    let filter: UtxoAddressesFilter = if body.is_empty() {
+        Default::default()
+    } else {
+        serde_json::from_slice(&body).map_err(|_| {
+            r.clone()
+                .into_err_bad_req(ApiErrorType::InvalidRequestBody)
+                .unwrap_err()
+        })?
+    };
+
     let addresses = make_api_threaded_call(
         &mut threaded_calls,
-        |c| c.get_committed_utxo_tracked_set().get_all_addresses(),
+        move |c| {
+            let mut addresses = c.get_committed_utxo_tracked_set().get_all_addresses();
+            addresses.sort_unstable();
+            addresses.dedup();
+
+            let prefix = filter.prefix.unwrap_or_default();
+            let limit = filter.limit.unwrap_or(usize::MAX);
+            addresses
+                .into_iter()
+                .filter(|address| address.starts_with(&prefix))
+                .take(limit)
+                .collect::<Vec<String>>()
+        },
         "Can't access UTXO",
     )
     .await
-    .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .map_err(|e| map_api_err(r.clone(), e))?;
 
     r.into_ok(
         "UTXO addresses successfully retrieved",
@@ -351,6 +721,125 @@ pub async fn get_utxo_addresses(
     )
 }
 
+/// Gets the readiness of the node to serve requests
+///
+/// The node is ready once its initial raft snapshot is applied and it is
+/// connected to its expected peers. When a blockchain db is provided, the
+/// last block must also be loadable from it. A node whose block production
+/// has stalled is reported as not ready.
+pub async fn get_readiness(
+    node: Node,
+    readiness: NodeReadiness,
+    db: Option<Arc<Mutex<SimpleDb>>>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let not_ready = |reason: String| {
+        r.clone().into_err(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ApiErrorType::NodeNotReady(reason),
+        )
+    };
+
+    if !readiness.is_initial_state_applied() {
+        return not_ready("Initial raft snapshot not applied".to_owned());
+    }
+
+    if readiness.is_block_production_stalled() {
+        return not_ready("Block production stalled".to_owned());
+    }
+
+    let unconnected = node.unconnected_peers(readiness.expected_peers()).await;
+    if !unconnected.is_empty() {
+        return not_ready(format!("Not connected to peers {unconnected:?}"));
+    }
+
+    if let Some(db) = db {
+        if get_stored_value_from_db(db, LAST_BLOCK_HASH_KEY).is_none() {
+            return not_ready("Last block cannot be loaded".to_owned());
+        }
+    }
+
+    r.into_ok("Node is ready", json_serialize_embed("null"))
+}
+
+/// Gets the software, network protocol and db versions of the node
+pub async fn get_version(
+    db: Option<Arc<Mutex<SimpleDb>>>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let db_version = match db {
+        Some(db) => match db.lock().unwrap().get_cf(DB_COL_DEFAULT, DB_VERSION_KEY) {
+            Ok(version) => version.map(|v| String::from_utf8_lossy(&v).into_owned()),
+            Err(e) => {
+                error!("Failed to read db version: {:?}", e);
+                return r.into_api_err(ApiErrorType::DbAccess(e.to_string()));
+            }
+        },
+        None => None,
+    };
+
+    let data = VersionInfo {
+        software_version: env!("CARGO_PKG_VERSION").to_owned(),
+        network_version: String::from_utf8_lossy(NETWORK_VERSION_SERIALIZED).into_owned(),
+        db_version,
+    };
+    r.into_ok("Version successfully retrieved", json_serialize_embed(data))
+}
+
+/// Gets the progress of a pre-launch node
+pub async fn get_prelaunch_status(
+    status: PreLaunchStatus,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let mut fetched_dbs = Vec::new();
+    if status.is_raft_db_fetched() {
+        fetched_dbs.push("raft_db");
+    }
+
+    let data = PreLaunchStatusInfo {
+        fetched_dbs,
+        complete: status.is_complete(),
+    };
+    r.into_ok(
+        "Pre-launch status successfully retrieved",
+        json_serialize_embed(data),
+    )
+}
+
+/// Gets the last block stored in consensus and how far it lags behind compute
+pub async fn get_storage_raft_status(
+    status: StorageRaftStatus,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let stored_block_num = status.stored_block_num();
+    let compute_block_num = status.compute_block_num();
+    let lag = compute_block_num.map(|c| match stored_block_num {
+        Some(s) => c.saturating_sub(s),
+        None => c + 1,
+    });
+
+    let data = StorageRaftStatusInfo {
+        stored_block_num,
+        compute_block_num,
+        lag,
+    };
+    r.into_ok(
+        "Storage raft status successfully retrieved",
+        json_serialize_embed(data),
+    )
+}
+
 //POST get a compute node's config which is shareable amongst its peers
 pub async fn get_shared_config_compute(
     mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
@@ -365,7 +854,7 @@ pub async fn get_shared_config_compute(
         "Cannot access Compute Node",
     )
     .await
-    .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .map_err(|e| map_api_err(r.clone(), e))?;
 
     r.into_ok(
         "Successfully fetched shared config",
@@ -373,6 +862,116 @@ pub async fn get_shared_config_compute(
     )
 }
 
+/// Gets the block number a coordinated shutdown is scheduled at, null if none
+pub async fn get_coordinated_shutdown(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let res = make_api_threaded_call(
+        &mut threaded_calls,
+        |c| c.get_coordinated_shutdown(),
+        "Cannot access Compute Node",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    r.into_ok(
+        "Successfully fetched coordinated shutdown",
+        json_serialize_embed(res),
+    )
+}
+
+/// Gets the payouts to the winning miners of the most recent blocks
+pub async fn get_miner_payouts(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let payouts = make_api_threaded_call(
+        &mut threaded_calls,
+        |c| c.get_miner_payouts(),
+        "Cannot access Compute Node",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    r.into_ok(
+        "Miner payouts successfully retrieved",
+        json_serialize_embed(payouts),
+    )
+}
+
+/// Gets the inconsistencies between the UTXO set and its address tracking, empty if none
+pub async fn get_utxo_consistency(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let errors = make_api_threaded_call(
+        &mut threaded_calls,
+        |c| {
+            c.get_committed_utxo_tracked_set()
+                .check_consistency()
+                .err()
+                .unwrap_or_default()
+        },
+        "Cannot access Compute Node",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    r.into_ok(
+        "UTXO set consistency successfully checked",
+        json_serialize_embed(errors),
+    )
+}
+
+/// Gets size information for the compute database and its raft proposal metrics
+pub async fn get_db_metrics_compute(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let res = make_api_threaded_call(
+        &mut threaded_calls,
+        move |c| NodeMetrics {
+            db: c.get_db_metrics(),
+            raft: c.get_raft_metrics(),
+        },
+        "Cannot access Compute Node",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    r.into_ok(
+        "Database metrics successfully retrieved",
+        json_serialize_embed(res),
+    )
+}
+
+/// Gets size information for the storage database and its raft proposal metrics
+pub async fn get_db_metrics_storage(
+    db: Arc<Mutex<SimpleDb>>,
+    status: StorageRaftStatus,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let metrics = NodeMetrics {
+        db: db.lock().unwrap().metrics(),
+        raft: status.proposal_metrics(),
+    };
+    r.into_ok(
+        "Database metrics successfully retrieved",
+        json_serialize_embed(metrics),
+    )
+}
+
 //======= POST HANDLERS =======//
 
 /// Post to retrieve an item from the blockchain db by hash key
@@ -385,6 +984,123 @@ pub async fn post_blockchain_entry_by_key(
     get_json_reply_stored_value_from_db(db, &key, true, call_id, route)
 }
 
+/// Post to retrieve items from the blockchain db by hash keys, null for missing keys
+pub async fn post_blockchain_entries_by_keys(
+    db: Arc<Mutex<SimpleDb>>,
+    keys: Vec<String>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    if keys.len() > BLOCKCHAIN_ENTRIES_MAX_KEYS {
+        return r.into_api_err(ApiErrorType::BatchTooLarge(BLOCKCHAIN_ENTRIES_MAX_KEYS));
+    }
+
+    // Make JSON object of key to wrapped item, deduplicating keys
+    let keys: BTreeSet<String> = keys.into_iter().collect();
+    let mut json = b"{".to_vec();
+    for (idx, key) in keys.iter().enumerate() {
+        if idx != 0 {
+            json.push(b',');
+        }
+        json.extend(serde_json::to_vec(key).unwrap());
+        json.push(b':');
+        match get_stored_value_from_db(db.clone(), key.as_bytes()) {
+            Some(item) => {
+                let wrap: &[u8] = match item.item_meta.as_type() {
+                    BlockchainItemType::Block => b"{\"Block\":",
+                    BlockchainItemType::Tx => b"{\"Transaction\":",
+                };
+                json.extend(wrap);
+                json.extend(item.data_json);
+                json.push(b'}');
+            }
+            None => json.extend(b"null"),
+        }
+    }
+    json.push(b'}');
+
+    r.into_ok(
+        "Database item(s) successfully retrieved",
+        json_embed(&[&json]),
+    )
+}
+
+/// Post to retrieve a transaction and the block number it was mined in by its hash
+pub async fn post_transaction_by_hash(
+    db: Arc<Mutex<SimpleDb>>,
+    tx_hash: String,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let item = get_stored_value_from_db(db, tx_hash.as_bytes());
+
+    match item.as_ref().map(|i| i.item_meta) {
+        Some(BlockchainItemMeta::Tx { block_num, tx_num }) => {
+            let item = item.unwrap();
+            let block_num = block_num.to_string();
+            let tx_num = tx_num.to_string();
+            r.into_ok(
+                "Transaction successfully retrieved",
+                json_embed(&[
+                    b"{\"transaction\":",
+                    &item.data_json,
+                    b",\"block_num\":",
+                    block_num.as_bytes(),
+                    b",\"tx_num\":",
+                    tx_num.as_bytes(),
+                    b"}",
+                ]),
+            )
+        }
+        _ => r.into_err(StatusCode::NO_CONTENT, ApiErrorType::NoDataFoundForKey),
+    }
+}
+
+/// Post to retrieve the number of blocks confirming a transaction by its hash
+pub async fn post_tx_confirmations(
+    db: Arc<Mutex<SimpleDb>>,
+    tx_hash: String,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let tx_block_num = match get_stored_value_from_db(db.clone(), tx_hash.as_bytes()) {
+        Some(BlockchainItem {
+            item_meta: BlockchainItemMeta::Tx { block_num, .. },
+            ..
+        }) => Some(block_num),
+        _ => None,
+    };
+    let last_block_num = match get_stored_value_from_db(db, LAST_BLOCK_HASH_KEY) {
+        Some(BlockchainItem {
+            item_meta: BlockchainItemMeta::Block { block_num, .. },
+            ..
+        }) => Some(block_num),
+        _ => None,
+    };
+
+    let data = match (tx_block_num, last_block_num) {
+        (Some(tx_block_num), Some(last_block_num)) if last_block_num >= tx_block_num => {
+            TxConfirmations {
+                found: true,
+                confirmations: last_block_num - tx_block_num + 1,
+            }
+        }
+        (found, _) => TxConfirmations {
+            found: found.is_some(),
+            confirmations: 0,
+        },
+    };
+
+    r.into_ok(
+        "Transaction confirmations successfully retrieved",
+        json_serialize_embed(data),
+    )
+}
+
 /// Post to batch retrieve multiple transactions from the blockchain db by hash keys
 pub async fn post_transactions_by_key(
     db: Arc<Mutex<SimpleDb>>,
@@ -395,18 +1111,88 @@ pub async fn post_transactions_by_key(
     get_json_reply_items_from_db(db, keys, route, call_id)
 }
 
-/// Post to retrieve block information by number
+/// Post to retrieve block information by number, optionally trimmed to the projected fields
 pub async fn post_block_by_num(
     db: Arc<Mutex<SimpleDb>>,
-    block_nums: Vec<u64>,
+    request: BlockNumsRequest,
     route: &'static str,
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
+    let (block_nums, projection) = request.into_parts();
     let keys: Vec<_> = block_nums
         .iter()
         .map(|num| indexed_block_hash_key(*num))
         .collect();
-    get_json_reply_items_from_db(db, keys, route, call_id)
+    if projection.is_full() {
+        return get_json_reply_items_from_db(db, keys, route, call_id);
+    }
+
+    let r = CallResponse::new(route, &call_id);
+    let fields = match projection.kept_fields() {
+        Ok(fields) => fields,
+        Err(e) => return r.into_err_bad_req(e),
+    };
+    let key_values: Vec<_> = keys
+        .into_iter()
+        .map(|key| {
+            get_stored_value_from_db(db.clone(), key)
+                .and_then(|item| {
+                    let block = bincode::deserialize::<StoredSerializingBlock>(&item.data).ok()?;
+                    let projected = project_stored_block(&block, &fields);
+                    Some((item.key, serde_json::to_vec(&projected).ok()?))
+                })
+                .unwrap_or_else(|| (b"".to_vec(), b"\"\"".to_vec()))
+        })
+        .collect();
+
+    r.into_ok(
+        "Database item(s) successfully retrieved",
+        json_embed_key_values(&key_values),
+    )
+}
+
+/// Post to reconstruct the UTXO set as of a given block number
+pub async fn post_utxo_at_block(
+    db: Arc<Mutex<SimpleDb>>,
+    utxo_checkpoints: UtxoCheckpoints,
+    block_num: u64,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    match utxo_set_at_block(db, block_num, utxo_checkpoints).await {
+        Ok(utxo_set) => r.into_ok(
+            "UTXO set successfully reconstructed",
+            json_serialize_embed(utxo_set),
+        ),
+        Err(e) => r.into_err_bad_req(ApiErrorType::Generic(e.to_string())),
+    }
+}
+
+/// Post to retrieve the hashes of the transactions affecting an address
+/// Only available on storage nodes with the address index enabled
+pub async fn post_address_transactions(
+    db: Arc<Mutex<SimpleDb>>,
+    filter: AddressTransactionsFilter,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let offset = filter.offset.unwrap_or_default();
+    let limit = filter
+        .limit
+        .map_or(ADDRESS_TRANSACTIONS_MAX_LIMIT, |limit| {
+            limit.min(ADDRESS_TRANSACTIONS_MAX_LIMIT)
+        });
+
+    let tx_hashes = {
+        let db = db.lock().unwrap();
+        get_address_transactions(&db, &filter.address, offset, limit)
+    };
+    r.into_ok(
+        "Address transactions successfully retrieved",
+        json_serialize_embed(tx_hashes),
+    )
 }
 
 /// Post to import new keypairs to the connected wallet
@@ -460,19 +1246,22 @@ pub async fn post_import_keypairs(
                 ))),
             ) {
                 error!("route:update_running_total error: {:?}", e);
-                return r.into_err_internal(ApiErrorType::CannotAccessMinerNode);
+                return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
             }
         }
         NodeType::User => {
             // Update running total from compute node
             if let Err(e) = peer.inject_next_event(
                 peer.local_address(),
-                UserRequest::UserApi(UserApiRequest::UpdateWalletFromUtxoSet {
-                    address_list: UtxoFetchType::AnyOf(addresses),
-                }),
+                UserRequest::UserApi {
+                    request: UserApiRequest::UpdateWalletFromUtxoSet {
+                        address_list: UtxoFetchType::AnyOf(addresses),
+                    },
+                    correlation_id: call_id.clone(),
+                },
             ) {
                 error!("route:update_running_total error: {:?}", e);
-                return r.into_err_internal(ApiErrorType::CannotAccessUserNode);
+                return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
             }
         }
         _ => return r.into_err_internal(ApiErrorType::InternalError),
@@ -498,12 +1287,20 @@ pub async fn post_make_payment(
 
     let r = CallResponse::new(route, &call_id);
 
+    let address = match validate_address(&address, None) {
+        Ok(address) => address.into_string(),
+        Err(e) => return r.into_err_bad_req(ApiErrorType::InvalidAddress(e.to_string())),
+    };
+
     let request = match db.test_passphrase(passphrase).await {
-        Ok(_) => UserRequest::UserApi(UserApiRequest::MakePayment {
-            address: address.clone(),
-            amount,
-            locktime,
-        }),
+        Ok(_) => UserRequest::UserApi {
+            request: UserApiRequest::MakePayment {
+                address: address.clone(),
+                amount,
+                locktime,
+            },
+            correlation_id: call_id.clone(),
+        },
         Err(e) => {
             return wallet_db_error(e, r);
         }
@@ -511,7 +1308,7 @@ pub async fn post_make_payment(
 
     if let Err(e) = peer.inject_next_event(peer.local_address(), request) {
         error!("route:make_payment error: {:?}", e);
-        return r.into_err_internal(ApiErrorType::CannotAccessUserNode);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
     }
 
     r.into_ok(
@@ -545,11 +1342,14 @@ pub async fn post_make_ip_payment(
     };
 
     let request = match db.test_passphrase(passphrase).await {
-        Ok(_) => UserRequest::UserApi(UserApiRequest::MakeIpPayment {
-            payment_peer,
-            amount,
-            locktime,
-        }),
+        Ok(_) => UserRequest::UserApi {
+            request: UserApiRequest::MakeIpPayment {
+                payment_peer,
+                amount,
+                locktime,
+            },
+            correlation_id: call_id.clone(),
+        },
         Err(e) => {
             return wallet_db_error(e, r);
         }
@@ -557,7 +1357,7 @@ pub async fn post_make_ip_payment(
 
     if let Err(e) = peer.inject_next_event(peer.local_address(), request) {
         error!("route:make_payment error: {:?}", e);
-        return r.into_err_internal(ApiErrorType::CannotAccessUserNode);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
     }
 
     r.into_ok(
@@ -581,11 +1381,14 @@ pub async fn post_request_donation(
         }
     };
 
-    let request = UserRequest::UserApi(UserApiRequest::RequestDonation { paying_peer });
+    let request = UserRequest::UserApi {
+        request: UserApiRequest::RequestDonation { paying_peer },
+        correlation_id: call_id.clone(),
+    };
 
     if let Err(e) = peer.inject_next_event(peer.local_address(), request) {
         error!("route:request_donation error: {:?}", e);
-        return r.into_err_internal(ApiErrorType::CannotAccessUserNode);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
     }
 
     r.into_ok("Donation request sent", json_serialize_embed("null"))
@@ -598,14 +1401,17 @@ pub async fn post_update_running_total(
     route: &'static str,
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
-    let request = UserRequest::UserApi(UserApiRequest::UpdateWalletFromUtxoSet {
-        address_list: UtxoFetchType::AnyOf(addresses.address_list),
-    });
+    let request = UserRequest::UserApi {
+        request: UserApiRequest::UpdateWalletFromUtxoSet {
+            address_list: UtxoFetchType::AnyOf(addresses.address_list),
+        },
+        correlation_id: call_id.clone(),
+    };
     let r = CallResponse::new(route, &call_id);
 
     if let Err(e) = peer.inject_next_event(peer.local_address(), request) {
         error!("route:update_running_total error: {:?}", e);
-        return r.into_err_internal(ApiErrorType::CannotAccessUserNode);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
     }
 
     r.into_ok("Running total updated", json_serialize_embed("null"))
@@ -629,7 +1435,80 @@ pub async fn post_fetch_utxo_balance(
         "Cannot fetch UTXO balance",
     )
     .await
-    .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    r.into_ok(
+        "Balance successfully fetched",
+        json_serialize_embed(balances),
+    )
+}
+
+/// Post to fetch a page of the `OutPoint`s of an address in UTXO
+pub async fn post_fetch_utxo_balance_page(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    request: AddressUtxoPageRequest,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    if request.limit == Some(0) {
+        return r.into_err_bad_req(ApiErrorType::Generic(
+            "Page limit must be at least 1".to_owned(),
+        ));
+    }
+    let limit = request
+        .limit
+        .map_or(UTXO_PAGE_MAX_LIMIT, |limit| limit.min(UTXO_PAGE_MAX_LIMIT));
+
+    let page = make_api_threaded_call(
+        &mut threaded_calls,
+        move |c| {
+            c.get_committed_utxo_tracked_set()
+                .get_utxo_page_for_address(&request.address, request.cursor.as_ref(), limit)
+        },
+        "Cannot fetch UTXO balance",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    r.into_ok(
+        "Balance page successfully fetched",
+        json_serialize_embed(page),
+    )
+}
+
+//POST fetch balance for addresses, with and without the spends pending in the tx pool
+//
+// NOTE: The available balance is best-effort as the pool contents can change at any time
+pub async fn post_fetch_utxo_balance_with_pending(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    addresses: PublicKeyAddresses,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let balances = make_api_threaded_call(
+        &mut threaded_calls,
+        move |c| {
+            let pending_spent: BTreeSet<OutPoint> = c
+                .get_committed_tx_pool()
+                .values()
+                .flat_map(|tx| tx.inputs.iter())
+                .filter_map(|tx_in| tx_in.previous_out.clone())
+                .collect();
+
+            let utxo_set = c.get_committed_utxo_tracked_set();
+            BalanceWithPending {
+                confirmed: utxo_set.get_balance_for_addresses(&addresses.address_list),
+                available: utxo_set
+                    .get_balance_for_addresses_excluding(&addresses.address_list, &pending_spent),
+            }
+        },
+        "Cannot fetch UTXO balance",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
 
     r.into_ok(
         "Balance successfully fetched",
@@ -637,6 +1516,124 @@ pub async fn post_fetch_utxo_balance(
     )
 }
 
+//POST stream the committed UTXO set, filtered by addresses if any provided
+//
+// NOTE: The set is fetched in chunks, so blocks committed while streaming may
+// be partially reflected
+pub async fn post_utxo_snapshot(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    addresses: PublicKeyAddresses,
+    route: &'static str,
+    call_id: String,
+) -> Result<warp::reply::Response, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let addresses = Arc::new(addresses.address_list);
+
+    let first_chunk = fetch_utxo_snapshot_chunk(&mut threaded_calls, addresses.clone(), None)
+        .await
+        .map_err(|e| map_api_err(r.clone(), e))?;
+
+    // Each step streams one chunk, fetching the ones after the first on demand
+    let mut first_chunk = Some(first_chunk);
+    let chunks = stream::unfold(Some(None), move |cursor: Option<Option<OutPoint>>| {
+        let first_chunk = first_chunk.take();
+        let mut threaded_calls = threaded_calls.clone();
+        let addresses = addresses.clone();
+        async move {
+            let cursor = cursor?;
+            let chunk = match first_chunk {
+                Some(chunk) => chunk,
+                None => {
+                    match fetch_utxo_snapshot_chunk(&mut threaded_calls, addresses, cursor).await {
+                        Ok(chunk) => chunk,
+                        Err(e) => return Some((Err(StringError(e.to_string())), None)),
+                    }
+                }
+            };
+
+            let next_cursor = match chunk.last() {
+                Some((out_point, _)) if chunk.len() == UTXO_SNAPSHOT_CHUNK_SIZE => {
+                    Some(Some(out_point.clone()))
+                }
+                _ => None,
+            };
+            Some((utxo_snapshot_lines(chunk), next_cursor))
+        }
+    });
+
+    let mut res = warp::reply::Response::new(Body::wrap_stream(chunks));
+    res.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    Ok(res)
+}
+
+/// Fetch a chunk of the committed UTXO set entries after the cursor
+async fn fetch_utxo_snapshot_chunk(
+    threaded_calls: &mut ThreadedCallSender<dyn ComputeApi>,
+    addresses: Arc<Vec<String>>,
+    cursor: Option<OutPoint>,
+) -> Result<Vec<(OutPoint, TxOut)>, ApiErrorType> {
+    make_api_threaded_call(
+        threaded_calls,
+        move |c| {
+            c.get_committed_utxo_tracked_set()
+                .get_utxo_entries_chunk_for_addresses(
+                    &addresses,
+                    cursor.as_ref(),
+                    UTXO_SNAPSHOT_CHUNK_SIZE,
+                )
+        },
+        "Cannot fetch UTXO snapshot",
+    )
+    .await
+}
+
+/// Serialize the UTXO set entries as newline delimited JSON
+fn utxo_snapshot_lines(entries: Vec<(OutPoint, TxOut)>) -> Result<Vec<u8>, StringError> {
+    let mut lines = Vec::new();
+    for (out_point, tx_out) in entries {
+        let entry = UtxoSnapshotEntry { out_point, tx_out };
+        serde_json::to_writer(&mut lines, &entry).map_err(|e| StringError(e.to_string()))?;
+        lines.push(b'\n');
+    }
+    Ok(lines)
+}
+
+//POST cancel the caller participation in a pending DRUID on a compute node
+pub async fn post_cancel_druid(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    cancel_input: CancelDruidData,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let CancelDruidData {
+        druid,
+        tx_hash,
+        expiry,
+        public_key,
+        signature,
+    } = cancel_input;
+
+    let res = make_api_threaded_call(
+        &mut threaded_calls,
+        move |c| c.cancel_druid(&druid, &tx_hash, expiry, &public_key, &signature),
+        "Cannot access Compute Node",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    if !res.success {
+        debug!("route:cancel_druid error: {:?}", res.reason);
+        return r.into_err_bad_req(ApiErrorType::Generic(res.reason.to_string()));
+    }
+
+    r.into_ok(res.reason.as_str(), json_serialize_embed("null"))
+}
+
 //POST fetch pending transaction from a computet node
 pub async fn post_fetch_druid_pending(
     mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
@@ -658,7 +1655,7 @@ pub async fn post_fetch_druid_pending(
         "Cannot fetch pending transactions",
     )
     .await
-    .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
+    .map_err(|e| map_api_err(r.clone(), e))?;
 
     r.into_ok(
         "Pending transactions successfully fetched",
@@ -679,16 +1676,19 @@ pub async fn post_create_receipt_asset_user(
         metadata,
     } = receipt_data;
 
-    let request = UserRequest::UserApi(UserApiRequest::SendCreateReceiptRequest {
-        receipt_amount,
-        drs_tx_hash_spec,
-        metadata,
-    });
+    let request = UserRequest::UserApi {
+        request: UserApiRequest::SendCreateReceiptRequest {
+            receipt_amount,
+            drs_tx_hash_spec,
+            metadata,
+        },
+        correlation_id: call_id.clone(),
+    };
     let r = CallResponse::new(route, &call_id);
 
     if let Err(e) = peer.inject_next_event(peer.local_address(), request) {
         error!("route:create_receipt_asset error: {:?}", e);
-        return r.into_err_internal(ApiErrorType::CannotAccessUserNode);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
     }
 
     r.into_ok(
@@ -718,25 +1718,28 @@ pub async fn post_create_receipt_asset(
     // Create receipt asset on the Compute node
     let spk = script_public_key.clone();
     let md = metadata.clone();
+    let correlation_id = call_id.clone();
     let (tx_hash, compute_resp) = make_api_threaded_call(
         &mut threaded_calls,
         move |c| {
-            let (tx, tx_hash) = c
-                .create_receipt_asset_tx(
-                    receipt_amount,
-                    spk,
-                    public_key,
-                    signature,
-                    drs_tx_hash_spec,
-                    md
-                )?;
-            let compute_resp = c.receive_transactions(vec![tx]);
+            let (tx, tx_hash) = c.create_receipt_asset_tx(
+                receipt_amount,
+                spk,
+                public_key,
+                signature,
+                drs_tx_hash_spec,
+                md,
+            )?;
+            let request = ComputeApiRequest::SendTransactions {
+                transactions: vec![tx],
+            };
+            let compute_resp = c.receive_api_request(request, &correlation_id)?;
             Ok::<(String, Response), ComputeError>((tx_hash, compute_resp))
         },
         "Cannot access Compute Node",
     )
     .await
-    .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))? /* Error from threaded call */
+    .map_err(|e| map_api_err(r.clone(), e))? /* Error from threaded call */
     .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?; /* Error in transaction creation process */
 
     match compute_resp.success {
@@ -750,7 +1753,7 @@ pub async fn post_create_receipt_asset(
         }
         false => r.into_err(
             StatusCode::INTERNAL_SERVER_ERROR,
-            ApiErrorType::Generic(compute_resp.reason.to_owned()),
+            ApiErrorType::Generic(compute_resp.reason.to_string()),
         ),
     }
 }
@@ -774,12 +1777,15 @@ pub async fn post_create_transactions(
     let ctx_map = construct_ctx_map(&transactions);
 
     // Send request to compute node
+    let request = ComputeApiRequest::SendTransactions { transactions };
+    let correlation_id = call_id.clone();
     let compute_resp = make_api_threaded_call(
         &mut threaded_calls,
-        move |c| c.receive_transactions(transactions),
+        move |c| c.receive_api_request(request, &correlation_id),
         "Cannot access Compute Node",
     )
     .await
+    .map_err(|e| map_api_err(r.clone(), e))?
     .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     // If the creation failed for some reason
@@ -788,7 +1794,7 @@ pub async fn post_create_transactions(
             "route:post_create_transactions error: {:?}",
             compute_resp.reason
         );
-        return r.into_err_internal(ApiErrorType::Generic(compute_resp.reason.to_owned()));
+        return r.into_err_internal(ApiErrorType::Generic(compute_resp.reason.to_string()));
     }
 
     r.into_ok("Transaction(s) processing", json_serialize_embed(ctx_map))
@@ -824,6 +1830,27 @@ pub async fn post_change_wallet_passphrase(
     }
 }
 
+// POST a block PoW nonce found by an external worker, forwarded to compute if valid
+pub async fn post_submit_pow(
+    peer: Node,
+    submit_pow: SubmitPowData,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let SubmitPowData { nonce } = submit_pow;
+
+    if let Err(e) = peer.inject_next_event(
+        peer.local_address(),
+        MineRequest::MinerApi(MineApiRequest::SubmitPoW { nonce }),
+    ) {
+        error!("route:submit_pow error: {:?}", e);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
+    }
+
+    r.into_ok("PoW submission received", json_serialize_embed("null"))
+}
+
 // POST to check for transaction presence
 pub async fn post_blocks_by_tx_hashes(
     db: Arc<Mutex<SimpleDb>>,
@@ -850,6 +1877,37 @@ pub async fn post_blocks_by_tx_hashes(
     )
 }
 
+/// Post to flush and compact the database, returning its metrics once done.
+/// The compaction runs on a blocking thread without locking the database.
+pub async fn post_compact_db(
+    db: Arc<Mutex<SimpleDb>>,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+    let handle = db.lock().unwrap().compaction_handle();
+    let compacted = tokio::task::spawn_blocking(move || match handle {
+        Some(handle) => handle.flush().map(|_| handle.compact()),
+        None => Ok(()),
+    })
+    .await;
+
+    match compacted {
+        Ok(Ok(())) => (),
+        Ok(Err(e)) => return r.into_api_err(ApiErrorType::DbAccess(e.to_string())),
+        Err(e) => {
+            error!("route:compact_db error: {:?}", e);
+            return r.into_err_internal(ApiErrorType::InternalError);
+        }
+    }
+
+    let metrics = db.lock().unwrap().metrics();
+    r.into_ok(
+        "Database successfully compacted",
+        json_serialize_embed(metrics),
+    )
+}
+
 //POST create a new payment address from a compute node
 pub async fn post_payment_address_construction(
     data: AddressConstructData,
@@ -866,11 +1924,14 @@ pub async fn post_payment_address_construction(
     let pub_key = pub_key.and_then(|k| PublicKey::from_slice(&k));
 
     if let Some(pub_key) = pub_key {
-        let data: String = construct_address_for(&pub_key, version);
-        return r.into_ok(
-            "Address successfully constructed",
-            json_serialize_embed(data),
-        );
+        let data = construct_address_for(&pub_key, version);
+        return match validate_address(&data, version) {
+            Ok(data) => r.into_ok(
+                "Address successfully constructed",
+                json_serialize_embed(data.into_string()),
+            ),
+            Err(e) => r.into_api_err(ApiErrorType::InvalidAddress(e.to_string())),
+        };
     }
 
     r.into_ok(
@@ -887,22 +1948,27 @@ pub async fn pause_nodes(
     b_num: Option<u64>, // NOTE: Nodes will pause at b_num + b_num
 ) -> Result<JsonReply, JsonReply> {
     let r = CallResponse::new(route, &call_id);
+    // NOTE: Nodes will pause at current_block + b_num; default is 1 block from current block
+    let request = ComputeApiRequest::PauseNodes {
+        b_num: b_num.unwrap_or(1),
+    };
+    let correlation_id = call_id.clone();
     // Send request to compute node
     let res = make_api_threaded_call(
         &mut threaded_calls,
-        // NOTE: Nodes will pause at current_block + b_num; default is 1 block from current block
-        move |c| c.pause_nodes(b_num.unwrap_or(1)),
+        move |c| c.receive_api_request(request, &correlation_id),
         "Cannot access Compute Node",
     )
     .await
+    .map_err(|e| map_api_err(r.clone(), e))?
     .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     if !res.success {
         debug!("route:pause_nodes error: {:?}", res.reason);
-        return r.into_err_internal(ApiErrorType::Generic(res.reason.to_owned()));
+        return r.into_err_internal(ApiErrorType::Generic(res.reason.to_string()));
     }
 
-    r.into_ok(res.reason, json_serialize_embed("null"))
+    r.into_ok(res.reason.as_str(), json_serialize_embed("null"))
 }
 
 //POST resume nodes in a coordinated manner
@@ -912,21 +1978,23 @@ pub async fn resume_nodes(
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
     let r = CallResponse::new(route, &call_id);
+    let correlation_id = call_id.clone();
     // Send request to compute node
     let res = make_api_threaded_call(
         &mut threaded_calls,
-        |c| c.resume_nodes(),
+        move |c| c.receive_api_request(ComputeApiRequest::ResumeNodes, &correlation_id),
         "Cannot access Compute Node",
     )
     .await
+    .map_err(|e| map_api_err(r.clone(), e))?
     .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     if !res.success {
         debug!("route:resume_nodes error: {:?}", res.reason);
-        return r.into_err_internal(ApiErrorType::Generic(res.reason.to_owned()));
+        return r.into_err_internal(ApiErrorType::Generic(res.reason.to_string()));
     }
 
-    r.into_ok(res.reason, json_serialize_embed("null"))
+    r.into_ok(res.reason.as_str(), json_serialize_embed("null"))
 }
 
 //POST update a compute node's config, sharing it to all other peers
@@ -937,21 +2005,117 @@ pub async fn update_shared_config(
     call_id: String,
 ) -> Result<JsonReply, JsonReply> {
     let r = CallResponse::new(route, &call_id);
+    let request = ComputeApiRequest::SendSharedConfig { shared_config };
+    let correlation_id = call_id.clone();
     // Send request to compute node
     let res = make_api_threaded_call(
         &mut threaded_calls,
-        move |c| c.send_shared_config(shared_config),
+        move |c| c.receive_api_request(request, &correlation_id),
         "Cannot access Compute Node",
     )
     .await
+    .map_err(|e| map_api_err(r.clone(), e))?
     .map_err(|e| map_string_err(r.clone(), e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     if !res.success {
         debug!("route:update_shared_config error: {:?}", res.reason);
-        return r.into_err_internal(ApiErrorType::Generic(res.reason.to_owned()));
+        return r.into_err_internal(ApiErrorType::Generic(res.reason.to_string()));
+    }
+
+    r.into_ok(res.reason.as_str(), json_serialize_embed("null"))
+}
+
+//POST schedule a coordinated shutdown of the compute node at the given block
+pub async fn post_coordinated_shutdown(
+    mut threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    mut local_event_tx: LocalEventSender,
+    shutdown_data: CoordinatedShutdownData,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let CoordinatedShutdownData { at_block } = shutdown_data;
+    let r = CallResponse::new(route, &call_id);
+
+    let current_b_num = make_api_threaded_call(
+        &mut threaded_calls,
+        |c| c.get_current_block_num(),
+        "Cannot access Compute Node",
+    )
+    .await
+    .map_err(|e| map_api_err(r.clone(), e))?;
+
+    if at_block < current_b_num {
+        return r.into_err_bad_req(ApiErrorType::Generic(format!(
+            "Shutdown block {at_block} is before current block {current_b_num}"
+        )));
+    }
+
+    let event = LocalEvent::CoordinatedShutdown(at_block);
+    if let Err(e) = local_event_tx.send(event, "api coordinated shutdown").await {
+        error!("route:coordinated_shutdown error: {:?}", e);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
+    }
+
+    r.into_ok(
+        "Coordinated shutdown scheduled",
+        json_serialize_embed(at_block),
+    )
+}
+
+//POST cancel a scheduled coordinated shutdown of the compute node
+pub async fn post_cancel_coordinated_shutdown(
+    mut local_event_tx: LocalEventSender,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let event = LocalEvent::CancelShutdown;
+    if let Err(e) = local_event_tx.send(event, "api cancel shutdown").await {
+        error!("route:cancel_coordinated_shutdown error: {:?}", e);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
+    }
+
+    r.into_ok(
+        "Coordinated shutdown cancelled",
+        json_serialize_embed("null"),
+    )
+}
+
+//POST generate a raft snapshot of the node now
+pub async fn post_force_snapshot(
+    mut local_event_tx: LocalEventSender,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    let event = LocalEvent::ForceSnapshot;
+    if let Err(e) = local_event_tx.send(event, "api force snapshot").await {
+        error!("route:force_snapshot error: {:?}", e);
+        return r.into_api_err(ApiErrorType::CommsInject(e.to_string()));
+    }
+
+    r.into_ok("Snapshot requested", json_serialize_embed("null"))
+}
+
+/// Post to replace the node log filter without restarting it
+pub async fn post_log_level(
+    log_filter: LogFilterHandle,
+    log_level_data: LogLevelData,
+    route: &'static str,
+    call_id: String,
+) -> Result<JsonReply, JsonReply> {
+    let r = CallResponse::new(route, &call_id);
+
+    if let Err(e) = reload_log_filter(&log_filter, &log_level_data.log_level) {
+        return r.into_err_bad_req(ApiErrorType::Generic(e.to_string()));
     }
 
-    r.into_ok(res.reason, json_serialize_embed("null"))
+    r.into_ok(
+        "Log level updated",
+        json_serialize_embed(log_level_data.log_level),
+    )
 }
 
 //======= Helpers =======//
@@ -965,7 +2129,19 @@ pub fn wallet_db_error(
         WalletDbError::PassphraseError => {
             call_response.into_err(StatusCode::UNAUTHORIZED, ApiErrorType::InvalidPassphrase)
         }
-        _ => call_response.into_err_internal(ApiErrorType::InternalError),
+        WalletDbError::MasterKeyRetrievalError | WalletDbError::MasterKeyMissingError => {
+            call_response.into_api_err(ApiErrorType::WalletDecrypt(err.to_string()))
+        }
+        WalletDbError::Database(e) => {
+            call_response.into_api_err(ApiErrorType::DbAccess(e.to_string()))
+        }
+        WalletDbError::WeakPassphrase(reason) => {
+            call_response.into_api_err(ApiErrorType::WeakPassphrase(reason))
+        }
+        _ => {
+            error!("Unexpected wallet error: {:?}", err);
+            call_response.into_err_internal(ApiErrorType::InternalError)
+        }
     }
 }
 
@@ -1067,6 +2243,14 @@ pub fn get_json_reply_items_from_db(
         })
         .collect();
 
+    r.into_ok(
+        "Database item(s) successfully retrieved",
+        json_embed_key_values(&key_values),
+    )
+}
+
+/// Embed keys and their JSON items into a JSON array of tupples
+fn json_embed_key_values(key_values: &[(Vec<u8>, Vec<u8>)]) -> JsonReply {
     // Make JSON tupple with key and JSON item
     let key_values: Vec<_> = key_values
         .iter()
@@ -1078,10 +2262,39 @@ pub fn get_json_reply_items_from_db(
     key_values.insert(0, &b"["[..]);
     key_values.push(&b"]"[..]);
 
-    r.into_ok(
-        "Database item(s) successfully retrieved",
-        json_embed(&key_values),
-    )
+    json_embed(&key_values)
+}
+
+/// Strong ETag of a block reply: the block hash, qualified by the fields of a projection
+fn block_etag(block_hash: &[u8], projection: &BlockProjection, fields: &[&str]) -> String {
+    let block_hash = String::from_utf8_lossy(block_hash);
+    if projection.is_full() {
+        format!("\"{block_hash}\"")
+    } else {
+        format!("\"{block_hash};{}\"", fields.join(","))
+    }
+}
+
+/// Whether an `If-None-Match` header value matches the given ETag, weakly compared
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+/// Re-serialize a stored block keeping only the given fields
+fn project_stored_block<'a>(
+    block: &'a StoredSerializingBlock,
+    fields: &[&str],
+) -> ProjectedStoredBlock<'a> {
+    let keep = |field: &str| fields.contains(&field);
+    ProjectedStoredBlock {
+        block: ProjectedBlock {
+            header: Some(&block.block.header).filter(|_| keep("header")),
+            transactions: Some(&block.block.transactions).filter(|_| keep("transactions")),
+        },
+    }
 }
 
 /// Threaded call for API
@@ -1089,8 +2302,14 @@ pub async fn make_api_threaded_call<'a, T: ?Sized, R: Send + Sized + Sync + 'sta
     tx: &mut ThreadedCallSender<T>,
     f: impl FnOnce(&mut T) -> R + Send + Sized + Sync + 'static,
     tag: &'a str,
-) -> Result<R, StringError> {
-    threaded_call::make_threaded_call(tx, f, tag).await
+) -> Result<R, ApiErrorType> {
+    threaded_call::make_threaded_call(tx, f, tag)
+        .await
+        .map_err(|e| match e {
+            ThreadedCallError::TimedOut(_) => ApiErrorType::NodeBusy(tag.to_owned()),
+            ThreadedCallError::Overloaded => ApiErrorType::NodeOverloaded(tag.to_owned()),
+            e => ApiErrorType::ThreadedCallFailed(format!("{tag}: {e}")),
+        })
 }
 
 /// Constructs the mapping of output address to asset for `create_transactions`
@@ -10,13 +10,22 @@ use warp::hyper::StatusCode;
 pub struct JsonReply {
     data: Vec<u8>,
     status_code: StatusCode,
+    etag: Option<String>,
+    call_id: Option<String>,
 }
 
+/// Call ID a reply was made for, kept in the response extensions
+/// so the correlation ID middleware echoes the ID the handler used.
+#[derive(Debug, Clone)]
+pub struct ReplyCallId(pub String);
+
 impl JsonReply {
     pub fn new(data: Vec<u8>) -> Self {
         JsonReply {
             data,
             status_code: StatusCode::OK,
+            etag: None,
+            call_id: None,
         }
     }
 
@@ -24,16 +33,32 @@ impl JsonReply {
         self.status_code = status_code;
         self
     }
+
+    pub fn with_etag(mut self, etag: String) -> Self {
+        self.etag = Some(etag);
+        self
+    }
+
+    pub fn with_call_id(mut self, call_id: &str) -> Self {
+        self.call_id = Some(call_id.to_owned()).filter(|id| !id.is_empty());
+        self
+    }
 }
 
 impl warp::reply::Reply for JsonReply {
     #[inline]
     fn into_response(self) -> warp::reply::Response {
-        use warp::http::header::{HeaderValue, CONTENT_TYPE};
+        use warp::http::header::{HeaderValue, CONTENT_TYPE, ETAG};
         let res = warp::reply::Response::new(self.data.into());
         let mut res = warp::reply::with_status(res, self.status_code).into_response();
         res.headers_mut()
             .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(etag) = self.etag.and_then(|etag| HeaderValue::from_str(&etag).ok()) {
+            res.headers_mut().insert(ETAG, etag);
+        }
+        if let Some(call_id) = self.call_id {
+            res.extensions_mut().insert(ReplyCallId(call_id));
+        }
         res
     }
 }
@@ -80,6 +105,10 @@ impl<'a> CallResponse<'a> {
         self.into_err(StatusCode::BAD_REQUEST, api_error_type)
     }
 
+    pub fn into_api_err(self, api_error_type: ApiErrorType) -> Result<JsonReply, JsonReply> {
+        self.into_err(api_error_type.status_code(), api_error_type)
+    }
+
     pub fn into_err_with_data(
         self,
         status: StatusCode,
@@ -187,6 +216,7 @@ pub fn common_reply(
         &content.data,
         b"}",
     ])
+    .with_call_id(id)
 }
 
 /// Handles common success replies
@@ -1,16 +1,24 @@
 use crate::api::handlers::{self, DbgPaths};
 use crate::api::utils::{
-    auth_request, create_new_cache, handle_rejection, map_api_res_and_cache, warp_path,
-    with_node_component, ReplyCache, CACHE_LIVE_TIME,
+    auth_request, auth_request_with_api_key, correlation_id, create_new_cache, handle_rejection,
+    hashed_body, json_body, map_api_res, map_api_res_and_cache, map_api_stream_res, warp_path,
+    with_compression, with_correlation_id, with_node_component, ReplyCache, CACHE_LIVE_TIME,
 };
 use crate::comms_handler::Node;
 use crate::db_utils::SimpleDb;
 use crate::interfaces::ComputeApi;
 use crate::miner::CurrentBlockWithMutex;
+use crate::storage::UtxoCheckpoints;
 use crate::threaded_call::ThreadedCallSender;
-use crate::utils::{ApiKeys, RoutesPoWInfo};
+use crate::utils::{
+    ApiKeys, LocalEventSender, LogFilterHandle, NodeReadiness, PreLaunchStatus, RoutesPoWInfo,
+    StorageRaftStatus,
+};
 use crate::wallet::WalletDb;
+use std::collections::BTreeSet;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
 use warp::{Filter, Rejection, Reply};
 
@@ -34,6 +42,9 @@ pub fn get_cors() -> warp::cors::Builder {
             "x-request-id",
             "x-nonce",
             "x-api-key",
+            "x-timestamp",
+            "x-content-hash",
+            "x-signature",
         ])
         .allow_methods(vec!["GET"])
 }
@@ -116,7 +127,7 @@ pub fn payment_address(
         .with(get_cors())
 }
 
-// GET latest block
+// GET latest block, optionally projected to some of its fields
 pub fn latest_block(
     dp: &mut DbgPaths,
     db: Arc<Mutex<SimpleDb>>,
@@ -125,6 +136,72 @@ pub fn latest_block(
     cache: ReplyCache,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     let route = "latest_block";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(warp::query::<handlers::BlockProjectionQuery>())
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and(with_node_component(cache))
+        .and_then(
+            move |call_id: String, db, projection, if_none_match, cache| {
+                map_api_res_and_cache(
+                    call_id.clone(),
+                    cache,
+                    handlers::get_latest_block(
+                        db,
+                        projection.into(),
+                        if_none_match,
+                        route,
+                        call_id,
+                    ),
+                )
+            },
+        )
+        .with(get_cors())
+}
+
+// GET latest block once it differs from the client's last known block, or not modified on timeout
+pub fn latest_block_longpoll(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    status: StorageRaftStatus,
+    timeout: Duration,
+    max_pending: usize,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "latest_block_longpoll";
+    let pending = Arc::new(Semaphore::new(max_pending));
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(with_node_component(status))
+        .and(warp::query::<handlers::LatestBlockLongPollQuery>())
+        .and_then(move |call_id: String, db, status, query| {
+            map_api_res(handlers::get_latest_block_longpoll(
+                db,
+                status,
+                pending.clone(),
+                query,
+                timeout,
+                route,
+                call_id,
+            ))
+        })
+        .with(get_cors())
+}
+
+// GET verify stored chain
+pub fn verify_chain(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "verify_chain";
     warp_path(dp, route)
         .and(warp::get())
         .and(auth_request(routes_pow, api_keys))
@@ -134,7 +211,7 @@ pub fn latest_block(
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::get_latest_block(db, route, call_id),
+                handlers::get_verify_chain(db, route, call_id),
             )
         })
         .with(get_cors())
@@ -195,7 +272,7 @@ pub fn current_mining_block(
         .with(get_cors())
 }
 
-// GET UTXO set addresses
+// GET UTXO set addresses, or POST to filter them by prefix and limit
 pub fn utxo_addresses(
     dp: &mut DbgPaths,
     threaded_calls: ThreadedCallSender<dyn ComputeApi>,
@@ -205,18 +282,19 @@ pub fn utxo_addresses(
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     let route = "utxo_addresses";
     warp_path(dp, route)
-        .and(warp::get())
+        .and(warp::get().or(warp::post()).unify())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(threaded_calls))
+        .and(hashed_body())
         .and(with_node_component(cache))
-        .and_then(move |call_id: String, a, cache| {
+        .and_then(move |call_id: String, a, body, cache| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::get_utxo_addresses(a, route, call_id),
+                handlers::get_utxo_addresses(a, body, route, call_id),
             )
         })
-        .with(get_cors())
+        .with(get_cors().allow_method("POST"))
 }
 
 // GET current config for node
@@ -243,6 +321,202 @@ pub fn get_shared_config(
         .with(get_cors())
 }
 
+// GET scheduled coordinated shutdown block
+pub fn get_coordinated_shutdown(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "get_coordinated_shutdown";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(cache))
+        .and(with_node_component(threaded_calls))
+        .and_then(move |call_id: String, cache, tc| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::get_coordinated_shutdown(tc, route, call_id),
+            )
+        })
+        .with(get_cors())
+}
+
+// GET inconsistencies between the UTXO set and its address tracking
+pub fn utxo_consistency(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "utxo_consistency";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(cache))
+        .and(with_node_component(threaded_calls))
+        .and_then(move |call_id: String, cache, tc| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::get_utxo_consistency(tc, route, call_id),
+            )
+        })
+        .with(get_cors())
+}
+
+// GET payouts to the winning miners of the most recent blocks
+pub fn miner_payouts(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "miner_payouts";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(cache))
+        .and(with_node_component(threaded_calls))
+        .and_then(move |call_id: String, cache, tc| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::get_miner_payouts(tc, route, call_id),
+            )
+        })
+        .with(get_cors())
+}
+
+// GET compute database and raft proposal metrics
+pub fn compute_metrics(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "metrics";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(cache))
+        .and(with_node_component(threaded_calls))
+        .and_then(move |call_id: String, cache, tc| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::get_db_metrics_compute(tc, route, call_id),
+            )
+        })
+        .with(get_cors())
+}
+
+// GET storage database and raft proposal metrics
+pub fn storage_metrics(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    status: StorageRaftStatus,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "metrics";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(with_node_component(status))
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, status, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::get_db_metrics_storage(db, status, route, call_id),
+            )
+        })
+        .with(get_cors())
+}
+
+// GET last block stored in consensus against the last block received from compute
+pub fn storage_raft_status(
+    dp: &mut DbgPaths,
+    status: StorageRaftStatus,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "storage_raft_status";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(status))
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, status, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::get_storage_raft_status(status, route, call_id),
+            )
+        })
+        .with(get_cors())
+}
+
+// GET node readiness
+pub fn readiness(
+    dp: &mut DbgPaths,
+    node: Node,
+    readiness: NodeReadiness,
+    db: Option<Arc<Mutex<SimpleDb>>>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "readyz";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(correlation_id())
+        .and(with_node_component(node))
+        .and(with_node_component(readiness))
+        .and(with_node_component(db))
+        .and_then(move |call_id: String, node, readiness, db| {
+            map_api_res(handlers::get_readiness(node, readiness, db, route, call_id))
+        })
+        .with(get_cors())
+}
+
+// GET software and protocol version information
+pub fn version(
+    dp: &mut DbgPaths,
+    db: Option<Arc<Mutex<SimpleDb>>>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "version";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(correlation_id())
+        .and(with_node_component(db))
+        .and_then(move |call_id: String, db| map_api_res(handlers::get_version(db, route, call_id)))
+        .with(get_cors())
+}
+
+// GET progress of a pre-launch node
+pub fn prelaunch_status(
+    dp: &mut DbgPaths,
+    status: PreLaunchStatus,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "prelaunch_status";
+    warp_path(dp, route)
+        .and(warp::get())
+        .and(correlation_id())
+        .and(with_node_component(status))
+        .and_then(move |call_id: String, status| {
+            map_api_res(handlers::get_prelaunch_status(status, route, call_id))
+        })
+        .with(get_cors())
+}
+
 //======= POST ROUTES =======//
 
 // POST CORS
@@ -263,6 +537,9 @@ pub fn post_cors() -> warp::cors::Builder {
             "x-request-id",
             "x-nonce",
             "x-api-key",
+            "x-timestamp",
+            "x-content-hash",
+            "x-signature",
         ])
         .allow_methods(vec!["POST"])
 }
@@ -280,7 +557,7 @@ pub fn blockchain_entry_by_key(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(db))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, db, info, cache| {
             map_api_res_and_cache(
@@ -292,7 +569,82 @@ pub fn blockchain_entry_by_key(
         .with(post_cors())
 }
 
-// POST get block information by number
+// POST get multiple blockchain items by key, null for missing keys
+pub fn blockchain_entries_by_keys(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "blockchain_entries_by_keys";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, keys, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_blockchain_entries_by_keys(db, keys, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST get transaction and its block number by transaction hash
+pub fn transaction_by_hash(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "transaction_by_hash";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_transaction_by_hash(db, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST get number of blocks confirming a transaction
+pub fn tx_confirmations(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "tx_confirmations";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_tx_confirmations(db, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST get block information by number, optionally projected to some of its fields
 pub fn block_by_num(
     dp: &mut DbgPaths,
     db: Arc<Mutex<SimpleDb>>,
@@ -305,7 +657,7 @@ pub fn block_by_num(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(db))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, db, info, cache| {
             map_api_res_and_cache(
@@ -317,7 +669,59 @@ pub fn block_by_num(
         .with(post_cors())
 }
 
-// POST get block information by number
+// POST get the transactions affecting an address
+pub fn address_transactions(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "address_transactions";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_address_transactions(db, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST reconstruct the UTXO set at a given block number
+pub fn utxo_at_block(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    utxo_checkpoints: UtxoCheckpoints,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "utxo_at_block";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(with_node_component(utxo_checkpoints))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, utxo_checkpoints, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_utxo_at_block(db, utxo_checkpoints, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST get block information by number, optionally projected to some of its fields
 pub fn transactions_by_key(
     dp: &mut DbgPaths,
     db: Arc<Mutex<SimpleDb>>,
@@ -330,7 +734,7 @@ pub fn transactions_by_key(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(db))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, db, info, cache| {
             map_api_res_and_cache(
@@ -358,7 +762,7 @@ pub fn import_keypairs(
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(db))
         .and(with_node_component(node))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, db, node, kp, cache| {
             map_api_res_and_cache(
@@ -385,7 +789,7 @@ pub fn make_payment(
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(db))
         .and(with_node_component(node))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, db, node, pi, cache| {
             map_api_res_and_cache(
@@ -412,7 +816,7 @@ pub fn make_ip_payment(
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(db))
         .and(with_node_component(node))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, db, node, pi, cache| {
             map_api_res_and_cache(
@@ -437,7 +841,7 @@ pub fn request_donation(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(node))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, node, info, cache| {
             map_api_res_and_cache(
@@ -462,7 +866,7 @@ pub fn update_running_total(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(node))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, node, info, cache| {
             map_api_res_and_cache(
@@ -487,7 +891,7 @@ pub fn fetch_balance(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(threaded_calls))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, tc, info, cache| {
             map_api_res_and_cache(
@@ -499,6 +903,75 @@ pub fn fetch_balance(
         .with(post_cors())
 }
 
+// POST fetch a page of the outpoints of an address
+pub fn fetch_balance_page(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "fetch_balance_page";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(threaded_calls))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, tc, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_fetch_utxo_balance_page(tc, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST fetch balance for addresses, including spends pending in the tx pool
+pub fn fetch_balance_with_pending(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "fetch_balance_with_pending";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(threaded_calls))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, tc, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_fetch_utxo_balance_with_pending(tc, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST stream the committed UTXO set as newline-delimited JSON
+pub fn utxo_snapshot(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "utxo_snapshot";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(threaded_calls))
+        .and(json_body())
+        .and_then(move |call_id: String, tc, info| {
+            map_api_stream_res(handlers::post_utxo_snapshot(tc, info, route, call_id))
+        })
+        .with(post_cors())
+}
+
 // POST fetch balance for addresses
 pub fn fetch_pending(
     dp: &mut DbgPaths,
@@ -512,7 +985,7 @@ pub fn fetch_pending(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(threaded_calls))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, tc, info, cache| {
             map_api_res_and_cache(
@@ -524,6 +997,31 @@ pub fn fetch_pending(
         .with(post_cors())
 }
 
+// POST cancel participation in a pending DRUID
+pub fn cancel_druid(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "cancel_druid";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(threaded_calls))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, tc, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_cancel_druid(tc, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
 // POST create receipt-based asset transaction
 pub fn create_receipt_asset(
     dp: &mut DbgPaths,
@@ -537,7 +1035,7 @@ pub fn create_receipt_asset(
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(threaded_calls))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
         .and_then(move |call_id: String, tc, info, cache| {
             map_api_res_and_cache(
@@ -561,186 +1059,335 @@ pub fn create_receipt_asset_user(
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
-        .and(with_node_component(node))
-        .and(warp::body::json())
+        .and(with_node_component(node))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, node, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_create_receipt_asset_user(node, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST change passphrase
+pub fn change_passphrase(
+    dp: &mut DbgPaths,
+    db: WalletDb,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "change_passphrase";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_change_wallet_passphrase(db, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST a block PoW nonce found by an external worker
+pub fn submit_pow(
+    dp: &mut DbgPaths,
+    node: Node,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "submit_pow";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(node))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, node, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_submit_pow(node, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST create transactions
+pub fn create_transactions(
+    dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let route = "create_transactions";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(threaded_calls))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, tc, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_create_transactions(tc, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST check for address presence
+pub fn blocks_by_tx_hashes(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let route = "check_transaction_presence";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(json_body())
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, info, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_blocks_by_tx_hashes(db, info, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST flush and compact the database
+pub fn compact_db(
+    dp: &mut DbgPaths,
+    db: Arc<Mutex<SimpleDb>>,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "compact_db";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(with_node_component(db))
+        .and(with_node_component(cache))
+        .and_then(move |call_id: String, db, cache| {
+            map_api_res_and_cache(
+                call_id.clone(),
+                cache,
+                handlers::post_compact_db(db, route, call_id),
+            )
+        })
+        .with(post_cors())
+}
+
+// POST construct payment address
+pub fn address_construction(
+    dp: &mut DbgPaths,
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+    cache: ReplyCache,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "address_construction";
+    warp_path(dp, route)
+        .and(warp::post())
+        .and(auth_request(routes_pow, api_keys))
+        .and(json_body())
         .and(with_node_component(cache))
-        .and_then(move |call_id: String, node, info, cache| {
+        .and_then(move |call_id: String, info, cache| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::post_create_receipt_asset_user(node, info, route, call_id),
+                handlers::post_payment_address_construction(info, route, call_id),
             )
         })
         .with(post_cors())
 }
 
-// POST change passphrase
-pub fn change_passphrase(
+// POST pause nodes
+pub fn pause_nodes(
     dp: &mut DbgPaths,
-    db: WalletDb,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let route = "change_passphrase";
+    let route = "pause_nodes";
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
-        .and(with_node_component(db))
-        .and(warp::body::json())
         .and(with_node_component(cache))
-        .and_then(move |call_id: String, db, info, cache| {
+        .and(with_node_component(threaded_calls))
+        .and(json_body())
+        .and_then(move |call_id: String, cache, tc, b_num| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::post_change_wallet_passphrase(db, info, route, call_id),
+                handlers::pause_nodes(tc, route, call_id, b_num),
             )
         })
         .with(post_cors())
 }
 
-// POST create transactions
-pub fn create_transactions(
+// POST resume nodes
+pub fn resume_nodes(
     dp: &mut DbgPaths,
     threaded_calls: ThreadedCallSender<dyn ComputeApi>,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
-) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
-    let route = "create_transactions";
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "resume_nodes";
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
-        .and(with_node_component(threaded_calls))
-        .and(warp::body::json())
         .and(with_node_component(cache))
-        .and_then(move |call_id: String, tc, info, cache| {
+        .and(with_node_component(threaded_calls))
+        .and_then(move |call_id: String, cache, tc| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::post_create_transactions(tc, info, route, call_id),
+                handlers::resume_nodes(tc, route, call_id),
             )
         })
         .with(post_cors())
 }
 
-// POST check for address presence
-pub fn blocks_by_tx_hashes(
+// POST update config in a coordinated manner, sharing it to peers
+pub fn update_shared_config(
     dp: &mut DbgPaths,
-    db: Arc<Mutex<SimpleDb>>,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
-) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
-    let route = "check_transaction_presence";
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let route = "update_shared_config";
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
-        .and(with_node_component(db))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
-        .and_then(move |call_id: String, db, info, cache| {
+        .and(with_node_component(threaded_calls))
+        .and_then(move |call_id: String, shared_config, cache, tc| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::post_blocks_by_tx_hashes(db, info, route, call_id),
+                handlers::update_shared_config(tc, shared_config, route, call_id),
             )
         })
         .with(post_cors())
 }
 
-// POST construct payment address
-pub fn address_construction(
+// POST schedule a coordinated shutdown
+pub fn coordinated_shutdown(
     dp: &mut DbgPaths,
+    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    local_event_tx: LocalEventSender,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let route = "address_construction";
+    let route = "coordinated_shutdown";
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
-        .and(warp::body::json())
+        .and(json_body())
         .and(with_node_component(cache))
-        .and_then(move |call_id: String, info, cache| {
+        .and(with_node_component(threaded_calls))
+        .and(with_node_component(local_event_tx))
+        .and_then(move |call_id: String, shutdown_data, cache, tc, ev_tx| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::post_payment_address_construction(info, route, call_id),
+                handlers::post_coordinated_shutdown(tc, ev_tx, shutdown_data, route, call_id),
             )
         })
         .with(post_cors())
 }
 
-// POST pause nodes
-pub fn pause_nodes(
+// POST cancel a scheduled coordinated shutdown
+pub fn cancel_coordinated_shutdown(
     dp: &mut DbgPaths,
-    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    local_event_tx: LocalEventSender,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let route = "pause_nodes";
+    let route = "cancel_coordinated_shutdown";
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(cache))
-        .and(with_node_component(threaded_calls))
-        .and(warp::body::json())
-        .and_then(move |call_id: String, cache, tc, b_num| {
+        .and(with_node_component(local_event_tx))
+        .and_then(move |call_id: String, cache, ev_tx| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::pause_nodes(tc, route, call_id, b_num),
+                handlers::post_cancel_coordinated_shutdown(ev_tx, route, call_id),
             )
         })
         .with(post_cors())
 }
 
-// POST resume nodes
-pub fn resume_nodes(
+// POST generate a raft snapshot now
+pub fn force_snapshot(
     dp: &mut DbgPaths,
-    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    local_event_tx: LocalEventSender,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let route = "resume_nodes";
+    let route = "force_snapshot";
     warp_path(dp, route)
         .and(warp::post())
         .and(auth_request(routes_pow, api_keys))
         .and(with_node_component(cache))
-        .and(with_node_component(threaded_calls))
-        .and_then(move |call_id: String, cache, tc| {
+        .and(with_node_component(local_event_tx))
+        .and_then(move |call_id: String, cache, ev_tx| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::resume_nodes(tc, route, call_id),
+                handlers::post_force_snapshot(ev_tx, route, call_id),
             )
         })
         .with(post_cors())
 }
 
-// POST update config in a coordinated manner, sharing it to peers
-pub fn update_shared_config(
+// POST change the log filter directives at runtime
+pub fn log_level(
     dp: &mut DbgPaths,
-    threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    log_filter: LogFilterHandle,
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
     cache: ReplyCache,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let route = "update_shared_config";
+    let route = "log_level";
     warp_path(dp, route)
         .and(warp::post())
-        .and(auth_request(routes_pow, api_keys))
-        .and(warp::body::json())
+        .and(auth_request_with_api_key(routes_pow, api_keys))
+        .and(json_body())
         .and(with_node_component(cache))
-        .and(with_node_component(threaded_calls))
-        .and_then(move |call_id: String, shared_config, cache, tc| {
+        .and(with_node_component(log_filter))
+        .and_then(move |call_id: String, log_level_data, cache, log_filter| {
             map_api_res_and_cache(
                 call_id.clone(),
                 cache,
-                handlers::update_shared_config(tc, shared_config, route, call_id),
+                handlers::post_log_level(log_filter, log_level_data, route, call_id),
             )
         })
         .with(post_cors())
@@ -755,8 +1402,11 @@ pub fn user_node_routes(
     routes_pow_info: RoutesPoWInfo,
     db: WalletDb,
     node: Node,
+    log_filter: LogFilterHandle,
+    disabled_routes: BTreeSet<String>,
+    compression: bool,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let mut dp_vec = DbgPaths::new();
+    let mut dp_vec = DbgPaths::new(disabled_routes);
     let dp = &mut dp_vec;
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
@@ -839,6 +1489,14 @@ pub fn user_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(log_level(
+        dp,
+        log_filter,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(version(dp, None))
     .or(debug_data(
         dp_vec,
         node,
@@ -848,7 +1506,10 @@ pub fn user_node_routes(
         cache,
     ));
 
-    routes.recover(handle_rejection)
+    with_correlation_id(with_compression(
+        compression,
+        routes.recover(handle_rejection),
+    ))
 }
 
 // API routes for Storage nodes
@@ -856,11 +1517,20 @@ pub fn storage_node_routes(
     api_keys: ApiKeys,
     routes_pow_info: RoutesPoWInfo,
     db: Arc<Mutex<SimpleDb>>,
+    local_event_tx: LocalEventSender,
     node: Node,
+    node_readiness: NodeReadiness,
+    raft_status: StorageRaftStatus,
+    latest_block_longpoll_timeout: Duration,
+    latest_block_longpoll_max_pending: usize,
+    log_filter: LogFilterHandle,
+    disabled_routes: BTreeSet<String>,
+    compression: bool,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let mut dp_vec = DbgPaths::new();
+    let mut dp_vec = DbgPaths::new(disabled_routes);
     let dp = &mut dp_vec;
     let cache = create_new_cache(CACHE_LIVE_TIME);
+    let utxo_checkpoints = UtxoCheckpoints::default();
 
     let routes = block_by_num(
         dp,
@@ -883,6 +1553,15 @@ pub fn storage_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(latest_block_longpoll(
+        dp,
+        db.clone(),
+        raft_status.clone(),
+        latest_block_longpoll_timeout,
+        latest_block_longpoll_max_pending,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+    ))
     .or(blockchain_entry_by_key(
         dp,
         db.clone(),
@@ -890,9 +1569,81 @@ pub fn storage_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(blockchain_entries_by_keys(
+        dp,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(blocks_by_tx_hashes(
         dp,
-        db,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(transaction_by_hash(
+        dp,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(tx_confirmations(
+        dp,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(verify_chain(
+        dp,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(utxo_at_block(
+        dp,
+        db.clone(),
+        utxo_checkpoints,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(address_transactions(
+        dp,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(storage_metrics(
+        dp,
+        db.clone(),
+        raft_status.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(storage_raft_status(
+        dp,
+        raft_status,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(compact_db(
+        dp,
+        db.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(force_snapshot(
+        dp,
+        local_event_tx,
         routes_pow_info.clone(),
         api_keys.clone(),
         cache.clone(),
@@ -903,6 +1654,20 @@ pub fn storage_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(log_level(
+        dp,
+        log_filter,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(readiness(
+        dp,
+        node.clone(),
+        node_readiness,
+        Some(db.clone()),
+    ))
+    .or(version(dp, Some(db)))
     .or(debug_data(
         dp_vec,
         node,
@@ -912,7 +1677,10 @@ pub fn storage_node_routes(
         cache,
     ));
 
-    routes.recover(handle_rejection)
+    with_correlation_id(with_compression(
+        compression,
+        routes.recover(handle_rejection),
+    ))
 }
 
 // API routes for Compute nodes
@@ -922,9 +1690,14 @@ pub fn compute_node_routes(
     api_keys: ApiKeys,
     routes_pow_info: RoutesPoWInfo,
     threaded_calls: ThreadedCallSender<dyn ComputeApi>,
+    local_event_tx: LocalEventSender,
     node: Node,
+    node_readiness: NodeReadiness,
+    log_filter: LogFilterHandle,
+    disabled_routes: BTreeSet<String>,
+    compression: bool,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let mut dp_vec = DbgPaths::new();
+    let mut dp_vec = DbgPaths::new(disabled_routes);
     let dp = &mut dp_vec;
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
@@ -935,6 +1708,20 @@ pub fn compute_node_routes(
         api_keys.clone(),
         cache.clone(),
     )
+    .or(fetch_balance_with_pending(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(fetch_balance_page(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(create_receipt_asset(
         dp,
         threaded_calls.clone(),
@@ -949,6 +1736,13 @@ pub fn compute_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(cancel_druid(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(utxo_addresses(
         dp,
         threaded_calls.clone(),
@@ -956,6 +1750,26 @@ pub fn compute_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(utxo_snapshot(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+    ))
+    .or(utxo_consistency(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(miner_payouts(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(address_construction(
         dp,
         routes_pow_info.clone(),
@@ -983,13 +1797,58 @@ pub fn compute_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(coordinated_shutdown(
+        dp,
+        threaded_calls.clone(),
+        local_event_tx.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(cancel_coordinated_shutdown(
+        dp,
+        local_event_tx.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(force_snapshot(
+        dp,
+        local_event_tx,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(get_coordinated_shutdown(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(get_shared_config(
+        dp,
+        threaded_calls.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(compute_metrics(
         dp,
         threaded_calls,
         routes_pow_info.clone(),
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(log_level(
+        dp,
+        log_filter,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(readiness(dp, node.clone(), node_readiness, None))
+    .or(version(dp, None))
     .or(debug_data(
         dp_vec,
         node,
@@ -999,7 +1858,10 @@ pub fn compute_node_routes(
         cache,
     ));
 
-    routes.recover(handle_rejection)
+    with_correlation_id(with_compression(
+        compression,
+        routes.recover(handle_rejection),
+    ))
 }
 
 // API routes for Miner nodes
@@ -1009,8 +1871,11 @@ pub fn miner_node_routes(
     current_block: CurrentBlockWithMutex,
     db: WalletDb,
     node: Node,
+    log_filter: LogFilterHandle,
+    disabled_routes: BTreeSet<String>,
+    compression: bool,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let mut dp_vec = DbgPaths::new();
+    let mut dp_vec = DbgPaths::new(disabled_routes);
     let dp = &mut dp_vec;
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
@@ -1057,12 +1922,27 @@ pub fn miner_node_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(submit_pow(
+        dp,
+        node.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(address_construction(
         dp,
         routes_pow_info.clone(),
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(log_level(
+        dp,
+        log_filter,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(version(dp, None))
     .or(debug_data(
         dp_vec,
         node,
@@ -1072,7 +1952,10 @@ pub fn miner_node_routes(
         cache,
     ));
 
-    routes.recover(handle_rejection)
+    with_correlation_id(with_compression(
+        compression,
+        routes.recover(handle_rejection),
+    ))
 }
 
 // API routes for Miner nodes with User node capabilities
@@ -1083,8 +1966,11 @@ pub fn miner_node_with_user_routes(
     db: WalletDb, /* Shared WalletDb */
     miner_node: Node,
     user_node: Node, /* Additional User `Node` */
+    log_filter: LogFilterHandle,
+    disabled_routes: BTreeSet<String>,
+    compression: bool,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let mut dp_vec = DbgPaths::new();
+    let mut dp_vec = DbgPaths::new(disabled_routes);
     let dp = &mut dp_vec;
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
@@ -1168,12 +2054,27 @@ pub fn miner_node_with_user_routes(
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(submit_pow(
+        dp,
+        miner_node.clone(),
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
     .or(address_construction(
         dp,
         routes_pow_info.clone(),
         api_keys.clone(),
         cache.clone(),
     ))
+    .or(log_level(
+        dp,
+        log_filter,
+        routes_pow_info.clone(),
+        api_keys.clone(),
+        cache.clone(),
+    ))
+    .or(version(dp, None))
     .or(debug_data(
         dp_vec,
         miner_node,
@@ -1183,5 +2084,20 @@ pub fn miner_node_with_user_routes(
         cache,
     ));
 
-    routes.recover(handle_rejection)
+    with_correlation_id(with_compression(
+        compression,
+        routes.recover(handle_rejection),
+    ))
+}
+
+// API routes for Pre-launch nodes
+pub fn pre_launch_node_routes(
+    status: PreLaunchStatus,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let mut dp_vec = DbgPaths::default();
+    let dp = &mut dp_vec;
+
+    let routes = prelaunch_status(dp, status).or(version(dp, None));
+
+    with_correlation_id(routes.recover(handle_rejection))
 }
@@ -1,27 +1,37 @@
 use crate::api::handlers::{
-    AddressConstructData, Addresses, ChangePassphraseData, CreateReceiptAssetDataCompute,
-    CreateReceiptAssetDataUser, CreateTransaction, CreateTxIn, CreateTxInScript, DbgPaths,
-    EncapsulatedPayment, FetchPendingData, PublicKeyAddresses,
+    AddressConstructData, AddressUtxoPageRequest, Addresses, CancelDruidData, ChangePassphraseData,
+    CoordinatedShutdownData, CreateReceiptAssetDataCompute, CreateReceiptAssetDataUser,
+    CreateTransaction, CreateTxIn, CreateTxInScript, DbgPaths, EncapsulatedPayment,
+    FetchPendingData, LogLevelData, PublicKeyAddresses, UtxoAddressesFilter,
 };
 use crate::api::routes;
-use crate::api::utils::{auth_request, create_new_cache, handle_rejection, CACHE_LIVE_TIME};
+use crate::api::utils::{
+    auth_request, create_new_cache, handle_rejection, hashed_body, json_body, request_content_hash,
+    request_signing_content, CACHE_LIVE_TIME, CORRELATION_ID_HEADER,
+};
 use crate::comms_handler::{Event, Node, TcpTlsConfig};
 use crate::compute::ComputeError;
 use crate::configurations::{ComputeNodeSharedConfig, DbMode};
-use crate::constants::FUND_KEY;
-use crate::db_utils::{new_db, SimpleDb};
+use crate::constants::{FUND_KEY, NETWORK_VERSION_SERIALIZED};
+use crate::db_utils::{new_db, DbMetrics, SimpleDb};
 use crate::interfaces::{
-    BlockchainItemMeta, ComputeApi, ComputeApiRequest, DruidDroplet, DruidPool, NodeType, Response,
-    StoredSerializingBlock, UserApiRequest, UserRequest, UtxoFetchType,
+    BlockchainItemMeta, ComputeApi, ComputeApiRequest, DruidDroplet, DruidPool, MinerPayout,
+    NodeType, Response, ResponseReason, StoredSerializingBlock, UserApiRequest, UserRequest,
+    UtxoFetchType,
+};
+use crate::raft_util::RaftProposalMetricsInfo;
+use crate::storage::{
+    put_named_last_block_to_block_chain, put_to_block_chain, BLOCKCHAIN_ENTRIES_MAX_KEYS, DB_SPEC,
 };
-use crate::storage::{put_named_last_block_to_block_chain, put_to_block_chain, DB_SPEC};
-use crate::test_utils::{generate_rb_transactions, RbReceiverData, RbSenderData};
-use crate::threaded_call::ThreadedCallChannel;
+use crate::test_utils::{generate_rb_transactions, EventCounter, RbReceiverData, RbSenderData};
+use crate::threaded_call::{ThreadedCallChannel, ThreadedCallSender};
 use crate::tracked_utxo::TrackedUtxoSet;
 use crate::utils::{
-    apply_mining_tx, construct_valid_block_pow_hash, create_receipt_asset_tx_from_sig,
-    decode_secret_key, generate_pow_for_block, to_api_keys, to_route_pow_infos,
-    tracing_log_try_init, validate_pow_block, ApiKeys,
+    apply_mining_tx, cancel_druid_participation, construct_valid_block_pow_hash,
+    create_receipt_asset_tx_from_sig, decode_secret_key, druid_cancellation_message,
+    generate_pow_for_block, mining_difficulty_from_bits, to_api_keys, to_route_pow_infos,
+    to_signed_api_keys, tracing_log_try_init, validate_pow_block, ApiKeys, LocalEvent,
+    LocalEventChannel, LocalEventSender, LogFilterHandle, NodeReadiness, StorageRaftStatus,
 };
 use crate::wallet::{AddressStore, AddressStoreHex, WalletDb, WalletDbError};
 use crate::ComputeRequest;
@@ -35,12 +45,14 @@ use naom::script::lang::Script;
 use naom::utils::transaction_utils::{
     construct_tx_hash, construct_tx_in_signable_asset_hash, construct_tx_in_signable_hash,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::error;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{reload, EnvFilter};
 use warp::http::{HeaderMap, HeaderValue, StatusCode};
 use warp::Filter;
 
@@ -50,6 +62,7 @@ const COMMON_VALID_API_KEY: &str = "some_key";
 const COMMON_VALID_API_KEYS: [&str; 2] = ["debug_data", COMMON_VALID_API_KEY];
 
 const COMMON_VALID_POW_NONCE: &str = "81234";
+const COMMON_SIGNED_BODY: &str = "{\"amount\":10}";
 const COMMON_PUB_KEY: &str = "5371832122a8e804fa3520ec6861c3fa554a7f6fb617e6f0768452090207e07c";
 const COMMON_SEC_KEY: &str = "3053020101300506032b6570042204200186bc08f16428d2059227082b93e439ff50f8c162f24b9594b132f2cc15fca4a1230321005371832122a8e804fa3520ec6861c3fa554a7f6fb617e6f0768452090207e07c";
 const COMMON_PUB_ADDR: &str = "13bd3351b78beb2d0dadf2058dcc926c";
@@ -67,8 +80,12 @@ const BLOCK_NONCE: &str = "780c05806a3b70b15c9673396171674f";
 #[derive(Default)]
 struct ComputeTest {
     pub utxo_set: TrackedUtxoSet,
+    pub tx_pool: BTreeMap<String, Transaction>,
     pub druid_pool: DruidPool,
     pub threaded_calls: ThreadedCallChannel<dyn ComputeApi>,
+    pub current_block_num: u64,
+    pub coordinated_shutdown: Option<u64>,
+    pub miner_payouts: Vec<MinerPayout>,
 }
 
 impl ComputeTest {
@@ -117,34 +134,48 @@ impl ComputeTest {
 }
 
 impl ComputeApi for ComputeTest {
+    fn get_current_block_num(&self) -> u64 {
+        self.current_block_num
+    }
+
+    fn get_coordinated_shutdown(&self) -> Option<u64> {
+        self.coordinated_shutdown
+    }
+
+    fn get_miner_payouts(&self) -> Vec<MinerPayout> {
+        self.miner_payouts.clone()
+    }
+
+    fn get_db_metrics(&self) -> DbMetrics {
+        Default::default()
+    }
+
+    fn get_raft_metrics(&self) -> RaftProposalMetricsInfo {
+        Default::default()
+    }
+
     fn get_shared_config(&self) -> ComputeNodeSharedConfig {
         Default::default()
     }
 
     fn pause_nodes(&mut self, _b_num: u64) -> Response {
-        let reason: &'static str = "";
-
         Response {
             success: true,
-            reason,
+            reason: ResponseReason::AttemptCoordinatedNodePause,
         }
     }
 
     fn resume_nodes(&mut self) -> Response {
-        let reason: &'static str = "";
-
         Response {
             success: true,
-            reason,
+            reason: ResponseReason::AttemptCoordinatedNodeResume,
         }
     }
 
     fn send_shared_config(&mut self, _shared_config: ComputeNodeSharedConfig) -> Response {
-        let reason: &'static str = "";
-
         Response {
             success: true,
-            reason,
+            reason: ResponseReason::AttemptSendSharedConfig,
         }
     }
 
@@ -152,16 +183,38 @@ impl ComputeApi for ComputeTest {
         &self.utxo_set
     }
 
+    fn get_committed_tx_pool(&self) -> &BTreeMap<String, Transaction> {
+        &self.tx_pool
+    }
+
     fn get_pending_druid_pool(&self) -> &DruidPool {
         &self.druid_pool
     }
 
-    fn receive_transactions(&mut self, _transactions: Vec<Transaction>) -> Response {
-        let reason: &'static str = "";
+    fn cancel_druid(
+        &mut self,
+        druid: &str,
+        tx_hash: &str,
+        expiry: u64,
+        public_key: &str,
+        signature: &str,
+    ) -> Response {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        cancel_druid_participation(
+            &mut self.druid_pool,
+            druid,
+            tx_hash,
+            expiry,
+            public_key,
+            signature,
+            now.as_secs(),
+        )
+    }
 
+    fn receive_transactions(&mut self, _transactions: Vec<Transaction>) -> Response {
         Response {
             success: true,
-            reason,
+            reason: ResponseReason::TransactionsAddedToTxPool,
         }
     }
 
@@ -230,7 +283,10 @@ async fn get_db_with_block_no_mutex() -> SimpleDb {
     block.set_txs_merkle_root_and_hash().await;
     block.header = apply_mining_tx(block.header, nonce, "test".to_string());
 
-    if !validate_pow_block(&block.header) {
+    if !validate_pow_block(
+        &block.header,
+        mining_difficulty_from_bits(block.header.bits),
+    ) {
         block.header = generate_pow_for_block(block.header);
         let new_nonce = hex::encode(&block.header.nonce_and_mining_tx_hash.0);
         panic!(
@@ -270,6 +326,102 @@ async fn get_db_with_block_no_mutex() -> SimpleDb {
     db
 }
 
+// Util function to create a db with a chain of linked blocks.
+// The block at `tampered_b_num` has a transaction added after its merkle root is set.
+async fn get_db_with_chain(chain_len: u64, tampered_b_num: Option<u64>) -> Arc<Mutex<SimpleDb>> {
+    let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+    let mut batch = db.batch_writer();
+
+    let mut previous_hash = None;
+    for block_num in 0..chain_len {
+        let (tx_hash, _) = generate_transaction(&format!("tx_hash_{block_num}"), COMMON_PUB_ADDR);
+        let mut block = Block::new();
+        block.header.b_num = block_num;
+        block.header.previous_hash = previous_hash.take();
+        block.transactions.push(tx_hash);
+        block.set_txs_merkle_root_and_hash().await;
+        if tampered_b_num == Some(block_num) {
+            block
+                .transactions
+                .push("g00000000000000000000000000000000".to_owned());
+        }
+
+        let block_to_input = StoredSerializingBlock { block };
+        let block_input = serialize(&block_to_input).unwrap();
+        let block_json = serde_json::to_vec(&block_to_input).unwrap();
+        let block_hash = format!("b{block_num:064x}");
+
+        let t = BlockchainItemMeta::Block {
+            block_num,
+            tx_len: 1,
+        };
+        let pointer = put_to_block_chain(&mut batch, &t, &block_hash, &block_input, &block_json);
+        put_named_last_block_to_block_chain(&mut batch, &pointer);
+        previous_hash = Some(block_hash);
+    }
+
+    let batch = batch.done();
+    db.write(batch).unwrap();
+    Arc::new(Mutex::new(db))
+}
+
+// Util function to create a db with a chain of blocks, each spending the previous block
+// transaction output and having its own mining transaction.
+// Returns the db and the (transaction hash, mining transaction hash) of each block.
+async fn get_db_with_utxo_chain(chain_len: u64) -> (Arc<Mutex<SimpleDb>>, Vec<(String, String)>) {
+    let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+    let mut batch = db.batch_writer();
+
+    let mut block_tx_hashes = Vec::new();
+    let mut spent_tx_hash = "000000".to_owned();
+    for block_num in 0..chain_len {
+        let (tx_hash, tx) = generate_transaction(&spent_tx_hash, COMMON_PUB_ADDR);
+        let mining_tx = Transaction {
+            outputs: vec![TxOut::new_token_amount(
+                COMMON_PUB_ADDR.to_owned(),
+                TokenAmount(block_num + 1),
+            )],
+            ..Default::default()
+        };
+        let mining_tx_hash = construct_tx_hash(&mining_tx);
+
+        let mut block = Block::new();
+        block.header.b_num = block_num;
+        block.header.nonce_and_mining_tx_hash = (Vec::new(), mining_tx_hash.clone());
+        block.transactions.push(tx_hash.clone());
+
+        let block_to_input = StoredSerializingBlock { block };
+        let block_input = serialize(&block_to_input).unwrap();
+        let block_json = serde_json::to_vec(&block_to_input).unwrap();
+        let block_hash = format!("b{block_num:064x}");
+
+        let t = BlockchainItemMeta::Block {
+            block_num,
+            tx_len: 2,
+        };
+        let pointer = put_to_block_chain(&mut batch, &t, &block_hash, &block_input, &block_json);
+        put_named_last_block_to_block_chain(&mut batch, &pointer);
+
+        let txs = vec![(&tx_hash, &tx), (&mining_tx_hash, &mining_tx)];
+        for (tx_num, (hash, tx)) in txs.into_iter().enumerate() {
+            let t = BlockchainItemMeta::Tx {
+                block_num,
+                tx_num: tx_num as u32,
+            };
+            let tx_value = serialize(tx).unwrap();
+            let tx_json = serde_json::to_vec(tx).unwrap();
+            put_to_block_chain(&mut batch, &t, hash, &tx_value, &tx_json);
+        }
+
+        block_tx_hashes.push((tx_hash.clone(), mining_tx_hash));
+        spent_tx_hash = tx_hash;
+    }
+
+    let batch = batch.done();
+    db.write(batch).unwrap();
+    (Arc::new(Mutex::new(db)), block_tx_hashes)
+}
+
 // Util function to create a transaction.
 // Returns the hash of the tx and the tx itself
 fn get_transaction() -> (String, Transaction) {
@@ -315,6 +467,26 @@ fn get_rb_transactions() -> Vec<(String, Transaction)> {
     generate_rb_transactions(rb_sender_data, rb_receiver_data)
 }
 
+fn cancel_druid_data(druid: &str, tx_hash: &str, expiry: u64) -> CancelDruidData {
+    let sk = decode_secret_key(COMMON_SEC_KEY).unwrap();
+    let message = druid_cancellation_message(druid, tx_hash, expiry);
+    let signature = sign::sign_detached(&message, &sk);
+
+    CancelDruidData {
+        druid: druid.to_owned(),
+        tx_hash: tx_hash.to_owned(),
+        expiry,
+        public_key: COMMON_PUB_KEY.to_owned(),
+        signature: hex::encode(signature.as_ref()),
+    }
+}
+
+/// Cancellation expiry within the accepted window
+fn cancel_druid_expiry() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    now.as_secs() + 60
+}
+
 fn success_json() -> (StatusCode, HeaderMap) {
     let mut headers = HeaderMap::new();
     headers.insert("content-type", HeaderValue::from_static("application/json"));
@@ -328,23 +500,45 @@ fn fail_json(code: StatusCode) -> (StatusCode, HeaderMap) {
     (code, headers)
 }
 
+fn correlated(
+    (code, mut headers): (StatusCode, HeaderMap),
+    correlation_id: &'static str,
+) -> (StatusCode, HeaderMap) {
+    headers.insert(
+        CORRELATION_ID_HEADER,
+        HeaderValue::from_static(correlation_id),
+    );
+    (code, headers)
+}
+
+fn with_etag((code, mut headers): (StatusCode, HeaderMap), etag: &str) -> (StatusCode, HeaderMap) {
+    headers.insert("etag", HeaderValue::from_str(etag).unwrap());
+    (code, headers)
+}
+
 pub async fn ok_reply() -> Result<impl warp::Reply, warp::Rejection> {
     Ok(warp::reply::json(&0))
 }
 
 fn user_api_request_as_frame(request: UserApiRequest) -> Option<Vec<u8>> {
-    let sent_request = UserRequest::UserApi(request);
+    let sent_request = UserRequest::UserApi {
+        request,
+        correlation_id: COMMON_REQ_ID.to_owned(),
+    };
     Some(serialize(&sent_request).unwrap())
 }
 
 fn compute_api_request_as_frame(request: ComputeApiRequest) -> Option<Vec<u8>> {
-    let sent_request = ComputeRequest::ComputeApi(request);
+    let sent_request = ComputeRequest::ComputeApi {
+        request,
+        correlation_id: COMMON_REQ_ID.to_owned(),
+    };
     Some(serialize(&sent_request).unwrap())
 }
 
 async fn next_event_frame(node: &mut Node) -> Option<Vec<u8>> {
     let evt = node.next_event().await;
-    evt.map(|Event::NewFrame { peer: _, frame }| frame.to_vec())
+    evt.map(|Event::NewFrame { frame, .. }| frame.to_vec())
 }
 
 async fn new_self_node(node_type: NodeType) -> (Node, SocketAddr) {
@@ -368,6 +562,10 @@ fn dp() -> DbgPaths {
     Default::default()
 }
 
+fn test_log_filter() -> LogFilterHandle {
+    reload::Layer::new(EnvFilter::new("off")).1
+}
+
 /*------- GET TESTS--------*/
 
 /// Test GET latest block info
@@ -387,10 +585,271 @@ async fn test_get_latest_block() {
         .recover(handle_rejection);
     let res = request.reply(&filter).await;
 
-    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        with_etag(
+            success_json(),
+            "\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\""
+        )
+    );
     assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"latest_block\",\"content\":{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}}");
 }
 
+/// Test GET latest block info is not modified when the client has its ETag
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_latest_block_not_modified() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    let filter = |cache| {
+        routes::latest_block(&mut dp(), db.clone(), Default::default(), ks.clone(), cache)
+            .recover(handle_rejection)
+    };
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/latest_block")
+    };
+
+    //
+    // Act
+    //
+    let res_first = request()
+        .reply(&filter(create_new_cache(CACHE_LIVE_TIME)))
+        .await;
+    let etag = res_first.headers().get("etag").cloned().unwrap();
+    let res_cached = request()
+        .header("if-none-match", etag.to_str().unwrap())
+        .reply(&filter(create_new_cache(CACHE_LIVE_TIME)))
+        .await;
+    let res_stale = request()
+        .header("if-none-match", "\"b0001\"")
+        .reply(&filter(create_new_cache(CACHE_LIVE_TIME)))
+        .await;
+
+    //
+    // Assert
+    //
+    assert_eq!(res_first.status(), StatusCode::OK);
+    assert_eq!(
+        etag,
+        "\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\""
+    );
+
+    assert_eq!(
+        (res_cached.status(), res_cached.headers().clone()),
+        with_etag(
+            fail_json(StatusCode::NOT_MODIFIED),
+            "\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\""
+        )
+    );
+    assert!(res_cached.body().is_empty());
+
+    assert_eq!(res_stale.status(), StatusCode::OK);
+    assert_eq!(res_stale.body(), res_first.body());
+}
+
+/// Test GET latest block long-poll returns a block stored while it is pending
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_latest_block_longpoll() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let status = StorageRaftStatus::default();
+    let ks = to_api_keys(Default::default());
+    let filter = routes::latest_block_longpoll(
+        &mut dp(),
+        db.clone(),
+        status.clone(),
+        Duration::from_secs(10),
+        1,
+        Default::default(),
+        ks.clone(),
+    )
+    .recover(handle_rejection);
+    let filter_timeout = routes::latest_block_longpoll(
+        &mut dp(),
+        db.clone(),
+        status.clone(),
+        Duration::from_millis(10),
+        1,
+        Default::default(),
+        ks,
+    )
+    .recover(handle_rejection);
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/latest_block_longpoll?last_hash=b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc")
+    };
+
+    let block_hash = format!("b{:064x}", 1);
+    let store_block = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut block = Block::new();
+        block.header.b_num = 1;
+        let stored_block = StoredSerializingBlock { block };
+        {
+            let mut db = db.lock().unwrap();
+            let mut batch = db.batch_writer();
+            let t = BlockchainItemMeta::Block {
+                block_num: 1,
+                tx_len: 0,
+            };
+            let block_input = serialize(&stored_block).unwrap();
+            let block_json = serde_json::to_vec(&stored_block).unwrap();
+            let pointer =
+                put_to_block_chain(&mut batch, &t, &block_hash, &block_input, &block_json);
+            put_named_last_block_to_block_chain(&mut batch, &pointer);
+            let batch = batch.done();
+            db.write(batch).unwrap();
+        }
+        status.set_stored_block_num(Some(1));
+    };
+
+    //
+    // Act
+    //
+    let res_timeout = request().reply(&filter_timeout).await;
+    let (res, ()) = tokio::join!(request().reply(&filter), store_block);
+    let res_stored = request().reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(res_timeout.status(), StatusCode::NOT_MODIFIED);
+    assert!(res_timeout.body().is_empty());
+
+    let etag = format!("\"{block_hash}\"");
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        with_etag(success_json(), &etag)
+    );
+    assert!(from_utf8(res.body()).contains("\"b_num\":1"));
+    assert_eq!(res_stored.body(), res.body());
+}
+
+/// Test GET latest block long-poll is rejected as overloaded when too many are pending
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_latest_block_longpoll_max_pending() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    let filter = routes::latest_block_longpoll(
+        &mut dp(),
+        db,
+        Default::default(),
+        Duration::from_millis(200),
+        1,
+        Default::default(),
+        ks,
+    )
+    .recover(handle_rejection);
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/latest_block_longpoll?last_hash=b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc")
+    };
+    let request_while_pending = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        request().reply(&filter).await
+    };
+
+    //
+    // Act
+    //
+    let (res_pending, res_overloaded) =
+        tokio::join!(request().reply(&filter), request_while_pending);
+    let res_after = request().reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(res_pending.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(
+        (res_overloaded.status(), res_overloaded.headers().clone()),
+        fail_json(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(res_overloaded.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Node overloaded: Too many pending long-polls\",\"route\":\"latest_block_longpoll\",\"content\":\"null\"}");
+    assert_eq!(res_after.status(), StatusCode::NOT_MODIFIED);
+}
+
+/// Test GET latest block info projected to headers only or transaction hashes only
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_latest_block_projected() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    let filter = |cache| {
+        routes::latest_block(&mut dp(), db.clone(), Default::default(), ks.clone(), cache)
+            .recover(handle_rejection)
+    };
+    let request = |path: &str| {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path(path)
+    };
+
+    //
+    // Act
+    //
+    let res_header = request("/latest_block?fields=header")
+        .reply(&filter(create_new_cache(CACHE_LIVE_TIME)))
+        .await;
+    let res_tx_hashes = request("/latest_block?tx_hashes_only=true")
+        .reply(&filter(create_new_cache(CACHE_LIVE_TIME)))
+        .await;
+    let res_unknown = request("/latest_block?fields=header,nonce")
+        .reply(&filter(create_new_cache(CACHE_LIVE_TIME)))
+        .await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res_header.status(), res_header.headers().clone()),
+        with_etag(
+            success_json(),
+            "\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc;header\""
+        )
+    );
+    assert!(!from_utf8(res_header.body()).contains("transactions"));
+    assert_eq!(res_header.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"latest_block\",\"content\":{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]}}}}");
+
+    assert_eq!(
+        (res_tx_hashes.status(), res_tx_hashes.headers().clone()),
+        with_etag(
+            success_json(),
+            "\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc;transactions\""
+        )
+    );
+    assert_eq!(res_tx_hashes.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"latest_block\",\"content\":{\"block\":{\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}}");
+
+    assert_eq!(
+        (res_unknown.status(), res_unknown.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+}
+
 /// Test GET wallet keypairs
 #[tokio::test(flavor = "current_thread")]
 async fn test_get_export_keypairs() {
@@ -443,8 +902,8 @@ async fn test_get_user_debug_data() {
     // Arrange
     //
     let db = get_wallet_db("").await;
-    let ks: ApiKeys = Arc::new(Mutex::new(BTreeMap::new()));
-    ks.lock().unwrap().insert(
+    let ks: ApiKeys = Default::default();
+    ks.lock().unwrap().route_keys.insert(
         COMMON_VALID_API_KEYS[0].to_string(),
         vec![COMMON_VALID_API_KEYS[1].to_string()],
     );
@@ -463,15 +922,23 @@ async fn test_get_user_debug_data() {
     //
     // Act
     //
-    let filter = routes::user_node_routes(ks, Default::default(), db, self_node.clone())
-        .recover(handle_rejection);
+    let filter = routes::user_node_routes(
+        ks,
+        Default::default(),
+        db,
+        self_node.clone(),
+        test_log_filter(),
+        Default::default(),
+        false,
+    )
+    .recover(handle_rejection);
     let res_a = request_x_api().reply(&filter).await;
     let res_m = request().reply(&filter).await;
 
     //
     // Assert
     //
-    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"User\",\"node_api\":[\"wallet_info\",\"make_payment\",\"make_ip_payment\",\"request_donation\",\"export_keypairs\",\"import_keypairs\",\"update_running_total\",\"create_receipt_asset\",\"payment_address\",\"change_passphrase\",\"address_construction\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13000\",\"127.0.0.1:13000\",\"Compute\"]],\"routes_pow\":{}}}";
+    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"User\",\"node_api\":[\"wallet_info\",\"make_payment\",\"make_ip_payment\",\"request_donation\",\"export_keypairs\",\"import_keypairs\",\"update_running_total\",\"create_receipt_asset\",\"payment_address\",\"change_passphrase\",\"address_construction\",\"log_level\",\"version\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13000\",\"127.0.0.1:13000\",\"Compute\"]],\"routes_pow\":{}}}";
     assert_eq!((res_a.status(), res_a.headers().clone()), success_json());
     assert_eq!(res_a.body(), expected_string);
 
@@ -491,8 +958,8 @@ async fn test_get_storage_debug_data() {
     // Arrange
     //
     let db = get_db_with_block().await;
-    let ks: ApiKeys = Arc::new(Mutex::new(BTreeMap::new()));
-    ks.lock().unwrap().insert(
+    let ks: ApiKeys = Default::default();
+    ks.lock().unwrap().route_keys.insert(
         COMMON_VALID_API_KEYS[0].to_string(),
         vec![COMMON_VALID_API_KEYS[1].to_string()],
     );
@@ -511,15 +978,28 @@ async fn test_get_storage_debug_data() {
     //
     // Act
     //
-    let filter = routes::storage_node_routes(ks, Default::default(), db, self_node.clone())
-        .recover(handle_rejection);
+    let filter = routes::storage_node_routes(
+        ks,
+        Default::default(),
+        db,
+        LocalEventChannel::default().tx,
+        self_node.clone(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        test_log_filter(),
+        Default::default(),
+        false,
+    )
+    .recover(handle_rejection);
     let res_a = request_x_api().reply(&filter).await;
     let res_m = request().reply(&filter).await;
 
     //
     // Assert
     //
-    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Storage\",\"node_api\":[\"block_by_num\",\"transactions_by_key\",\"latest_block\",\"blockchain_entry\",\"check_transaction_presence\",\"address_construction\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13010\",\"127.0.0.1:13010\",\"Compute\"]],\"routes_pow\":{}}}";
+    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Storage\",\"node_api\":[\"block_by_num\",\"transactions_by_key\",\"latest_block\",\"latest_block_longpoll\",\"blockchain_entry\",\"blockchain_entries_by_keys\",\"check_transaction_presence\",\"transaction_by_hash\",\"tx_confirmations\",\"verify_chain\",\"utxo_at_block\",\"address_transactions\",\"metrics\",\"storage_raft_status\",\"compact_db\",\"force_snapshot\",\"address_construction\",\"log_level\",\"readyz\",\"version\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13010\",\"127.0.0.1:13010\",\"Compute\"]],\"routes_pow\":{}}}";
     assert_eq!((res_a.status(), res_a.headers().clone()), success_json());
     assert_eq!(res_a.body(), expected_string);
 
@@ -530,20 +1010,91 @@ async fn test_get_storage_debug_data() {
     assert_eq!(res_m.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Unauthorized\",\"route\":\"debug_data\",\"content\":\"null\"}");
 }
 
-/// Test get compute debug data
+/// Test node routes compress replies as accepted by the client when enabled
 #[tokio::test(flavor = "current_thread")]
-async fn test_get_compute_debug_data() {
+async fn test_storage_routes_compression() {
     let _ = tracing_log_try_init();
 
     //
     // Arrange
     //
-    let compute = ComputeTest::new(vec![]);
-    let ks: ApiKeys = Arc::new(Mutex::new(BTreeMap::new()));
-    ks.lock().unwrap().insert(
-        COMMON_VALID_API_KEYS[0].to_string(),
-        vec![COMMON_VALID_API_KEYS[1].to_string()],
-    );
+    let db = get_db_with_block().await;
+    let (self_node, _self_socket) = new_self_node(NodeType::Storage).await;
+    let filter = |compression| {
+        routes::storage_node_routes(
+            to_api_keys(Default::default()),
+            Default::default(),
+            db.clone(),
+            LocalEventChannel::default().tx,
+            self_node.clone(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            test_log_filter(),
+            Default::default(),
+            compression,
+        )
+    };
+    let request = |accept_encoding: Option<&str>| {
+        let request = warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/latest_block");
+        match accept_encoding {
+            Some(accept_encoding) => request.header("accept-encoding", accept_encoding),
+            None => request,
+        }
+    };
+
+    //
+    // Act
+    //
+    let res_gzip = request(Some("gzip, deflate")).reply(&filter(true)).await;
+    let res_deflate = request(Some("gzip;q=0, deflate"))
+        .reply(&filter(true))
+        .await;
+    let res_identity = request(None).reply(&filter(true)).await;
+    let res_disabled = request(Some("gzip")).reply(&filter(false)).await;
+
+    //
+    // Assert
+    //
+    let content_encoding = |res: &warp::http::Response<bytes::Bytes>| {
+        res.headers()
+            .get("content-encoding")
+            .map(|v| v.to_str().unwrap().to_owned())
+    };
+    assert_eq!(res_gzip.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&res_gzip), Some("gzip".to_owned()));
+    assert_eq!(&res_gzip.body()[..2], &[0x1f, 0x8b]);
+
+    assert_eq!(res_deflate.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&res_deflate), Some("deflate".to_owned()));
+    assert_ne!(res_deflate.body(), res_identity.body());
+
+    assert_eq!(res_identity.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&res_identity), None);
+    assert!(from_utf8(res_identity.body()).contains("\"route\":\"latest_block\""));
+
+    assert_eq!(content_encoding(&res_disabled), None);
+    assert_eq!(res_disabled.body(), res_identity.body());
+}
+
+/// Test get compute debug data
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_compute_debug_data() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let compute = ComputeTest::new(vec![]);
+    let ks: ApiKeys = Default::default();
+    ks.lock().unwrap().route_keys.insert(
+        COMMON_VALID_API_KEYS[0].to_string(),
+        vec![COMMON_VALID_API_KEYS[1].to_string()],
+    );
     let (mut self_node, _self_socket) = new_self_node(NodeType::Compute).await;
     let (_c_node, c_socket) = new_self_node_with_port(NodeType::Compute, 13020).await;
     self_node.connect_to(c_socket).await.unwrap();
@@ -568,15 +1119,25 @@ async fn test_get_compute_debug_data() {
         .into_iter()
         .collect(),
     );
-    let filter = routes::compute_node_routes(ks, routes_pow, tx, self_node.clone())
-        .recover(handle_rejection);
+    let filter = routes::compute_node_routes(
+        ks,
+        routes_pow,
+        tx,
+        LocalEventChannel::default().tx,
+        self_node.clone(),
+        Default::default(),
+        test_log_filter(),
+        Default::default(),
+        false,
+    )
+    .recover(handle_rejection);
     let res_a = request_x_api().reply(&filter).await;
     let res_m = request().reply(&filter).await;
 
     //
     // Assert
     //
-    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Compute\",\"node_api\":[\"fetch_balance\",\"create_receipt_asset\",\"create_transactions\",\"utxo_addresses\",\"address_construction\",\"pause_nodes\",\"resume_nodes\",\"update_shared_config\",\"get_shared_config\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13020\",\"127.0.0.1:13020\",\"Compute\"]],\"routes_pow\":{\"create_transactions\":2}}}";
+    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Compute\",\"node_api\":[\"fetch_balance\",\"fetch_balance_with_pending\",\"fetch_balance_page\",\"create_receipt_asset\",\"create_transactions\",\"cancel_druid\",\"utxo_addresses\",\"utxo_snapshot\",\"utxo_consistency\",\"miner_payouts\",\"address_construction\",\"pause_nodes\",\"resume_nodes\",\"update_shared_config\",\"coordinated_shutdown\",\"cancel_coordinated_shutdown\",\"force_snapshot\",\"get_coordinated_shutdown\",\"get_shared_config\",\"metrics\",\"log_level\",\"readyz\",\"version\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13020\",\"127.0.0.1:13020\",\"Compute\"]],\"routes_pow\":{\"create_transactions\":2}}}";
     assert_eq!((res_a.status(), res_a.headers().clone()), success_json());
     assert_eq!(res_a.body(), expected_string);
 
@@ -587,6 +1148,111 @@ async fn test_get_compute_debug_data() {
     assert_eq!(res_m.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Unauthorized\",\"route\":\"debug_data\",\"content\":\"null\"}");
 }
 
+/// Test compute routes with a disabled route
+#[tokio::test(flavor = "current_thread")]
+async fn test_compute_disabled_routes() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let compute = ComputeTest::new(vec![]);
+    let ks = to_api_keys(Default::default());
+    let (self_node, _self_socket) = new_self_node(NodeType::Compute).await;
+    let disabled_routes = vec!["create_transactions".to_owned()].into_iter().collect();
+
+    let request = |method: &str, path: &str| {
+        warp::test::request()
+            .method(method)
+            .header("x-request-id", COMMON_REQ_ID)
+            .path(path)
+    };
+
+    //
+    // Act
+    //
+    let tx = compute.threaded_calls.tx.clone();
+    let filter = routes::compute_node_routes(
+        ks,
+        Default::default(),
+        tx,
+        LocalEventChannel::default().tx,
+        self_node,
+        Default::default(),
+        test_log_filter(),
+        disabled_routes,
+        false,
+    )
+    .recover(handle_rejection);
+    let res_disabled = request("POST", "/create_transactions").reply(&filter).await;
+    let res_enabled = request("GET", "/version").reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res_disabled.status(), res_disabled.headers().clone()),
+        correlated(fail_json(StatusCode::NOT_FOUND), COMMON_REQ_ID)
+    );
+    assert_eq!(
+        (res_enabled.status(), res_enabled.headers().clone()),
+        correlated(success_json(), COMMON_REQ_ID)
+    );
+}
+
+/// Test the correlation ID of a request is echoed in the response
+#[tokio::test(flavor = "current_thread")]
+async fn test_compute_correlation_id() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let compute = ComputeTest::new(vec![]);
+    let ks = to_api_keys(Default::default());
+    let (self_node, _self_socket) = new_self_node(NodeType::Compute).await;
+
+    //
+    // Act
+    //
+    let tx = compute.threaded_calls.tx.clone();
+    let filter = routes::compute_node_routes(
+        ks,
+        Default::default(),
+        tx,
+        LocalEventChannel::default().tx,
+        self_node,
+        Default::default(),
+        test_log_filter(),
+        Default::default(),
+        false,
+    );
+    let res_provided = warp::test::request()
+        .method("GET")
+        .header("x-request-id", COMMON_REQ_ID)
+        .path("/version")
+        .reply(&filter)
+        .await;
+    let res_generated = warp::test::request()
+        .method("GET")
+        .path("/version")
+        .reply(&filter)
+        .await;
+
+    //
+    // Assert
+    //
+    let generated_id = res_generated.headers().get(CORRELATION_ID_HEADER).cloned();
+    let generated_id = generated_id.and_then(|id| id.to_str().ok().map(str::to_owned));
+    assert_eq!(
+        (res_provided.status(), res_provided.headers().clone()),
+        correlated(success_json(), COMMON_REQ_ID)
+    );
+    assert_eq!(generated_id.as_ref().map(|id| id.len()), Some(32));
+    let body: serde_json::Value = serde_json::from_slice(res_generated.body()).unwrap();
+    assert_eq!(body["id"].as_str(), generated_id.as_deref());
+}
+
 /// Test get miner debug data
 #[tokio::test(flavor = "current_thread")]
 async fn test_get_miner_debug_data() {
@@ -597,8 +1263,8 @@ async fn test_get_miner_debug_data() {
     //
     let db = get_wallet_db("").await;
     let current_block = Default::default();
-    let ks: ApiKeys = Arc::new(Mutex::new(BTreeMap::new()));
-    ks.lock().unwrap().insert(
+    let ks: ApiKeys = Default::default();
+    ks.lock().unwrap().route_keys.insert(
         COMMON_VALID_API_KEYS[0].to_string(),
         vec![COMMON_VALID_API_KEYS[1].to_string()],
     );
@@ -617,16 +1283,24 @@ async fn test_get_miner_debug_data() {
     //
     // Act
     //
-    let filter =
-        routes::miner_node_routes(ks, Default::default(), current_block, db, self_node.clone())
-            .recover(handle_rejection);
+    let filter = routes::miner_node_routes(
+        ks,
+        Default::default(),
+        current_block,
+        db,
+        self_node.clone(),
+        test_log_filter(),
+        Default::default(),
+        false,
+    )
+    .recover(handle_rejection);
     let res_a = request_x_api().reply(&filter).await;
     let res_m = request().reply(&filter).await;
 
     //
     // Assert
     //
-    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Miner\",\"node_api\":[\"wallet_info\",\"export_keypairs\",\"import_keypairs\",\"payment_address\",\"change_passphrase\",\"current_mining_block\",\"address_construction\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13030\",\"127.0.0.1:13030\",\"Compute\"]],\"routes_pow\":{}}}";
+    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Miner\",\"node_api\":[\"wallet_info\",\"export_keypairs\",\"import_keypairs\",\"payment_address\",\"change_passphrase\",\"current_mining_block\",\"submit_pow\",\"address_construction\",\"log_level\",\"version\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13030\",\"127.0.0.1:13030\",\"Compute\"]],\"routes_pow\":{}}}";
     assert_eq!((res_a.status(), res_a.headers().clone()), success_json());
     assert_eq!(res_a.body(), expected_string);
 
@@ -647,8 +1321,8 @@ async fn test_get_miner_with_user_debug_data() {
     //
     let db = get_wallet_db("").await;
     let current_block = Default::default();
-    let ks: ApiKeys = Arc::new(Mutex::new(BTreeMap::new()));
-    ks.lock().unwrap().insert(
+    let ks: ApiKeys = Default::default();
+    ks.lock().unwrap().route_keys.insert(
         COMMON_VALID_API_KEYS[0].to_string(),
         vec![COMMON_VALID_API_KEYS[1].to_string()],
     );
@@ -677,6 +1351,9 @@ async fn test_get_miner_with_user_debug_data() {
         db,
         self_node,
         self_node_u,
+        test_log_filter(),
+        Default::default(),
+        false,
     )
     .recover(handle_rejection);
     let res_a = request_x_api().reply(&filter).await;
@@ -685,7 +1362,7 @@ async fn test_get_miner_with_user_debug_data() {
     //
     // Assert
     //
-    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Miner/User\",\"node_api\":[\"wallet_info\",\"make_payment\",\"make_ip_payment\",\"request_donation\",\"export_keypairs\",\"import_keypairs\",\"update_running_total\",\"create_receipt_asset\",\"payment_address\",\"change_passphrase\",\"current_mining_block\",\"address_construction\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13040\",\"127.0.0.1:13040\",\"Compute\"],[\"127.0.0.1:13041\",\"127.0.0.1:13041\",\"Storage\"]],\"routes_pow\":{}}}";
+    let expected_string = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Debug data successfully retrieved\",\"route\":\"debug_data\",\"content\":{\"node_type\":\"Miner/User\",\"node_api\":[\"wallet_info\",\"make_payment\",\"make_ip_payment\",\"request_donation\",\"export_keypairs\",\"import_keypairs\",\"update_running_total\",\"create_receipt_asset\",\"payment_address\",\"change_passphrase\",\"current_mining_block\",\"submit_pow\",\"address_construction\",\"log_level\",\"version\",\"debug_data\"],\"node_peers\":[[\"127.0.0.1:13040\",\"127.0.0.1:13040\",\"Compute\"],[\"127.0.0.1:13041\",\"127.0.0.1:13041\",\"Storage\"]],\"routes_pow\":{}}}";
     assert_eq!((res_a.status(), res_a.headers().clone()), success_json());
     assert_eq!(res_a.body(), expected_string);
 
@@ -696,6 +1373,192 @@ async fn test_get_miner_with_user_debug_data() {
     assert_eq!(res_m.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Unauthorized\",\"route\":\"debug_data\",\"content\":\"null\"}");
 }
 
+/// Test GET readiness on a compute node before and after its initial snapshot
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_compute_readiness() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let (self_node, _self_socket) = new_self_node(NodeType::Compute).await;
+    let readiness = NodeReadiness::new(Vec::new());
+
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/readyz")
+    };
+
+    //
+    // Act
+    //
+    let filter =
+        routes::readiness(&mut dp(), self_node, readiness.clone(), None).recover(handle_rejection);
+    let res_not_ready = request().reply(&filter).await;
+    readiness.set_initial_state_applied(true);
+    let res_ready = request().reply(&filter).await;
+    readiness.set_block_production_stalled(true);
+    let res_stalled = request().reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res_not_ready.status(), res_not_ready.headers().clone()),
+        fail_json(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(res_not_ready.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Node not ready: Initial raft snapshot not applied\",\"route\":\"readyz\",\"content\":\"null\"}");
+
+    assert_eq!(
+        (res_ready.status(), res_ready.headers().clone()),
+        success_json()
+    );
+    assert_eq!(res_ready.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Node is ready\",\"route\":\"readyz\",\"content\":\"null\"}");
+
+    assert_eq!(
+        (res_stalled.status(), res_stalled.headers().clone()),
+        fail_json(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(res_stalled.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Node not ready: Block production stalled\",\"route\":\"readyz\",\"content\":\"null\"}");
+}
+
+/// Test GET readiness on a storage node requires a loadable last block
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_storage_readiness() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let empty_db = Arc::new(Mutex::new(new_db(DbMode::InMemory, &DB_SPEC, None, None)));
+    let (self_node, _self_socket) = new_self_node(NodeType::Storage).await;
+    let readiness = NodeReadiness::new(Vec::new());
+
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/readyz")
+    };
+
+    //
+    // Act
+    //
+    let filter = routes::readiness(&mut dp(), self_node.clone(), readiness.clone(), Some(db))
+        .recover(handle_rejection);
+    let empty_filter = routes::readiness(&mut dp(), self_node, readiness.clone(), Some(empty_db))
+        .recover(handle_rejection);
+    let res_not_ready = request().reply(&filter).await;
+    readiness.set_initial_state_applied(true);
+    let res_ready = request().reply(&filter).await;
+    let res_no_block = request().reply(&empty_filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res_not_ready.status(), res_not_ready.headers().clone()),
+        fail_json(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(res_not_ready.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Node not ready: Initial raft snapshot not applied\",\"route\":\"readyz\",\"content\":\"null\"}");
+
+    assert_eq!(
+        (res_ready.status(), res_ready.headers().clone()),
+        success_json()
+    );
+    assert_eq!(res_ready.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Node is ready\",\"route\":\"readyz\",\"content\":\"null\"}");
+
+    assert_eq!(
+        (res_no_block.status(), res_no_block.headers().clone()),
+        fail_json(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(res_no_block.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Node not ready: Last block cannot be loaded\",\"route\":\"readyz\",\"content\":\"null\"}");
+}
+
+/// Test GET version reports the network protocol version, and the db version when available
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_version() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let network_version = String::from_utf8_lossy(NETWORK_VERSION_SERIALIZED);
+    let software_version = env!("CARGO_PKG_VERSION");
+
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/version")
+    };
+
+    //
+    // Act
+    //
+    let filter = routes::version(&mut dp(), Some(db)).recover(handle_rejection);
+    let no_db_filter = routes::version(&mut dp(), None).recover(handle_rejection);
+    let res = request().reply(&filter).await;
+    let res_no_db = request().reply(&no_db_filter).await;
+
+    //
+    // Assert
+    //
+    let expected_string = format!("{{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Version successfully retrieved\",\"route\":\"version\",\"content\":{{\"software_version\":\"{software_version}\",\"network_version\":\"{network_version}\",\"db_version\":\"{network_version}\"}}}}");
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), &expected_string);
+
+    let expected_string = format!("{{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Version successfully retrieved\",\"route\":\"version\",\"content\":{{\"software_version\":\"{software_version}\",\"network_version\":\"{network_version}\",\"db_version\":null}}}}");
+    assert_eq!(
+        (res_no_db.status(), res_no_db.headers().clone()),
+        success_json()
+    );
+    assert_eq!(res_no_db.body(), &expected_string);
+}
+
+/// Test GET storage raft status reports the stored block lag behind compute
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_storage_raft_status() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let status = StorageRaftStatus::default();
+    status.set_stored_block_num(Some(2));
+    status.set_compute_block_num(5);
+
+    let request = || {
+        warp::test::request()
+            .method("GET")
+            .header("x-request-id", COMMON_REQ_ID)
+            .path("/storage_raft_status")
+    };
+
+    //
+    // Act
+    //
+    let filter = routes::storage_raft_status(
+        &mut dp(),
+        status,
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let res = request().reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Storage raft status successfully retrieved\",\"route\":\"storage_raft_status\",\"content\":{\"stored_block_num\":2,\"compute_block_num\":5,\"lag\":3}}");
+}
+
 // Authorize a request where no proof-of-work or API key is required
 #[tokio::test(flavor = "current_thread")]
 async fn auth_request_no_pow_with_no_api_key() {
@@ -829,17 +1692,152 @@ async fn auth_request_common(
     assert_eq!(actual_response.body(), expected_response_body);
 }
 
-/// Test GET wallet info
+// Authorize a request correctly signed by an API key that opted in to signing
 #[tokio::test(flavor = "current_thread")]
-async fn test_get_wallet_info() {
-    let _ = tracing_log_try_init();
+async fn auth_request_signed_with_api_key() {
+    signed_request_common(COMMON_SIGNED_BODY, "?limit=1", 0, 0, true).await;
+}
 
-    //
-    // Arrange
-    //
-    let db = get_wallet_db("").await;
-    let mut fund_store = db.get_fund_store();
-    let out_point = OutPoint::new("tx_hash".to_string(), 0);
+// Authorize a signed request whose body was altered after signing
+#[tokio::test(flavor = "current_thread")]
+async fn auth_request_signed_tampered_body_failure() {
+    signed_request_common("{\"amount\":1000}", "?limit=1", 0, 0, false).await;
+}
+
+// Authorize a signed request whose query string was altered after signing
+#[tokio::test(flavor = "current_thread")]
+async fn auth_request_signed_tampered_query_failure() {
+    signed_request_common(COMMON_SIGNED_BODY, "?limit=1000", 0, 0, false).await;
+}
+
+// Authorize a signed request whose timestamp is too old to be accepted
+#[tokio::test(flavor = "current_thread")]
+async fn auth_request_signed_stale_timestamp_failure() {
+    signed_request_common(COMMON_SIGNED_BODY, "?limit=1", 3600, 0, false).await;
+}
+
+// Authorize a signed request replayed after it was already accepted
+#[tokio::test(flavor = "current_thread")]
+async fn auth_request_signed_replay_failure() {
+    signed_request_common(COMMON_SIGNED_BODY, "?limit=1", 0, 1, false).await;
+}
+
+// Check a raw body read by a route against the content hash header
+#[tokio::test(flavor = "current_thread")]
+async fn hashed_body_tampered_failure() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let content_hash = request_content_hash(COMMON_SIGNED_BODY.as_bytes());
+    let request = |body: &str| {
+        warp::test::request()
+            .method("POST")
+            .header("x-request-id", COMMON_REQ_ID)
+            .header("x-content-hash", content_hash.as_str())
+            .path("/utxo_addresses")
+            .body(body.to_owned())
+    };
+
+    //
+    // Act
+    //
+    let filter = hashed_body()
+        .and_then(|_| ok_reply())
+        .recover(handle_rejection);
+    let signed_response = request(COMMON_SIGNED_BODY).reply(&filter).await;
+    let tampered_response = request("{\"amount\":1000}").reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (signed_response.status(), tampered_response.status()),
+        (StatusCode::OK, StatusCode::UNAUTHORIZED)
+    );
+}
+
+async fn signed_request_common(
+    body: &str,
+    query: &str,
+    timestamp_age: u64,
+    previous_sends: usize,
+    authorization_success: bool,
+) {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+
+    let (pk, sk) = sign::gen_keypair();
+    let mut route_keys = BTreeMap::new();
+    route_keys.insert(
+        "debug_data".to_owned(),
+        vec![COMMON_VALID_API_KEY.to_owned()],
+    );
+    let mut signing_keys = BTreeMap::new();
+    signing_keys.insert(COMMON_VALID_API_KEY.to_owned(), hex::encode(pk.as_ref()));
+    let api_keys = to_signed_api_keys(route_keys, signing_keys);
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let timestamp = now.as_secs() - timestamp_age;
+    let content_hash = request_content_hash(COMMON_SIGNED_BODY.as_bytes());
+    let content = request_signing_content("POST", "/debug_data?limit=1", timestamp, &content_hash);
+    let signature = sign::sign_detached(content.as_bytes(), &sk);
+
+    let request = || {
+        warp::test::request()
+            .method("POST")
+            .header("x-request-id", COMMON_REQ_ID)
+            .header("x-api-key", COMMON_VALID_API_KEY)
+            .header("x-timestamp", timestamp.to_string())
+            .header("x-content-hash", content_hash.as_str())
+            .header("x-signature", hex::encode(signature.as_ref()))
+            .path(&format!("/debug_data{query}"))
+            .body(body)
+    };
+
+    //
+    // Act
+    //
+    let filter = auth_request(Default::default(), api_keys)
+        .and(json_body::<serde_json::Value>())
+        .and_then(|_, _| ok_reply())
+        .recover(handle_rejection);
+    for _ in 0..previous_sends {
+        request().reply(&filter).await;
+    }
+    let actual_response = request().reply(&filter).await;
+
+    let (expected_response, expected_response_body) = if authorization_success {
+        (success_json(), "0")
+    } else {
+        (fail_json(StatusCode::UNAUTHORIZED), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Unauthorized\",\"route\":\"debug_data\",\"content\":\"null\"}")
+    };
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (actual_response.status(), actual_response.headers().clone()),
+        expected_response
+    );
+    assert_eq!(actual_response.body(), expected_response_body);
+}
+
+/// Test GET wallet info
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_wallet_info() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_wallet_db("").await;
+    let mut fund_store = db.get_fund_store();
+    let out_point = OutPoint::new("tx_hash".to_string(), 0);
     let out_point_s = OutPoint::new("tx_hash_spent".to_string(), 0);
     let asset = Asset::token_u64(11);
     fund_store.store_tx(out_point.clone(), asset.clone());
@@ -920,7 +1918,87 @@ async fn test_get_shared_config() {
     // Assert
     //
     assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Successfully fetched shared config\",\"route\":\"get_shared_config\",\"content\":{\"compute_mining_event_timeout\":0,\"compute_partition_full_size\":0,\"compute_miner_whitelist\":{\"active\":false,\"miner_api_keys\":null,\"miner_addresses\":null}}}");
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Successfully fetched shared config\",\"route\":\"get_shared_config\",\"content\":{\"compute_mining_event_timeout\":0,\"compute_partition_full_size\":0,\"compute_miner_whitelist\":{\"active\":false,\"miner_api_keys\":null,\"miner_addresses\":null},\"compute_max_block_size\":null,\"compute_utxo_set_size_cap\":null,\"compute_dust_threshold\":0,\"compute_min_block_reward\":0,\"compute_reward_halving\":null,\"compute_winning_miner_fairness\":null}}");
+}
+
+/// Test a threaded call the compute node does not service in time is reported as busy
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_shared_config_node_busy() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut compute = ComputeTest::new(Default::default());
+    compute.threaded_calls = ThreadedCallChannel::new(1, Duration::from_millis(500));
+    let request = warp::test::request()
+        .method("GET")
+        .path("/get_shared_config")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID);
+
+    //
+    // Act
+    //
+    let filter = routes::get_shared_config(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+    drop(compute);
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Node busy: Cannot access Compute Node\",\"route\":\"get_shared_config\",\"content\":\"null\"}");
+}
+
+/// Test a threaded call to a compute node that has gone away is reported as an internal error
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_shared_config_threaded_call_failed() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let compute = ComputeTest::new(Default::default());
+    let tx = compute.threaded_calls.tx.clone();
+    drop(compute);
+    let request = warp::test::request()
+        .method("GET")
+        .path("/get_shared_config")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID);
+
+    //
+    // Act
+    //
+    let filter = routes::get_shared_config(
+        &mut dp(),
+        tx,
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::INTERNAL_SERVER_ERROR)
+    );
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Threaded call failed: Cannot access Compute Node: ThreadedCall fail to send call\",\"route\":\"get_shared_config\",\"content\":\"null\"}");
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -1186,57 +2264,213 @@ async fn test_get_utxo_set_addresses() {
     );
 }
 
-/*------- POST TESTS--------*/
-
-/// Test POST for get blockchain block by key
+/// Test POST addresses on the UTXO set filtered by prefix and limit
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_blockchain_entry_by_key_block() {
-    let expected_meta = success_json();
-    let expected_body = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"blockchain_entry\",\"content\":{\"Block\":{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}}}";
+async fn test_post_utxo_set_addresses_filter() {
+    let _ = tracing_log_try_init();
 
-    test_post_blockchain_entry_by_key(
-        "b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc",
-        expected_meta.clone(),
-        expected_body,
-    )
-    .await;
-    test_post_blockchain_entry_by_key(
-        "nIndexedBlockHashKey_0000000000000000",
-        expected_meta,
-        expected_body,
-    )
-    .await;
-}
+    //
+    // Arrange
+    //
+    let tx_vals = vec![
+        generate_transaction("tx_hash_1", "b2_address"),
+        generate_transaction("tx_hash_2", "a1_address"),
+        generate_transaction("tx_hash_3", "b1_address"),
+        generate_transaction("tx_hash_4", "b3_address"),
+    ];
 
-/// Test POST for get blockchain tx by key
-#[tokio::test(flavor = "current_thread")]
-async fn test_post_blockchain_entry_by_key_tx() {
-    let expected_meta = success_json();
-    let expected_body =
-    "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"blockchain_entry\",\"content\":{\"Transaction\":{\"inputs\":[],\"outputs\":[],\"version\":2,\"druid_info\":null}}}";
+    let compute = ComputeTest::new(tx_vals);
+    let addresses_filter = UtxoAddressesFilter {
+        prefix: Some("b".to_owned()),
+        limit: Some(2),
+    };
+    let request = warp::test::request()
+        .method("POST")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .path("/utxo_addresses")
+        .json(&addresses_filter);
 
-    test_post_blockchain_entry_by_key(
-        "g98d0ab9304ca82f098a86ad6251803b",
-        expected_meta.clone(),
-        expected_body,
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::utxo_addresses(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
     )
-    .await;
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"UTXO addresses successfully retrieved\",\"route\":\"utxo_addresses\",\"content\":[\"b1_address\",\"b2_address\"]}"
+    );
 }
 
-/// Test POST for get blockchain with wrong key
+/// Test GET UTXO set tracking consistency
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_blockchain_entry_by_key_failure() {
-    let expected_meta = fail_json(StatusCode::NO_CONTENT);
-    let expected_body = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"No data found for key\",\"route\":\"blockchain_entry\",\"content\":\"null\"}";
+async fn test_get_utxo_consistency() {
+    let _ = tracing_log_try_init();
 
-    test_post_blockchain_entry_by_key(
-        "b6d369ad3595c1348772ad89e7ce314032687579f1bbe288b1a4d065a00000000",
-        expected_meta.clone(),
-        expected_body,
+    //
+    // Arrange
+    //
+    let tx_vals = vec![
+        generate_transaction("tx_hash_1", "public_address_1"),
+        generate_transaction("tx_hash_2", "public_address_2"),
+    ];
+
+    let compute = ComputeTest::new(tx_vals);
+    let request = warp::test::request()
+        .method("GET")
+        .header("x-request-id", COMMON_REQ_ID)
+        .path("/utxo_consistency");
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::utxo_consistency(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
     )
-    .await;
-    test_post_blockchain_entry_by_key(
-        "1842d4e51e99e14671077e4cac648339c3ca57e7219257fed707afd0f0000000",
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"UTXO set consistency successfully checked\",\"route\":\"utxo_consistency\",\"content\":[]}"
+    );
+}
+
+/// Test GET payouts to the winning miners
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_miner_payouts() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut compute = ComputeTest::new(vec![]);
+    compute.miner_payouts = vec![
+        MinerPayout {
+            block_num: 1,
+            miner_address: "127.0.0.1:12340".parse().unwrap(),
+            reward: TokenAmount(7510185),
+        },
+        MinerPayout {
+            block_num: 2,
+            miner_address: "127.0.0.1:12341".parse().unwrap(),
+            reward: TokenAmount(7510185),
+        },
+    ];
+    let request = warp::test::request()
+        .method("GET")
+        .header("x-request-id", COMMON_REQ_ID)
+        .path("/miner_payouts");
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::miner_payouts(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Miner payouts successfully retrieved\",\"route\":\"miner_payouts\",\"content\":[{\"block_num\":1,\"miner_address\":\"127.0.0.1:12340\",\"reward\":7510185},{\"block_num\":2,\"miner_address\":\"127.0.0.1:12341\",\"reward\":7510185}]}"
+    );
+}
+
+/*------- POST TESTS--------*/
+
+/// Test POST for get blockchain block by key
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_blockchain_entry_by_key_block() {
+    let expected_meta = success_json();
+    let expected_body = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"blockchain_entry\",\"content\":{\"Block\":{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}}}";
+
+    test_post_blockchain_entry_by_key(
+        "b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc",
+        expected_meta.clone(),
+        expected_body,
+    )
+    .await;
+    test_post_blockchain_entry_by_key(
+        "nIndexedBlockHashKey_0000000000000000",
+        expected_meta,
+        expected_body,
+    )
+    .await;
+}
+
+/// Test POST for get blockchain tx by key
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_blockchain_entry_by_key_tx() {
+    let expected_meta = success_json();
+    let expected_body =
+    "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"blockchain_entry\",\"content\":{\"Transaction\":{\"inputs\":[],\"outputs\":[],\"version\":2,\"druid_info\":null}}}";
+
+    test_post_blockchain_entry_by_key(
+        "g98d0ab9304ca82f098a86ad6251803b",
+        expected_meta.clone(),
+        expected_body,
+    )
+    .await;
+}
+
+/// Test POST for get blockchain with wrong key
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_blockchain_entry_by_key_failure() {
+    let expected_meta = fail_json(StatusCode::NO_CONTENT);
+    let expected_body = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"No data found for key\",\"route\":\"blockchain_entry\",\"content\":\"null\"}";
+
+    test_post_blockchain_entry_by_key(
+        "b6d369ad3595c1348772ad89e7ce314032687579f1bbe288b1a4d065a00000000",
+        expected_meta.clone(),
+        expected_body,
+    )
+    .await;
+    test_post_blockchain_entry_by_key(
+        "1842d4e51e99e14671077e4cac648339c3ca57e7219257fed707afd0f0000000",
         expected_meta.clone(),
         expected_body,
     )
@@ -1282,393 +2516,1137 @@ async fn test_post_blockchain_entry_by_key(
     assert_eq!(res.body(), expected_body);
 }
 
-/// Test POST for get block info by nums
+/// Test POST for get blockchain items by keys, with existing and missing keys
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_block_info_by_nums() {
+async fn test_post_blockchain_entries_by_keys() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    // New reply cache per request: they share the same request id
+    let filter = || {
+        let cache = create_new_cache(CACHE_LIVE_TIME);
+        routes::blockchain_entries_by_keys(
+            &mut dp(),
+            db.clone(),
+            Default::default(),
+            ks.clone(),
+            cache,
+        )
+        .recover(handle_rejection)
+    };
+
+    let request = |keys: Vec<String>| {
+        warp::test::request()
+            .method("POST")
+            .path("/blockchain_entries_by_keys")
+            .header("Content-Type", "application/json")
+            .header("x-request-id", COMMON_REQ_ID)
+            .json(&keys)
+    };
+    let keys = vec![
+        "g98d0ab9304ca82f098a86ad6251803b".to_owned(),
+        "Test".to_owned(),
+        "b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc".to_owned(),
+    ];
+    let too_many_keys = vec!["Test".to_owned(); BLOCKCHAIN_ENTRIES_MAX_KEYS + 1];
+
+    //
+    // Act
+    //
+    let res = request(keys).reply(&filter()).await;
+    let res_too_many = request(too_many_keys).reply(&filter()).await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"blockchain_entries_by_keys\",\"content\":{\"Test\":null,\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\":{\"Block\":{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}},\"g98d0ab9304ca82f098a86ad6251803b\":{\"Transaction\":{\"inputs\":[],\"outputs\":[],\"version\":2,\"druid_info\":null}}}}");
+    assert_eq!(
+        (res_too_many.status(), res_too_many.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(res_too_many.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Batch too large: at most 100 items\",\"route\":\"blockchain_entries_by_keys\",\"content\":\"null\"}");
+}
+
+/// Test POST for get transaction and its block number by hash
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_transaction_by_hash() {
     let _ = tracing_log_try_init();
 
     let db = get_db_with_block().await;
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
-    let filter = routes::block_by_num(&mut dp(), db, Default::default(), ks, cache)
+    let filter = routes::transaction_by_hash(&mut dp(), db, Default::default(), ks, cache)
         .recover(handle_rejection);
 
-    let res = warp::test::request()
-        .method("POST")
-        .path("/block_by_num")
-        .header("Content-Type", "application/json")
-        .header("x-request-id", COMMON_REQ_ID)
-        .json(&vec![0_u64, 10, 0])
+    let request = |tx_hash: &str| {
+        warp::test::request()
+            .method("POST")
+            .path("/transaction_by_hash")
+            .header("Content-Type", "application/json")
+            .header("x-request-id", COMMON_REQ_ID)
+            .json(&tx_hash)
+    };
+
+    let res = request("g98d0ab9304ca82f098a86ad6251803b")
+        .reply(&filter)
+        .await;
+    let res_block = request("b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc")
+        .reply(&filter)
+        .await;
+    let res_missing = request("g00000000000000000000000000000000")
         .reply(&filter)
         .await;
 
-    // Header to match
-    let mut headers = HeaderMap::new();
-    headers.insert("content-type", HeaderValue::from_static("application/json"));
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Transaction successfully retrieved\",\"route\":\"transaction_by_hash\",\"content\":{\"transaction\":{\"inputs\":[],\"outputs\":[],\"version\":2,\"druid_info\":null},\"block_num\":0,\"tx_num\":1}}");
 
-    assert_eq!(res.status(), 200);
-    assert_eq!(res.headers(), &headers);
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"block_by_num\",\"content\":[[\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\",{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}],[\"\",\"\"],[\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\",{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}]]}");
+    let expected_body = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"No data found for key\",\"route\":\"transaction_by_hash\",\"content\":\"null\"}";
+    assert_eq!(
+        (res_block.status(), res_block.headers().clone()),
+        fail_json(StatusCode::NO_CONTENT)
+    );
+    assert_eq!(res_block.body(), expected_body);
+    assert_eq!(
+        (res_missing.status(), res_missing.headers().clone()),
+        fail_json(StatusCode::NO_CONTENT)
+    );
+    assert_eq!(res_missing.body(), expected_body);
 }
 
-/// Test POST for get transactions info by tx_hash
+/// Test POST for get number of blocks confirming a transaction
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_transactions_by_key() {
+async fn test_post_tx_confirmations() {
     let _ = tracing_log_try_init();
 
     let db = get_db_with_block().await;
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
-    let filter = routes::transactions_by_key(&mut dp(), db, Default::default(), ks, cache)
+    let filter = routes::tx_confirmations(&mut dp(), db, Default::default(), ks, cache)
+        .recover(handle_rejection);
+
+    let request = |tx_hash: &str| {
+        warp::test::request()
+            .method("POST")
+            .path("/tx_confirmations")
+            .header("Content-Type", "application/json")
+            .header("x-request-id", COMMON_REQ_ID)
+            .json(&tx_hash)
+    };
+
+    // Transaction in block 0 with block 0 as the last block
+    let res = request("g98d0ab9304ca82f098a86ad6251803b")
+        .reply(&filter)
+        .await;
+    let res_missing = request("g00000000000000000000000000000000")
+        .reply(&filter)
+        .await;
+
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Transaction confirmations successfully retrieved\",\"route\":\"tx_confirmations\",\"content\":{\"found\":true,\"confirmations\":1}}");
+
+    assert_eq!(
+        (res_missing.status(), res_missing.headers().clone()),
+        success_json()
+    );
+    assert_eq!(res_missing.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Transaction confirmations successfully retrieved\",\"route\":\"tx_confirmations\",\"content\":{\"found\":false,\"confirmations\":0}}");
+}
+
+/// Test GET verify chain with a consistent chain
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_verify_chain() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_chain(3, None).await;
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::verify_chain(&mut dp(), db, Default::default(), ks, cache)
         .recover(handle_rejection);
 
+    //
+    // Act
+    //
     let res = warp::test::request()
-        .method("POST")
-        .path("/transactions_by_key")
-        .header("Content-Type", "application/json")
+        .method("GET")
+        .path("/verify_chain")
+        .header("x-request-id", COMMON_REQ_ID)
+        .reply(&filter)
+        .await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Stored chain successfully verified\",\"route\":\"verify_chain\",\"content\":{\"blocks_checked\":3,\"inconsistency\":null}}");
+}
+
+/// Test GET verify chain detects a tampered block
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_verify_chain_tampered_block() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_chain(3, Some(1)).await;
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::verify_chain(&mut dp(), db, Default::default(), ks, cache)
+        .recover(handle_rejection);
+
+    //
+    // Act
+    //
+    let res = warp::test::request()
+        .method("GET")
+        .path("/verify_chain")
         .header("x-request-id", COMMON_REQ_ID)
-        .json(&vec!["g98d0ab9304ca82f098a86ad6251803b".to_string()])
         .reply(&filter)
         .await;
 
-    // Header to match
-    let mut headers = HeaderMap::new();
-    headers.insert("content-type", HeaderValue::from_static("application/json"));
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Stored chain inconsistency found\",\"route\":\"verify_chain\",\"content\":{\"blocks_checked\":1,\"inconsistency\":{\"b_num\":1,\"reason\":\"Merkle root mismatch\"}}}");
+}
+
+/// Test POST utxo at block reconstructs the UTXO set at a historical height
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_utxo_at_block() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let (db, block_tx_hashes) = get_db_with_utxo_chain(3).await;
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::utxo_at_block(
+        &mut dp(),
+        db,
+        Default::default(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+
+    //
+    // Act
+    //
+    let res = warp::test::request()
+        .method("POST")
+        .path("/utxo_at_block")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&1)
+        .reply(&filter)
+        .await;
+
+    //
+    // Assert
+    //
+    let (block_0_mining_tx, block_1_tx, block_1_mining_tx) = (
+        &block_tx_hashes[0].1,
+        &block_tx_hashes[1].0,
+        &block_tx_hashes[1].1,
+    );
+    let expected_utxo_set: BTreeSet<OutPoint> = vec![
+        OutPoint::new(block_0_mining_tx.clone(), 0),
+        OutPoint::new(block_1_tx.clone(), 0),
+        OutPoint::new(block_1_mining_tx.clone(), 0),
+    ]
+    .into_iter()
+    .collect();
+    let expected_body = format!("{{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"UTXO set successfully reconstructed\",\"route\":\"utxo_at_block\",\"content\":{}}}", serde_json::to_string(&expected_utxo_set).unwrap());
+
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), &expected_body);
+}
+
+/// Test POST for get block info by nums
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_block_info_by_nums() {
+    let _ = tracing_log_try_init();
+
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::block_by_num(&mut dp(), db, Default::default(), ks, cache)
+        .recover(handle_rejection);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/block_by_num")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&vec![0_u64, 10, 0])
+        .reply(&filter)
+        .await;
+
+    // Header to match
+    let mut headers = HeaderMap::new();
+    headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers(), &headers);
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"block_by_num\",\"content\":[[\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\",{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}],[\"\",\"\"],[\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\",{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]},\"transactions\":[\"g98d0ab9304ca82f098a86ad6251803b\"]}}]]}");
+}
+
+/// Test POST for get block info by nums projected to headers only
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_block_info_by_nums_headers_only() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::block_by_num(&mut dp(), db, Default::default(), ks, cache)
+        .recover(handle_rejection);
+
+    //
+    // Act
+    //
+    let res = warp::test::request()
+        .method("POST")
+        .path("/block_by_num")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&serde_json::json!({ "nums": [0_u64, 10], "fields": ["header"] }))
+        .reply(&filter)
+        .await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert!(!from_utf8(res.body()).contains("transactions"));
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"block_by_num\",\"content\":[[\"b0004e829238707b7a600a95d3089e320448f706c2c7f6b0427201cc384c7fbfc\",{\"block\":{\"header\":{\"version\":2,\"bits\":0,\"nonce_and_mining_tx_hash\":[[120,12,5,128,106,59,112,177,92,150,115,57,97,113,103,79],\"test\"],\"b_num\":0,\"seed_value\":[],\"previous_hash\":null,\"txs_merkle_root_and_hash\":[\"42fbcc73bc0eeb41a991a32a6f6e145d1d45b2738657db5b4781d1fa707693cf\",\"35260a02627ae9d586dbb9f11de79afd46d1096f41ffb6b9ee88cca6b78bf374\"]}}}],[\"\",\"\"]]}");
+}
+
+/// Test POST for get transactions info by tx_hash
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_transactions_by_key() {
+    let _ = tracing_log_try_init();
+
+    let db = get_db_with_block().await;
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::transactions_by_key(&mut dp(), db, Default::default(), ks, cache)
+        .recover(handle_rejection);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/transactions_by_key")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&vec!["g98d0ab9304ca82f098a86ad6251803b".to_string()])
+        .reply(&filter)
+        .await;
+
+    // Header to match
+    let mut headers = HeaderMap::new();
+    headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.headers(), &headers);
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"transactions_by_key\",\"content\":[[\"g98d0ab9304ca82f098a86ad6251803b\",{\"inputs\":[],\"outputs\":[],\"version\":2,\"druid_info\":null}]]}");
+}
+
+/// Test POST make payment
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_make_payment() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let (mut self_node, self_socket) = new_self_node(NodeType::User).await;
+
+    let encapsulated_data = EncapsulatedPayment {
+        address: COMMON_PUB_ADDR.to_string(),
+        amount: TokenAmount(25),
+        passphrase: String::new(),
+        locktime: None,
+    };
+
+    let db = get_wallet_db(&encapsulated_data.passphrase).await;
+    let request = warp::test::request()
+        .method("POST")
+        .path("/make_payment")
+        .remote_addr(self_socket)
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&encapsulated_data);
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::make_payment(
+        &mut dp(),
+        db,
+        self_node.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Payment processing\",\"route\":\"make_payment\",\"content\":{\"13bd3351b78beb2d0dadf2058dcc926c\":{\"asset\":{\"Token\":25},\"extra_info\":null}}}");
+
+    // Frame expected
+    let (address, amount, locktime) = (
+        encapsulated_data.address,
+        encapsulated_data.amount,
+        encapsulated_data.locktime,
+    );
+    let expected_frame = user_api_request_as_frame(UserApiRequest::MakePayment {
+        address,
+        amount,
+        locktime,
+    });
+    let actual_frame = next_event_frame(&mut self_node).await;
+    assert_eq!(expected_frame, actual_frame);
+}
+
+/// Test POST make payment with a malformed address is rejected
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_make_payment_invalid_address() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let (self_node, self_socket) = new_self_node(NodeType::User).await;
+
+    let encapsulated_data = EncapsulatedPayment {
+        address: "not_an_address".to_string(),
+        amount: TokenAmount(25),
+        passphrase: String::new(),
+        locktime: None,
+    };
+
+    let db = get_wallet_db(&encapsulated_data.passphrase).await;
+    let request = warp::test::request()
+        .method("POST")
+        .path("/make_payment")
+        .remote_addr(self_socket)
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&encapsulated_data);
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::make_payment(&mut dp(), db, self_node, Default::default(), ks, cache)
+        .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Invalid address: Address is not hexadecimal\",\"route\":\"make_payment\",\"content\":\"null\"}");
+}
+
+/// Test POST make ip payment with correct address
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_make_ip_payment() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let (mut self_node, self_socket) = new_self_node(NodeType::User).await;
+
+    let encapsulated_data = EncapsulatedPayment {
+        address: "127.0.0.1:12345".to_owned(),
+        amount: TokenAmount(25),
+        passphrase: String::new(),
+        locktime: None,
+    };
+    let db = get_wallet_db(&encapsulated_data.passphrase).await;
+    let request = warp::test::request()
+        .method("POST")
+        .path("/make_ip_payment")
+        .remote_addr(self_socket)
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&encapsulated_data);
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::make_ip_payment(
+        &mut dp(),
+        db,
+        self_node.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"IP payment processing\",\"route\":\"make_ip_payment\",\"content\":{\"127.0.0.1:12345\":{\"asset\":{\"Token\":25},\"extra_info\":null}}}");
+
+    // Frame expected
+    let (payment_peer, amount, locktime) = (
+        encapsulated_data.address.parse::<SocketAddr>().unwrap(),
+        encapsulated_data.amount,
+        encapsulated_data.locktime,
+    );
+    let expected_frame = user_api_request_as_frame(UserApiRequest::MakeIpPayment {
+        payment_peer,
+        amount,
+        locktime,
+    });
+    let actual_frame = next_event_frame(&mut self_node).await;
+    assert_eq!(expected_frame, actual_frame);
+}
+
+/// Test POST construct address from public key
+#[tokio::test(flavor = "current_thread")]
+async fn test_address_construction() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut address1 = BTreeMap::new();
+    address1.insert(
+        "pub_key",
+        vec![
+            109, 133, 37, 100, 46, 243, 13, 156, 189, 123, 142, 12, 24, 169, 49, 186, 187, 0, 63,
+            27, 129, 207, 183, 13, 156, 208, 171, 164, 179, 118, 131, 183,
+        ],
+    );
+
+    let mut address2 = BTreeMap::new();
+    address2.insert(
+        "pub_key_hex",
+        "6d8525642ef30d9cbd7b8e0c18a931babb003f1b81cfb70d9cd0aba4b37683b7",
+    );
+
+    let address3 = AddressConstructData {
+        pub_key_hex: Some(
+            "6d8525642ef30d9cbd7b8e0c18a931babb003f1b81cfb70d9cd0aba4b37683b7".to_owned(),
+        ),
+        version: Some(0),
+        ..Default::default()
+    };
+
+    let request1 = || {
+        warp::test::request()
+            .method("POST")
+            .path("/address_construction")
+            .header("x-request-id", COMMON_REQ_ID)
+            .header("Content-Type", "application/json")
+    };
+
+    let com_req_id_plus_1 = "2ae7bc9cba924e3cb73c0249893078d8";
+    let request2 = || {
+        warp::test::request()
+            .method("POST")
+            .path("/address_construction")
+            .header("x-request-id", com_req_id_plus_1)
+            .header("Content-Type", "application/json")
+    };
+
+    let com_req_id_plus_2 = "2ae7bc9cba924e3cb73c0249893078d9";
+    let request3 = || {
+        warp::test::request()
+            .method("POST")
+            .path("/address_construction")
+            .header("x-request-id", com_req_id_plus_2)
+            .header("Content-Type", "application/json")
+    };
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::address_construction(&mut dp(), Default::default(), ks, cache)
+        .recover(handle_rejection);
+    let res1 = request1().json(&address1).reply(&filter).await;
+    let res2 = request2().json(&address2).reply(&filter).await;
+    let res3 = request3().json(&address3).reply(&filter).await;
+
+    //
+    // Assert
+    //
+    let expected1 = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Address successfully constructed\",\"route\":\"address_construction\",\"content\":\"ca0abdcd2826a77218af0914601ee34c7ff44127aab9d0671267b25a7d36946a\"}";
+    let expected2 = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d8\",\"status\":\"Success\",\"reason\":\"Address successfully constructed\",\"route\":\"address_construction\",\"content\":\"ca0abdcd2826a77218af0914601ee34c7ff44127aab9d0671267b25a7d36946a\"}";
+
+    assert_eq!((res1.status(), res1.headers().clone()), success_json());
+    assert_eq!(res1.body(), expected1);
+
+    assert_eq!((res2.status(), res2.headers().clone()), success_json());
+    assert_eq!(res2.body(), expected2);
+
+    assert_eq!((res3.status(), res3.headers().clone()), success_json());
+    assert_eq!(res3.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d9\",\"status\":\"Success\",\"reason\":\"Address successfully constructed\",\"route\":\"address_construction\",\"content\":\"56d5b6da467e6c588966967ef5405dd2\"}");
+}
+
+/// Test POST make ip payment with correct address
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_request_donation() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arange
+    //
+    let (mut self_node, self_socket) = new_self_node(NodeType::User).await;
+
+    let address = "127.0.0.1:12345".to_owned();
+    let paying_peer = address.parse::<SocketAddr>().unwrap();
+
+    let request = warp::test::request()
+        .method("POST")
+        .path("/request_donation")
+        .remote_addr(self_socket)
+        .header("x-request-id", COMMON_REQ_ID)
+        .header("Content-Type", "application/json")
+        .json(&address);
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter =
+        routes::request_donation(&mut dp(), self_node.clone(), Default::default(), ks, cache)
+            .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Donation request sent\",\"route\":\"request_donation\",\"content\":\"null\"}");
+
+    // Frame expected
+    let expected_frame = user_api_request_as_frame(UserApiRequest::RequestDonation { paying_peer });
+    let actual_frame = next_event_frame(&mut self_node).await;
+    assert_eq!(expected_frame, actual_frame);
+}
+
+/// Test POST import key-pairs
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_import_keypairs_success() {
+    let _ = tracing_log_try_init();
+    let (self_node, _self_socket) = new_self_node(NodeType::User).await;
+    let db = get_wallet_db("").await;
+    let mut addresses: BTreeMap<String, AddressStoreHex> = BTreeMap::new();
+    addresses.insert(
+        COMMON_PUB_ADDR.to_string(),
+        AddressStoreHex {
+            public_key: COMMON_PUB_KEY.to_string(),
+            secret_key: COMMON_SEC_KEY.to_string(),
+            address_version: None,
+        },
+    );
+    let imported_addresses = Addresses { addresses };
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::import_keypairs(
+        &mut dp(),
+        db.clone(),
+        self_node,
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let wallet_addresses_before = db.get_known_addresses();
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/import_keypairs")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&imported_addresses)
+        .reply(&filter)
+        .await;
+
+    error!("res: {:?}", res);
+
+    let wallet_addresses_after = db.get_known_addresses();
+
+    // Header to match
+    let mut headers = HeaderMap::new();
+    headers.insert("content-type", HeaderValue::from_static("application/json"));
+    assert_eq!(wallet_addresses_before, Vec::<String>::new());
+    assert_eq!(wallet_addresses_after, vec![COMMON_PUB_ADDR.to_string()]);
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Key-pairs successfully imported\",\"route\":\"import_keypairs\",\"content\":[\"13bd3351b78beb2d0dadf2058dcc926c\"]}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_fetch_balance() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let tx_vals = vec![get_transaction()];
+    let compute = ComputeTest::new(tx_vals);
+    let addresses = PublicKeyAddresses {
+        address_list: vec![COMMON_PUB_ADDR.to_string()],
+    };
+
+    let request = warp::test::request()
+        .method("POST")
+        .path("/fetch_balance")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&addresses);
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::fetch_balance(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Balance successfully fetched\",\"route\":\"fetch_balance\",\"content\":{\"total\":{\"tokens\":25200,\"receipts\":{}},\"address_list\":{\"13bd3351b78beb2d0dadf2058dcc926c\":[{\"out_point\":{\"t_hash\":\"tx_hash\",\"n\":0},\"value\":{\"Token\":25200}}]}}}"
+    );
+}
+
+/// Test POST fetch pages of the outpoints of an address
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_fetch_balance_page() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let tx_vals = vec![
+        generate_transaction("tx_hash_1", COMMON_PUB_ADDR),
+        generate_transaction("tx_hash_2", COMMON_PUB_ADDR),
+        generate_transaction("tx_hash_3", COMMON_PUB_ADDR),
+    ];
+    let compute = ComputeTest::new(tx_vals);
+    let page_request = AddressUtxoPageRequest {
+        address: COMMON_PUB_ADDR.to_string(),
+        limit: Some(1),
+        cursor: Some(OutPoint::new("tx_hash_1".to_owned(), 0)),
+    };
+
+    let request = warp::test::request()
+        .method("POST")
+        .path("/fetch_balance_page")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&page_request);
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::fetch_balance_page(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Balance page successfully fetched\",\"route\":\"fetch_balance_page\",\"content\":{\"address\":\"13bd3351b78beb2d0dadf2058dcc926c\",\"out_points\":[{\"out_point\":{\"t_hash\":\"tx_hash_2\",\"n\":0},\"value\":{\"Token\":25200}}],\"next_cursor\":{\"t_hash\":\"tx_hash_2\",\"n\":0}}}"
+    );
+}
+
+/// Test POST fetch balance page rejects an empty page limit
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_fetch_balance_page_zero_limit() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let compute = ComputeTest::new(Vec::new());
+    let page_request = AddressUtxoPageRequest {
+        address: COMMON_PUB_ADDR.to_string(),
+        limit: Some(0),
+        cursor: None,
+    };
+
+    let request = warp::test::request()
+        .method("POST")
+        .path("/fetch_balance_page")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&page_request);
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+
+    let filter = routes::fetch_balance_page(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"Generic error: Page limit must be at least 1\",\"route\":\"fetch_balance_page\",\"content\":\"null\"}"
+    );
+}
+
+/// Test POST for streaming the committed UTXO set
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_utxo_snapshot() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let tx_vals = vec![
+        get_transaction(),
+        generate_transaction("tx_hash_2", "other_address"),
+    ];
+    let compute = ComputeTest::new(tx_vals);
+    let all_addresses = PublicKeyAddresses {
+        address_list: Vec::new(),
+    };
+    let addresses = PublicKeyAddresses {
+        address_list: vec![COMMON_PUB_ADDR.to_string()],
+    };
+
+    let request = |addresses: &PublicKeyAddresses| {
+        warp::test::request()
+            .method("POST")
+            .path("/utxo_snapshot")
+            .header("Content-Type", "application/json")
+            .header("x-request-id", COMMON_REQ_ID)
+            .json(addresses)
+    };
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let filter = routes::utxo_snapshot(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+    )
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res_all = request(&all_addresses).reply(&filter).await;
+    let compute = handle.await.unwrap();
+    let handle = compute.spawn();
+    let res = request(&addresses).reply(&filter).await;
+    let _compute = handle.await.unwrap();
+
+    //
+    // Assert
+    //
+    let entry_1 = "{\"out_point\":{\"t_hash\":\"tx_hash\",\"n\":0},\"tx_out\":{\"value\":{\"Token\":25200},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":\"13bd3351b78beb2d0dadf2058dcc926c\"}}\n";
+    let entry_2 = "{\"out_point\":{\"t_hash\":\"tx_hash_2\",\"n\":0},\"tx_out\":{\"value\":{\"Token\":25200},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":\"other_address\"}}\n";
 
-    assert_eq!(res.status(), 200);
-    assert_eq!(res.headers(), &headers);
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"transactions_by_key\",\"content\":[[\"g98d0ab9304ca82f098a86ad6251803b\",{\"inputs\":[],\"outputs\":[],\"version\":2,\"druid_info\":null}]]}");
+    assert_eq!(res_all.status(), StatusCode::OK);
+    assert_eq!(
+        res_all.headers().get("content-type").unwrap(),
+        "application/x-ndjson"
+    );
+    assert_eq!(res_all.body(), &format!("{entry_1}{entry_2}"));
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body(), entry_1);
 }
 
-/// Test POST make payment
+/// Test POST for balance with a pending transaction spending a UTXO
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_make_payment() {
+async fn test_post_fetch_balance_with_pending() {
     let _ = tracing_log_try_init();
 
     //
     // Arrange
     //
-    let (mut self_node, self_socket) = new_self_node(NodeType::User).await;
-
-    let encapsulated_data = EncapsulatedPayment {
-        address: COMMON_PUB_ADDR.to_string(),
-        amount: TokenAmount(25),
-        passphrase: String::new(),
-        locktime: None,
+    let tx_vals = vec![
+        get_transaction(),
+        generate_transaction("tx_hash_2", COMMON_PUB_ADDR),
+    ];
+    let mut compute = ComputeTest::new(tx_vals);
+    let (pending_hash, pending_tx) = generate_transaction("tx_hash_2", "receiver_address");
+    compute.tx_pool.insert(pending_hash, pending_tx);
+    let addresses = PublicKeyAddresses {
+        address_list: vec![COMMON_PUB_ADDR.to_string()],
     };
 
-    let db = get_wallet_db(&encapsulated_data.passphrase).await;
     let request = warp::test::request()
         .method("POST")
-        .path("/make_payment")
-        .remote_addr(self_socket)
+        .path("/fetch_balance_with_pending")
         .header("Content-Type", "application/json")
         .header("x-request-id", COMMON_REQ_ID)
-        .json(&encapsulated_data);
+        .json(&addresses);
 
     //
     // Act
     //
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
-    let filter = routes::make_payment(
+
+    let filter = routes::fetch_balance_with_pending(
         &mut dp(),
-        db,
-        self_node.clone(),
+        compute.threaded_calls.tx.clone(),
         Default::default(),
         ks,
         cache,
     )
     .recover(handle_rejection);
+    let handle = compute.spawn();
     let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
 
     //
     // Assert
     //
     assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Payment processing\",\"route\":\"make_payment\",\"content\":{\"13bd3351b78beb2d0dadf2058dcc926c\":{\"asset\":{\"Token\":25},\"extra_info\":null}}}");
-
-    // Frame expected
-    let (address, amount, locktime) = (
-        encapsulated_data.address,
-        encapsulated_data.amount,
-        encapsulated_data.locktime,
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Balance successfully fetched\",\"route\":\"fetch_balance_with_pending\",\"content\":{\"confirmed\":{\"total\":{\"tokens\":50400,\"receipts\":{}},\"address_list\":{\"13bd3351b78beb2d0dadf2058dcc926c\":[{\"out_point\":{\"t_hash\":\"tx_hash\",\"n\":0},\"value\":{\"Token\":25200}},{\"out_point\":{\"t_hash\":\"tx_hash_2\",\"n\":0},\"value\":{\"Token\":25200}}]}},\"available\":{\"total\":{\"tokens\":25200,\"receipts\":{}},\"address_list\":{\"13bd3351b78beb2d0dadf2058dcc926c\":[{\"out_point\":{\"t_hash\":\"tx_hash\",\"n\":0},\"value\":{\"Token\":25200}}]}}}}"
     );
-    let expected_frame = user_api_request_as_frame(UserApiRequest::MakePayment {
-        address,
-        amount,
-        locktime,
-    });
-    let actual_frame = next_event_frame(&mut self_node).await;
-    assert_eq!(expected_frame, actual_frame);
 }
 
-/// Test POST make ip payment with correct address
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_make_ip_payment() {
+async fn test_post_fetch_pending() {
     let _ = tracing_log_try_init();
 
     //
     // Arrange
     //
-    let (mut self_node, self_socket) = new_self_node(NodeType::User).await;
-
-    let encapsulated_data = EncapsulatedPayment {
-        address: "127.0.0.1:12345".to_owned(),
-        amount: TokenAmount(25),
-        passphrase: String::new(),
-        locktime: None,
+    let tx_vals = get_rb_transactions();
+    let compute = ComputeTest::new(tx_vals);
+    let druids = FetchPendingData {
+        druid_list: vec!["full_druid".to_owned(), "non_existing_druid".to_owned()],
     };
-    let db = get_wallet_db(&encapsulated_data.passphrase).await;
+
     let request = warp::test::request()
         .method("POST")
-        .path("/make_ip_payment")
-        .remote_addr(self_socket)
+        .path("/fetch_pending")
         .header("Content-Type", "application/json")
         .header("x-request-id", COMMON_REQ_ID)
-        .json(&encapsulated_data);
+        .json(&druids);
     //
     // Act
     //
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
-    let filter = routes::make_ip_payment(
+    let filter = routes::fetch_pending(
         &mut dp(),
-        db,
-        self_node.clone(),
+        compute.threaded_calls.tx.clone(),
         Default::default(),
         ks,
         cache,
     )
     .recover(handle_rejection);
+    let handle = compute.spawn();
     let res = request.reply(&filter).await;
+    let _compute = handle.await.unwrap();
 
     //
     // Assert
     //
     assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"IP payment processing\",\"route\":\"make_ip_payment\",\"content\":{\"127.0.0.1:12345\":{\"asset\":{\"Token\":25},\"extra_info\":null}}}");
-
-    // Frame expected
-    let (payment_peer, amount, locktime) = (
-        encapsulated_data.address.parse::<SocketAddr>().unwrap(),
-        encapsulated_data.amount,
-        encapsulated_data.locktime,
-    );
-    let expected_frame = user_api_request_as_frame(UserApiRequest::MakeIpPayment {
-        payment_peer,
-        amount,
-        locktime,
-    });
-    let actual_frame = next_event_frame(&mut self_node).await;
-    assert_eq!(expected_frame, actual_frame);
+    assert_eq!(
+        res.body(),
+        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Pending transactions successfully fetched\",\"route\":\"fetch_pending\",\"content\":{\"full_druid\":{\"participants\":2,\"txs\":{\"g490b4fc3b3953a8a006ec17ae4a6055\":{\"inputs\":[{\"previous_out\":{\"t_hash\":\"000001\",\"n\":0},\"script_signature\":{\"stack\":[{\"Bytes\":\"754dc248d1c847e8a10c6f8ded6ccad96381551ebb162583aea2a86b9bb78dfa\"},{\"Signature\":[21,103,185,228,19,36,74,158,249,211,229,41,187,113,248,98,27,55,85,97,36,94,216,242,20,156,39,245,55,212,95,22,52,161,77,8,211,241,24,217,126,208,39,154,87,136,126,31,154,177,219,197,151,174,148,122,67,147,4,59,177,191,172,8]},{\"PubKey\":[83,113,131,33,34,168,232,4,250,53,32,236,104,97,195,250,85,74,127,111,182,23,230,240,118,132,82,9,2,7,224,124]},{\"Op\":\"OP_DUP\"},{\"Op\":\"OP_HASH256\"},{\"PubKeyHash\":\"5423e6bd848e0ce5cd794e55235c23138d8833633cd2d7de7f4a10935178457b\"},{\"Op\":\"OP_EQUALVERIFY\"},{\"Op\":\"OP_CHECKSIG\"}]}}],\"outputs\":[{\"value\":{\"Token\":0},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":null},{\"value\":{\"Receipt\":{\"amount\":1,\"drs_tx_hash\":\"drs_tx_hash\",\"metadata\":null}},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":\"sender_address\"}],\"version\":5,\"druid_info\":{\"druid\":\"full_druid\",\"participants\":2,\"expectations\":[{\"from\":\"6efcefb27d1e1149b243ce319c5e5352bb100dc328a59f630ee7a9fd5ebe9da9\",\"to\":\"receiver_address\",\"asset\":{\"Token\":25200}}]}},\"g8118c848762693bda7be3f804601ad0\":{\"inputs\":[{\"previous_out\":{\"t_hash\":\"000000\",\"n\":0},\"script_signature\":{\"stack\":[{\"Bytes\":\"927b3411743452e5e0d73e9e40a4fa3c842b3d00dabde7f9af7e44661ce02c88\"},{\"Signature\":[35,226,158,202,184,227,77,178,40,234,140,161,109,206,131,187,171,159,103,146,89,201,220,227,212,184,216,166,69,26,92,67,221,248,253,165,17,176,190,4,48,76,146,12,179,195,90,227,170,17,196,234,76,57,254,242,83,89,237,117,68,193,105,10]},{\"PubKey\":[83,113,131,33,34,168,232,4,250,53,32,236,104,97,195,250,85,74,127,111,182,23,230,240,118,132,82,9,2,7,224,124]},{\"Op\":\"OP_DUP\"},{\"Op\":\"OP_HASH256\"},{\"PubKeyHash\":\"5423e6bd848e0ce5cd794e55235c23138d8833633cd2d7de7f4a10935178457b\"},{\"Op\":\"OP_EQUALVERIFY\"},{\"Op\":\"OP_CHECKSIG\"}]}}],\"outputs\":[{\"value\":{\"Token\":0},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":null},{\"value\":{\"Token\":25200},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":\"receiver_address\"}],\"version\":5,\"druid_info\":{\"druid\":\"full_druid\",\"participants\":2,\"expectations\":[{\"from\":\"b519b3fd271bb33a7ea949a918cc45b00b32095a04f2a9172797f7441f7298e6\",\"to\":\"sender_address\",\"asset\":{\"Receipt\":{\"amount\":1,\"drs_tx_hash\":\"drs_tx_hash\",\"metadata\":null}}}]}}}}}}");
 }
 
-/// Test POST construct address from public key
+/// Test POST cancel DRUID removes the caller half from an incomplete droplet
 #[tokio::test(flavor = "current_thread")]
-async fn test_address_construction() {
+async fn test_post_cancel_druid() {
     let _ = tracing_log_try_init();
 
     //
     // Arrange
     //
-    let mut address1 = BTreeMap::new();
-    address1.insert(
-        "pub_key",
-        vec![
-            109, 133, 37, 100, 46, 243, 13, 156, 189, 123, 142, 12, 24, 169, 49, 186, 187, 0, 63,
-            27, 129, 207, 183, 13, 156, 208, 171, 164, 179, 118, 131, 183,
-        ],
-    );
-
-    let mut address2 = BTreeMap::new();
-    address2.insert(
-        "pub_key_hex",
-        "6d8525642ef30d9cbd7b8e0c18a931babb003f1b81cfb70d9cd0aba4b37683b7",
-    );
-
-    let address3 = AddressConstructData {
-        pub_key_hex: Some(
-            "6d8525642ef30d9cbd7b8e0c18a931babb003f1b81cfb70d9cd0aba4b37683b7".to_owned(),
-        ),
-        version: Some(0),
-        ..Default::default()
-    };
-
-    let request1 = || {
-        warp::test::request()
-            .method("POST")
-            .path("/address_construction")
-            .header("x-request-id", COMMON_REQ_ID)
-            .header("Content-Type", "application/json")
-    };
+    let tx_vals = get_rb_transactions();
+    let compute = ComputeTest::new(vec![tx_vals[0].clone()]);
+    let cancel_data = cancel_druid_data("full_druid", &tx_vals[0].0, cancel_druid_expiry());
 
-    let com_req_id_plus_1 = "2ae7bc9cba924e3cb73c0249893078d8";
-    let request2 = || {
-        warp::test::request()
-            .method("POST")
-            .path("/address_construction")
-            .header("x-request-id", com_req_id_plus_1)
-            .header("Content-Type", "application/json")
-    };
+    let request = warp::test::request()
+        .method("POST")
+        .path("/cancel_druid")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&cancel_data);
 
-    let com_req_id_plus_2 = "2ae7bc9cba924e3cb73c0249893078d9";
-    let request3 = || {
-        warp::test::request()
-            .method("POST")
-            .path("/address_construction")
-            .header("x-request-id", com_req_id_plus_2)
-            .header("Content-Type", "application/json")
-    };
     //
     // Act
     //
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
-    let filter = routes::address_construction(&mut dp(), Default::default(), ks, cache)
-        .recover(handle_rejection);
-    let res1 = request1().json(&address1).reply(&filter).await;
-    let res2 = request2().json(&address2).reply(&filter).await;
-    let res3 = request3().json(&address3).reply(&filter).await;
+
+    let filter = routes::cancel_druid(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        ks,
+        cache,
+    )
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let compute = handle.await.unwrap();
 
     //
     // Assert
     //
-    let expected1 = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Address successfully constructed\",\"route\":\"address_construction\",\"content\":\"ca0abdcd2826a77218af0914601ee34c7ff44127aab9d0671267b25a7d36946a\"}";
-    let expected2 = "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d8\",\"status\":\"Success\",\"reason\":\"Address successfully constructed\",\"route\":\"address_construction\",\"content\":\"ca0abdcd2826a77218af0914601ee34c7ff44127aab9d0671267b25a7d36946a\"}";
-
-    assert_eq!((res1.status(), res1.headers().clone()), success_json());
-    assert_eq!(res1.body(), expected1);
-
-    assert_eq!((res2.status(), res2.headers().clone()), success_json());
-    assert_eq!(res2.body(), expected2);
-
-    assert_eq!((res3.status(), res3.headers().clone()), success_json());
-    assert_eq!(res3.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d9\",\"status\":\"Success\",\"reason\":\"Address successfully constructed\",\"route\":\"address_construction\",\"content\":\"56d5b6da467e6c588966967ef5405dd2\"}");
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"DRUID participation cancelled\",\"route\":\"cancel_druid\",\"content\":\"null\"}");
+    assert!(compute.druid_pool.is_empty());
 }
 
-/// Test POST make ip payment with correct address
+/// Test POST cancel DRUID is rejected once all participants have joined
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_request_donation() {
+async fn test_post_cancel_druid_all_participants_joined() {
     let _ = tracing_log_try_init();
 
     //
-    // Arange
+    // Arrange
     //
-    let (mut self_node, self_socket) = new_self_node(NodeType::User).await;
-
-    let address = "127.0.0.1:12345".to_owned();
-    let paying_peer = address.parse::<SocketAddr>().unwrap();
+    let tx_vals = get_rb_transactions();
+    let cancel_data = cancel_druid_data("full_druid", &tx_vals[0].0, cancel_druid_expiry());
+    let compute = ComputeTest::new(tx_vals);
 
     let request = warp::test::request()
         .method("POST")
-        .path("/request_donation")
-        .remote_addr(self_socket)
-        .header("x-request-id", COMMON_REQ_ID)
+        .path("/cancel_druid")
         .header("Content-Type", "application/json")
-        .json(&address);
-    //
-    // Act
-    //
-    let ks = to_api_keys(Default::default());
-    let cache = create_new_cache(CACHE_LIVE_TIME);
-
-    let filter =
-        routes::request_donation(&mut dp(), self_node.clone(), Default::default(), ks, cache)
-            .recover(handle_rejection);
-    let res = request.reply(&filter).await;
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&cancel_data);
 
     //
-    // Assert
+    // Act
     //
-    assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Donation request sent\",\"route\":\"request_donation\",\"content\":\"null\"}");
-
-    // Frame expected
-    let expected_frame = user_api_request_as_frame(UserApiRequest::RequestDonation { paying_peer });
-    let actual_frame = next_event_frame(&mut self_node).await;
-    assert_eq!(expected_frame, actual_frame);
-}
-
-/// Test POST import key-pairs
-#[tokio::test(flavor = "current_thread")]
-async fn test_post_import_keypairs_success() {
-    let _ = tracing_log_try_init();
-    let (self_node, _self_socket) = new_self_node(NodeType::User).await;
-    let db = get_wallet_db("").await;
-    let mut addresses: BTreeMap<String, AddressStoreHex> = BTreeMap::new();
-    addresses.insert(
-        COMMON_PUB_ADDR.to_string(),
-        AddressStoreHex {
-            public_key: COMMON_PUB_KEY.to_string(),
-            secret_key: COMMON_SEC_KEY.to_string(),
-            address_version: None,
-        },
-    );
-    let imported_addresses = Addresses { addresses };
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
-    let filter = routes::import_keypairs(
+    let filter = routes::cancel_druid(
         &mut dp(),
-        db.clone(),
-        self_node,
+        compute.threaded_calls.tx.clone(),
         Default::default(),
         ks,
         cache,
     )
-    .recover(handle_rejection);
-    let wallet_addresses_before = db.get_known_addresses();
-
-    let res = warp::test::request()
-        .method("POST")
-        .path("/import_keypairs")
-        .header("Content-Type", "application/json")
-        .header("x-request-id", COMMON_REQ_ID)
-        .json(&imported_addresses)
-        .reply(&filter)
-        .await;
-
-    error!("res: {:?}", res);
-
-    let wallet_addresses_after = db.get_known_addresses();
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let compute = handle.await.unwrap();
 
-    // Header to match
-    let mut headers = HeaderMap::new();
-    headers.insert("content-type", HeaderValue::from_static("application/json"));
-    assert_eq!(wallet_addresses_before, Vec::<String>::new());
-    assert_eq!(wallet_addresses_after, vec![COMMON_PUB_ADDR.to_string()]);
-    assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Key-pairs successfully imported\",\"route\":\"import_keypairs\",\"content\":[\"13bd3351b78beb2d0dadf2058dcc926c\"]}");
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"DRUID has all participants and is executing\",\"route\":\"cancel_druid\",\"content\":\"null\"}");
+    assert_eq!(compute.druid_pool["full_druid"].txs.len(), 2);
 }
 
+/// Test POST cancel DRUID is rejected once the signed cancellation has expired
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_fetch_balance() {
+async fn test_post_cancel_druid_expired() {
     let _ = tracing_log_try_init();
 
     //
     // Arrange
     //
-    let tx_vals = vec![get_transaction()];
-    let compute = ComputeTest::new(tx_vals);
-    let addresses = PublicKeyAddresses {
-        address_list: vec![COMMON_PUB_ADDR.to_string()],
-    };
+    let tx_vals = get_rb_transactions();
+    let compute = ComputeTest::new(vec![tx_vals[0].clone()]);
+    let cancel_data = cancel_druid_data("full_druid", &tx_vals[0].0, 1);
 
     let request = warp::test::request()
         .method("POST")
-        .path("/fetch_balance")
+        .path("/cancel_druid")
         .header("Content-Type", "application/json")
         .header("x-request-id", COMMON_REQ_ID)
-        .json(&addresses);
+        .json(&cancel_data);
+
     //
     // Act
     //
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
-    let filter = routes::fetch_balance(
+    let filter = routes::cancel_druid(
         &mut dp(),
         compute.threaded_calls.tx.clone(),
         Default::default(),
@@ -1678,44 +3656,45 @@ async fn test_post_fetch_balance() {
     .recover(handle_rejection);
     let handle = compute.spawn();
     let res = request.reply(&filter).await;
-    let _compute = handle.await.unwrap();
+    let compute = handle.await.unwrap();
 
     //
     // Assert
     //
-    assert_eq!((res.status(), res.headers().clone()), success_json());
     assert_eq!(
-        res.body(),
-        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Balance successfully fetched\",\"route\":\"fetch_balance\",\"content\":{\"total\":{\"tokens\":25200,\"receipts\":{}},\"address_list\":{\"13bd3351b78beb2d0dadf2058dcc926c\":[{\"out_point\":{\"t_hash\":\"tx_hash\",\"n\":0},\"value\":{\"Token\":25200}}]}}}"
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
     );
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"DRUID cancellation expired\",\"route\":\"cancel_druid\",\"content\":\"null\"}");
+    assert_eq!(compute.druid_pool["full_druid"].txs.len(), 1);
 }
 
+/// Test POST cancel DRUID is rejected when the expiry is beyond the maximum window
 #[tokio::test(flavor = "current_thread")]
-async fn test_post_fetch_pending() {
+async fn test_post_cancel_druid_expiry_too_far() {
     let _ = tracing_log_try_init();
 
     //
     // Arrange
     //
     let tx_vals = get_rb_transactions();
-    let compute = ComputeTest::new(tx_vals);
-    let druids = FetchPendingData {
-        druid_list: vec!["full_druid".to_owned(), "non_existing_druid".to_owned()],
-    };
+    let compute = ComputeTest::new(vec![tx_vals[0].clone()]);
+    let cancel_data = cancel_druid_data("full_druid", &tx_vals[0].0, u64::MAX);
 
     let request = warp::test::request()
         .method("POST")
-        .path("/fetch_pending")
+        .path("/cancel_druid")
         .header("Content-Type", "application/json")
         .header("x-request-id", COMMON_REQ_ID)
-        .json(&druids);
+        .json(&cancel_data);
+
     //
     // Act
     //
     let ks = to_api_keys(Default::default());
     let cache = create_new_cache(CACHE_LIVE_TIME);
 
-    let filter = routes::fetch_pending(
+    let filter = routes::cancel_druid(
         &mut dp(),
         compute.threaded_calls.tx.clone(),
         Default::default(),
@@ -1725,15 +3704,17 @@ async fn test_post_fetch_pending() {
     .recover(handle_rejection);
     let handle = compute.spawn();
     let res = request.reply(&filter).await;
-    let _compute = handle.await.unwrap();
+    let compute = handle.await.unwrap();
 
     //
     // Assert
     //
-    assert_eq!((res.status(), res.headers().clone()), success_json());
     assert_eq!(
-        res.body(),
-        "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Pending transactions successfully fetched\",\"route\":\"fetch_pending\",\"content\":{\"full_druid\":{\"participants\":2,\"txs\":{\"g490b4fc3b3953a8a006ec17ae4a6055\":{\"inputs\":[{\"previous_out\":{\"t_hash\":\"000001\",\"n\":0},\"script_signature\":{\"stack\":[{\"Bytes\":\"754dc248d1c847e8a10c6f8ded6ccad96381551ebb162583aea2a86b9bb78dfa\"},{\"Signature\":[21,103,185,228,19,36,74,158,249,211,229,41,187,113,248,98,27,55,85,97,36,94,216,242,20,156,39,245,55,212,95,22,52,161,77,8,211,241,24,217,126,208,39,154,87,136,126,31,154,177,219,197,151,174,148,122,67,147,4,59,177,191,172,8]},{\"PubKey\":[83,113,131,33,34,168,232,4,250,53,32,236,104,97,195,250,85,74,127,111,182,23,230,240,118,132,82,9,2,7,224,124]},{\"Op\":\"OP_DUP\"},{\"Op\":\"OP_HASH256\"},{\"PubKeyHash\":\"5423e6bd848e0ce5cd794e55235c23138d8833633cd2d7de7f4a10935178457b\"},{\"Op\":\"OP_EQUALVERIFY\"},{\"Op\":\"OP_CHECKSIG\"}]}}],\"outputs\":[{\"value\":{\"Token\":0},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":null},{\"value\":{\"Receipt\":{\"amount\":1,\"drs_tx_hash\":\"drs_tx_hash\",\"metadata\":null}},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":\"sender_address\"}],\"version\":5,\"druid_info\":{\"druid\":\"full_druid\",\"participants\":2,\"expectations\":[{\"from\":\"6efcefb27d1e1149b243ce319c5e5352bb100dc328a59f630ee7a9fd5ebe9da9\",\"to\":\"receiver_address\",\"asset\":{\"Token\":25200}}]}},\"g8118c848762693bda7be3f804601ad0\":{\"inputs\":[{\"previous_out\":{\"t_hash\":\"000000\",\"n\":0},\"script_signature\":{\"stack\":[{\"Bytes\":\"927b3411743452e5e0d73e9e40a4fa3c842b3d00dabde7f9af7e44661ce02c88\"},{\"Signature\":[35,226,158,202,184,227,77,178,40,234,140,161,109,206,131,187,171,159,103,146,89,201,220,227,212,184,216,166,69,26,92,67,221,248,253,165,17,176,190,4,48,76,146,12,179,195,90,227,170,17,196,234,76,57,254,242,83,89,237,117,68,193,105,10]},{\"PubKey\":[83,113,131,33,34,168,232,4,250,53,32,236,104,97,195,250,85,74,127,111,182,23,230,240,118,132,82,9,2,7,224,124]},{\"Op\":\"OP_DUP\"},{\"Op\":\"OP_HASH256\"},{\"PubKeyHash\":\"5423e6bd848e0ce5cd794e55235c23138d8833633cd2d7de7f4a10935178457b\"},{\"Op\":\"OP_EQUALVERIFY\"},{\"Op\":\"OP_CHECKSIG\"}]}}],\"outputs\":[{\"value\":{\"Token\":0},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":null},{\"value\":{\"Token\":25200},\"locktime\":0,\"drs_block_hash\":null,\"script_public_key\":\"receiver_address\"}],\"version\":5,\"druid_info\":{\"druid\":\"full_druid\",\"participants\":2,\"expectations\":[{\"from\":\"b519b3fd271bb33a7ea949a918cc45b00b32095a04f2a9172797f7441f7298e6\",\"to\":\"sender_address\",\"asset\":{\"Receipt\":{\"amount\":1,\"drs_tx_hash\":\"drs_tx_hash\",\"metadata\":null}}}]}}}}}}");
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Error\",\"reason\":\"DRUID cancellation expiry too far in the future\",\"route\":\"cancel_druid\",\"content\":\"null\"}");
+    assert_eq!(compute.druid_pool["full_druid"].txs.len(), 1);
 }
 
 /// Test POST update running total successful
@@ -2198,6 +4179,41 @@ async fn test_post_block_nums_by_tx_hashes() {
     assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database item(s) successfully retrieved\",\"route\":\"check_transaction_presence\",\"content\":[]}");
 }
 
+/// Test POST compact db keeps the stored data readable
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_compact_db() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let db = get_db_with_block().await;
+    let metrics_before = db.lock().unwrap().metrics();
+
+    let request = warp::test::request()
+        .method("POST")
+        .path("/compact_db")
+        .header("x-request-id", COMMON_REQ_ID);
+
+    //
+    // Act
+    //
+    let ks = to_api_keys(Default::default());
+    let cache = create_new_cache(CACHE_LIVE_TIME);
+    let filter = routes::compact_db(&mut dp(), db.clone(), Default::default(), ks, cache)
+        .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    let metrics_after = db.lock().unwrap().metrics();
+    let expected_string = format!("{{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Database successfully compacted\",\"route\":\"compact_db\",\"content\":{}}}", serde_json::to_string(&metrics_after).unwrap());
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), &expected_string);
+    assert_eq!(metrics_after, metrics_before);
+}
+
 /// Test POST pause nodes
 #[tokio::test(flavor = "current_thread")]
 async fn test_post_pause_nodes() {
@@ -2233,7 +4249,7 @@ async fn test_post_pause_nodes() {
     // Assert
     //
     assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"\",\"route\":\"pause_nodes\",\"content\":\"null\"}");
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Attempt coordinated node pause\",\"route\":\"pause_nodes\",\"content\":\"null\"}");
 }
 
 /// Test POST resume nodes
@@ -2270,7 +4286,278 @@ async fn test_post_resume_nodes() {
     // Assert
     //
     assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"\",\"route\":\"resume_nodes\",\"content\":\"null\"}");
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Attempt coordinated node resume\",\"route\":\"resume_nodes\",\"content\":\"null\"}");
+}
+
+/// Test POST coordinated shutdown
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_coordinated_shutdown() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut compute = ComputeTest::new(Default::default());
+    compute.current_block_num = 5;
+    let mut local_event = LocalEventChannel::default();
+    let request = |at_block: u64| {
+        warp::test::request()
+            .method("POST")
+            .path("/coordinated_shutdown")
+            .header("Content-Type", "application/json")
+            .header("x-request-id", COMMON_REQ_ID)
+            .json(&CoordinatedShutdownData { at_block })
+    };
+    let filter = |tc: ThreadedCallSender<dyn ComputeApi>, ev_tx: LocalEventSender| {
+        routes::coordinated_shutdown(
+            &mut dp(),
+            tc,
+            ev_tx,
+            Default::default(),
+            Default::default(),
+            create_new_cache(CACHE_LIVE_TIME),
+        )
+        .recover(handle_rejection)
+    };
+
+    //
+    // Act
+    //
+    let tx = compute.threaded_calls.tx.clone();
+    let handle = compute.spawn();
+    let res = request(10)
+        .reply(&filter(tx.clone(), local_event.tx.clone()))
+        .await;
+    let event = local_event.rx.recv().await;
+
+    let handle = handle.await.unwrap().spawn();
+    let res_past = request(2).reply(&filter(tx, local_event.tx.clone())).await;
+    let _ = handle.await;
+    let event_past = local_event.rx.try_recv().ok();
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Coordinated shutdown scheduled\",\"route\":\"coordinated_shutdown\",\"content\":10}");
+    assert_eq!(event, Some(LocalEvent::CoordinatedShutdown(10)));
+
+    assert_eq!(
+        (res_past.status(), res_past.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(event_past, None);
+}
+
+/// Test POST cancel coordinated shutdown
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_cancel_coordinated_shutdown() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut local_event = LocalEventChannel::default();
+    let request = warp::test::request()
+        .method("POST")
+        .path("/cancel_coordinated_shutdown")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID);
+
+    //
+    // Act
+    //
+    let filter = routes::cancel_coordinated_shutdown(
+        &mut dp(),
+        local_event.tx.clone(),
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+    let event = local_event.rx.recv().await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Coordinated shutdown cancelled\",\"route\":\"cancel_coordinated_shutdown\",\"content\":\"null\"}");
+    assert_eq!(event, Some(LocalEvent::CancelShutdown));
+}
+
+/// Test POST force snapshot sends the local event
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_force_snapshot() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut local_event = LocalEventChannel::default();
+    let request = warp::test::request()
+        .method("POST")
+        .path("/force_snapshot")
+        .header("x-request-id", COMMON_REQ_ID);
+
+    //
+    // Act
+    //
+    let filter = routes::force_snapshot(
+        &mut dp(),
+        local_event.tx.clone(),
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+    let event = local_event.rx.recv().await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Snapshot requested\",\"route\":\"force_snapshot\",\"content\":\"null\"}");
+    assert_eq!(event, Some(LocalEvent::ForceSnapshot));
+}
+
+/// Test POST log level changes the active log filter
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_log_level() {
+    //
+    // Arrange
+    //
+    let counter = EventCounter::default();
+    let (filter_layer, log_filter) = reload::Layer::new(EnvFilter::new("off"));
+    let subscriber = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(counter.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let request = |log_level: &str| {
+        warp::test::request()
+            .method("POST")
+            .path("/log_level")
+            .header("Content-Type", "application/json")
+            .header("x-request-id", COMMON_REQ_ID)
+            .header("x-api-key", COMMON_VALID_API_KEY)
+            .json(&LogLevelData {
+                log_level: log_level.to_owned(),
+            })
+    };
+    let mut route_keys = BTreeMap::new();
+    route_keys.insert(
+        "log_level".to_owned(),
+        vec![COMMON_VALID_API_KEY.to_owned()],
+    );
+    let filter = routes::log_level(
+        &mut dp(),
+        log_filter,
+        Default::default(),
+        to_api_keys(route_keys),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+
+    //
+    // Act
+    //
+    tracing::debug!("Filtered out");
+    let count_before = counter.count();
+    let res = request("znp::api::tests=debug").reply(&filter).await;
+    tracing::debug!("Emitted");
+    let count_after = counter.count();
+    let res_invalid = request("znp=not_a_level").reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Log level updated\",\"route\":\"log_level\",\"content\":\"znp::api::tests=debug\"}");
+    assert_eq!((count_before, count_after), (0, 1));
+
+    assert_eq!(
+        (res_invalid.status(), res_invalid.headers().clone()),
+        fail_json(StatusCode::BAD_REQUEST)
+    );
+}
+
+/// Test POST log level is rejected when no API key is configured for it
+#[tokio::test(flavor = "current_thread")]
+async fn test_post_log_level_no_api_key_configured() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let request = warp::test::request()
+        .method("POST")
+        .path("/log_level")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID)
+        .json(&LogLevelData {
+            log_level: "znp=debug".to_owned(),
+        });
+
+    //
+    // Act
+    //
+    let filter = routes::log_level(
+        &mut dp(),
+        test_log_filter(),
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let res = request.reply(&filter).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        (res.status(), res.headers().clone()),
+        fail_json(StatusCode::UNAUTHORIZED)
+    );
+}
+
+/// Test GET scheduled coordinated shutdown
+#[tokio::test(flavor = "current_thread")]
+async fn test_get_coordinated_shutdown() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut compute = ComputeTest::new(Default::default());
+    compute.coordinated_shutdown = Some(10);
+    let request = warp::test::request()
+        .method("GET")
+        .path("/get_coordinated_shutdown")
+        .header("Content-Type", "application/json")
+        .header("x-request-id", COMMON_REQ_ID);
+
+    //
+    // Act
+    //
+    let filter = routes::get_coordinated_shutdown(
+        &mut dp(),
+        compute.threaded_calls.tx.clone(),
+        Default::default(),
+        Default::default(),
+        create_new_cache(CACHE_LIVE_TIME),
+    )
+    .recover(handle_rejection);
+    let handle = compute.spawn();
+    let res = request.reply(&filter).await;
+    let _ = handle.await;
+
+    //
+    // Assert
+    //
+    assert_eq!((res.status(), res.headers().clone()), success_json());
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Successfully fetched coordinated shutdown\",\"route\":\"get_coordinated_shutdown\",\"content\":10}");
 }
 
 /// Test POST update shared config
@@ -2285,6 +4572,12 @@ async fn test_post_update_shared_config() {
         compute_mining_event_timeout: 10000,
         compute_partition_full_size: 5,
         compute_miner_whitelist: Default::default(),
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_dust_threshold: TokenAmount(0),
+        compute_min_block_reward: TokenAmount(0),
+        compute_reward_halving: None,
+        compute_winning_miner_fairness: None,
     };
     let compute = ComputeTest::new(Default::default());
     let request = warp::test::request()
@@ -2313,5 +4606,5 @@ async fn test_post_update_shared_config() {
     // Assert
     //
     assert_eq!((res.status(), res.headers().clone()), success_json());
-    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"\",\"route\":\"update_shared_config\",\"content\":\"null\"}");
+    assert_eq!(res.body(), "{\"id\":\"2ae7bc9cba924e3cb73c0249893078d7\",\"status\":\"Success\",\"reason\":\"Attempt send shared config\",\"route\":\"update_shared_config\",\"content\":\"null\"}");
 }
@@ -1,16 +1,25 @@
 use super::{
     errors::{ApiError, ApiErrorType},
     handlers::DbgPaths,
-    responses::{common_error_reply, json_serialize_embed, CallResponse, JsonReply},
+    responses::{common_error_reply, json_serialize_embed, CallResponse, JsonReply, ReplyCallId},
 };
-use crate::utils::{validate_pow_for_diff, ApiKeys, RoutesPoWInfo};
+use crate::utils::{
+    decode_pub_key, decode_signature, validate_pow_for_diff, ApiKeys, RoutesPoWInfo,
+};
+use bytes::Bytes;
 use futures::Future;
 use moka::future::{Cache, CacheBuilder};
+use naom::crypto::{sha3_256, sign_ed25519 as sign};
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 use std::convert::Infallible;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
 use tracing::log::error;
 use warp::{
-    hyper::{HeaderMap, StatusCode},
+    hyper::{header::HeaderValue, HeaderMap, Method, StatusCode},
     path::FullPath,
     Filter, Rejection, Reply,
 };
@@ -22,13 +31,21 @@ pub fn with_node_component<T: Clone + Send>(
     warp::any().map(move || comp.clone())
 }
 
-// Add route path to mutable reference DbgPaths
+// Add route path to mutable reference DbgPaths: disabled route paths are not found
 pub fn warp_path(
     dp: &mut DbgPaths,
     p: &'static str,
 ) -> impl Filter<Extract = (), Error = Rejection> + Clone {
-    dp.push(p);
+    let enabled = dp.push(p);
     warp::path(p)
+        .and_then(move || async move {
+            if enabled {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
 }
 
 // Maps an error that implements `ToString` to JsonReply error for bad requests.
@@ -37,6 +54,11 @@ pub fn map_string_err<T: ToString>(r: CallResponse, e: T, s: StatusCode) -> Json
         .unwrap_err() // Should panic if result is not Err
 }
 
+// Maps a structured API error to JsonReply error with the status code matching its cause.
+pub fn map_api_err(r: CallResponse, e: ApiErrorType) -> JsonReply {
+    r.into_api_err(e).unwrap_err() // Should panic if result is not Err
+}
+
 // Map API response from Result<JsonReply, JsonReply> to Result<warp::Reply, warp::Rejection>
 //Adds responses to a cache
 pub fn map_api_res_and_cache(
@@ -49,70 +71,402 @@ pub fn map_api_res_and_cache(
     r_2.map_ok_or_else(Ok, Ok)
 }
 
+// Map API response from Result<JsonReply, JsonReply> to Result<warp::Reply, warp::Rejection>
+// without caching, for replies that must always reflect the current node state
+pub fn map_api_res(
+    r: impl Future<Output = Result<JsonReply, JsonReply>>,
+) -> impl Future<Output = Result<impl warp::Reply, warp::Rejection>> {
+    use futures::future::TryFutureExt;
+    r.map_ok_or_else(Ok, Ok)
+}
+
+// Map API response from Result<Response, JsonReply> to Result<warp::Reply, warp::Rejection>
+// without caching, for replies streamed rather than buffered
+pub fn map_api_stream_res(
+    r: impl Future<Output = Result<warp::reply::Response, JsonReply>>,
+) -> impl Future<Output = Result<warp::reply::Response, warp::Rejection>> {
+    use futures::future::TryFutureExt;
+    r.map_ok_or_else(|e| Ok(e.into_response()), Ok)
+}
+
+// Headers of requests signed by API keys that opted in to signing
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+pub const CONTENT_HASH_HEADER: &str = "x-content-hash";
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+// Maximum age in seconds of a signed request timestamp, in either direction
+pub const SIGNED_REQUEST_MAX_AGE_SECS: u64 = 5 * 60;
+
+// Response header echoing the correlation ID of the request
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+// Extracts the x-request-id header value as correlation ID, generating one if absent.
+// Routes that need no authorization use it as call ID, so the handler and the
+// x-correlation-id response header share the generated ID.
+pub fn correlation_id() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("x-request-id").map(|id: Option<String>| {
+        id.filter(|id| !id.is_empty())
+            .unwrap_or_else(|| hex::encode(rand::thread_rng().gen::<[u8; 16]>()))
+    })
+}
+
+// Echoes the call ID the reply was made for in the x-correlation-id response header,
+// logging the request and response under it.
+// Replies made without a handler, such as rejections, use the request correlation ID.
+pub fn with_correlation_id<F, T>(
+    routes: F,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (T,), Error = Rejection> + Clone + Send + Sync + 'static,
+    T: Reply,
+{
+    correlation_id()
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(routes)
+        .map(|id: String, method: Method, path: FullPath, reply: T| {
+            let mut response = reply.into_response();
+            let id = match response.extensions_mut().remove::<ReplyCallId>() {
+                Some(ReplyCallId(call_id)) => call_id,
+                None => id,
+            };
+            if let Ok(value) = HeaderValue::from_str(&id) {
+                response.headers_mut().insert(CORRELATION_ID_HEADER, value);
+            }
+            let status = response.status();
+            debug!(correlation_id = %id, %method, path = path.as_str(), %status, "API request");
+            response
+        })
+}
+
+// Content codings replies can be compressed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReplyEncoding {
+    Gzip,
+    Deflate,
+    Identity,
+}
+
+// Compresses replies with gzip or deflate when enabled and accepted by the client
+pub fn with_compression<F, T>(
+    enabled: bool,
+    routes: F,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (T,), Error = Rejection> + Clone + Send + Sync + 'static,
+    T: Reply,
+{
+    let encoding = move |wanted: ReplyEncoding| {
+        warp::header::optional::<String>("accept-encoding")
+            .and_then(move |accept: Option<String>| async move {
+                let encoding = match accept {
+                    Some(accept) if enabled => accepted_encoding(&accept),
+                    _ => ReplyEncoding::Identity,
+                };
+                if encoding == wanted {
+                    Ok(())
+                } else {
+                    Err(warp::reject::not_found())
+                }
+            })
+            .untuple_one()
+    };
+
+    let gzip = encoding(ReplyEncoding::Gzip)
+        .and(routes.clone())
+        .with(warp::compression::gzip())
+        .map(into_response);
+    let deflate = encoding(ReplyEncoding::Deflate)
+        .and(routes.clone())
+        .with(warp::compression::deflate())
+        .map(into_response);
+    let identity = encoding(ReplyEncoding::Identity)
+        .and(routes)
+        .map(into_response);
+
+    gzip.or(deflate).unify().or(identity).unify()
+}
+
+// Picks the preferred content coding among those accepted in an Accept-Encoding header
+fn accepted_encoding(accept: &str) -> ReplyEncoding {
+    let accepted: Vec<&str> = accept
+        .split(',')
+        .filter_map(|coding| {
+            let mut params = coding.split(';');
+            let name = params.next()?.trim();
+            let refused = params.any(|param| {
+                let q = param.trim().strip_prefix("q=");
+                q.and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+            });
+            (!refused).then(|| name)
+        })
+        .collect();
+
+    let is_accepted = |coding: &str| accepted.iter().any(|c| c.eq_ignore_ascii_case(coding));
+    if is_accepted("gzip") {
+        ReplyEncoding::Gzip
+    } else if is_accepted("deflate") {
+        ReplyEncoding::Deflate
+    } else {
+        ReplyEncoding::Identity
+    }
+}
+
+// Converts differently compressed replies to a common response type
+fn into_response<T: Reply>(reply: T) -> warp::reply::Response {
+    reply.into_response()
+}
+
 // Authorizes a request based on API keys as well as PoW requirements for the route
 // Successfull authorization will extract the x-request-id header value
 pub fn auth_request(
     routes_pow: RoutesPoWInfo,
     api_keys: ApiKeys,
 ) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
-    warp::path::full()
+    let seen_signatures: SeenSignatures = Default::default();
+    warp::method()
+        .and(warp::path::full())
+        .and(raw_query())
         .and(warp::header::headers_cloned())
-        .and_then(move |path: FullPath, headers: HeaderMap| {
-            let route_path = path.as_str()[1..].to_owned(); /* Slice to remove '/' prefix */
-            let route_difficulty = routes_pow.lock().unwrap().get(&route_path).cloned();
-            let needed_keys = api_keys.lock().unwrap().get(&route_path).cloned();
+        .and_then(
+            move |method: Method, path: FullPath, query: String, headers: HeaderMap| {
+                let route_path = path.as_str()[1..].to_owned(); /* Slice to remove '/' prefix */
+                let route_difficulty = routes_pow.lock().unwrap().get(&route_path).cloned();
+                let (needed_keys, signing_keys) = {
+                    let api_keys = api_keys.lock().unwrap();
+                    let needed_keys = api_keys.route_keys.get(&route_path).cloned();
+                    (needed_keys, api_keys.signing_keys.clone())
+                };
+                let seen_signatures = seen_signatures.clone();
 
-            async move {
-                // Extract headers
-                let id = headers
-                    .get("x-request-id")
-                    .and_then(|n| n.to_str().ok())
-                    .unwrap_or_default();
-
-                let nonce = headers
-                    .get("x-nonce")
-                    .and_then(|n| n.to_str().ok())
-                    .unwrap_or_default();
-
-                let api_key = headers
-                    .get("x-api-key")
-                    .and_then(|n| n.to_str().ok())
-                    .unwrap_or_default();
-
-                // Error for authorization failure
-                let err_unauthorized = Err(warp::reject::custom(ApiError::new(
-                    StatusCode::UNAUTHORIZED,
-                    ApiErrorType::Unauthorized,
-                    id.to_owned(),
-                    route_path,
-                )));
-
-                // All requests require a unique ID of 32 characters
-                if id.chars().count() != 32 {
-                    return err_unauthorized;
-                }
+                async move {
+                    // Extract headers
+                    let id = headers
+                        .get("x-request-id")
+                        .and_then(|n| n.to_str().ok())
+                        .unwrap_or_default();
+
+                    let nonce = headers
+                        .get("x-nonce")
+                        .and_then(|n| n.to_str().ok())
+                        .unwrap_or_default();
+
+                    let api_key = headers
+                        .get("x-api-key")
+                        .and_then(|n| n.to_str().ok())
+                        .unwrap_or_default();
+
+                    // Error for authorization failure
+                    let err_unauthorized = Err(warp::reject::custom(ApiError::new(
+                        StatusCode::UNAUTHORIZED,
+                        ApiErrorType::Unauthorized,
+                        id.to_owned(),
+                        route_path,
+                    )));
 
-                // API key is needed
-                if let Some(needed_api_keys) = needed_keys {
-                    if !needed_api_keys.contains(&api_key.to_string()) {
+                    // All requests require a unique ID of 32 characters
+                    if id.chars().count() != 32 {
                         return err_unauthorized;
                     }
-                }
 
-                let hash_content = format!("{nonce}-{id}");
+                    // API key is needed
+                    if let Some(needed_api_keys) = needed_keys {
+                        if !needed_api_keys.contains(&api_key.to_string()) {
+                            return err_unauthorized;
+                        }
+                    }
 
-                // This route requires PoW
-                if let Some(difficulty) = route_difficulty {
-                    if validate_pow_for_diff(difficulty, hash_content.as_bytes()).is_none() {
-                        return err_unauthorized;
+                    // API key that opted in to signing must sign the request
+                    if let Some(public_key) = signing_keys.get(api_key) {
+                        let path_and_query = if query.is_empty() {
+                            path.as_str().to_owned()
+                        } else {
+                            format!("{}?{}", path.as_str(), query)
+                        };
+                        let signed = verify_request_signature(
+                            &headers,
+                            method.as_str(),
+                            &path_and_query,
+                            public_key,
+                            &seen_signatures,
+                            unix_timestamp_now(),
+                        );
+                        if !signed {
+                            return err_unauthorized;
+                        }
+                    }
+
+                    let hash_content = format!("{nonce}-{id}");
+
+                    // This route requires PoW
+                    if let Some(difficulty) = route_difficulty {
+                        if validate_pow_for_diff(difficulty, hash_content.as_bytes()).is_none() {
+                            return err_unauthorized;
+                        }
                     }
+
+                    // No PoW required
+                    Ok(id.to_owned())
                 }
+            },
+        )
+        .or_else(move |err| async move { Err(err) })
+}
 
-                // No PoW required
-                Ok(id.to_owned())
+// Authorizes a request like `auth_request`, but only if API keys are configured for the route.
+// Routes changing how the node operates use it so they are never open by default.
+pub fn auth_request_with_api_key(
+    routes_pow: RoutesPoWInfo,
+    api_keys: ApiKeys,
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    api_key_configured(api_keys.clone()).and(auth_request(routes_pow, api_keys))
+}
+
+// Rejects requests to routes without any API key configured
+fn api_key_configured(api_keys: ApiKeys) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(warp::header::optional::<String>("x-request-id"))
+        .and_then(move |path: FullPath, id: Option<String>| {
+            let route_path = path.as_str()[1..].to_owned(); /* Slice to remove '/' prefix */
+            let configured = api_keys
+                .lock()
+                .unwrap()
+                .route_keys
+                .contains_key(&route_path);
+            async move {
+                if configured {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(ApiError::new(
+                        StatusCode::UNAUTHORIZED,
+                        ApiErrorType::Unauthorized,
+                        id.unwrap_or_default(),
+                        route_path,
+                    )))
+                }
             }
         })
-        .or_else(move |err| async move { Err(err) })
+        .untuple_one()
+}
+
+// Canonical representation of a request signed by API keys that opted in to signing.
+// The path includes the query string if any, so query parameters cannot be altered.
+pub fn request_signing_content(
+    method: &str,
+    path_and_query: &str,
+    timestamp: u64,
+    content_hash: &str,
+) -> String {
+    format!("{method}\n{path_and_query}\n{timestamp}\n{content_hash}")
+}
+
+// Extracts the raw query string, empty if the request has none
+fn raw_query() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::query::raw().or(warp::any().map(String::new)).unify()
+}
+
+// Hash of a request body as expected in the content hash header
+pub fn request_content_hash(body: &[u8]) -> String {
+    hex::encode(sha3_256::digest(body))
+}
+
+// Current time in seconds since the unix epoch
+fn unix_timestamp_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// Signatures accepted within the timestamp window, with their request timestamp
+type SeenSignatures = Arc<Mutex<BTreeMap<String, u64>>>;
+
+// Check the request signature headers against the registered public key.
+// Stale timestamps are rejected, and signatures already accepted within the
+// timestamp window are rejected, so a captured request cannot be replayed.
+// The body itself is checked against the content hash header by `hashed_body`.
+fn verify_request_signature(
+    headers: &HeaderMap,
+    method: &str,
+    path_and_query: &str,
+    public_key: &str,
+    seen_signatures: &Mutex<BTreeMap<String, u64>>,
+    now: u64,
+) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|n| n.to_str().ok());
+
+    let (timestamp, content_hash, signature) = match (
+        header(TIMESTAMP_HEADER).and_then(|t| t.parse::<u64>().ok()),
+        header(CONTENT_HASH_HEADER),
+        header(SIGNATURE_HEADER),
+    ) {
+        (Some(timestamp), Some(content_hash), Some(signature)) => {
+            (timestamp, content_hash, signature)
+        }
+        _ => return false,
+    };
+
+    if now.abs_diff(timestamp) > SIGNED_REQUEST_MAX_AGE_SECS {
+        return false;
+    }
+
+    let signature = match (decode_pub_key(public_key), decode_signature(signature)) {
+        (Ok(public_key), Ok(signature)) => {
+            let content = request_signing_content(method, path_and_query, timestamp, content_hash);
+            if !sign::verify_detached(&signature, content.as_bytes(), &public_key) {
+                return false;
+            }
+            signature
+        }
+        _ => return false,
+    };
+
+    // Entries outside the window can be dropped: their timestamp is rejected anyway
+    let mut seen_signatures = seen_signatures.lock().unwrap();
+    seen_signatures.retain(|_, seen| now.abs_diff(*seen) <= SIGNED_REQUEST_MAX_AGE_SECS);
+    seen_signatures
+        .insert(hex::encode(signature.as_ref()), timestamp)
+        .is_none()
+}
+
+// Extract the raw body, checking it against the content hash header if provided.
+// Every route reading a body must use it, so signed requests cover their body.
+pub fn hashed_body() -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(warp::header::optional::<String>("x-request-id"))
+        .and(warp::header::optional::<String>(CONTENT_HASH_HEADER))
+        .and(warp::body::bytes())
+        .and_then(
+            |path: FullPath, id: Option<String>, hash: Option<String>, body: Bytes| async move {
+                if let Some(hash) = hash {
+                    if hash != request_content_hash(&body) {
+                        return Err(warp::reject::custom(ApiError::new(
+                            StatusCode::UNAUTHORIZED,
+                            ApiErrorType::Unauthorized,
+                            id.unwrap_or_default(),
+                            path.as_str()[1..].to_owned(),
+                        )));
+                    }
+                }
+
+                Ok(body)
+            },
+        )
+}
+
+// Deserialize a JSON body, checking it against the content hash header if provided
+pub fn json_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = Rejection> + Clone {
+    hashed_body().and_then(|body: Bytes| async move {
+        serde_json::from_slice::<T>(&body).map_err(|_| {
+            warp::reject::custom(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                ApiErrorType::BadRequest,
+                "null".to_string(),
+                "null".to_string(),
+            ))
+        })
+    })
 }
 
 // Custom function to handle request errors that occur before the request can be logically processed
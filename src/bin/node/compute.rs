@@ -2,21 +2,35 @@
 
 use clap::{App, Arg, ArgMatches};
 use config::ConfigError;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use znp::configurations::ComputeNodeConfig;
 use znp::ComputeNode;
 use znp::{
     get_sanction_addresses, loop_wait_connnect_to_peers_async, loops_re_connect_disconnect, routes,
-    shutdown_connections, ResponseResult, SANC_LIST_PROD,
+    shutdown_connections, tracing_log_reloadable_init, ResponseResult, SANC_LIST_PROD,
 };
 
 pub async fn run_node(matches: &ArgMatches<'_>) {
     let mut config = configuration(load_settings(matches));
+    let log_filter =
+        tracing_log_reloadable_init(config.log_level.as_deref(), config.log_json).unwrap();
 
     println!("Start node with config {config:?}");
+    let api_bind_address = config
+        .api_bind_address
+        .unwrap_or(Ipv4Addr::UNSPECIFIED.into());
+    let api_disabled_routes = config.api_disabled_routes.clone();
+    let api_compression = config.api_compression;
 
     config.sanction_list = get_sanction_addresses(SANC_LIST_PROD.to_string(), &config.jurisdiction);
-    let node = ComputeNode::new(config, Default::default()).await.unwrap();
+    let node = match ComputeNode::new(config.clone(), Default::default()).await {
+        Err(e) if e.is_corrupt_db() => {
+            println!("Repair database and restart node after: {e}");
+            ComputeNode::repair_db(&config).unwrap();
+            ComputeNode::new(config, Default::default()).await.unwrap()
+        }
+        node => node.unwrap(),
+    };
     let api_inputs = node.api_inputs();
 
     println!("API Inputs: {api_inputs:?}");
@@ -53,21 +67,26 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
 
     // Warp API
     let warp_handle = tokio::spawn({
-        let (api_addr, api_tls, api_keys, routes_pow, peer) = api_inputs;
+        let (api_addr, api_tls, api_keys, routes_pow, peer, readiness) = api_inputs;
         let threaded_calls_tx = threaded_calls_tx;
+        let local_event_tx = node.local_event_tx().clone();
 
         println!("Warp API started on port {:?}", api_addr.port());
         println!();
 
-        let mut bind_address = "0.0.0.0:0".parse::<SocketAddr>().unwrap();
-        bind_address.set_port(api_addr.port());
+        let bind_address = SocketAddr::new(api_bind_address, api_addr.port());
 
         async move {
             let serve = warp::serve(routes::compute_node_routes(
                 api_keys,
                 routes_pow,
                 threaded_calls_tx,
+                local_event_tx,
                 peer,
+                readiness,
+                log_filter,
+                api_disabled_routes,
+                api_compression,
             ));
             if let Some(api_tls) = api_tls {
                 serve
@@ -146,6 +165,19 @@ pub fn clap_app<'a, 'b>() -> App<'a, 'b> {
                 .help("The port to run the http API from")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("api_bind_address")
+                .long("api_bind_address")
+                .help("The IP address to bind the http API to, all interfaces if not set")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("disable_routes")
+                .long("disable_routes")
+                .help("Comma separated names of the http API routes to disable")
+                .takes_value(true)
+                .use_delimiter(true),
+        )
         .arg(
             Arg::with_name("api_use_tls")
                 .long("api_use_tls")
@@ -209,6 +241,7 @@ fn load_settings(matches: &clap::ArgMatches) -> config::Config {
         .unwrap();
     settings.set_default("compute_api_port", 3002).unwrap();
     settings.set_default("compute_api_use_tls", true).unwrap();
+    settings.set_default("api_compression", true).unwrap();
     settings.set_default("jurisdiction", "US").unwrap();
     settings.set_default("compute_node_idx", 0).unwrap();
     settings.set_default("compute_raft", 0).unwrap();
@@ -249,6 +282,13 @@ fn load_settings(matches: &clap::ArgMatches) -> config::Config {
     if let Some(port) = matches.value_of("api_port") {
         settings.set("compute_api_port", port).unwrap();
     }
+    if let Some(address) = matches.value_of("api_bind_address") {
+        settings.set("api_bind_address", address).unwrap();
+    }
+    if let Some(routes) = matches.values_of("disable_routes") {
+        let routes: Vec<String> = routes.map(|r| r.to_owned()).collect();
+        settings.set("api_disabled_routes", routes).unwrap();
+    }
     if let Some(use_tls) = matches.value_of("api_use_tls") {
         settings.set("compute_api_use_tls", use_tls).unwrap();
     }
@@ -356,6 +396,28 @@ mod test {
         validate_startup_common(args, expected);
     }
 
+    #[test]
+    fn validate_startup_api_bind_address() {
+        //
+        // Arrange
+        //
+        let args = vec!["bin_name", "--api_bind_address=127.0.0.1"];
+        let default_args = vec!["bin_name"];
+
+        //
+        // Act
+        //
+        let config = configuration(load_settings(&clap_app().get_matches_from(args)));
+        let default_config =
+            configuration(load_settings(&clap_app().get_matches_from(default_args)));
+
+        //
+        // Assert
+        //
+        assert_eq!(config.api_bind_address, Some(Ipv4Addr::LOCALHOST.into()));
+        assert_eq!(default_config.api_bind_address, None);
+    }
+
     fn validate_startup_common(args: Vec<&str>, expected: Expected) {
         //
         // Act
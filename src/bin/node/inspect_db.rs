@@ -0,0 +1,106 @@
+//! App to inspect the databases of a node.
+
+use clap::{App, Arg, ArgMatches};
+use std::path::Path;
+use znp::configurations::DbMode;
+use znp::db_utils::{new_db_no_check_version, new_db_save_path, CustomDbSpec, SimpleDbSpec};
+use znp::upgrade::{dump_db, inspect_db, DB_SPEC_INFOS};
+
+pub async fn run_node(matches: &ArgMatches<'_>) {
+    tracing_subscriber::fmt::init();
+    let node_type = matches.value_of("type").unwrap();
+    let db_mode = match matches.value_of("index") {
+        Some(index) => DbMode::Test(index.parse::<usize>().unwrap()),
+        None => DbMode::Live,
+    };
+    let dump = matches.is_present("dump");
+
+    let specs: Vec<_> = DB_SPEC_INFOS
+        .iter()
+        .filter(|s| s.node_type == node_type)
+        .collect();
+    if specs.is_empty() {
+        println!("Invalid node type: {node_type:?}");
+        return;
+    }
+
+    for spec in specs {
+        let db_path = matches.value_of("db_path").unwrap_or(spec.db_path);
+        let custom_spec = CustomDbSpec {
+            db_path: db_path.to_owned(),
+            suffix: spec.suffix.to_owned(),
+        };
+        let db_spec = SimpleDbSpec {
+            db_path: spec.db_path,
+            suffix: spec.suffix,
+            columns: &[],
+        };
+
+        // Do not create a database where none exists
+        let save_path = new_db_save_path(db_mode, &db_spec, Some(custom_spec.clone()));
+        if !save_path.as_ref().map_or(false, |p| Path::new(p).exists()) {
+            println!(
+                "No database found for {node_type}{} at {save_path:?}",
+                spec.suffix
+            );
+            continue;
+        }
+
+        let db = match new_db_no_check_version(db_mode, &db_spec, None, Some(custom_spec)) {
+            Ok(db) => db,
+            Err(e) => {
+                println!("Failed to open database at {save_path:?}: {e}");
+                continue;
+            }
+        };
+
+        match inspect_db(&db) {
+            Ok(report) => {
+                println!("Database {node_type}{} at {save_path:?}", spec.suffix);
+                println!("  version: {:?}", report.version);
+                for (column, count) in &report.column_key_counts {
+                    println!("  column {column:?}: {count} keys");
+                }
+                println!("  blocks: {}", report.block_count);
+                println!("  last block num: {:?}", report.last_block_num);
+            }
+            Err(e) => println!("Failed to inspect database at {save_path:?}: {e}"),
+        }
+
+        if dump {
+            for column_key_value in dump_db(&db) {
+                println!("({column_key_value}),");
+            }
+        }
+    }
+}
+
+pub fn clap_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("inspect_db")
+        .about("Summarize the content of a node databases.")
+        .arg(
+            Arg::with_name("type")
+                .long("type")
+                .help("Inspect the databases for type (compute, storage, user, miner)")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("db_path")
+                .long("db_path")
+                .help("Directory containing the databases, default to the node directory")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("index")
+                .short("i")
+                .long("index")
+                .help("Inspect the test databases with the given index instead of live ones")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("dump")
+                .long("dump")
+                .help("Also dump all the databases entries"),
+        )
+}
@@ -3,6 +3,7 @@
 use clap::{App, ArgMatches};
 
 mod compute;
+mod inspect_db;
 mod miner;
 mod pre_launch;
 mod storage;
@@ -10,7 +11,6 @@ mod user;
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
     let matches = clap_app().get_matches();
     launch_node_with_args(matches).await;
 }
@@ -24,6 +24,7 @@ async fn launch_node_with_args(matches: ArgMatches<'_>) {
             "compute" => compute::run_node(sub_matches).await,
             "storage" => storage::run_node(sub_matches).await,
             "pre_launch" => pre_launch::run_node(sub_matches).await,
+            "inspect_db" => inspect_db::run_node(sub_matches).await,
             invalid_type => panic!("Invalid node type: {:?}", invalid_type),
         }
     } else {
@@ -39,4 +40,5 @@ fn clap_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(compute::clap_app())
         .subcommand(storage::clap_app())
         .subcommand(pre_launch::clap_app())
+        .subcommand(inspect_db::clap_app())
 }
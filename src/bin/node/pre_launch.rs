@@ -2,15 +2,21 @@
 
 use clap::{App, Arg, ArgMatches};
 use config::ConfigError;
+use std::net::{Ipv4Addr, SocketAddr};
 use znp::configurations::PreLaunchNodeConfig;
 use znp::PreLaunchNode;
 use znp::{
-    loop_wait_connnect_to_peers_async, loops_re_connect_disconnect, shutdown_connections,
+    loop_wait_connnect_to_peers_async, loops_re_connect_disconnect, routes, shutdown_connections,
     ResponseResult,
 };
 
 pub async fn run_node(matches: &ArgMatches<'_>) {
+    tracing_subscriber::fmt::init();
     let config = configuration(load_settings(matches));
+    let api_port = config.pre_launch_api_port;
+    let api_bind_address = config
+        .api_bind_address
+        .unwrap_or(Ipv4Addr::UNSPECIFIED.into());
 
     println!("Start node with config {config:?}");
     let node = PreLaunchNode::new(config, Default::default())
@@ -22,6 +28,15 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
     let (node_conn, addrs_to_connect, expected_connected_addrs) = node.connect_info_peers();
     let local_event_tx = node.local_event_tx().clone();
 
+    // Warp API: Only reports progress so stopped with the node
+    let warp_handle = api_port.map(|port| {
+        let status = node.status();
+        let bind_address = SocketAddr::new(api_bind_address, port);
+
+        println!("Warp API started on port {port:?}");
+        tokio::spawn(warp::serve(routes::pre_launch_node_routes(status)).run(bind_address))
+    });
+
     // PERMANENT CONNEXION/DISCONNECTION HANDLING
     let ((conn_loop_handle, stop_re_connect_tx), (disconn_loop_handle, stop_disconnect_tx)) = {
         let (re_connect, disconnect_test) =
@@ -63,6 +78,10 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
     main.unwrap();
     conn.unwrap();
     disconn.unwrap();
+
+    if let Some(warp_handle) = warp_handle {
+        warp_handle.abort();
+    }
 }
 
 pub fn clap_app<'a, 'b>() -> App<'a, 'b> {
@@ -2,19 +2,43 @@
 
 use clap::{App, Arg, ArgMatches};
 use config::ConfigError;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
 use znp::configurations::StorageNodeConfig;
 use znp::StorageNode;
 use znp::{
     loop_wait_connnect_to_peers_async, loops_re_connect_disconnect, routes, shutdown_connections,
-    ResponseResult,
+    tracing_log_reloadable_init, ResponseResult, API_LATEST_BLOCK_LONGPOLL_MAX_PENDING,
+    API_LATEST_BLOCK_LONGPOLL_TIMEOUT_MS,
 };
 
 pub async fn run_node(matches: &ArgMatches<'_>) {
     let config = configuration(load_settings(matches));
+    let log_filter =
+        tracing_log_reloadable_init(config.log_level.as_deref(), config.log_json).unwrap();
 
     println!("Start node with config {config:?}");
-    let node = StorageNode::new(config, Default::default()).await.unwrap();
+    let api_bind_address = config
+        .api_bind_address
+        .unwrap_or(Ipv4Addr::UNSPECIFIED.into());
+    let api_disabled_routes = config.api_disabled_routes.clone();
+    let api_compression = config.api_compression;
+    let api_longpoll_timeout = Duration::from_millis(
+        config
+            .storage_api_longpoll_timeout_ms
+            .unwrap_or(API_LATEST_BLOCK_LONGPOLL_TIMEOUT_MS),
+    );
+    let api_longpoll_max_pending = config
+        .storage_api_longpoll_max_pending
+        .unwrap_or(API_LATEST_BLOCK_LONGPOLL_MAX_PENDING);
+    let node = match StorageNode::new(config.clone(), Default::default()).await {
+        Err(e) if e.is_corrupt_db() => {
+            println!("Repair database and restart node after: {e}");
+            StorageNode::repair_db(&config).unwrap();
+            StorageNode::new(config, Default::default()).await.unwrap()
+        }
+        node => node.unwrap(),
+    };
 
     println!("Started node at {}", node.local_address());
 
@@ -37,6 +61,9 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
     // Need to connect first so Raft messages can be sent.
     loop_wait_connnect_to_peers_async(node_conn.clone(), expected_connected_addrs).await;
 
+    let raft_status = node.raft_status();
+    let api_local_event_tx = node.local_event_tx().clone();
+
     // RAFT HANDLING
     let raft_loop_handle = {
         let raft_loop = node.raft_loop();
@@ -49,13 +76,12 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
 
     // Warp API
     let warp_handle = tokio::spawn({
-        let (db, api_addr, api_tls, api_keys, api_pow_info) = api_inputs;
+        let (db, api_addr, api_tls, api_keys, api_pow_info, readiness) = api_inputs;
 
         println!("Warp API started on port {:?}", api_addr.port());
         println!();
 
-        let mut bind_address = "0.0.0.0:0".parse::<SocketAddr>().unwrap();
-        bind_address.set_port(api_addr.port());
+        let bind_address = SocketAddr::new(api_bind_address, api_addr.port());
         let node_conn_debug = node_conn.clone();
 
         async move {
@@ -63,7 +89,15 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
                 api_keys,
                 api_pow_info,
                 db,
+                api_local_event_tx,
                 node_conn_debug,
+                readiness,
+                raft_status,
+                api_longpoll_timeout,
+                api_longpoll_max_pending,
+                log_filter,
+                api_disabled_routes,
+                api_compression,
             ));
             if let Some(api_tls) = api_tls {
                 serve
@@ -151,6 +185,19 @@ pub fn clap_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Run the API for the storage node as the specified port")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("api_bind_address")
+                .long("api_bind_address")
+                .help("The IP address to bind the http API to, all interfaces if not set")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("disable_routes")
+                .long("disable_routes")
+                .help("Comma separated names of the http API routes to disable")
+                .takes_value(true)
+                .use_delimiter(true),
+        )
         .arg(
             Arg::with_name("api_use_tls")
                 .long("api_use_tls")
@@ -186,6 +233,7 @@ fn load_settings(matches: &clap::ArgMatches) -> config::Config {
     settings.set_default("storage_raft", 0).unwrap();
     settings.set_default("storage_api_port", 3001).unwrap();
     settings.set_default("storage_api_use_tls", true).unwrap();
+    settings.set_default("api_compression", true).unwrap();
 
     settings
         .set_default("storage_raft_tick_timeout", 10)
@@ -211,6 +259,13 @@ fn load_settings(matches: &clap::ArgMatches) -> config::Config {
     if let Some(port) = matches.value_of("api_port") {
         settings.set("storage_api_port", port).unwrap();
     }
+    if let Some(address) = matches.value_of("api_bind_address") {
+        settings.set("api_bind_address", address).unwrap();
+    }
+    if let Some(routes) = matches.values_of("disable_routes") {
+        let routes: Vec<String> = routes.map(|r| r.to_owned()).collect();
+        settings.set("api_disabled_routes", routes).unwrap();
+    }
     if let Some(use_tls) = matches.value_of("api_use_tls") {
         settings.set("storage_api_use_tls", use_tls).unwrap();
     }
@@ -3,15 +3,22 @@
 use clap::{App, Arg, ArgMatches};
 use config::{ConfigError, Value};
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use znp::configurations::UserNodeConfig;
 use znp::{
     loop_wait_connnect_to_peers_async, loops_re_connect_disconnect, routes, shutdown_connections,
-    ResponseResult, UserNode,
+    tracing_log_reloadable_init, ResponseResult, UserNode,
 };
 
 pub async fn run_node(matches: &ArgMatches<'_>) {
     let config = configuration(load_settings(matches));
+    let log_filter =
+        tracing_log_reloadable_init(config.log_level.as_deref(), config.log_json).unwrap();
+    let api_bind_address = config
+        .api_bind_address
+        .unwrap_or(Ipv4Addr::UNSPECIFIED.into());
+    let api_disabled_routes = config.api_disabled_routes.clone();
+    let api_compression = config.api_compression;
 
     println!("Starting node with config: {config:?}");
     println!();
@@ -65,11 +72,18 @@ pub async fn run_node(matches: &ArgMatches<'_>) {
         println!("Warp API started on port {:?}", api_addr.port());
         println!();
 
-        let mut bind_address = "0.0.0.0:0".parse::<SocketAddr>().unwrap();
-        bind_address.set_port(api_addr.port());
+        let bind_address = SocketAddr::new(api_bind_address, api_addr.port());
 
         async move {
-            let serve = warp::serve(routes::user_node_routes(api_keys, api_pow_info, db, node));
+            let serve = warp::serve(routes::user_node_routes(
+                api_keys,
+                api_pow_info,
+                db,
+                node,
+                log_filter,
+                api_disabled_routes,
+                api_compression,
+            ));
             if let Some(api_tls) = api_tls {
                 serve
                     .tls()
@@ -129,6 +143,19 @@ pub fn clap_app<'a, 'b>() -> App<'a, 'b> {
                 .help("The port to run the http API from")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("api_bind_address")
+                .long("api_bind_address")
+                .help("The IP address to bind the http API to, all interfaces if not set")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("disable_routes")
+                .long("disable_routes")
+                .help("Comma separated names of the http API routes to disable")
+                .takes_value(true)
+                .use_delimiter(true),
+        )
         .arg(
             Arg::with_name("api_use_tls")
                 .long("api_use_tls")
@@ -204,6 +231,7 @@ fn load_settings(matches: &clap::ArgMatches) -> config::Config {
         .unwrap();
     settings.set_default("user_api_port", 3000).unwrap();
     settings.set_default("user_api_use_tls", true).unwrap();
+    settings.set_default("api_compression", true).unwrap();
     settings.set_default("user_compute_node_idx", 0).unwrap();
     settings.set_default("user_auto_donate", 0).unwrap();
 
@@ -306,6 +334,13 @@ fn load_settings(matches: &clap::ArgMatches) -> config::Config {
     if let Some(api_port) = matches.value_of("api_port") {
         settings.set("user_api_port", api_port).unwrap();
     }
+    if let Some(address) = matches.value_of("api_bind_address") {
+        settings.set("api_bind_address", address).unwrap();
+    }
+    if let Some(routes) = matches.values_of("disable_routes") {
+        let routes: Vec<String> = routes.map(|r| r.to_owned()).collect();
+        settings.set("api_disabled_routes", routes).unwrap();
+    }
 
     if let Some(index) = matches.value_of("compute_index") {
         settings.set("user_compute_node_idx", index).unwrap();
@@ -5,8 +5,8 @@ use std::collections::BTreeSet;
 use znp::configurations::DbMode;
 use znp::upgrade::{
     dump_db, get_db_to_dump_no_checks, get_upgrade_compute_db, get_upgrade_storage_db,
-    get_upgrade_wallet_db, upgrade_compute_db, upgrade_storage_db, upgrade_wallet_db, DbSpecInfo,
-    UpgradeCfg, UpgradeError, DB_SPEC_INFOS,
+    get_upgrade_wallet_db, upgrade_compute_db, upgrade_storage_db_with_progress,
+    upgrade_transactional, upgrade_wallet_db, DbSpecInfo, UpgradeCfg, UpgradeError, DB_SPEC_INFOS,
 };
 
 const NODE_TYPES: &[&str] = &["compute", "storage", "user", "miner"];
@@ -78,13 +78,21 @@ fn process_upgrade(
     for (node_type, mode) in db_modes {
         println!("Upgrade Database {node_type}, {mode:?}");
         let extra = Default::default();
-        let (_, status) = match node_type.as_str() {
-            "compute" => upgrade_compute_db(get_upgrade_compute_db(mode, extra)?, &upgrade_cfg)?,
-            "storage" => upgrade_storage_db(get_upgrade_storage_db(mode, extra)?, &upgrade_cfg)?,
-            "user" => upgrade_wallet_db(get_upgrade_wallet_db(mode, extra)?, &upgrade_cfg)?,
-            "miner" => upgrade_wallet_db(get_upgrade_wallet_db(mode, extra)?, &upgrade_cfg)?,
+        let mut dbs = match node_type.as_str() {
+            "compute" => get_upgrade_compute_db(mode, extra)?,
+            "storage" => get_upgrade_storage_db(mode, extra)?,
+            "user" | "miner" => get_upgrade_wallet_db(mode, extra)?,
             _ => return Err(UpgradeError::ConfigError("Type does not exists")),
         };
+        let status = match node_type.as_str() {
+            "compute" => upgrade_transactional(&mut dbs, &upgrade_cfg, upgrade_compute_db)?,
+            "storage" => upgrade_transactional(&mut dbs, &upgrade_cfg, |dbs, cfg| {
+                upgrade_storage_db_with_progress(dbs, cfg, |processed, total| {
+                    println!("Upgrade Storage items: {processed}/{total}")
+                })
+            })?,
+            _ => upgrade_transactional(&mut dbs, &upgrade_cfg, upgrade_wallet_db)?,
+        };
 
         let last_block_num = status.last_block_num.as_ref();
         let last_raft_block_num = status.last_raft_block_num.as_ref();
@@ -139,7 +147,9 @@ fn clap_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("processing")
                 .long("processing")
-                .help("Type of processing to do: read or upgrade")
+                .help(
+                    "Type of processing to do: read, upgrade or validate (upgrade without writing)",
+                )
                 .takes_value(true)
                 .required(true),
         )
@@ -179,10 +189,11 @@ fn configuration(
         .unwrap_or_default()
         .to_owned();
     let node_type = matches.value_of("type").unwrap();
-    let processing = match matches.value_of("processing").unwrap() {
-        "read" => Processing::Read,
-        "upgrade" => Processing::Upgrade,
-        v => panic!("expect processing to be read or upgrade: {}", v),
+    let (processing, validate_only) = match matches.value_of("processing").unwrap() {
+        "read" => (Processing::Read, false),
+        "upgrade" => (Processing::Upgrade, false),
+        "validate" => (Processing::Upgrade, true),
+        v => panic!("expect processing to be read, upgrade or validate: {}", v),
     };
     let raft_len = settings.get_array("storage_nodes").unwrap().len();
     let compute_partition_full_size = settings.get("compute_partition_full_size").unwrap();
@@ -192,6 +203,7 @@ fn configuration(
         compute_partition_full_size,
         compute_unicorn_fixed_param,
         passphrase,
+        validate_only,
     };
 
     let ignore = matches.value_of("ignore").unwrap_or("");
@@ -259,6 +271,7 @@ mod test {
                 compute_partition_full_size: 1,
                 compute_unicorn_fixed_param: get_test_common_unicorn(),
                 passphrase: String::new(),
+                validate_only: false,
             },
         );
 
@@ -283,6 +296,7 @@ mod test {
                 compute_partition_full_size: 1,
                 compute_unicorn_fixed_param: get_test_common_unicorn(),
                 passphrase: "TestPassPhrase".to_owned(),
+                validate_only: false,
             },
         );
 
@@ -305,6 +319,7 @@ mod test {
                 compute_partition_full_size: 2,
                 compute_unicorn_fixed_param: get_test_common_unicorn(),
                 passphrase: String::new(),
+                validate_only: false,
             },
         );
 
@@ -335,6 +350,7 @@ mod test {
                 compute_partition_full_size: 2,
                 compute_unicorn_fixed_param: get_test_common_unicorn(),
                 passphrase: String::new(),
+                validate_only: false,
             },
         );
 
@@ -92,6 +92,7 @@ pub struct PipelineEventInfo {
     pub sufficient_majority: usize,
     pub unanimous_majority: usize,
     pub partition_full_size: usize,
+    pub winning_miner_fairness: Option<u64>,
 }
 
 /// Rolling info particular to a specific mining pipeline
@@ -279,7 +280,7 @@ impl MiningPipelineInfo {
             }
             AllItemsIntake => {
                 if self.has_ready_select_winning_miner(extra.sufficient_majority) {
-                    self.start_winning_pow_halted();
+                    self.start_winning_pow_halted(extra.winning_miner_fairness);
                     return Some(MiningPipelinePhaseChange::StartPhaseHalted);
                 }
             }
@@ -361,7 +362,7 @@ impl MiningPipelineInfo {
 
         self.unicorn_select_participants_mining(usize::MAX);
         self.all_winning_pow.push(winning_pow);
-        self.start_winning_pow_halted();
+        self.start_winning_pow_halted(None);
     }
 
     /// Retrieves the current UNICORN for this pipeline
@@ -397,6 +398,20 @@ impl MiningPipelineInfo {
         }
     }
 
+    /// Whether the participants proposed by the given peer filled the partition
+    /// during the participant only intake
+    pub fn is_participants_intake_full(
+        &self,
+        proposer_id: u64,
+        partition_full_size: usize,
+    ) -> bool {
+        self.mining_pipeline_status == MiningPipelineStatus::ParticipantOnlyIntake
+            && self
+                .participants_intake
+                .get(&proposer_id)
+                .map_or(false, |ps| ps.len() >= partition_full_size)
+    }
+
     /// Add winning PoW to the running list
     pub fn add_to_winning_pow(
         &mut self,
@@ -454,13 +469,24 @@ impl MiningPipelineInfo {
     }
 
     /// Selects a winning miner from the list via UNICORN and move to halted state
-    pub fn start_winning_pow_halted(&mut self) {
+    ///
+    /// ### Arguments
+    ///
+    /// * `winning_miner_fairness` - Percentage moving the selection towards equal chance per miner
+    pub fn start_winning_pow_halted(&mut self, winning_miner_fairness: Option<u64>) {
         let all_winning_pow = std::mem::take(&mut self.all_winning_pow);
         let _timeouts = std::mem::take(&mut self.current_phase_timeout_peer_ids);
 
-        self.winning_pow = self
-            .get_unicorn_item(WINNING_MINER_UN, &all_winning_pow)
-            .cloned();
+        self.winning_pow = match winning_miner_fairness {
+            Some(fairness) if !all_winning_pow.is_empty() => {
+                let prn = self.get_unicorn_prn(WINNING_MINER_UN);
+                let selection = select_fair_winning_pow(&all_winning_pow, fairness, prn);
+                Some(all_winning_pow[selection].clone())
+            }
+            _ => self
+                .get_unicorn_item(WINNING_MINER_UN, &all_winning_pow)
+                .cloned(),
+        };
         self.last_winning_hashes = all_winning_pow
             .into_iter()
             .map(|(_, pow)| pow.mining_tx.0)
@@ -585,3 +611,123 @@ impl MiningPipelineInfo {
 fn get_unicorn_seed_value(u: &UnicornInfo) -> Vec<u8> {
     format!("{}-{}", u.unicorn.seed, u.witness).into_bytes()
 }
+
+/// Weight of each miner as the index of its first PoW entry and its selection weight.
+/// The weight moves from the miner's number of entries towards an equal share per miner
+/// as the fairness percentage increases.
+///
+/// ### Arguments
+///
+/// * `all_winning_pow` - The PoW entries in consensused order
+/// * `fairness`        - Percentage: 0 gives one chance per entry, 100 one chance per miner
+fn fair_miner_weights(
+    all_winning_pow: &[(SocketAddr, WinningPoWInfo)],
+    fairness: u64,
+) -> Vec<(usize, u64)> {
+    let mut miner_entries: BTreeMap<SocketAddr, (usize, u64)> = BTreeMap::new();
+    for (idx, (addr, _)) in all_winning_pow.iter().enumerate() {
+        miner_entries.entry(*addr).or_insert((idx, 0)).1 += 1;
+    }
+
+    let fairness = fairness.min(100);
+    let total_entries = all_winning_pow.len() as u64;
+    let miners_len = miner_entries.len() as u64;
+    miner_entries
+        .into_iter()
+        .map(|(_, (idx, entries))| {
+            let weight = (100 - fairness) * entries * miners_len + fairness * total_entries;
+            (idx, weight)
+        })
+        .collect()
+}
+
+/// Index of the winning PoW entry selected with the given pseudo random number
+///
+/// ### Arguments
+///
+/// * `all_winning_pow` - The non empty PoW entries in consensused order
+/// * `fairness`        - Percentage weighting the selection towards an equal chance per miner
+/// * `prn`             - UNICORN-generated pseudo random number
+fn select_fair_winning_pow(
+    all_winning_pow: &[(SocketAddr, WinningPoWInfo)],
+    fairness: u64,
+    prn: u64,
+) -> usize {
+    let weights = fair_miner_weights(all_winning_pow, fairness);
+    let total_weight: u64 = weights.iter().map(|(_, weight)| weight).sum();
+
+    let mut selection = prn % total_weight;
+    for (idx, weight) in weights {
+        if selection < weight {
+            return idx;
+        }
+        selection -= weight;
+    }
+    0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fair_winning_pow_shifts_selection_to_smaller_miners() {
+        //
+        // Arrange
+        //
+        let large: SocketAddr = "127.0.0.1:12300".parse().unwrap();
+        let small_1: SocketAddr = "127.0.0.1:12301".parse().unwrap();
+        let small_2: SocketAddr = "127.0.0.1:12302".parse().unwrap();
+
+        // Skewed hashrate: the large miner found 8 of the 10 PoW entries
+        let mut miners = vec![large; 8];
+        miners.insert(3, small_1);
+        miners.push(small_2);
+        let all_winning_pow: Vec<_> = miners
+            .into_iter()
+            .enumerate()
+            .map(|(idx, addr)| (addr, winning_pow_info(idx)))
+            .collect();
+
+        //
+        // Act
+        //
+        let wins: Vec<Vec<usize>> = [0, 50, 100]
+            .iter()
+            .map(|fairness| {
+                let mut wins = vec![0; 3];
+                for prn in 0..3000 {
+                    let selection = select_fair_winning_pow(&all_winning_pow, *fairness, prn);
+                    let winner = all_winning_pow[selection].0;
+                    let miner_idx = [large, small_1, small_2].iter().position(|a| *a == winner);
+                    wins[miner_idx.unwrap()] += 1;
+                }
+                wins
+            })
+            .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            wins,
+            vec![
+                // Pure PoW: one chance per entry
+                vec![2400, 300, 300],
+                // Halfway between one chance per entry and per miner
+                vec![1700, 650, 650],
+                // One chance per miner
+                vec![1000, 1000, 1000],
+            ]
+        );
+    }
+
+    fn winning_pow_info(idx: usize) -> WinningPoWInfo {
+        WinningPoWInfo {
+            nonce: vec![idx as u8],
+            mining_tx: (format!("g{:031}", idx), Transaction::new()),
+            p_value: 0,
+            d_value: 0,
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub use error::CommsError;
 pub use node::Node;
 pub use tcp_tls::{TcpTlsConfig, TcpTlsConnector, TcpTlsListner};
 
+use crate::interfaces::CommsFormat;
 use bytes::Bytes;
 use std::net::SocketAddr;
 
@@ -21,5 +22,9 @@ pub type Result<T> = std::result::Result<T, CommsError>;
 /// Events from peer.
 #[derive(Debug)]
 pub enum Event {
-    NewFrame { peer: SocketAddr, frame: Bytes },
+    NewFrame {
+        peer: SocketAddr,
+        frame: Bytes,
+        format: CommsFormat,
+    },
 }
@@ -7,6 +7,9 @@
 //! Nodes use a custom TCP protocol to communicate which is message-based. The available message types can be found in [`CommMessage`](crate::interfaces::CommMessage).
 //! The messages are serialized into a binary format using the `bincode` crate for efficiency (it can be easily replaced with any other [serde][serde]-compatible serialization
 //! format such as JSON if needed).
+//! A node can request JSON for a connection during the handshake (see [`CommsFormat`](crate::interfaces::CommsFormat)): the direct messages
+//! following the handshake are then serialized as JSON, while handshakes and gossip payloads always use `bincode`.
+//! Nodes using the default `bincode` format send the same handshake as peers predating the format negotiation.
 //!
 //! Because each node acts as a server, a _listener_ service is started automatically when you crate a new [`Node`](crate::comms_handler::node::Node).
 //!
@@ -86,15 +89,15 @@ use super::tcp_tls::{
 use super::{CommsError, Event, Result, TcpTlsConfig};
 use crate::comms_handler::error::PeerInfo;
 use crate::constants::NETWORK_VERSION;
-use crate::interfaces::{node_type_as_str, CommMessage, NodeType, Token};
+use crate::interfaces::{node_type_as_str, CommMessage, CommsFormat, NodeType, Token};
 use crate::utils::MpscTracingSender;
-use bincode::{deserialize, serialize};
-use bytes::Bytes;
+use bincode::serialize;
+use bytes::{Bytes, BytesMut};
 use futures::future::join_all;
 use futures::SinkExt;
 use rand::prelude::*;
 use serde::Serialize;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::{fmt, io};
@@ -135,6 +138,10 @@ type CloseListener = Option<(oneshot::Sender<()>, JoinHandle<()>)>;
 pub struct Node {
     /// Node network version.
     network_version: u32,
+    /// Other network versions this node accepts peers from.
+    compatible_network_versions: Arc<RwLock<BTreeSet<u32>>>,
+    /// Serialization format requested to the peers this node connects to.
+    comms_format: Arc<RwLock<CommsFormat>>,
     /// This node's local listener address.
     local_listener_address: SocketAddr,
     // This node's public listener address.
@@ -170,6 +177,8 @@ pub struct Node {
 pub(crate) struct Peer {
     /// Node network version.
     network_version: Option<u32>,
+    /// Serialization format negotiated during the handshake.
+    comms_format: CommsFormat,
     /// Channel for sending frames to the peer.
     send_tx: ResultBytesSender,
     /// Peer remote address.
@@ -258,6 +267,8 @@ impl Node {
 
         let mut node = Self {
             network_version,
+            compatible_network_versions: Arc::new(RwLock::new(BTreeSet::new())),
+            comms_format: Arc::new(RwLock::new(CommsFormat::default())),
             local_listener_address,
             public_listener_address: Arc::new(RwLock::new(None)), // Will get filled on handshake success
             listener_stop_and_join_handles: Arc::new(Mutex::new(None)),
@@ -318,11 +329,36 @@ impl Node {
         })
     }
 
-    fn is_compatible(&self, node_type: NodeType, network_version: u32) -> bool {
-        self.network_version == network_version
+    async fn is_compatible(&self, node_type: NodeType, network_version: u32) -> bool {
+        let version_compatible = self.network_version == network_version
+            || self
+                .compatible_network_versions
+                .read()
+                .await
+                .contains(&network_version);
+        if !version_compatible {
+            warn!(
+                "Peer network version {} incompatible with ours {}",
+                network_version, self.network_version
+            );
+        }
+
+        version_compatible
             && (self.node_type == NodeType::PreLaunch) == (node_type == NodeType::PreLaunch)
     }
 
+    /// Sets the network versions, other than our own, accepted during peer handshakes.
+    /// Used to allow rolling upgrades between protocol compatible versions.
+    pub async fn set_compatible_network_versions(&self, versions: impl IntoIterator<Item = u32>) {
+        *self.compatible_network_versions.write().await = versions.into_iter().collect();
+    }
+
+    /// Sets the serialization format requested during handshakes with the peers we connect to.
+    /// Handshakes and gossip payloads always use bincode.
+    pub async fn set_comms_format(&self, format: CommsFormat) {
+        *self.comms_format.write().await = format;
+    }
+
     pub fn set_connect_to_handshake_contacts(&mut self, value: bool) {
         self.connect_to_handshake_contacts = value;
     }
@@ -671,24 +707,36 @@ impl Node {
         }))
     }
 
+    /// Get the serialization format negotiated with a peer.
+    async fn get_peer_comms_format(&self, peer_addr: SocketAddr) -> CommsFormat {
+        let peers = self.peers.read().await;
+        peers
+            .get(&peer_addr)
+            .map(|peer| peer.comms_format)
+            .unwrap_or_default()
+    }
+
     /// Sends data to a peer.
     async fn send_message(&mut self, peer_addr: SocketAddr, message: CommMessage) -> Result<()> {
-        let data = Bytes::from(serialize(&message)?);
+        let (mut tx, format) = {
+            let peers = self.peers.read().await;
+            let peer = peers
+                .get(&peer_addr)
+                .ok_or(CommsError::PeerNotFound(PeerInfo {
+                    node_type: None,
+                    address: Some(peer_addr),
+                }))?;
+            (peer.send_tx.clone(), peer.comms_format)
+        };
 
-        let peers = self.peers.read().await;
-        let peer = peers
-            .get(&peer_addr)
-            .ok_or(CommsError::PeerNotFound(PeerInfo {
-                node_type: None,
-                address: Some(peer_addr),
-            }))?;
-        let mut tx = peer.send_tx.clone();
+        let data = Bytes::from(format.serialize(&message)?);
         self.send_bytes(peer_addr, &mut tx, data).await
     }
 
     /// Sends a serialized message to a given peer.
     pub async fn send(&mut self, peer: SocketAddr, data: impl Serialize) -> Result<()> {
-        let payload = Bytes::from(serialize(&data)?);
+        let format = self.get_peer_comms_format(peer).await;
+        let payload = Bytes::from(format.serialize(&data)?);
         let id = rand::thread_rng().gen();
         self.send_message(peer, CommMessage::Direct { payload, id })
             .await
@@ -700,11 +748,19 @@ impl Node {
         peers: impl Iterator<Item = SocketAddr>,
         data: impl Serialize,
     ) -> Result<Vec<SocketAddr>> {
-        let payload = Bytes::from(serialize(&data)?);
+        let mut peers_by_format: BTreeMap<CommsFormat, Vec<SocketAddr>> = BTreeMap::new();
+        for peer in peers {
+            let format = self.get_peer_comms_format(peer).await;
+            peers_by_format.entry(format).or_default().push(peer);
+        }
+
         let id = rand::thread_rng().gen();
-        let unsent_nodes = self
-            .send_multicast(peers, CommMessage::Direct { payload, id })
-            .await;
+        let mut unsent_nodes = Vec::new();
+        for (format, peers) in peers_by_format {
+            let payload = Bytes::from(format.serialize(&data)?);
+            let message = CommMessage::Direct { payload, id };
+            unsent_nodes.extend(self.send_multicast(peers.into_iter(), message).await);
+        }
         Ok(unsent_nodes)
     }
 
@@ -740,11 +796,12 @@ impl Node {
     async fn send_handshake(&mut self, peer: SocketAddr) -> Result<()> {
         self.send_message(
             peer,
-            CommMessage::HandshakeRequest {
-                network_version: self.network_version,
-                node_type: self.node_type,
-                public_address: self.local_listener_address,
-            },
+            CommMessage::handshake_request(
+                self.network_version,
+                self.node_type,
+                self.local_listener_address,
+                *self.comms_format.read().await,
+            ),
         )
         .await
     }
@@ -763,6 +820,7 @@ impl Node {
         Ok(self.event_tx.send(Event::NewFrame {
             peer: from_peer_addr,
             frame: payload,
+            format: CommsFormat::Bincode,
         })?)
     }
 
@@ -793,11 +851,12 @@ impl Node {
         while let Some(message) = messages.next().await {
             trace!(?message, "handle_peer_recv_handshake");
 
-            match message {
-                CommMessage::HandshakeRequest {
+            match message.with_handshake_format() {
+                CommMessage::HandshakeRequestWithFormat {
                     network_version: v,
                     node_type: t,
                     public_address,
+                    comms_format,
                 } => {
                     match self
                         .handle_handshake_request(
@@ -807,6 +866,7 @@ impl Node {
                             send_tx.clone(),
                             v,
                             t,
+                            comms_format,
                         )
                         .await
                     {
@@ -818,11 +878,12 @@ impl Node {
                         }
                     }
                 }
-                CommMessage::HandshakeResponse {
+                CommMessage::HandshakeResponseWithFormat {
                     network_version,
                     node_type,
                     contacts,
                     public_address,
+                    comms_format,
                 } => {
                     match self
                         .handle_handshake_response(
@@ -831,6 +892,7 @@ impl Node {
                             node_type,
                             contacts,
                             public_address,
+                            comms_format,
                         )
                         .await
                     {
@@ -853,10 +915,12 @@ impl Node {
     ///
     /// ### Arguments
     /// * `peer_addr`    - address of a remote peer.
+    /// * `format`       - serialization format negotiated with the peer.
     /// * `messages`     - stream of incoming messages.
     async fn handle_peer_recv(
         &self,
         peer_addr: SocketAddr,
+        format: CommsFormat,
         mut messages: impl Stream<Item = CommMessage> + std::marker::Unpin,
     ) {
         while let Some(message) = messages.next().await {
@@ -869,6 +933,7 @@ impl Node {
                     if let Err(error) = self.event_tx.send(Event::NewFrame {
                         peer: peer_addr,
                         frame,
+                        format,
                     }) {
                         warn!(?error, ?peer_addr, "event_tx.send");
                     }
@@ -913,6 +978,7 @@ impl Node {
         self.event_tx.send(Event::NewFrame {
             peer: from_peer,
             frame: payload.clone(),
+            format: CommsFormat::Bincode,
         })?;
 
         // Retransmit the gossip message.
@@ -960,6 +1026,8 @@ impl Node {
     /// * `send_tx`         - channel to send messages to the peer.
     /// * `network_version` - network version of the peer.
     /// * `peer_type`       - type of the peer.
+    /// * `comms_format`    - serialization format requested by the peer.
+    #[allow(clippy::too_many_arguments)]
     async fn handle_handshake_request(
         &self,
         peer_out_addr: SocketAddr,
@@ -968,6 +1036,7 @@ impl Node {
         mut send_tx: ResultBytesSender,
         network_version: u32,
         peer_type: NodeType,
+        comms_format: CommsFormat,
     ) -> Result<SocketAddr> {
         info!(
             "peer_out_addr: {:?}, peer_in_addr: {:?}",
@@ -976,7 +1045,7 @@ impl Node {
         // Derive IP from peer_out_addr; resolved through connection
         // Use port from peer_in_addr; resolved through handshake data
         peer_in_addr = SocketAddr::new(peer_out_addr.ip(), peer_in_addr.port());
-        if !self.is_compatible(peer_type, network_version) {
+        if !self.is_compatible(peer_type, network_version).await {
             return Err(CommsError::PeerIncompatible(PeerInfo {
                 node_type: Some(peer_type),
                 address: Some(peer_in_addr),
@@ -1013,14 +1082,16 @@ impl Node {
         peer.network_version = Some(network_version);
         peer.peer_type = Some(peer_type);
         peer.public_address = Some(peer_in_addr);
+        peer.comms_format = comms_format;
 
         // Send handshake response which will contain contacts of all valid peers within our ring.
-        let response = CommMessage::HandshakeResponse {
-            network_version: self.network_version,
-            node_type: self.node_type,
-            contacts: self.ring_peers(&all_peers).collect(),
-            public_address: peer_in_addr,
-        };
+        let response = CommMessage::handshake_response(
+            self.network_version,
+            self.node_type,
+            self.ring_peers(&all_peers).collect(),
+            peer_in_addr,
+            comms_format,
+        );
         let message = Bytes::from(serialize(&response)?);
         self.send_bytes(peer_out_addr, &mut send_tx, message)
             .await?;
@@ -1037,8 +1108,9 @@ impl Node {
         peer_type: NodeType,
         contacts: Vec<SocketAddr>,
         public_address: SocketAddr,
+        comms_format: CommsFormat,
     ) -> Result<()> {
-        if !self.is_compatible(peer_type, network_version) {
+        if !self.is_compatible(peer_type, network_version).await {
             return Err(CommsError::PeerIncompatible(PeerInfo {
                 node_type: Some(peer_type),
                 address: Some(peer_addr),
@@ -1057,6 +1129,7 @@ impl Node {
                 }))?;
             peer.network_version = Some(network_version);
             peer.peer_type = Some(peer_type);
+            peer.comms_format = comms_format;
 
             if let Some(notify) = peer.notify_handshake_response.0.take() {
                 notify.send(()).unwrap();
@@ -1147,16 +1220,18 @@ impl Node {
 
         // Spawn the receiver task which will redirect the incoming messages into the MPSC channel
         // and manage the peer state transitions.
-        let (messages, close_receiver_tx) = get_messages_stream(sock_in);
+        let (frames, close_receiver_tx) = get_frames_stream(sock_in);
         let sock_in_h = spawn({
             let mut node = self.clone();
             let send_tx = send_tx.clone().into();
             let peers = self.peers.clone();
             async move {
-                let mut messages = messages;
-                let public_address = {
+                let mut frames = frames;
+                let (public_address, format) = {
+                    // Handshake messages always use bincode
+                    let messages = decode_messages(&mut frames, CommsFormat::Bincode);
                     match node
-                        .handle_peer_recv_handshake(peer_addr, peer_cert, send_tx, &mut messages)
+                        .handle_peer_recv_handshake(peer_addr, peer_cert, send_tx, messages)
                         .await
                     {
                         Ok(public_address) => {
@@ -1169,8 +1244,9 @@ impl Node {
                                         peer_addr,
                                         public_address
                                     );
+                                    let format = peer.comms_format;
                                     peers_list.insert(public_address, peer);
-                                    public_address
+                                    (public_address, format)
                                 } else {
                                     // Peer not present
                                     trace!("sock_in dropped for {:?}", peer_addr);
@@ -1189,7 +1265,9 @@ impl Node {
                     }
                 };
 
-                node.handle_peer_recv(public_address, messages).await;
+                let messages = decode_messages(frames, format);
+                node.handle_peer_recv(public_address, format, messages)
+                    .await;
                 // Since we don't wait for any messages from this peer, we can drop the connection.
                 warn!("Remove peer: {}", public_address);
                 let mut peers_list = peers.write().await;
@@ -1201,6 +1279,7 @@ impl Node {
 
         Peer {
             network_version: None,
+            comms_format: CommsFormat::default(),
             addr: peer_addr,
             send_tx: send_tx.into(),
             peer_type: None,
@@ -1261,26 +1340,18 @@ impl Node {
     }
 }
 
-/// Transforms a stream of incoming TCP frames into a stream of deserialized messages.
-fn get_messages_stream(
+/// Transforms a stream of incoming TCP frames into a stream of decoded frames.
+fn get_frames_stream(
     sock_in: FramedRead<tokio::io::ReadHalf<TcpTlsStream>, LengthDelimitedCodec>,
-) -> (impl Stream<Item = CommMessage>, oneshot::Sender<()>) {
-    let messages = sock_in
+) -> (impl Stream<Item = BytesMut>, oneshot::Sender<()>) {
+    let frames = sock_in
         .map(|frame| {
             trace!(?frame, "recv_frame");
 
-            let frame = match frame {
-                Ok(inner) => inner,
+            match frame {
+                Ok(inner) => Some(inner),
                 Err(error) => {
                     warn!(?error, "Could not decode frame");
-                    return None;
-                }
-            };
-
-            match deserialize::<CommMessage>(&frame) {
-                Ok(message) => Some(message),
-                Err(error) => {
-                    warn!(?error, "Could not deserialize message; ignoring");
                     None
                 }
             }
@@ -1291,9 +1362,28 @@ fn get_messages_stream(
     use super::stream_cancel::StreamCancel;
     use futures::TryFutureExt;
     let (close_tx, close_rx) = oneshot::channel::<()>();
-    let cancellable_messages = messages.take_until(close_rx.unwrap_or_else(|_| ()));
+    let cancellable_frames = frames.take_until(close_rx.unwrap_or_else(|_| ()));
 
-    (cancellable_messages, close_tx)
+    (cancellable_frames, close_tx)
+}
+
+/// Transforms a stream of frames into a stream of deserialized messages.
+fn decode_messages(
+    frames: impl Stream<Item = BytesMut>,
+    format: CommsFormat,
+) -> impl Stream<Item = CommMessage> {
+    frames
+        .map(
+            move |frame| match format.deserialize::<CommMessage>(&frame) {
+                Ok(message) => Some(message),
+                Err(error) => {
+                    warn!(?error, "Could not deserialize message; ignoring");
+                    None
+                }
+            },
+        )
+        .take_while(|v| v.is_some())
+        .filter_map(|v| v)
 }
 
 fn take_join_handles<'a>(peers: impl Iterator<Item = &'a mut Peer>) -> Vec<JoinHandle<()>> {
@@ -1307,6 +1397,7 @@ fn take_join_handles<'a>(peers: impl Iterator<Item = &'a mut Peer>) -> Vec<JoinH
 mod test {
     use super::*;
     use crate::test_utils::get_common_tls_config;
+    use bincode::deserialize;
     use std::time::Duration;
 
     #[tokio::test(flavor = "current_thread")]
@@ -1350,11 +1441,11 @@ mod test {
         //
         // Assert
         //
-        if let Some(Event::NewFrame { peer: _, frame }) = n1.next_event().await {
+        if let Some(Event::NewFrame { frame, .. }) = n1.next_event().await {
             let recv_frame: &str = deserialize(&frame).unwrap();
             assert_eq!(recv_frame, "Hello1");
         }
-        if let Some(Event::NewFrame { peer: _, frame }) = n2.next_event().await {
+        if let Some(Event::NewFrame { frame, .. }) = n2.next_event().await {
             let recv_frame: &str = deserialize(&frame).unwrap();
             assert_eq!(recv_frame, "Hello2");
         }
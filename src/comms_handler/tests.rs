@@ -2,7 +2,7 @@
 
 use super::{CommsError, Event, Node, TcpTlsConfig};
 use crate::constants::NETWORK_VERSION;
-use crate::interfaces::NodeType;
+use crate::interfaces::{CommsFormat, ComputeRequest, NodeType};
 use crate::test_utils::{get_bound_common_tls_configs, get_common_tls_config, get_test_tls_spec};
 use crate::utils::tracing_log_try_init;
 use bincode::deserialize;
@@ -29,11 +29,11 @@ async fn direct_messages() {
     n2.send(n1.local_address(), "Hello1").await.unwrap();
     n1.send(n2.local_address(), "Hello2").await.unwrap();
 
-    if let Some(Event::NewFrame { peer: _, frame }) = n1.next_event().await {
+    if let Some(Event::NewFrame { frame, .. }) = n1.next_event().await {
         let recv_frame: &str = deserialize(&frame).unwrap();
         assert_eq!(recv_frame, "Hello1");
     }
-    if let Some(Event::NewFrame { peer: _, frame }) = n2.next_event().await {
+    if let Some(Event::NewFrame { frame, .. }) = n2.next_event().await {
         let recv_frame: &str = deserialize(&frame).unwrap();
         assert_eq!(recv_frame, "Hello2");
     }
@@ -41,6 +41,42 @@ async fn direct_messages() {
     complete_compute_nodes(nodes).await;
 }
 
+/// Check that 2 nodes negotiating JSON during the handshake exchange JSON messages
+/// in both direction.
+#[tokio::test(flavor = "current_thread")]
+async fn direct_messages_json_format() {
+    let _ = tracing_log_try_init();
+
+    let mut nodes = create_compute_nodes(2, 2).await;
+    let (n1, tail) = nodes.split_first_mut().unwrap();
+    let (n2, _) = tail.split_first_mut().unwrap();
+    let request = ComputeRequest::SendPartitionRequest {
+        mining_api_key: Some("key".to_owned()),
+    };
+
+    n2.set_comms_format(CommsFormat::Json).await;
+    n2.connect_to(n1.local_address()).await.unwrap();
+    n2.send(n1.local_address(), &request).await.unwrap();
+    n1.send(n2.local_address(), &request).await.unwrap();
+
+    let events = vec![n1.next_event().await, n2.next_event().await];
+    for event in events {
+        match event {
+            Some(Event::NewFrame { frame, format, .. }) => {
+                let recv_request: ComputeRequest = serde_json::from_slice(&frame).unwrap();
+                assert_eq!(format, CommsFormat::Json);
+                assert!(matches!(
+                    recv_request,
+                    ComputeRequest::SendPartitionRequest { mining_api_key: Some(key) } if key == "key"
+                ));
+            }
+            None => panic!("Channel disconnected"),
+        }
+    }
+
+    complete_compute_nodes(nodes).await;
+}
+
 /// Check that 2 prelaunch nodes can exchange arbitrary messages in both direction,
 /// using their public address after one node connected to the other.
 #[tokio::test(flavor = "current_thread")]
@@ -57,11 +93,11 @@ async fn direct_prelaunch_messages() {
     n2.send(n1.local_address(), "Hello1").await.unwrap();
     n1.send(n2.local_address(), "Hello2").await.unwrap();
 
-    if let Some(Event::NewFrame { peer: _, frame }) = n1.next_event().await {
+    if let Some(Event::NewFrame { frame, .. }) = n1.next_event().await {
         let recv_frame: &str = deserialize(&frame).unwrap();
         assert_eq!(recv_frame, "Hello1");
     }
-    if let Some(Event::NewFrame { peer: _, frame }) = n2.next_event().await {
+    if let Some(Event::NewFrame { frame, .. }) = n2.next_event().await {
         let recv_frame: &str = deserialize(&frame).unwrap();
         assert_eq!(recv_frame, "Hello2");
     }
@@ -105,7 +141,7 @@ async fn multicast() {
     // Verify that all other nodes have received the message
     for (i, node) in nodes.iter_mut().enumerate().skip(1) {
         match time::timeout(TIMEOUT_TEST_WAIT_DURATION, node.next_event()).await {
-            Ok(Some(Event::NewFrame { peer: _, frame })) => {
+            Ok(Some(Event::NewFrame { frame, .. })) => {
                 debug!(?i, "received");
 
                 let recv_frame: &str = deserialize(&frame).unwrap();
@@ -372,6 +408,58 @@ async fn nodes_incompatible() {
     complete_compute_nodes(nodes).await;
 }
 
+/// Check nodes only accept other network versions from their compatibility set,
+/// and that connections with mismatched versions are closed.
+#[tokio::test(flavor = "current_thread")]
+async fn nodes_compatible_network_versions() {
+    let _ = tracing_log_try_init();
+
+    //
+    // Arrange
+    //
+    let mut nodes = vec![
+        create_compute_node_version(4, NETWORK_VERSION).await,
+        create_compute_node_version(4, NETWORK_VERSION + 1).await,
+        create_compute_node_version(4, NETWORK_VERSION + 2).await,
+    ];
+    let (n1, tail) = nodes.split_first_mut().unwrap();
+    let (n2, tail) = tail.split_first_mut().unwrap();
+    let (n3, _) = tail.split_first_mut().unwrap();
+    n1.set_compatible_network_versions([NETWORK_VERSION + 1])
+        .await;
+    n2.set_compatible_network_versions([NETWORK_VERSION]).await;
+
+    //
+    // Act
+    //
+    let actual_c1_2 = n1.connect_to(n2.local_address()).await;
+    let actual_c1_3 = n1.connect_to(n3.local_address()).await;
+    let actual_s1_2 = n1.send(n2.local_address(), "Hello2").await;
+    let actual_s1_3 = n1.send(n3.local_address(), "Hello3").await;
+    let n1_peers = n1.get_peers().await;
+
+    //
+    // Assert
+    //
+    let actual = (actual_c1_2, actual_s1_2, actual_c1_3, actual_s1_3);
+    assert!(
+        matches!(
+            actual,
+            (
+                Ok(()),
+                Ok(()),
+                Err(CommsError::PeerNotFound(_)),
+                Err(CommsError::PeerNotFound(_))
+            )
+        ),
+        "{actual:?}"
+    );
+    assert!(n1_peers.contains_key(&n2.local_address()));
+    assert!(!n1_peers.contains_key(&n3.local_address()));
+
+    complete_compute_nodes(nodes).await;
+}
+
 /// Check nodes who cannot establish connections because of unexpected certificates.
 #[tokio::test(flavor = "current_thread")]
 async fn nodes_tls_mismatch() {
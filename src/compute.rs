@@ -1,32 +1,42 @@
+use crate::active_raft::ElectionTicks;
 use crate::block_pipeline::{MiningPipelineItem, MiningPipelineStatus, Participants};
 use crate::comms_handler::{CommsError, Event, TcpTlsConfig};
 use crate::compute_raft::{
-    CommittedItem, ComputeConsensusedRuntimeData, ComputeRaft, ComputeRuntimeItem,
-    CoordinatedCommand,
+    transaction_timeouts, CommittedItem, ComputeConsensusedRuntimeData, ComputeRaft,
+    ComputeRuntimeItem, CoordinatedCommand,
 };
 use crate::configurations::{
     ComputeNodeConfig, ComputeNodeSharedConfig, ExtraNodeParams, TlsPrivateInfo,
 };
-use crate::constants::{DB_PATH, RESEND_TRIGGER_MESSAGES_COMPUTE_LIMIT};
-use crate::db_utils::{self, SimpleDb, SimpleDbError, SimpleDbSpec};
+use crate::constants::{
+    DB_PATH, MINING_DIFFICULTY, NETWORK_VERSION_SERIALIZED, RESEND_TRIGGER_MESSAGES_COMPUTE_LIMIT,
+    SUPPORTED_TX_VERSIONS,
+};
+use crate::db_utils::{self, CustomDbSpec, DbMetrics, SimpleDb, SimpleDbError, SimpleDbSpec};
 use crate::interfaces::{
-    BlockStoredInfo, CommonBlockInfo, ComputeApi, ComputeApiRequest, ComputeInterface,
+    BlockStoredInfo, CommonBlockInfo, CommsFormat, ComputeApi, ComputeApiRequest, ComputeInterface,
     ComputeRequest, Contract, DruidDroplet, DruidPool, MineRequest, MinedBlock,
-    MinedBlockExtraInfo, NodeType, PowInfo, ProofOfWork, Response, StorageRequest, UserRequest,
-    UtxoFetchType, UtxoSet, WinningPoWInfo,
+    MinedBlockExtraInfo, MinerPayout, NodeType, PowInfo, ProofOfWork, Response, ResponseReason,
+    StorageRequest, UserRequest, UtxoFetchType, UtxoSet, WinningPoWInfo,
 };
 use crate::raft::RaftCommit;
+use crate::raft_util::RaftProposalMetricsInfo;
 use crate::threaded_call::{ThreadedCallChannel, ThreadedCallSender};
 use crate::tracked_utxo::TrackedUtxoSet;
+use crate::tx_policy::{AcceptAllPolicy, PolicyDecision, TxPolicy};
 use crate::utils::{
-    apply_mining_tx, check_druid_participants, create_receipt_asset_tx_from_sig,
-    format_parition_pow_address, generate_pow_random_num, to_api_keys, to_route_pow_infos,
-    validate_pow_block, validate_pow_for_address, ApiKeys, LocalEvent, LocalEventChannel,
-    LocalEventSender, ResponseResult, RoutesPoWInfo, StringError,
+    apply_mining_tx, cancel_druid_participation, check_druid_droplet_balanced,
+    check_druid_participants, create_receipt_asset_tx_from_sig, decode_pub_key,
+    format_parition_pow_address, generate_pow_random_num, has_dust_output,
+    mining_difficulty_from_bits, to_route_pow_infos, to_signed_api_keys, tx_fee,
+    tx_receipts_are_balanced, tx_signed_by_any, validate_pow_block, validate_pow_for_address,
+    ApiKeys, LocalEvent, LocalEventChannel, LocalEventSender, NodeReadiness, ResponseResult,
+    RoutesPoWInfo, StringError,
 };
 use crate::Node;
 use bincode::{deserialize, serialize};
 use bytes::Bytes;
+use naom::crypto::sign_ed25519::PublicKey;
 use naom::primitives::asset::TokenAmount;
 use naom::primitives::block::Block;
 use naom::primitives::transaction::{DrsTxHashSpec, Transaction};
@@ -36,12 +46,8 @@ use naom::utils::transaction_utils::construct_tx_hash;
 use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
-use std::{
-    error::Error,
-    fmt,
-    future::Future,
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{error::Error, fmt, future::Future, net::SocketAddr};
 use tokio::sync::RwLock;
 use tokio::task;
 use tracing::{debug, error, error_span, info, trace, warn};
@@ -52,6 +58,7 @@ pub const REQUEST_LIST_KEY: &str = "RequestListKey";
 pub const USER_NOTIFY_LIST_KEY: &str = "UserNotifyListKey";
 pub const POW_RANDOM_NUM_KEY: &str = "PowRandomNumKey";
 pub const POW_PREV_RANDOM_NUM_KEY: &str = "PowPreviousRandomNumKey";
+pub const MINED_BLOCK_KEY: &str = "MinedBlockKey";
 pub const RAFT_KEY_RUN: &str = "RaftKeyRun";
 
 /// Database columns
@@ -75,6 +82,8 @@ pub enum ComputeError {
     Serialization(bincode::Error),
     AsyncTask(task::JoinError),
     GenericError(StringError),
+    GenesisMismatch(String, String),
+    UnauthorizedReceiptMinter(String),
 }
 
 impl fmt::Display for ComputeError {
@@ -86,6 +95,12 @@ impl fmt::Display for ComputeError {
             Self::AsyncTask(err) => write!(f, "Async task error: {err}"),
             Self::Serialization(err) => write!(f, "Serialization error: {err}"),
             Self::GenericError(err) => write!(f, "Generic error: {err}"),
+            Self::GenesisMismatch(local, proposed) => {
+                write!(f, "Genesis mismatch: local {local}, proposed {proposed}")
+            }
+            Self::UnauthorizedReceiptMinter(pk) => {
+                write!(f, "Public key not authorized to create receipts: {pk}")
+            }
         }
     }
 }
@@ -99,10 +114,19 @@ impl Error for ComputeError {
             Self::AsyncTask(ref e) => Some(e),
             Self::Serialization(ref e) => Some(e),
             Self::GenericError(ref e) => Some(e),
+            Self::GenesisMismatch(_, _) => None,
+            Self::UnauthorizedReceiptMinter(_) => None,
         }
     }
 }
 
+impl ComputeError {
+    /// Whether the error is a corrupt database that `repair_db` could recover
+    pub fn is_corrupt_db(&self) -> bool {
+        matches!(self, Self::DbError(SimpleDbError::CorruptNeedsRepair(_)))
+    }
+}
+
 impl From<CommsError> for ComputeError {
     fn from(other: CommsError) -> Self {
         Self::Network(other)
@@ -151,19 +175,33 @@ pub struct ComputeNode {
     druid_pool: DruidPool,
     previous_random_num: Vec<u8>,
     current_random_num: Vec<u8>,
+    random_num_rotation: Option<Duration>,
+    random_num_rotate_at: Instant,
     current_trigger_messages_count: usize,
     enable_trigger_messages_pipeline_reset: bool,
     miners_changed: bool,
     partition_full_size: usize,
+    partition_pow_difficulty: usize,
     request_list: BTreeSet<SocketAddr>,
     request_list_first_flood: Option<usize>,
+    first_block_grace: Option<Duration>,
+    first_block_propose_at: Option<Instant>,
+    miner_missed_floods: BTreeMap<SocketAddr, usize>,
+    miner_max_missed_floods: Option<usize>,
+    block_stall_timeout: Option<Duration>,
+    last_block_committed_at: Instant,
     miner_removal_list: Arc<RwLock<BTreeSet<SocketAddr>>>,
     storage_addr: SocketAddr,
     sanction_list: Vec<String>,
+    tx_policy: Box<dyn TxPolicy>,
+    min_tx_fee: TokenAmount,
+    max_locktime_offset: Option<u64>,
+    receipt_minters: Option<Vec<PublicKey>>,
     user_notification_list: BTreeSet<SocketAddr>,
     coordinated_shutdown: u64,
     shutdown_group: BTreeSet<SocketAddr>,
     fetched_utxo_set: Option<(SocketAddr, NodeType, UtxoSet)>,
+    readiness: NodeReadiness,
     api_info: (
         SocketAddr,
         Option<TlsPrivateInfo>,
@@ -184,17 +222,48 @@ impl ComputeNode {
             .get(config.compute_node_idx)
             .ok_or(ComputeError::ConfigError("Invalid compute index"))?
             .address;
-        let storage_addr = config
-            .storage_nodes
-            .get(config.compute_node_idx)
-            .ok_or(ComputeError::ConfigError("Invalid storage index"))?
-            .address;
+        let storage_addr = match config.compute_to_storage_mapping.get(&addr) {
+            Some(storage_addr) => *storage_addr,
+            None => {
+                config
+                    .storage_nodes
+                    .get(config.compute_node_idx)
+                    .ok_or(ComputeError::ConfigError("Invalid storage index"))?
+                    .address
+            }
+        };
+        ElectionTicks::new(
+            config.compute_raft_election_min_ticks,
+            config.compute_raft_election_max_ticks,
+        )
+        .validate()
+        .map_err(ComputeError::ConfigError)?;
+        let (_, timeout_min, timeout_max) = transaction_timeouts(&config);
+        if timeout_min > timeout_max {
+            return Err(ComputeError::ConfigError(
+                "Transaction timeout minimum above maximum",
+            ));
+        }
         let tcp_tls_config = TcpTlsConfig::from_tls_spec(addr, &config.tls_config)?;
         let api_addr = SocketAddr::new(addr.ip(), config.compute_api_port);
         let api_tls_info = config
             .compute_api_use_tls
             .then(|| tcp_tls_config.clone_private_info());
 
+        let custom_db_spec = custom_db_spec(&config);
+        if config.backup_restore.unwrap_or(false) {
+            let spec = custom_db_spec.clone();
+            db_utils::restore_file_backup(config.compute_db_mode, &DB_SPEC, spec).unwrap();
+        }
+        let db = db_utils::new_db_with_version_and_tuning(
+            config.compute_db_mode,
+            &DB_SPEC,
+            Some(NETWORK_VERSION_SERIALIZED),
+            extra.db.take(),
+            custom_db_spec,
+            &config.compute_db_tuning.clone().unwrap_or_default(),
+        )?;
+
         let node = Node::new(
             &tcp_tls_config,
             config.peer_limit,
@@ -203,29 +272,50 @@ impl ComputeNode {
             true,
         )
         .await?;
-        let node_raft = ComputeRaft::new(&config, extra.raft_db.take()).await;
+        node.set_comms_format(config.comms_format).await;
+        node.set_compatible_network_versions(config.compatible_network_versions.iter().copied())
+            .await;
+        let node_raft = ComputeRaft::new(&config, extra.raft_db.take()).await?;
+        let threaded_calls = ThreadedCallChannel::with_config(
+            config.api_threaded_call_capacity,
+            config.api_threaded_call_timeout_ms,
+        )
+        .map_err(ComputeError::ConfigError)?;
 
-        if config.backup_restore.unwrap_or(false) {
-            db_utils::restore_file_backup(config.compute_db_mode, &DB_SPEC, None).unwrap();
-        }
-        let db = db_utils::new_db(config.compute_db_mode, &DB_SPEC, extra.db.take(), None);
-        let shutdown_group = {
+        let shutdown_group: BTreeSet<SocketAddr> = {
             let storage = std::iter::once(storage_addr);
             let raft_peers = node_raft.raft_peer_addrs().copied();
             raft_peers.chain(storage).collect()
         };
+        let readiness = NodeReadiness::new(shutdown_group.iter().copied().collect());
 
         let api_pow_info = to_route_pow_infos(config.routes_pow.clone());
-        let api_keys = to_api_keys(config.api_keys.clone());
+        let api_keys = to_signed_api_keys(config.api_keys.clone(), config.api_signing_keys.clone());
         let enable_trigger_messages_pipeline_reset = config
             .enable_trigger_messages_pipeline_reset
             .unwrap_or(false);
         let api_info = (api_addr, api_tls_info, api_keys, api_pow_info, node.clone());
 
+        let receipt_minters: Option<Vec<PublicKey>> = config
+            .compute_receipt_minters
+            .as_ref()
+            .map(|keys| keys.iter().map(|k| decode_pub_key(k)).collect())
+            .transpose()
+            .map_err(|_| ComputeError::ConfigError("Invalid receipt minter public key"))?;
+        let random_num_rotation = config
+            .compute_partition_random_num_rotation
+            .map(|t| Duration::from_millis(t as u64));
+
         let shared_config = ComputeNodeSharedConfig {
             compute_mining_event_timeout: config.compute_mining_event_timeout,
             compute_partition_full_size: config.compute_partition_full_size,
             compute_miner_whitelist: config.compute_miner_whitelist,
+            compute_max_block_size: config.compute_max_block_size,
+            compute_utxo_set_size_cap: config.compute_utxo_set_size_cap,
+            compute_dust_threshold: config.compute_dust_threshold,
+            compute_min_block_reward: config.compute_min_block_reward,
+            compute_reward_halving: config.compute_reward_halving,
+            compute_winning_miner_fairness: config.compute_winning_miner_fairness,
         };
 
         ComputeNode {
@@ -239,24 +329,44 @@ impl ComputeNode {
             pause_node: Default::default(),
             b_num_to_pause: Default::default(),
             disable_trigger_messages: Default::default(),
-            threaded_calls: Default::default(),
+            threaded_calls,
             current_mined_block: None,
             druid_pool: Default::default(),
             current_trigger_messages_count: Default::default(),
             enable_trigger_messages_pipeline_reset,
             previous_random_num: Default::default(),
             current_random_num: Default::default(),
+            random_num_rotation,
+            random_num_rotate_at: Instant::now() + random_num_rotation.unwrap_or_default(),
             miner_removal_list: Default::default(),
             miners_changed: false,
             request_list: Default::default(),
             sanction_list: config.sanction_list,
+            tx_policy: Box::new(AcceptAllPolicy),
+            min_tx_fee: config.compute_min_tx_fee,
+            max_locktime_offset: config.compute_max_locktime_offset,
+            receipt_minters,
             jurisdiction: config.jurisdiction,
             request_list_first_flood: Some(config.compute_minimum_miner_pool_len),
+            first_block_grace: config
+                .compute_first_block_grace_ms
+                .map(|t| Duration::from_millis(t as u64)),
+            first_block_propose_at: None,
+            miner_missed_floods: Default::default(),
+            miner_max_missed_floods: config.compute_miner_max_missed_floods,
+            block_stall_timeout: config
+                .compute_block_stall_timeout
+                .map(|t| Duration::from_millis(t as u64)),
+            last_block_committed_at: Instant::now(),
             partition_full_size: config.compute_partition_full_size,
+            partition_pow_difficulty: config
+                .compute_partition_pow_difficulty
+                .unwrap_or(MINING_DIFFICULTY),
             storage_addr,
             user_notification_list: Default::default(),
             coordinated_shutdown: u64::MAX,
             shutdown_group,
+            readiness,
             api_info,
             fetched_utxo_set: None,
         }
@@ -314,6 +424,11 @@ impl ComputeNode {
         self.node.public_address().await
     }
 
+    /// Returns the address of the storage node blocks are sent to.
+    pub fn storage_address(&self) -> SocketAddr {
+        self.storage_addr
+    }
+
     /// Get the node's mined block if any
     pub fn get_current_mined_block(&self) -> &Option<MinedBlock> {
         &self.current_mined_block
@@ -377,6 +492,16 @@ impl ComputeNode {
         self.node_raft.get_committed_utxo_tracked_set().clone()
     }
 
+    /// Index of the last committed raft entry and of the last snapshot generated
+    pub fn get_snapshot_indexes(&self) -> (u64, Option<u64>) {
+        self.node_raft.get_snapshot_indexes()
+    }
+
+    /// The random number partition entries are currently generated with
+    pub fn get_current_random_num(&self) -> &Vec<u8> {
+        &self.current_random_num
+    }
+
     /// The current tx_pool that will be used to generate next block
     pub fn get_committed_tx_pool(&self) -> &BTreeMap<String, Transaction> {
         self.node_raft.get_committed_tx_pool()
@@ -397,6 +522,48 @@ impl ComputeNode {
         &self.druid_pool
     }
 
+    /// Cancel the caller participation in a pending DRUID
+    ///
+    /// ### Arguments
+    ///
+    /// * `druid`      - DRUID id to cancel the participation in
+    /// * `tx_hash`    - Hash of the caller transaction in the droplet
+    /// * `expiry`     - UNIX timestamp in seconds after which the cancellation is rejected
+    /// * `public_key` - Hex encoded public key of the caller
+    /// * `signature`  - Hex encoded signature of the cancellation message by the caller
+    pub fn cancel_druid(
+        &mut self,
+        druid: &str,
+        tx_hash: &str,
+        expiry: u64,
+        public_key: &str,
+        signature: &str,
+    ) -> Response {
+        let executing = (self.node_raft.get_local_tx_druid_pool().iter())
+            .chain(self.node_raft.get_committed_tx_druid_pool())
+            .flat_map(|droplet| droplet.values())
+            .any(|tx| tx.druid_info.as_ref().map(|i| i.druid.as_str()) == Some(druid));
+        if executing {
+            return Response {
+                success: false,
+                reason: ResponseReason::DruidAlreadyExecuting,
+            };
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        cancel_druid_participation(
+            &mut self.druid_pool,
+            druid,
+            tx_hash,
+            expiry,
+            public_key,
+            signature,
+            now,
+        )
+    }
+
     /// Get request list
     pub fn get_request_list(&self) -> &BTreeSet<SocketAddr> {
         &self.request_list
@@ -457,6 +624,18 @@ impl ComputeNode {
         }
     }
 
+    /// Repair the database of a node that failed to start with `CorruptNeedsRepair`
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - ComputeNodeConfig the node failed to start with
+    pub fn repair_db(config: &ComputeNodeConfig) -> Result<()> {
+        let custom_db_spec = custom_db_spec(config);
+        db_utils::repair_db(config.compute_db_mode, &DB_SPEC, custom_db_spec)?;
+        ComputeRaft::repair_db(config)?;
+        Ok(())
+    }
+
     /// Info needed to run the API point.
     pub fn api_inputs(
         &self,
@@ -466,8 +645,20 @@ impl ComputeNode {
         ApiKeys,
         RoutesPoWInfo,
         Node,
+        NodeReadiness,
     ) {
-        self.api_info.clone()
+        let (api_addr, api_tls, api_keys, api_pow_info, node) = self.api_info.clone();
+        let readiness = self.readiness.clone();
+        (api_addr, api_tls, api_keys, api_pow_info, node, readiness)
+    }
+
+    /// Register the operator policy consulted before accepting transactions
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx_policy` - Policy replacing the current one
+    pub fn set_tx_policy(&mut self, tx_policy: Box<dyn TxPolicy>) {
+        self.tx_policy = tx_policy;
     }
 
     /// Validate and get DDE transactions that are ready to be added to the RAFT
@@ -495,7 +686,14 @@ impl ComputeNode {
                 // Execute the DDE tx if it's ready
                 if droplet.txs.len() == droplet.participants {
                     let valid = druid_expectations_are_met(&druid, droplet.txs.values())
-                        && check_druid_participants(droplet);
+                        && check_druid_participants(droplet)
+                        && match check_druid_droplet_balanced(droplet) {
+                            Ok(()) => true,
+                            Err(reason) => {
+                                warn!("Dropping unbalanced DRUID droplet {}: {}", druid, reason);
+                                false
+                            }
+                        };
                     ready_txs.push((valid, droplet.txs.clone()));
                     // TODO: Implement time-based removal?
                     self.druid_pool.remove(&druid);
@@ -536,17 +734,6 @@ impl ComputeNode {
         self.mining_block_mined();
     }
 
-    /// Gets a decremented socket address of peer for storage
-    /// ### Arguments
-    /// * `address`    - Address to decrement
-    fn get_storage_address(&self, address: SocketAddr) -> SocketAddr {
-        let mut storage_address = address;
-        storage_address.set_ip(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)));
-        storage_address.set_port(address.port() - 1);
-
-        storage_address
-    }
-
     /// Return closure use to validate a transaction
     fn transactions_validator(&self) -> impl Fn(&Transaction) -> bool + '_ {
         let utxo_set = self.node_raft.get_committed_utxo_set();
@@ -555,13 +742,20 @@ impl ComputeNode {
             .get_committed_current_block_num()
             .unwrap_or_default();
         let sanction_list = &self.sanction_list;
+        let receipt_minters = &self.receipt_minters;
 
         move |tx| {
+            if !SUPPORTED_TX_VERSIONS.contains(&tx.version) {
+                return false;
+            }
+
             if tx.is_create_tx() {
                 return tx_has_valid_create_script(
                     &tx.inputs[0].script_signature,
                     &tx.outputs[0].value,
-                );
+                ) && receipt_minters
+                    .as_ref()
+                    .map_or(true, |minters| tx_signed_by_any(tx, minters));
             }
 
             !tx.is_coinbase()
@@ -571,7 +765,24 @@ impl ComputeNode {
                         .filter(|_| !sanction_list.contains(&v.t_hash))
                         .filter(|tx_out| lock_expired >= tx_out.locktime)
                 })
+                && tx_receipts_are_balanced(tx, utxo_set)
+        }
+    }
+
+    /// Whether the mined block is waiting to be acknowledged by storage
+    pub fn is_block_pending_storage(&self) -> bool {
+        self.current_mined_block.is_some()
+    }
+
+    /// Re-sends the mined block to storage if it has not been acknowledged yet
+    ///
+    /// Returns whether a block was sent
+    pub async fn resend_pending_block_to_storage(&mut self) -> Result<bool> {
+        if !self.is_block_pending_storage() {
+            return Ok(false);
         }
+        self.send_block_to_storage().await?;
+        Ok(true)
     }
 
     /// Sends the latest block to storage
@@ -638,7 +849,7 @@ impl ComputeNode {
         match response {
             Ok(Response {
                 success: true,
-                reason: "Received UTXO fetch request",
+                reason: ResponseReason::ReceivedUtxoFetchRequest,
             }) => {
                 if let Err(e) = self.send_fetched_utxo_set().await {
                     error!("Requested UTXO set not sent {:?}", e);
@@ -646,86 +857,100 @@ impl ComputeNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Received coordinated pause request",
+                reason: ResponseReason::ReceivedCoordinatedPauseRequest,
             }) => {
                 debug!("Received coordinated pause request");
             }
             Ok(Response {
                 success: true,
-                reason: "Node pause configuration set",
+                reason: ResponseReason::NodePauseConfigurationSet,
             }) => {
                 debug!("Node pause configuration set");
             }
             Ok(Response {
                 success: true,
-                reason: "Received coordinated resume request",
+                reason: ResponseReason::ReceivedCoordinatedResumeRequest,
             }) => {
                 debug!("Received coordinated resume request");
             }
             Ok(Response {
                 success: true,
-                reason: "Node resumed",
+                reason: ResponseReason::NodeResumed,
             }) => {
                 warn!("NODE RESUMED");
             }
             Ok(Response {
                 success: true,
-                reason: "Received shared config",
+                reason: ResponseReason::ReceivedSharedConfig,
             }) => {
                 debug!("Shared config received");
             }
             Ok(Response {
                 success: true,
-                reason: "Shared config applied",
+                reason: ResponseReason::SharedConfigApplied,
             }) => {
                 debug!("Shared config applied");
             }
             Ok(Response {
                 success: false,
-                reason: "No shared config to apply",
+                reason: ResponseReason::NoSharedConfigToApply,
             }) => {
                 warn!("No shared config to apply");
             }
             Ok(Response {
                 success: true,
-                reason: "Miner removal request received",
+                reason: ResponseReason::MinerRemovalRequestReceived,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Shutdown",
+                reason: ResponseReason::Shutdown,
             }) => {
                 warn!("Shutdown now");
                 return ResponseResult::Exit;
             }
             Ok(Response {
                 success: true,
-                reason: "Shutdown pending",
+                reason: ResponseReason::ShutdownPending,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Start coordinated shutdown",
+                reason: ResponseReason::StartCoordinatedShutdown,
             }) => {}
+            Ok(Response {
+                success: false,
+                reason: ResponseReason::CoordinatedShutdownBlockPassed,
+            }) => {}
+            Ok(Response {
+                success: true,
+                reason: ResponseReason::CancelCoordinatedShutdown,
+            }) => {}
+            Ok(Response {
+                success: true,
+                reason: ResponseReason::SnapshotForced,
+            }) => {
+                warn!("Snapshot forced");
+            }
             Ok(Response {
                 success: true,
-                reason: "Received partition request successfully",
+                reason: ResponseReason::ReceivedPartitionRequestSuccessfully,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Received first full partition request",
+                reason: ResponseReason::ReceivedFirstFullPartitionRequest,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Removing unauthorized miner",
+                reason: ResponseReason::RemovingUnauthorizedMiner,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Received PoW successfully",
+                reason: ResponseReason::ReceivedPowSuccessfully,
             }) => {
                 debug!("Proposing winning PoW entry");
             }
             Ok(Response {
                 success: true,
-                reason: "Winning PoW intake open",
+                reason: ResponseReason::WinningPowIntakeOpen,
             }) => {
                 debug!(
                     "Block and participants ready to mine: {:?}",
@@ -735,17 +960,17 @@ impl ComputeNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Pipeline halted",
+                reason: ResponseReason::PipelineHalted,
             }) => {
                 info!("Send Block to storage");
                 debug!("CURRENT MINED BLOCK: {:?}", self.current_mined_block);
                 if let Err(e) = self.send_block_to_storage().await {
-                    error!("Block not sent to storage {:?}", e);
+                    error!("Block not sent to storage, pending until retried {:?}", e);
                 }
             }
             Ok(Response {
                 success: true,
-                reason: "Pipeline reset",
+                reason: ResponseReason::PipelineReset,
             }) => {
                 warn!(
                     "Pipeline reset to :{:?}",
@@ -754,15 +979,15 @@ impl ComputeNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Sent runtime data to peer",
+                reason: ResponseReason::SentRuntimeDataToPeer,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Failed to send runtime data to peer",
+                reason: ResponseReason::FailedToSendRuntimeDataToPeer,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Received runtime data from peer",
+                reason: ResponseReason::ReceivedRuntimeDataFromPeer,
             }) => {
                 debug!("Received runtime data from peer");
                 if let Some(runtime_data) = self.received_runtime_data.take() {
@@ -776,17 +1001,17 @@ impl ComputeNode {
             }
             Ok(Response {
                 success: false,
-                reason: "Received runtime data from unknown peer",
+                reason: ResponseReason::ReceivedRuntimeDataFromUnknownPeer,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Transactions added to tx pool",
+                reason: ResponseReason::TransactionsAddedToTxPool,
             }) => {
                 debug!("Transactions received and processed successfully");
             }
             Ok(Response {
                 success: true,
-                reason: "First Block committed",
+                reason: ResponseReason::FirstBlockCommitted,
             }) => {
                 // Only continue with the mining process if the node is not paused
                 if !self.is_paused().await {
@@ -801,7 +1026,7 @@ impl ComputeNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Block committed",
+                reason: ResponseReason::BlockCommitted,
             }) => {
                 // Only continue with the mining process if the node is not paused
                 if !self.is_paused().await {
@@ -816,56 +1041,60 @@ impl ComputeNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Block shutdown",
+                reason: ResponseReason::BlockShutdown,
             }) => {
                 debug!("Block shutdown (not ready to mine)");
                 self.flood_closing_events().await.unwrap();
             }
             Ok(Response {
                 success: true,
-                reason: "Transactions committed",
+                reason: ResponseReason::TransactionsCommitted,
             }) => {
                 debug!("Transactions ready to be used in next block");
             }
             Ok(Response {
                 success: true,
-                reason: "Received block stored",
+                reason: ResponseReason::ReceivedBlockStored,
             }) => {
                 info!("Block info received from storage: ready to generate block");
             }
             Ok(Response {
                 success: true,
-                reason: "Snapshot applied",
+                reason: ResponseReason::SnapshotApplied,
             }) => {
                 warn!("Snapshot applied");
             }
             Ok(Response {
                 success: true,
-                reason: "Received block notification",
+                reason: ResponseReason::ReceivedBlockNotification,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Partition PoW received successfully",
+                reason: ResponseReason::PartitionPowReceivedSuccessfully,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Sent startup requests on reconnection",
+                reason: ResponseReason::SentStartupRequestsOnReconnection,
             }) => debug!("Sent startup requests on reconnection"),
+            Ok(Response {
+                success: true,
+                reason: ResponseReason::ResentBlockToStorageOnReconnection,
+            }) => info!("Resent pending block to storage on reconnection"),
             Ok(Response {
                 success: false,
-                reason: "Failed to send startup requests on reconnection",
+                reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
             }) => error!("Failed to send startup requests on reconnection"),
             Ok(Response {
                 success: false,
-                reason: "Partition list complete",
+                reason: ResponseReason::PartitionListComplete,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "PoW received is invalid",
+                reason: ResponseReason::PowReceivedIsInvalid,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Not block currently mined",
+                reason: ResponseReason::NotBlockCurrentlyMined,
             }) => {}
             Ok(Response {
                 success: true,
@@ -879,6 +1108,12 @@ impl ComputeNode {
             }) => {
                 error!("WARNING: UNHANDLED RESPONSE TYPE FAILURE: {:?}", reason);
             }
+            Err(ComputeError::GenesisMismatch(local, proposed)) => {
+                error!(
+                    "Peers started from a different genesis: local {local}, proposed {proposed}"
+                );
+                return ResponseResult::Exit;
+            }
             Err(error) => {
                 error!("ERROR HANDLING RESPONSE: {:?}", error);
             }
@@ -896,6 +1131,7 @@ impl ComputeNode {
         loop {
             let ready = !self.node_raft.need_initial_state();
             let shutdown = self.node_raft.is_shutdown_commit_processed();
+            self.readiness.set_initial_state_applied(ready);
 
             // State machines are not keept between iterations or calls.
             // All selection calls (between = and =>), need to be dropable
@@ -927,6 +1163,21 @@ impl ComputeNode {
                     self.node_raft.propose_local_transactions_at_timeout().await;
                     self.node_raft.propose_local_druid_transactions().await;
                 }
+                _ = self.timeout_first_block_grace(), if ready && self.first_block_propose_at.is_some() => {
+                    trace!("handle_next_event timeout first block grace");
+                    self.first_block_propose_at = None;
+                    self.node_raft.propose_initial_item().await;
+                }
+                _ = self.timeout_rotate_random_num(), if ready && !shutdown && self.random_num_rotation.is_some() => {
+                    trace!("handle_next_event timeout random number rotation");
+                    if let Err(e) = self.rotate_partition_random_num().await {
+                        error!("Partition random number rotation failed {:?}", e);
+                    }
+                }
+                _ = self.timeout_block_stall(), if ready && !shutdown && self.block_stall_timeout.is_some() && !self.is_block_production_stalled() => {
+                    trace!("handle_next_event timeout block stall");
+                    self.check_block_production_stall(Instant::now());
+                }
                 _ = self.node_raft.timeout_propose_mining_event(), if ready && !shutdown => {
                     trace!("handle_next_event timeout mining pipeline");
                     if !self.node_raft.propose_mining_event_at_timeout().await
@@ -948,7 +1199,7 @@ impl ComputeNode {
                 }
                 reason = &mut *exit => return Some(Ok(Response {
                     success: true,
-                    reason,
+                    reason: ResponseReason::Exit(reason),
                 }))
             }
         }
@@ -962,43 +1213,49 @@ impl ComputeNode {
     async fn handle_committed_data(&mut self, commit_data: RaftCommit) -> Option<Result<Response>> {
         match self.node_raft.received_commit(commit_data).await {
             Some(CommittedItem::FirstBlock) => {
+                self.record_block_committed();
                 self.reset_mining_block_process().await;
                 self.backup_persistent_dbs().await;
                 Some(Ok(Response {
                     success: true,
-                    reason: "First Block committed",
+                    reason: ResponseReason::FirstBlockCommitted,
                 }))
             }
+            Some(CommittedItem::GenesisMismatch { local, proposed }) => {
+                Some(Err(ComputeError::GenesisMismatch(local, proposed)))
+            }
             Some(CommittedItem::Block) => {
+                self.record_block_committed();
                 self.reset_mining_block_process().await;
                 self.backup_persistent_dbs().await;
                 Some(Ok(Response {
                     success: true,
-                    reason: "Block committed",
+                    reason: ResponseReason::BlockCommitted,
                 }))
             }
             Some(CommittedItem::BlockShutdown) => {
+                self.record_block_committed();
                 self.reset_mining_block_process().await;
                 self.backup_persistent_dbs().await;
                 Some(Ok(Response {
                     success: true,
-                    reason: "Block shutdown",
+                    reason: ResponseReason::BlockShutdown,
                 }))
             }
             Some(CommittedItem::StartPhasePowIntake) => Some(Ok(Response {
                 success: true,
-                reason: "Winning PoW intake open",
+                reason: ResponseReason::WinningPowIntakeOpen,
             })),
             Some(CommittedItem::StartPhaseHalted) => {
                 self.mining_block_mined();
                 Some(Ok(Response {
                     success: true,
-                    reason: "Pipeline halted",
+                    reason: ResponseReason::PipelineHalted,
                 }))
             }
             Some(CommittedItem::ResetPipeline) => Some(Ok(Response {
                 success: true,
-                reason: "Pipeline reset",
+                reason: ResponseReason::PipelineReset,
             })),
             Some(CommittedItem::Transactions) => {
                 delete_local_transactions(
@@ -1007,7 +1264,7 @@ impl ComputeNode {
                 );
                 Some(Ok(Response {
                     success: true,
-                    reason: "Transactions committed",
+                    reason: ResponseReason::TransactionsCommitted,
                 }))
             }
             Some(CommittedItem::Snapshot) => {
@@ -1017,7 +1274,7 @@ impl ComputeNode {
 
                 Some(Ok(Response {
                     success: true,
-                    reason: "Snapshot applied",
+                    reason: ResponseReason::SnapshotApplied,
                 }))
             }
             Some(CommittedItem::CoordinatedCmd(cmd)) => self.handle_coordinated_cmd(cmd).await,
@@ -1041,19 +1298,54 @@ impl ComputeNode {
                     error!("Failed to send startup requests on reconnect: {}", err);
                     return Some(Response {
                         success: false,
-                        reason: "Failed to send startup requests on reconnection",
+                        reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
                     });
                 }
+                match self.resend_pending_block_to_storage().await {
+                    Ok(true) => Some(Response {
+                        success: true,
+                        reason: ResponseReason::ResentBlockToStorageOnReconnection,
+                    }),
+                    Ok(false) => Some(Response {
+                        success: true,
+                        reason: ResponseReason::SentStartupRequestsOnReconnection,
+                    }),
+                    Err(e) => {
+                        error!("Failed to resend block to storage on reconnect: {}", e);
+                        Some(Response {
+                            success: true,
+                            reason: ResponseReason::SentStartupRequestsOnReconnection,
+                        })
+                    }
+                }
+            }
+            LocalEvent::CoordinatedShutdown(shutdown) => {
+                let current_b_num = self.node_raft.get_current_block_num();
+                if shutdown < current_b_num {
+                    warn!("Shutdown block {shutdown} is before current block {current_b_num}");
+                    return Some(Response {
+                        success: false,
+                        reason: ResponseReason::CoordinatedShutdownBlockPassed,
+                    });
+                }
+                self.coordinated_shutdown = shutdown;
                 Some(Response {
                     success: true,
-                    reason: "Sent startup requests on reconnection",
+                    reason: ResponseReason::StartCoordinatedShutdown,
                 })
             }
-            LocalEvent::CoordinatedShutdown(shutdown) => {
-                self.coordinated_shutdown = shutdown;
+            LocalEvent::CancelShutdown => {
+                self.coordinated_shutdown = u64::MAX;
                 Some(Response {
                     success: true,
-                    reason: "Start coordinated shutdown",
+                    reason: ResponseReason::CancelCoordinatedShutdown,
+                })
+            }
+            LocalEvent::ForceSnapshot => {
+                self.node_raft.generate_snapshot();
+                Some(Response {
+                    success: true,
+                    reason: ResponseReason::SnapshotForced,
                 })
             }
             LocalEvent::Ignore => None,
@@ -1067,9 +1359,13 @@ impl ComputeNode {
     /// * `event` - Event holding the frame to be handled
     async fn handle_event(&mut self, event: Event) -> Result<Option<Response>> {
         match event {
-            Event::NewFrame { peer, frame } => {
+            Event::NewFrame {
+                peer,
+                frame,
+                format,
+            } => {
                 let peer_span = error_span!("peer", ?peer);
-                self.handle_new_frame(peer, frame)
+                self.handle_new_frame(peer, frame, format)
                     .instrument(peer_span)
                     .await
             }
@@ -1081,15 +1377,19 @@ impl ComputeNode {
     ///
     /// * `peer` - Sending peer's socket address
     /// * 'frame' - Bytes representing the new frame.
+    /// * 'format' - Serialization format of the frame.
     async fn handle_new_frame(
         &mut self,
         peer: SocketAddr,
         frame: Bytes,
+        format: CommsFormat,
     ) -> Result<Option<Response>> {
-        let req = deserialize::<ComputeRequest>(&frame).map_err(|error| {
-            warn!(?error, "frame-deserialize");
-            error
-        })?;
+        let req = format
+            .deserialize::<ComputeRequest>(&frame)
+            .map_err(|error| {
+                warn!(?error, "frame-deserialize");
+                error
+            })?;
 
         let req_span = error_span!("request", ?req);
         let response = self.handle_request(peer, req).instrument(req_span).await;
@@ -1107,8 +1407,14 @@ impl ComputeNode {
         use ComputeRequest::*;
         trace!("handle_request");
 
+        // Any message from a miner shows it is still alive
+        self.miner_missed_floods.remove(&peer);
+
         match req {
-            ComputeApi(req) => self.handle_api_request(peer, req).await,
+            ComputeApi {
+                request,
+                correlation_id,
+            } => self.handle_api_request(peer, request, &correlation_id),
             SendUtxoRequest {
                 address_list,
                 requester_node_type,
@@ -1164,14 +1470,14 @@ impl ComputeNode {
         {
             return Some(Response {
                 success: false,
-                reason: "Received runtime data from unknown peer",
+                reason: ResponseReason::ReceivedRuntimeDataFromUnknownPeer,
             });
         }
 
         self.received_runtime_data = Some(runtime_data);
         Some(Response {
             success: true,
-            reason: "Received runtime data from peer",
+            reason: ResponseReason::ReceivedRuntimeDataFromPeer,
         })
     }
 
@@ -1183,13 +1489,13 @@ impl ComputeNode {
             error!("Failed to send runtime data to peer: {}", e);
             return Some(Response {
                 success: false,
-                reason: "Failed to send runtime data to peer",
+                reason: ResponseReason::FailedToSendRuntimeDataToPeer,
             });
         }
 
         Some(Response {
             success: true,
-            reason: "Sent runtime data to peer",
+            reason: ResponseReason::SentRuntimeDataToPeer,
         })
     }
 
@@ -1201,7 +1507,7 @@ impl ComputeNode {
         self.miner_removal_list.write().await.insert(peer);
         Some(Response {
             success: true,
-            reason: "Miner removal request received",
+            reason: ResponseReason::MinerRemovalRequestReceived,
         })
     }
 
@@ -1220,7 +1526,7 @@ impl ComputeNode {
                 warn!("Pausing node at b_num: {b_num}");
                 Some(Ok(Response {
                     success: true,
-                    reason: "Node pause configuration set",
+                    reason: ResponseReason::NodePauseConfigurationSet,
                 }))
             }
             CoordinatedCommand::ResumeNodes => {
@@ -1232,7 +1538,7 @@ impl ComputeNode {
                 *self.disable_trigger_messages.write().await = false;
                 Some(Ok(Response {
                     success: true,
-                    reason: "Node resumed",
+                    reason: ResponseReason::NodeResumed,
                 }))
             }
             CoordinatedCommand::ApplySharedConfig => {
@@ -1240,12 +1546,12 @@ impl ComputeNode {
                     self.apply_shared_config(received_shared_config).await;
                     return Some(Ok(Response {
                         success: true,
-                        reason: "Shared config applied",
+                        reason: ResponseReason::SharedConfigApplied,
                     }));
                 }
                 Some(Ok(Response {
                     success: false,
-                    reason: "No shared config to apply",
+                    reason: ResponseReason::NoSharedConfigToApply,
                 }))
             }
         }
@@ -1255,46 +1561,26 @@ impl ComputeNode {
     ///
     /// ### Arguments
     ///
-    /// * `peer` - Peer sending the request.
-    /// * `req`  - Request to execute
-    async fn handle_api_request(
+    /// * `peer`           - Peer sending the request.
+    /// * `req`            - Request to execute
+    /// * `correlation_id` - Correlation ID of the API call
+    fn handle_api_request(
         &mut self,
         peer: SocketAddr,
         req: ComputeApiRequest,
+        correlation_id: &str,
     ) -> Option<Response> {
-        use ComputeApiRequest::*;
-
         if peer != self.local_address() {
             // Do not process if not internal request
             return None;
         }
 
-        match req {
-            SendCreateReceiptRequest {
-                receipt_amount,
-                script_public_key,
-                public_key,
-                signature,
-                drs_tx_hash_spec,
-                metadata,
-            } => match self.create_receipt_asset_tx(
-                receipt_amount,
-                script_public_key,
-                public_key,
-                signature,
-                drs_tx_hash_spec,
-                metadata,
-            ) {
-                Ok((tx, _)) => Some(self.receive_transactions(vec![tx])),
-                Err(e) => {
-                    error!("Error creating receipt asset transaction: {:?}", e);
-                    None
-                }
-            },
-            SendTransactions { transactions } => Some(self.receive_transactions(transactions)),
-            PauseNodes { b_num } => Some(self.pause_nodes(b_num)),
-            ResumeNodes => Some(self.resume_nodes()),
-            SendSharedConfig { shared_config } => Some(self.send_shared_config(shared_config)),
+        match self.receive_api_request(req, correlation_id) {
+            Ok(response) => Some(response),
+            Err(e) => {
+                error!("Error creating receipt asset transaction: {:?}", e);
+                None
+            }
         }
     }
 
@@ -1311,13 +1597,13 @@ impl ComputeNode {
         if !self.shutdown_group.is_empty() {
             return Some(Response {
                 success: true,
-                reason: "Shutdown pending",
+                reason: ResponseReason::ShutdownPending,
             });
         }
 
         Some(Response {
             success: true,
-            reason: "Shutdown",
+            reason: ResponseReason::Shutdown,
         })
     }
 
@@ -1337,6 +1623,12 @@ impl ComputeNode {
             compute_mining_event_timeout,
             compute_partition_full_size,
             compute_miner_whitelist,
+            compute_max_block_size,
+            compute_utxo_set_size_cap,
+            compute_dust_threshold,
+            compute_min_block_reward,
+            compute_reward_halving,
+            compute_winning_miner_fairness,
         } = received_shared_config.clone();
 
         self.node_raft
@@ -1350,6 +1642,15 @@ impl ComputeNode {
         );
         self.node_raft
             .update_compute_miner_whitelist_addresses(compute_miner_whitelist.miner_addresses);
+        self.node_raft.update_max_block_size(compute_max_block_size);
+        self.node_raft
+            .update_utxo_set_size_cap(compute_utxo_set_size_cap);
+        self.node_raft.update_dust_threshold(compute_dust_threshold);
+        self.node_raft
+            .update_min_block_reward(compute_min_block_reward);
+        self.node_raft.update_reward_halving(compute_reward_halving);
+        self.node_raft
+            .update_winning_miner_fairness(compute_winning_miner_fairness);
 
         if let Some(unauthorized) = self.flush_unauthorized_miners().await {
             self.node_raft
@@ -1383,7 +1684,7 @@ impl ComputeNode {
         }
         Some(Response {
             success: true,
-            reason: "Received coordinated pause request",
+            reason: ResponseReason::ReceivedCoordinatedPauseRequest,
         })
     }
 
@@ -1401,7 +1702,7 @@ impl ComputeNode {
         }
         Some(Response {
             success: true,
-            reason: "Received coordinated resume request",
+            reason: ResponseReason::ReceivedCoordinatedResumeRequest,
         })
     }
 
@@ -1429,7 +1730,7 @@ impl ComputeNode {
         self.received_shared_config = Some(shared_config);
         Some(Response {
             success: true,
-            reason: "Received shared config",
+            reason: ResponseReason::ReceivedSharedConfig,
         })
     }
 
@@ -1449,7 +1750,7 @@ impl ComputeNode {
 
         Response {
             success: true,
-            reason: "Received block notification",
+            reason: ResponseReason::ReceivedBlockNotification,
         }
     }
 
@@ -1510,7 +1811,7 @@ impl ComputeNode {
             }
             return Response {
                 success: true,
-                reason: "Removing unauthorized miner",
+                reason: ResponseReason::RemovingUnauthorizedMiner,
             };
         }
 
@@ -1531,15 +1832,23 @@ impl ComputeNode {
             .unwrap();
         if self.request_list_first_flood == Some(self.request_list.len()) {
             self.request_list_first_flood = None;
-            self.node_raft.propose_initial_item().await;
+            if let Some(grace) = self.first_block_grace {
+                debug!(
+                    "Minimum miner pool reached, first block proposed in {:?}",
+                    grace
+                );
+                self.first_block_propose_at = Some(Instant::now() + grace);
+            } else {
+                self.node_raft.propose_initial_item().await;
+            }
             Response {
                 success: true,
-                reason: "Received first full partition request",
+                reason: ResponseReason::ReceivedFirstFullPartitionRequest,
             }
         } else {
             Response {
                 success: true,
-                reason: "Received partition request successfully",
+                reason: ResponseReason::ReceivedPartitionRequestSuccessfully,
             }
         }
     }
@@ -1567,19 +1876,23 @@ impl ComputeNode {
             (MiningPipelineStatus::Halted, _) => {
                 return Some(Response {
                     success: false,
-                    reason: "Partition list complete",
+                    reason: ResponseReason::PartitionListComplete,
                 });
             }
             _ => return None,
         };
 
         let valid_pow = format_parition_pow_address(peer) == partition_entry.address
-            && validate_pow_for_address(&partition_entry, &Some(random_number));
+            && validate_pow_for_address(
+                &partition_entry,
+                &Some(random_number),
+                self.partition_pow_difficulty,
+            );
 
         if !valid_pow {
             return Some(Response {
                 success: false,
-                reason: "PoW received is invalid",
+                reason: ResponseReason::PowReceivedIsInvalid,
             });
         }
 
@@ -1593,7 +1906,7 @@ impl ComputeNode {
 
         Some(Response {
             success: true,
-            reason: "Partition PoW received successfully",
+            reason: ResponseReason::PartitionPowReceivedSuccessfully,
         })
     }
 
@@ -1663,14 +1976,23 @@ impl ComputeNode {
         };
 
         let miner_removal_list = self.miner_removal_list.read().await.clone();
+        let unresponsive_miners = self.take_unresponsive_miners();
         let all_participants = self.node_raft.get_mining_participants().clone();
-        let participants = all_participants
+        let participants: Vec<_> = all_participants
             .iter()
             .filter(|participant| !miner_removal_list.contains(participant))
-            .copied();
+            .filter(|participant| !unresponsive_miners.contains(participant))
+            .copied()
+            .collect();
         let request_list = self.request_list.clone();
-        let non_participants = request_list.difference(all_participants.lookup()).copied();
+        let non_participants: Vec<_> = request_list
+            .difference(all_participants.lookup())
+            .filter(|miner| !unresponsive_miners.contains(miner))
+            .copied()
+            .collect();
+        self.record_miners_flooded(participants.iter().chain(&non_participants));
         let mut unsent_miners = self.flush_unauthorized_miners().await.unwrap_or_default();
+        unsent_miners.extend(unresponsive_miners);
 
         let _ = self
             .node
@@ -1683,10 +2005,11 @@ impl ComputeNode {
         if let Ok(unsent_nodes) = self
             .node
             .send_to_all(
-                participants,
+                participants.into_iter(),
                 MineRequest::SendBlock {
                     pow_info,
                     rnum: rnum.clone(),
+                    partition_difficulty: self.partition_pow_difficulty,
                     win_coinbases: win_coinbases.clone(),
                     block: Some(header.clone()),
                     reward,
@@ -1701,10 +2024,11 @@ impl ComputeNode {
         if let Ok(unsent_nodes) = self
             .node
             .send_to_all(
-                non_participants,
+                non_participants.into_iter(),
                 MineRequest::SendBlock {
                     pow_info,
                     rnum,
+                    partition_difficulty: self.partition_pow_difficulty,
                     win_coinbases,
                     block: None,
                     reward,
@@ -1736,6 +2060,38 @@ impl ComputeNode {
         Ok(())
     }
 
+    /// Count one more unanswered flood for each of the given miners
+    fn record_miners_flooded<'a>(&mut self, miners: impl Iterator<Item = &'a SocketAddr>) {
+        if self.miner_max_missed_floods.is_none() {
+            return;
+        }
+
+        for miner in miners {
+            *self.miner_missed_floods.entry(*miner).or_default() += 1;
+        }
+    }
+
+    /// Take the miners that did not answer the configured number of consecutive floods
+    fn take_unresponsive_miners(&mut self) -> Vec<SocketAddr> {
+        let max_missed = match self.miner_max_missed_floods {
+            Some(max_missed) => max_missed,
+            None => return Vec::new(),
+        };
+
+        let unresponsive: Vec<SocketAddr> = self
+            .miner_missed_floods
+            .iter()
+            .filter(|(_, missed)| **missed >= max_missed)
+            .map(|(miner, _)| *miner)
+            .collect();
+
+        for miner in &unresponsive {
+            warn!("Evicting miner {miner:?} unresponsive to {max_missed} floods");
+            self.miner_missed_floods.remove(miner);
+        }
+        unresponsive
+    }
+
     /// If whitelisting is active, this function will remove all miners that are not whitelisted
     pub async fn flush_unauthorized_miners(&mut self) -> Option<Vec<SocketAddr>> {
         // Determine if whitelisting is active
@@ -1775,6 +2131,8 @@ impl ComputeNode {
         // Flush stale miners
         self.request_list
             .retain(|addr| !stale_miners.contains(addr));
+        self.miner_missed_floods
+            .retain(|addr, _| !stale_miners.contains(addr));
 
         // Update DB
         if let Err(e) = self.db.put_cf(
@@ -1857,7 +2215,15 @@ impl ComputeNode {
             unicorn: unicorn.unicorn,
             unicorn_witness: unicorn.witness,
         };
-        self.current_mined_block = Some(MinedBlock { common, extra_info });
+        let mined_block = MinedBlock { common, extra_info };
+        self.db
+            .put_cf(
+                DB_COL_INTERNAL,
+                MINED_BLOCK_KEY,
+                &serialize(&mined_block).unwrap(),
+            )
+            .unwrap();
+        self.current_mined_block = Some(mined_block);
     }
 
     /// Reset the mining block processing to allow a new block.
@@ -1882,7 +2248,10 @@ impl ComputeNode {
             )
             .unwrap();
 
+        self.set_next_random_num_rotation();
+
         self.current_mined_block = None;
+        self.db.delete_cf(DB_COL_INTERNAL, MINED_BLOCK_KEY).unwrap();
         self.node_raft.clear_block_pipeline_proposed_keys();
         // If the node should pause, set the pause node flag to true
         if self.should_pause() {
@@ -1890,6 +2259,92 @@ impl ComputeNode {
         }
     }
 
+    /// Blocks & waits until the grace period before proposing the first block elapsed.
+    async fn timeout_first_block_grace(&self) {
+        let propose_at = self.first_block_propose_at.unwrap_or_else(Instant::now);
+        tokio::time::sleep_until(propose_at.into()).await;
+    }
+
+    /// Blocks & waits until the partition random number is due for rotation.
+    async fn timeout_rotate_random_num(&self) {
+        tokio::time::sleep_until(self.random_num_rotate_at.into()).await;
+    }
+
+    /// Restart the partition random number rotation timer
+    fn set_next_random_num_rotation(&mut self) {
+        if let Some(rotation) = self.random_num_rotation {
+            self.random_num_rotate_at = Instant::now() + rotation;
+        }
+    }
+
+    /// Replace the random number partition entries are currently checked against
+    /// and flood it to the miners: entries computed with the old number are rejected.
+    pub async fn rotate_partition_random_num(&mut self) -> Result<()> {
+        self.set_next_random_num_rotation();
+        if self.is_paused().await {
+            return Ok(());
+        }
+
+        let (random_num, key) = match self.node_raft.get_mining_pipeline_status() {
+            MiningPipelineStatus::ParticipantOnlyIntake => {
+                (&mut self.previous_random_num, POW_PREV_RANDOM_NUM_KEY)
+            }
+            MiningPipelineStatus::AllItemsIntake => {
+                (&mut self.current_random_num, POW_RANDOM_NUM_KEY)
+            }
+            MiningPipelineStatus::Halted => return Ok(()),
+        };
+        *random_num = generate_pow_random_num();
+        self.db.put_cf(DB_COL_INTERNAL, key, &*random_num).unwrap();
+
+        info!("Rotated partition random number: {:?}", random_num);
+        self.flood_rand_and_block_to_partition().await
+    }
+
+    /// Blocks & waits until block production is due to be reported stalled.
+    async fn timeout_block_stall(&self) {
+        let timeout = self.block_stall_timeout.unwrap_or_default();
+        let stall_at = self.last_block_committed_at + timeout;
+        tokio::time::sleep_until(stall_at.into()).await;
+    }
+
+    /// Restart the block production stall timer after a block is committed
+    fn record_block_committed(&mut self) {
+        self.last_block_committed_at = Instant::now();
+        if self.readiness.is_block_production_stalled() {
+            info!("Block production resumed");
+            self.readiness.set_block_production_stalled(false);
+        }
+    }
+
+    /// Flag block production as stalled if no block was committed within the timeout
+    ///
+    /// ### Arguments
+    ///
+    /// * `now` - Time to check the last committed block against
+    pub fn check_block_production_stall(&mut self, now: Instant) -> bool {
+        let timeout = match self.block_stall_timeout {
+            Some(timeout) => timeout,
+            None => return false,
+        };
+
+        let elapsed = now.saturating_duration_since(self.last_block_committed_at);
+        let stalled = elapsed >= timeout;
+        if stalled && !self.readiness.is_block_production_stalled() {
+            error!(
+                "Block production stalled: no block committed for {:?}",
+                elapsed
+            );
+        }
+        self.readiness.set_block_production_stalled(stalled);
+        stalled
+    }
+
+    /// Whether block production is currently flagged as stalled
+    pub fn is_block_production_stalled(&self) -> bool {
+        self.readiness.is_block_production_stalled()
+    }
+
     /// Load and apply the local database to our state
     fn load_local_db(mut self) -> Result<Self> {
         self.request_list = match self.db.get_cf(DB_COL_INTERNAL, REQUEST_LIST_KEY) {
@@ -1936,6 +2391,19 @@ impl ComputeNode {
             };
         }
 
+        self.current_mined_block = match self.db.get_cf(DB_COL_INTERNAL, MINED_BLOCK_KEY) {
+            Ok(Some(mined_block)) => {
+                let mined_block = deserialize::<MinedBlock>(&mined_block)?;
+                debug!(
+                    "load_local_db: current_mined_block {:?}",
+                    mined_block.common.block.header.b_num
+                );
+                Some(mined_block)
+            }
+            Ok(None) => None,
+            Err(e) => panic!("Error accessing db: {:?}", e),
+        };
+
         self.node_raft.set_key_run({
             let key_run = match self.db.get_cf(DB_COL_INTERNAL, RAFT_KEY_RUN) {
                 Ok(Some(key_run)) => deserialize::<u64>(&key_run)? + 1,
@@ -1985,7 +2453,7 @@ impl ComputeNode {
             trace!(?address, "Received outdated PoW");
             return Some(Response {
                 success: false,
-                reason: "Not block currently mined",
+                reason: ResponseReason::NotBlockCurrentlyMined,
             });
         };
 
@@ -1994,17 +2462,18 @@ impl ComputeNode {
         if !coinbase.is_coinbase() || coinbase.outputs[0].value.token_amount() != *coinbase_amount {
             return Some(Response {
                 success: false,
-                reason: "Coinbase transaction invalid",
+                reason: ResponseReason::CoinbaseTransactionInvalid,
             });
         }
 
         // Perform validation
         let coinbase_hash = construct_tx_hash(&coinbase);
+        let mining_difficulty = mining_difficulty_from_bits(block_to_check.bits);
         let block_to_check = apply_mining_tx(block_to_check, nonce, coinbase_hash);
-        if !validate_pow_block(&block_to_check) {
+        if !validate_pow_block(&block_to_check, mining_difficulty) {
             return Some(Response {
                 success: false,
-                reason: "Invalid PoW for block",
+                reason: ResponseReason::InvalidPowForBlock,
             });
         }
 
@@ -2028,7 +2497,7 @@ impl ComputeNode {
 
         Some(Response {
             success: true,
-            reason: "Received PoW successfully",
+            reason: ResponseReason::ReceivedPowSuccessfully,
         })
     }
 
@@ -2046,10 +2515,20 @@ impl ComputeNode {
         if peer != self.storage_addr {
             return Some(Response {
                 success: false,
-                reason: "Received block stored not from our storage peer",
+                reason: ResponseReason::ReceivedBlockStoredNotFromStoragePeer,
             });
         }
 
+        let mined_b_num = self
+            .current_mined_block
+            .as_ref()
+            .map(|b| b.common.block.header.b_num);
+        if mined_b_num == Some(previous_block_info.block_num) {
+            if let Err(e) = self.db.delete_cf(DB_COL_INTERNAL, MINED_BLOCK_KEY) {
+                error!("Failed to clear stored mined block: {:?}", e);
+            }
+        }
+
         if !self
             .node_raft
             .propose_block_with_last_info(previous_block_info)
@@ -2061,13 +2540,22 @@ impl ComputeNode {
 
         Some(Response {
             success: true,
-            reason: "Received block stored",
+            reason: ResponseReason::ReceivedBlockStored,
         })
     }
 
     /// Re-sends messages triggering the next step in flow
     pub async fn resend_trigger_message(&mut self) {
-        match self.node_raft.get_mining_pipeline_status().clone() {
+        let status = self.node_raft.get_mining_pipeline_status().clone();
+        if self.current_mined_block.is_some() && status != MiningPipelineStatus::Halted {
+            info!("Resend reloaded mined block to storage");
+            if let Err(e) = self.send_block_to_storage().await {
+                error!("Resend reloaded mined block to storage failed {:?}", e);
+            }
+            return;
+        }
+
+        match status {
             MiningPipelineStatus::Halted => {
                 info!("Resend block to storage");
                 if let Err(e) = self.send_block_to_storage().await {
@@ -2120,6 +2608,8 @@ impl ComputeNode {
     /// * `script_public_key`   - Public address key
     /// * `public_key`          - Public key
     /// * `signature`           - Signature
+    ///
+    /// Fails if receipt minters are restricted and the public key is not one of them
     pub fn create_receipt_asset_tx(
         &mut self,
         receipt_amount: u64,
@@ -2130,15 +2620,22 @@ impl ComputeNode {
         metadata: Option<String>,
     ) -> Result<(Transaction, String)> {
         let b_num = self.node_raft.get_current_block_num();
-        Ok(create_receipt_asset_tx_from_sig(
+        let (tx, tx_hash) = create_receipt_asset_tx_from_sig(
             b_num,
             receipt_amount,
             script_public_key,
-            public_key,
+            public_key.clone(),
             signature,
             drs_tx_hash_spec,
             metadata,
-        )?)
+        )?;
+
+        if let Some(minters) = &self.receipt_minters {
+            if !tx_signed_by_any(&tx, minters) {
+                return Err(ComputeError::UnauthorizedReceiptMinter(public_key));
+            }
+        }
+        Ok((tx, tx_hash))
     }
 
     /// Get `Node` member
@@ -2156,26 +2653,132 @@ impl ComputeNode {
         if !self.node_raft.tx_pool_can_accept(transactions_len) {
             return Response {
                 success: false,
-                reason: "Transaction pool for this compute node is full",
+                reason: ResponseReason::TransactionPoolFull,
             };
         }
 
+        let unsupported_txs_len = transactions
+            .iter()
+            .filter(|tx| !SUPPORTED_TX_VERSIONS.contains(&tx.version))
+            .count();
+        if unsupported_txs_len != 0 {
+            warn!(
+                "Rejecting {} transaction(s) with version outside supported range {:?}",
+                unsupported_txs_len, SUPPORTED_TX_VERSIONS
+            );
+        }
+
+        let mut low_fee_txs_len = 0;
+        let mut dust_txs_len = 0;
+        let mut too_large_txs_len = 0;
+        let mut far_locktime_txs_len = 0;
+        let mut utxo_cap_txs_len = 0;
+        let mut policy_rejected_txs_len = 0;
         let (valid_dde_txs, valid_txs): (BTreeMap<_, _>, BTreeMap<_, _>) = {
             let tx_validator = self.transactions_validator();
+            let utxo_set = self.node_raft.get_committed_utxo_set();
+            let min_tx_fee = self.min_tx_fee;
+            let dust_threshold = self.node_raft.get_dust_threshold();
+            let max_locktime = self.max_locktime_offset.map(|offset| {
+                let b_num = self.node_raft.get_committed_current_block_num();
+                b_num.unwrap_or_default().saturating_add(offset)
+            });
             transactions
                 .into_iter()
                 .filter(|tx| tx_validator(tx))
+                .filter(|tx| {
+                    let enough_fee = tx_fee(tx, utxo_set) >= min_tx_fee;
+                    low_fee_txs_len += usize::from(!enough_fee);
+                    enough_fee
+                })
+                .filter(|tx| {
+                    let far_locktime = max_locktime.map_or(false, |max| {
+                        tx.outputs.iter().any(|tx_out| tx_out.locktime > max)
+                    });
+                    far_locktime_txs_len += usize::from(far_locktime);
+                    !far_locktime
+                })
+                .filter(|tx| {
+                    let dust = has_dust_output(tx, dust_threshold);
+                    dust_txs_len += usize::from(dust);
+                    !dust
+                })
+                .filter(|tx| {
+                    let fits = self.node_raft.fits_max_block_size(tx);
+                    too_large_txs_len += usize::from(!fits);
+                    fits
+                })
+                .filter(|tx| {
+                    let cap_allows = self.node_raft.utxo_set_size_cap_allows(tx);
+                    utxo_cap_txs_len += usize::from(!cap_allows);
+                    cap_allows
+                })
                 .map(|tx| (construct_tx_hash(&tx), tx))
+                .filter(|(hash, tx)| match self.tx_policy.accept(tx, utxo_set) {
+                    PolicyDecision::Accept => true,
+                    PolicyDecision::Reject(reason) => {
+                        warn!("Transaction {} rejected by policy: {}", hash, reason);
+                        policy_rejected_txs_len += 1;
+                        false
+                    }
+                })
                 .partition(|tx| tx.1.druid_info.is_some())
         };
+        if low_fee_txs_len != 0 {
+            warn!(
+                "Rejecting {} transaction(s) with fee below minimum {:?}",
+                low_fee_txs_len, self.min_tx_fee
+            );
+        }
+        if far_locktime_txs_len != 0 {
+            warn!(
+                "Rejecting {} transaction(s) locked beyond {:?} blocks from current block",
+                far_locktime_txs_len, self.max_locktime_offset
+            );
+        }
+        if dust_txs_len != 0 {
+            warn!(
+                "Rejecting {} transaction(s) with output below dust threshold",
+                dust_txs_len
+            );
+        }
+        if too_large_txs_len != 0 {
+            warn!(
+                "Rejecting {} transaction(s) larger than the maximum block size",
+                too_large_txs_len
+            );
+        }
+        if utxo_cap_txs_len != 0 {
+            warn!(
+                "Rejecting {} transaction(s) growing the UTXO set near its size cap",
+                utxo_cap_txs_len
+            );
+        }
 
         let total_valid_txs_len = valid_txs.len() + valid_dde_txs.len();
 
         // No valid transactions (normal or DDE) provided
         if total_valid_txs_len == 0 {
+            let reason = if unsupported_txs_len != 0 && unsupported_txs_len == transactions_len {
+                ResponseReason::UnsupportedTransactionVersion
+            } else if low_fee_txs_len != 0 && low_fee_txs_len == transactions_len {
+                ResponseReason::FeeTooLow
+            } else if far_locktime_txs_len != 0 && far_locktime_txs_len == transactions_len {
+                ResponseReason::LocktimeBeyondMaximum
+            } else if dust_txs_len != 0 && dust_txs_len == transactions_len {
+                ResponseReason::OutputBelowDustThreshold
+            } else if too_large_txs_len != 0 && too_large_txs_len == transactions_len {
+                ResponseReason::TransactionTooLarge
+            } else if utxo_cap_txs_len != 0 && utxo_cap_txs_len == transactions_len {
+                ResponseReason::UtxoSetNearCap
+            } else if policy_rejected_txs_len != 0 && policy_rejected_txs_len == transactions_len {
+                ResponseReason::TransactionRejectedByPolicy
+            } else {
+                ResponseReason::NoValidTransactionsProvided
+            };
             return Response {
                 success: false,
-                reason: "No valid transactions provided",
+                reason,
             };
         }
 
@@ -2200,13 +2803,13 @@ impl ComputeNode {
         if (total_valid_txs_len < transactions_len) || invalid_dde_txs_len != 0 {
             return Response {
                 success: true,
-                reason: "Some transactions invalid. Adding valid transactions only",
+                reason: ResponseReason::SomeTransactionsInvalid,
             };
         }
 
         Response {
             success: true,
-            reason: "Transactions added to tx pool",
+            reason: ResponseReason::TransactionsAddedToTxPool,
         }
     }
 
@@ -2277,28 +2880,28 @@ impl ComputeInterface for ComputeNode {
         };
         Response {
             success: true,
-            reason: "Received UTXO fetch request",
+            reason: ResponseReason::ReceivedUtxoFetchRequest,
         }
     }
 
     fn partition(&self, _uuids: Vec<&'static str>) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 
     fn get_service_levels(&self) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 
     fn execute_contract(&self, _contract: Contract) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 
@@ -2308,11 +2911,37 @@ impl ComputeInterface for ComputeNode {
 }
 
 impl ComputeApi for ComputeNode {
+    fn get_current_block_num(&self) -> u64 {
+        self.node_raft.get_current_block_num()
+    }
+
+    fn get_coordinated_shutdown(&self) -> Option<u64> {
+        Some(self.coordinated_shutdown).filter(|b_num| *b_num != u64::MAX)
+    }
+
+    fn get_miner_payouts(&self) -> Vec<MinerPayout> {
+        self.node_raft.get_miner_payouts().iter().cloned().collect()
+    }
+
+    fn get_db_metrics(&self) -> DbMetrics {
+        self.db.metrics()
+    }
+
+    fn get_raft_metrics(&self) -> RaftProposalMetricsInfo {
+        self.node_raft.proposal_metrics().info()
+    }
+
     fn get_shared_config(&self) -> ComputeNodeSharedConfig {
         ComputeNodeSharedConfig {
             compute_mining_event_timeout: self.node_raft.get_compute_mining_event_timeout(),
             compute_partition_full_size: self.node_raft.get_compute_partition_full_size(),
             compute_miner_whitelist: self.node_raft.get_compute_miner_whitelist(),
+            compute_max_block_size: self.node_raft.get_max_block_size(),
+            compute_utxo_set_size_cap: self.node_raft.get_utxo_set_size_cap(),
+            compute_dust_threshold: self.node_raft.get_dust_threshold(),
+            compute_min_block_reward: self.node_raft.get_min_block_reward(),
+            compute_reward_halving: self.node_raft.get_reward_halving(),
+            compute_winning_miner_fairness: self.node_raft.get_winning_miner_fairness(),
         }
     }
 
@@ -2320,10 +2949,25 @@ impl ComputeApi for ComputeNode {
         self.node_raft.get_committed_utxo_tracked_set()
     }
 
+    fn get_committed_tx_pool(&self) -> &BTreeMap<String, Transaction> {
+        self.get_committed_tx_pool()
+    }
+
     fn get_pending_druid_pool(&self) -> &DruidPool {
         self.get_pending_druid_pool()
     }
 
+    fn cancel_druid(
+        &mut self,
+        druid: &str,
+        tx_hash: &str,
+        expiry: u64,
+        public_key: &str,
+        signature: &str,
+    ) -> Response {
+        self.cancel_druid(druid, tx_hash, expiry, public_key, signature)
+    }
+
     fn receive_transactions(&mut self, transactions: Vec<Transaction>) -> Response {
         self.receive_transactions(transactions)
     }
@@ -2357,12 +3001,12 @@ impl ComputeApi for ComputeNode {
         {
             return Response {
                 success: false,
-                reason: "Failed to initiate coordinated pause",
+                reason: ResponseReason::FailedToInitiateCoordinatedPause,
             };
         }
         Response {
             success: true,
-            reason: "Attempt coordinated node pause",
+            reason: ResponseReason::AttemptCoordinatedNodePause,
         }
     }
 
@@ -2373,12 +3017,12 @@ impl ComputeApi for ComputeNode {
         {
             return Response {
                 success: false,
-                reason: "Failed to initiate coordinated resume",
+                reason: ResponseReason::FailedToInitiateCoordinatedResume,
             };
         }
         Response {
             success: true,
-            reason: "Attempt coordinated node resume",
+            reason: ResponseReason::AttemptCoordinatedNodeResume,
         }
     }
 
@@ -2395,12 +3039,12 @@ impl ComputeApi for ComputeNode {
         {
             return Response {
                 success: false,
-                reason: "Failed to initiate sharing of config",
+                reason: ResponseReason::FailedToInitiateSharingOfConfig,
             };
         }
         Response {
             success: true,
-            reason: "Attempt send shared config",
+            reason: ResponseReason::AttemptSendSharedConfig,
         }
     }
 }
@@ -2447,3 +3091,13 @@ fn delete_local_transactions(db: &mut SimpleDb, keys: &[String]) {
     let batch = batch.done();
     db.write(batch).unwrap();
 }
+
+/// Custom specification of the main database, within the node data directory if set
+///
+/// ### Arguments
+///
+/// * `config` - ComputeNodeConfig for the compute node
+fn custom_db_spec(config: &ComputeNodeConfig) -> Option<CustomDbSpec> {
+    let node_dir = format!("compute.{}", config.compute_node_idx);
+    db_utils::data_dir_db_spec(config.data_dir.as_deref(), &node_dir, &DB_SPEC)
+}
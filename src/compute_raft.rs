@@ -1,28 +1,34 @@
-use crate::active_raft::ActiveRaft;
+use crate::active_raft::{ActiveRaft, ElectionTicks};
 use crate::block_pipeline::{
     MiningPipelineInfo, MiningPipelineInfoImport, MiningPipelineItem, MiningPipelinePhaseChange,
     MiningPipelineStatus, Participants, PipelineEventInfo,
 };
-use crate::configurations::{ComputeNodeConfig, UnicornFixedInfo};
-use crate::constants::{BLOCK_SIZE_IN_TX, DB_PATH, TX_POOL_LIMIT};
-use crate::db_utils::{self, SimpleDb, SimpleDbError, SimpleDbSpec};
-use crate::interfaces::{BlockStoredInfo, UtxoSet, WinningPoWInfo};
+use crate::configurations::{
+    ComputeNodeConfig, RewardHalvingSchedule, UnicornFixedInfo, UtxoSetSizeCap,
+};
+use crate::constants::{
+    BLOCK_SIZE, BLOCK_SIZE_IN_TX, DB_PATH, MINER_PAYOUT_HISTORY_LEN, NETWORK_VERSION_SERIALIZED,
+    TX_POOL_LIMIT,
+};
+use crate::db_utils::{self, CustomDbSpec, SimpleDb, SimpleDbError, SimpleDbSpec};
+use crate::interfaces::{BlockStoredInfo, MinerPayout, UtxoSet, WinningPoWInfo};
 use crate::raft::{RaftCommit, RaftCommitData, RaftData, RaftMessageWrapper};
-use crate::raft_util::{RaftContextKey, RaftInFlightProposals};
+use crate::raft_util::{RaftContextKey, RaftInFlightProposals, RaftProposalMetrics};
 use crate::tracked_utxo::TrackedUtxoSet;
 use crate::unicorn::{UnicornFixedParam, UnicornInfo};
 use crate::utils::{
-    calculate_reward, get_total_coinbase_tokens, make_utxo_set_from_seed, BackupCheck,
-    UtxoReAlignCheck,
+    calculate_reward, get_total_coinbase_tokens, has_dust_output, make_utxo_set_from_seed, tx_fee,
+    BackupCheck, UtxoReAlignCheck,
 };
 use bincode::{deserialize, serialize};
+use naom::constants::TOTAL_TOKENS;
 use naom::crypto::sha3_256;
 use naom::primitives::asset::TokenAmount;
 use naom::primitives::block::Block;
 use naom::primitives::transaction::Transaction;
 use naom::utils::transaction_utils::get_inputs_previous_out_point;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::fmt;
 use std::future::Future;
 use std::net::SocketAddr;
@@ -36,6 +42,11 @@ pub const DB_SPEC: SimpleDbSpec = SimpleDbSpec {
     columns: &[],
 };
 
+/// Prefix of versioned snapshots: unversioned ones start with the unanimous majority.
+const SNAPSHOT_VERSION_TAG: &[u8] = b"ZNP:SNAP";
+/// Version of the serialized `ComputeConsensused` in snapshots.
+const SNAPSHOT_VERSION: u32 = 1;
+
 // A coordinated command sent through the RAFT to all peers
 #[derive(Default, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum CoordinatedCommand {
@@ -78,6 +89,7 @@ pub enum ComputeRuntimeItem {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CommittedItem {
     FirstBlock,
+    GenesisMismatch { local: String, proposed: String },
     Block,
     BlockShutdown,
     StartPhasePowIntake,
@@ -167,10 +179,24 @@ pub struct ComputeConsensused {
     last_committed_raft_idx_and_term: (u64, u64),
     /// The current circulation of tokens
     current_circulation: TokenAmount,
+    /// Minimum reward per compute node for a mined block
+    min_block_reward: TokenAmount,
+    /// Schedule halving the block reward
+    reward_halving: Option<RewardHalvingSchedule>,
+    /// Maximum serialized size in bytes of the block transactions, BLOCK_SIZE if not set
+    max_block_size: Option<usize>,
+    /// Cap on the UTXO set size, unbounded if not set
+    utxo_set_size_cap: Option<UtxoSetSizeCap>,
+    /// Minimum amount of a token output, except for coinbase
+    dust_threshold: TokenAmount,
+    /// Percentage moving the winning miner selection towards an equal chance per miner
+    winning_miner_fairness: Option<u64>,
     /// The block pipeline
     block_pipeline: MiningPipelineInfo,
     /// The last mining rewards.
     last_mining_transaction_hashes: Vec<String>,
+    /// Payouts to the winning miners of the most recent blocks, oldest first.
+    miner_payouts: VecDeque<MinerPayout>,
     /// Special handling for processing blocks.
     special_handling: Option<SpecialHandling>,
     /// Whitelisted miner nodes.
@@ -180,6 +206,28 @@ pub struct ComputeConsensused {
     runtime_data: ComputeConsensusedRuntimeData,
 }
 
+/// `ComputeConsensused` as serialized in unversioned snapshots.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Default, Serialize))]
+struct ComputeConsensusedV0 {
+    unanimous_majority: usize,
+    sufficient_majority: usize,
+    partition_full_size: usize,
+    tx_pool: BTreeMap<String, Transaction>,
+    tx_druid_pool: Vec<BTreeMap<String, Transaction>>,
+    tx_current_block_previous_hash: Option<String>,
+    initial_utxo_txs: Option<BTreeMap<String, Transaction>>,
+    utxo_set: TrackedUtxoSet,
+    current_block_stored_info: BTreeMap<Vec<u8>, (AccumulatingBlockStoredInfo, BTreeSet<u64>)>,
+    current_raft_coordinated_cmd_stored_info: BTreeMap<CoordinatedCommand, BTreeSet<u64>>,
+    last_committed_raft_idx_and_term: (u64, u64),
+    current_circulation: TokenAmount,
+    block_pipeline: MiningPipelineInfo,
+    last_mining_transaction_hashes: Vec<String>,
+    special_handling: Option<SpecialHandling>,
+    miner_whitelist: MinerWhitelist,
+}
+
 /// Consensused info to apply on start up after upgrade.
 pub struct ComputeConsensusedImport {
     pub unanimous_majority: usize,
@@ -193,6 +241,10 @@ pub struct ComputeConsensusedImport {
     pub current_circulation: TokenAmount,
     pub special_handling: Option<SpecialHandling>,
     pub miner_whitelist: MinerWhitelist,
+    pub dust_threshold: TokenAmount,
+    pub min_block_reward: TokenAmount,
+    pub reward_halving: Option<RewardHalvingSchedule>,
+    pub winning_miner_fairness: Option<u64>,
 }
 
 /// Consensused Compute fields and consensus management.
@@ -203,24 +255,37 @@ pub struct ComputeRaft {
     raft_active: ActiveRaft,
     /// Consensused fields.
     consensused: ComputeConsensused,
+    /// Hash of the seed UTXO set all peers must agree on for the first block.
+    genesis_hash: String,
     /// Whether consensused received initial snapshot
     consensused_snapshot_applied: bool,
     /// Initial item to propose when ready.
     local_initial_proposal: Option<InitialProposal>,
     /// Local transaction pool.
     local_tx_pool: BTreeMap<String, Transaction>,
+    /// Minimum fee increase for a transaction to replace the local ones spending the same inputs,
+    /// no replacement if not set.
+    /// Only applies until the local transactions are proposed: the delta is a local setting,
+    /// so applying it to the committed pool would let nodes build different blocks.
+    replace_by_fee_delta: Option<TokenAmount>,
     /// Local DRUID transaction pool.
     local_tx_druid_pool: Vec<BTreeMap<String, Transaction>>,
     /// Ordered transaction hashes from the last commit.
     local_tx_hash_last_commited: Vec<String>,
     /// Min duration between each transaction poposal.
     propose_transactions_timeout_duration: Duration,
+    /// Lower bound for the adaptive duration between each transaction poposal.
+    propose_transactions_timeout_min: Duration,
+    /// Upper bound for the adaptive duration between each transaction poposal.
+    propose_transactions_timeout_max: Duration,
     /// Timeout expiration time for transactimining_pipeline_statusons poposal.
     propose_transactions_timeout_at: Instant,
     /// Min duration between each event in the mining pipeline.
     propose_mining_event_timeout_duration: Duration,
     /// Timeout expiration time for mining event poposal.
     propose_mining_event_timeout_at: Instant,
+    /// Propose the end of participant intake as soon as the local list is full.
+    close_full_participant_intake: bool,
     /// Proposed items in flight.
     proposed_in_flight: RaftInFlightProposals,
     /// Proposed transaction in flight length.
@@ -250,22 +315,42 @@ impl ComputeRaft {
     ///
     /// * `config`  - Configuration option for a computer node.
     /// * `raft_db` - Override raft db to use.
-    pub async fn new(config: &ComputeNodeConfig, raft_db: Option<SimpleDb>) -> Self {
+    pub async fn new(
+        config: &ComputeNodeConfig,
+        raft_db: Option<SimpleDb>,
+    ) -> Result<Self, SimpleDbError> {
         let use_raft = config.compute_raft != 0;
 
+        let (
+            propose_transactions_timeout_duration,
+            propose_transactions_timeout_min,
+            propose_transactions_timeout_max,
+        ) = transaction_timeouts(config);
+
+        let custom_db_spec = custom_db_spec(config);
         if config.backup_restore.unwrap_or(false) {
-            db_utils::restore_file_backup(config.compute_db_mode, &DB_SPEC, None).unwrap();
+            let spec = custom_db_spec.clone();
+            db_utils::restore_file_backup(config.compute_db_mode, &DB_SPEC, spec).unwrap();
         }
         let raft_active = ActiveRaft::new(
             config.compute_node_idx,
             &config.compute_nodes,
             use_raft,
             Duration::from_millis(config.compute_raft_tick_timeout as u64),
-            db_utils::new_db(config.compute_db_mode, &DB_SPEC, raft_db, None),
+            ElectionTicks::new(
+                config.compute_raft_election_min_ticks,
+                config.compute_raft_election_max_ticks,
+            ),
+            db_utils::new_db_with_version_and_tuning(
+                config.compute_db_mode,
+                &DB_SPEC,
+                Some(NETWORK_VERSION_SERIALIZED),
+                raft_db,
+                custom_db_spec,
+                &config.compute_db_tuning.clone().unwrap_or_default(),
+            )?,
         );
 
-        let propose_transactions_timeout_duration =
-            Duration::from_millis(config.compute_transaction_timeout as u64);
         let propose_transactions_timeout_at = Instant::now();
 
         let propose_mining_event_timeout_duration =
@@ -274,6 +359,7 @@ impl ComputeRaft {
 
         let utxo_set =
             make_utxo_set_from_seed(&config.compute_seed_utxo, &config.compute_genesis_tx_in);
+        let genesis_hash = genesis_hash(&utxo_set);
 
         let first_raft_peer = config.compute_node_idx == 0 || !raft_active.use_raft();
         let peers_len = raft_active.peers_len();
@@ -281,6 +367,12 @@ impl ComputeRaft {
         let consensused = ComputeConsensused::default()
             .with_peers_len(peers_len)
             .with_partition_full_size(config.compute_partition_full_size)
+            .with_min_block_reward(config.compute_min_block_reward)
+            .with_reward_halving(config.compute_reward_halving)
+            .with_max_block_size(config.compute_max_block_size)
+            .with_utxo_set_size_cap(config.compute_utxo_set_size_cap)
+            .with_dust_threshold(config.compute_dust_threshold)
+            .with_winning_miner_fairness(config.compute_winning_miner_fairness)
             .with_unicorn_fixed_param(config.compute_unicorn_fixed_param.clone())
             .init_block_pipeline_status();
         let local_initial_proposal = Some(InitialProposal::PendingItem {
@@ -290,27 +382,44 @@ impl ComputeRaft {
         let backup_check = BackupCheck::new(config.backup_block_modulo);
         let utxo_re_align_check = UtxoReAlignCheck::new(config.utxo_re_align_block_modulo);
 
-        Self {
+        Ok(Self {
             first_raft_peer,
             raft_active,
             consensused,
+            genesis_hash,
             consensused_snapshot_applied: !use_raft,
             local_initial_proposal,
             local_tx_pool: Default::default(),
+            replace_by_fee_delta: config.compute_replace_by_fee_delta,
             local_tx_druid_pool: Default::default(),
             local_tx_hash_last_commited: Default::default(),
             propose_transactions_timeout_duration,
+            propose_transactions_timeout_min,
+            propose_transactions_timeout_max,
             propose_transactions_timeout_at,
             propose_mining_event_timeout_duration,
             propose_mining_event_timeout_at,
-            proposed_in_flight: Default::default(),
+            close_full_participant_intake: config
+                .compute_close_full_participant_intake
+                .unwrap_or(false),
+            proposed_in_flight: RaftInFlightProposals::default()
+                .with_max_in_flight(config.compute_raft_max_in_flight_proposals),
             proposed_tx_pool_len: 0,
             proposed_tx_pool_len_max: BLOCK_SIZE_IN_TX / peers_len,
             proposed_and_consensused_tx_pool_len_max: BLOCK_SIZE_IN_TX * 2,
             shutdown_no_commit_process: false,
             backup_check,
             utxo_re_align_check,
-        }
+        })
+    }
+
+    /// Repair the raft database of a node that failed to start with `CorruptNeedsRepair`
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - ComputeNodeConfig the node failed to start with
+    pub fn repair_db(config: &ComputeNodeConfig) -> Result<(), SimpleDbError> {
+        db_utils::repair_db(config.compute_db_mode, &DB_SPEC, custom_db_spec(config))
     }
 
     /// Get runtime data
@@ -374,6 +483,67 @@ impl ComputeRaft {
             .update_partition_full_size(partition_full_size);
     }
 
+    /// Get the maximum serialized size in bytes of the block transactions
+    pub fn get_max_block_size(&self) -> Option<usize> {
+        self.consensused.max_block_size
+    }
+
+    /// Update the maximum serialized size in bytes of the block transactions
+    pub fn update_max_block_size(&mut self, max_block_size: Option<usize>) {
+        self.consensused.update_max_block_size(max_block_size);
+    }
+
+    /// Get the cap on the UTXO set size
+    pub fn get_utxo_set_size_cap(&self) -> Option<UtxoSetSizeCap> {
+        self.consensused.utxo_set_size_cap
+    }
+
+    /// Update the cap on the UTXO set size
+    pub fn update_utxo_set_size_cap(&mut self, utxo_set_size_cap: Option<UtxoSetSizeCap>) {
+        self.consensused.update_utxo_set_size_cap(utxo_set_size_cap);
+    }
+
+    /// Get the minimum amount of a token output
+    pub fn get_dust_threshold(&self) -> TokenAmount {
+        self.consensused.dust_threshold
+    }
+
+    /// Update the minimum amount of a token output
+    pub fn update_dust_threshold(&mut self, dust_threshold: TokenAmount) {
+        self.consensused.update_dust_threshold(dust_threshold);
+    }
+
+    /// Get the minimum reward per compute node for a mined block
+    pub fn get_min_block_reward(&self) -> TokenAmount {
+        self.consensused.min_block_reward
+    }
+
+    /// Update the minimum reward per compute node for a mined block
+    pub fn update_min_block_reward(&mut self, min_block_reward: TokenAmount) {
+        self.consensused.update_min_block_reward(min_block_reward);
+    }
+
+    /// Get the block reward halving schedule
+    pub fn get_reward_halving(&self) -> Option<RewardHalvingSchedule> {
+        self.consensused.reward_halving
+    }
+
+    /// Update the block reward halving schedule
+    pub fn update_reward_halving(&mut self, reward_halving: Option<RewardHalvingSchedule>) {
+        self.consensused.update_reward_halving(reward_halving);
+    }
+
+    /// Get the fairness percentage of the winning miner selection
+    pub fn get_winning_miner_fairness(&self) -> Option<u64> {
+        self.consensused.winning_miner_fairness
+    }
+
+    /// Update the fairness percentage of the winning miner selection
+    pub fn update_winning_miner_fairness(&mut self, winning_miner_fairness: Option<u64>) {
+        self.consensused
+            .update_winning_miner_fairness(winning_miner_fairness);
+    }
+
     /// Update the miner whitelisting state
     pub fn update_compute_miner_whitelist_active(&mut self, active: bool) {
         self.consensused.update_miner_whitelist_active(active);
@@ -442,6 +612,16 @@ impl ComputeRaft {
         !self.consensused_snapshot_applied
     }
 
+    /// Number of proposed items not yet committed
+    pub fn proposed_in_flight_len(&self) -> usize {
+        self.proposed_in_flight.in_flight_len()
+    }
+
+    /// Shared commit latency and throughput metrics of the proposals
+    pub fn proposal_metrics(&self) -> RaftProposalMetrics {
+        self.proposed_in_flight.metrics()
+    }
+
     /// Blocks & waits for a next commit from a peer.
     pub async fn next_commit(&self) -> Option<RaftCommit> {
         self.raft_active.next_commit().await
@@ -469,17 +649,29 @@ impl ComputeRaft {
 
     /// Apply snapshot
     fn apply_snapshot(&mut self, consensused_ser: RaftData) -> Option<CommittedItem> {
-        self.consensused_snapshot_applied = true;
-
         if consensused_ser.is_empty() {
             // Empty initial snapshot
+            self.consensused_snapshot_applied = true;
             self.set_next_propose_transactions_timeout_at();
             self.set_next_propose_mining_event_timeout_at();
             None
         } else {
             // Non empty snapshot
             warn!("apply_snapshot called self.consensused updated");
-            self.consensused = deserialize(&consensused_ser).unwrap();
+            self.consensused = match deserialize_snapshot(&consensused_ser, &self.consensused) {
+                Ok(consensused) => consensused,
+                Err(e) => {
+                    error!("apply_snapshot: unsupported snapshot ignored: {:?}", e);
+                    return None;
+                }
+            };
+            self.consensused_snapshot_applied = true;
+            if let Err(errors) = self.consensused.utxo_set.check_consistency() {
+                error!(
+                    "apply_snapshot: inconsistent UTXO set tracking: {:?}",
+                    errors
+                );
+            }
             self.set_ignore_dedeup_b_num_less_than_current();
             self.set_next_propose_transactions_timeout_at();
             self.set_next_propose_mining_event_timeout_at();
@@ -510,6 +702,9 @@ impl ComputeRaft {
             .proposed_in_flight
             .received_commit_proposal(&raft_data, &raft_ctx)
             .await?;
+        self.proposed_in_flight
+            .propose_deferred_items(&mut self.raft_active)
+            .await;
         if removed {
             if let ComputeRaftItem::Transactions(ref txs) = &item {
                 self.proposed_tx_pool_len -= txs.len();
@@ -524,6 +719,18 @@ impl ComputeRaft {
                     return None;
                 }
 
+                let proposed = genesis_hash(&uxto_set);
+                if proposed != self.genesis_hash {
+                    error!(
+                        "Proposed genesis {} differs from local genesis {} {:?}",
+                        proposed, self.genesis_hash, key
+                    );
+                    return Some(CommittedItem::GenesisMismatch {
+                        local: self.genesis_hash.clone(),
+                        proposed,
+                    });
+                }
+
                 self.consensused.append_first_block_info(key, uxto_set);
                 if self.consensused.has_different_block_stored_info() {
                     error!("Proposed uxtosets are different {:?}", key);
@@ -585,6 +792,12 @@ impl ComputeRaft {
                     return None;
                 }
 
+                let is_participant = matches!(
+                    mining_pipeline_item,
+                    MiningPipelineItem::MiningParticipant(..)
+                );
+                let proposer_id = key.proposer_id;
+
                 match self
                     .consensused
                     .handle_mining_pipeline_item(mining_pipeline_item, key)
@@ -603,7 +816,13 @@ impl ComputeRaft {
                         self.consensused.block_pipeline.clear_proposed_keys();
                         return Some(v.into());
                     }
-                    None => return None,
+                    None => {
+                        if is_participant && self.is_own_participants_intake_full(proposer_id) {
+                            // No need to wait for the timeout to close the intake
+                            self.propose_mining_event_timeout_at = Instant::now();
+                        }
+                        return None;
+                    }
                 }
             }
             ComputeRaftItem::CoordinatedCmd(cmd) => {
@@ -670,19 +889,23 @@ impl ComputeRaft {
     }
 
     /// Propose initial item
+    /// Calling it again once proposed is ignored.
     pub async fn propose_initial_item(&mut self) {
         self.local_initial_proposal = match self.local_initial_proposal.take() {
             Some(InitialProposal::PendingAll) => Some(InitialProposal::PendingAuthorized),
             Some(InitialProposal::PendingItem { item, dedup_b_num }) => {
                 if let Some(b_num) = dedup_b_num {
-                    self.propose_item_dedup(&item, b_num).await.unwrap();
+                    if self.propose_item_dedup(&item, b_num).await.is_none() {
+                        warn!("propose_initial_item duplicate ignored: {:?}", item);
+                    }
                 } else {
                     self.propose_item(&item).await;
                 }
                 None
             }
-            Some(InitialProposal::PendingAuthorized) | None => {
-                panic!("propose_initial_item called again")
+            v @ Some(InitialProposal::PendingAuthorized) | v @ None => {
+                warn!("propose_initial_item called again: {:?}", v);
+                v
             }
         };
     }
@@ -719,6 +942,16 @@ impl ComputeRaft {
             Instant::now() + self.propose_mining_event_timeout_duration
     }
 
+    /// Whether this node's participant intake is full and should be closed early
+    fn is_own_participants_intake_full(&self, proposer_id: u64) -> bool {
+        self.close_full_participant_intake
+            && proposer_id == self.raft_active.peer_id()
+            && self
+                .consensused
+                .block_pipeline
+                .is_participants_intake_full(proposer_id, self.consensused.partition_full_size)
+    }
+
     /// Propose a new mining event if relecant
     /// Restart timeout, for re-proposal.
     pub async fn propose_mining_event_at_timeout(&mut self) -> bool {
@@ -800,23 +1033,54 @@ impl ComputeRaft {
     /// Process as a result of timeout_propose_transactions.
     /// Reset timeout, and propose local transactions if available.
     pub async fn propose_local_transactions_at_timeout(&mut self) {
+        if !self.proposed_in_flight.is_at_capacity() {
+            let max_add = self
+                .proposed_and_consensused_tx_pool_len_max
+                .saturating_sub(self.proposed_and_consensused_tx_pool_len());
+
+            let max_propose_len = std::cmp::min(max_add, self.proposed_tx_pool_len_max);
+            let txs = take_first_n(max_propose_len, &mut self.local_tx_pool);
+            let txs_len = txs.len();
+            if !txs.is_empty() {
+                self.proposed_tx_pool_len += txs.len();
+                self.propose_item(&ComputeRaftItem::Transactions(txs)).await;
+            }
+            self.adapt_propose_transactions_timeout_duration(txs_len, max_propose_len);
+        }
         self.set_next_propose_transactions_timeout_at();
+    }
 
-        let max_add = self
-            .proposed_and_consensused_tx_pool_len_max
-            .saturating_sub(self.proposed_and_consensused_tx_pool_len());
-
-        let max_propose_len = std::cmp::min(max_add, self.proposed_tx_pool_len_max);
-        let txs = take_first_n(max_propose_len, &mut self.local_tx_pool);
-        if !txs.is_empty() {
-            self.proposed_tx_pool_len += txs.len();
-            self.propose_item(&ComputeRaftItem::Transactions(txs)).await;
-        }
+    /// Adapt the duration between transaction proposals within the configured bounds:
+    /// Shorten it when the proposal filled its budget, lengthen it when nothing was proposed.
+    ///
+    /// ### Arguments
+    ///
+    /// * `proposed_len`    - Number of transactions proposed.
+    /// * `max_propose_len` - Maximum number of transactions that could be proposed.
+    fn adapt_propose_transactions_timeout_duration(
+        &mut self,
+        proposed_len: usize,
+        max_propose_len: usize,
+    ) {
+        let duration = self.propose_transactions_timeout_duration;
+        self.propose_transactions_timeout_duration = if max_propose_len == 0 {
+            duration
+        } else if proposed_len == 0 {
+            std::cmp::min(duration * 2, self.propose_transactions_timeout_max)
+        } else if proposed_len >= max_propose_len {
+            std::cmp::max(duration / 2, self.propose_transactions_timeout_min)
+        } else {
+            duration
+        };
     }
 
     /// Process as a result of timeout_propose_transactions.
     /// Propose druid transactions if available.
     pub async fn propose_local_druid_transactions(&mut self) {
+        if self.proposed_in_flight.is_at_capacity() {
+            return;
+        }
+
         let txs = std::mem::take(&mut self.local_tx_druid_pool);
         if !txs.is_empty() {
             self.propose_item(&ComputeRaftItem::DruidTransactions(txs))
@@ -851,15 +1115,15 @@ impl ComputeRaft {
     }
 
     /// Propose an item to raft if use_raft, or commit it otherwise.
+    /// Return None if deferred because too many items are in flight.
     ///
     /// ### Arguments
     ///
     /// * `item` - The item to be proposed to a raft.
-    async fn propose_item(&mut self, item: &ComputeRaftItem) -> RaftContextKey {
+    async fn propose_item(&mut self, item: &ComputeRaftItem) -> Option<RaftContextKey> {
         self.proposed_in_flight
             .propose_item(&mut self.raft_active, item, None)
             .await
-            .unwrap()
     }
 
     /// Get the UNICORN value for the current mining round
@@ -926,11 +1190,21 @@ impl ComputeRaft {
     }
 
     /// Append new transaction to our local pool from which to propose
-    /// consensused transactions.
+    /// consensused transactions, replacing the local transactions spending the same inputs
+    /// when replace by fee is enabled.
+    /// Transactions already proposed are never replaced: a conflicting transaction received
+    /// after the proposal is added as is and left to the committed pool double spend checks.
     /// ### Arguments
     /// * 'transactions' - a mutable BTreeMap that has a String and a Transaction parameters
     pub fn append_to_tx_pool(&mut self, mut transactions: BTreeMap<String, Transaction>) {
-        self.local_tx_pool.append(&mut transactions);
+        if let Some(min_fee_delta) = self.replace_by_fee_delta {
+            let utxo_set = self.consensused.get_committed_utxo_set();
+            for (hash, tx) in transactions {
+                insert_replacing_by_fee(&mut self.local_tx_pool, utxo_set, min_fee_delta, hash, tx);
+            }
+        } else {
+            self.local_tx_pool.append(&mut transactions);
+        }
     }
 
     /// Append new transaction to our local pool from which to propose
@@ -958,6 +1232,17 @@ impl ComputeRaft {
         self.consensused.get_committed_utxo_set()
     }
 
+    /// Check the transaction fits in a block given the committed maximum block size
+    pub fn fits_max_block_size(&self, tx: &Transaction) -> bool {
+        self.consensused.fits_max_block_size(tx)
+    }
+
+    /// Check the transaction may be added given the committed UTXO set size cap:
+    /// Near the cap, it must spend at least as many outputs as it creates.
+    pub fn utxo_set_size_cap_allows(&self, tx: &Transaction) -> bool {
+        self.consensused.utxo_set_size_cap_allows(tx)
+    }
+
     /// Current utxo_set returned as `TrackedUtxoSet`
     pub fn get_committed_utxo_tracked_set(&self) -> &TrackedUtxoSet {
         self.consensused.get_committed_utxo_tracked_set()
@@ -1012,18 +1297,29 @@ impl ComputeRaft {
     /// Generate a snapshot, needs to happen at the end of the event processing.
     pub fn event_processed_generate_snapshot(&mut self) {
         self.set_ignore_dedeup_b_num_less_than_current();
+        self.generate_snapshot();
+
+        if self.is_shutdown_on_commit() {
+            self.shutdown_no_commit_process = true;
+        }
+    }
 
-        let consensused_ser = serialize(&self.consensused).unwrap();
+    /// Snapshot the consensused state at the last committed index.
+    /// Safe to call between events as it does not change the consensused state.
+    pub fn generate_snapshot(&mut self) {
+        let consensused_ser = serialize_snapshot(&self.consensused);
         let (snapshot_idx, term) = self.consensused.last_committed_raft_idx_and_term;
 
         debug!("generate_snapshot: (idx: {}, term: {})", snapshot_idx, term);
         let backup = self.need_backup();
         self.raft_active
             .create_snapshot(snapshot_idx, consensused_ser, backup);
+    }
 
-        if self.is_shutdown_on_commit() {
-            self.shutdown_no_commit_process = true;
-        }
+    /// Index of the last committed raft entry and of the last snapshot generated
+    pub fn get_snapshot_indexes(&self) -> (u64, Option<u64>) {
+        let (committed_idx, _) = self.consensused.last_committed_raft_idx_and_term;
+        (committed_idx, self.raft_active.last_snapshot_idx())
     }
 
     /// Ignore processing raft item out of date.
@@ -1051,6 +1347,11 @@ impl ComputeRaft {
         &self.consensused.last_mining_transaction_hashes
     }
 
+    /// Payouts to the winning miners of the most recent blocks, oldest first
+    pub fn get_miner_payouts(&self) -> &VecDeque<MinerPayout> {
+        &self.consensused.miner_payouts
+    }
+
     /// Whether to shutdown when block committed
     pub fn is_shutdown_on_commit(&self) -> bool {
         self.consensused.special_handling == Some(SpecialHandling::Shutdown)
@@ -1109,6 +1410,36 @@ impl ComputeConsensused {
         self.partition_full_size = partition_full_size;
     }
 
+    /// Update the maximum serialized size in bytes of the block transactions
+    pub fn update_max_block_size(&mut self, max_block_size: Option<usize>) {
+        self.max_block_size = max_block_size;
+    }
+
+    /// Update the cap on the UTXO set size
+    pub fn update_utxo_set_size_cap(&mut self, utxo_set_size_cap: Option<UtxoSetSizeCap>) {
+        self.utxo_set_size_cap = utxo_set_size_cap;
+    }
+
+    /// Update the minimum amount of a token output
+    pub fn update_dust_threshold(&mut self, dust_threshold: TokenAmount) {
+        self.dust_threshold = dust_threshold;
+    }
+
+    /// Update the minimum reward per compute node for a mined block
+    pub fn update_min_block_reward(&mut self, min_block_reward: TokenAmount) {
+        self.min_block_reward = min_block_reward;
+    }
+
+    /// Update the block reward halving schedule
+    pub fn update_reward_halving(&mut self, reward_halving: Option<RewardHalvingSchedule>) {
+        self.reward_halving = reward_halving;
+    }
+
+    /// Update the fairness percentage of the winning miner selection
+    pub fn update_winning_miner_fairness(&mut self, winning_miner_fairness: Option<u64>) {
+        self.winning_miner_fairness = winning_miner_fairness;
+    }
+
     /// Update the miner IP addresses used for whitelisting
     pub fn update_miner_whitelist_addresses(
         &mut self,
@@ -1140,6 +1471,42 @@ impl ComputeConsensused {
         self
     }
 
+    /// Specify the minimum reward per compute node for a mined block
+    pub fn with_min_block_reward(mut self, min_block_reward: TokenAmount) -> Self {
+        self.min_block_reward = min_block_reward;
+        self
+    }
+
+    /// Specify the block reward halving schedule
+    pub fn with_reward_halving(mut self, reward_halving: Option<RewardHalvingSchedule>) -> Self {
+        self.reward_halving = reward_halving;
+        self
+    }
+
+    /// Specify the maximum serialized size in bytes of the block transactions
+    pub fn with_max_block_size(mut self, max_block_size: Option<usize>) -> Self {
+        self.max_block_size = max_block_size;
+        self
+    }
+
+    /// Specify the cap on the UTXO set size
+    pub fn with_utxo_set_size_cap(mut self, utxo_set_size_cap: Option<UtxoSetSizeCap>) -> Self {
+        self.utxo_set_size_cap = utxo_set_size_cap;
+        self
+    }
+
+    /// Specify the minimum amount of a token output
+    pub fn with_dust_threshold(mut self, dust_threshold: TokenAmount) -> Self {
+        self.dust_threshold = dust_threshold;
+        self
+    }
+
+    /// Specify the fairness percentage of the winning miner selection
+    pub fn with_winning_miner_fairness(mut self, winning_miner_fairness: Option<u64>) -> Self {
+        self.winning_miner_fairness = winning_miner_fairness;
+        self
+    }
+
     /// Specify the unicorn fixed params
     pub fn with_unicorn_fixed_param(mut self, unicorn_fixed_info: UnicornFixedInfo) -> Self {
         self.block_pipeline = self
@@ -1155,6 +1522,7 @@ impl ComputeConsensused {
             unanimous_majority: self.unanimous_majority,
             sufficient_majority: self.sufficient_majority,
             partition_full_size: self.partition_full_size,
+            winning_miner_fairness: self.winning_miner_fairness,
         };
         self.block_pipeline = self.block_pipeline.init_block_pipeline_status(extra);
         self
@@ -1174,6 +1542,10 @@ impl ComputeConsensused {
             current_circulation,
             special_handling,
             miner_whitelist,
+            dust_threshold,
+            min_block_reward,
+            reward_halving,
+            winning_miner_fairness,
         } = consensused;
 
         let block_pipeline = MiningPipelineInfoImport {
@@ -1195,8 +1567,15 @@ impl ComputeConsensused {
             current_raft_coordinated_cmd_stored_info: Default::default(),
             last_committed_raft_idx_and_term,
             current_circulation,
+            min_block_reward,
+            reward_halving,
+            max_block_size: Default::default(),
+            utxo_set_size_cap: Default::default(),
+            dust_threshold,
+            winning_miner_fairness,
             block_pipeline: MiningPipelineInfo::from_import(block_pipeline),
             last_mining_transaction_hashes: Default::default(),
+            miner_payouts: Default::default(),
             runtime_data: Default::default(),
             special_handling,
             miner_whitelist,
@@ -1222,6 +1601,10 @@ impl ComputeConsensused {
             current_circulation: self.current_circulation,
             miner_whitelist: self.miner_whitelist,
             special_handling,
+            dust_threshold: self.dust_threshold,
+            min_block_reward: self.min_block_reward,
+            reward_halving: self.reward_halving,
+            winning_miner_fairness: self.winning_miner_fairness,
         }
     }
 
@@ -1285,6 +1668,22 @@ impl ComputeConsensused {
         &self.utxo_set
     }
 
+    /// Check the transaction fits in a block given the maximum block size
+    pub fn fits_max_block_size(&self, tx: &Transaction) -> bool {
+        serialized_len(tx) <= self.max_block_size.unwrap_or(BLOCK_SIZE)
+    }
+
+    /// Check the transaction may be added given the UTXO set size cap
+    pub fn utxo_set_size_cap_allows(&self, tx: &Transaction) -> bool {
+        let cap = match self.utxo_set_size_cap {
+            Some(cap) => cap,
+            None => return true,
+        };
+
+        let near_cap = self.utxo_set.len() + cap.net_reducing_margin >= cap.max_size;
+        !near_cap || tx.outputs.len() <= tx.inputs.len()
+    }
+
     /// Current tracked UTXO set
     pub fn get_committed_utxo_tracked_set(&self) -> &TrackedUtxoSet {
         &self.utxo_set
@@ -1321,7 +1720,8 @@ impl ComputeConsensused {
         self.set_committed_mining_block(next_block, next_block_tx)
     }
 
-    /// Apply all consensused transactions to the block
+    /// Apply all consensused transactions to the block, processing DRUID droplets
+    /// in canonical order
     /// ### Arguments
     ///
     /// * `block`   - commited Block to be set to be updated
@@ -1332,6 +1732,9 @@ impl ComputeConsensused {
         block_tx: &mut BTreeMap<String, Transaction>,
     ) {
         let mut tx_druid_pool = std::mem::take(&mut self.tx_druid_pool);
+
+        // Process droplets in canonical order so all peers build the same block.
+        tx_druid_pool.sort_by_cached_key(droplet_order_key);
         for txs in tx_druid_pool.drain(..) {
             if !self.find_invalid_new_txs(&txs).is_empty() {
                 // Drop invalid DRUID droplet
@@ -1344,6 +1747,8 @@ impl ComputeConsensused {
     }
 
     /// Apply all valid consensused transactions to the block until BLOCK_SIZE_IN_TX
+    /// or the maximum block size in bytes is reached:
+    /// Transactions not fitting in the remaining space are kept for the next block.
     /// ### Arguments
     ///
     /// * `block`   - current Block to be set to be updated
@@ -1358,8 +1763,21 @@ impl ComputeConsensused {
             self.tx_pool.remove(&invalid);
         }
 
+        // Drop transactions that can never fit in a block, growing the UTXO set near its cap,
+        // or creating dust.
+        let max_block_size = self.max_block_size.unwrap_or(BLOCK_SIZE);
+        let mut tx_pool = std::mem::take(&mut self.tx_pool);
+        tx_pool.retain(|_, tx| {
+            serialized_len(tx) <= max_block_size
+                && self.utxo_set_size_cap_allows(tx)
+                && !has_dust_output(tx, self.dust_threshold)
+        });
+        self.tx_pool = tx_pool;
+
         // Select subset of transaction to fill the block.
-        let txs = take_first_n(BLOCK_SIZE_IN_TX, &mut self.tx_pool);
+        let used_size: usize = block_tx.values().map(serialized_len).sum();
+        let max_size = max_block_size.saturating_sub(used_size);
+        let txs = take_first_n_within_size(BLOCK_SIZE_IN_TX, max_size, &mut self.tx_pool);
 
         // Process valid set of transactions.
         self.update_current_block_tx_with_given_valid_txs(txs, block, block_tx);
@@ -1618,13 +2036,30 @@ impl ComputeConsensused {
                 info.block_num + 1
             }
         };
-        let reward = calculate_reward(self.current_circulation) / self.unanimous_majority as u64;
+        let reward = self.calculate_node_reward(block_num);
 
         self.block_pipeline
             .apply_ready_block_stored_info(block_num, reward);
     }
 
+    /// Reward for each compute node given the current circulation and block number,
+    /// never below the minimum unless the remaining supply is too low
+    fn calculate_node_reward(&self, b_num: u64) -> TokenAmount {
+        let peers_len = self.unanimous_majority as u64;
+        let reward = calculate_reward(
+            self.current_circulation,
+            b_num,
+            self.reward_halving.as_ref(),
+        ) / peers_len;
+        let remaining = TOTAL_TOKENS.saturating_sub(self.current_circulation.0) / peers_len;
+        let min_block_reward = std::cmp::min(self.min_block_reward, TokenAmount(remaining));
+        std::cmp::max(reward, min_block_reward)
+    }
+
     /// Take the block info with most vote and reset accumulator.
+    ///
+    /// Ties go to the info with the highest hash: infos are iterated in hash order
+    /// and `max_by_key` returns the last of the equally voted ones.
     fn take_ready_block_stored_info(&mut self) -> AccumulatingBlockStoredInfo {
         let infos = std::mem::take(&mut self.current_block_stored_info);
         infos
@@ -1654,6 +2089,7 @@ impl ComputeConsensused {
             unanimous_majority: self.unanimous_majority,
             sufficient_majority: self.sufficient_majority,
             partition_full_size: self.partition_full_size,
+            winning_miner_fairness: self.winning_miner_fairness,
         };
         self.block_pipeline.construct_unicorn();
         self.block_pipeline.start_items_intake(extra);
@@ -1670,10 +2106,34 @@ impl ComputeConsensused {
             unanimous_majority: self.unanimous_majority,
             sufficient_majority: self.sufficient_majority,
             partition_full_size: self.partition_full_size,
+            winning_miner_fairness: self.winning_miner_fairness,
         };
-        self.block_pipeline
+        let phase_change = self
+            .block_pipeline
             .handle_mining_pipeline_item(pipeline_item, extra)
-            .await
+            .await;
+        if phase_change == Some(MiningPipelinePhaseChange::StartPhaseHalted) {
+            self.record_miner_payout();
+        }
+        phase_change
+    }
+
+    /// Record the payout to the selected winning miner, keeping only the most recent ones
+    fn record_miner_payout(&mut self) {
+        let block_num = self.block_pipeline.current_block_num();
+        let winning_miner = self.block_pipeline.get_winning_miner();
+        if let (Some(block_num), Some((miner_address, pow))) = (block_num, winning_miner) {
+            let reward = pow.mining_tx.1.outputs.iter();
+            let reward = reward.map(|out| out.value.token_amount()).sum();
+            self.miner_payouts.push_back(MinerPayout {
+                block_num,
+                miner_address: *miner_address,
+                reward,
+            });
+            if self.miner_payouts.len() > MINER_PAYOUT_HISTORY_LEN {
+                self.miner_payouts.pop_front();
+            }
+        }
     }
 }
 
@@ -1691,16 +2151,323 @@ fn take_first_n<K: Clone + Ord, V>(n: usize, from: &mut BTreeMap<K, V>) -> BTree
     result
 }
 
+/// Take the first n entries with a total serialized size within max_size,
+/// skipping the entries not fitting in the remaining size
+/// ### Arguments
+///
+/// * `n`        - Maximum number of entries to take
+/// * `max_size` - Maximum total serialized size in bytes of the taken entries
+/// * `from`     - Map to take the entries from, keeping the remaining ones
+fn take_first_n_within_size<K: Clone + Ord, V: Serialize>(
+    n: usize,
+    max_size: usize,
+    from: &mut BTreeMap<K, V>,
+) -> BTreeMap<K, V> {
+    let mut size = 0;
+    let keys: Vec<K> = from
+        .iter()
+        .filter(|(_, v)| {
+            let len = serialized_len(*v);
+            let fits = size + len <= max_size;
+            size += if fits { len } else { 0 };
+            fits
+        })
+        .map(|(k, _)| k.clone())
+        .take(n)
+        .collect();
+
+    keys.into_iter()
+        .filter_map(|k| from.remove_entry(&k))
+        .collect()
+}
+
+/// Canonical key ordering DRUID droplets: DRUID id then transaction hashes
+/// ### Arguments
+///
+/// * `droplet` - DRUID droplet transactions
+fn droplet_order_key(droplet: &BTreeMap<String, Transaction>) -> (Option<String>, Vec<String>) {
+    let druid = droplet
+        .values()
+        .find_map(|tx| tx.druid_info.as_ref().map(|info| info.druid.clone()));
+    (druid, droplet.keys().cloned().collect())
+}
+
+/// Insert a transaction in the pool, replacing the pool transactions spending any of
+/// the same inputs if its fee exceeds each of theirs by at least `min_fee_delta`.
+/// Returns whether the transaction was inserted.
+/// ### Arguments
+///
+/// * `pool`          - Local transactions not yet proposed
+/// * `utxo_set`      - UTXO set the transaction inputs are spent from
+/// * `min_fee_delta` - Minimum fee increase over the replaced transactions
+/// * `hash`          - Hash of the transaction to insert
+/// * `tx`            - Transaction to insert
+fn insert_replacing_by_fee(
+    pool: &mut BTreeMap<String, Transaction>,
+    utxo_set: &UtxoSet,
+    min_fee_delta: TokenAmount,
+    hash: String,
+    tx: Transaction,
+) -> bool {
+    let inputs: BTreeSet<_> = get_inputs_previous_out_point(Some(&tx).into_iter()).collect();
+    let conflicts: Vec<String> = pool
+        .iter()
+        .filter(|(_, p)| {
+            get_inputs_previous_out_point(Some(*p).into_iter()).any(|o| inputs.contains(o))
+        })
+        .map(|(h, _)| h.clone())
+        .collect();
+
+    if let Some(max_conflict_fee) = conflicts.iter().map(|h| tx_fee(&pool[h], utxo_set)).max() {
+        let fee = tx_fee(&tx, utxo_set);
+        if fee <= max_conflict_fee || fee.0 - max_conflict_fee.0 < min_fee_delta.0 {
+            debug!(
+                "Transaction {} fee too low to replace {:?}",
+                hash, conflicts
+            );
+            return false;
+        }
+        for conflict in &conflicts {
+            pool.remove(conflict);
+        }
+        debug!("Transaction {} replaced {:?}", hash, conflicts);
+    }
+
+    pool.insert(hash, tx);
+    true
+}
+
+/// Serialized size in bytes of the given value
+fn serialized_len<V: Serialize>(value: &V) -> usize {
+    serialize(value).map(|v| v.len()).unwrap_or_default()
+}
+
+/// Serialize consensused data into a versioned snapshot
+/// ### Arguments
+///
+/// * `consensused` - Consensused data to snapshot
+pub fn serialize_snapshot(consensused: &ComputeConsensused) -> RaftData {
+    let mut data = SNAPSHOT_VERSION_TAG.to_vec();
+    data.extend(serialize(&SNAPSHOT_VERSION).unwrap());
+    data.extend(serialize(consensused).unwrap());
+    data
+}
+
+/// Deserialize consensused data from a versioned or unversioned snapshot:
+/// Fields missing from older versions are taken from the given defaults.
+/// ### Arguments
+///
+/// * `data`     - Snapshot data
+/// * `defaults` - Consensused data holding the values of the fields missing from the snapshot
+pub fn deserialize_snapshot(
+    data: &[u8],
+    defaults: &ComputeConsensused,
+) -> bincode::Result<ComputeConsensused> {
+    let data = match data.strip_prefix(SNAPSHOT_VERSION_TAG) {
+        Some(data) => data,
+        None => return deserialize::<ComputeConsensusedV0>(data).map(|v| v.upgrade(defaults)),
+    };
+
+    let version_len = serialize(&SNAPSHOT_VERSION).unwrap().len();
+    let (version, data) = data.split_at(version_len.min(data.len()));
+    match deserialize::<u32>(version)? {
+        SNAPSHOT_VERSION => deserialize(data),
+        version => Err(Box::new(bincode::ErrorKind::Custom(format!(
+            "unsupported snapshot version {version}"
+        )))),
+    }
+}
+
+impl ComputeConsensusedV0 {
+    /// Convert to the current version, with the missing fields from the defaults
+    fn upgrade(self, defaults: &ComputeConsensused) -> ComputeConsensused {
+        ComputeConsensused {
+            unanimous_majority: self.unanimous_majority,
+            sufficient_majority: self.sufficient_majority,
+            partition_full_size: self.partition_full_size,
+            tx_pool: self.tx_pool,
+            tx_druid_pool: self.tx_druid_pool,
+            tx_current_block_previous_hash: self.tx_current_block_previous_hash,
+            initial_utxo_txs: self.initial_utxo_txs,
+            utxo_set: self.utxo_set,
+            current_block_stored_info: self.current_block_stored_info,
+            current_raft_coordinated_cmd_stored_info: self.current_raft_coordinated_cmd_stored_info,
+            last_committed_raft_idx_and_term: self.last_committed_raft_idx_and_term,
+            current_circulation: self.current_circulation,
+            min_block_reward: defaults.min_block_reward,
+            reward_halving: defaults.reward_halving,
+            max_block_size: defaults.max_block_size,
+            utxo_set_size_cap: defaults.utxo_set_size_cap,
+            dust_threshold: defaults.dust_threshold,
+            winning_miner_fairness: defaults.winning_miner_fairness,
+            block_pipeline: self.block_pipeline,
+            last_mining_transaction_hashes: self.last_mining_transaction_hashes,
+            miner_payouts: Default::default(),
+            special_handling: self.special_handling,
+            miner_whitelist: self.miner_whitelist,
+            runtime_data: Default::default(),
+        }
+    }
+}
+
+/// Hash identifying the genesis seed UTXO set of the first block.
+/// ### Arguments
+///
+/// * `utxo_set` - Seed UTXO set of the first block
+pub fn genesis_hash(utxo_set: &BTreeMap<String, Transaction>) -> String {
+    hex::encode(sha3_256::digest(&serialize(utxo_set).unwrap()))
+}
+
+/// Initial, minimum and maximum duration between each transaction proposal.
+/// Unset bounds default to the configured timeout, which is clamped within the bounds.
+///
+/// ### Arguments
+///
+/// * `config` - ComputeNodeConfig for the compute node
+pub fn transaction_timeouts(config: &ComputeNodeConfig) -> (Duration, Duration, Duration) {
+    let initial = Duration::from_millis(config.compute_transaction_timeout as u64);
+    let to_bound = |v: Option<usize>| v.map_or(initial, |v| Duration::from_millis(v as u64));
+    let min = to_bound(config.compute_transaction_timeout_min);
+    let max = to_bound(config.compute_transaction_timeout_max);
+    (initial.max(min).min(max), min, max)
+}
+
+/// Custom specification of the raft database, within the node data directory if set
+///
+/// ### Arguments
+///
+/// * `config` - ComputeNodeConfig for the compute node
+fn custom_db_spec(config: &ComputeNodeConfig) -> Option<CustomDbSpec> {
+    let node_dir = format!("compute.{}", config.compute_node_idx);
+    db_utils::data_dir_db_spec(config.data_dir.as_deref(), &node_dir, &DB_SPEC)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::configurations::{DbMode, NodeSpec, TxOutSpec};
     use crate::utils::{create_valid_transaction, get_test_common_unicorn};
     use naom::crypto::sign_ed25519 as sign;
-    use naom::primitives::asset::TokenAmount;
+    use naom::primitives::asset::{Asset, TokenAmount};
+    use naom::primitives::druid::DdeValues;
+    use naom::utils::transaction_utils::construct_tx_hash;
     use rug::Integer;
     use std::collections::BTreeSet;
 
+    #[test]
+    fn block_reward_clamped_to_min_block_reward() {
+        //
+        // Arrange
+        //
+        let min_block_reward = TokenAmount(1000);
+        let mut consensused = ComputeConsensused::default()
+            .with_peers_len(3)
+            .with_min_block_reward(min_block_reward);
+        let reward_at_zero_circulation = consensused.calculate_node_reward(1);
+
+        //
+        // Act
+        //
+        consensused.current_circulation = TokenAmount(TOTAL_TOKENS - 10_000);
+        let reward_at_high_circulation = consensused.calculate_node_reward(1);
+        consensused.current_circulation = TokenAmount(TOTAL_TOKENS - 1);
+        let reward_near_total_tokens = consensused.calculate_node_reward(1);
+        consensused.current_circulation = TokenAmount(TOTAL_TOKENS);
+        let reward_at_total_tokens = consensused.calculate_node_reward(1);
+
+        //
+        // Assert
+        //
+        assert!(reward_at_zero_circulation > min_block_reward);
+        assert_eq!(reward_at_high_circulation, min_block_reward);
+        assert_eq!(reward_near_total_tokens, TokenAmount(0));
+        assert_eq!(reward_at_total_tokens, TokenAmount(0));
+    }
+
+    #[test]
+    fn snapshot_deserialize_versioned_and_unversioned() {
+        //
+        // Arrange
+        //
+        let defaults = ComputeConsensused::default()
+            .with_peers_len(3)
+            .with_min_block_reward(TokenAmount(7))
+            .with_winning_miner_fairness(Some(50));
+        let versioned = serialize_snapshot(&defaults);
+        let unversioned = serialize(&ComputeConsensusedV0 {
+            unanimous_majority: 2,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut unsupported = versioned.clone();
+        unsupported[SNAPSHOT_VERSION_TAG.len()] += 1;
+
+        //
+        // Act
+        //
+        let from_versioned = deserialize_snapshot(&versioned, &Default::default()).unwrap();
+        let from_unversioned = deserialize_snapshot(&unversioned, &defaults).unwrap();
+        let from_unsupported = deserialize_snapshot(&unsupported, &defaults);
+        let from_import = ComputeConsensused::from_import(defaults.clone().into_import(None));
+
+        //
+        // Assert
+        //
+        assert_eq!(from_versioned.unanimous_majority, 3);
+        assert_eq!(from_versioned.min_block_reward, TokenAmount(7));
+        assert_eq!(from_unversioned.unanimous_majority, 2);
+        assert_eq!(from_unversioned.min_block_reward, TokenAmount(7));
+        assert_eq!(from_versioned.winning_miner_fairness, Some(50));
+        assert_eq!(from_unversioned.winning_miner_fairness, Some(50));
+        assert_eq!(from_import.winning_miner_fairness, Some(50));
+        assert!(from_unsupported.is_err());
+    }
+
+    #[test]
+    fn block_stored_info_tie_selected_by_all_peers() {
+        //
+        // Arrange
+        //
+        let infos: Vec<_> = (0..3)
+            .map(|b_num| BlockStoredInfo {
+                block_hash: format!("hash{b_num}"),
+                block_num: b_num,
+                ..Default::default()
+            })
+            .map(AccumulatingBlockStoredInfo::Block)
+            .collect();
+        let vote_orders = vec![vec![0, 1, 2], vec![2, 1, 0], vec![1, 2, 0]];
+        let highest_hash_info = infos
+            .iter()
+            .max_by_key(|info| sha3_256::digest(&serialize(info).unwrap()).to_vec())
+            .cloned()
+            .unwrap();
+
+        //
+        // Act
+        //
+        let selected: Vec<_> = vote_orders
+            .iter()
+            .map(|order| {
+                let mut consensused = ComputeConsensused::default().with_peers_len(3);
+                for (proposer_id, info_idx) in order.iter().enumerate() {
+                    let key = RaftContextKey {
+                        proposer_id: proposer_id as u64,
+                        proposer_run: 0,
+                        proposal_id: 0,
+                    };
+                    consensused.append_current_block_stored_info(key, infos[*info_idx].clone());
+                }
+                consensused.take_ready_block_stored_info()
+            })
+            .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(selected, vec![highest_hash_info; 3]);
+    }
+
     #[tokio::test]
     async fn generate_first_block_no_raft() {
         //
@@ -1754,11 +2521,251 @@ mod test {
     }
 
     #[tokio::test]
-    async fn generate_current_block_no_raft() {
+    async fn proposals_deferred_at_max_in_flight_no_raft() {
         //
         // Arrange
         //
-        let seed_utxo = [
+        let seed_utxo = ["000000", "000001", "000002"];
+        let mut node = new_test_node_with_max_in_flight(&seed_utxo, Some(2)).await;
+        let mut expected_block_addr_to_hashes = BTreeMap::new();
+        node.append_to_tx_pool(valid_transaction(
+            &["000000"],
+            &["000100"],
+            &mut expected_block_addr_to_hashes,
+        ));
+
+        //
+        // Act
+        //
+        node.propose_initial_item().await;
+        node.propose_pause_nodes(0).await;
+        let len_at_cap = node.proposed_in_flight_len();
+
+        node.propose_resume_nodes().await;
+        node.propose_local_transactions_at_timeout().await;
+        let len_deferred = node.proposed_in_flight_len();
+        let deferred_len = node.proposed_in_flight.deferred_len();
+        let local_tx_pool_len_deferred = node.local_tx_pool.len();
+
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await;
+        let len_after_commit = node.proposed_in_flight_len();
+        let deferred_len_after_commit = node.proposed_in_flight.deferred_len();
+
+        for _ in 0..2 {
+            let commit = node.next_commit().await.unwrap();
+            node.received_commit(commit).await;
+        }
+        let len_after_all_commits = node.proposed_in_flight_len();
+
+        node.propose_local_transactions_at_timeout().await;
+        let len_after_propose = node.proposed_in_flight_len();
+
+        //
+        // Assert
+        //
+        assert_eq!(len_at_cap, 2);
+        assert_eq!(len_deferred, 2);
+        assert_eq!(deferred_len, 1);
+        assert_eq!(local_tx_pool_len_deferred, 1);
+        assert_eq!(len_after_commit, 2);
+        assert_eq!(deferred_len_after_commit, 0);
+        assert_eq!(len_after_all_commits, 0);
+        assert_eq!(len_after_propose, 1);
+        assert_eq!(node.local_tx_pool.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn committed_proposal_records_latency_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001", "000002"];
+        let mut node = new_test_node(&seed_utxo).await;
+        let metrics = node.proposal_metrics();
+        let info_before = metrics.info();
+
+        //
+        // Act
+        //
+        node.propose_initial_item().await;
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await.unwrap();
+        let info_after = metrics.info();
+
+        //
+        // Assert
+        //
+        let bucket_counts: u64 = info_after.latency_buckets.iter().map(|b| b.count).sum();
+        assert_eq!(info_before.committed, 0);
+        assert_eq!(info_before.latency_samples, 0);
+        assert_eq!(info_after.committed, 1);
+        assert_eq!(info_after.latency_samples, 1);
+        assert!(info_after.latency_sum_us >= 2000);
+        assert_eq!(info_after.latency_max_us, info_after.latency_sum_us);
+        assert_eq!(bucket_counts, 1);
+    }
+
+    #[tokio::test]
+    async fn re_proposed_committed_item_not_applied_twice_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001", "000002"];
+        let mut node = new_test_node(&seed_utxo).await;
+        let mut expected_block_addr_to_hashes = BTreeMap::new();
+
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await.unwrap();
+
+        node.append_to_tx_pool(valid_transaction(
+            &["000000"],
+            &["000100"],
+            &mut expected_block_addr_to_hashes,
+        ));
+        node.propose_local_transactions_at_timeout().await;
+
+        //
+        // Act
+        //
+        // Leader change before the in flight transactions are seen committed:
+        // They are re-proposed while the original proposal also commits.
+        let new_leader = RaftCommit {
+            data: RaftCommitData::NewLeader,
+            ..RaftCommit::default()
+        };
+        let leader_change = node.received_commit(new_leader).await;
+        let commit = node.next_commit().await.unwrap();
+        let first_commit = node.received_commit(commit).await;
+        let commit = node.next_commit().await.unwrap();
+        let second_commit = node.received_commit(commit).await;
+
+        //
+        // Assert
+        //
+        assert_eq!(leader_change, None);
+        assert_eq!(first_commit, Some(CommittedItem::Transactions));
+        assert_eq!(second_commit, None);
+        assert_eq!(node.proposed_in_flight_len(), 0);
+        assert_eq!(node.proposed_tx_pool_len, 0);
+        assert_eq!(
+            node.consensused.tx_pool.len(),
+            expected_block_addr_to_hashes.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn propose_transactions_timeout_adapts_within_bounds_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001", "000002", "000003", "000004", "000005"];
+        let mut node = new_test_node(&seed_utxo).await;
+        node.propose_transactions_timeout_min = Duration::from_millis(10);
+        node.propose_transactions_timeout_max = Duration::from_millis(400);
+        node.proposed_tx_pool_len_max = 1;
+        let mut expected_block_addr_to_hashes = BTreeMap::new();
+
+        //
+        // Act
+        //
+        let mut idle_durations = Vec::new();
+        for _ in 0..5 {
+            node.propose_local_transactions_at_timeout().await;
+            idle_durations.push(node.propose_transactions_timeout_duration.as_millis());
+        }
+
+        node.append_to_tx_pool(valid_transaction(
+            &["000000", "000001", "000002", "000003", "000004", "000005"],
+            &["000100", "000101", "000102", "000103", "000104", "000105"],
+            &mut expected_block_addr_to_hashes,
+        ));
+        let mut burst_durations = Vec::new();
+        for _ in 0..7 {
+            node.propose_local_transactions_at_timeout().await;
+            burst_durations.push(node.propose_transactions_timeout_duration.as_millis());
+        }
+
+        //
+        // Assert
+        //
+        assert_eq!(idle_durations, vec![100, 200, 400, 400, 400]);
+        assert_eq!(burst_durations, vec![200, 100, 50, 25, 12, 10, 20]);
+    }
+
+    #[test]
+    fn transaction_timeouts_clamped_within_bounds() {
+        //
+        // Arrange
+        //
+        let timeouts = |timeout: usize, min: Option<usize>, max: Option<usize>| {
+            let (initial, min, max) = transaction_timeouts(&ComputeNodeConfig {
+                compute_transaction_timeout: timeout,
+                compute_transaction_timeout_min: min,
+                compute_transaction_timeout_max: max,
+                ..new_test_config(&[])
+            });
+            (initial.as_millis(), min.as_millis(), max.as_millis())
+        };
+
+        //
+        // Act
+        //
+        let actual = vec![
+            timeouts(100, None, None),
+            timeouts(100, Some(10), Some(400)),
+            timeouts(5, Some(10), Some(400)),
+            timeouts(500, Some(10), Some(400)),
+            timeouts(500, Some(10), None),
+        ];
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            actual,
+            vec![
+                (100, 100, 100),
+                (100, 10, 400),
+                (10, 10, 400),
+                (400, 10, 400),
+                (500, 10, 500),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn propose_initial_item_twice_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001", "000002"];
+        let mut node = new_test_node(&seed_utxo).await;
+
+        //
+        // Act
+        //
+        node.propose_initial_item().await;
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let first_block = node.received_commit(commit).await;
+
+        //
+        // Assert
+        //
+        assert_eq!(node.local_initial_proposal, None);
+        assert_eq!(first_block, Some(CommittedItem::FirstBlock));
+        assert_eq!(node.proposed_in_flight_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn generate_current_block_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = [
             // 1: (Skip "000003")
             "000000", "000001", "000002", // 2: (Skip "000012")
             "000010", "000011", // 3:
@@ -1812,16 +2819,22 @@ mod test {
         ));
         expected_unused_utxo_hashes.extend(["000010", "000011"]);
         // 3. Add double spend between DRUID droplet
-        // Keep first one added
-        node.append_to_tx_druid_pool(valid_transaction(
-            &["000020", "000023"],
-            &["000420", "000423"],
-            &mut expected_block_addr_to_hashes,
+        // Keep first one in canonical droplet order (lowest DRUID), not first one added
+        node.append_to_tx_druid_pool(with_druid(
+            valid_transaction(
+                &["000021", "000023"],
+                &["000521", "000523"],
+                &mut BTreeMap::new(),
+            ),
+            "DRUID0x02",
         ));
-        node.append_to_tx_druid_pool(valid_transaction(
-            &["000021", "000023"],
-            &["000521", "000523"],
-            &mut BTreeMap::new(),
+        node.append_to_tx_druid_pool(with_druid(
+            valid_transaction(
+                &["000020", "000023"],
+                &["000420", "000423"],
+                &mut expected_block_addr_to_hashes,
+            ),
+            "DRUID0x01",
         ));
         expected_unused_utxo_hashes.extend(["000021"]);
         // 4. Add double spend between DRUID droplet and transaction
@@ -1895,6 +2908,249 @@ mod test {
         assert_eq!(node.consensused.tx_current_block_previous_hash, None);
     }
 
+    #[tokio::test]
+    async fn generate_current_block_within_max_block_size_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001", "000002", "000003", "000004", "000005"];
+        let mut node = new_test_node(&seed_utxo).await;
+
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await.unwrap();
+
+        let mut large_txs: BTreeMap<String, Transaction> = valid_transaction(
+            &seed_utxo,
+            &["000100", "000101", "000102", "000103", "000104", "000105"],
+            &mut BTreeMap::new(),
+        );
+        for tx in large_txs.values_mut() {
+            tx.outputs[0].script_public_key = Some("0".repeat(10_000));
+        }
+        let tx_size = serialized_len(large_txs.values().next().unwrap());
+        let oversized_hash = large_txs.keys().next().unwrap().clone();
+        large_txs.get_mut(&oversized_hash).unwrap().outputs[0].script_public_key =
+            Some("0".repeat(30_000));
+        node.consensused.max_block_size = Some(tx_size * 5 / 2);
+        node.consensused.tx_pool.append(&mut large_txs);
+
+        //
+        // Act
+        //
+        let mut block = Block::new();
+        let mut block_tx = BTreeMap::new();
+        node.consensused
+            .update_current_block_tx(&mut block, &mut block_tx);
+
+        //
+        // Assert
+        //
+        let block_size: usize = block_tx.values().map(serialized_len).sum();
+        assert!(seed_utxo.len() < BLOCK_SIZE_IN_TX);
+        assert_eq!(block_tx.len(), 2);
+        assert_eq!(block.transactions.len(), 2);
+        assert!(block_size <= tx_size * 5 / 2);
+        assert!(!block_tx.contains_key(&oversized_hash));
+        assert!(!node.consensused.tx_pool.contains_key(&oversized_hash));
+        assert_eq!(node.consensused.tx_pool.len(), seed_utxo.len() - 3);
+    }
+
+    #[tokio::test]
+    async fn generate_current_block_drops_utxo_growing_near_cap_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001"];
+        let mut node = new_test_node(&seed_utxo).await;
+        node.consensused.utxo_set_size_cap = Some(UtxoSetSizeCap {
+            max_size: seed_utxo.len(),
+            net_reducing_margin: 0,
+        });
+
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await.unwrap();
+
+        let mut new_hashes = BTreeMap::new();
+        let mut txs = valid_transaction(&seed_utxo, &["000100", "000101"], &mut new_hashes);
+        let growing_hash = new_hashes["000101"].clone();
+        let growing_tx = txs.get_mut(&growing_hash).unwrap();
+        growing_tx.outputs.push(growing_tx.outputs[0].clone());
+        node.consensused.tx_pool.append(&mut txs);
+
+        //
+        // Act
+        //
+        let mut block = Block::new();
+        let mut block_tx = BTreeMap::new();
+        node.consensused
+            .update_current_block_tx(&mut block, &mut block_tx);
+
+        //
+        // Assert
+        //
+        assert_eq!(block.transactions, vec![new_hashes["000100"].clone()]);
+        assert!(!block_tx.contains_key(&growing_hash));
+        assert!(node.consensused.tx_pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn generate_current_block_drops_dust_output_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001"];
+        let mut node = new_test_node(&seed_utxo).await;
+        node.update_dust_threshold(TokenAmount(1));
+
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await.unwrap();
+
+        let mut new_hashes = BTreeMap::new();
+        let mut txs = valid_transaction(&seed_utxo, &["000100", "000101"], &mut new_hashes);
+        let dust_hash = new_hashes["000101"].clone();
+        txs.get_mut(&dust_hash).unwrap().outputs[0].value = Asset::Token(TokenAmount(0));
+        node.consensused.tx_pool.append(&mut txs);
+
+        //
+        // Act
+        //
+        let mut block = Block::new();
+        let mut block_tx = BTreeMap::new();
+        node.consensused
+            .update_current_block_tx(&mut block, &mut block_tx);
+
+        //
+        // Assert
+        //
+        assert_eq!(block.transactions, vec![new_hashes["000100"].clone()]);
+        assert!(!block_tx.contains_key(&dust_hash));
+        assert!(node.consensused.tx_pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn replace_by_fee_local_transaction_before_proposal_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001"];
+        let mut node = new_test_node(&seed_utxo).await;
+        node.replace_by_fee_delta = Some(TokenAmount(1));
+
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let _first_block = node.received_commit(commit).await.unwrap();
+        let previous_block = BlockStoredInfo {
+            block_hash: "0123".to_string(),
+            block_num: 0,
+            nonce: vec![0],
+            mining_transactions: BTreeMap::new(),
+            shutdown: false,
+        };
+
+        // Seed outputs are all spent: no fee unless an output is lowered
+        let original = valid_transaction(&["000000"], &["000100"], &mut BTreeMap::new());
+        let same_fee = valid_transaction(&["000000"], &["000200"], &mut BTreeMap::new());
+        let higher_fee: BTreeMap<String, Transaction> =
+            valid_transaction(&["000000"], &["000300"], &mut BTreeMap::new())
+                .into_values()
+                .map(|mut tx| {
+                    tx.outputs[0].value = Asset::Token(TokenAmount(0));
+                    (construct_tx_hash(&tx), tx)
+                })
+                .collect();
+
+        //
+        // Act
+        //
+        let mut commits = Vec::new();
+        node.append_to_tx_pool(original.clone());
+        node.append_to_tx_pool(same_fee);
+        let local_tx_pool_same_fee = node.local_tx_pool.clone();
+        node.append_to_tx_pool(higher_fee.clone());
+        let local_tx_pool = node.local_tx_pool.clone();
+
+        node.propose_local_transactions_at_timeout().await;
+        let commit = node.next_commit().await.unwrap();
+        commits.push(node.received_commit(commit).await.unwrap());
+        let committed_tx_pool = node.consensused.tx_pool.clone();
+
+        node.propose_block_with_last_info(previous_block).await;
+        let commit = node.next_commit().await.unwrap();
+        commits.push(node.received_commit(commit).await.unwrap());
+
+        //
+        // Assert
+        //
+        let actual_block_t_hashes = node
+            .get_mining_block()
+            .as_ref()
+            .map(|b| b.transactions.clone());
+        assert_eq!(
+            commits,
+            vec![CommittedItem::Transactions, CommittedItem::Block]
+        );
+        assert_eq!(local_tx_pool_same_fee, original);
+        assert_eq!(local_tx_pool, higher_fee);
+        assert_eq!(committed_tx_pool, higher_fee);
+        assert_eq!(
+            actual_block_t_hashes,
+            Some(higher_fee.keys().cloned().collect())
+        );
+    }
+
+    #[tokio::test]
+    async fn generate_current_block_droplet_order_independent_no_raft() {
+        //
+        // Arrange
+        //
+        let seed_utxo = ["000000", "000001", "000002", "000003"];
+        let droplets = vec![
+            valid_transaction(
+                &["000000", "000001"],
+                &["000100", "000101"],
+                &mut BTreeMap::new(),
+            ),
+            valid_transaction(
+                &["000002", "000001"],
+                &["000202", "000201"],
+                &mut BTreeMap::new(),
+            ),
+            valid_transaction(
+                &["000003", "000002"],
+                &["000303", "000302"],
+                &mut BTreeMap::new(),
+            ),
+        ];
+        let reversed_droplets: Vec<_> = droplets.iter().rev().cloned().collect();
+
+        //
+        // Act
+        //
+        let mut blocks = Vec::new();
+        for droplets in vec![droplets, reversed_droplets] {
+            let mut node = new_test_node(&seed_utxo).await;
+            node.propose_initial_item().await;
+            let commit = node.next_commit().await.unwrap();
+            let _first_block = node.received_commit(commit).await.unwrap();
+
+            let mut block = Block::new();
+            let mut block_tx = BTreeMap::new();
+            node.consensused.tx_druid_pool = droplets;
+            node.consensused
+                .update_committed_dde_tx(&mut block, &mut block_tx);
+            blocks.push((block.transactions, block_tx));
+        }
+
+        //
+        // Assert
+        //
+        assert_eq!(blocks[0], blocks[1]);
+        assert!(!blocks[0].1.is_empty());
+    }
+
     #[tokio::test]
     async fn in_flight_transactions_no_raft() {
         //
@@ -1982,7 +3238,109 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn first_block_genesis_mismatch_detected() {
+        //
+        // Arrange
+        //
+        let mut node = new_test_node(&["000000", "000001"]).await;
+        let mut other_node = new_test_node(&["000000", "000002"]).await;
+        other_node.set_key_run(1);
+        let other_genesis_hash = other_node.genesis_hash.clone();
+
+        //
+        // Act
+        //
+        other_node.propose_initial_item().await;
+        let other_commit = other_node.next_commit().await.unwrap();
+        let mismatch = node.received_commit(other_commit).await;
+
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        let first_block = node.received_commit(commit).await;
+
+        //
+        // Assert
+        //
+        assert_ne!(node.genesis_hash, other_genesis_hash);
+        assert_eq!(
+            mismatch,
+            Some(CommittedItem::GenesisMismatch {
+                local: node.genesis_hash.clone(),
+                proposed: other_genesis_hash,
+            })
+        );
+        assert_eq!(first_block, Some(CommittedItem::FirstBlock));
+    }
+
+    #[tokio::test]
+    async fn full_participant_intake_closes_before_timeout_no_raft() {
+        //
+        // Arrange
+        //
+        let mut node = new_test_node(&["000000"]).await;
+        node.close_full_participant_intake = true;
+        node.update_partition_full_size(2);
+        node.propose_initial_item().await;
+        let commit = node.next_commit().await.unwrap();
+        node.received_commit(commit).await;
+
+        let miners: Vec<SocketAddr> = vec![
+            "127.0.0.1:12300".parse().unwrap(),
+            "127.0.0.1:12301".parse().unwrap(),
+        ];
+
+        //
+        // Act
+        //
+        let mut timeout_reached = Vec::new();
+        for miner in &miners {
+            let status = MiningPipelineStatus::ParticipantOnlyIntake;
+            let item = MiningPipelineItem::MiningParticipant(*miner, status);
+            node.propose_mining_pipeline_item(item).await;
+            let commit = node.next_commit().await.unwrap();
+            node.received_commit(commit).await;
+
+            let wait = node.timeout_propose_mining_event();
+            let reached = time::timeout(Duration::from_millis(50), wait).await;
+            timeout_reached.push(reached.is_ok());
+        }
+
+        node.propose_mining_event_at_timeout().await;
+        let commit = node.next_commit().await.unwrap();
+        let phase_change = node.received_commit(commit).await;
+
+        //
+        // Assert
+        //
+        assert_eq!(timeout_reached, vec![false, true]);
+        assert_eq!(phase_change, Some(CommittedItem::StartPhasePowIntake));
+        assert_eq!(
+            node.get_mining_pipeline_status(),
+            &MiningPipelineStatus::AllItemsIntake
+        );
+    }
+
     async fn new_test_node(seed_utxo: &[&str]) -> ComputeRaft {
+        new_test_node_with_max_in_flight(seed_utxo, None).await
+    }
+
+    async fn new_test_node_with_max_in_flight(
+        seed_utxo: &[&str],
+        max_in_flight: Option<usize>,
+    ) -> ComputeRaft {
+        let compute_config = ComputeNodeConfig {
+            compute_raft_max_in_flight_proposals: max_in_flight,
+            ..new_test_config(seed_utxo)
+        };
+        let mut node = ComputeRaft::new(&compute_config, Default::default())
+            .await
+            .unwrap();
+        node.set_key_run(0);
+        node
+    }
+
+    fn new_test_config(seed_utxo: &[&str]) -> ComputeNodeConfig {
         let compute_node = NodeSpec {
             address: "0.0.0.0:0".parse().unwrap(),
         };
@@ -1991,29 +3349,62 @@ mod test {
                 .to_owned(),
             amount: TokenAmount(1),
         };
-        let compute_config = ComputeNodeConfig {
+        ComputeNodeConfig {
             compute_node_idx: 0,
             compute_db_mode: DbMode::InMemory,
             tls_config: Default::default(),
             api_keys: Default::default(),
+            api_signing_keys: Default::default(),
+            comms_format: Default::default(),
+            compatible_network_versions: Default::default(),
+            api_threaded_call_capacity: None,
+            api_threaded_call_timeout_ms: None,
             compute_unicorn_fixed_param: get_test_common_unicorn(),
             compute_nodes: vec![compute_node],
             storage_nodes: vec![],
+            compute_to_storage_mapping: Default::default(),
             user_nodes: vec![],
             compute_raft: 0,
             compute_raft_tick_timeout: 10,
+            compute_raft_election_min_ticks: None,
+            compute_raft_election_max_ticks: None,
             compute_mining_event_timeout: 500,
             compute_transaction_timeout: 50,
+            compute_transaction_timeout_min: None,
+            compute_transaction_timeout_max: None,
             compute_seed_utxo: seed_utxo
                 .iter()
                 .map(|v| (v.to_string(), vec![tx_out.clone()]))
                 .collect(),
             compute_genesis_tx_in: None,
             compute_partition_full_size: 1,
+            compute_partition_pow_difficulty: None,
             compute_minimum_miner_pool_len: 1,
+            compute_first_block_grace_ms: None,
+            compute_miner_max_missed_floods: None,
+            compute_block_stall_timeout: None,
+            compute_partition_random_num_rotation: None,
+            compute_close_full_participant_intake: None,
+            compute_winning_miner_fairness: None,
+            compute_db_tuning: None,
+            data_dir: None,
+            compute_min_block_reward: TokenAmount(0),
+            compute_min_tx_fee: TokenAmount(0),
+            compute_dust_threshold: TokenAmount(0),
+            compute_max_locktime_offset: None,
+            compute_replace_by_fee_delta: None,
+            compute_receipt_minters: None,
+            compute_reward_halving: None,
+            compute_max_block_size: None,
+            compute_utxo_set_size_cap: None,
             jurisdiction: "US".to_string(),
             sanction_list: Vec::new(),
             compute_api_use_tls: true,
+            api_bind_address: None,
+            api_disabled_routes: Default::default(),
+            api_compression: Default::default(),
+            log_level: None,
+            log_json: false,
             compute_api_port: 3003,
             routes_pow: Default::default(),
             backup_block_modulo: Default::default(),
@@ -2022,10 +3413,8 @@ mod test {
             enable_trigger_messages_pipeline_reset: Default::default(),
             compute_miner_whitelist: Default::default(),
             peer_limit: 1000,
-        };
-        let mut node = ComputeRaft::new(&compute_config, Default::default()).await;
-        node.set_key_run(0);
-        node
+            compute_raft_max_in_flight_proposals: None,
+        }
     }
 
     fn valid_transaction(
@@ -2049,6 +3438,21 @@ mod test {
         txs.into_iter().map(|(tx, _)| tx).collect()
     }
 
+    fn with_druid(
+        mut droplet: BTreeMap<String, Transaction>,
+        druid: &str,
+    ) -> BTreeMap<String, Transaction> {
+        let participants = droplet.len();
+        for tx in droplet.values_mut() {
+            tx.druid_info = Some(DdeValues {
+                druid: druid.to_owned(),
+                participants,
+                expectations: Vec::new(),
+            });
+        }
+        droplet
+    }
+
     pub fn key_with_max_value<'a>(
         map: &BTreeMap<String, String>,
         key1: &'a str,
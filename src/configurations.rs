@@ -1,11 +1,12 @@
 use crate::compute_raft::MinerWhitelist;
 use crate::db_utils::{CustomDbSpec, SimpleDb};
+use crate::interfaces::CommsFormat;
 use crate::wallet::WalletDb;
 use naom::primitives::asset::TokenAmount;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 
 pub type UtxoSetSpec = BTreeMap<String, Vec<TxOutSpec>>;
 
@@ -61,6 +62,24 @@ pub struct UnicornFixedInfo {
     pub security: u32,
 }
 
+/// Configuration info for the block reward halving schedule
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RewardHalvingSchedule {
+    /// Number of blocks after which the reward is halved
+    pub halving_interval: u64,
+    /// Reward before the first halving, derived from circulation if not set
+    pub base_reward: Option<u64>,
+}
+
+/// Configuration info for the UTXO set size cap
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UtxoSetSizeCap {
+    /// Maximum number of entries in the UTXO set
+    pub max_size: usize,
+    /// Number of entries below max_size from which transactions must not grow the UTXO set
+    pub net_reducing_margin: usize,
+}
+
 /// Configuration info for a TxOut
 #[derive(Debug, Clone, Deserialize)]
 pub struct TxOutSpec {
@@ -95,6 +114,26 @@ pub enum DbMode {
     InMemory,
 }
 
+/// Compaction style for a file database
+#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Eq)]
+pub enum DbCompactionStyle {
+    Level,
+    Universal,
+}
+
+/// Performance tuning for a file database: unset values keep rocksDB defaults
+#[derive(Default, Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct DbTuning {
+    /// Size in bytes of the LRU block cache
+    pub block_cache_size: Option<usize>,
+    /// Size in bytes of a single memtable
+    pub write_buffer_size: Option<usize>,
+    /// Compaction style
+    pub compaction_style: Option<DbCompactionStyle>,
+    /// Bits per key of the bloom filter
+    pub bloom_filter_bits: Option<i32>,
+}
+
 /// Configuration option for a compute node
 #[derive(Debug, Clone, Deserialize)]
 pub struct ComputeNodeConfig {
@@ -102,16 +141,37 @@ pub struct ComputeNodeConfig {
     pub compute_node_idx: usize,
     /// Use specific database
     pub compute_db_mode: DbMode,
+    /// Performance tuning for the compute file database
+    pub compute_db_tuning: Option<DbTuning>,
+    /// Base directory for the node databases, default location if not set
+    pub data_dir: Option<String>,
     /// Configuration for handling TLS
     pub tls_config: TlsSpec,
     /// Initial API keys
     pub api_keys: BTreeMap<String, Vec<String>>,
+    /// API keys that must sign their requests, with their hex encoded ed25519 public key
+    #[serde(default)]
+    pub api_signing_keys: BTreeMap<String, String>,
+    /// Serialization format requested to connected peers, bincode by default
+    #[serde(default)]
+    pub comms_format: CommsFormat,
+    /// Network versions, other than our own, accepted from connecting peers
+    #[serde(default)]
+    pub compatible_network_versions: Vec<u32>,
+    /// Number of API calls queued for the node before rejecting as overloaded
+    pub api_threaded_call_capacity: Option<usize>,
+    /// Time in milliseconds an API call waits on a busy node before giving up, 30000 if not set
+    pub api_threaded_call_timeout_ms: Option<u64>,
     /// Configuation for unicorn
     pub compute_unicorn_fixed_param: UnicornFixedInfo,
     /// All compute nodes addresses
     pub compute_nodes: Vec<NodeSpec>,
     /// All storage nodes addresses: only use first
     pub storage_nodes: Vec<NodeSpec>,
+    /// Storage node address to use for a given compute node address,
+    /// the storage node at the same index is used if not mapped
+    #[serde(default)]
+    pub compute_to_storage_mapping: BTreeMap<SocketAddr, SocketAddr>,
     /// All user nodes addresses
     pub user_nodes: Vec<NodeSpec>,
     /// Whether compute node will use raft or act independently (0)
@@ -120,20 +180,92 @@ pub struct ComputeNodeConfig {
     pub compute_api_port: u16,
     /// API use TLS
     pub compute_api_use_tls: bool,
+    /// IP address to bind the API to, all interfaces if not set
+    pub api_bind_address: Option<IpAddr>,
+    /// Names of the API routes to disable
+    #[serde(default)]
+    pub api_disabled_routes: BTreeSet<String>,
+    /// Compress API responses with gzip or deflate when accepted by the client
+    #[serde(default)]
+    pub api_compression: bool,
+    /// Default log filter directives per module, e.g. "info,znp::compute=debug", RUST_LOG takes precedence.
+    /// Invalid directives fail the node at startup
+    pub log_level: Option<String>,
+    /// Emit logs as one JSON object per line instead of the human readable format
+    #[serde(default)]
+    pub log_json: bool,
     /// Timeout for ticking raft
     pub compute_raft_tick_timeout: usize,
+    /// Minimum raft election timeout as a number of ticks, raft default if not set
+    pub compute_raft_election_min_ticks: Option<usize>,
+    /// Maximum raft election timeout as a number of ticks, twice the minimum if not set
+    pub compute_raft_election_max_ticks: Option<usize>,
     /// Timeout duration between mining event pipelines
     pub compute_mining_event_timeout: usize,
     /// Timeout duration between committing transactions
     pub compute_transaction_timeout: usize,
+    /// Minimum adaptive timeout duration between committing transactions
+    pub compute_transaction_timeout_min: Option<usize>,
+    /// Maximum adaptive timeout duration between committing transactions
+    pub compute_transaction_timeout_max: Option<usize>,
     /// Transaction hash and TxOut info to use to seed utxo
     pub compute_seed_utxo: UtxoSetSpec,
     /// String to use for genesis block TxIn
     pub compute_genesis_tx_in: Option<String>,
     /// Partition full size
     pub compute_partition_full_size: usize,
+    /// Difficulty of the PoW for partition entry, block difficulty if not set
+    pub compute_partition_pow_difficulty: Option<usize>,
     /// Minimum miner pool size
     pub compute_minimum_miner_pool_len: usize,
+    /// Time to let more miners join once the minimum pool is reached before proposing
+    /// the first block, proposed immediately if not set
+    pub compute_first_block_grace_ms: Option<usize>,
+    /// Consecutive floods a miner can leave unanswered before eviction, never evicted if not set
+    pub compute_miner_max_missed_floods: Option<usize>,
+    /// Time in milliseconds without a committed block before block production is reported
+    /// stalled, never if not set
+    pub compute_block_stall_timeout: Option<usize>,
+    /// Time between rotations of the partition PoW random number, only renewed per block if not set
+    pub compute_partition_random_num_rotation: Option<usize>,
+    /// Close the participant intake as soon as this node's list reaches the partition full size,
+    /// instead of waiting for the mining event timeout
+    pub compute_close_full_participant_intake: Option<bool>,
+    /// Percentage (0-100) moving the winning miner selection from one chance per PoW entry
+    /// towards an equal chance per miner, pure PoW entry selection if not set:
+    /// initial value, then only updated through the shared config
+    pub compute_winning_miner_fairness: Option<u64>,
+    /// Minimum reward per compute node for a mined block:
+    /// initial value, then only updated through the shared config
+    #[serde(default, deserialize_with = "deserialize_token_amount")]
+    pub compute_min_block_reward: TokenAmount,
+    /// Minimum fee, inputs minus outputs, of transactions accepted in this node's transaction pool.
+    /// Mempool-only policy not consensused: blocks from peers are not checked against it
+    #[serde(default, deserialize_with = "deserialize_token_amount")]
+    pub compute_min_tx_fee: TokenAmount,
+    /// Maximum number of blocks past the current one a transaction output may be locked until,
+    /// unbounded if not set
+    pub compute_max_locktime_offset: Option<u64>,
+    /// Minimum amount of a token output, except for coinbase:
+    /// initial value, then only updated through the shared config
+    #[serde(default, deserialize_with = "deserialize_token_amount")]
+    pub compute_dust_threshold: TokenAmount,
+    /// Minimum fee increase for a transaction not yet proposed to be replaced by one spending
+    /// the same inputs, transactions are never replaced if not set.
+    /// Proposed transactions are never replaced, even before they are included in a block
+    #[serde(default, deserialize_with = "deserialize_opt_token_amount")]
+    pub compute_replace_by_fee_delta: Option<TokenAmount>,
+    /// Hex encoded public keys allowed to create receipt assets, anyone may if not set
+    pub compute_receipt_minters: Option<Vec<String>>,
+    /// Schedule halving the block reward, no halving if not set:
+    /// initial value, then only updated through the shared config
+    pub compute_reward_halving: Option<RewardHalvingSchedule>,
+    /// Maximum serialized size in bytes of the transactions in a block, BLOCK_SIZE if not set:
+    /// initial value, then only updated through the shared config
+    pub compute_max_block_size: Option<usize>,
+    /// Cap on the UTXO set size, unbounded if not set:
+    /// initial value, then only updated through the shared config
+    pub compute_utxo_set_size_cap: Option<UtxoSetSizeCap>,
     /// Node's legal jurisdiction
     pub jurisdiction: String,
     /// Node's address sanction list
@@ -152,6 +284,8 @@ pub struct ComputeNodeConfig {
     pub compute_miner_whitelist: MinerWhitelist,
     /// Limit for the number of peers this node can have
     pub peer_limit: usize,
+    /// Maximum number of raft proposals in flight, unbounded if not set
+    pub compute_raft_max_in_flight_proposals: Option<usize>,
 }
 
 /// Configuration option for a compute node that can be shared across peers
@@ -163,6 +297,25 @@ pub struct ComputeNodeSharedConfig {
     pub compute_partition_full_size: usize,
     /// Miner whitelisting
     pub compute_miner_whitelist: MinerWhitelist,
+    /// Maximum serialized size in bytes of the transactions in a block, BLOCK_SIZE if not set
+    #[serde(default)]
+    pub compute_max_block_size: Option<usize>,
+    /// Cap on the UTXO set size, unbounded if not set
+    #[serde(default)]
+    pub compute_utxo_set_size_cap: Option<UtxoSetSizeCap>,
+    /// Minimum amount of a token output, except for coinbase
+    #[serde(default)]
+    pub compute_dust_threshold: TokenAmount,
+    /// Minimum reward per compute node for a mined block
+    #[serde(default)]
+    pub compute_min_block_reward: TokenAmount,
+    /// Schedule halving the block reward, no halving if not set
+    #[serde(default)]
+    pub compute_reward_halving: Option<RewardHalvingSchedule>,
+    /// Percentage (0-100) moving the winning miner selection towards an equal chance per miner,
+    /// pure PoW entry selection if not set
+    #[serde(default)]
+    pub compute_winning_miner_fairness: Option<u64>,
 }
 
 /// Configuration option for a storage node
@@ -172,22 +325,55 @@ pub struct StorageNodeConfig {
     pub storage_node_idx: usize,
     /// Use specific database
     pub storage_db_mode: DbMode,
+    /// Base directory for the node databases, default location if not set
+    pub data_dir: Option<String>,
     /// Configuration for handling TLS
     pub tls_config: TlsSpec,
     /// Initial API keys
     pub api_keys: BTreeMap<String, Vec<String>>,
+    /// API keys that must sign their requests, with their hex encoded ed25519 public key
+    #[serde(default)]
+    pub api_signing_keys: BTreeMap<String, String>,
+    /// Serialization format requested to connected peers, bincode by default
+    #[serde(default)]
+    pub comms_format: CommsFormat,
+    /// Network versions, other than our own, accepted from connecting peers
+    #[serde(default)]
+    pub compatible_network_versions: Vec<u32>,
     /// All compute nodes addresses
     pub compute_nodes: Vec<NodeSpec>,
     /// All storage nodes addresses: only use first
     pub storage_nodes: Vec<NodeSpec>,
+    /// Storage node address to use for a given compute node address,
+    /// the compute node at the same index is used if not mapped
+    #[serde(default)]
+    pub compute_to_storage_mapping: BTreeMap<SocketAddr, SocketAddr>,
     /// Whether storage node will use raft or act independently (0)
     pub storage_raft: usize,
     /// API port
     pub storage_api_port: u16,
     /// API use TLS
     pub storage_api_use_tls: bool,
+    /// IP address to bind the API to, all interfaces if not set
+    pub api_bind_address: Option<IpAddr>,
+    /// Names of the API routes to disable
+    #[serde(default)]
+    pub api_disabled_routes: BTreeSet<String>,
+    /// Compress API responses with gzip or deflate when accepted by the client
+    #[serde(default)]
+    pub api_compression: bool,
+    /// Default log filter directives per module, e.g. "info,znp::compute=debug", RUST_LOG takes precedence.
+    /// Invalid directives fail the node at startup
+    pub log_level: Option<String>,
+    /// Emit logs as one JSON object per line instead of the human readable format
+    #[serde(default)]
+    pub log_json: bool,
     /// Timeout for ticking raft
     pub storage_raft_tick_timeout: usize,
+    /// Minimum raft election timeout as a number of ticks, raft default if not set
+    pub storage_raft_election_min_ticks: Option<usize>,
+    /// Maximum raft election timeout as a number of ticks, twice the minimum if not set
+    pub storage_raft_election_max_ticks: Option<usize>,
     /// Timeout for fetch catchup
     pub storage_catchup_duration: usize,
     // Routes that require PoW validation and their corresponding difficulties
@@ -198,6 +384,20 @@ pub struct StorageNodeConfig {
     pub backup_restore: Option<bool>,
     /// Limit for the number of peers this node can have
     pub peer_limit: usize,
+    /// Maximum number of raft proposals in flight, unbounded if not set
+    pub storage_raft_max_in_flight_proposals: Option<usize>,
+    /// Performance tuning for the storage file database
+    pub storage_db_tuning: Option<DbTuning>,
+    /// Persist a full UTXO set checkpoint every given number of blocks, off if not set
+    pub storage_utxo_checkpoint_interval: Option<u64>,
+    /// Index transactions by affected address, off if not set as it grows with the chain
+    pub storage_address_index: Option<bool>,
+    /// Flush the database before acknowledging a stored block to compute, off if not set
+    pub storage_sync_block_ack: Option<bool>,
+    /// Time in milliseconds a latest block long-poll waits for a new block, default if not set
+    pub storage_api_longpoll_timeout_ms: Option<u64>,
+    /// Maximum number of latest block long-polls pending at once, default if not set
+    pub storage_api_longpoll_max_pending: Option<usize>,
 }
 
 /// Configuration option for a storage node
@@ -207,10 +407,25 @@ pub struct MinerNodeConfig {
     pub miner_address: SocketAddr,
     /// Use specific database
     pub miner_db_mode: DbMode,
+    /// Base directory for the node databases, default location if not set
+    pub data_dir: Option<String>,
     /// Configuration for handling TLS
     pub tls_config: TlsSpec,
     /// Initial API keys
     pub api_keys: BTreeMap<String, Vec<String>>,
+    /// API keys that must sign their requests, with their hex encoded ed25519 public key
+    #[serde(default)]
+    pub api_signing_keys: BTreeMap<String, String>,
+    /// Serialization format requested to connected peers, bincode by default
+    #[serde(default)]
+    pub comms_format: CommsFormat,
+    /// Network versions, other than our own, accepted from connecting peers
+    #[serde(default)]
+    pub compatible_network_versions: Vec<u32>,
+    /// Number of API calls queued for the node before rejecting as overloaded
+    pub api_threaded_call_capacity: Option<usize>,
+    /// Time in milliseconds an API call waits on a busy node before giving up, 30000 if not set
+    pub api_threaded_call_timeout_ms: Option<u64>,
     /// Index of the compute node to use in compute_nodes
     pub miner_compute_node_idx: usize,
     /// All compute nodes addresses
@@ -219,8 +434,34 @@ pub struct MinerNodeConfig {
     pub miner_api_port: u16,
     /// API use TLS
     pub miner_api_use_tls: bool,
+    /// IP address to bind the API to, all interfaces if not set
+    pub api_bind_address: Option<IpAddr>,
+    /// Names of the API routes to disable
+    #[serde(default)]
+    pub api_disabled_routes: BTreeSet<String>,
+    /// Compress API responses with gzip or deflate when accepted by the client
+    #[serde(default)]
+    pub api_compression: bool,
+    /// Default log filter directives per module, e.g. "info,znp::compute=debug", RUST_LOG takes precedence.
+    /// Invalid directives fail the node at startup
+    pub log_level: Option<String>,
+    /// Emit logs as one JSON object per line instead of the human readable format
+    #[serde(default)]
+    pub log_json: bool,
     /// Option of the passphrase used for encryption
     pub passphrase: Option<String>,
+    /// Maximum number of active wallet addresses before archiving unused ones
+    pub wallet_max_active_addresses: Option<usize>,
+    /// Whether to encrypt all wallet values at rest, not only the address keys
+    pub wallet_encrypted_at_rest: Option<bool>,
+    /// Minimum length of a new wallet passphrase
+    pub wallet_passphrase_min_length: Option<usize>,
+    /// Minimum estimated entropy in bits of a new wallet passphrase
+    pub wallet_passphrase_min_entropy_bits: Option<u32>,
+    /// Key derivation iterations used when sealing the wallet master key
+    pub wallet_kdf_iterations: Option<u32>,
+    /// Performance tuning for the wallet file database
+    pub wallet_db_tuning: Option<DbTuning>,
     // Routes that require PoW validation and their corresponding difficulties
     pub routes_pow: BTreeMap<String, usize>,
     /// Backup block that given modulo result in 0
@@ -229,6 +470,8 @@ pub struct MinerNodeConfig {
     pub backup_restore: Option<bool>,
     /// When provided, all new coinbase transactions will be assigned to this address
     pub static_miner_address: Option<String>,
+    /// When provided, coinbase transactions pay to this address instead of a wallet address
+    pub miner_reward_address: Option<String>,
     /// When provided, the miner will use this API key to participate in mining
     pub mining_api_key: Option<String>,
     /// Limit for the number of peers this node can have
@@ -244,10 +487,25 @@ pub struct UserNodeConfig {
     pub user_address: SocketAddr,
     /// Use specific database
     pub user_db_mode: DbMode,
+    /// Base directory for the node databases, default location if not set
+    pub data_dir: Option<String>,
     /// Configuration for handling TLS
     pub tls_config: TlsSpec,
     /// Initial API keys
     pub api_keys: BTreeMap<String, Vec<String>>,
+    /// API keys that must sign their requests, with their hex encoded ed25519 public key
+    #[serde(default)]
+    pub api_signing_keys: BTreeMap<String, String>,
+    /// Serialization format requested to connected peers, bincode by default
+    #[serde(default)]
+    pub comms_format: CommsFormat,
+    /// Network versions, other than our own, accepted from connecting peers
+    #[serde(default)]
+    pub compatible_network_versions: Vec<u32>,
+    /// Number of API calls queued for the node before rejecting as overloaded
+    pub api_threaded_call_capacity: Option<usize>,
+    /// Time in milliseconds an API call waits on a busy node before giving up, 30000 if not set
+    pub api_threaded_call_timeout_ms: Option<u64>,
     /// Index of the compute node to use in compute_nodes
     pub user_compute_node_idx: usize,
     /// All compute nodes addresses
@@ -256,13 +514,45 @@ pub struct UserNodeConfig {
     pub user_api_port: u16,
     /// API use TLS
     pub user_api_use_tls: bool,
+    /// IP address to bind the API to, all interfaces if not set
+    pub api_bind_address: Option<IpAddr>,
+    /// Names of the API routes to disable
+    #[serde(default)]
+    pub api_disabled_routes: BTreeSet<String>,
+    /// Compress API responses with gzip or deflate when accepted by the client
+    #[serde(default)]
+    pub api_compression: bool,
+    /// Default log filter directives per module, e.g. "info,znp::compute=debug", RUST_LOG takes precedence.
+    /// Invalid directives fail the node at startup
+    pub log_level: Option<String>,
+    /// Emit logs as one JSON object per line instead of the human readable format
+    #[serde(default)]
+    pub log_json: bool,
     /// Wallet seeds
     pub user_wallet_seeds: Vec<WalletTxSpec>,
     /// Option of the passphrase used for encryption
     pub passphrase: Option<String>,
+    /// Maximum number of active wallet addresses before archiving unused ones
+    pub wallet_max_active_addresses: Option<usize>,
+    /// Whether to encrypt all wallet values at rest, not only the address keys
+    pub wallet_encrypted_at_rest: Option<bool>,
+    /// Minimum length of a new wallet passphrase
+    pub wallet_passphrase_min_length: Option<usize>,
+    /// Minimum estimated entropy in bits of a new wallet passphrase
+    pub wallet_passphrase_min_entropy_bits: Option<u32>,
+    /// Key derivation iterations used when sealing the wallet master key
+    pub wallet_kdf_iterations: Option<u32>,
+    /// Performance tuning for the wallet file database
+    pub wallet_db_tuning: Option<DbTuning>,
     /// Will donate amount to all unkown incomming payment request.
     /// Only enable in test net for the distribution users.
     pub user_auto_donate: u64,
+    /// Maximum amount auto donated to a single requesting IP address, unlimited if not set
+    pub user_auto_donate_max_per_peer: Option<u64>,
+    /// Maximum amount auto donated to all requesting peers, unlimited if not set
+    pub user_auto_donate_max_total: Option<u64>,
+    /// Window in seconds after which the auto donation caps reset, never reset if not set
+    pub user_auto_donate_window_secs: Option<u64>,
     /// Configuration options for auto generating transactions for test
     pub user_test_auto_gen_setup: UserAutoGenTxSetup,
     // Routes that require PoW validation and their corresponding difficulties
@@ -288,12 +578,18 @@ pub struct PreLaunchNodeConfig {
     pub storage_node_idx: usize,
     /// Use specific database
     pub storage_db_mode: DbMode,
+    /// Base directory for the node databases, default location if not set
+    pub data_dir: Option<String>,
     /// All compute nodes addresses
     pub compute_nodes: Vec<NodeSpec>,
     /// All storage nodes addresses: only use first
     pub storage_nodes: Vec<NodeSpec>,
     /// Limit for the number of peers this node can have
     pub peer_limit: usize,
+    /// Port to serve the pre-launch status API from, no API if not set
+    pub pre_launch_api_port: Option<u16>,
+    /// IP address to bind the API to, all interfaces if not set
+    pub api_bind_address: Option<IpAddr>,
 }
 
 /// Type of node in pre-launch mode
@@ -334,3 +630,11 @@ fn deserialize_token_amount<'de, D: serde::Deserializer<'de>>(
     let value: u64 = serde::Deserialize::deserialize(deserializer)?;
     Ok(TokenAmount(value))
 }
+
+///Hacky deserializer to work around deserializatio error with u128
+fn deserialize_opt_token_amount<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<TokenAmount>, D::Error> {
+    let value: Option<u64> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(value.map(TokenAmount))
+}
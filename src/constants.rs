@@ -1,4 +1,5 @@
 pub use naom::constants::*;
+use std::ops::RangeInclusive;
 
 /*------- BLOCK CONSTANTS --------*/
 
@@ -64,6 +65,12 @@ pub const SANC_LIST_PROD: &str = "src/db/sanc_list.json";
 /// Path to test sanction list
 pub const SANC_LIST_TEST: &str = "src/db/sanc_list_test.json";
 
+/*------- TRANSACTION CONSTANTS -------*/
+
+/// Range of transaction versions this node is able to validate.
+/// The upper bound must be bumped when an upgrade introduces a new transaction version.
+pub const SUPPORTED_TX_VERSIONS: RangeInclusive<usize> = 0..=(NETWORK_VERSION as usize);
+
 /*------- LIMIT CONSTANTS -------*/
 
 /// Maximum number of attempts to resend trigger messages before proposing to reset the mining pipeline
@@ -72,6 +79,9 @@ pub const RESEND_TRIGGER_MESSAGES_COMPUTE_LIMIT: usize = 10;
 /// Limit for the transaction pool per compute node
 pub const TX_POOL_LIMIT: usize = 10_000_000;
 
+/// Number of most recent winning miner payouts kept by a compute node
+pub const MINER_PAYOUT_HISTORY_LEN: usize = 100;
+
 /// Limit for the number of PoWs a compute node may have for UnicornShard creation
 pub const UNICORN_LIMIT: usize = 5;
 
@@ -94,6 +104,18 @@ pub const OLD_BACKUP_COUNT: usize = 5;
 /// TODO: Update to 5 once locktime tests are introduced
 pub const COINBASE_MATURITY: u64 = if cfg!(test) { 0 } else { 100 };
 
+/// Default number of threaded calls queued for a node before rejecting new ones
+pub const THREADED_CALL_CHANNEL_CAPACITY: usize = 100;
+
+/// Default time in milliseconds an API call waits on a busy node before giving up
+pub const API_THREADED_CALL_TIMEOUT_MS: u64 = 30_000;
+
+/// Default time in milliseconds a latest block long-poll waits for a new block
+pub const API_LATEST_BLOCK_LONGPOLL_TIMEOUT_MS: u64 = 30_000;
+
+/// Default maximum number of latest block long-polls pending at once
+pub const API_LATEST_BLOCK_LONGPOLL_MAX_PENDING: usize = 1000;
+
 /*------- TESTS -------*/
 
 #[cfg(test)]
@@ -1,13 +1,18 @@
-use crate::configurations::DbMode;
+use crate::configurations::{DbCompactionStyle, DbMode, DbTuning};
 use crate::constants::{
     DB_PATH_LIVE, DB_PATH_TEST, DB_VERSION_KEY, NETWORK_VERSION_SERIALIZED, OLD_BACKUP_COUNT,
 };
 use rocksdb::backup::{BackupEngine, BackupEngineOptions};
-use rocksdb::{DBCompressionType, IteratorMode, Options, WriteBatch, DB};
+use rocksdb::{
+    BlockBasedOptions, DBCompactionStyle, DBCompressionType, Direction, IteratorMode, Options,
+    WriteBatch, DB,
+};
 pub use rocksdb::{Error as DBError, DEFAULT_COLUMN_FAMILY_NAME as DB_COL_DEFAULT};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Arc;
 use std::{error::Error, fmt};
-use tracing::{debug, warn};
+use tracing::{debug, error, warn};
 
 pub type DbIteratorItem = (Vec<u8>, Vec<u8>);
 pub type InMemoryWriteBatch = Vec<(usize, Vec<u8>, Option<Vec<u8>>)>;
@@ -32,30 +37,78 @@ pub struct CustomDbSpec {
 }
 
 #[derive(Debug)]
-pub struct SimpleDbError(String);
+pub enum SimpleDbError {
+    /// Database failure
+    Other(String),
+    /// File database could not be opened as corrupt: can be repaired with `repair_db`
+    CorruptNeedsRepair(String),
+    /// File database could not be opened as locked by another process
+    Locked(String),
+}
 
 impl Error for SimpleDbError {
     fn description(&self) -> &str {
-        &self.0
+        self.message()
     }
 }
 
 impl fmt::Display for SimpleDbError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        self.0.fmt(formatter)
+        match self {
+            Self::Other(err) => err.fmt(formatter),
+            Self::CorruptNeedsRepair(err) => write!(formatter, "Corrupt db: {err}"),
+            Self::Locked(err) => write!(formatter, "Locked db: {err}"),
+        }
     }
 }
 
 impl From<DBError> for SimpleDbError {
     fn from(other: DBError) -> Self {
-        Self(other.into_string())
+        Self::Other(other.into_string())
     }
 }
 
 impl SimpleDbError {
     pub fn into_string(self) -> String {
-        self.0
+        match self {
+            Self::Other(err) | Self::CorruptNeedsRepair(err) | Self::Locked(err) => err,
+        }
     }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::Other(err) | Self::CorruptNeedsRepair(err) | Self::Locked(err) => err,
+        }
+    }
+
+    /// Classify an error opening the file database at the given path
+    fn from_open_error(path: &str, other: DBError) -> Self {
+        let err = other.into_string();
+        if err.starts_with("Corruption") {
+            error!("Db at {} is corrupt and needs repair: {}", path, err);
+            Self::CorruptNeedsRepair(err)
+        } else if err.starts_with("IO error") && err.contains("LOCK") {
+            error!("Db at {} is locked by another process: {}", path, err);
+            Self::Locked(err)
+        } else {
+            Self::Other(err)
+        }
+    }
+}
+
+/// Size information for a database: estimated for rocksDB, exact in memory
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbMetrics {
+    pub approximate_size: u64,
+    pub key_count_per_column: BTreeMap<String, u64>,
+}
+
+/// Outcome of a validated batch write
+pub enum BatchOutcome<T> {
+    /// Validation approved: the batch was written
+    Written(T),
+    /// Validation rejected: the batch was not written
+    Rejected(SimpleDbWriteBatchDone),
 }
 
 /// Database that can store in memory or using rocksDB.
@@ -64,7 +117,7 @@ pub enum SimpleDb {
         options: Options,
         path: String,
         columns: BTreeSet<String>,
-        db: DB,
+        db: Arc<DB>,
     },
     InMemory {
         columns: InMemoryColumns,
@@ -98,21 +151,27 @@ impl Drop for SimpleDb {
 impl SimpleDb {
     /// Create rocksDB
     pub fn new_file(path: String, columns: &[&str]) -> Result<Self> {
+        Self::new_file_with_tuning(path, columns, &Default::default())
+    }
+
+    /// Create rocksDB with the given performance tuning
+    pub fn new_file_with_tuning(path: String, columns: &[&str], tuning: &DbTuning) -> Result<Self> {
         debug!("Open/Create Db at {}", path);
         let columns = [DB_COL_DEFAULT].iter().chain(columns.iter());
-        let mut options = get_db_options();
+        let mut options = get_db_options(tuning);
 
         if let Ok(old_columns) = DB::list_cf(&options, &path) {
             let c_old = old_columns.iter().map(|k| k.as_str());
             let c_new = columns.copied();
 
             check_old_includes_new(c_old, c_new)?;
-            let db = DB::open_cf(&options, path.clone(), &old_columns)?;
+            let db = DB::open_cf(&options, path.clone(), &old_columns)
+                .map_err(|e| SimpleDbError::from_open_error(&path, e))?;
             Ok(Self::File {
                 columns: old_columns.into_iter().collect(),
                 options,
                 path,
-                db,
+                db: Arc::new(db),
             })
         } else {
             // Allow create empty db with required column families.
@@ -121,12 +180,13 @@ impl SimpleDb {
             options.create_if_missing(true);
             options.create_missing_column_families(true);
 
-            let db = DB::open_cf(&options, path.clone(), columns.clone())?;
+            let db = DB::open_cf(&options, path.clone(), columns.clone())
+                .map_err(|e| SimpleDbError::from_open_error(&path, e))?;
             with_initial_data(Self::File {
                 columns: columns.map(|k| k.to_string()).collect(),
                 options,
                 path,
-                db,
+                db: Arc::new(db),
             })
         }
     }
@@ -196,6 +256,46 @@ impl SimpleDb {
         }
     }
 
+    /// Return a copy of the db content as an in memory db, for any db kind
+    pub fn cloned_as_in_memory(&self) -> Self {
+        let columns: InMemoryColumns = self
+            .open_columns()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, cf)| (cf, idx))
+            .collect();
+        let key_values = columns
+            .keys()
+            .map(|cf| self.iter_cf_clone_pvt(cf).collect())
+            .collect();
+        Self::InMemory {
+            columns,
+            key_values,
+        }
+    }
+
+    /// Replace the whole db content with the content of other in a single write.
+    /// All columns of other must be open in this db.
+    ///
+    /// ### Arguments
+    ///
+    /// * `other` - db to take the content from
+    pub fn replace_content_with(&mut self, other: &SimpleDb) -> Result<()> {
+        let mut writter = self.batch_writer();
+        for cf in self.open_columns() {
+            for (key, _) in self.iter_cf_clone_pvt(&cf) {
+                writter.delete_cf_checked(&cf, key)?;
+            }
+        }
+        for (cf, items) in other.iter_all_cf_clone() {
+            for (key, value) in items {
+                writter.put_cf_checked(&cf, key, value)?;
+            }
+        }
+        let writter = writter.done();
+        self.write(writter)
+    }
+
     /// Backup path for file db
     pub fn file_backup_path(&self) -> Option<String> {
         if let Self::File { path, .. } = &self {
@@ -223,6 +323,36 @@ impl SimpleDb {
         Ok(())
     }
 
+    /// Flush the file db memtables to disk, no-op for in memory db
+    pub fn flush(&self) -> Result<()> {
+        if let Self::File { db, .. } = &self {
+            db.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Compact all columns of the file db, no-op for in memory db
+    pub fn compact(&self) -> Result<()> {
+        if let Some(handle) = self.compaction_handle() {
+            handle.compact();
+        }
+
+        Ok(())
+    }
+
+    /// Handle to flush and compact the file db without holding on to it,
+    /// None for in memory db
+    pub fn compaction_handle(&self) -> Option<DbCompactionHandle> {
+        match self {
+            Self::File { db, columns, .. } => Some(DbCompactionHandle {
+                db: db.clone(),
+                columns: columns.iter().cloned().collect(),
+            }),
+            Self::InMemory { .. } => None,
+        }
+    }
+
     /// Create a column as part of an upgrade if not already open
     pub fn upgrade_create_missing_cf(&mut self, name: &'static str) -> Result<()> {
         match self {
@@ -233,6 +363,9 @@ impl SimpleDb {
                 ..
             } => {
                 if db.cf_handle(name).is_none() {
+                    let db = Arc::get_mut(db).ok_or_else(|| {
+                        SimpleDbError::Other("Cannot upgrade a database in use".to_owned())
+                    })?;
                     db.create_cf(name, options)?;
                     columns.insert(name.to_owned());
                 }
@@ -255,7 +388,7 @@ impl SimpleDb {
         match self {
             Self::File { db, .. } => SimpleDbWriteBatch::File {
                 write: Default::default(),
-                db,
+                db: db.as_ref(),
             },
             Self::InMemory { columns, .. } => SimpleDbWriteBatch::InMemory {
                 write: Default::default(),
@@ -290,6 +423,31 @@ impl SimpleDb {
         Ok(())
     }
 
+    /// Build a batch across columns and only write it if validation approves it.
+    /// Validation can read the database as it was before the batch.
+    ///
+    /// ### Arguments
+    ///
+    /// * `build`    - Add put/delete to the batch, returning a value for validation
+    /// * `validate` - Approve the batch given the database and the build value
+    pub fn try_batch<T>(
+        &mut self,
+        build: impl FnOnce(&SimpleDb, &mut SimpleDbWriteBatch) -> T,
+        validate: impl FnOnce(&SimpleDb, &T) -> bool,
+    ) -> Result<BatchOutcome<T>> {
+        let (value, batch) = {
+            let mut batch = self.batch_writer();
+            let value = build(self, &mut batch);
+            (value, batch.done())
+        };
+
+        if !validate(self, &value) {
+            return Ok(BatchOutcome::Rejected(batch));
+        }
+        self.write(batch)?;
+        Ok(BatchOutcome::Written(value))
+    }
+
     /// Write batch imported items to database, if error nothing added.
     ///
     /// ### Arguments
@@ -381,6 +539,33 @@ impl SimpleDb {
         }
     }
 
+    /// Get the entry with the greatest key not after the given key
+    ///
+    /// ### Arguments
+    ///
+    /// * `cf`  - The column family to use
+    /// * `key` - Upper bound of the key to find
+    pub fn get_cf_at_or_before(&self, cf: &'static str, key: &[u8]) -> Option<DbIteratorItem> {
+        match self {
+            Self::File { db, .. } => {
+                let cf = db.cf_handle(cf).unwrap();
+                db.iterator_cf(cf, IteratorMode::From(key, Direction::Reverse))
+                    .next()
+                    .map(|(k, v)| (k.to_vec(), v.to_vec()))
+            }
+            Self::InMemory {
+                key_values,
+                columns,
+            } => {
+                let cf = columns.get(cf).unwrap();
+                key_values[*cf]
+                    .range(..=key.to_vec())
+                    .next_back()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+            }
+        }
+    }
+
     /// Count entries from database
     pub fn count_cf(&self, cf: &'static str) -> usize {
         match self {
@@ -403,6 +588,35 @@ impl SimpleDb {
         self.iter_cf_clone_pvt(cf)
     }
 
+    /// Get entries with keys starting with the given prefix as iterable db items
+    pub fn iter_cf_prefix_clone<'a>(
+        &'a self,
+        cf: &'static str,
+        prefix: &'a [u8],
+    ) -> Box<dyn Iterator<Item = DbIteratorItem> + 'a> {
+        match self {
+            Self::File { db, .. } => {
+                let cf = db.cf_handle(cf).unwrap();
+                let iter = db
+                    .iterator_cf(cf, IteratorMode::From(prefix, Direction::Forward))
+                    .take_while(move |(k, _)| k.starts_with(prefix))
+                    .map(|(k, v)| (k.to_vec(), v.to_vec()));
+                Box::new(iter)
+            }
+            Self::InMemory {
+                key_values,
+                columns,
+            } => {
+                let cf = columns.get(cf).unwrap();
+                let iter = key_values[*cf]
+                    .range(prefix.to_vec()..)
+                    .take_while(move |(k, _)| k.starts_with(prefix))
+                    .map(|(k, v)| (k.clone(), v.clone()));
+                Box::new(iter)
+            }
+        }
+    }
+
     /// Get entries from database as iterable db items for all opened columns
     pub fn iter_all_cf_clone(
         &self,
@@ -434,6 +648,73 @@ impl SimpleDb {
         }
     }
 
+    /// Approximate size in bytes of the data stored across all columns
+    pub fn approximate_size(&self) -> u64 {
+        match self {
+            Self::File { db, columns, .. } => columns
+                .iter()
+                .filter_map(|cf| db.cf_handle(cf))
+                .map(|cf| {
+                    let size = db.property_int_value_cf(cf, "rocksdb.estimate-live-data-size");
+                    ok_or_warn_size(size, "approximate_size")
+                })
+                .sum(),
+            Self::InMemory { key_values, .. } => key_values
+                .iter()
+                .flat_map(|kv| kv.iter())
+                .map(|(k, v)| (k.len() + v.len()) as u64)
+                .sum(),
+        }
+    }
+
+    /// Number of keys in each open column
+    pub fn key_count_per_column(&self) -> BTreeMap<String, u64> {
+        match self {
+            Self::File { db, columns, .. } => columns
+                .iter()
+                .filter_map(|cf| Some((cf, db.cf_handle(cf)?)))
+                .map(|(name, cf)| {
+                    let count = db.property_int_value_cf(cf, "rocksdb.estimate-num-keys");
+                    (name.clone(), ok_or_warn_size(count, "key_count_per_column"))
+                })
+                .collect(),
+            Self::InMemory {
+                columns,
+                key_values,
+            } => columns
+                .iter()
+                .map(|(name, cf)| (name.clone(), key_values[*cf].len() as u64))
+                .collect(),
+        }
+    }
+
+    /// Number of entries held in memtables and not yet flushed to disk,
+    /// always 0 for in memory db
+    pub fn unflushed_entry_count(&self) -> u64 {
+        match self {
+            Self::File { db, columns, .. } => columns
+                .iter()
+                .filter_map(|cf| db.cf_handle(cf))
+                .flat_map(|cf| {
+                    let active =
+                        db.property_int_value_cf(cf, "rocksdb.num-entries-active-mem-table");
+                    let imm = db.property_int_value_cf(cf, "rocksdb.num-entries-imm-mem-tables");
+                    vec![active, imm]
+                })
+                .map(|count| ok_or_warn_size(count, "unflushed_entry_count"))
+                .sum(),
+            Self::InMemory { .. } => 0,
+        }
+    }
+
+    /// Size information for the database
+    pub fn metrics(&self) -> DbMetrics {
+        DbMetrics {
+            approximate_size: self.approximate_size(),
+            key_count_per_column: self.key_count_per_column(),
+        }
+    }
+
     /// Return all open columns
     fn open_columns(&self) -> Vec<String> {
         match self {
@@ -449,6 +730,27 @@ pub enum SimpleDbWriteBatchDone {
     InMemory { write: InMemoryWriteBatch },
 }
 
+/// Shared handle to a file db, to flush and compact it
+/// while the `SimpleDb` remains available to other users
+pub struct DbCompactionHandle {
+    db: Arc<DB>,
+    columns: Vec<String>,
+}
+
+impl DbCompactionHandle {
+    /// Flush the memtables to disk
+    pub fn flush(&self) -> Result<()> {
+        Ok(self.db.flush()?)
+    }
+
+    /// Compact all columns
+    pub fn compact(&self) {
+        for cf in self.columns.iter().filter_map(|cf| self.db.cf_handle(cf)) {
+            self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
+    }
+}
+
 /// Database Atomic update accross column with performance benefit.
 pub enum SimpleDbWriteBatch<'a> {
     File {
@@ -498,13 +800,13 @@ impl SimpleDbWriteBatch<'_> {
             Self::File { write, db } => {
                 let cf = db
                     .cf_handle(cf)
-                    .ok_or_else(|| SimpleDbError(format!("Missing column {cf}")))?;
+                    .ok_or_else(|| SimpleDbError::Other(format!("Missing column {cf}")))?;
                 write.put_cf(cf, key, value);
             }
             Self::InMemory { write, columns } => {
                 let cf = columns
                     .get(cf)
-                    .ok_or_else(|| SimpleDbError(format!("Missing column {cf}")))?;
+                    .ok_or_else(|| SimpleDbError::Other(format!("Missing column {cf}")))?;
                 write.push((*cf, key.as_ref().to_vec(), Some(value.as_ref().to_vec())));
             }
         }
@@ -518,16 +820,31 @@ impl SimpleDbWriteBatch<'_> {
     /// * `cf`  - The column family to use
     /// * `key` - position in database to be deleted
     pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &'static str, key: K) {
+        self.delete_cf_checked(cf, key).unwrap();
+    }
+
+    /// Remove entry from database on write
+    ///
+    /// ### Arguments
+    ///
+    /// * `cf`  - The column family to use: may not be found not removing the value
+    /// * `key` - position in database to be deleted
+    fn delete_cf_checked<K: AsRef<[u8]>>(&mut self, cf: &str, key: K) -> Result<()> {
         match self {
             Self::File { write, db } => {
-                let cf = db.cf_handle(cf).unwrap();
+                let cf = db
+                    .cf_handle(cf)
+                    .ok_or_else(|| SimpleDbError::Other(format!("Missing column {cf}")))?;
                 write.delete_cf(cf, key);
             }
             Self::InMemory { write, columns } => {
-                let cf = columns.get(cf).unwrap();
+                let cf = columns
+                    .get(cf)
+                    .ok_or_else(|| SimpleDbError::Other(format!("Missing column {cf}")))?;
                 write.push((*cf, key.as_ref().to_vec(), None));
             }
         }
+        Ok(())
     }
 }
 
@@ -547,18 +864,51 @@ fn check_version(db: &SimpleDb, expected: Option<&[u8]>) -> Result<()> {
         Ok(())
     } else {
         warn!("DB Version mismatch {:?} != {:?}", version, expected);
-        Err(SimpleDbError("DB Version mismatch".to_owned()))
+        Err(SimpleDbError::Other("DB Version mismatch".to_owned()))
     }
 }
 
 /// Creates a set of DB opening options for rocksDB instances
-fn get_db_options() -> Options {
+///
+/// ### Arguments
+///
+/// * `tuning` - Performance tuning: unset values keep rocksDB defaults
+fn get_db_options(tuning: &DbTuning) -> Options {
     let mut opts = Options::default();
     opts.set_compression_type(DBCompressionType::Snappy);
 
+    if let Some(size) = tuning.write_buffer_size {
+        opts.set_write_buffer_size(size);
+    }
+    if let Some(style) = tuning.compaction_style {
+        opts.set_compaction_style(match style {
+            DbCompactionStyle::Level => DBCompactionStyle::Level,
+            DbCompactionStyle::Universal => DBCompactionStyle::Universal,
+        });
+    }
+    if tuning.block_cache_size.is_some() || tuning.bloom_filter_bits.is_some() {
+        let mut block_opts = BlockBasedOptions::default();
+        if let Some(size) = tuning.block_cache_size {
+            block_opts.set_lru_cache(size);
+        }
+        if let Some(bits) = tuning.bloom_filter_bits {
+            block_opts.set_bloom_filter(bits, false);
+        }
+        opts.set_block_based_table_factory(&block_opts);
+    }
+
     opts
 }
 
+/// Size from a rocksDB property, emitting a warning for errors converted to 0
+fn ok_or_warn_size(size: std::result::Result<Option<u64>, DBError>, tag: &str) -> u64 {
+    size.unwrap_or_else(|e| {
+        warn!("{}: {}", tag, e);
+        None
+    })
+    .unwrap_or_default()
+}
+
 /// Check iterators are equals when sorted
 fn check_old_includes_new<'a>(
     old: impl Iterator<Item = &'a str>,
@@ -569,7 +919,9 @@ fn check_old_includes_new<'a>(
     if new.is_subset(&old) {
         Ok(())
     } else {
-        Err(SimpleDbError("Column mismatch while opening".to_owned()))
+        Err(SimpleDbError::Other(
+            "Column mismatch while opening".to_owned(),
+        ))
     }
 }
 
@@ -587,12 +939,38 @@ pub fn new_db(
     old_db: Option<SimpleDb>,
     custom_db_spec: Option<CustomDbSpec>,
 ) -> SimpleDb {
-    new_db_with_version(
+    new_db_with_tuning(
+        db_mode,
+        db_spec,
+        old_db,
+        custom_db_spec,
+        &Default::default(),
+    )
+}
+
+/// Creates a new database(db) object in selected mode, tuned if a file database
+///
+/// ### Arguments
+///
+/// * `db_moode` - Mode for the database.
+/// * `db_spec`  - Database specification.
+/// * `old_db`   - Old in memory Database to try to open.
+/// * `custom_db_spec` - Custom database specification.
+/// * `tuning`   - Performance tuning for file database.
+pub fn new_db_with_tuning(
+    db_mode: DbMode,
+    db_spec: &SimpleDbSpec,
+    old_db: Option<SimpleDb>,
+    custom_db_spec: Option<CustomDbSpec>,
+    tuning: &DbTuning,
+) -> SimpleDb {
+    new_db_with_version_and_tuning(
         db_mode,
         db_spec,
         Some(NETWORK_VERSION_SERIALIZED),
         old_db,
         custom_db_spec,
+        tuning,
     )
     .unwrap()
 }
@@ -613,7 +991,35 @@ pub fn new_db_with_version(
     old_db: Option<SimpleDb>,
     custom_db_spec: Option<CustomDbSpec>,
 ) -> Result<SimpleDb> {
-    let db = new_db_no_check_version(db_mode, db_spec, old_db, custom_db_spec)?;
+    new_db_with_version_and_tuning(
+        db_mode,
+        db_spec,
+        version,
+        old_db,
+        custom_db_spec,
+        &Default::default(),
+    )
+}
+
+/// Creates a new database(db) object in selected mode, tuned if a file database
+///
+/// ### Arguments
+///
+/// * `db_moode` - Mode for the database.
+/// * `db_spec`  - Database specification.
+/// * `version`  - Database exact version to use (if none check key absent).
+/// * `old_db`   - Old in memory Database to try to open.
+/// * `custom_db_spec` - Custom database specification.
+/// * `tuning`   - Performance tuning for file database.
+pub fn new_db_with_version_and_tuning(
+    db_mode: DbMode,
+    db_spec: &SimpleDbSpec,
+    version: Option<&[u8]>,
+    old_db: Option<SimpleDb>,
+    custom_db_spec: Option<CustomDbSpec>,
+    tuning: &DbTuning,
+) -> Result<SimpleDb> {
+    let db = open_db(db_mode, db_spec, old_db, custom_db_spec, tuning)?;
     check_version(&db, version)?;
     Ok(db)
 }
@@ -631,12 +1037,29 @@ pub fn new_db_no_check_version(
     db_spec: &SimpleDbSpec,
     old_db: Option<SimpleDb>,
     custom_db_spec: Option<CustomDbSpec>,
+) -> Result<SimpleDb> {
+    open_db(
+        db_mode,
+        db_spec,
+        old_db,
+        custom_db_spec,
+        &Default::default(),
+    )
+}
+
+/// Open or create the database in selected mode
+fn open_db(
+    db_mode: DbMode,
+    db_spec: &SimpleDbSpec,
+    old_db: Option<SimpleDb>,
+    custom_db_spec: Option<CustomDbSpec>,
+    tuning: &DbTuning,
 ) -> Result<SimpleDb> {
     if let Some(save_path) = new_db_save_path(db_mode, db_spec, custom_db_spec) {
         if old_db.is_some() {
             panic!("new_db: Do not provide database, read it from disk");
         }
-        SimpleDb::new_file(save_path, db_spec.columns)
+        SimpleDb::new_file_with_tuning(save_path, db_spec.columns, tuning)
     } else {
         SimpleDb::new_in_memory(db_spec.columns, old_db)
     }
@@ -668,6 +1091,44 @@ pub fn new_db_save_path(
     }
 }
 
+/// Custom database specification placing the database in a node subdirectory
+///
+/// ### Arguments
+///
+/// * `data_dir` - Base directory for the databases, default location if none.
+/// * `node_dir` - Subdirectory specific to the node (e.g `compute.0`).
+/// * `db_spec`  - Database specification.
+pub fn data_dir_db_spec(
+    data_dir: Option<&str>,
+    node_dir: &str,
+    db_spec: &SimpleDbSpec,
+) -> Option<CustomDbSpec> {
+    data_dir.map(|data_dir| CustomDbSpec {
+        db_path: format!("{data_dir}/{node_dir}"),
+        suffix: db_spec.suffix.to_owned(),
+    })
+}
+
+/// Repair a corrupt file db, recovering as much data as possible
+///
+/// ### Arguments
+///
+/// * `db_moode` - Mode for the database.
+/// * `db_spec`  - Database specification.
+/// * `custom_db_spec` - Custom database specification.
+pub fn repair_db(
+    db_mode: DbMode,
+    db_spec: &SimpleDbSpec,
+    custom_db_spec: Option<CustomDbSpec>,
+) -> Result<()> {
+    if let Some(path) = new_db_save_path(db_mode, db_spec, custom_db_spec) {
+        warn!("Repair db {}", path);
+        DB::repair(get_db_options(&Default::default()), &path)?;
+    }
+
+    Ok(())
+}
+
 /// Restore backup for file db
 pub fn restore_file_backup(
     db_mode: DbMode,
@@ -688,3 +1149,228 @@ pub fn restore_file_backup(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_COL: &str = "test_col";
+    const TEST_SPEC: SimpleDbSpec = SimpleDbSpec {
+        db_path: DB_PATH_TEST,
+        suffix: ".tuning",
+        columns: &[TEST_COL],
+    };
+
+    #[test]
+    fn live_db_with_tuning_round_trip() {
+        //
+        // Arrange
+        //
+        let db_path = std::env::temp_dir().join("znp_db_tuning_test");
+        let _ = std::fs::remove_dir_all(&db_path);
+        std::fs::create_dir_all(&db_path).unwrap();
+        let custom_db_spec = CustomDbSpec {
+            db_path: db_path.to_str().unwrap().to_owned(),
+            suffix: TEST_SPEC.suffix.to_owned(),
+        };
+        let tuning = DbTuning {
+            block_cache_size: Some(8 * 1024 * 1024),
+            write_buffer_size: Some(4 * 1024 * 1024),
+            compaction_style: Some(DbCompactionStyle::Universal),
+            bloom_filter_bits: Some(10),
+        };
+
+        //
+        // Act
+        //
+        let mut db = new_db_with_tuning(
+            DbMode::Live,
+            &TEST_SPEC,
+            None,
+            Some(custom_db_spec),
+            &tuning,
+        );
+        db.put_cf(TEST_COL, "key", "value").unwrap();
+        let value = db.get_cf(TEST_COL, "key").unwrap();
+
+        //
+        // Assert
+        //
+        assert!(matches!(db, SimpleDb::File { .. }));
+        assert_eq!(value, Some(b"value".to_vec()));
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
+    #[test]
+    fn flush_compact_keeps_data_readable() {
+        //
+        // Arrange
+        //
+        let db_path = std::env::temp_dir().join("znp_db_compact_test");
+        let _ = std::fs::remove_dir_all(&db_path);
+        std::fs::create_dir_all(&db_path).unwrap();
+        let custom_db_spec = CustomDbSpec {
+            db_path: db_path.to_str().unwrap().to_owned(),
+            suffix: TEST_SPEC.suffix.to_owned(),
+        };
+        let mut dbs = vec![
+            new_db(DbMode::InMemory, &TEST_SPEC, None, None),
+            new_db(DbMode::Live, &TEST_SPEC, None, Some(custom_db_spec)),
+        ];
+
+        //
+        // Act
+        //
+        let results: Vec<_> = dbs
+            .iter_mut()
+            .map(|db| {
+                db.put_cf(TEST_COL, "key", "value").unwrap();
+                db.delete_cf(TEST_COL, "key").unwrap();
+                db.put_cf(TEST_COL, "key", "value").unwrap();
+                let done = db.flush().and_then(|_| db.compact());
+                (done.is_ok(), db.get_cf(TEST_COL, "key").unwrap())
+            })
+            .collect();
+
+        //
+        // Assert
+        //
+        let expected = (true, Some(b"value".to_vec()));
+        assert_eq!(results, vec![expected.clone(), expected]);
+
+        drop(dbs);
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
+    #[test]
+    fn data_dir_db_independent() {
+        //
+        // Arrange
+        //
+        let data_dir = std::env::temp_dir().join("znp_data_dir_test");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        let data_dir_1 = data_dir.join("1");
+        let data_dir_2 = data_dir.join("2");
+        let spec_1 = data_dir_db_spec(data_dir_1.to_str(), "compute.0", &TEST_SPEC);
+        let spec_2 = data_dir_db_spec(data_dir_2.to_str(), "compute.0", &TEST_SPEC);
+        let path_1 = new_db_save_path(DbMode::Test(0), &TEST_SPEC, spec_1.clone());
+        let path_2 = new_db_save_path(DbMode::Test(0), &TEST_SPEC, spec_2.clone());
+
+        //
+        // Act
+        //
+        let mut db_1 = new_db(DbMode::Test(0), &TEST_SPEC, None, spec_1);
+        let db_2 = new_db(DbMode::Test(0), &TEST_SPEC, None, spec_2);
+        db_1.put_cf(TEST_COL, "key", "value").unwrap();
+
+        //
+        // Assert
+        //
+        let expected_1 = format!("{}/compute.0/test.tuning.0", data_dir_1.to_str().unwrap());
+        assert_eq!(path_1, Some(expected_1));
+        assert_ne!(path_1, path_2);
+        assert_eq!(
+            db_1.get_cf(TEST_COL, "key").unwrap(),
+            Some(b"value".to_vec())
+        );
+        assert_eq!(db_2.get_cf(TEST_COL, "key").unwrap(), None);
+
+        drop((db_1, db_2));
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn corrupt_db_open_error() {
+        //
+        // Arrange
+        //
+        let db_path = std::env::temp_dir().join("znp_corrupt_db_test");
+        let _ = std::fs::remove_dir_all(&db_path);
+        let custom_db_spec = CustomDbSpec {
+            db_path: db_path.to_str().unwrap().to_owned(),
+            suffix: TEST_SPEC.suffix.to_owned(),
+        };
+        let save_path = new_db_save_path(DbMode::Live, &TEST_SPEC, Some(custom_db_spec.clone()));
+        let save_path = save_path.unwrap();
+        std::fs::create_dir_all(&save_path).unwrap();
+        std::fs::write(format!("{save_path}/CURRENT"), "no_trailing_newline").unwrap();
+
+        //
+        // Act
+        //
+        let result = new_db_no_check_version(DbMode::Live, &TEST_SPEC, None, Some(custom_db_spec));
+
+        //
+        // Assert
+        //
+        assert!(
+            matches!(result, Err(SimpleDbError::CorruptNeedsRepair(_))),
+            "{result:?}"
+        );
+
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
+    #[test]
+    fn in_memory_key_count_per_column() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &TEST_SPEC, None, None);
+
+        //
+        // Act
+        //
+        for key in ["a", "b", "c"] {
+            db.put_cf(TEST_COL, key, "value").unwrap();
+        }
+        db.put_cf(DB_COL_DEFAULT, "d", "value").unwrap();
+        let metrics = db.metrics();
+
+        //
+        // Assert
+        //
+        // Default column also holds the db version
+        let expected: BTreeMap<String, u64> =
+            vec![(DB_COL_DEFAULT.to_owned(), 2), (TEST_COL.to_owned(), 3)]
+                .into_iter()
+                .collect();
+        let version_size = (DB_VERSION_KEY.len() + NETWORK_VERSION_SERIALIZED.len()) as u64;
+        assert_eq!(metrics.key_count_per_column, expected);
+        assert_eq!(metrics.approximate_size, version_size + 4 * 6);
+    }
+
+    #[test]
+    fn try_batch_rejected_writes_nothing() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &TEST_SPEC, None, None);
+        db.put_cf(TEST_COL, "balance", [10]).unwrap();
+
+        //
+        // Act
+        //
+        let outcome = db
+            .try_batch(
+                |db, batch| {
+                    let balance = db.get_cf(TEST_COL, "balance").unwrap().unwrap()[0];
+                    let new_balance = balance.wrapping_sub(20);
+                    batch.put_cf(TEST_COL, "balance", [new_balance]);
+                    batch.put_cf(DB_COL_DEFAULT, "spent", [20]);
+                    (balance, new_balance)
+                },
+                |_, (balance, new_balance)| new_balance <= balance,
+            )
+            .unwrap();
+
+        //
+        // Assert
+        //
+        assert!(matches!(outcome, BatchOutcome::Rejected(_)));
+        assert_eq!(db.get_cf(TEST_COL, "balance").unwrap(), Some(vec![10]));
+        assert_eq!(db.get_cf(DB_COL_DEFAULT, "spent").unwrap(), None);
+    }
+}
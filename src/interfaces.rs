@@ -1,10 +1,12 @@
 use crate::compute::ComputeError;
 use crate::compute_raft::ComputeConsensusedRuntimeData;
 use crate::configurations::ComputeNodeSharedConfig;
+use crate::db_utils::DbMetrics;
 use crate::raft::{CommittedIndex, RaftMessageWrapper};
+use crate::raft_util::RaftProposalMetricsInfo;
 use crate::tracked_utxo::TrackedUtxoSet;
 use crate::unicorn::Unicorn;
-use crate::utils::rug_integer;
+use crate::utils::{rug_integer, utxo_set_pairs};
 use bytes::Bytes;
 use naom::primitives::asset::Asset;
 use naom::primitives::asset::TokenAmount;
@@ -17,6 +19,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::net::SocketAddr;
+use tracing::info_span;
 
 /// Struct used for simplifying JSON deserialization on the client
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -29,6 +32,10 @@ impl OutPointData {
     pub fn new(out_point: OutPoint, value: Asset) -> Self {
         OutPointData { out_point, value }
     }
+
+    pub fn get_out_point(&self) -> &OutPoint {
+        &self.out_point
+    }
 }
 
 /// Simple BTreeMap structure used to hold `(OutPoint, Asset, String/Data to Sign)` pairs
@@ -83,7 +90,307 @@ pub struct RbPaymentResponseData {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Response {
     pub success: bool,
-    pub reason: &'static str,
+    pub reason: ResponseReason,
+}
+
+/// Reason given with a node event response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseReason {
+    AddressAddedToWhitelist,
+    AddressesDeleted,
+    AlreadyDisconnectedFromCompute,
+    AttemptCoordinatedNodePause,
+    AttemptCoordinatedNodeResume,
+    AttemptSendSharedConfig,
+    BlockCommitted,
+    BlockCompleteStored,
+    BlockIsNotValid,
+    BlockIsValid,
+    BlockMiningNotified,
+    BlockPowComplete,
+    BlockReceivedAlreadyStored,
+    BlockReceivedAlreadyStoredNotFlushed,
+    BlockReceivedNotAddedPowInvalid,
+    BlockReceivedToBeAdded,
+    BlockShutdown,
+    BlockchainItemFetchedFromStorage,
+    BlockchainItemReceived,
+    BlockchainItemReceivedBlockFailed,
+    BlockchainItemReceivedBlockStored,
+    BlockchainItemReceivedBlockStoredDone,
+    CancelCoordinatedShutdown,
+    CatchUpStoredBlocks,
+    CoinbaseTransactionInvalid,
+    ComputeShutdown,
+    ConnectedToCompute,
+    CoordinatedShutdownBlockPassed,
+    DisconnectedFromCompute,
+    DonationRequested,
+    DruidAlreadyExecuting,
+    DruidCancellationExpired,
+    DruidCancellationExpiryTooFar,
+    DruidNotPending,
+    DruidParticipantNotFound,
+    DruidParticipationCancelled,
+    ExternalPowForwarded,
+    ExternalPowRejected,
+    FailedToConnectToCompute,
+    FailedToDisconnectFromCompute,
+    FailedToInitiateCoordinatedPause,
+    FailedToInitiateCoordinatedResume,
+    FailedToInitiateSharingOfConfig,
+    FailedToSendRuntimeDataToPeer,
+    FailedToSendStartupRequestsOnReconnection,
+    FeeTooLow,
+    FirstBlockCommitted,
+    IgnoreDonationOverPeerLimit,
+    IgnoreDonationOverTotalLimit,
+    IgnoreUnexpectedTransaction,
+    InitiatePauseNode,
+    InsufficientFundsForPayment,
+    InvalidBlockMiningNotifier,
+    InvalidDruidCancellationSignature,
+    InvalidPowForBlock,
+    LocktimeBeyondMaximum,
+    MinerNotAuthorized,
+    MinerRemovalRequestReceived,
+    MinerRemovedAckFromNonComputePeer,
+    MinerUnauthorizedFromNonComputePeer,
+    NewAddressGenerated,
+    NewAddressReadyToBeSent,
+    NextPaymentTransactionReady,
+    NoSharedConfigToApply,
+    NoValidTransactionsProvided,
+    NodeIsConnected,
+    NodeIsDisconnected,
+    NodeIsMining,
+    NodeIsNotMining,
+    NodeIsPaused,
+    NodeIsResumed,
+    NodePauseConfigurationSet,
+    NodeResumed,
+    NotBlockCurrentlyMined,
+    NotImplementedYet,
+    OutputBelowDustThreshold,
+    PartitionListComplete,
+    PartitionPowComplete,
+    PartitionPowReceivedSuccessfully,
+    PartitionRequestResentAfterMissedRound,
+    PaymentTransactionReceived,
+    PipelineHalted,
+    PipelineReset,
+    PowReceivedIsInvalid,
+    PreBlockReceivedSuccessfully,
+    ReceiptAssetCreateTransactionReady,
+    ReceivedBlockNotification,
+    ReceivedBlockStored,
+    ReceivedBlockStoredNotFromStoragePeer,
+    ReceivedCoordinatedPauseRequest,
+    ReceivedCoordinatedResumeRequest,
+    ReceivedDbItems,
+    ReceivedDbItemsWithInvalidChecksum,
+    ReceivedFirstFullPartitionRequest,
+    ReceivedInvalidDbItems,
+    ReceivedPartitionRequestSuccessfully,
+    ReceivedPowSuccessfully,
+    ReceivedRandomNumberSuccessfully,
+    ReceivedReceiptBasedPaymentRequest,
+    ReceivedReceiptBasedPaymentResponse,
+    ReceivedRuntimeDataFromPeer,
+    ReceivedRuntimeDataFromUnknownPeer,
+    ReceivedSharedConfig,
+    ReceivedUtxoFetchRequest,
+    ReceivedUtxoSet,
+    RemovingUnauthorizedMiner,
+    RequestPaymentAddress,
+    RequestUtxoSet,
+    ResentBlockToStorageOnReconnection,
+    SentRuntimeDataToPeer,
+    SentStartupRequestsOnReconnection,
+    SentUtxoRequest,
+    SharedConfigApplied,
+    Shutdown,
+    ShutdownPending,
+    SnapshotApplied,
+    SnapshotAppliedFetchMissingBlocks,
+    SnapshotForced,
+    SomeTransactionsInvalid,
+    StartCoordinatedShutdown,
+    StaticMinerAddressSet,
+    TransactionPoolFull,
+    TransactionRejectedByPolicy,
+    TransactionTooLarge,
+    TransactionsAddedToTxPool,
+    TransactionsCommitted,
+    UnsupportedTransactionVersion,
+    UtxoSetNearCap,
+    WinningPowIntakeOpen,
+    /// Exit requested by the caller handling the node events
+    Exit(&'static str),
+}
+
+impl ResponseReason {
+    /// The reason as displayed in logs and API responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AddressAddedToWhitelist => "Address added to whitelist",
+            Self::AddressesDeleted => "Addresses deleted",
+            Self::AlreadyDisconnectedFromCompute => "Already disconnected from compute",
+            Self::AttemptCoordinatedNodePause => "Attempt coordinated node pause",
+            Self::AttemptCoordinatedNodeResume => "Attempt coordinated node resume",
+            Self::AttemptSendSharedConfig => "Attempt send shared config",
+            Self::BlockCommitted => "Block committed",
+            Self::BlockCompleteStored => "Block complete stored",
+            Self::BlockIsNotValid => "Block is not valid",
+            Self::BlockIsValid => "Block is valid",
+            Self::BlockMiningNotified => "Block mining notified",
+            Self::BlockPowComplete => "Block PoW complete",
+            Self::BlockReceivedAlreadyStored => "Block received already stored",
+            Self::BlockReceivedAlreadyStoredNotFlushed => {
+                "Block received already stored not flushed"
+            }
+            Self::BlockReceivedNotAddedPowInvalid => "Block received not added. PoW invalid",
+            Self::BlockReceivedToBeAdded => "Block received to be added",
+            Self::BlockShutdown => "Block shutdown",
+            Self::BlockchainItemFetchedFromStorage => "Blockchain item fetched from storage",
+            Self::BlockchainItemReceived => "Blockchain item received",
+            Self::BlockchainItemReceivedBlockFailed => "Blockchain item received: Block failed",
+            Self::BlockchainItemReceivedBlockStored => "Blockchain item received: Block stored",
+            Self::BlockchainItemReceivedBlockStoredDone => {
+                "Blockchain item received: Block stored(Done)"
+            }
+            Self::CancelCoordinatedShutdown => "Cancel coordinated shutdown",
+            Self::CatchUpStoredBlocks => "Catch up stored blocks",
+            Self::CoinbaseTransactionInvalid => "Coinbase transaction invalid",
+            Self::ComputeShutdown => "Compute Shutdown",
+            Self::ConnectedToCompute => "Connected to compute",
+            Self::CoordinatedShutdownBlockPassed => "Coordinated shutdown block already passed",
+            Self::DisconnectedFromCompute => "Disconnected from compute",
+            Self::DonationRequested => "Donation Requested",
+            Self::DruidAlreadyExecuting => "DRUID has all participants and is executing",
+            Self::DruidCancellationExpired => "DRUID cancellation expired",
+            Self::DruidCancellationExpiryTooFar => {
+                "DRUID cancellation expiry too far in the future"
+            }
+            Self::DruidNotPending => "DRUID is not pending",
+            Self::DruidParticipantNotFound => "No DRUID transaction signed by this public key",
+            Self::DruidParticipationCancelled => "DRUID participation cancelled",
+            Self::ExternalPowForwarded => "External PoW forwarded to compute",
+            Self::ExternalPowRejected => "External PoW rejected",
+            Self::FailedToConnectToCompute => "Failed to connect to compute",
+            Self::FailedToDisconnectFromCompute => "Failed to disconnect from compute",
+            Self::FailedToInitiateCoordinatedPause => "Failed to initiate coordinated pause",
+            Self::FailedToInitiateCoordinatedResume => "Failed to initiate coordinated resume",
+            Self::FailedToInitiateSharingOfConfig => "Failed to initiate sharing of config",
+            Self::FailedToSendRuntimeDataToPeer => "Failed to send runtime data to peer",
+            Self::FailedToSendStartupRequestsOnReconnection => {
+                "Failed to send startup requests on reconnection"
+            }
+            Self::FeeTooLow => "Fee too low",
+            Self::FirstBlockCommitted => "First Block committed",
+            Self::IgnoreDonationOverPeerLimit => "Ignore donation over peer limit",
+            Self::IgnoreDonationOverTotalLimit => "Ignore donation over total limit",
+            Self::IgnoreUnexpectedTransaction => "Ignore unexpected transaction",
+            Self::InitiatePauseNode => "Initiate pause node",
+            Self::InsufficientFundsForPayment => "Insufficient funds for payment",
+            Self::InvalidBlockMiningNotifier => "Invalid block mining notifier",
+            Self::InvalidDruidCancellationSignature => "Invalid DRUID cancellation signature",
+            Self::InvalidPowForBlock => "Invalid PoW for block",
+            Self::LocktimeBeyondMaximum => {
+                "Transaction locktime beyond maximum offset from current block"
+            }
+            Self::MinerNotAuthorized => "Miner not authorized",
+            Self::MinerRemovalRequestReceived => "Miner removal request received",
+            Self::MinerRemovedAckFromNonComputePeer => {
+                "Received miner removed ack from non-compute peer"
+            }
+            Self::MinerUnauthorizedFromNonComputePeer => {
+                "Received miner unauthorized notification from non-compute peer"
+            }
+            Self::NewAddressGenerated => "New address generated",
+            Self::NewAddressReadyToBeSent => "New address ready to be sent",
+            Self::NextPaymentTransactionReady => "Next payment transaction ready",
+            Self::NoSharedConfigToApply => "No shared config to apply",
+            Self::NoValidTransactionsProvided => "No valid transactions provided",
+            Self::NodeIsConnected => "Node is connected",
+            Self::NodeIsDisconnected => "Node is disconnected",
+            Self::NodeIsMining => "Node is mining",
+            Self::NodeIsNotMining => "Node is not mining",
+            Self::NodeIsPaused => "Node is paused",
+            Self::NodeIsResumed => "Node is resumed",
+            Self::NodePauseConfigurationSet => "Node pause configuration set",
+            Self::NodeResumed => "Node resumed",
+            Self::NotBlockCurrentlyMined => "Not block currently mined",
+            Self::NotImplementedYet => "Not implemented yet",
+            Self::OutputBelowDustThreshold => "Transaction output below dust threshold",
+            Self::PartitionListComplete => "Partition list complete",
+            Self::PartitionPowComplete => "Partition PoW complete",
+            Self::PartitionPowReceivedSuccessfully => "Partition PoW received successfully",
+            Self::PartitionRequestResentAfterMissedRound => {
+                "Partition request resent after missed round"
+            }
+            Self::PaymentTransactionReceived => "Payment transaction received",
+            Self::PipelineHalted => "Pipeline halted",
+            Self::PipelineReset => "Pipeline reset",
+            Self::PowReceivedIsInvalid => "PoW received is invalid",
+            Self::PreBlockReceivedSuccessfully => "Pre-block received successfully",
+            Self::ReceiptAssetCreateTransactionReady => "Receipt asset create transaction ready",
+            Self::ReceivedBlockNotification => "Received block notification",
+            Self::ReceivedBlockStored => "Received block stored",
+            Self::ReceivedBlockStoredNotFromStoragePeer => {
+                "Received block stored not from our storage peer"
+            }
+            Self::ReceivedCoordinatedPauseRequest => "Received coordinated pause request",
+            Self::ReceivedCoordinatedResumeRequest => "Received coordinated resume request",
+            Self::ReceivedDbItems => "Received Db Items",
+            Self::ReceivedDbItemsWithInvalidChecksum => "Received Db Items With Invalid Checksum",
+            Self::ReceivedFirstFullPartitionRequest => "Received first full partition request",
+            Self::ReceivedInvalidDbItems => "Received Invalid Db Items",
+            Self::ReceivedPartitionRequestSuccessfully => "Received partition request successfully",
+            Self::ReceivedPowSuccessfully => "Received PoW successfully",
+            Self::ReceivedRandomNumberSuccessfully => "Received random number successfully",
+            Self::ReceivedReceiptBasedPaymentRequest => "Received receipt-based payment request",
+            Self::ReceivedReceiptBasedPaymentResponse => "Received receipt-based payment response",
+            Self::ReceivedRuntimeDataFromPeer => "Received runtime data from peer",
+            Self::ReceivedRuntimeDataFromUnknownPeer => "Received runtime data from unknown peer",
+            Self::ReceivedSharedConfig => "Received shared config",
+            Self::ReceivedUtxoFetchRequest => "Received UTXO fetch request",
+            Self::ReceivedUtxoSet => "Received UTXO set",
+            Self::RemovingUnauthorizedMiner => "Removing unauthorized miner",
+            Self::RequestPaymentAddress => "Request Payment Address",
+            Self::RequestUtxoSet => "Request UTXO set",
+            Self::ResentBlockToStorageOnReconnection => "Resent block to storage on reconnection",
+            Self::SentRuntimeDataToPeer => "Sent runtime data to peer",
+            Self::SentStartupRequestsOnReconnection => "Sent startup requests on reconnection",
+            Self::SentUtxoRequest => "Sent UTXO Request",
+            Self::SharedConfigApplied => "Shared config applied",
+            Self::Shutdown => "Shutdown",
+            Self::ShutdownPending => "Shutdown pending",
+            Self::SnapshotApplied => "Snapshot applied",
+            Self::SnapshotAppliedFetchMissingBlocks => "Snapshot applied: Fetch missing blocks",
+            Self::SnapshotForced => "Snapshot forced",
+            Self::SomeTransactionsInvalid => {
+                "Some transactions invalid. Adding valid transactions only"
+            }
+            Self::StartCoordinatedShutdown => "Start coordinated shutdown",
+            Self::StaticMinerAddressSet => "Static miner address set",
+            Self::TransactionPoolFull => "Transaction pool for this compute node is full",
+            Self::TransactionRejectedByPolicy => "Transaction rejected by node policy",
+            Self::TransactionTooLarge => "Transaction larger than the maximum block size",
+            Self::TransactionsAddedToTxPool => "Transactions added to tx pool",
+            Self::TransactionsCommitted => "Transactions committed",
+            Self::UnsupportedTransactionVersion => "Transaction version is not supported",
+            Self::UtxoSetNearCap => "UTXO set near its size cap: transaction must not grow it",
+            Self::WinningPowIntakeOpen => "Winning PoW intake open",
+            Self::Exit(reason) => reason,
+        }
+    }
+}
+
+impl fmt::Display for ResponseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Mined block as stored in DB.
@@ -174,6 +481,14 @@ pub struct WinningPoWInfo {
     pub d_value: u8,
 }
 
+/// Payout to the winning miner of a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinerPayout {
+    pub block_num: u64,
+    pub miner_address: SocketAddr,
+    pub reward: TokenAmount,
+}
+
 /// Druid pool structure for checking and holding participants
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DruidDroplet {
@@ -216,6 +531,50 @@ pub fn node_type_as_str(node_type: NodeType) -> &'static str {
     }
 }
 
+/// Serialization format of the messages exchanged with a peer
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommsFormat {
+    Bincode,
+    Json,
+}
+
+impl Default for CommsFormat {
+    fn default() -> Self {
+        Self::Bincode
+    }
+}
+
+impl CommsFormat {
+    /// Serializes a value in this format
+    ///
+    /// ### Arguments
+    ///
+    /// * `value` - Value to serialize
+    pub fn serialize<T: ?Sized + Serialize>(self, value: &T) -> bincode::Result<Vec<u8>> {
+        match self {
+            Self::Bincode => bincode::serialize(value),
+            Self::Json => serde_json::to_vec(value).map_err(json_to_bincode_error),
+        }
+    }
+
+    /// Deserializes a value from this format
+    ///
+    /// ### Arguments
+    ///
+    /// * `bytes` - Serialized value
+    pub fn deserialize<'a, T: Deserialize<'a>>(self, bytes: &'a [u8]) -> bincode::Result<T> {
+        match self {
+            Self::Bincode => bincode::deserialize(bytes),
+            Self::Json => serde_json::from_slice(bytes).map_err(json_to_bincode_error),
+        }
+    }
+}
+
+/// Wraps a JSON error so both formats surface the same error type
+fn json_to_bincode_error(error: serde_json::Error) -> bincode::Error {
+    Box::new(bincode::ErrorKind::Custom(error.to_string()))
+}
+
 /// Mined block or transaction as stored in DB.
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BlockchainItem {
@@ -328,6 +687,125 @@ pub enum CommMessage {
         id: Token,
     },
     HeartBeatProbe(Token),
+    /// Handshake request also requesting a serialization format.
+    /// Only sent for a format other than the default, so peers predating it still
+    /// understand the handshakes of nodes using the default format.
+    HandshakeRequestWithFormat {
+        /// Node network version.
+        network_version: u32,
+        /// Type of node sending the request.
+        node_type: NodeType,
+        /// Publicly available socket address of the node that can be used for inbound connections.
+        public_address: SocketAddr,
+        /// Serialization format requested for the messages following the handshake.
+        comms_format: CommsFormat,
+    },
+    /// Handshake response to a `HandshakeRequestWithFormat`.
+    HandshakeResponseWithFormat {
+        /// Node network version.
+        network_version: u32,
+        /// Type of node sending the response.
+        node_type: NodeType,
+        /// contacts of ring members.
+        contacts: Vec<SocketAddr>,
+        /// Publicly resolved IP address of the node who made the handshake request
+        public_address: SocketAddr,
+        /// Serialization format agreed for the messages following the handshake.
+        comms_format: CommsFormat,
+    },
+}
+
+impl CommMessage {
+    /// Handshake request in the variant understood by the peer for the given format
+    ///
+    /// ### Arguments
+    ///
+    /// * `network_version` - Node network version
+    /// * `node_type`       - Type of node sending the request
+    /// * `public_address`  - Public address of the node sending the request
+    /// * `comms_format`    - Serialization format requested
+    pub fn handshake_request(
+        network_version: u32,
+        node_type: NodeType,
+        public_address: SocketAddr,
+        comms_format: CommsFormat,
+    ) -> Self {
+        match comms_format {
+            CommsFormat::Bincode => Self::HandshakeRequest {
+                network_version,
+                node_type,
+                public_address,
+            },
+            comms_format => Self::HandshakeRequestWithFormat {
+                network_version,
+                node_type,
+                public_address,
+                comms_format,
+            },
+        }
+    }
+
+    /// Handshake response in the variant understood by the peer for the given format
+    ///
+    /// ### Arguments
+    ///
+    /// * `network_version` - Node network version
+    /// * `node_type`       - Type of node sending the response
+    /// * `contacts`        - Contacts of ring members
+    /// * `public_address`  - Public address of the node who made the request
+    /// * `comms_format`    - Serialization format agreed
+    pub fn handshake_response(
+        network_version: u32,
+        node_type: NodeType,
+        contacts: Vec<SocketAddr>,
+        public_address: SocketAddr,
+        comms_format: CommsFormat,
+    ) -> Self {
+        match comms_format {
+            CommsFormat::Bincode => Self::HandshakeResponse {
+                network_version,
+                node_type,
+                contacts,
+                public_address,
+            },
+            comms_format => Self::HandshakeResponseWithFormat {
+                network_version,
+                node_type,
+                contacts,
+                public_address,
+                comms_format,
+            },
+        }
+    }
+
+    /// Convert a default handshake to its variant with the default format explicit
+    pub fn with_handshake_format(self) -> Self {
+        match self {
+            Self::HandshakeRequest {
+                network_version,
+                node_type,
+                public_address,
+            } => Self::HandshakeRequestWithFormat {
+                network_version,
+                node_type,
+                public_address,
+                comms_format: CommsFormat::Bincode,
+            },
+            Self::HandshakeResponse {
+                network_version,
+                node_type,
+                contacts,
+                public_address,
+            } => Self::HandshakeResponseWithFormat {
+                network_version,
+                node_type,
+                contacts,
+                public_address,
+                comms_format: CommsFormat::Bincode,
+            },
+            other => other,
+        }
+    }
 }
 
 ///============ STORAGE NODE ============///
@@ -447,6 +925,10 @@ pub enum MineApiRequest {
     },
     // Get static miner address
     GetStaticMinerAddress,
+    // Submit a block PoW nonce found by an external worker
+    SubmitPoW {
+        nonce: Vec<u8>,
+    },
 }
 
 /// Encapsulates miner requests
@@ -455,6 +937,8 @@ pub enum MineRequest {
     SendBlock {
         pow_info: PowInfo,
         rnum: Vec<u8>,
+        /// Difficulty the partition entry PoW must reach
+        partition_difficulty: usize,
         win_coinbases: Vec<String>,
         reward: TokenAmount,
         block: Option<BlockHeader>,
@@ -469,6 +953,7 @@ pub enum MineRequest {
     },
     /// Process received utxo set
     SendUtxoSet {
+        #[serde(with = "utxo_set_pairs")]
         utxo_set: UtxoSet,
     },
     MinerRemovedAck,
@@ -500,6 +985,7 @@ impl fmt::Debug for MineRequest {
                 write!(f, "SetStaticMinerAddress")
             }
             MinerApi(MineApiRequest::GetStaticMinerAddress) => write!(f, "GetStaticMinerAddress"),
+            MinerApi(MineApiRequest::SubmitPoW { .. }) => write!(f, "SubmitPoW"),
         }
     }
 }
@@ -569,8 +1055,11 @@ pub enum ComputeApiRequest {
 #[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone)]
 pub enum ComputeRequest {
-    /// Process an API internal request
-    ComputeApi(ComputeApiRequest),
+    /// Process an API internal request, logged under the correlation ID of the API call
+    ComputeApi {
+        request: ComputeApiRequest,
+        correlation_id: String,
+    },
 
     SendSharedConfig {
         shared_config: ComputeNodeSharedConfig,
@@ -614,15 +1103,30 @@ impl fmt::Debug for ComputeRequest {
         use ComputeRequest::*;
 
         match *self {
-            ComputeApi(ComputeApiRequest::SendCreateReceiptRequest { .. }) => {
+            ComputeApi {
+                request: ComputeApiRequest::SendCreateReceiptRequest { .. },
+                ..
+            } => {
                 write!(f, "Api::SendCreateReceiptRequest")
             }
-            ComputeApi(ComputeApiRequest::SendTransactions { .. }) => {
+            ComputeApi {
+                request: ComputeApiRequest::SendTransactions { .. },
+                ..
+            } => {
                 write!(f, "Api::SendTransactions")
             }
-            ComputeApi(ComputeApiRequest::PauseNodes { .. }) => write!(f, "Api::PauseNodes"),
-            ComputeApi(ComputeApiRequest::ResumeNodes) => write!(f, "Api::ResumeNodes"),
-            ComputeApi(ComputeApiRequest::SendSharedConfig { .. }) => {
+            ComputeApi {
+                request: ComputeApiRequest::PauseNodes { .. },
+                ..
+            } => write!(f, "Api::PauseNodes"),
+            ComputeApi {
+                request: ComputeApiRequest::ResumeNodes,
+                ..
+            } => write!(f, "Api::ResumeNodes"),
+            ComputeApi {
+                request: ComputeApiRequest::SendSharedConfig { .. },
+                ..
+            } => {
                 write!(f, "Api::SendSharedConfig")
             }
             SendUtxoRequest { .. } => write!(f, "SendUtxoRequest"),
@@ -676,6 +1180,15 @@ pub trait ComputeApi {
     /// Get compute node configuration that is shareable between peers
     fn get_shared_config(&self) -> ComputeNodeSharedConfig;
 
+    /// Get the current block number
+    fn get_current_block_num(&self) -> u64;
+
+    /// Get the block number a coordinated shutdown is scheduled at, if any
+    fn get_coordinated_shutdown(&self) -> Option<u64>;
+
+    /// Get the payouts to the winning miners of the most recent blocks, oldest first
+    fn get_miner_payouts(&self) -> Vec<MinerPayout>;
+
     /// Pause all compute nodes
     fn pause_nodes(&mut self, b_num: u64) -> Response;
 
@@ -688,9 +1201,36 @@ pub trait ComputeApi {
     /// Get the UTXO tracked set
     fn get_committed_utxo_tracked_set(&self) -> &TrackedUtxoSet;
 
+    /// Get the transactions pool to be included in the next block
+    fn get_committed_tx_pool(&self) -> &BTreeMap<String, Transaction>;
+
     /// Get pending DRUID pool
     fn get_pending_druid_pool(&self) -> &DruidPool;
 
+    /// Cancel the caller participation in a pending DRUID
+    ///
+    /// ### Arguments
+    ///
+    /// * `druid`      - DRUID id to cancel the participation in
+    /// * `tx_hash`    - Hash of the caller transaction in the droplet
+    /// * `expiry`     - UNIX timestamp in seconds after which the cancellation is rejected
+    /// * `public_key` - Hex encoded public key of the caller
+    /// * `signature`  - Hex encoded signature of the cancellation message by the caller
+    fn cancel_druid(
+        &mut self,
+        druid: &str,
+        tx_hash: &str,
+        expiry: u64,
+        public_key: &str,
+        signature: &str,
+    ) -> Response;
+
+    /// Get size information for the compute database
+    fn get_db_metrics(&self) -> DbMetrics;
+
+    /// Get commit latency and throughput of the compute raft proposals
+    fn get_raft_metrics(&self) -> RaftProposalMetricsInfo;
+
     /// Receives transactions to be bundled into blocks
     ///
     /// ### Arguments
@@ -708,6 +1248,47 @@ pub trait ComputeApi {
         drs_tx_hash_spec: DrsTxHashSpec,
         metadata: Option<String>,
     ) -> Result<(Transaction, String), ComputeError>;
+
+    /// Process an API request, logged under the correlation ID of the API call
+    ///
+    /// ### Arguments
+    ///
+    /// * `request`        - Request to process
+    /// * `correlation_id` - Correlation ID of the API call
+    fn receive_api_request(
+        &mut self,
+        request: ComputeApiRequest,
+        correlation_id: &str,
+    ) -> Result<Response, ComputeError> {
+        use ComputeApiRequest::*;
+
+        let api_span = info_span!("api", %correlation_id);
+        let _entered = api_span.enter();
+        match request {
+            SendCreateReceiptRequest {
+                receipt_amount,
+                script_public_key,
+                public_key,
+                signature,
+                drs_tx_hash_spec,
+                metadata,
+            } => {
+                let (tx, _) = self.create_receipt_asset_tx(
+                    receipt_amount,
+                    script_public_key,
+                    public_key,
+                    signature,
+                    drs_tx_hash_spec,
+                    metadata,
+                )?;
+                Ok(self.receive_transactions(vec![tx]))
+            }
+            SendTransactions { transactions } => Ok(self.receive_transactions(transactions)),
+            PauseNodes { b_num } => Ok(self.pause_nodes(b_num)),
+            ResumeNodes => Ok(self.resume_nodes()),
+            SendSharedConfig { shared_config } => Ok(self.send_shared_config(shared_config)),
+        }
+    }
 }
 
 ///============ USER NODE ============///
@@ -778,8 +1359,11 @@ pub enum UserApiRequest {
 /// Encapsulates user requests
 #[derive(Deserialize, Serialize, Clone)]
 pub enum UserRequest {
-    /// Process an API internal request
-    UserApi(UserApiRequest),
+    /// Process an API internal request, logged under the correlation ID of the API call
+    UserApi {
+        request: UserApiRequest,
+        correlation_id: String,
+    },
 
     /// Request to make a receipt-based payment
     SendRbPaymentRequest {
@@ -802,6 +1386,7 @@ pub enum UserRequest {
 
     /// Process received utxo set
     SendUtxoSet {
+        #[serde(with = "utxo_set_pairs")]
         utxo_set: UtxoSet,
     },
     /// Process received block being mined
@@ -817,20 +1402,56 @@ impl fmt::Debug for UserRequest {
         use UserRequest::*;
 
         match *self {
-            UserApi(UpdateWalletFromUtxoSet { .. }) => write!(f, "UpdateWalletFromUtxoSet"),
-            UserApi(RequestDonation { .. }) => write!(f, "RequestDonation"),
-            UserApi(MakeIpPayment { .. }) => write!(f, "MakeIpPayment"),
-            UserApi(MakePayment { .. }) => write!(f, "MakePayment"),
-            UserApi(SendCreateReceiptRequest { .. }) => write!(f, "SendCreateReceiptRequest"),
-            UserApi(MakePaymentWithExcessAddress { .. }) => {
+            UserApi {
+                request: UpdateWalletFromUtxoSet { .. },
+                ..
+            } => write!(f, "UpdateWalletFromUtxoSet"),
+            UserApi {
+                request: RequestDonation { .. },
+                ..
+            } => write!(f, "RequestDonation"),
+            UserApi {
+                request: MakeIpPayment { .. },
+                ..
+            } => write!(f, "MakeIpPayment"),
+            UserApi {
+                request: MakePayment { .. },
+                ..
+            } => write!(f, "MakePayment"),
+            UserApi {
+                request: SendCreateReceiptRequest { .. },
+                ..
+            } => write!(f, "SendCreateReceiptRequest"),
+            UserApi {
+                request: MakePaymentWithExcessAddress { .. },
+                ..
+            } => {
                 write!(f, "MakePaymentWithExcessAddress")
             }
-            UserApi(GenerateNewAddress) => write!(f, "GenerateNewAddress"),
-            UserApi(GetConnectionStatus) => write!(f, "GetConnectionStatus"),
-            UserApi(ConnectToCompute) => write!(f, "ConnectToCompute"),
-            UserApi(DisconnectFromCompute) => write!(f, "DisconnectFromCompute"),
-            UserApi(DeleteAddresses { .. }) => write!(f, "DeleteAddresses"),
-            UserApi(MergeAddresses { .. }) => write!(f, "MergeAddresses"),
+            UserApi {
+                request: GenerateNewAddress,
+                ..
+            } => write!(f, "GenerateNewAddress"),
+            UserApi {
+                request: GetConnectionStatus,
+                ..
+            } => write!(f, "GetConnectionStatus"),
+            UserApi {
+                request: ConnectToCompute,
+                ..
+            } => write!(f, "ConnectToCompute"),
+            UserApi {
+                request: DisconnectFromCompute,
+                ..
+            } => write!(f, "DisconnectFromCompute"),
+            UserApi {
+                request: DeleteAddresses { .. },
+                ..
+            } => write!(f, "DeleteAddresses"),
+            UserApi {
+                request: MergeAddresses { .. },
+                ..
+            } => write!(f, "MergeAddresses"),
 
             SendAddressRequest { .. } => write!(f, "SendAddressRequest"),
             SendPaymentAddress { .. } => write!(f, "SendPaymentAddress"),
@@ -863,6 +1484,7 @@ pub enum PreLaunchRequest {
     SendDbItems {
         committed: CommittedIndex,
         items: Vec<DbItem>,
+        checksum: Vec<u8>,
     },
     Closing,
 }
@@ -877,3 +1499,523 @@ impl fmt::Debug for PreLaunchRequest {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn response_reason_historical_strings() {
+        //
+        // Arrange
+        //
+        let expected = vec![
+            (
+                ResponseReason::AddressAddedToWhitelist,
+                "Address added to whitelist",
+            ),
+            (ResponseReason::AddressesDeleted, "Addresses deleted"),
+            (
+                ResponseReason::AlreadyDisconnectedFromCompute,
+                "Already disconnected from compute",
+            ),
+            (
+                ResponseReason::AttemptCoordinatedNodePause,
+                "Attempt coordinated node pause",
+            ),
+            (
+                ResponseReason::AttemptCoordinatedNodeResume,
+                "Attempt coordinated node resume",
+            ),
+            (
+                ResponseReason::AttemptSendSharedConfig,
+                "Attempt send shared config",
+            ),
+            (ResponseReason::BlockCommitted, "Block committed"),
+            (ResponseReason::BlockCompleteStored, "Block complete stored"),
+            (ResponseReason::BlockIsNotValid, "Block is not valid"),
+            (ResponseReason::BlockIsValid, "Block is valid"),
+            (ResponseReason::BlockMiningNotified, "Block mining notified"),
+            (ResponseReason::BlockPowComplete, "Block PoW complete"),
+            (
+                ResponseReason::BlockReceivedAlreadyStored,
+                "Block received already stored",
+            ),
+            (
+                ResponseReason::BlockReceivedAlreadyStoredNotFlushed,
+                "Block received already stored not flushed",
+            ),
+            (
+                ResponseReason::BlockReceivedNotAddedPowInvalid,
+                "Block received not added. PoW invalid",
+            ),
+            (
+                ResponseReason::BlockReceivedToBeAdded,
+                "Block received to be added",
+            ),
+            (ResponseReason::BlockShutdown, "Block shutdown"),
+            (
+                ResponseReason::BlockchainItemFetchedFromStorage,
+                "Blockchain item fetched from storage",
+            ),
+            (
+                ResponseReason::BlockchainItemReceived,
+                "Blockchain item received",
+            ),
+            (
+                ResponseReason::BlockchainItemReceivedBlockFailed,
+                "Blockchain item received: Block failed",
+            ),
+            (
+                ResponseReason::BlockchainItemReceivedBlockStored,
+                "Blockchain item received: Block stored",
+            ),
+            (
+                ResponseReason::BlockchainItemReceivedBlockStoredDone,
+                "Blockchain item received: Block stored(Done)",
+            ),
+            (
+                ResponseReason::CancelCoordinatedShutdown,
+                "Cancel coordinated shutdown",
+            ),
+            (
+                ResponseReason::CatchUpStoredBlocks,
+                "Catch up stored blocks",
+            ),
+            (
+                ResponseReason::CoinbaseTransactionInvalid,
+                "Coinbase transaction invalid",
+            ),
+            (ResponseReason::ComputeShutdown, "Compute Shutdown"),
+            (ResponseReason::ConnectedToCompute, "Connected to compute"),
+            (
+                ResponseReason::CoordinatedShutdownBlockPassed,
+                "Coordinated shutdown block already passed",
+            ),
+            (
+                ResponseReason::DisconnectedFromCompute,
+                "Disconnected from compute",
+            ),
+            (ResponseReason::DonationRequested, "Donation Requested"),
+            (
+                ResponseReason::DruidAlreadyExecuting,
+                "DRUID has all participants and is executing",
+            ),
+            (
+                ResponseReason::DruidCancellationExpired,
+                "DRUID cancellation expired",
+            ),
+            (
+                ResponseReason::DruidCancellationExpiryTooFar,
+                "DRUID cancellation expiry too far in the future",
+            ),
+            (ResponseReason::DruidNotPending, "DRUID is not pending"),
+            (
+                ResponseReason::DruidParticipantNotFound,
+                "No DRUID transaction signed by this public key",
+            ),
+            (
+                ResponseReason::DruidParticipationCancelled,
+                "DRUID participation cancelled",
+            ),
+            (
+                ResponseReason::ExternalPowForwarded,
+                "External PoW forwarded to compute",
+            ),
+            (ResponseReason::ExternalPowRejected, "External PoW rejected"),
+            (
+                ResponseReason::FailedToConnectToCompute,
+                "Failed to connect to compute",
+            ),
+            (
+                ResponseReason::FailedToDisconnectFromCompute,
+                "Failed to disconnect from compute",
+            ),
+            (
+                ResponseReason::FailedToInitiateCoordinatedPause,
+                "Failed to initiate coordinated pause",
+            ),
+            (
+                ResponseReason::FailedToInitiateCoordinatedResume,
+                "Failed to initiate coordinated resume",
+            ),
+            (
+                ResponseReason::FailedToInitiateSharingOfConfig,
+                "Failed to initiate sharing of config",
+            ),
+            (
+                ResponseReason::FailedToSendRuntimeDataToPeer,
+                "Failed to send runtime data to peer",
+            ),
+            (
+                ResponseReason::FailedToSendStartupRequestsOnReconnection,
+                "Failed to send startup requests on reconnection",
+            ),
+            (ResponseReason::FeeTooLow, "Fee too low"),
+            (ResponseReason::FirstBlockCommitted, "First Block committed"),
+            (
+                ResponseReason::IgnoreDonationOverPeerLimit,
+                "Ignore donation over peer limit",
+            ),
+            (
+                ResponseReason::IgnoreDonationOverTotalLimit,
+                "Ignore donation over total limit",
+            ),
+            (
+                ResponseReason::IgnoreUnexpectedTransaction,
+                "Ignore unexpected transaction",
+            ),
+            (ResponseReason::InitiatePauseNode, "Initiate pause node"),
+            (
+                ResponseReason::InsufficientFundsForPayment,
+                "Insufficient funds for payment",
+            ),
+            (
+                ResponseReason::InvalidBlockMiningNotifier,
+                "Invalid block mining notifier",
+            ),
+            (
+                ResponseReason::InvalidDruidCancellationSignature,
+                "Invalid DRUID cancellation signature",
+            ),
+            (ResponseReason::InvalidPowForBlock, "Invalid PoW for block"),
+            (
+                ResponseReason::LocktimeBeyondMaximum,
+                "Transaction locktime beyond maximum offset from current block",
+            ),
+            (ResponseReason::MinerNotAuthorized, "Miner not authorized"),
+            (
+                ResponseReason::MinerRemovalRequestReceived,
+                "Miner removal request received",
+            ),
+            (
+                ResponseReason::MinerRemovedAckFromNonComputePeer,
+                "Received miner removed ack from non-compute peer",
+            ),
+            (
+                ResponseReason::MinerUnauthorizedFromNonComputePeer,
+                "Received miner unauthorized notification from non-compute peer",
+            ),
+            (ResponseReason::NewAddressGenerated, "New address generated"),
+            (
+                ResponseReason::NewAddressReadyToBeSent,
+                "New address ready to be sent",
+            ),
+            (
+                ResponseReason::NextPaymentTransactionReady,
+                "Next payment transaction ready",
+            ),
+            (
+                ResponseReason::NoSharedConfigToApply,
+                "No shared config to apply",
+            ),
+            (
+                ResponseReason::NoValidTransactionsProvided,
+                "No valid transactions provided",
+            ),
+            (ResponseReason::NodeIsConnected, "Node is connected"),
+            (ResponseReason::NodeIsDisconnected, "Node is disconnected"),
+            (ResponseReason::NodeIsMining, "Node is mining"),
+            (ResponseReason::NodeIsNotMining, "Node is not mining"),
+            (ResponseReason::NodeIsPaused, "Node is paused"),
+            (ResponseReason::NodeIsResumed, "Node is resumed"),
+            (
+                ResponseReason::NodePauseConfigurationSet,
+                "Node pause configuration set",
+            ),
+            (ResponseReason::NodeResumed, "Node resumed"),
+            (
+                ResponseReason::NotBlockCurrentlyMined,
+                "Not block currently mined",
+            ),
+            (ResponseReason::NotImplementedYet, "Not implemented yet"),
+            (
+                ResponseReason::OutputBelowDustThreshold,
+                "Transaction output below dust threshold",
+            ),
+            (
+                ResponseReason::PartitionListComplete,
+                "Partition list complete",
+            ),
+            (
+                ResponseReason::PartitionPowComplete,
+                "Partition PoW complete",
+            ),
+            (
+                ResponseReason::PartitionPowReceivedSuccessfully,
+                "Partition PoW received successfully",
+            ),
+            (
+                ResponseReason::PartitionRequestResentAfterMissedRound,
+                "Partition request resent after missed round",
+            ),
+            (
+                ResponseReason::PaymentTransactionReceived,
+                "Payment transaction received",
+            ),
+            (ResponseReason::PipelineHalted, "Pipeline halted"),
+            (ResponseReason::PipelineReset, "Pipeline reset"),
+            (
+                ResponseReason::PowReceivedIsInvalid,
+                "PoW received is invalid",
+            ),
+            (
+                ResponseReason::PreBlockReceivedSuccessfully,
+                "Pre-block received successfully",
+            ),
+            (
+                ResponseReason::ReceiptAssetCreateTransactionReady,
+                "Receipt asset create transaction ready",
+            ),
+            (
+                ResponseReason::ReceivedBlockNotification,
+                "Received block notification",
+            ),
+            (ResponseReason::ReceivedBlockStored, "Received block stored"),
+            (
+                ResponseReason::ReceivedBlockStoredNotFromStoragePeer,
+                "Received block stored not from our storage peer",
+            ),
+            (
+                ResponseReason::ReceivedCoordinatedPauseRequest,
+                "Received coordinated pause request",
+            ),
+            (
+                ResponseReason::ReceivedCoordinatedResumeRequest,
+                "Received coordinated resume request",
+            ),
+            (ResponseReason::ReceivedDbItems, "Received Db Items"),
+            (
+                ResponseReason::ReceivedDbItemsWithInvalidChecksum,
+                "Received Db Items With Invalid Checksum",
+            ),
+            (
+                ResponseReason::ReceivedFirstFullPartitionRequest,
+                "Received first full partition request",
+            ),
+            (
+                ResponseReason::ReceivedInvalidDbItems,
+                "Received Invalid Db Items",
+            ),
+            (
+                ResponseReason::ReceivedPartitionRequestSuccessfully,
+                "Received partition request successfully",
+            ),
+            (
+                ResponseReason::ReceivedPowSuccessfully,
+                "Received PoW successfully",
+            ),
+            (
+                ResponseReason::ReceivedRandomNumberSuccessfully,
+                "Received random number successfully",
+            ),
+            (
+                ResponseReason::ReceivedReceiptBasedPaymentRequest,
+                "Received receipt-based payment request",
+            ),
+            (
+                ResponseReason::ReceivedReceiptBasedPaymentResponse,
+                "Received receipt-based payment response",
+            ),
+            (
+                ResponseReason::ReceivedRuntimeDataFromPeer,
+                "Received runtime data from peer",
+            ),
+            (
+                ResponseReason::ReceivedRuntimeDataFromUnknownPeer,
+                "Received runtime data from unknown peer",
+            ),
+            (
+                ResponseReason::ReceivedSharedConfig,
+                "Received shared config",
+            ),
+            (
+                ResponseReason::ReceivedUtxoFetchRequest,
+                "Received UTXO fetch request",
+            ),
+            (ResponseReason::ReceivedUtxoSet, "Received UTXO set"),
+            (
+                ResponseReason::RemovingUnauthorizedMiner,
+                "Removing unauthorized miner",
+            ),
+            (
+                ResponseReason::RequestPaymentAddress,
+                "Request Payment Address",
+            ),
+            (ResponseReason::RequestUtxoSet, "Request UTXO set"),
+            (
+                ResponseReason::ResentBlockToStorageOnReconnection,
+                "Resent block to storage on reconnection",
+            ),
+            (
+                ResponseReason::SentRuntimeDataToPeer,
+                "Sent runtime data to peer",
+            ),
+            (
+                ResponseReason::SentStartupRequestsOnReconnection,
+                "Sent startup requests on reconnection",
+            ),
+            (ResponseReason::SentUtxoRequest, "Sent UTXO Request"),
+            (ResponseReason::SharedConfigApplied, "Shared config applied"),
+            (ResponseReason::Shutdown, "Shutdown"),
+            (ResponseReason::ShutdownPending, "Shutdown pending"),
+            (ResponseReason::SnapshotApplied, "Snapshot applied"),
+            (
+                ResponseReason::SnapshotAppliedFetchMissingBlocks,
+                "Snapshot applied: Fetch missing blocks",
+            ),
+            (ResponseReason::SnapshotForced, "Snapshot forced"),
+            (
+                ResponseReason::SomeTransactionsInvalid,
+                "Some transactions invalid. Adding valid transactions only",
+            ),
+            (
+                ResponseReason::StartCoordinatedShutdown,
+                "Start coordinated shutdown",
+            ),
+            (
+                ResponseReason::StaticMinerAddressSet,
+                "Static miner address set",
+            ),
+            (
+                ResponseReason::TransactionPoolFull,
+                "Transaction pool for this compute node is full",
+            ),
+            (
+                ResponseReason::TransactionRejectedByPolicy,
+                "Transaction rejected by node policy",
+            ),
+            (
+                ResponseReason::TransactionTooLarge,
+                "Transaction larger than the maximum block size",
+            ),
+            (
+                ResponseReason::TransactionsAddedToTxPool,
+                "Transactions added to tx pool",
+            ),
+            (
+                ResponseReason::TransactionsCommitted,
+                "Transactions committed",
+            ),
+            (
+                ResponseReason::UnsupportedTransactionVersion,
+                "Transaction version is not supported",
+            ),
+            (
+                ResponseReason::UtxoSetNearCap,
+                "UTXO set near its size cap: transaction must not grow it",
+            ),
+            (
+                ResponseReason::WinningPowIntakeOpen,
+                "Winning PoW intake open",
+            ),
+            (
+                ResponseReason::Exit("Test timeout elapsed"),
+                "Test timeout elapsed",
+            ),
+        ];
+
+        //
+        // Act
+        //
+        let actual: Vec<_> = expected
+            .iter()
+            .map(|(reason, _)| (*reason, reason.to_string()))
+            .collect();
+
+        //
+        // Assert
+        //
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(reason, s)| (reason, s.to_owned()))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn default_format_handshake_understood_by_previous_peers() {
+        //
+        // Arrange
+        //
+        let address: SocketAddr = "127.0.0.1:12300".parse().unwrap();
+        let request = |f| CommMessage::handshake_request(2, NodeType::Compute, address, f);
+        let response =
+            |f| CommMessage::handshake_response(2, NodeType::Compute, vec![], address, f);
+
+        //
+        // Act
+        //
+        let default_request = request(CommsFormat::Bincode);
+        let default_response = response(CommsFormat::Bincode);
+        let json_request = request(CommsFormat::Json).with_handshake_format();
+        let json_response = response(CommsFormat::Json).with_handshake_format();
+        let read_default = default_request.clone().with_handshake_format();
+
+        //
+        // Assert
+        //
+        assert!(matches!(
+            default_request,
+            CommMessage::HandshakeRequest { .. }
+        ));
+        assert!(matches!(
+            default_response,
+            CommMessage::HandshakeResponse { .. }
+        ));
+        assert!(matches!(
+            json_request,
+            CommMessage::HandshakeRequestWithFormat {
+                comms_format: CommsFormat::Json,
+                ..
+            }
+        ));
+        assert!(matches!(
+            json_response,
+            CommMessage::HandshakeResponseWithFormat {
+                comms_format: CommsFormat::Json,
+                ..
+            }
+        ));
+        assert!(matches!(
+            read_default,
+            CommMessage::HandshakeRequestWithFormat {
+                comms_format: CommsFormat::Bincode,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn utxo_set_serialized_in_both_formats() {
+        //
+        // Arrange
+        //
+        let utxo_set: UtxoSet = vec![
+            (OutPoint::new("000000".to_owned(), 0), TxOut::new()),
+            (OutPoint::new("000001".to_owned(), 1), TxOut::new()),
+        ]
+        .into_iter()
+        .collect();
+        let request = MineRequest::SendUtxoSet {
+            utxo_set: utxo_set.clone(),
+        };
+
+        //
+        // Act
+        //
+        let bincode = CommsFormat::Bincode.serialize(&request).unwrap();
+        let json = CommsFormat::Json.serialize(&request).unwrap();
+        let from_json = CommsFormat::Json.deserialize::<MineRequest>(&json);
+
+        //
+        // Assert
+        //
+        let map_encoding = bincode::serialize(&utxo_set).unwrap();
+        assert_eq!(&bincode[4..], &map_encoding[..]);
+        assert!(matches!(
+            from_json,
+            Ok(MineRequest::SendUtxoSet { utxo_set: actual }) if actual == utxo_set
+        ));
+    }
+}
@@ -30,6 +30,7 @@ pub mod threaded_call;
 mod tracked_utxo;
 pub mod transaction_gen;
 pub mod transactor;
+pub mod tx_policy;
 mod unicorn;
 pub mod upgrade;
 mod user;
@@ -38,7 +39,9 @@ pub mod wallet;
 
 pub use api::routes;
 pub use compute::ComputeNode;
-pub use constants::SANC_LIST_PROD;
+pub use constants::{
+    API_LATEST_BLOCK_LONGPOLL_MAX_PENDING, API_LATEST_BLOCK_LONGPOLL_TIMEOUT_MS, SANC_LIST_PROD,
+};
 pub use interfaces::Rs2JsMsg;
 pub use interfaces::{ComputeRequest, MinerInterface, Response, StorageInterface};
 pub use miner::MinerNode;
@@ -50,7 +53,7 @@ pub use utils::LocalEvent;
 pub use utils::{
     create_and_save_fake_to_wallet, create_valid_transaction, get_sanction_addresses,
     get_test_common_unicorn, loop_connnect_to_peers_async, loop_wait_connnect_to_peers_async,
-    loops_re_connect_disconnect, shutdown_connections, ResponseResult,
+    loops_re_connect_disconnect, shutdown_connections, tracing_log_reloadable_init, ResponseResult,
 };
 pub use wallet::WalletDb;
 
@@ -1,19 +1,22 @@
 use crate::comms_handler::{CommsError, Event, TcpTlsConfig};
 use crate::configurations::{ExtraNodeParams, MinerNodeConfig, TlsPrivateInfo};
 use crate::interfaces::{
-    BlockchainItem, ComputeRequest, MineApiRequest, MineRequest, MinerInterface, NodeType, PowInfo,
-    ProofOfWork, Response, Rs2JsMsg, StorageRequest, UtxoFetchType, UtxoSet,
+    BlockchainItem, CommsFormat, ComputeRequest, MineApiRequest, MineRequest, MinerInterface,
+    NodeType, PowInfo, ProofOfWork, Response, ResponseReason, Rs2JsMsg, StorageRequest,
+    UtxoFetchType, UtxoSet,
 };
 use crate::threaded_call::{ThreadedCallChannel, ThreadedCallSender};
 use crate::transactor::Transactor;
 use crate::utils::{
     self, apply_mining_tx, construct_coinbase_tx, format_parition_pow_address,
     generate_pow_for_block, get_paiments_for_wallet, get_paiments_for_wallet_from_utxo,
-    to_api_keys, to_route_pow_infos, try_send_to_ui, ApiKeys, DeserializedBlockchainItem,
-    LocalEvent, LocalEventChannel, LocalEventSender, ResponseResult, RoutesPoWInfo,
-    RunningTaskOrResult,
+    mining_difficulty_from_bits, to_route_pow_infos, to_signed_api_keys, try_send_to_ui,
+    validate_address, validate_pow_block, ApiKeys, DeserializedBlockchainItem, LocalEvent,
+    LocalEventChannel, LocalEventSender, ResponseResult, RoutesPoWInfo, RunningTaskOrResult,
+};
+use crate::wallet::{
+    KdfParams, LockedCoinbase, PassphraseConfig, WalletDb, WalletDbError, DB_SPEC,
 };
-use crate::wallet::{LockedCoinbase, WalletDb, WalletDbError, DB_SPEC};
 use crate::{db_utils, Node};
 use async_trait::async_trait;
 use bincode::{deserialize, serialize};
@@ -67,6 +70,10 @@ pub struct BlockPoWInfo {
 pub struct BlockPoWReceived {
     block: BlockHeader,
     reward: TokenAmount,
+    /// Header with our coinbase applied: the header to find a nonce for with `submit_pow`
+    mining_header: Option<BlockHeader>,
+    /// Number of leading zero bytes required for the `mining_header` PoW hash
+    mining_difficulty: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -155,11 +162,15 @@ pub struct MinerNode {
     current_coinbase: Option<(String, Transaction)>,
     current_payment_address: Option<String>,
     static_miner_address: Arc<RwLock<Option<String>>>,
+    miner_reward_address: Option<String>,
     aggregation_status: AggregationStatus,
     wait_partition_task: bool,
+    current_round: Option<u64>,
+    partition_entry_sent: bool,
     received_utxo_set: Option<UtxoSet>,
     mining_partition_task: RunningTaskOrResult<(ProofOfWork, PowInfo, SocketAddr)>,
     mining_block_task: RunningTaskOrResult<BlockPoWInfo>,
+    current_block_pow: Option<BlockPoWInfo>,
     mining_api_key: Option<String>,
     blockchain_item_received: Option<(String, BlockchainItem, SocketAddr)>,
     api_info: (SocketAddr, Option<TlsPrivateInfo>, ApiKeys, RoutesPoWInfo),
@@ -180,24 +191,45 @@ impl MinerNode {
             .ok_or(MinerError::ConfigError("Invalid compute index"))?
             .address;
 
+        let custom_wallet_spec = extra.custom_wallet_spec.or_else(|| {
+            db_utils::data_dir_db_spec(
+                config.data_dir.as_deref(),
+                &format!("miner.{}", addr.port()),
+                &DB_SPEC,
+            )
+        });
+
         // Restore old keys if backup is present
         if config.backup_restore.unwrap_or(false) {
-            db_utils::restore_file_backup(config.miner_db_mode, &DB_SPEC, None).unwrap();
+            let spec = custom_wallet_spec.clone();
+            db_utils::restore_file_backup(config.miner_db_mode, &DB_SPEC, spec).unwrap();
         }
 
-        let wallet_db = WalletDb::new(
+        let passphrase_config = PassphraseConfig {
+            min_length: config.wallet_passphrase_min_length,
+            min_entropy_bits: config.wallet_passphrase_min_entropy_bits,
+            kdf: config
+                .wallet_kdf_iterations
+                .map(|iterations| KdfParams { iterations })
+                .unwrap_or_default(),
+        };
+        let wallet_db = WalletDb::new_with_passphrase_config_and_tuning(
             config.miner_db_mode,
             extra.wallet_db.take(),
             config.passphrase,
-            extra.custom_wallet_spec,
-        )?;
+            custom_wallet_spec,
+            passphrase_config,
+            &config.wallet_db_tuning.clone().unwrap_or_default(),
+        )?
+        .with_max_active_addresses(config.wallet_max_active_addresses)
+        .with_encryption_at_rest(config.wallet_encrypted_at_rest.unwrap_or(false))?;
         let disable_tcp_listener = extra.disable_tcp_listener;
         let tcp_tls_config = TcpTlsConfig::from_tls_spec(addr, &config.tls_config)?;
         let api_addr = SocketAddr::new(addr.ip(), config.miner_api_port);
         let api_tls_info = config
             .miner_api_use_tls
             .then(|| tcp_tls_config.clone_private_info());
-        let api_keys = to_api_keys(config.api_keys.clone());
+        let api_keys = to_signed_api_keys(config.api_keys.clone(), config.api_signing_keys.clone());
         let node = Node::new(
             &tcp_tls_config,
             config.peer_limit,
@@ -206,15 +238,30 @@ impl MinerNode {
             false,
         )
         .await?;
+        node.set_comms_format(config.comms_format).await;
+        node.set_compatible_network_versions(config.compatible_network_versions.iter().copied())
+            .await;
         let api_pow_info = to_route_pow_infos(config.routes_pow.clone());
         let static_miner_address = Arc::new(RwLock::new(config.static_miner_address.clone()));
+        let miner_reward_address = match &config.miner_reward_address {
+            Some(address) => match validate_address(address, None) {
+                Ok(address) => Some(address.into_string()),
+                Err(_) => return Err(MinerError::ConfigError("Invalid miner reward address")),
+            },
+            None => None,
+        };
         let mining_api_key = config.mining_api_key.clone();
         let address_aggregation_limit = config.address_aggregation_limit;
+        let threaded_calls = ThreadedCallChannel::with_config(
+            config.api_threaded_call_capacity,
+            config.api_threaded_call_timeout_ms,
+        )
+        .map_err(MinerError::ConfigError)?;
 
         MinerNode {
             node,
             local_events: Default::default(),
-            threaded_calls: Default::default(),
+            threaded_calls,
             ui_feedback_tx: Default::default(),
             wallet_db,
             compute_addr,
@@ -225,11 +272,15 @@ impl MinerNode {
             current_coinbase: None,
             current_payment_address: None,
             static_miner_address,
+            miner_reward_address,
             aggregation_status: Default::default(),
             received_utxo_set: None,
             wait_partition_task: Default::default(),
+            current_round: None,
+            partition_entry_sent: false,
             mining_partition_task: Default::default(),
             mining_block_task: Default::default(),
+            current_block_pow: None,
             blockchain_item_received: Default::default(),
             mining_api_key,
             api_info: (api_addr, api_tls_info, api_keys, api_pow_info),
@@ -390,10 +441,10 @@ impl MinerNode {
         if let Ok(resp) = &response {
             let ui_message = match resp.success {
                 true => Rs2JsMsg::Info {
-                    info: resp.reason.to_owned(),
+                    info: resp.reason.to_string(),
                 },
                 false => Rs2JsMsg::Error {
-                    error: resp.reason.to_owned(),
+                    error: resp.reason.to_string(),
                 },
             };
             try_send_to_ui(self.ui_feedback_tx.as_ref(), ui_message).await;
@@ -402,15 +453,15 @@ impl MinerNode {
         match response {
             Ok(Response {
                 success: true,
-                reason: "Sent startup requests on reconnection",
+                reason: ResponseReason::SentStartupRequestsOnReconnection,
             }) => debug!("Sent startup requests on reconnection"),
             Ok(Response {
                 success: false,
-                reason: "Failed to send startup requests on reconnection",
+                reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
             }) => error!("Failed to send startup requests on reconnection"),
             Ok(Response {
                 success: true,
-                reason: "Shutdown",
+                reason: ResponseReason::Shutdown,
             }) => {
                 warn!("Shutdown now");
                 try_send_to_ui(self.ui_feedback_tx.as_ref(), Rs2JsMsg::Exit).await;
@@ -418,7 +469,7 @@ impl MinerNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Blockchain item received",
+                reason: ResponseReason::BlockchainItemReceived,
             }) => {
                 if let Some((key, item, peer)) = self.blockchain_item_received.as_ref() {
                     log_received_blockchain_item(key, item, peer);
@@ -428,13 +479,13 @@ impl MinerNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Received random number successfully",
+                reason: ResponseReason::ReceivedRandomNumberSuccessfully,
             }) => {
                 info!("RANDOM NUMBER RECEIVED: {:?}", self.rand_num);
             }
             Ok(Response {
                 success: true,
-                reason: "Partition PoW complete",
+                reason: ResponseReason::PartitionPowComplete,
             }) => {
                 if self.process_found_partition_pow().await {
                     info!("Partition Pow found and sent");
@@ -442,25 +493,25 @@ impl MinerNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Pre-block received successfully",
+                reason: ResponseReason::PreBlockReceivedSuccessfully,
             }) => {
                 info!("PRE-BLOCK RECEIVED");
             }
             Ok(Response {
                 success: true,
-                reason: "Block is valid",
+                reason: ResponseReason::BlockIsValid,
             }) => {
                 info!("MERKLE ROOT VALID");
             }
             Ok(Response {
                 success: false,
-                reason: "Block is not valid",
+                reason: ResponseReason::BlockIsNotValid,
             }) => {
                 info!("MERKLE ROOT INVALID");
             }
             Ok(Response {
                 success: true,
-                reason: "Block PoW complete",
+                reason: ResponseReason::BlockPowComplete,
             }) => {
                 if self.process_found_block_pow().await {
                     info!("Block PoW found and sent");
@@ -468,53 +519,53 @@ impl MinerNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Received UTXO set",
+                reason: ResponseReason::ReceivedUtxoSet,
             }) => {
                 self.update_running_total().await;
             }
             Ok(Response {
                 success: true,
-                reason: "Node is not mining",
+                reason: ResponseReason::NodeIsNotMining,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Node is mining",
+                reason: ResponseReason::NodeIsMining,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Node is connected",
+                reason: ResponseReason::NodeIsConnected,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             }) => {}
             Ok(Response {
                 success: true, // Not always an error
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Connected to compute",
+                reason: ResponseReason::ConnectedToCompute,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Disconnected from compute",
+                reason: ResponseReason::DisconnectedFromCompute,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Failed to connect to compute",
+                reason: ResponseReason::FailedToConnectToCompute,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Failed to disconnect from compute",
+                reason: ResponseReason::FailedToDisconnectFromCompute,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Already disconnected from compute",
+                reason: ResponseReason::AlreadyDisconnectedFromCompute,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Initiate pause node",
+                reason: ResponseReason::InitiatePauseNode,
             }) => {
                 info!("Initiate pause node");
                 if let Err(e) = self
@@ -527,33 +578,33 @@ impl MinerNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Static miner address set",
+                reason: ResponseReason::StaticMinerAddressSet,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Node is paused",
+                reason: ResponseReason::NodeIsPaused,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Node is resumed",
+                reason: ResponseReason::NodeIsResumed,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Received miner removed ack from non-compute peer",
+                reason: ResponseReason::MinerRemovedAckFromNonComputePeer,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Sent UTXO Request",
+                reason: ResponseReason::SentUtxoRequest,
             }) => {
                 debug!("Sent UTXO Request for wallet update")
             }
             Ok(Response {
                 success: false,
-                reason: "Received miner unauthorized notification from non-compute peer",
+                reason: ResponseReason::MinerUnauthorizedFromNonComputePeer,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Miner not authorized",
+                reason: ResponseReason::MinerNotAuthorized,
             }) => return ResponseResult::Exit,
             Ok(Response {
                 success: true,
@@ -596,13 +647,13 @@ impl MinerNode {
                     self.wait_partition_task = false;
                     return Some(Ok(Response {
                         success: true,
-                        reason: "Partition PoW complete",
+                        reason: ResponseReason::PartitionPowComplete,
                     }));
                 }
                 _ = self.mining_block_task.wait(), if !self.wait_partition_task => {
                     return Some(Ok(Response {
                         success: true,
-                        reason: "Block PoW complete",
+                        reason: ResponseReason::BlockPowComplete,
                     }));
                 }
                 Some(event) = self.local_events.rx.recv() => {
@@ -615,7 +666,7 @@ impl MinerNode {
                 }
                 reason = &mut *exit => return Some(Ok(Response {
                     success: true,
-                    reason,
+                    reason: ResponseReason::Exit(reason),
                 }))
             }
         }
@@ -637,15 +688,17 @@ impl MinerNode {
                     error!("Failed to send startup requests on reconnect: {}", err);
                     return Some(Response {
                         success: false,
-                        reason: "Failed to send startup requests on reconnection",
+                        reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
                     });
                 }
                 Some(Response {
                     success: true,
-                    reason: "Sent startup requests on reconnection",
+                    reason: ResponseReason::SentStartupRequestsOnReconnection,
                 })
             }
             LocalEvent::CoordinatedShutdown(_) => None,
+            LocalEvent::CancelShutdown => None,
+            LocalEvent::ForceSnapshot => None,
             LocalEvent::Ignore => None,
         }
     }
@@ -657,9 +710,13 @@ impl MinerNode {
     /// * `event`   - Event object to be handled.
     async fn handle_event(&mut self, event: Event) -> Result<Option<Response>> {
         match event {
-            Event::NewFrame { peer, frame } => {
+            Event::NewFrame {
+                peer,
+                frame,
+                format,
+            } => {
                 let peer_span = error_span!("peer", ?peer);
-                self.handle_new_frame(peer, frame)
+                self.handle_new_frame(peer, frame, format)
                     .instrument(peer_span)
                     .await
             }
@@ -672,12 +729,14 @@ impl MinerNode {
     ///
     /// * `peer`   - Socket address of the peer sending the message.
     /// * `frame`   - Bytes object holding the frame
+    /// * `format`  - Serialization format of the frame
     async fn handle_new_frame(
         &mut self,
         peer: SocketAddr,
         frame: Bytes,
+        format: CommsFormat,
     ) -> Result<Option<Response>> {
-        let req = deserialize::<MineRequest>(&frame).map_err(|error| {
+        let req = format.deserialize::<MineRequest>(&frame).map_err(|error| {
             warn!(?error, "frame-deserialize");
             error
         })?;
@@ -705,6 +764,7 @@ impl MinerNode {
             SendBlock {
                 pow_info,
                 rnum,
+                partition_difficulty,
                 win_coinbases,
                 reward,
                 block,
@@ -714,6 +774,7 @@ impl MinerNode {
                     peer,
                     pow_info,
                     rnum,
+                    partition_difficulty,
                     win_coinbases,
                     reward,
                     block,
@@ -769,12 +830,13 @@ impl MinerNode {
                 .ok()
                 .map(|_| Response {
                     success: true,
-                    reason: "Sent UTXO Request",
+                    reason: ResponseReason::SentUtxoRequest,
                 }),
             MineApiRequest::SetStaticMinerAddress { address } => {
                 Some(self.handle_set_static_miner_address(address).await)
             }
             MineApiRequest::GetStaticMinerAddress => self.handle_get_static_miner_address().await,
+            MineApiRequest::SubmitPoW { nonce } => Some(self.handle_submit_pow(nonce).await),
         }
     }
 
@@ -783,7 +845,7 @@ impl MinerNode {
 
         Response {
             success: true,
-            reason: "Static miner address set",
+            reason: ResponseReason::StaticMinerAddressSet,
         }
     }
 
@@ -819,12 +881,12 @@ impl MinerNode {
             .await;
             Response {
                 success: false,
-                reason: "Miner not authorized",
+                reason: ResponseReason::MinerNotAuthorized,
             }
         } else {
             Response {
                 success: false,
-                reason: "Received miner unauthorized notification from non-compute peer",
+                reason: ResponseReason::MinerUnauthorizedFromNonComputePeer,
             }
         }
     }
@@ -842,12 +904,12 @@ impl MinerNode {
             .await;
             Response {
                 success: true,
-                reason: "Node is paused",
+                reason: ResponseReason::NodeIsPaused,
             }
         } else {
             Response {
                 success: false,
-                reason: "Received miner removed ack from non-compute peer",
+                reason: ResponseReason::MinerRemovedAckFromNonComputePeer,
             }
         }
     }
@@ -859,7 +921,7 @@ impl MinerNode {
         if join_handles.is_empty() {
             return Response {
                 success: false,
-                reason: "Already disconnected from compute",
+                reason: ResponseReason::AlreadyDisconnectedFromCompute,
             };
         }
         for join_handle in join_handles {
@@ -867,7 +929,7 @@ impl MinerNode {
                 error!("Failed to disconnect from compute: {}", err);
                 return Response {
                     success: false,
-                    reason: "Failed to disconnect from compute",
+                    reason: ResponseReason::FailedToDisconnectFromCompute,
                 };
             }
         }
@@ -880,7 +942,7 @@ impl MinerNode {
         .await;
         Response {
             success: true,
-            reason: "Disconnected from compute",
+            reason: ResponseReason::DisconnectedFromCompute,
         }
     }
 
@@ -891,7 +953,7 @@ impl MinerNode {
             error!("Failed to connect to compute: {e:?}");
             return Response {
                 success: false,
-                reason: "Failed to connect to compute",
+                reason: ResponseReason::FailedToConnectToCompute,
             };
         }
         try_send_to_ui(
@@ -905,7 +967,7 @@ impl MinerNode {
         // because we don't necessarily want to start mining
         Response {
             success: true,
-            reason: "Connected to compute",
+            reason: ResponseReason::ConnectedToCompute,
         }
     }
 
@@ -922,7 +984,7 @@ impl MinerNode {
 
             return Response {
                 success: false,
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             };
         }
 
@@ -933,7 +995,7 @@ impl MinerNode {
             // Pause mining
             Response {
                 success: true,
-                reason: "Initiate pause node",
+                reason: ResponseReason::InitiatePauseNode,
             }
         } else {
             // Resume mining
@@ -948,7 +1010,7 @@ impl MinerNode {
                 .await;
                 return Response {
                     success: false,
-                    reason: "Failed to send startup requests on reconnection",
+                    reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
                 };
             }
             *self.pause_node.write().await = false;
@@ -961,7 +1023,7 @@ impl MinerNode {
             .await;
             Response {
                 success: true,
-                reason: "Node is resumed",
+                reason: ResponseReason::NodeIsResumed,
             }
         }
     }
@@ -978,7 +1040,7 @@ impl MinerNode {
             .await;
             return Response {
                 success: true,
-                reason: "Node is not mining",
+                reason: ResponseReason::NodeIsNotMining,
             };
         }
         try_send_to_ui(
@@ -990,7 +1052,7 @@ impl MinerNode {
         .await;
         Response {
             success: true,
-            reason: "Node is mining",
+            reason: ResponseReason::NodeIsMining,
         }
     }
 
@@ -1006,7 +1068,7 @@ impl MinerNode {
             .await;
             return Response {
                 success: true,
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             };
         }
         try_send_to_ui(
@@ -1018,7 +1080,7 @@ impl MinerNode {
         .await;
         Response {
             success: true,
-            reason: "Node is connected",
+            reason: ResponseReason::NodeIsConnected,
         }
     }
 
@@ -1034,7 +1096,7 @@ impl MinerNode {
 
         Some(Response {
             success: true,
-            reason: "Shutdown",
+            reason: ResponseReason::Shutdown,
         })
     }
 
@@ -1073,6 +1135,7 @@ impl MinerNode {
     /// ### Arguments
     ///
     /// * `peer`     - Sending peer's socket address
+    /// * `partition_difficulty` - Difficulty of the partition PoW
     /// * `pre_block` - New block to be mined
     /// * `reward`    - The block reward to be paid on successful PoW
     #[allow(clippy::too_many_arguments)]
@@ -1081,13 +1144,21 @@ impl MinerNode {
         peer: SocketAddr,
         pow_info: PowInfo,
         rand_num: Vec<u8>,
+        partition_difficulty: usize,
         win_coinbases: Vec<String>,
         reward: TokenAmount,
         pre_block: Option<BlockHeader>,
         b_num: u64,
     ) -> Option<Response> {
+        let missed_round = self.track_mining_round(peer, pow_info, pre_block.is_some());
         let process_rnd = self
-            .receive_random_number(peer, pow_info, rand_num, win_coinbases)
+            .receive_random_number(
+                peer,
+                pow_info,
+                rand_num,
+                partition_difficulty,
+                win_coinbases,
+            )
             .await;
         let process_block = if let Some(pre_block) = pre_block {
             self.receive_pre_block(peer, pre_block, reward).await
@@ -1098,31 +1169,78 @@ impl MinerNode {
         self.wallet_db.filter_locked_coinbase(b_num).await;
         // TODO: should we check even if coinbase was not committed?
         self.check_for_threshold_and_send_aggregation_tx().await;
+        self.send_archived_addresses_utxo_request().await;
+
+        if missed_round {
+            match self.send_partition_request().await {
+                Ok(()) => {
+                    return Some(Response {
+                        success: true,
+                        reason: ResponseReason::PartitionRequestResentAfterMissedRound,
+                    })
+                }
+                Err(e) => error!("Failed to resend partition request: {:?}", e),
+            }
+        }
 
         match (process_rnd, process_block) {
             (true, false) => Some(Response {
                 success: true,
-                reason: "Received random number successfully",
+                reason: ResponseReason::ReceivedRandomNumberSuccessfully,
             }),
             (_, true) => Some(Response {
                 success: true,
-                reason: "Pre-block received successfully",
+                reason: ResponseReason::PreBlockReceivedSuccessfully,
             }),
             (false, false) => None,
         }
     }
 
+    /// Track the mining round flooded by compute, returning whether we were
+    /// left out of the partition list without having sent a partition entry
+    /// since the previous flood
+    ///
+    /// ### Arguments
+    ///
+    /// * `peer`         - Sending peer's socket address
+    /// * `pow_info`     - PoW info of the flooded round
+    /// * `in_partition` - Whether the flood included the block to mine
+    fn track_mining_round(
+        &mut self,
+        peer: SocketAddr,
+        pow_info: PowInfo,
+        in_partition: bool,
+    ) -> bool {
+        if peer != self.compute_address() {
+            return false;
+        }
+
+        let previous_round = self.current_round;
+        self.current_round = previous_round.max(Some(pow_info.b_num));
+        let entry_sent = std::mem::take(&mut self.partition_entry_sent);
+
+        // The partition list is final once intake is restricted to participants
+        previous_round.is_some() && pow_info.participant_only && !in_partition && !entry_sent
+    }
+
+    /// Get the last mining round flooded by compute
+    pub fn get_current_round(&self) -> Option<u64> {
+        self.current_round
+    }
+
     /// Handles the receipt of the random number of partitioning
     ///
     /// ### Arguments
     ///
     /// * `peer`     - Sending peer's socket address
     /// * `rand_num` - random num to be recieved in Vec<u8>
+    /// * `partition_difficulty` - Difficulty of the partition PoW
     async fn receive_random_number(
         &mut self,
         peer: SocketAddr,
         pow_info: PowInfo,
         rand_num: Vec<u8>,
+        partition_difficulty: usize,
         win_coinbases: Vec<String>,
     ) -> bool {
         if peer != self.compute_address() {
@@ -1139,7 +1257,7 @@ impl MinerNode {
             self.commit_found_coinbase().await;
         }
 
-        self.start_generate_partition_pow(peer, pow_info, rand_num)
+        self.start_generate_partition_pow(peer, pow_info, rand_num, partition_difficulty)
             .await;
         true
     }
@@ -1164,6 +1282,8 @@ impl MinerNode {
         let new_block = BlockPoWReceived {
             block: pre_block,
             reward,
+            mining_header: None,
+            mining_difficulty: None,
         };
 
         let new_b_num = Some(new_block.block.b_num);
@@ -1208,12 +1328,12 @@ impl MinerNode {
         if valid {
             Some(Response {
                 success: true,
-                reason: "Block is valid",
+                reason: ResponseReason::BlockIsValid,
             })
         } else {
             Some(Response {
                 success: false,
-                reason: "Block is not valid",
+                reason: ResponseReason::BlockIsNotValid,
             })
         }
     }
@@ -1231,18 +1351,7 @@ impl MinerNode {
 
     /// Process the found PoW sending it to the related peer and logging errors
     pub async fn process_found_block_pow(&mut self) -> bool {
-        let BlockPoWInfo {
-            peer,
-            start_time,
-            header:
-                BlockHeader {
-                    b_num,
-                    nonce_and_mining_tx_hash: (nonce, coinbase_hash),
-                    ..
-                },
-            coinbase,
-            ..
-        } = match self.mining_block_task.completed_result() {
+        let info = match self.mining_block_task.completed_result() {
             Some(Ok(v)) => v.clone(),
             Some(Err(e)) => {
                 let error = format!("process_found_block_pow PoW {:?}", e);
@@ -1256,30 +1365,90 @@ impl MinerNode {
             }
         };
 
-        if let Ok(elapsed) = start_time.elapsed() {
+        if let Ok(elapsed) = info.start_time.elapsed() {
             debug!("Found block in {}ms", elapsed.as_millis());
         }
 
         let is_paused = *self.pause_node.read().await;
 
         if !is_paused {
-            if let Err(e) = self.send_pow(peer, b_num, nonce, coinbase.clone()).await {
+            if let Err(e) = self.send_block_pow(info).await {
                 let error = format!("process_found_block_pow PoW {:?}", e);
                 error!("{:?}", &error);
                 try_send_to_ui(self.ui_feedback_tx.as_ref(), Rs2JsMsg::Error { error }).await;
                 return false;
             }
-
-            self.current_coinbase = store_last_coinbase(
-                &self.wallet_db,
-                Some((coinbase_hash.clone(), coinbase.clone())),
-            )
-            .await;
         }
 
         true
     }
 
+    /// Validate a block PoW nonce found by an external worker for the block
+    /// currently mined, forwarding it to compute if no PoW was sent for it yet
+    ///
+    /// ### Arguments
+    ///
+    /// * `nonce` - Nonce found for the current block header
+    pub async fn handle_submit_pow(&mut self, nonce: Vec<u8>) -> Response {
+        let rejected = Response {
+            success: false,
+            reason: ResponseReason::ExternalPowRejected,
+        };
+        if *self.pause_node.read().await {
+            return rejected;
+        }
+
+        let mut info = match &self.current_block_pow {
+            Some(info) => info.clone(),
+            None => return rejected,
+        };
+        let coinbase_hash = &info.header.nonce_and_mining_tx_hash.1;
+        if matches!(&self.current_coinbase, Some((hash, _)) if hash == coinbase_hash) {
+            return rejected;
+        }
+        info.header.nonce_and_mining_tx_hash.0 = nonce;
+        let mining_difficulty = mining_difficulty_from_bits(info.header.bits);
+        if !validate_pow_block(&info.header, mining_difficulty) {
+            return rejected;
+        }
+
+        if let Err(e) = self.send_block_pow(info).await {
+            error!("handle_submit_pow PoW {:?}", e);
+            return rejected;
+        }
+        // PoW sent for this block: a local result would only be sent again
+        self.mining_block_task.abort();
+
+        Response {
+            success: true,
+            reason: ResponseReason::ExternalPowForwarded,
+        }
+    }
+
+    /// Send a found block PoW to compute and keep its coinbase
+    ///
+    /// ### Arguments
+    ///
+    /// * `info` - Block PoW info with the found nonce
+    async fn send_block_pow(&mut self, info: BlockPoWInfo) -> Result<()> {
+        let BlockPoWInfo {
+            peer,
+            header:
+                BlockHeader {
+                    b_num,
+                    nonce_and_mining_tx_hash: (nonce, coinbase_hash),
+                    ..
+                },
+            coinbase,
+            ..
+        } = info;
+
+        self.send_pow(peer, b_num, nonce, coinbase.clone()).await?;
+        self.current_coinbase =
+            store_last_coinbase(&self.wallet_db, Some((coinbase_hash, coinbase))).await;
+        Ok(())
+    }
+
     /// Sends PoW to a compute node.
     ///
     /// ### Arguments
@@ -1330,6 +1499,7 @@ impl MinerNode {
                 try_send_to_ui(self.ui_feedback_tx.as_ref(), Rs2JsMsg::Error { error }).await;
                 return false;
             }
+            self.partition_entry_sent = true;
         }
 
         true
@@ -1448,8 +1618,8 @@ impl MinerNode {
                     "Checking if we are holding more than {address_aggregation_limit:?} addresses to trigger aggregation tx"
                 );
 
-                    // All last known addresses
-                    let known_addresses = self.wallet_db.get_known_addresses();
+                    // All last known addresses, and archived ones that received funds
+                    let known_addresses = self.wallet_db.get_active_or_funded_addresses();
 
                     // Check if we have a reached the threshold of addresses stored
                     if known_addresses.len() >= address_aggregation_limit {
@@ -1555,6 +1725,34 @@ impl MinerNode {
         }
     }
 
+    /// Requests the UTXO set of the archived addresses so funds sent to them
+    /// after they were archived are still detected
+    pub async fn send_archived_addresses_utxo_request(&mut self) {
+        if self.aggregation_status != AggregationStatus::Idle {
+            // The pending aggregation UTXO request must not be superseded
+            return;
+        }
+
+        let archived_addresses = self.wallet_db.get_archived_addresses();
+        if archived_addresses.is_empty() {
+            return;
+        }
+
+        let compute_addr = self.compute_address();
+        if let Err(e) = self
+            .send_request_utxo_set(
+                UtxoFetchType::AnyOf(archived_addresses),
+                compute_addr,
+                NodeType::Miner,
+            )
+            .await
+        {
+            error!("Error sending archived addresses UTXO request to compute: {e:?}");
+        } else {
+            trace!("Sending UTXO request from Miner node for our archived addresses");
+        }
+    }
+
     /// Generates a valid PoW for a block specifically
     /// TODO: Update the numbers used for reward and block time
     /// * `peer`      - Peer to send PoW to
@@ -1562,23 +1760,30 @@ impl MinerNode {
     pub async fn start_generate_pow_for_current_block(
         &mut self,
         peer: SocketAddr,
-        new_block: BlockPoWReceived,
+        mut new_block: BlockPoWReceived,
     ) {
         let b_num = new_block.block.b_num;
-        let current_payment_address = self.current_payment_address.clone().unwrap();
+        let current_payment_address = match &self.miner_reward_address {
+            Some(reward_address) => reward_address.clone(),
+            None => self.current_payment_address.clone().unwrap(),
+        };
 
         let mining_tx = construct_coinbase_tx(b_num, new_block.reward, current_payment_address);
         let mining_tx_hash = construct_tx_hash(&mining_tx);
 
         self.mining_block_task = {
             let header = apply_mining_tx(new_block.block.clone(), Vec::new(), mining_tx_hash);
+            new_block.mining_difficulty = Some(mining_difficulty_from_bits(header.bits));
+            new_block.mining_header = Some(header.clone());
             let start_time = SystemTime::now();
-            RunningTaskOrResult::Running(Self::generate_pow_for_block(BlockPoWInfo {
+            let info = BlockPoWInfo {
                 peer,
                 start_time,
                 header,
                 coinbase: mining_tx,
-            }))
+            };
+            self.current_block_pow = Some(info.clone());
+            RunningTaskOrResult::Running(Self::generate_pow_for_block(info))
         };
         let mut current_block = self.current_block.lock().await;
         *current_block = Some(new_block);
@@ -1603,11 +1808,13 @@ impl MinerNode {
     ///
     /// * `peer`     - Peer to send PoW to
     /// * `rand_num` - random num for PoW
+    /// * `difficulty` - Difficulty the PoW must reach
     pub async fn start_generate_partition_pow(
         &mut self,
         peer: SocketAddr,
         pow_info: PowInfo,
         rand_num: Vec<u8>,
+        difficulty: usize,
     ) {
         // Here we need to generate a proof-of-work based on the public address
         // resolved by the compute node.
@@ -1632,6 +1839,7 @@ impl MinerNode {
             pow_info,
             address_proof,
             Some(rand_num.clone()),
+            difficulty,
         ));
         self.rand_num = rand_num;
     }
@@ -1658,7 +1866,7 @@ impl MinerNode {
             warn!("load_local_db: generating new locked coinbase from UTXO set");
             // Existing locked coinbase failed to deserialize, so we need to
             // generate a new one using a UTXO subset from the compute node
-            let all_known_addresses = self.wallet_db.get_known_addresses();
+            let all_known_addresses = self.wallet_db.get_all_addresses();
             let request = ComputeRequest::SendUtxoRequest {
                 address_list: UtxoFetchType::AnyOf(all_known_addresses),
                 requester_node_type: NodeType::Miner,
@@ -1718,7 +1926,7 @@ impl MinerInterface for MinerNode {
             Some((key, item, peer)).filter(|(_, i, _)| !i.data.is_empty());
         Response {
             success: true,
-            reason: "Blockchain item received",
+            reason: ResponseReason::BlockchainItemReceived,
         }
     }
 }
@@ -1771,7 +1979,7 @@ impl Transactor for MinerNode {
 
         Response {
             success: true,
-            reason: "Received UTXO set",
+            reason: ResponseReason::ReceivedUtxoSet,
         }
     }
 
@@ -3,11 +3,16 @@ use crate::configurations::{
     DbMode, ExtraNodeParams, NodeSpec, PreLaunchNodeConfig, PreLaunchNodeType, TlsSpec,
 };
 use crate::db_utils::{self, SimpleDb, SimpleDbSpec};
-use crate::interfaces::{DbItem, NodeType, PreLaunchRequest, Response};
+use crate::interfaces::{
+    CommsFormat, DbItem, NodeType, PreLaunchRequest, Response, ResponseReason,
+};
 use crate::raft_store::{get_presistent_committed, CommittedIndex};
-use crate::utils::{LocalEvent, LocalEventChannel, LocalEventSender, ResponseResult};
-use bincode::deserialize;
+use crate::utils::{
+    LocalEvent, LocalEventChannel, LocalEventSender, PreLaunchStatus, ResponseResult,
+};
+use bincode::serialize;
 use bytes::Bytes;
+use naom::crypto::sha3_256;
 use std::{collections::BTreeSet, error::Error, fmt, future::Future, net::SocketAddr};
 use tokio::task;
 use tracing::{debug, error, error_span, info, trace, warn};
@@ -22,6 +27,7 @@ pub enum PreLaunchError {
     Network(CommsError),
     AsyncTask(task::JoinError),
     Serialization(bincode::Error),
+    ChecksumMismatch,
 }
 
 impl fmt::Display for PreLaunchError {
@@ -31,6 +37,7 @@ impl fmt::Display for PreLaunchError {
             Self::Network(err) => write!(f, "Network error: {err}"),
             Self::AsyncTask(err) => write!(f, "Async task error: {err}"),
             Self::Serialization(err) => write!(f, "Serialization error: {err}"),
+            Self::ChecksumMismatch => write!(f, "Checksum mismatch for received db items"),
         }
     }
 }
@@ -39,6 +46,7 @@ impl Error for PreLaunchError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::ConfigError(_) => None,
+            Self::ChecksumMismatch => None,
             Self::Network(ref e) => Some(e),
             Self::Serialization(ref e) => Some(e),
             Self::AsyncTask(ref e) => Some(e),
@@ -71,6 +79,10 @@ struct PreLaunchNodeConfigSelected {
     pub pre_launch_node_idx: usize,
     /// Use specific database
     pub pre_launch_db_mode: DbMode,
+    /// Base directory for the node databases, default location if not set
+    pub data_dir: Option<String>,
+    /// Subdirectory of the data directory for this node
+    pub node_dir: String,
     /// Configuration for handling TLS
     pub tls_config: TlsSpec,
     /// All nodes addresses
@@ -89,6 +101,8 @@ impl PreLaunchNodeConfigSelected {
             PreLaunchNodeType::Compute => Self {
                 pre_launch_node_idx: config.compute_node_idx,
                 pre_launch_db_mode: config.compute_db_mode,
+                data_dir: config.data_dir,
+                node_dir: format!("compute.{}", config.compute_node_idx),
                 tls_config: config.tls_config,
                 pre_launch_nodes: config.compute_nodes,
                 db_spec: crate::compute::DB_SPEC,
@@ -98,6 +112,8 @@ impl PreLaunchNodeConfigSelected {
             PreLaunchNodeType::Storage => Self {
                 pre_launch_node_idx: config.storage_node_idx,
                 pre_launch_db_mode: config.storage_db_mode,
+                data_dir: config.data_dir,
+                node_dir: format!("storage.{}", config.storage_node_idx),
                 tls_config: config.tls_config,
                 pre_launch_nodes: config.storage_nodes,
                 db_spec: crate::storage::DB_SPEC,
@@ -118,6 +134,7 @@ pub struct PreLaunchNode {
     pre_launch_nodes: Vec<SocketAddr>,
     shutdown_group: BTreeSet<SocketAddr>,
     raft_db_send: Option<PreLaunchRequest>,
+    status: PreLaunchStatus,
 }
 
 impl PreLaunchNode {
@@ -147,13 +164,21 @@ impl PreLaunchNode {
             false,
         )
         .await?;
+        let data_dir = config.data_dir.as_deref();
         let db = {
             let spec = &config.db_spec;
-            db_utils::new_db(config.pre_launch_db_mode, spec, extra.db.take(), None)
+            let custom = db_utils::data_dir_db_spec(data_dir, &config.node_dir, spec);
+            db_utils::new_db(config.pre_launch_db_mode, spec, extra.db.take(), custom)
         };
         let raft_db = {
             let spec = &config.raft_db_spec;
-            db_utils::new_db(config.pre_launch_db_mode, spec, extra.raft_db.take(), None)
+            let custom = db_utils::data_dir_db_spec(data_dir, &config.node_dir, spec);
+            db_utils::new_db(
+                config.pre_launch_db_mode,
+                spec,
+                extra.raft_db.take(),
+                custom,
+            )
         };
 
         let pre_launch_nodes = config.pre_launch_nodes.iter().map(|s| s.address);
@@ -161,6 +186,12 @@ impl PreLaunchNode {
         let pre_launch_nodes: Vec<_> = pre_launch_nodes.filter(|a| *a != addr).collect();
 
         let raft_db_send = if config.pre_launch_node_idx == 0 {
+            let items: Vec<DbItem> = raft_db
+                .iter_all_cf_clone()
+                .into_iter()
+                .flat_map(|(c, it)| it.map(move |(k, v)| (c.clone(), k, v)))
+                .map(|(column, key, data)| DbItem { column, key, data })
+                .collect();
             Some(PreLaunchRequest::SendDbItems {
                 committed: get_presistent_committed(&raft_db)
                     .map_err(|e| {
@@ -168,17 +199,19 @@ impl PreLaunchNode {
                         PreLaunchError::ConfigError("Invalid pre-launch index")
                     })?
                     .unwrap_or_default(),
-                items: raft_db
-                    .iter_all_cf_clone()
-                    .into_iter()
-                    .flat_map(|(c, it)| it.map(move |(k, v)| (c.clone(), k, v)))
-                    .map(|(column, key, data)| DbItem { column, key, data })
-                    .collect(),
+                checksum: db_items_checksum(&items)?,
+                items,
             })
         } else {
             None
         };
 
+        // The node sending its raft db already has it
+        let status = PreLaunchStatus::default();
+        if raft_db_send.is_some() {
+            status.set_raft_db_fetched();
+        }
+
         Ok(PreLaunchNode {
             node,
             db,
@@ -187,9 +220,15 @@ impl PreLaunchNode {
             pre_launch_nodes,
             shutdown_group,
             raft_db_send,
+            status,
         })
     }
 
+    /// Progress of the pre-launch, shared with the API
+    pub fn status(&self) -> PreLaunchStatus {
+        self.status.clone()
+    }
+
     /// Returns the node's local endpoint.
     pub fn local_address(&self) -> SocketAddr {
         self.node.local_address()
@@ -253,30 +292,32 @@ impl PreLaunchNode {
         match response {
             Ok(Response {
                 success: true,
-                reason: "Sent startup requests on reconnection",
+                reason: ResponseReason::SentStartupRequestsOnReconnection,
             }) => debug!("Sent startup requests on reconnection"),
             Ok(Response {
                 success: false,
-                reason: "Failed to send startup requests on reconnection",
+                reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
             }) => error!("Failed to send startup requests on reconnection"),
             Ok(Response {
                 success: true,
-                reason: "Shutdown",
+                reason: ResponseReason::Shutdown,
             }) => {
                 warn!("Shutdown now");
+                self.status.set_complete();
                 return ResponseResult::Exit;
             }
             Ok(Response {
                 success: true,
-                reason: "Shutdown pending",
+                reason: ResponseReason::ShutdownPending,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Received Db Items",
+                reason: ResponseReason::ReceivedDbItems,
             }) => {
                 info!("Received Db Items: Closing");
                 if self.flood_closing_events().await.unwrap() {
                     warn!("Flood closing event shutdown");
+                    self.status.set_complete();
                     return ResponseResult::Exit;
                 }
             }
@@ -324,7 +365,7 @@ impl PreLaunchNode {
                 }
                 reason = &mut *exit => return Some(Ok(Response {
                     success: true,
-                    reason,
+                    reason: ResponseReason::Exit(reason),
                 }))
             }
         }
@@ -351,15 +392,17 @@ impl PreLaunchNode {
                     error!("Failed to send startup requests on reconnect: {}", err);
                     return Some(Response {
                         success: false,
-                        reason: "Failed to send startup requests on reconnection",
+                        reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
                     });
                 }
                 Some(Response {
                     success: true,
-                    reason: "Sent startup requests on reconnection",
+                    reason: ResponseReason::SentStartupRequestsOnReconnection,
                 })
             }
             LocalEvent::CoordinatedShutdown(_) => None,
+            LocalEvent::CancelShutdown => None,
+            LocalEvent::ForceSnapshot => None,
             LocalEvent::Ignore => None,
         }
     }
@@ -371,9 +414,13 @@ impl PreLaunchNode {
     /// * `event` - Event object holding the frame to be passed.
     async fn handle_event(&mut self, event: Event) -> Result<Option<Response>> {
         match event {
-            Event::NewFrame { peer, frame } => {
+            Event::NewFrame {
+                peer,
+                frame,
+                format,
+            } => {
                 let peer_span = error_span!("peer", ?peer);
-                self.handle_new_frame(peer, frame)
+                self.handle_new_frame(peer, frame, format)
                     .instrument(peer_span)
                     .await
             }
@@ -386,15 +433,19 @@ impl PreLaunchNode {
     ///
     /// * `peer` - Socket Address of the sending peer node.
     /// * `frame` - Byte object holding the frame being handled.
+    /// * `format` - Serialization format of the frame.
     async fn handle_new_frame(
         &mut self,
         peer: SocketAddr,
         frame: Bytes,
+        format: CommsFormat,
     ) -> Result<Option<Response>> {
-        let req = deserialize::<PreLaunchRequest>(&frame).map_err(|error| {
-            warn!(?error, "frame-deserialize");
-            error
-        })?;
+        let req = format
+            .deserialize::<PreLaunchRequest>(&frame)
+            .map_err(|error| {
+                warn!(?error, "frame-deserialize");
+                error
+            })?;
 
         let req_span = error_span!("request", ?req);
         let response = self.handle_request(peer, req).instrument(req_span).await;
@@ -418,7 +469,11 @@ impl PreLaunchNode {
         trace!("handle_request: {:?}", req);
 
         match req {
-            SendDbItems { committed, items } => self.receive_db_items(peer, committed, items),
+            SendDbItems {
+                committed,
+                items,
+                checksum,
+            } => self.receive_db_items(peer, committed, items, checksum),
             Closing => self.receive_closing(peer),
         }
     }
@@ -430,12 +485,22 @@ impl PreLaunchNode {
     /// * `peer`      - Sending peer's socket address
     /// * `committed` - Info on last committed item
     /// * `items`     - Database items
+    /// * `checksum`  - Checksum of the items computed by the sender
     fn receive_db_items(
         &mut self,
         _peer: SocketAddr,
         _committed: CommittedIndex,
         items: Vec<DbItem>,
+        checksum: Vec<u8>,
     ) -> Option<Response> {
+        if let Err(e) = verify_db_items_checksum(&items, &checksum) {
+            error!("Received db items failed verification: {}", e);
+            return Some(Response {
+                success: false,
+                reason: ResponseReason::ReceivedDbItemsWithInvalidChecksum,
+            });
+        }
+
         if let Err(e) = self.raft_db.import_items(
             items
                 .iter()
@@ -444,13 +509,14 @@ impl PreLaunchNode {
             error!("Received invalid item: {:?}", e);
             return Some(Response {
                 success: false,
-                reason: "Received Invalid Db Items",
+                reason: ResponseReason::ReceivedInvalidDbItems,
             });
         }
+        self.status.set_raft_db_fetched();
 
         Some(Response {
             success: true,
-            reason: "Received Db Items",
+            reason: ResponseReason::ReceivedDbItems,
         })
     }
 
@@ -467,13 +533,13 @@ impl PreLaunchNode {
         if !self.shutdown_group.is_empty() {
             return Some(Response {
                 success: true,
-                reason: "Shutdown pending",
+                reason: ResponseReason::ShutdownPending,
             });
         }
 
         Some(Response {
             success: true,
-            reason: "Shutdown",
+            reason: ResponseReason::Shutdown,
         })
     }
 
@@ -489,3 +555,69 @@ impl PreLaunchNode {
         Ok(self.shutdown_group.is_empty())
     }
 }
+
+/// Checksum of the db items sent during pre-launch
+///
+/// ### Arguments
+///
+/// * `items` - Database items to send
+pub fn db_items_checksum(items: &[DbItem]) -> Result<Vec<u8>> {
+    Ok(sha3_256::digest(&serialize(items)?).to_vec())
+}
+
+/// Verify received db items match the checksum computed by the sender
+///
+/// ### Arguments
+///
+/// * `items`    - Received database items
+/// * `checksum` - Checksum computed by the sender
+fn verify_db_items_checksum(items: &[DbItem], checksum: &[u8]) -> Result<()> {
+    if db_items_checksum(items)? != checksum {
+        return Err(PreLaunchError::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn corrupted_db_items_fail_checksum() {
+        //
+        // Arrange
+        //
+        let items = vec![
+            DbItem {
+                column: "default".to_owned(),
+                key: b"key1".to_vec(),
+                data: b"data1".to_vec(),
+            },
+            DbItem {
+                column: "default".to_owned(),
+                key: b"key2".to_vec(),
+                data: b"data2".to_vec(),
+            },
+        ];
+        let checksum = db_items_checksum(&items).unwrap();
+
+        let mut corrupted = items.clone();
+        corrupted[1].data.truncate(2);
+        let mut truncated = items.clone();
+        truncated.pop();
+
+        //
+        // Act
+        //
+        let valid = verify_db_items_checksum(&items, &checksum);
+        let corrupted = verify_db_items_checksum(&corrupted, &checksum);
+        let truncated = verify_db_items_checksum(&truncated, &checksum);
+
+        //
+        // Assert
+        //
+        assert!(valid.is_ok());
+        assert!(matches!(corrupted, Err(PreLaunchError::ChecksumMismatch)));
+        assert!(matches!(truncated, Err(PreLaunchError::ChecksumMismatch)));
+    }
+}
@@ -88,7 +88,7 @@ impl RaftStore {
         Ok(())
     }
 
-    /// Create and store snapshot
+    /// Create and store snapshot, discarding the persisted entries it covers
     pub fn create_snapshot(
         &mut self,
         idx: u64,
@@ -404,3 +404,66 @@ pub fn get_presistent_committed(presistent: &SimpleDb) -> RaftResult<Option<Comm
 
     Ok(None)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_entry(index: u64) -> Entry {
+        let mut entry = Entry::default();
+        entry.set_index(index);
+        entry.set_term(1);
+        entry.set_data(index.to_le_bytes().to_vec());
+        entry
+    }
+
+    #[test]
+    fn snapshots_bound_persistent_log_and_reload() {
+        //
+        // Arrange
+        //
+        let db = SimpleDb::new_in_memory(&[], None).unwrap();
+        let mut store = RaftStore::new(db);
+
+        //
+        // Act
+        //
+        let mut persisted_counts = Vec::new();
+        for snapshot_idx in (10u64..=50).step_by(10) {
+            let entries: Vec<_> = (snapshot_idx - 9..=snapshot_idx).map(test_entry).collect();
+            store.append(&entries).unwrap();
+            let data = snapshot_idx.to_le_bytes().to_vec();
+            store
+                .create_snapshot(snapshot_idx, None, None, data)
+                .unwrap();
+            persisted_counts.push(store.presistent.count_cf(DB_COL_DEFAULT));
+        }
+        let entries: Vec<_> = (51..=55).map(test_entry).collect();
+        store.append(&entries).unwrap();
+
+        let reloaded = RaftStore::new(store.take_persistent())
+            .load_in_memory_or_default(ConfState::default())
+            .unwrap();
+
+        //
+        // Assert
+        //
+        let expected_counts = vec![persisted_counts[0]; persisted_counts.len()];
+        assert_eq!(persisted_counts, expected_counts);
+        assert_eq!(
+            reloaded.snapshot().unwrap().get_data(),
+            &50u64.to_le_bytes()
+        );
+        assert_eq!(
+            (
+                reloaded.first_index().unwrap(),
+                reloaded.last_index().unwrap()
+            ),
+            (51, 55)
+        );
+        assert_eq!(
+            reloaded.entries(51, 56, u64::MAX).unwrap(),
+            (51..=55).map(test_entry).collect::<Vec<_>>()
+        );
+    }
+}
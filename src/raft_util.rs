@@ -3,10 +3,15 @@ use crate::raft::RaftData;
 use bincode::{deserialize, serialize};
 use naom::crypto::sha3_256;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
+/// Upper bounds in milliseconds of the proposal commit latency histogram buckets.
+const LATENCY_BUCKETS_MS: [u64; 8] = [1, 5, 10, 50, 100, 500, 1000, 5000];
+
 /// Key serialized into RaftData and process by Raft.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct RaftContextKey {
@@ -19,6 +24,8 @@ pub struct RaftContextKey {
 pub struct RaftInFlightProposals {
     /// Proposed items in flight.
     proposed_in_flight: BTreeMap<RaftContextKey, (RaftData, RaftData)>,
+    /// Items waiting for room in flight, proposed in order as items commit.
+    proposed_deferred: VecDeque<(RaftContextKey, RaftData, RaftData)>,
     /// Proposal block num associated with key
     proposed_keys_b_num: BTreeMap<RaftContextKey, u64>,
     /// The last id of a proposed item.
@@ -29,16 +36,146 @@ pub struct RaftInFlightProposals {
     already_proposed_hashes: BTreeMap<Vec<u8>, (RaftContextKey, u64)>,
     /// Minimum block number to accept for deduplicated entries
     min_b_num: u64,
+    /// Maximum number of proposed items in flight, unbounded if None.
+    max_in_flight: Option<usize>,
+    /// Keys committed since the last min_b_num update, to drop re-proposed commits.
+    committed_keys: BTreeSet<RaftContextKey>,
+    /// Keys committed before the last min_b_num update.
+    previous_committed_keys: BTreeSet<RaftContextKey>,
+    /// Time each item in flight was first proposed.
+    proposed_at: BTreeMap<RaftContextKey, Instant>,
+    /// Commit latency and throughput of the proposals.
+    metrics: RaftProposalMetrics,
+}
+
+/// Proposal commit latency and throughput, shared with the node API.
+#[derive(Clone, Debug, Default)]
+pub struct RaftProposalMetrics {
+    inner: Arc<Mutex<RaftProposalMetricsState>>,
+}
+
+#[derive(Debug, Default)]
+struct RaftProposalMetricsState {
+    committed: u64,
+    first_commit: Option<Instant>,
+    latency_samples: u64,
+    latency_sum: Duration,
+    latency_max: Duration,
+    latency_bucket_counts: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+/// Number of latency samples up to a bucket upper bound, unbounded if None.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    pub le_ms: Option<u64>,
+    pub count: u64,
+}
+
+/// Snapshot of the raft proposal metrics.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaftProposalMetricsInfo {
+    /// Items committed, proposed by any peer.
+    pub committed: u64,
+    /// Commits per second since the first commit.
+    pub commit_rate: f64,
+    /// Items proposed by this node and committed.
+    pub latency_samples: u64,
+    /// Sum of the commit latencies in microseconds.
+    pub latency_sum_us: u64,
+    /// Largest commit latency in microseconds.
+    pub latency_max_us: u64,
+    /// Commit latency histogram, with non cumulative counts.
+    pub latency_buckets: Vec<LatencyBucket>,
+}
+
+impl RaftProposalMetrics {
+    /// Record a committed item, with its latency if proposed by this node.
+    ///
+    /// ### Arguments
+    ///
+    ///  * `latency` - Time between the proposal and its commit.
+    pub fn record_commit(&self, latency: Option<Duration>) {
+        let mut state = self.inner.lock().unwrap();
+        state.committed += 1;
+        state.first_commit.get_or_insert_with(Instant::now);
+
+        if let Some(latency) = latency {
+            let bucket = LATENCY_BUCKETS_MS
+                .iter()
+                .position(|le_ms| latency <= Duration::from_millis(*le_ms))
+                .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+            state.latency_samples += 1;
+            state.latency_sum += latency;
+            state.latency_max = state.latency_max.max(latency);
+            state.latency_bucket_counts[bucket] += 1;
+        }
+    }
+
+    /// Current values of the metrics.
+    pub fn info(&self) -> RaftProposalMetricsInfo {
+        let state = self.inner.lock().unwrap();
+        let elapsed = state.first_commit.map(|t| t.elapsed().as_secs_f64());
+        let le_ms = LATENCY_BUCKETS_MS.iter().copied().map(Some);
+
+        RaftProposalMetricsInfo {
+            committed: state.committed,
+            commit_rate: elapsed
+                .filter(|e| *e > 0.0)
+                .map_or(0.0, |e| state.committed as f64 / e),
+            latency_samples: state.latency_samples,
+            latency_sum_us: state.latency_sum.as_micros() as u64,
+            latency_max_us: state.latency_max.as_micros() as u64,
+            latency_buckets: le_ms
+                .chain(std::iter::once(None))
+                .zip(state.latency_bucket_counts.iter())
+                .map(|(le_ms, count)| LatencyBucket {
+                    le_ms,
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
 }
 
 impl RaftInFlightProposals {
+    /// Set the maximum number of proposed items in flight.
+    pub fn with_max_in_flight(mut self, max_in_flight: Option<usize>) -> Self {
+        self.max_in_flight = max_in_flight;
+        self
+    }
+
+    /// Number of proposed items in flight.
+    pub fn in_flight_len(&self) -> usize {
+        self.proposed_in_flight.len()
+    }
+
+    /// Number of items waiting for room in flight.
+    pub fn deferred_len(&self) -> usize {
+        self.proposed_deferred.len()
+    }
+
+    /// Check if no more items can be proposed until some are committed.
+    /// Items already deferred count against the capacity, to keep proposals ordered.
+    pub fn is_at_capacity(&self) -> bool {
+        self.max_in_flight.map_or(false, |max| {
+            self.proposed_in_flight.len() + self.proposed_deferred.len() >= max
+        })
+    }
+
+    /// Shared commit latency and throughput metrics.
+    pub fn metrics(&self) -> RaftProposalMetrics {
+        self.metrics.clone()
+    }
+
     /// Set the key run for all proposals (load from db before first proposal).
     pub fn set_key_run(&mut self, key_run: u64) {
         self.proposed_key_run = Some(key_run);
     }
 
     /// Checks a commit of the RaftData for validity
-    /// Return commited proposal
+    /// Return commited proposal, or None if already committed:
+    /// Items in flight are re-proposed on leader change, and may commit twice.
     ///
     /// ### Arguments
     ///
@@ -54,8 +191,15 @@ impl RaftInFlightProposals {
             deserialize::<RaftContextKey>(raft_ctx),
         ) {
             (Ok(item), Ok(key)) => {
+                if self.previous_committed_keys.contains(&key) || !self.committed_keys.insert(key) {
+                    debug!("RaftItem already committed key {:?} -> ignore", key);
+                    return None;
+                }
+
                 let removed = self.proposed_in_flight.remove(&key).is_some();
                 self.proposed_keys_b_num.remove(&key);
+                let proposed_at = self.proposed_at.remove(&key);
+                self.metrics.record_commit(proposed_at.map(|t| t.elapsed()));
                 Some((key, item, removed))
             }
             (Err(error), Ok(key)) => {
@@ -70,6 +214,8 @@ impl RaftInFlightProposals {
     }
 
     /// Propose an item to raft if use_raft, or commit it otherwise.
+    /// Items over the in flight capacity are queued and proposed once earlier items commit.
+    /// Return None if the item is a duplicate.
     ///
     /// ### Arguments
     ///
@@ -100,24 +246,74 @@ impl RaftInFlightProposals {
         };
         let context = serialize(&key).unwrap();
 
-        debug!("propose_item: {:?} -> {:?}", key, item);
-
-        self.proposed_in_flight
-            .insert(key, (data.clone(), context.clone()));
         if let Some((item_hash, b_num)) = dedup_info {
             self.already_proposed_hashes.insert(item_hash, (key, b_num));
             self.proposed_keys_b_num.insert(key, b_num);
         }
 
-        raft_active.propose_data(data, context).await;
+        if self.is_at_capacity() {
+            warn!(
+                "propose_item deferred: {} items in flight -> {:?} -> {:?}",
+                self.proposed_in_flight.len(),
+                key,
+                item
+            );
+            self.proposed_deferred.push_back((key, data, context));
+            return Some(key);
+        }
+
+        debug!("propose_item: {:?} -> {:?}", key, item);
+        self.propose_in_flight(raft_active, key, data, context)
+            .await;
         Some(key)
     }
 
+    /// Propose deferred items, in order, while there is room in flight.
+    ///
+    /// ### Arguments
+    ///
+    ///  * `raft_active` - The raft instance to propose to.
+    pub async fn propose_deferred_items(&mut self, raft_active: &mut ActiveRaft) {
+        while !self.proposed_deferred.is_empty()
+            && self
+                .max_in_flight
+                .map_or(true, |max| self.proposed_in_flight.len() < max)
+        {
+            let (key, data, context) = self.proposed_deferred.pop_front().unwrap();
+            debug!("propose_item deferred: {:?}", key);
+            self.propose_in_flight(raft_active, key, data, context)
+                .await;
+        }
+    }
+
+    /// Add an item in flight and propose it to raft.
+    ///
+    /// ### Arguments
+    ///
+    ///  * `raft_active` - The raft instance to propose to.
+    ///  * `key`         - The item key.
+    ///  * `data`        - The serialized item.
+    ///  * `context`     - The serialized key.
+    async fn propose_in_flight(
+        &mut self,
+        raft_active: &mut ActiveRaft,
+        key: RaftContextKey,
+        data: RaftData,
+        context: RaftData,
+    ) {
+        self.proposed_in_flight
+            .insert(key, (data.clone(), context.clone()));
+        self.proposed_at.insert(key, Instant::now());
+        raft_active.propose_data(data, context).await;
+    }
+
     /// Remove all items with provided keys
     pub fn remove_all_keys(&mut self, keys: &BTreeSet<RaftContextKey>) {
         for key in keys.iter() {
             self.proposed_in_flight.remove(key);
+            self.proposed_deferred.retain(|(k, _, _)| *k != *key);
             self.proposed_keys_b_num.remove(key);
+            self.proposed_at.remove(key);
             self.already_proposed_hashes.retain(|_, (k, _)| *k != *key);
         }
     }
@@ -178,6 +374,9 @@ impl RaftInFlightProposals {
     ///
     ///  * `min_b_num` - The minimum block number to accept.
     pub fn ignore_dedeup_b_num_less_than(&mut self, min_b_num: u64) {
+        if self.min_b_num != min_b_num {
+            self.previous_committed_keys = std::mem::take(&mut self.committed_keys);
+        }
         self.min_b_num = min_b_num;
 
         self.already_proposed_hashes = {
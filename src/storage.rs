@@ -1,26 +1,34 @@
+use crate::active_raft::ElectionTicks;
 use crate::comms_handler::{CommsError, Event, Node, TcpTlsConfig};
-use crate::configurations::{ExtraNodeParams, StorageNodeConfig, TlsPrivateInfo};
+use crate::configurations::{DbMode, DbTuning, ExtraNodeParams, StorageNodeConfig, TlsPrivateInfo};
 use crate::constants::{
     DB_PATH, INDEXED_BLOCK_HASH_PREFIX_KEY, INDEXED_TX_HASH_PREFIX_KEY, LAST_BLOCK_HASH_KEY,
-    NAMED_CONSTANT_PREPEND,
+    NAMED_CONSTANT_PREPEND, NETWORK_VERSION_SERIALIZED,
+};
+use crate::db_utils::{
+    self, BatchOutcome, CustomDbSpec, SimpleDb, SimpleDbError, SimpleDbSpec, SimpleDbWriteBatch,
 };
-use crate::db_utils::{self, SimpleDb, SimpleDbError, SimpleDbSpec, SimpleDbWriteBatch};
 use crate::interfaces::{
-    BlockStoredInfo, BlockchainItem, BlockchainItemMeta, ComputeRequest, Contract, MineRequest,
-    MinedBlock, NodeType, ProofOfWork, Response, StorageInterface, StorageRequest,
-    StoredSerializingBlock,
+    BlockStoredInfo, BlockchainItem, BlockchainItemMeta, CommsFormat, ComputeRequest, Contract,
+    MineRequest, MinedBlock, NodeType, ProofOfWork, Response, ResponseReason, StorageInterface,
+    StorageRequest, StoredSerializingBlock,
 };
 use crate::raft::RaftCommit;
 use crate::storage_fetch::{FetchStatus, FetchedBlockChain, StorageFetch};
 use crate::storage_raft::{CommittedItem, CompleteBlock, StorageRaft};
 use crate::utils::{
-    construct_valid_block_pow_hash, get_genesis_tx_in_display, to_api_keys, to_route_pow_infos,
-    ApiKeys, LocalEvent, LocalEventChannel, LocalEventSender, ResponseResult, RoutesPoWInfo,
+    construct_valid_block_pow_hash, get_genesis_tx_in_display, to_route_pow_infos,
+    to_signed_api_keys, ApiKeys, LocalEvent, LocalEventChannel, LocalEventSender, NodeReadiness,
+    ResponseResult, RoutesPoWInfo, StorageRaftStatus, StringError,
 };
 use bincode::{deserialize, serialize};
 use bytes::Bytes;
-use serde::Serialize;
-use std::collections::{BTreeSet, HashMap};
+use naom::primitives::block::Block;
+use naom::primitives::transaction::{OutPoint, Transaction};
+use naom::utils::transaction_utils::{get_inputs_previous_out_point, get_tx_out_with_out_point};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 use std::future::Future;
@@ -46,6 +54,8 @@ pub const DB_COL_BC_V0_5_0: &str = "block_chain_v0.5.0";
 pub const DB_COL_BC_V0_4_0: &str = "block_chain_v0.4.0";
 pub const DB_COL_BC_V0_3_0: &str = "block_chain_v0.3.0";
 pub const DB_COL_BC_V0_2_0: &str = "block_chain_v0.2.0";
+pub const DB_COL_UTXO_CHECKPOINT: &str = "utxo_checkpoint";
+pub const DB_COL_ADDRESS_TX: &str = "address_transactions";
 
 /// Version columns
 pub const DB_COLS_BC: &[(&str, u32)] = &[
@@ -59,8 +69,167 @@ pub const DB_COLS_BC: &[(&str, u32)] = &[
 ];
 pub const DB_POINTER_SEPARATOR: u8 = b':';
 
+/// Maximum number of reconstructed UTXO sets cached in memory
+pub const UTXO_CHECKPOINT_CACHE_SIZE: usize = 16;
+
+/// Maximum number of stored blocks replayed for each new block while the
+/// UTXO checkpoint catches up with the contiguous stored chain
+pub const UTXO_CHECKPOINT_REPLAY_STEP: u64 = 16;
+
+/// Maximum number of transactions returned for an address at once
+pub const ADDRESS_TRANSACTIONS_MAX_LIMIT: usize = 1000;
+
+/// Maximum number of keys looked up in the blockchain at once
+pub const BLOCKCHAIN_ENTRIES_MAX_KEYS: usize = 100;
+
+/// UTXO sets reconstructed at requested block heights
+pub type UtxoCheckpoints = Arc<Mutex<UtxoCheckpointCache>>;
+
+/// Bounded cache of reconstructed UTXO sets, evicting the least recently used
+#[derive(Debug, Default)]
+pub struct UtxoCheckpointCache {
+    utxo_sets: BTreeMap<u64, (u64, BTreeSet<OutPoint>)>,
+    last_use: u64,
+}
+
+impl UtxoCheckpointCache {
+    /// Get the cached UTXO set nearest to, and not after, the given block
+    ///
+    /// ### Arguments
+    ///
+    /// * `b_num` - The block number
+    pub fn get_nearest(&mut self, b_num: u64) -> Option<(u64, BTreeSet<OutPoint>)> {
+        self.last_use += 1;
+        let (c_num, (used, utxo_set)) = self.utxo_sets.range_mut(..=b_num).next_back()?;
+        *used = self.last_use;
+        Some((*c_num, utxo_set.clone()))
+    }
+
+    /// Cache the UTXO set as of the given block
+    ///
+    /// ### Arguments
+    ///
+    /// * `b_num`    - The block number
+    /// * `utxo_set` - UTXO set as of the block
+    pub fn insert(&mut self, b_num: u64, utxo_set: BTreeSet<OutPoint>) {
+        self.last_use += 1;
+        self.utxo_sets.insert(b_num, (self.last_use, utxo_set));
+
+        if self.utxo_sets.len() > UTXO_CHECKPOINT_CACHE_SIZE {
+            let lru = self.utxo_sets.iter().min_by_key(|(_, (used, _))| *used);
+            if let Some(c_num) = lru.map(|(c_num, _)| *c_num) {
+                self.utxo_sets.remove(&c_num);
+            }
+        }
+    }
+
+    /// Number of cached UTXO sets
+    pub fn len(&self) -> usize {
+        self.utxo_sets.len()
+    }
+
+    /// Whether no UTXO set is cached
+    pub fn is_empty(&self) -> bool {
+        self.utxo_sets.is_empty()
+    }
+}
+
+/// UTXO set of the contiguous stored chain, advanced block by block so each
+/// periodic checkpoint is written in the same batch as its block
+#[derive(Debug)]
+pub struct UtxoCheckpointBuilder {
+    interval: u64,
+    next_b_num: u64,
+    utxo_set: BTreeSet<OutPoint>,
+}
+
+impl UtxoCheckpointBuilder {
+    /// Start from the last persisted checkpoint, or from genesis if none
+    ///
+    /// ### Arguments
+    ///
+    /// * `db`       - Database containing the checkpoints
+    /// * `interval` - Number of blocks between checkpoints, not 0
+    pub fn load(db: &SimpleDb, interval: u64) -> Self {
+        let (next_b_num, utxo_set) = match get_nearest_utxo_checkpoint(db, u64::MAX) {
+            Some((c_num, utxo_set)) => (c_num + 1, utxo_set),
+            None => (0, BTreeSet::new()),
+        };
+        Self {
+            interval,
+            next_b_num,
+            utxo_set,
+        }
+    }
+
+    /// Apply the block to the UTXO set, after replaying at most
+    /// `UTXO_CHECKPOINT_REPLAY_STEP` stored blocks still missing before it.
+    /// Return the UTXO set as of the block if a checkpoint is due for it.
+    ///
+    /// ### Arguments
+    ///
+    /// * `db`        - Database containing the stored chain before the block
+    /// * `block`     - Contiguous block being stored
+    /// * `block_txs` - The block transactions
+    pub fn apply_block(
+        &mut self,
+        db: &SimpleDb,
+        block: &Block,
+        block_txs: &BTreeMap<String, Transaction>,
+    ) -> Option<&BTreeSet<OutPoint>> {
+        let b_num = block.header.b_num;
+        let get_item = |key: &str| get_stored_value_from_simple_db(db, key);
+        let replay_end = b_num.min(self.next_b_num + UTXO_CHECKPOINT_REPLAY_STEP);
+        while self.next_b_num < replay_end {
+            if let Err(e) = replay_stored_block(&get_item, self.next_b_num, &mut self.utxo_set) {
+                warn!("UTXO checkpoint replay stopped: {}", e);
+                return None;
+            }
+            self.next_b_num += 1;
+        }
+        if self.next_b_num != b_num {
+            return None;
+        }
+
+        let all_txs = all_ordered_stored_block_tx_hashes(
+            &block.transactions,
+            std::iter::once(&block.header.nonce_and_mining_tx_hash),
+        );
+        let txs = all_txs.filter_map(|(_, tx_hash)| block_txs.get_key_value(tx_hash));
+        apply_block_txs_to_utxo_set(&mut self.utxo_set, txs);
+        self.next_b_num += 1;
+
+        (b_num % self.interval == 0).then_some(&self.utxo_set)
+    }
+}
+
 /// Database specification
 pub const DB_SPEC: SimpleDbSpec = SimpleDbSpec {
+    db_path: DB_PATH,
+    suffix: ".storage",
+    columns: &[
+        DB_COL_INTERNAL,
+        DB_COL_BC_ALL,
+        DB_COL_BC_NAMED,
+        DB_COL_BC_META,
+        DB_COL_BC_JSON,
+        DB_COL_BC_NOW,
+        DB_COL_BC_V0_6_0,
+        DB_COL_BC_V0_5_0,
+        DB_COL_BC_V0_4_0,
+        DB_COL_BC_V0_3_0,
+        DB_COL_BC_V0_2_0,
+        DB_COL_UTXO_CHECKPOINT,
+        DB_COL_ADDRESS_TX,
+    ],
+};
+
+/// Columns added without a network version change, created when opening a database
+/// missing them
+pub const DB_COLS_ADDED: &[&str] = &[DB_COL_UTXO_CHECKPOINT, DB_COL_ADDRESS_TX];
+
+/// Database specification of the columns an existing database must already have
+pub const DB_SPEC_REQUIRED: SimpleDbSpec = SimpleDbSpec {
     db_path: DB_PATH,
     suffix: ".storage",
     columns: &[
@@ -87,6 +256,7 @@ pub enum StorageError {
     Network(CommsError),
     DbError(SimpleDbError),
     Serialization(bincode::Error),
+    ConflictingBlock(u64),
 }
 
 impl fmt::Display for StorageError {
@@ -96,6 +266,7 @@ impl fmt::Display for StorageError {
             Self::Network(err) => write!(f, "Network error: {err}"),
             Self::DbError(err) => write!(f, "DB error: {err}"),
             Self::Serialization(err) => write!(f, "Serialization error: {err}"),
+            Self::ConflictingBlock(b_num) => write!(f, "Conflicting block at height: {b_num}"),
         }
     }
 }
@@ -107,10 +278,18 @@ impl Error for StorageError {
             Self::Network(ref e) => Some(e),
             Self::DbError(ref e) => Some(e),
             Self::Serialization(ref e) => Some(e),
+            Self::ConflictingBlock(_) => None,
         }
     }
 }
 
+impl StorageError {
+    /// Whether the error is a corrupt database that `repair_db` could recover
+    pub fn is_corrupt_db(&self) -> bool {
+        matches!(self, Self::DbError(SimpleDbError::CorruptNeedsRepair(_)))
+    }
+}
+
 impl From<CommsError> for StorageError {
     fn from(other: CommsError) -> Self {
         Self::Network(other)
@@ -140,7 +319,12 @@ pub struct StorageNode {
     api_info: (SocketAddr, Option<TlsPrivateInfo>, ApiKeys, RoutesPoWInfo),
     whitelisted: HashMap<SocketAddr, bool>,
     shutdown_group: BTreeSet<SocketAddr>,
+    readiness: NodeReadiness,
+    raft_status: StorageRaftStatus,
     blockchain_item_fetched: Option<(String, BlockchainItem, SocketAddr)>,
+    utxo_checkpoints: Option<UtxoCheckpointBuilder>,
+    address_index: bool,
+    sync_block_ack: bool,
 }
 
 impl StorageNode {
@@ -157,18 +341,49 @@ impl StorageNode {
             .ok_or(StorageError::ConfigError("Invalid storage index"))?
             .address;
 
-        let compute_addr = config
-            .compute_nodes
-            .get(config.storage_node_idx)
-            .ok_or(StorageError::ConfigError("Invalid compute index"))?
-            .address;
+        let mapped_compute_addr = config
+            .compute_to_storage_mapping
+            .iter()
+            .find(|(_, storage_addr)| **storage_addr == addr)
+            .map(|(compute_addr, _)| *compute_addr);
+        let compute_addr = match mapped_compute_addr {
+            Some(compute_addr) => compute_addr,
+            None => {
+                config
+                    .compute_nodes
+                    .get(config.storage_node_idx)
+                    .ok_or(StorageError::ConfigError("Invalid compute index"))?
+                    .address
+            }
+        };
 
+        ElectionTicks::new(
+            config.storage_raft_election_min_ticks,
+            config.storage_raft_election_max_ticks,
+        )
+        .validate()
+        .map_err(StorageError::ConfigError)?;
         let tcp_tls_config = TcpTlsConfig::from_tls_spec(addr, &config.tls_config)?;
         let api_addr = SocketAddr::new(addr.ip(), config.storage_api_port);
         let api_tls_info = config
             .storage_api_use_tls
             .then(|| tcp_tls_config.clone_private_info());
-        let api_keys = to_api_keys(config.api_keys.clone());
+        let api_keys = to_signed_api_keys(config.api_keys.clone(), config.api_signing_keys.clone());
+
+        let custom_db_spec = custom_db_spec(&config);
+        if config.backup_restore.unwrap_or(false) {
+            let spec = custom_db_spec.clone();
+            db_utils::restore_file_backup(config.storage_db_mode, &DB_SPEC, spec).unwrap();
+        }
+        let db = {
+            let raw_db = new_storage_db(
+                config.storage_db_mode,
+                extra.db.take(),
+                custom_db_spec,
+                &config.storage_db_tuning.clone().unwrap_or_default(),
+            )?;
+            Arc::new(Mutex::new(raw_db))
+        };
 
         let node = Node::new(
             &tcp_tls_config,
@@ -178,23 +393,26 @@ impl StorageNode {
             false,
         )
         .await?;
-        let node_raft = StorageRaft::new(&config, extra.raft_db.take());
+        node.set_comms_format(config.comms_format).await;
+        node.set_compatible_network_versions(config.compatible_network_versions.iter().copied())
+            .await;
+        let node_raft = StorageRaft::new(&config, extra.raft_db.take())?;
         let catchup_fetch = StorageFetch::new(&config, addr);
         let api_pow_info = to_route_pow_infos(config.routes_pow.clone());
 
-        if config.backup_restore.unwrap_or(false) {
-            db_utils::restore_file_backup(config.storage_db_mode, &DB_SPEC, None).unwrap();
-        }
-        let db = {
-            let raw_db = db_utils::new_db(config.storage_db_mode, &DB_SPEC, extra.db.take(), None);
-            Arc::new(Mutex::new(raw_db))
-        };
-
         let shutdown_group = {
             let compute = std::iter::once(compute_addr);
             let raft_peers = node_raft.raft_peer_addrs().copied();
             raft_peers.chain(compute).collect()
         };
+        let readiness = NodeReadiness::new(node_raft.raft_peer_addrs().copied().collect());
+        let raft_status =
+            StorageRaftStatus::default().with_proposal_metrics(node_raft.proposal_metrics());
+        raft_status.set_stored_block_num(node_raft.get_last_stored_block_num());
+        let utxo_checkpoints = config
+            .storage_utxo_checkpoint_interval
+            .filter(|interval| *interval > 0)
+            .map(|interval| UtxoCheckpointBuilder::load(&db.lock().unwrap(), interval));
 
         StorageNode {
             node,
@@ -206,7 +424,12 @@ impl StorageNode {
             compute_addr,
             whitelisted: Default::default(),
             shutdown_group,
+            readiness,
+            raft_status,
             blockchain_item_fetched: Default::default(),
+            utxo_checkpoints,
+            address_index: config.storage_address_index.unwrap_or(false),
+            sync_block_ack: config.storage_sync_block_ack.unwrap_or(false),
         }
         .load_local_db()
     }
@@ -221,6 +444,18 @@ impl StorageNode {
         self.node.public_address().await
     }
 
+    /// Repair the database of a node that failed to start with `CorruptNeedsRepair`
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - StorageNodeConfig the node failed to start with
+    pub fn repair_db(config: &StorageNodeConfig) -> Result<()> {
+        let custom_db_spec = custom_db_spec(config);
+        db_utils::repair_db(config.storage_db_mode, &DB_SPEC, custom_db_spec)?;
+        StorageRaft::repair_db(config)?;
+        Ok(())
+    }
+
     /// Returns the storage node's API info
     pub fn api_inputs(
         &self,
@@ -230,9 +465,28 @@ impl StorageNode {
         Option<TlsPrivateInfo>,
         ApiKeys,
         RoutesPoWInfo,
+        NodeReadiness,
     ) {
         let (api_addr, api_tls, api_keys, api_pow_info) = self.api_info.clone();
-        (self.db.clone(), api_addr, api_tls, api_keys, api_pow_info)
+        let readiness = self.readiness.clone();
+        (
+            self.db.clone(),
+            api_addr,
+            api_tls,
+            api_keys,
+            api_pow_info,
+            readiness,
+        )
+    }
+
+    /// Returns the address of the compute node blocks are received from.
+    pub fn compute_address(&self) -> SocketAddr {
+        self.compute_addr
+    }
+
+    /// Stored and compute block numbers, shared with the API
+    pub fn raft_status(&self) -> StorageRaftStatus {
+        self.raft_status.clone()
     }
 
     ///Adds a uses data as the payload to create a frame, from the peer address, in the node object of this class.
@@ -313,15 +567,15 @@ impl StorageNode {
         match response {
             Ok(Response {
                 success: true,
-                reason: "Sent startup requests on reconnection",
+                reason: ResponseReason::SentStartupRequestsOnReconnection,
             }) => debug!("Sent startup requests on reconnection"),
             Ok(Response {
                 success: false,
-                reason: "Failed to send startup requests on reconnection",
+                reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
             }) => error!("Failed to send startup requests on reconnection"),
             Ok(Response {
                 success: true,
-                reason: "Blockchain item fetched from storage",
+                reason: ResponseReason::BlockchainItemFetchedFromStorage,
             }) => {
                 if let Err(e) = self.send_blockchain_item().await {
                     error!("Blockchain item not sent {:?}", e);
@@ -329,14 +583,14 @@ impl StorageNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Shutdown",
+                reason: ResponseReason::Shutdown,
             }) => {
                 warn!("Shutdown now");
                 return ResponseResult::Exit;
             }
             Ok(Response {
                 success: true,
-                reason: "Compute Shutdown",
+                reason: ResponseReason::ComputeShutdown,
             }) => {
                 debug!("Compute shutdown");
                 if self.flood_closing_events().await.unwrap() {
@@ -346,15 +600,15 @@ impl StorageNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Shutdown pending",
+                reason: ResponseReason::ShutdownPending,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Block received to be added",
+                reason: ResponseReason::BlockReceivedToBeAdded,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Block complete stored",
+                reason: ResponseReason::BlockCompleteStored,
             }) => {
                 info!("Block stored: Send to compute");
                 if let Err(e) = self.send_stored_block().await {
@@ -363,19 +617,25 @@ impl StorageNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Snapshot applied",
+                reason: ResponseReason::SnapshotApplied,
             }) => {
                 warn!("Snapshot applied");
             }
             Ok(Response {
                 success: true,
-                reason: "Snapshot applied: Fetch missing blocks",
+                reason: ResponseReason::SnapshotAppliedFetchMissingBlocks,
             }) => {
                 warn!("Snapshot applied: Fetch missing blocks");
             }
             Ok(Response {
                 success: true,
-                reason: "Catch up stored blocks",
+                reason: ResponseReason::SnapshotForced,
+            }) => {
+                warn!("Snapshot forced");
+            }
+            Ok(Response {
+                success: true,
+                reason: ResponseReason::CatchUpStoredBlocks,
             }) => {
                 if let Err(e) = self.catchup_fetch_blockchain_item().await {
                     error!("Resend block stored failed {:?}", e);
@@ -383,19 +643,19 @@ impl StorageNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Blockchain item received",
+                reason: ResponseReason::BlockchainItemReceived,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Blockchain item received: Block stored",
+                reason: ResponseReason::BlockchainItemReceivedBlockStored,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Blockchain item received: Block stored(Done)",
+                reason: ResponseReason::BlockchainItemReceivedBlockStoredDone,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Blockchain item received: Block failed",
+                reason: ResponseReason::BlockchainItemReceivedBlockFailed,
             }) => {}
             Ok(Response {
                 success: true,
@@ -426,6 +686,7 @@ impl StorageNode {
         loop {
             let ready = !self.node_raft.need_initial_state();
             let shutdown = self.node_raft.is_shutdown_commit_processed();
+            self.readiness.set_initial_state_applied(ready);
 
             // State machines are not keept between iterations or calls.
             // All selection calls (between = and =>), need to be dropable
@@ -460,7 +721,7 @@ impl StorageNode {
                     }
                     return Some(Ok(Response {
                         success: true,
-                        reason: "Catch up stored blocks",
+                        reason: ResponseReason::CatchUpStoredBlocks,
                     }))
                 }
                 Some(event) = self.local_events.rx.recv(), if ready => {
@@ -470,7 +731,7 @@ impl StorageNode {
                 }
                 reason = &mut *exit => return Some(Ok(Response {
                     success: true,
-                    reason,
+                    reason: ResponseReason::Exit(reason),
                 }))
             }
         }
@@ -492,15 +753,23 @@ impl StorageNode {
                     error!("Failed to send startup requests on reconnect: {}", err);
                     return Some(Response {
                         success: false,
-                        reason: "Failed to send startup requests on reconnection",
+                        reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
                     });
                 }
                 Some(Response {
                     success: true,
-                    reason: "Sent startup requests on reconnection",
+                    reason: ResponseReason::SentStartupRequestsOnReconnection,
+                })
+            }
+            LocalEvent::ForceSnapshot => {
+                self.node_raft.generate_snapshot();
+                Some(Response {
+                    success: true,
+                    reason: ResponseReason::SnapshotForced,
                 })
             }
             LocalEvent::CoordinatedShutdown(_) => None,
+            LocalEvent::CancelShutdown => None,
             LocalEvent::Ignore => None,
         }
     }
@@ -514,26 +783,48 @@ impl StorageNode {
         match self.node_raft.received_commit(commit_data).await {
             Some(CommittedItem::Block) => {
                 let block = self.node_raft.generate_complete_block();
-                let block_stored = {
+                let b_num = block.common.block.header.b_num;
+                let (block_stored, flushed) = {
                     let mut self_db = self.db.lock().unwrap();
 
-                    let b_num = block.common.block.header.b_num;
                     let contiguous = self.catchup_fetch.check_contiguous_block_num(b_num);
-                    let stored = Self::store_complete_block(&mut self_db, contiguous, block);
+                    let stored = match Self::store_complete_block(
+                        &mut self_db,
+                        contiguous,
+                        self.address_index,
+                        self.utxo_checkpoints.as_mut(),
+                        block,
+                    ) {
+                        Ok(stored) => stored,
+                        Err(e) => {
+                            error!("Block rejected: {}", e);
+                            return Some(Err(e));
+                        }
+                    };
                     self.catchup_fetch.update_contiguous_block_num(contiguous);
                     self.catchup_fetch.increase_running_target(b_num);
 
-                    stored
+                    let flushed = Self::flush_before_block_ack(&self_db, self.sync_block_ack);
+                    (stored, flushed)
                 };
                 self.node_raft
                     .event_processed_generate_snapshot(block_stored);
+                self.raft_status
+                    .set_stored_block_num(self.node_raft.get_last_stored_block_num());
+                if let Err(e) = flushed {
+                    // Block is stored and tracked, but must not be acked until on disk
+                    error!("Block stored not flushed: {}", e);
+                    return Some(Err(e));
+                }
                 self.backup_persistent_dbs().await;
                 Some(Ok(Response {
                     success: true,
-                    reason: "Block complete stored",
+                    reason: ResponseReason::BlockCompleteStored,
                 }))
             }
             Some(CommittedItem::Snapshot) => {
+                self.raft_status
+                    .set_stored_block_num(self.node_raft.get_last_stored_block_num());
                 if let Some(stored) = self.node_raft.get_last_block_stored() {
                     let b_num = stored.block_num;
                     if self.catchup_fetch.fetch_missing_blockchain_items(b_num) {
@@ -543,13 +834,13 @@ impl StorageNode {
                         );
                         return Some(Ok(Response {
                             success: true,
-                            reason: "Snapshot applied: Fetch missing blocks",
+                            reason: ResponseReason::SnapshotAppliedFetchMissingBlocks,
                         }));
                     }
                 }
                 Some(Ok(Response {
                     success: true,
-                    reason: "Snapshot applied",
+                    reason: ResponseReason::SnapshotApplied,
                 }))
             }
             None => None,
@@ -563,9 +854,13 @@ impl StorageNode {
     /// * `event` - Event object containing a message from a peer.
     async fn handle_event(&mut self, event: Event) -> Result<Option<Response>> {
         match event {
-            Event::NewFrame { peer, frame } => {
+            Event::NewFrame {
+                peer,
+                frame,
+                format,
+            } => {
                 let peer_span = error_span!("peer", ?peer);
-                self.handle_new_frame(peer, frame)
+                self.handle_new_frame(peer, frame, format)
                     .instrument(peer_span)
                     .await
             }
@@ -578,15 +873,19 @@ impl StorageNode {
     ///
     /// * `peer` - Socket address of the sender.
     /// * `frame` - Bytes object holding the message from the sender.
+    /// * `format` - Serialization format of the message.
     async fn handle_new_frame(
         &mut self,
         peer: SocketAddr,
         frame: Bytes,
+        format: CommsFormat,
     ) -> Result<Option<Response>> {
-        let req = deserialize::<StorageRequest>(&frame).map_err(|error| {
-            warn!(?error, "frame-deserialize");
-            error
-        })?;
+        let req = format
+            .deserialize::<StorageRequest>(&frame)
+            .map_err(|error| {
+                warn!(?error, "frame-deserialize");
+                error
+            })?;
 
         let req_span = error_span!("request", ?req);
         let response = self.handle_request(peer, req).instrument(req_span).await;
@@ -643,17 +942,22 @@ impl StorageNode {
     }
 
     ///Stores a completed block including transactions and mining transactions.
+    ///Blocks are final: a block conflicting with the stored chain is rejected.
     ///
     /// ### Arguments
     ///
-    /// * `self_db`  - Database to update
-    /// * `status`   - Block is contiguous with last contiguous
-    /// * `complete` - CompleteBlock object to be stored.
+    /// * `self_db`             - Database to update
+    /// * `status`              - Block is contiguous with last contiguous
+    /// * `address_index`       - Index the block transactions by affected address
+    /// * `utxo_checkpoints`    - UTXO set to advance and checkpoint with the block if any
+    /// * `complete`            - CompleteBlock object to be stored.
     fn store_complete_block(
         self_db: &mut SimpleDb,
         status: FetchStatus,
+        address_index: bool,
+        utxo_checkpoints: Option<&mut UtxoCheckpointBuilder>,
         complete: CompleteBlock,
-    ) -> BlockStoredInfo {
+    ) -> Result<BlockStoredInfo> {
         // TODO: Makes the DB save process async
         // TODO: only accept whitelisted blocks
 
@@ -680,6 +984,7 @@ impl StorageNode {
         let block_json = serde_json::to_vec(&stored_block).unwrap();
         let block_hash = construct_valid_block_pow_hash(&stored_block.block)
             .unwrap_or_else(|e| panic!("Block always validated before: {}", e));
+        check_block_is_final(self_db, block_num, &block_hash, &stored_block)?;
 
         let (nonce, mining_tx_hash) = stored_block.block.header.nonce_and_mining_tx_hash.clone();
         let last_block_stored_info = BlockStoredInfo {
@@ -701,10 +1006,18 @@ impl StorageNode {
             block_hash
         );
 
+        // Checkpoint needs all previous blocks stored to replay from
+        let utxo_checkpoint = utxo_checkpoints
+            .filter(|_| FetchStatus::Contiguous(block_num) == status)
+            .and_then(|c| c.apply_block(self_db, &stored_block.block, &all_block_txs));
+
         //
         // Store to database
         //
         let mut batch = self_db.batch_writer();
+        if let Some(utxo_set) = utxo_checkpoint {
+            put_utxo_checkpoint(&mut batch, block_num, utxo_set);
+        }
 
         let all_txs = all_ordered_stored_block_tx_hashes(
             &stored_block.block.transactions,
@@ -718,6 +1031,15 @@ impl StorageNode {
                 let tx_json = serde_json::to_vec(tx_value).unwrap();
                 let t = BlockchainItemMeta::Tx { block_num, tx_num };
                 put_to_block_chain(&mut batch, &t, tx_hash, &tx_input, &tx_json);
+
+                if address_index {
+                    let get_tx = |h: &str| {
+                        let block_tx = all_block_txs.get(h).cloned();
+                        block_tx.or_else(|| get_stored_tx_from_simple_db(self_db, h))
+                    };
+                    let addresses = tx_affected_addresses(tx_value, get_tx);
+                    put_address_transactions(&mut batch, &addresses, block_num, tx_num, tx_hash);
+                }
             } else {
                 error!(
                     "Missing block {} transaction {}: \"{}\"",
@@ -763,64 +1085,98 @@ impl StorageNode {
             }
         }
 
-        last_block_stored_info
+        Ok(last_block_stored_info)
     }
 
     ///Stores a completed block including transactions and mining transactions.
     ///
     /// ### Arguments
     ///
-    /// * `self_db` - Database to update
-    /// * `b_num`   - Block number to store
-    /// * `status`  - Block is contiguous with last contiguous
-    /// * `items`   - Complete block object to be stored.
+    /// * `self_db`       - Database to update
+    /// * `b_num`         - Block number to store
+    /// * `status`        - Block is contiguous with last contiguous
+    /// * `address_index` - Index the block transactions by affected address
+    /// * `items`         - Complete block object to be stored.
     fn store_fetched_complete_block(
         self_db: &mut SimpleDb,
         last_block_stored: &BlockStoredInfo,
         status: FetchStatus,
+        address_index: bool,
         (b_num, items): FetchedBlockChain,
     ) -> Result<FetchStatus> {
-        let mut batch = self_db.batch_writer();
-        let mut block_pointer = None;
-
         info!(
             "Store catchup complete block summary: b_num={}, items={}",
             b_num,
             items.len(),
         );
 
-        for item in &items {
-            let key = str::from_utf8(&item.key)
-                .map_err(|_| StorageError::ConfigError("Non UTF-8 blockchain key"))?;
-            let pointer = put_to_block_chain(
-                &mut batch,
-                &item.item_meta,
-                key,
-                &item.data,
-                &item.data_json,
-            );
+        let keys = items
+            .iter()
+            .map(|item| str::from_utf8(&item.key))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| StorageError::ConfigError("Non UTF-8 blockchain key"))?;
+
+        // Only write the items if they include the block itself
+        let outcome = self_db.try_batch(
+            |self_db, batch| {
+                let mut block_pointer = None;
+                for (item, key) in items.iter().zip(&keys) {
+                    let pointer = put_to_block_chain(
+                        batch,
+                        &item.item_meta,
+                        key,
+                        &item.data,
+                        &item.data_json,
+                    );
 
-            if let BlockchainItemMeta::Block { block_num, .. } = &item.item_meta {
-                if block_num == &b_num {
-                    block_pointer = Some(pointer);
+                    if let BlockchainItemMeta::Block { block_num, .. } = &item.item_meta {
+                        if block_num == &b_num {
+                            block_pointer = Some(pointer);
+                        }
+                    }
                 }
-            }
-        }
 
-        if let Some(block_pointer) = block_pointer {
-            if last_block_stored.block_num == b_num {
-                put_named_last_block_to_block_chain(&mut batch, &block_pointer);
-            }
-            if FetchStatus::Contiguous(b_num) == status {
-                put_contiguous_block_num(&mut batch, b_num);
-            }
-        } else {
-            return Err(StorageError::ConfigError("Block not specified"));
-        }
+                if address_index {
+                    let block_txs: BTreeMap<&str, Transaction> = items
+                        .iter()
+                        .zip(&keys)
+                        .filter(|(item, _)| matches!(item.item_meta, BlockchainItemMeta::Tx { .. }))
+                        .filter_map(|(item, key)| Some((*key, deserialize(&item.data).ok()?)))
+                        .collect();
+
+                    for (item, tx_hash) in items.iter().zip(&keys) {
+                        if let BlockchainItemMeta::Tx { block_num, tx_num } = item.item_meta {
+                            if let Some(tx) = block_txs.get(tx_hash) {
+                                let get_tx = |h: &str| {
+                                    let block_tx = block_txs.get(h).cloned();
+                                    block_tx.or_else(|| get_stored_tx_from_simple_db(self_db, h))
+                                };
+                                let addresses = tx_affected_addresses(tx, get_tx);
+                                put_address_transactions(
+                                    batch, &addresses, block_num, tx_num, tx_hash,
+                                );
+                            }
+                        }
+                    }
+                }
 
-        let batch = batch.done();
-        self_db.write(batch).unwrap();
-        Ok(status)
+                if let Some(block_pointer) = &block_pointer {
+                    if last_block_stored.block_num == b_num {
+                        put_named_last_block_to_block_chain(batch, block_pointer);
+                    }
+                    if FetchStatus::Contiguous(b_num) == status {
+                        put_contiguous_block_num(batch, b_num);
+                    }
+                }
+                block_pointer
+            },
+            |_, block_pointer| block_pointer.is_some(),
+        )?;
+
+        match outcome {
+            BatchOutcome::Written(_) => Ok(status),
+            BatchOutcome::Rejected(_) => Err(StorageError::ConfigError("Block not specified")),
+        }
     }
 
     /// Sends a request to retrieve a blockchain item from storage
@@ -849,12 +1205,23 @@ impl StorageNode {
         self.node_raft.get_last_block_stored()
     }
 
+    /// Index of the last committed raft entry and of the last snapshot generated
+    pub fn get_snapshot_indexes(&self) -> (u64, Option<u64>) {
+        self.node_raft.get_snapshot_indexes()
+    }
+
     /// Get count of all the stored values
     pub fn get_stored_values_count(&self) -> usize {
         let db = self.db.lock().unwrap();
         db.count_cf(DB_COL_BC_ALL)
     }
 
+    /// Get count of the database entries not yet flushed to disk
+    pub fn get_unflushed_entry_count(&self) -> u64 {
+        let db = self.db.lock().unwrap();
+        db.unflushed_entry_count()
+    }
+
     /// Sends the latest block to storage
     pub async fn send_stored_block(&mut self) -> Result<()> {
         // Only the first call will send to storage.
@@ -906,20 +1273,20 @@ impl StorageNode {
         if peer == self.compute_addr {
             return Some(Response {
                 success: true,
-                reason: "Compute Shutdown",
+                reason: ResponseReason::ComputeShutdown,
             });
         }
 
         if !self.shutdown_group.is_empty() {
             return Some(Response {
                 success: true,
-                reason: "Shutdown pending",
+                reason: ResponseReason::ShutdownPending,
             });
         }
 
         Some(Response {
             success: true,
-            reason: "Shutdown",
+            reason: ResponseReason::Shutdown,
         })
     }
 
@@ -941,11 +1308,37 @@ impl StorageNode {
             return None;
         };
 
-        if let Err(e) = construct_valid_block_pow_hash(&common.block) {
-            debug!("Block received not added. PoW invalid: {}", e);
+        let block_hash = match construct_valid_block_pow_hash(&common.block) {
+            Ok(block_hash) => block_hash,
+            Err(e) => {
+                debug!("Block received not added. PoW invalid: {}", e);
+                return Some(Response {
+                    success: false,
+                    reason: ResponseReason::BlockReceivedNotAddedPowInvalid,
+                });
+            }
+        };
+        self.raft_status
+            .set_compute_block_num(common.block.header.b_num);
+
+        if self.get_stored_value(&block_hash).is_some() {
+            debug!("Block received already stored: {}", block_hash);
+            // The flush following the initial store may have failed
+            let flushed = {
+                let self_db = self.db.lock().unwrap();
+                Self::flush_before_block_ack(&self_db, self.sync_block_ack)
+            };
+            if let Err(e) = flushed {
+                error!("Block received already stored not flushed: {}", e);
+                return Some(Response {
+                    success: false,
+                    reason: ResponseReason::BlockReceivedAlreadyStoredNotFlushed,
+                });
+            }
+            self.resend_trigger_message().await;
             return Some(Response {
-                success: false,
-                reason: "Block received not added. PoW invalid",
+                success: true,
+                reason: ResponseReason::BlockReceivedAlreadyStored,
             });
         }
 
@@ -961,10 +1354,23 @@ impl StorageNode {
 
         Some(Response {
             success: true,
-            reason: "Block received to be added",
+            reason: ResponseReason::BlockReceivedToBeAdded,
         })
     }
 
+    /// Flush the stored blocks to disk before they are acked, if in sync ack mode
+    ///
+    /// ### Arguments
+    ///
+    /// * `db`             - Database the blocks are stored in
+    /// * `sync_block_ack` - Whether blocks must be on disk before they are acked
+    fn flush_before_block_ack(db: &SimpleDb, sync_block_ack: bool) -> Result<()> {
+        if sync_block_ack {
+            db.flush()?;
+        }
+        Ok(())
+    }
+
     /// Load and apply the local database to our state
     fn load_local_db(mut self) -> Result<Self> {
         self.node_raft.set_key_run({
@@ -1005,7 +1411,7 @@ impl StorageInterface for StorageNode {
         self.blockchain_item_fetched = Some((key, item, peer));
         Response {
             success: true,
-            reason: "Blockchain item fetched from storage",
+            reason: ResponseReason::BlockchainItemFetchedFromStorage,
         }
     }
 
@@ -1025,7 +1431,13 @@ impl StorageInterface for StorageNode {
             let result = match self.node_raft.get_last_block_stored() {
                 Some(last_stored) if last_stored.block_num >= b_num => {
                     let contiguous = self.catchup_fetch.check_contiguous_block_num(b_num);
-                    Self::store_fetched_complete_block(&mut self_db, last_stored, contiguous, block)
+                    Self::store_fetched_complete_block(
+                        &mut self_db,
+                        last_stored,
+                        contiguous,
+                        self.address_index,
+                        block,
+                    )
                 }
                 _ => Err(StorageError::ConfigError(
                     "Expect only block less than block stored",
@@ -1037,9 +1449,9 @@ impl StorageInterface for StorageNode {
                     self.catchup_fetch.update_contiguous_block_num(status);
                     self.catchup_fetch.set_first_timeout();
                     let reason = if is_complete {
-                        "Blockchain item received: Block stored(Done)"
+                        ResponseReason::BlockchainItemReceivedBlockStoredDone
                     } else {
-                        "Blockchain item received: Block stored"
+                        ResponseReason::BlockchainItemReceivedBlockStored
                     };
 
                     info!("{}(b_num = {})", reason, b_num);
@@ -1055,7 +1467,7 @@ impl StorageInterface for StorageNode {
                     );
                     Response {
                         success: false,
-                        reason: "Blockchain item received: Block failed",
+                        reason: ResponseReason::BlockchainItemReceivedBlockFailed,
                     }
                 }
             }
@@ -1066,7 +1478,7 @@ impl StorageInterface for StorageNode {
 
             Response {
                 success: true,
-                reason: "Blockchain item received",
+                reason: ResponseReason::BlockchainItemReceived,
             }
         }
     }
@@ -1074,7 +1486,7 @@ impl StorageInterface for StorageNode {
     fn get_history(&self, _start_time: &u64, _end_time: &u64) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 
@@ -1083,28 +1495,28 @@ impl StorageInterface for StorageNode {
 
         Response {
             success: true,
-            reason: "Address added to whitelist",
+            reason: ResponseReason::AddressAddedToWhitelist,
         }
     }
 
     fn get_unicorn_table(&self, _n_last_items: Option<u64>) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 
     fn receive_pow(&self, _pow: ProofOfWork) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 
     fn receive_contracts(&self, _contract: Contract) -> Response {
         Response {
             success: false,
-            reason: "Not implemented yet",
+            reason: ResponseReason::NotImplementedYet,
         }
     }
 }
@@ -1205,23 +1617,32 @@ pub fn all_ordered_stored_block_tx_hashes<'a>(
 pub fn get_stored_value_from_db<K: AsRef<[u8]>>(
     db: Arc<Mutex<SimpleDb>>,
     key: K,
+) -> Option<BlockchainItem> {
+    get_stored_value_from_simple_db(&db.lock().unwrap(), key)
+}
+
+/// Get the stored value at the given key from an already locked database
+///
+/// ### Arguments
+///
+/// * `db`  - Database containing the stored chain
+/// * `key` - Given key to find the value.
+fn get_stored_value_from_simple_db<K: AsRef<[u8]>>(
+    db: &SimpleDb,
+    key: K,
 ) -> Option<BlockchainItem> {
     let col_all = if key.as_ref().first() == Some(&NAMED_CONSTANT_PREPEND) {
         DB_COL_BC_NAMED
     } else {
         DB_COL_BC_ALL
     };
-    let u_db = db.lock().unwrap();
-    let pointer = ok_or_warn(u_db.get_cf(col_all, key), "get_stored_value pointer")?;
+    let pointer = ok_or_warn(db.get_cf(col_all, key), "get_stored_value pointer")?;
 
     let (version, cf, key) = decode_version_pointer(&pointer);
-    let data = ok_or_warn(u_db.get_cf(cf, key), "get_stored_value data")?;
-    let data_json = ok_or_warn(
-        u_db.get_cf(DB_COL_BC_JSON, key),
-        "get_stored_value data_json",
-    )?;
+    let data = ok_or_warn(db.get_cf(cf, key), "get_stored_value data")?;
+    let data_json = ok_or_warn(db.get_cf(DB_COL_BC_JSON, key), "get_stored_value data_json")?;
     let meta = {
-        let meta = u_db.get_cf(DB_COL_BC_META, key);
+        let meta = db.get_cf(DB_COL_BC_META, key);
         let meta = ok_or_warn(meta, "get_stored_value meta")?;
         let meta = deserialize::<BlockchainItemMeta>(&meta).map(Some);
         ok_or_warn(meta, "get_stored_value meta ser")?
@@ -1235,6 +1656,433 @@ pub fn get_stored_value_from_db<K: AsRef<[u8]>>(
     })
 }
 
+/// Open the main database, creating the columns added without a network version change
+///
+/// ### Arguments
+///
+/// * `db_mode`        - Mode for the database
+/// * `old_db`         - Old in memory database to try to open
+/// * `custom_db_spec` - Custom database specification
+/// * `tuning`         - Performance tuning for file database
+fn new_storage_db(
+    db_mode: DbMode,
+    old_db: Option<SimpleDb>,
+    custom_db_spec: Option<CustomDbSpec>,
+    tuning: &DbTuning,
+) -> Result<SimpleDb> {
+    let mut db = db_utils::new_db_with_version_and_tuning(
+        db_mode,
+        &DB_SPEC_REQUIRED,
+        Some(NETWORK_VERSION_SERIALIZED),
+        old_db,
+        custom_db_spec,
+        tuning,
+    )?;
+    for column in DB_COLS_ADDED {
+        db.upgrade_create_missing_cf(column)?;
+    }
+    Ok(db)
+}
+
+/// Custom specification of the main database, within the node data directory if set
+///
+/// ### Arguments
+///
+/// * `config` - StorageNodeConfig for the storage node
+fn custom_db_spec(config: &StorageNodeConfig) -> Option<CustomDbSpec> {
+    let node_dir = format!("storage.{}", config.storage_node_idx);
+    db_utils::data_dir_db_spec(config.data_dir.as_deref(), &node_dir, &DB_SPEC)
+}
+
+/// Check storing the block would not replace or fork from the stored chain:
+/// Any block stored at the same height must be the same block,
+/// and any block stored at the previous height must be its previous block.
+///
+/// ### Arguments
+///
+/// * `db`           - Database containing the stored chain
+/// * `block_num`    - Height of the block to store
+/// * `block_hash`   - Hash of the block to store
+/// * `stored_block` - Block to store
+fn check_block_is_final(
+    db: &SimpleDb,
+    block_num: u64,
+    block_hash: &str,
+    stored_block: &StoredSerializingBlock,
+) -> Result<()> {
+    if let Some(existing_hash) = get_stored_block_hash(db, block_num)? {
+        if existing_hash != block_hash {
+            error!(
+                "Conflicting block at height {}: stored={} new={}",
+                block_num, existing_hash, block_hash
+            );
+            return Err(StorageError::ConflictingBlock(block_num));
+        }
+    }
+
+    if let Some(previous_num) = block_num.checked_sub(1) {
+        if let Some(previous_hash) = get_stored_block_hash(db, previous_num)? {
+            if stored_block.block.header.previous_hash.as_ref() != Some(&previous_hash) {
+                error!(
+                    "Block at height {} not following stored block {}: previous_hash={:?}",
+                    block_num, previous_hash, stored_block.block.header.previous_hash
+                );
+                return Err(StorageError::ConflictingBlock(block_num));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Get the hash of the block stored at the given height if any
+///
+/// ### Arguments
+///
+/// * `db`    - Database containing the stored chain
+/// * `b_num` - The block number
+fn get_stored_block_hash(db: &SimpleDb, b_num: u64) -> Result<Option<String>> {
+    let pointer = db.get_cf(DB_COL_BC_NAMED, indexed_block_hash_key(b_num))?;
+    Ok(pointer.map(|pointer| {
+        let (_, _, key) = decode_version_pointer(&pointer);
+        String::from_utf8_lossy(key).into_owned()
+    }))
+}
+
+/// Result of walking the stored chain from genesis to head
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVerification {
+    pub blocks_checked: u64,
+    pub inconsistency: Option<ChainInconsistency>,
+}
+
+/// First inconsistency found walking the stored chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainInconsistency {
+    pub b_num: u64,
+    pub reason: String,
+}
+
+/// Walk the stored blocks from genesis to head, checking each block is linked to the
+/// previous one and has a merkle root and transactions hash matching its transactions.
+/// Stop at the first inconsistency found.
+///
+/// ### Arguments
+///
+/// * `db` - Database containing the stored chain
+pub async fn verify_stored_chain(db: Arc<Mutex<SimpleDb>>) -> ChainVerification {
+    let head_num = match get_stored_block_blocking(db.clone(), LAST_BLOCK_HASH_KEY).await {
+        Some((_, head)) => head.block.header.b_num,
+        None => return Default::default(),
+    };
+
+    let mut previous_hash = None;
+    for b_num in 0..=head_num {
+        let inconsistent = |reason: &str| ChainVerification {
+            blocks_checked: b_num,
+            inconsistency: Some(ChainInconsistency {
+                b_num,
+                reason: reason.to_owned(),
+            }),
+        };
+
+        let key = indexed_block_hash_key(b_num);
+        let (hash, stored) = match get_stored_block_blocking(db.clone(), key).await {
+            Some(stored) => stored,
+            None => return inconsistent("Block missing"),
+        };
+        if stored.block.header.b_num != b_num {
+            return inconsistent("Block number mismatch");
+        }
+        if stored.block.header.previous_hash != previous_hash {
+            return inconsistent("Previous hash mismatch");
+        }
+
+        let mut recomputed = stored.block.clone();
+        recomputed.set_txs_merkle_root_and_hash().await;
+        if recomputed.header.txs_merkle_root_and_hash
+            != stored.block.header.txs_merkle_root_and_hash
+        {
+            return inconsistent("Merkle root mismatch");
+        }
+
+        previous_hash = Some(hash);
+    }
+
+    ChainVerification {
+        blocks_checked: head_num + 1,
+        inconsistency: None,
+    }
+}
+
+/// Get the stored block and its hash at the given key, reading the db on a blocking task
+///
+/// ### Arguments
+///
+/// * `db`  - Database containing the stored chain
+/// * `key` - Given key to find the block.
+async fn get_stored_block_blocking<K: AsRef<[u8]> + Send + 'static>(
+    db: Arc<Mutex<SimpleDb>>,
+    key: K,
+) -> Option<(String, StoredSerializingBlock)> {
+    let read = tokio::task::spawn_blocking(move || {
+        let item = get_stored_value_from_db(db, key)?;
+        let block = deserialize::<StoredSerializingBlock>(&item.data).ok()?;
+        Some((String::from_utf8(item.key).ok()?, block))
+    });
+    read.await.ok().flatten()
+}
+
+/// Reconstruct the UTXO set as of the given block by replaying the stored block
+/// transactions from the nearest persisted or cached checkpoint.
+/// Replays from genesis if no checkpoint is at or before the block.
+/// The reconstructed set is cached for later calls.
+///
+/// ### Arguments
+///
+/// * `db`          - Database containing the stored chain
+/// * `b_num`       - The block number to reconstruct the UTXO set at
+/// * `checkpoints` - Cached reconstructed UTXO sets
+pub async fn utxo_set_at_block(
+    db: Arc<Mutex<SimpleDb>>,
+    b_num: u64,
+    checkpoints: UtxoCheckpoints,
+) -> std::result::Result<BTreeSet<OutPoint>, StringError> {
+    let replay =
+        tokio::task::spawn_blocking(move || replay_utxo_set_at_block(db, b_num, &checkpoints));
+    replay
+        .await
+        .unwrap_or_else(|e| Err(StringError(e.to_string())))
+}
+
+/// Replay the stored block transactions up to the given block
+///
+/// ### Arguments
+///
+/// * `db`          - Database containing the stored chain
+/// * `b_num`       - The block number to reconstruct the UTXO set at
+/// * `checkpoints` - Cached reconstructed UTXO sets
+fn replay_utxo_set_at_block(
+    db: Arc<Mutex<SimpleDb>>,
+    b_num: u64,
+    checkpoints: &UtxoCheckpoints,
+) -> std::result::Result<BTreeSet<OutPoint>, StringError> {
+    let cached = checkpoints.lock().unwrap().get_nearest(b_num);
+    let persisted = get_nearest_utxo_checkpoint(&db.lock().unwrap(), b_num);
+    let nearest = match (cached, persisted) {
+        (Some(cached), Some(persisted)) => Some(std::cmp::max_by_key(cached, persisted, |c| c.0)),
+        (cached, persisted) => cached.or(persisted),
+    };
+    // Replay from genesis if no checkpoint at or before the block
+    let (start_b_num, mut utxo_set) = match nearest {
+        Some((c_num, utxo_set)) if c_num == b_num => return Ok(utxo_set),
+        Some((c_num, utxo_set)) => (c_num + 1, utxo_set),
+        None => (0, BTreeSet::new()),
+    };
+
+    let get_item = |key: &str| get_stored_value_from_db(db.clone(), key);
+    for replay_b_num in start_b_num..=b_num {
+        replay_stored_block(&get_item, replay_b_num, &mut utxo_set)?;
+    }
+
+    checkpoints.lock().unwrap().insert(b_num, utxo_set.clone());
+    Ok(utxo_set)
+}
+
+/// Apply the transactions of the stored block to the UTXO set
+///
+/// ### Arguments
+///
+/// * `get_item` - Get the stored value at the given key
+/// * `b_num`    - The block number to replay
+/// * `utxo_set` - UTXO set as of the previous block
+fn replay_stored_block(
+    get_item: &impl Fn(&str) -> Option<BlockchainItem>,
+    b_num: u64,
+    utxo_set: &mut BTreeSet<OutPoint>,
+) -> std::result::Result<(), StringError> {
+    let stored = get_item(&indexed_block_hash_key(b_num))
+        .and_then(|item| deserialize::<StoredSerializingBlock>(&item.data).ok())
+        .ok_or_else(|| StringError(format!("Block {b_num} missing")))?;
+
+    let all_txs = all_ordered_stored_block_tx_hashes(
+        &stored.block.transactions,
+        std::iter::once(&stored.block.header.nonce_and_mining_tx_hash),
+    );
+    let mut block_txs = Vec::new();
+    for (_, tx_hash) in all_txs {
+        let tx = get_item(tx_hash)
+            .and_then(|item| deserialize::<Transaction>(&item.data).ok())
+            .ok_or_else(|| StringError(format!("Block {b_num} transaction {tx_hash} missing")))?;
+        block_txs.push((tx_hash, tx));
+    }
+
+    apply_block_txs_to_utxo_set(utxo_set, block_txs.iter().map(|(h, tx)| (*h, tx)));
+    Ok(())
+}
+
+/// Spend the inputs and add the outputs of the given transactions, in order
+///
+/// ### Arguments
+///
+/// * `utxo_set` - UTXO set to update
+/// * `txs`      - The block transactions in stored order
+fn apply_block_txs_to_utxo_set<'a>(
+    utxo_set: &mut BTreeSet<OutPoint>,
+    txs: impl Iterator<Item = (&'a String, &'a Transaction)>,
+) {
+    for (tx_hash, tx) in txs {
+        for spent in get_inputs_previous_out_point(std::iter::once(tx)) {
+            utxo_set.remove(spent);
+        }
+        utxo_set
+            .extend(get_tx_out_with_out_point(std::iter::once((tx_hash, tx))).map(|(op, _)| op));
+    }
+}
+
+/// Get the persisted UTXO set checkpoint nearest to, and not after, the given block
+///
+/// ### Arguments
+///
+/// * `db`    - Database containing the checkpoints
+/// * `b_num` - The block number
+pub fn get_nearest_utxo_checkpoint(db: &SimpleDb, b_num: u64) -> Option<(u64, BTreeSet<OutPoint>)> {
+    let key = utxo_checkpoint_key(b_num);
+    let (c_num, utxo_set) = db.get_cf_at_or_before(DB_COL_UTXO_CHECKPOINT, key.as_bytes())?;
+    let c_num = u64::from_str_radix(str::from_utf8(&c_num).ok()?, 16).ok()?;
+    let utxo_set = deserialize(&utxo_set);
+    Some((
+        c_num,
+        ok_or_warn(utxo_set.map(Some), "get_nearest_utxo_checkpoint")?,
+    ))
+}
+
+/// Add a UTXO set checkpoint for the given block
+///
+/// ### Arguments
+///
+/// * `batch`    - Database writer
+/// * `b_num`    - The block number
+/// * `utxo_set` - UTXO set as of the block
+pub fn put_utxo_checkpoint(
+    batch: &mut SimpleDbWriteBatch,
+    b_num: u64,
+    utxo_set: &BTreeSet<OutPoint>,
+) {
+    let utxo_set = serialize(utxo_set).unwrap();
+    batch.put_cf(
+        DB_COL_UTXO_CHECKPOINT,
+        utxo_checkpoint_key(b_num),
+        &utxo_set,
+    );
+}
+
+/// Get the hashes of the transactions affecting an address, in block order
+///
+/// ### Arguments
+///
+/// * `db`      - Database containing the address index
+/// * `address` - The address receiving or spending tokens
+/// * `offset`  - Number of transactions to skip
+/// * `limit`   - Maximum number of transactions to return
+pub fn get_address_transactions(
+    db: &SimpleDb,
+    address: &str,
+    offset: usize,
+    limit: usize,
+) -> Vec<String> {
+    let prefix = address_tx_prefix(address);
+    db.iter_cf_prefix_clone(DB_COL_ADDRESS_TX, prefix.as_bytes())
+        .skip(offset)
+        .take(limit)
+        .filter_map(|(_, tx_hash)| String::from_utf8(tx_hash).ok())
+        .collect()
+}
+
+/// Add the address index entries for a transaction
+///
+/// ### Arguments
+///
+/// * `batch`     - Database writer
+/// * `addresses` - Addresses affected by the transaction
+/// * `block_num` - The block number
+/// * `tx_num`    - The transaction index in the block
+/// * `tx_hash`   - The transaction hash
+pub fn put_address_transactions(
+    batch: &mut SimpleDbWriteBatch,
+    addresses: &BTreeSet<String>,
+    block_num: u64,
+    tx_num: u32,
+    tx_hash: &str,
+) {
+    for address in addresses {
+        let key = address_tx_key(address, block_num, tx_num);
+        batch.put_cf(DB_COL_ADDRESS_TX, key, tx_hash);
+    }
+}
+
+/// Get the addresses receiving tokens from, or spending tokens in, a transaction
+/// Spent outputs of transactions that cannot be found are ignored
+///
+/// ### Arguments
+///
+/// * `tx`     - The transaction
+/// * `get_tx` - Lookup for the transactions spent from
+fn tx_affected_addresses(
+    tx: &Transaction,
+    get_tx: impl Fn(&str) -> Option<Transaction>,
+) -> BTreeSet<String> {
+    let spent = get_inputs_previous_out_point(std::iter::once(tx)).filter_map(|op| {
+        let spent_tx = get_tx(&op.t_hash)?;
+        let spent_out = spent_tx.outputs.get(usize::try_from(op.n).ok()?)?;
+        spent_out.script_public_key.clone()
+    });
+    let received = tx
+        .outputs
+        .iter()
+        .filter_map(|o| o.script_public_key.clone());
+    spent.chain(received).collect()
+}
+
+/// Get a stored transaction
+///
+/// ### Arguments
+///
+/// * `db`      - Database to query
+/// * `tx_hash` - The transaction hash
+fn get_stored_tx_from_simple_db(db: &SimpleDb, tx_hash: &str) -> Option<Transaction> {
+    let item = get_stored_value_from_simple_db(db, tx_hash)?;
+    deserialize(&item.data).ok()
+}
+
+/// The key for an address index entry
+///
+/// ### Arguments
+///
+/// * `address` - The address
+/// * `b_num`   - The block number
+/// * `tx_num`  - The transaction index in the block
+fn address_tx_key(address: &str, b_num: u64, tx_num: u32) -> String {
+    format!("{}{b_num:016x}_{tx_num:08x}", address_tx_prefix(address))
+}
+
+/// The key prefix for all the address index entries of an address
+///
+/// ### Arguments
+///
+/// * `address` - The address
+fn address_tx_prefix(address: &str) -> String {
+    format!("{address}{}", DB_POINTER_SEPARATOR as char)
+}
+
+/// The key for a UTXO set checkpoint
+///
+/// ### Arguments
+///
+/// * `b_num`  - The block number
+fn utxo_checkpoint_key(b_num: u64) -> String {
+    format!("{b_num:016x}")
+}
+
 /// Version pointer for the column:key
 ///
 /// ### Arguments
@@ -1288,3 +2136,334 @@ fn ok_or_warn<V, E: fmt::Display>(r: std::result::Result<Option<V>, E>, tag: &st
         None
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::configurations::DbMode;
+    use crate::db_utils::new_db;
+    use crate::interfaces::{CommonBlockInfo, MinedBlockExtraInfo};
+    use crate::utils::{apply_mining_tx, generate_pow_for_block};
+    use naom::primitives::asset::TokenAmount;
+    use naom::primitives::transaction::{TxIn, TxOut};
+    use naom::script::lang::Script;
+    use naom::utils::transaction_utils::construct_tx_hash;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn conflicting_block_rejected() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+        let block_0 = complete_block(0, None, "g0000000000000000000000000000000").await;
+        let stored_0 = store(&mut db, block_0).unwrap();
+        let block_1 = complete_block(1, Some(stored_0.block_hash.clone()), "g1111").await;
+        let stored_1 = store(&mut db, block_1.clone()).unwrap();
+
+        //
+        // Act
+        //
+        let conflict_1 = complete_block(1, Some(stored_0.block_hash.clone()), "g2222").await;
+        let fork_2 = complete_block(2, Some(stored_0.block_hash.clone()), "g3333").await;
+        let same_1 = store(&mut db, block_1).map(|s| s.block_hash);
+        let conflict_1 = store(&mut db, conflict_1).map(|s| s.block_hash);
+        let fork_2 = store(&mut db, fork_2).map(|s| s.block_hash);
+
+        //
+        // Assert
+        //
+        assert_eq!(same_1.ok(), Some(stored_1.block_hash.clone()));
+        assert!(matches!(conflict_1, Err(StorageError::ConflictingBlock(1))));
+        assert!(matches!(fork_2, Err(StorageError::ConflictingBlock(2))));
+        assert_eq!(
+            get_stored_block_hash(&db, 1).unwrap(),
+            Some(stored_1.block_hash)
+        );
+        assert_eq!(get_stored_block_hash(&db, 2).unwrap(), None);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn utxo_checkpoint_stored_with_block() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+        let mut checkpoints = UtxoCheckpointBuilder::load(&db, 2);
+        let mut previous_hash = None;
+
+        //
+        // Act
+        //
+        for b_num in 0..4 {
+            let block = complete_block_spending_previous(b_num, previous_hash.take()).await;
+            let stored = store_with_checkpoints(&mut db, Some(&mut checkpoints), block);
+            previous_hash = Some(stored.unwrap().block_hash);
+        }
+        let full_replay: Vec<_> = (0..4).map(|b_num| full_utxo_replay(&db, b_num)).collect();
+        let checkpoint_1 = get_nearest_utxo_checkpoint(&db, 1);
+        let checkpoint_3 = get_nearest_utxo_checkpoint(&db, 3);
+        let from_checkpoint = utxo_set_at_block(Arc::new(Mutex::new(db)), 3, Default::default());
+
+        //
+        // Assert
+        //
+        assert_eq!(checkpoint_1, Some((0, full_replay[0].clone())));
+        assert_eq!(checkpoint_3, Some((2, full_replay[2].clone())));
+        assert_eq!(from_checkpoint.await.unwrap(), full_replay[3]);
+        assert_eq!(full_replay[3].len(), 4);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn utxo_checkpoint_catches_up_with_stored_chain() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+        let mut previous_hash = None;
+        for b_num in 0..3 {
+            let block = complete_block_spending_previous(b_num, previous_hash.take()).await;
+            let stored = store(&mut db, block);
+            previous_hash = Some(stored.unwrap().block_hash);
+        }
+        let mut checkpoints = UtxoCheckpointBuilder::load(&db, 3);
+
+        //
+        // Act
+        //
+        let block = complete_block_spending_previous(3, previous_hash.take()).await;
+        let stored = store_with_checkpoints(&mut db, Some(&mut checkpoints), block);
+        let checkpoint_3 = get_nearest_utxo_checkpoint(&db, 3);
+
+        //
+        // Assert
+        //
+        assert!(stored.is_ok());
+        assert_eq!(checkpoint_3, Some((3, full_utxo_replay(&db, 3))));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn utxo_set_at_block_replays_from_genesis_without_checkpoint() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+        let mut previous_hash = None;
+        for b_num in 0..3 {
+            let block = complete_block_spending_previous(b_num, previous_hash.take()).await;
+            let stored = store(&mut db, block);
+            previous_hash = Some(stored.unwrap().block_hash);
+        }
+        let full_replay_0 = full_utxo_replay(&db, 0);
+        let full_replay_2 = full_utxo_replay(&db, 2);
+        let db = Arc::new(Mutex::new(db));
+        let checkpoints = UtxoCheckpoints::default();
+
+        //
+        // Act
+        //
+        let at_genesis = utxo_set_at_block(db.clone(), 0, checkpoints.clone()).await;
+        let at_tip = utxo_set_at_block(db, 2, checkpoints.clone()).await;
+
+        //
+        // Assert
+        //
+        assert_eq!(at_genesis, Ok(full_replay_0));
+        assert_eq!(at_tip, Ok(full_replay_2.clone()));
+        assert_eq!(
+            checkpoints.lock().unwrap().get_nearest(2),
+            Some((2, full_replay_2))
+        );
+    }
+
+    #[test]
+    fn utxo_checkpoint_cache_evicts_least_recently_used() {
+        //
+        // Arrange
+        //
+        let mut cache = UtxoCheckpointCache::default();
+        let utxo_set = |b_num: u64| -> BTreeSet<OutPoint> {
+            Some(OutPoint::new(format!("{b_num}"), 0))
+                .into_iter()
+                .collect()
+        };
+        let size = UTXO_CHECKPOINT_CACHE_SIZE as u64;
+
+        //
+        // Act
+        //
+        for b_num in 0..size {
+            cache.insert(b_num, utxo_set(b_num));
+        }
+        let used_0 = cache.get_nearest(0);
+        cache.insert(size, utxo_set(size));
+
+        //
+        // Assert
+        //
+        assert_eq!(used_0, Some((0, utxo_set(0))));
+        assert_eq!(cache.len(), UTXO_CHECKPOINT_CACHE_SIZE);
+        assert_eq!(cache.get_nearest(0), Some((0, utxo_set(0))));
+        assert_eq!(cache.get_nearest(1), Some((0, utxo_set(0))));
+        assert_eq!(cache.get_nearest(size), Some((size, utxo_set(size))));
+    }
+
+    #[test]
+    fn added_columns_created_on_existing_db() {
+        //
+        // Arrange
+        //
+        let old_db = new_db(DbMode::InMemory, &DB_SPEC_REQUIRED, None, None);
+
+        //
+        // Act
+        //
+        let db = new_storage_db(DbMode::InMemory, Some(old_db), None, &Default::default());
+
+        //
+        // Assert
+        //
+        let db = db.unwrap();
+        assert_eq!(get_nearest_utxo_checkpoint(&db, 0), None);
+        assert_eq!(db.count_cf(DB_COL_UTXO_CHECKPOINT), 0);
+        assert!(get_address_transactions(&db, "receiver_address", 0, 10).is_empty());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn address_transactions_indexed_with_block() {
+        //
+        // Arrange
+        //
+        let mut db = new_db(DbMode::InMemory, &DB_SPEC, None, None);
+        let mut previous_hash = None;
+        let mut blocks = Vec::new();
+        for b_num in 0..2 {
+            let block = complete_block_spending_previous(b_num, previous_hash.take()).await;
+            let status = FetchStatus::Contiguous(b_num);
+            let stored =
+                StorageNode::store_complete_block(&mut db, status, true, None, block.clone());
+            previous_hash = Some(stored.unwrap().block_hash);
+            blocks.push(block);
+        }
+
+        //
+        // Act
+        //
+        let receiver_txs = get_address_transactions(&db, "receiver_address", 0, 10);
+        let mining_txs = get_address_transactions(&db, "mining_address", 0, 10);
+        let mining_txs_page = get_address_transactions(&db, "mining_address", 1, 1);
+        let unknown_txs = get_address_transactions(&db, "unknown_address", 0, 10);
+
+        //
+        // Assert
+        //
+        let header_mining_tx =
+            |b: &CompleteBlock| b.common.block.header.nonce_and_mining_tx_hash.1.clone();
+        let block_1_tx = blocks[1].common.block.transactions[0].clone();
+        assert_eq!(receiver_txs, vec![block_1_tx.clone()]);
+        assert_eq!(
+            mining_txs,
+            vec![
+                header_mining_tx(&blocks[0]),
+                block_1_tx.clone(),
+                header_mining_tx(&blocks[1])
+            ]
+        );
+        assert_eq!(mining_txs_page, vec![block_1_tx]);
+        assert!(unknown_txs.is_empty());
+    }
+
+    fn full_utxo_replay(db: &SimpleDb, b_num: u64) -> BTreeSet<OutPoint> {
+        let get_item = |key: &str| get_stored_value_from_simple_db(db, key);
+        let mut utxo_set = BTreeSet::new();
+        for replay_b_num in 0..=b_num {
+            replay_stored_block(&get_item, replay_b_num, &mut utxo_set).unwrap();
+        }
+        utxo_set
+    }
+
+    fn store(db: &mut SimpleDb, complete: CompleteBlock) -> Result<BlockStoredInfo> {
+        store_with_checkpoints(db, None, complete)
+    }
+
+    fn store_with_checkpoints(
+        db: &mut SimpleDb,
+        utxo_checkpoints: Option<&mut UtxoCheckpointBuilder>,
+        complete: CompleteBlock,
+    ) -> Result<BlockStoredInfo> {
+        let b_num = complete.common.block.header.b_num;
+        let status = FetchStatus::Contiguous(b_num);
+        StorageNode::store_complete_block(db, status, false, utxo_checkpoints, complete)
+    }
+
+    async fn complete_block(
+        b_num: u64,
+        previous_hash: Option<String>,
+        mining_tx_hash: &str,
+    ) -> CompleteBlock {
+        complete_block_with_txs(b_num, previous_hash, mining_tx_hash, Default::default()).await
+    }
+
+    // Block with a mining transaction and a transaction spending the previous block one
+    async fn complete_block_spending_previous(
+        b_num: u64,
+        previous_hash: Option<String>,
+    ) -> CompleteBlock {
+        let mining_tx = |b_num: u64| {
+            let tx_out = TxOut::new_token_amount("mining_address".to_owned(), TokenAmount(b_num));
+            let tx = Transaction {
+                outputs: vec![tx_out],
+                ..Default::default()
+            };
+            (construct_tx_hash(&tx), tx)
+        };
+
+        let mut block_txs: BTreeMap<_, _> = std::iter::once(mining_tx(b_num)).collect();
+        if let Some(previous_b_num) = b_num.checked_sub(1) {
+            let (previous_mining_tx_hash, _) = mining_tx(previous_b_num);
+            let previous_out = OutPoint::new(previous_mining_tx_hash, 0);
+            let tx = Transaction {
+                inputs: vec![TxIn::new_from_input(previous_out, Script::new())],
+                outputs: vec![TxOut::new_token_amount(
+                    "receiver_address".to_owned(),
+                    TokenAmount(b_num),
+                )],
+                ..Default::default()
+            };
+            block_txs.insert(construct_tx_hash(&tx), tx);
+        }
+
+        let (mining_tx_hash, _) = mining_tx(b_num);
+        complete_block_with_txs(b_num, previous_hash, &mining_tx_hash, block_txs).await
+    }
+
+    async fn complete_block_with_txs(
+        b_num: u64,
+        previous_hash: Option<String>,
+        mining_tx_hash: &str,
+        block_txs: BTreeMap<String, Transaction>,
+    ) -> CompleteBlock {
+        let mut block = Block::new();
+        block.header.b_num = b_num;
+        block.header.previous_hash = previous_hash;
+        block.transactions = block_txs
+            .keys()
+            .filter(|hash| *hash != mining_tx_hash)
+            .cloned()
+            .collect();
+        block.set_txs_merkle_root_and_hash().await;
+        block.header = apply_mining_tx(block.header, Vec::new(), mining_tx_hash.to_owned());
+        block.header = generate_pow_for_block(block.header);
+
+        CompleteBlock {
+            common: CommonBlockInfo {
+                block,
+                block_txs,
+                pow_d_value: Default::default(),
+                pow_p_value: Default::default(),
+                unicorn: Default::default(),
+                unicorn_witness: Default::default(),
+            },
+            extra_info: MinedBlockExtraInfo::default(),
+        }
+    }
+}
@@ -1,10 +1,10 @@
-use crate::active_raft::ActiveRaft;
+use crate::active_raft::{ActiveRaft, ElectionTicks};
 use crate::configurations::StorageNodeConfig;
-use crate::constants::DB_PATH;
-use crate::db_utils::{self, SimpleDb, SimpleDbError, SimpleDbSpec};
+use crate::constants::{DB_PATH, NETWORK_VERSION_SERIALIZED};
+use crate::db_utils::{self, CustomDbSpec, SimpleDb, SimpleDbError, SimpleDbSpec};
 use crate::interfaces::{BlockStoredInfo, CommonBlockInfo, MinedBlockExtraInfo};
 use crate::raft::{RaftCommit, RaftCommitData, RaftData, RaftMessageWrapper};
-use crate::raft_util::{RaftContextKey, RaftInFlightProposals};
+use crate::raft_util::{RaftContextKey, RaftInFlightProposals, RaftProposalMetrics};
 use crate::utils::BackupCheck;
 use bincode::{deserialize, serialize};
 use naom::crypto::sha3_256;
@@ -113,18 +113,34 @@ impl StorageRaft {
     ///
     /// * `config`  - Configuration option for a storage node.
     /// * `raft_db` - Override raft db to use.
-    pub fn new(config: &StorageNodeConfig, raft_db: Option<SimpleDb>) -> Self {
+    pub fn new(
+        config: &StorageNodeConfig,
+        raft_db: Option<SimpleDb>,
+    ) -> Result<Self, SimpleDbError> {
         let use_raft = config.storage_raft != 0;
 
+        let custom_db_spec = custom_db_spec(config);
         if config.backup_restore.unwrap_or(false) {
-            db_utils::restore_file_backup(config.storage_db_mode, &DB_SPEC, None).unwrap();
+            let spec = custom_db_spec.clone();
+            db_utils::restore_file_backup(config.storage_db_mode, &DB_SPEC, spec).unwrap();
         }
         let raft_active = ActiveRaft::new(
             config.storage_node_idx,
             &config.storage_nodes,
             use_raft,
             Duration::from_millis(config.storage_raft_tick_timeout as u64),
-            db_utils::new_db(config.storage_db_mode, &DB_SPEC, raft_db, None),
+            ElectionTicks::new(
+                config.storage_raft_election_min_ticks,
+                config.storage_raft_election_max_ticks,
+            ),
+            db_utils::new_db_with_version_and_tuning(
+                config.storage_db_mode,
+                &DB_SPEC,
+                Some(NETWORK_VERSION_SERIALIZED),
+                raft_db,
+                custom_db_spec,
+                &config.storage_db_tuning.clone().unwrap_or_default(),
+            )?,
         );
 
         let first_raft_peer = config.storage_node_idx == 0 || !raft_active.use_raft();
@@ -133,15 +149,25 @@ impl StorageRaft {
         let consensused = StorageConsensused::default().with_peers_len(peers_len);
         let backup_check = BackupCheck::new(config.backup_block_modulo);
 
-        Self {
+        Ok(Self {
             first_raft_peer,
             raft_active,
             consensused,
             consensused_snapshot_applied: !use_raft,
-            proposed_in_flight: Default::default(),
+            proposed_in_flight: RaftInFlightProposals::default()
+                .with_max_in_flight(config.storage_raft_max_in_flight_proposals),
             shutdown_no_commit_process: false,
             backup_check,
-        }
+        })
+    }
+
+    /// Repair the raft database of a node that failed to start with `CorruptNeedsRepair`
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - StorageNodeConfig the node failed to start with
+    pub fn repair_db(config: &StorageNodeConfig) -> Result<(), SimpleDbError> {
+        db_utils::repair_db(config.storage_db_mode, &DB_SPEC, custom_db_spec(config))
     }
 
     /// Set the key run for all proposals (load from db before first proposal).
@@ -184,6 +210,16 @@ impl StorageRaft {
         !self.consensused_snapshot_applied
     }
 
+    /// Number of proposed items not yet committed
+    pub fn proposed_in_flight_len(&self) -> usize {
+        self.proposed_in_flight.in_flight_len()
+    }
+
+    /// Shared commit latency and throughput metrics of the proposals
+    pub fn proposal_metrics(&self) -> RaftProposalMetrics {
+        self.proposed_in_flight.metrics()
+    }
+
     /// Blocks & waits for a next commit from a peer.
     pub async fn next_commit(&self) -> Option<RaftCommit> {
         self.raft_active.next_commit().await
@@ -242,6 +278,9 @@ impl StorageRaft {
             .proposed_in_flight
             .received_commit_proposal(&raft_data, &raft_ctx)
             .await?;
+        self.proposed_in_flight
+            .propose_deferred_items(&mut self.raft_active)
+            .await;
 
         trace!("received_commit_proposal {:?} -> {:?}", key, item);
         match item {
@@ -333,7 +372,16 @@ impl StorageRaft {
 
         let shutdown = block_stored.shutdown;
         self.consensused.last_block_stored = Some(block_stored);
+        self.generate_snapshot();
 
+        if shutdown {
+            self.shutdown_no_commit_process = true;
+        }
+    }
+
+    /// Snapshot the consensused state at the last committed index.
+    /// Safe to call between events as it does not change the consensused state.
+    pub fn generate_snapshot(&mut self) {
         let consensused_ser = serialize(&self.consensused).unwrap();
         let (snapshot_idx, term) = self.consensused.last_committed_raft_idx_and_term;
 
@@ -341,10 +389,12 @@ impl StorageRaft {
         let backup = self.need_backup();
         self.raft_active
             .create_snapshot(snapshot_idx, consensused_ser, backup);
+    }
 
-        if shutdown {
-            self.shutdown_no_commit_process = true;
-        }
+    /// Index of the last committed raft entry and of the last snapshot generated
+    pub fn get_snapshot_indexes(&self) -> (u64, Option<u64>) {
+        let (committed_idx, _) = self.consensused.last_committed_raft_idx_and_term;
+        (committed_idx, self.raft_active.last_snapshot_idx())
     }
 
     /// Ignore processing raft item out of date.
@@ -363,6 +413,11 @@ impl StorageRaft {
         self.consensused.get_last_block_stored()
     }
 
+    /// Block number of the last block stored in consensus
+    pub fn get_last_stored_block_num(&self) -> Option<u64> {
+        self.get_last_block_stored().as_ref().map(|b| b.block_num)
+    }
+
     /// Whether shut down block already processed
     pub fn is_shutdown_commit_processed(&self) -> bool {
         self.shutdown_no_commit_process
@@ -482,3 +537,13 @@ impl StorageConsensused {
         &self.last_block_stored
     }
 }
+
+/// Custom specification of the raft database, within the node data directory if set
+///
+/// ### Arguments
+///
+/// * `config` - StorageNodeConfig for the storage node
+fn custom_db_spec(config: &StorageNodeConfig) -> Option<CustomDbSpec> {
+    let node_dir = format!("storage.{}", config.storage_node_idx);
+    db_utils::data_dir_db_spec(config.data_dir.as_deref(), &node_dir, &DB_SPEC)
+}
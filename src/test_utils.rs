@@ -7,11 +7,11 @@ use crate::compute::ComputeNode;
 use crate::compute_raft::MinerWhitelist;
 use crate::configurations::{
     ComputeNodeConfig, DbMode, ExtraNodeParams, MinerNodeConfig, NodeSpec, PreLaunchNodeConfig,
-    PreLaunchNodeType, StorageNodeConfig, TlsSpec, UserAutoGenTxSetup, UserNodeConfig, UtxoSetSpec,
-    WalletTxSpec,
+    PreLaunchNodeType, StorageNodeConfig, TlsSpec, UserAutoGenTxSetup, UserNodeConfig,
+    UtxoSetSizeCap, UtxoSetSpec, WalletTxSpec,
 };
 use crate::constants::{DB_PATH, DB_PATH_TEST, WALLET_PATH};
-use crate::interfaces::Response;
+use crate::interfaces::{Response, ResponseReason};
 use crate::miner::MinerNode;
 use crate::pre_launch::PreLaunchNode;
 use crate::storage::StorageNode;
@@ -37,6 +37,7 @@ use naom::utils::transaction_utils::{construct_tx_hash, construct_tx_in_signable
 use std::collections::{BTreeMap, BTreeSet};
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -79,27 +80,47 @@ pub struct NetworkConfig {
     pub storage_raft: bool,
     pub in_memory_db: bool,
     pub compute_partition_full_size: usize,
+    pub compute_partition_pow_difficulty: Option<usize>,
     pub compute_minimum_miner_pool_len: usize,
+    pub compute_first_block_grace_ms: Option<usize>,
+    pub compute_miner_max_missed_floods: Option<usize>,
+    pub compute_block_stall_timeout: Option<usize>,
+    pub compute_partition_random_num_rotation: Option<usize>,
+    pub compute_close_full_participant_intake: Option<bool>,
+    pub compute_winning_miner_fairness: Option<u64>,
+    pub compute_min_tx_fee: TokenAmount,
+    pub compute_dust_threshold: TokenAmount,
+    pub compute_max_locktime_offset: Option<u64>,
+    pub compute_max_block_size: Option<usize>,
+    pub compute_utxo_set_size_cap: Option<UtxoSetSizeCap>,
+    pub compute_receipt_minters: Option<Vec<String>>,
     pub compute_seed_utxo: UtxoSetSpec,
     pub compute_genesis_tx_in: Option<String>,
     pub user_wallet_seeds: Vec<Vec<WalletTxSpec>>,
     pub nodes: BTreeMap<NodeType, Vec<String>>,
     pub compute_to_miner_mapping: BTreeMap<String, Vec<String>>,
+    pub compute_to_storage_mapping: BTreeMap<String, String>,
     pub test_duration_divider: usize,
     pub passphrase: Option<String>,
     pub user_auto_donate: u64,
+    pub user_auto_donate_max_per_peer: Option<u64>,
+    pub user_auto_donate_max_total: Option<u64>,
+    pub user_auto_donate_window_secs: Option<u64>,
     pub user_test_auto_gen_setup: UserAutoGenTxSetup,
     pub tls_config: TestTlsSpec,
     pub routes_pow: BTreeMap<String, usize>,
     pub backup_block_modulo: Option<u64>,
     pub utxo_re_align_block_modulo: Option<u64>,
     pub backup_restore: Option<bool>,
+    pub storage_sync_block_ack: Option<bool>,
     pub enable_pipeline_reset: Option<bool>,
     pub static_miner_address: Option<String>,
+    pub miner_reward_address: Option<String>,
     pub mining_api_key: Option<String>,
     pub compute_miner_whitelist: MinerWhitelist,
     pub peer_limit: usize,
     pub address_aggregation_limit: Option<usize>,
+    pub wallet_max_active_addresses: Option<usize>,
 }
 
 /// Node info to create node
@@ -393,6 +414,15 @@ impl Network {
         self.arc_nodes.get(name).and_then(|v| v.storage())
     }
 
+    ///returns a mutable reference to the pre-launch node with the matching name.
+    ///
+    /// ### Arguments
+    ///
+    /// * `name` - &str of the pre-launch node's name to be found.
+    pub fn pre_launch(&self, name: &str) -> Option<&ArcPreLaunchNode> {
+        self.arc_nodes.get(name).and_then(|v| v.pre_launch())
+    }
+
     ///returns a mutable reference to the user node with the matching name.
     ///
     /// ### Arguments
@@ -584,6 +614,15 @@ impl ArcNode {
         }
     }
 
+    /// Get pre-launch from node
+    pub fn pre_launch(&self) -> Option<&ArcPreLaunchNode> {
+        if let Self::PreLaunch(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Get storage from node
     pub fn storage(&self) -> Option<&ArcStorageNode> {
         if let Self::Storage(v) = self {
@@ -1040,17 +1079,35 @@ async fn init_miner(
     let config = MinerNodeConfig {
         miner_address: node_info.node_spec.address,
         miner_db_mode: node_info.db_mode,
+        data_dir: None,
         tls_config: config.tls_config.make_tls_spec(&info.socket_name_mapping),
         api_keys: Default::default(),
+        api_signing_keys: Default::default(),
+        comms_format: Default::default(),
+        compatible_network_versions: Default::default(),
+        api_threaded_call_capacity: None,
+        api_threaded_call_timeout_ms: None,
         miner_compute_node_idx,
         compute_nodes: info.compute_nodes.clone(),
         passphrase: config.passphrase.clone(),
+        wallet_max_active_addresses: config.wallet_max_active_addresses,
+        wallet_encrypted_at_rest: None,
+        wallet_passphrase_min_length: None,
+        wallet_passphrase_min_entropy_bits: None,
+        wallet_kdf_iterations: None,
+        wallet_db_tuning: None,
         miner_api_port: 3004,
         miner_api_use_tls: true,
+        api_bind_address: None,
+        api_disabled_routes: Default::default(),
+        api_compression: Default::default(),
+        log_level: None,
+        log_json: false,
         routes_pow: config.routes_pow.clone(),
         backup_block_modulo: Default::default(),
         backup_restore: config.backup_restore,
         static_miner_address: config.static_miner_address.clone(),
+        miner_reward_address: config.miner_reward_address.clone(),
         mining_api_key: config.mining_api_key.clone(),
         peer_limit: config.peer_limit,
         address_aggregation_limit: config.address_aggregation_limit,
@@ -1078,23 +1135,43 @@ async fn init_storage(
 ) -> ArcStorageNode {
     let node_info = &info.node_infos[name];
     let storage_raft = usize::from(config.storage_raft);
+    let compute_to_storage_mapping = to_compute_to_storage_mapping(config, info);
 
     let config = StorageNodeConfig {
         storage_node_idx: node_info.index,
         storage_db_mode: node_info.db_mode,
+        data_dir: None,
         tls_config: config.tls_config.make_tls_spec(&info.socket_name_mapping),
         api_keys: Default::default(),
+        api_signing_keys: Default::default(),
+        comms_format: Default::default(),
+        compatible_network_versions: Default::default(),
         compute_nodes: info.compute_nodes.clone(),
         storage_nodes: info.storage_nodes.clone(),
+        compute_to_storage_mapping,
         storage_raft,
         storage_api_port: 3001,
         storage_api_use_tls: true,
+        api_bind_address: None,
+        api_disabled_routes: Default::default(),
+        api_compression: Default::default(),
+        log_level: None,
+        log_json: false,
         storage_raft_tick_timeout: 200 / config.test_duration_divider,
+        storage_raft_election_min_ticks: None,
+        storage_raft_election_max_ticks: None,
         storage_catchup_duration: 2000 / config.test_duration_divider,
         routes_pow: Default::default(),
         backup_block_modulo: config.backup_block_modulo,
         backup_restore: config.backup_restore,
         peer_limit: config.peer_limit,
+        storage_raft_max_in_flight_proposals: None,
+        storage_db_tuning: None,
+        storage_utxo_checkpoint_interval: None,
+        storage_address_index: None,
+        storage_sync_block_ack: config.storage_sync_block_ack,
+        storage_api_longpoll_timeout_ms: None,
+        storage_api_longpoll_max_pending: None,
     };
     let info = format!("{} -> {}", name, node_info.node_spec.address);
     info!("New Storage {}", info);
@@ -1119,28 +1196,62 @@ async fn init_compute(
 ) -> ArcComputeNode {
     let node_info = &info.node_infos[name];
     let compute_raft = usize::from(config.compute_raft);
+    let compute_to_storage_mapping = to_compute_to_storage_mapping(config, info);
 
     let config = ComputeNodeConfig {
         compute_db_mode: node_info.db_mode,
         compute_node_idx: node_info.index,
         tls_config: config.tls_config.make_tls_spec(&info.socket_name_mapping),
         api_keys: Default::default(),
+        api_signing_keys: Default::default(),
+        comms_format: Default::default(),
+        compatible_network_versions: Default::default(),
+        api_threaded_call_capacity: None,
+        api_threaded_call_timeout_ms: None,
         compute_unicorn_fixed_param: get_test_common_unicorn(),
         compute_nodes: info.compute_nodes.clone(),
         storage_nodes: info.storage_nodes.clone(),
+        compute_to_storage_mapping,
         user_nodes: info.user_nodes.clone(),
         compute_raft,
         compute_raft_tick_timeout: 200 / config.test_duration_divider,
+        compute_raft_election_min_ticks: None,
+        compute_raft_election_max_ticks: None,
         compute_mining_event_timeout: 500 / config.test_duration_divider,
         compute_transaction_timeout: 100 / config.test_duration_divider,
+        compute_transaction_timeout_min: None,
+        compute_transaction_timeout_max: None,
         compute_seed_utxo: config.compute_seed_utxo.clone(),
         compute_genesis_tx_in: config.compute_genesis_tx_in.clone(),
         compute_partition_full_size: config.compute_partition_full_size,
+        compute_partition_pow_difficulty: config.compute_partition_pow_difficulty,
         compute_minimum_miner_pool_len: config.compute_minimum_miner_pool_len,
+        compute_first_block_grace_ms: config.compute_first_block_grace_ms,
+        compute_miner_max_missed_floods: config.compute_miner_max_missed_floods,
+        compute_block_stall_timeout: config.compute_block_stall_timeout,
+        compute_partition_random_num_rotation: config.compute_partition_random_num_rotation,
+        compute_close_full_participant_intake: config.compute_close_full_participant_intake,
+        compute_winning_miner_fairness: config.compute_winning_miner_fairness,
+        compute_db_tuning: None,
+        data_dir: None,
+        compute_min_block_reward: Default::default(),
+        compute_min_tx_fee: config.compute_min_tx_fee,
+        compute_dust_threshold: config.compute_dust_threshold,
+        compute_max_locktime_offset: config.compute_max_locktime_offset,
+        compute_replace_by_fee_delta: None,
+        compute_receipt_minters: config.compute_receipt_minters.clone(),
+        compute_max_block_size: config.compute_max_block_size,
+        compute_utxo_set_size_cap: config.compute_utxo_set_size_cap,
+        compute_reward_halving: None,
         jurisdiction: "US".to_string(),
         sanction_list: Vec::new(),
         compute_api_port: 3002,
         compute_api_use_tls: true,
+        api_bind_address: None,
+        api_disabled_routes: Default::default(),
+        api_compression: Default::default(),
+        log_level: None,
+        log_json: false,
         routes_pow: Default::default(),
         backup_block_modulo: config.backup_block_modulo,
         utxo_re_align_block_modulo: config.utxo_re_align_block_modulo,
@@ -1148,6 +1259,7 @@ async fn init_compute(
         enable_trigger_messages_pipeline_reset: config.enable_pipeline_reset,
         compute_miner_whitelist: config.compute_miner_whitelist.clone(),
         peer_limit: config.peer_limit,
+        compute_raft_max_in_flight_proposals: None,
     };
     let info = format!("{} -> {}", name, node_info.node_spec.address);
     info!("New Compute {}", info);
@@ -1156,6 +1268,27 @@ async fn init_compute(
     ))
 }
 
+///Resolve the named compute to storage mapping to node addresses.
+///
+/// ### Arguments
+///
+/// * `config` - &NetworkConfig holding configuration Infomation.
+/// * `info`   - &NetworkInstanceInfo holding the node addresses.
+fn to_compute_to_storage_mapping(
+    config: &NetworkConfig,
+    info: &NetworkInstanceInfo,
+) -> BTreeMap<SocketAddr, SocketAddr> {
+    config
+        .compute_to_storage_mapping
+        .iter()
+        .map(|(compute, storage)| {
+            let compute = info.node_infos[compute].node_spec.address;
+            let storage = info.node_infos[storage].node_spec.address;
+            (compute, storage)
+        })
+        .collect()
+}
+
 ///Initialize User node of given name based on network info.
 ///
 /// ### Arguments
@@ -1183,15 +1316,35 @@ async fn init_user(
     let config = UserNodeConfig {
         user_address: node_info.node_spec.address,
         user_db_mode: node_info.db_mode,
+        data_dir: None,
         tls_config: config.tls_config.make_tls_spec(&info.socket_name_mapping),
         api_keys: Default::default(),
+        api_signing_keys: Default::default(),
+        comms_format: Default::default(),
+        compatible_network_versions: Default::default(),
+        api_threaded_call_capacity: None,
+        api_threaded_call_timeout_ms: None,
         user_compute_node_idx: 0,
         compute_nodes: info.compute_nodes.clone(),
         user_api_port: 3000,
         user_api_use_tls: true,
+        api_bind_address: None,
+        api_disabled_routes: Default::default(),
+        api_compression: Default::default(),
+        log_level: None,
+        log_json: false,
         user_wallet_seeds,
         passphrase: config.passphrase.clone(),
+        wallet_max_active_addresses: config.wallet_max_active_addresses,
+        wallet_encrypted_at_rest: None,
+        wallet_passphrase_min_length: None,
+        wallet_passphrase_min_entropy_bits: None,
+        wallet_kdf_iterations: None,
+        wallet_db_tuning: None,
         user_auto_donate: config.user_auto_donate,
+        user_auto_donate_max_per_peer: config.user_auto_donate_max_per_peer,
+        user_auto_donate_max_total: config.user_auto_donate_max_total,
+        user_auto_donate_window_secs: config.user_auto_donate_window_secs,
         user_test_auto_gen_setup: config.user_test_auto_gen_setup.clone(),
         routes_pow: Default::default(),
         backup_block_modulo: Default::default(),
@@ -1231,9 +1384,12 @@ async fn init_pre_launch(
         tls_config: config.tls_config.make_tls_spec(&info.socket_name_mapping),
         storage_node_idx: node_info.index,
         storage_db_mode: node_info.db_mode,
+        data_dir: None,
         compute_nodes: info.compute_nodes.clone(),
         storage_nodes: info.storage_nodes.clone(),
         peer_limit: config.peer_limit,
+        pre_launch_api_port: None,
+        api_bind_address: None,
     };
 
     let info = format!("{} -> {}", name, node_info.node_spec.address);
@@ -1296,7 +1452,7 @@ fn check_timeout<E>(
 ) -> Result<Option<Result<Response, E>>, String> {
     if let Some(Ok(Response {
         success: true,
-        reason: "Test timeout elapsed",
+        reason: ResponseReason::Exit("Test timeout elapsed"),
     })) = response
     {
         Err("Test timeout elapsed".to_owned())
@@ -1501,3 +1657,46 @@ pub fn generate_rb_transactions(
 pub fn map_receipts(details: Vec<(String, u64)>) -> BTreeMap<String, u64> {
     details.into_iter().collect()
 }
+
+/// Tracing layer counting the events that pass the subscriber filters
+#[derive(Clone, Default)]
+pub struct EventCounter(Arc<AtomicUsize>);
+
+impl EventCounter {
+    /// Number of events recorded so far
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for EventCounter {
+    fn on_event(
+        &self,
+        _event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Writer capturing log output in memory
+#[derive(Clone, Default)]
+pub struct LogCapture(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl LogCapture {
+    /// Output written so far
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl std::io::Write for LogCapture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -3,14 +3,15 @@
 use crate::compute::ComputeNode;
 use crate::compute_raft::MinerWhitelist;
 use crate::configurations::{
-    ComputeNodeSharedConfig, TxOutSpec, UserAutoGenTxSetup, UtxoSetSpec, WalletTxSpec,
+    ComputeNodeSharedConfig, TxOutSpec, UserAutoGenTxSetup, UtxoSetSizeCap, UtxoSetSpec,
+    WalletTxSpec,
 };
-use crate::constants::{NETWORK_VERSION, SANC_LIST_TEST};
+use crate::constants::{MINING_DIFFICULTY, NETWORK_VERSION, SANC_LIST_TEST, SUPPORTED_TX_VERSIONS};
 use crate::interfaces::{
     BlockStoredInfo, BlockchainItem, BlockchainItemMeta, BlockchainItemType, CommonBlockInfo,
-    ComputeApi, ComputeRequest, DruidPool, MinedBlock, MinedBlockExtraInfo, Response,
-    StorageRequest, StoredSerializingBlock, UserApiRequest, UserRequest, UtxoFetchType, UtxoSet,
-    WinningPoWInfo,
+    ComputeApi, ComputeRequest, DruidPool, MineApiRequest, MinedBlock, MinedBlockExtraInfo,
+    MinerPayout, PowInfo, ProofOfWork, Response, ResponseReason, StorageRequest,
+    StoredSerializingBlock, UserApiRequest, UserRequest, UtxoFetchType, UtxoSet, WinningPoWInfo,
 };
 use crate::miner::MinerNode;
 use crate::storage::{all_ordered_stored_block_tx_hashes, StorageNode};
@@ -21,13 +22,17 @@ use crate::test_utils::{
 };
 use crate::tracked_utxo::TrackedUtxoBalance;
 use crate::transactor::Transactor;
-use crate::user::UserNode;
+use crate::tx_policy::{PolicyDecision, TxPolicy};
+use crate::user::{UserNode, OUTBOUND_PAYMENT_EXPIRY_SECS};
 use crate::utils::{
     apply_mining_tx, calculate_reward, construct_coinbase_tx, construct_valid_block_pow_hash,
     create_valid_create_transaction_with_ins_outs, create_valid_transaction_with_ins_outs,
-    decode_pub_key, decode_secret_key, generate_pow_for_block, get_sanction_addresses,
-    tracing_log_try_init, LocalEvent, StringError,
+    decode_pub_key, decode_pub_key_as_address, decode_secret_key, druid_cancellation_message,
+    format_parition_pow_address, generate_pow_for_block, generate_pow_nonce,
+    get_sanction_addresses, tracing_log_try_init, validate_pow_block, validate_pow_for_address,
+    LocalEvent, StringError,
 };
+use crate::wallet::AddressStore;
 use bincode::{deserialize, deserialize_from};
 use naom::crypto::sha3_256;
 use naom::crypto::sign_ed25519 as sign;
@@ -46,7 +51,7 @@ use std::future::Future;
 use std::io::Cursor;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::Barrier;
 use tokio::sync::Mutex;
 use tokio::time;
@@ -143,6 +148,25 @@ enum CfgModif {
     Reconnect(&'static str),
 }
 
+/// Policy rejecting transactions paying to a blacklisted address
+#[derive(Debug)]
+struct BlacklistPolicy(String);
+
+impl TxPolicy for BlacklistPolicy {
+    fn accept(&self, tx: &Transaction, _utxo: &UtxoSet) -> PolicyDecision {
+        let blacklisted = Some(&self.0);
+        if tx
+            .outputs
+            .iter()
+            .any(|o| o.script_public_key.as_ref() == blacklisted)
+        {
+            PolicyDecision::Reject(format!("Output to blacklisted address {}", self.0))
+        } else {
+            PolicyDecision::Accept
+        }
+    }
+}
+
 #[test]
 fn will_get_sanctioned_addresses() {
     let addresses = get_sanction_addresses(SANC_LIST_TEST.to_string(), "US");
@@ -352,788 +376,806 @@ async fn full_flow_single_miner_single_raft_with_static_miner_address_check() {
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn full_flow_multi_miners_raft_1_node() {
-    full_flow_multi_miners(complete_network_config_with_n_compute_miner(
-        11010, true, 1, 3,
-    ))
-    .await;
-}
+async fn storage_raft_status_reports_stored_block() {
+    test_step_start();
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_multi_miners_raft_2_nodes() {
-    full_flow_multi_miners(complete_network_config_with_n_compute_miner(
-        11020, true, 2, 6,
-    ))
-    .await;
-}
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config_with_n_compute_miner(11225, false, 1, 1);
+    let mut network = Network::create_from_config(&network_config).await;
+    let active_nodes = network.all_active_nodes().clone();
+    let storage = &active_nodes[&NodeType::Storage][0];
+    let initial_status = network.storage(storage).unwrap().lock().await.raft_status();
+    let initial = (
+        initial_status.stored_block_num(),
+        initial_status.compute_block_num(),
+    );
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_kill_forever_miner_node_3_nodes() {
-    let modify_cfg = vec![("After create block 0", CfgModif::Drop("miner2"))];
-    let network_config = complete_network_config_with_n_compute_raft(11130, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
-}
+    //
+    // Act
+    //
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_kill_forever_compute_node_3_nodes() {
-    let modify_cfg = vec![("After create block 0", CfgModif::Drop("compute2"))];
+    let status = network.storage(storage).unwrap().lock().await.raft_status();
 
-    let network_config = complete_network_config_with_n_compute_raft(11140, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
+    //
+    // Assert
+    //
+    assert_eq!(initial, (None, None));
+    assert_eq!(
+        (status.stored_block_num(), status.compute_block_num()),
+        (Some(0), Some(0))
+    );
+
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_kill_forever_storage_node_3_nodes() {
-    let modify_cfg = vec![("After create block 0", CfgModif::Drop("storage2"))];
+async fn full_flow_single_miner_with_reward_address() {
+    test_step_start();
 
-    let network_config = complete_network_config_with_n_compute_raft(11150, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
-}
+    //
+    // Arrange
+    //
+    let reward_address =
+        "1f3e5a4ab6d2c7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60".to_owned();
+    let mut network_config = complete_network_config_with_n_compute_miner(11050, true, 1, 1);
+    network_config.miner_reward_address = Some(reward_address.clone());
+    let mut network = Network::create_from_config(&network_config).await;
+    let active_nodes = network.all_active_nodes().clone();
+    let storage = &active_nodes[&NodeType::Storage][0];
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_kill_miner_node_3_nodes() {
-    let modify_cfg = vec![
-        ("After create block 0", CfgModif::Drop("miner2")),
-        ("After create block 1", CfgModif::Respawn("miner2")),
-        (
-            "After create block 1",
-            CfgModif::HandleEvents(&[("compute2", "Received partition request successfully")]),
-        ),
-    ];
-    let network_config = complete_network_config_with_n_compute_raft(11160, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
-}
+    //
+    // Act
+    //
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_kill_compute_node_3_nodes() {
-    let modify_cfg = vec![
-        ("After create block 0", CfgModif::Drop("compute2")),
-        ("After create block 1", CfgModif::Respawn("compute2")),
-        (
-            "After create block 1",
-            CfgModif::HandleEvents(&[
-                ("compute2", "Snapshot applied"),
-                ("compute1", "Sent runtime data to peer"),
-                ("compute3", "Sent runtime data to peer"),
-                ("compute2", "Received runtime data from peer"),
-                ("compute2", "Received runtime data from peer"),
-                ("compute2", "Winning PoW intake open"),
-                ("compute2", "Pipeline halted"),
-                ("compute2", "Transactions committed"),
-                ("compute2", "Block committed"),
-            ]),
-        ),
-    ];
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+    proof_of_work_act(&mut network, CfgPow::Parallel, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
 
-    let network_config = complete_network_config_with_n_compute_raft(11170, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
-}
+    let last_block = storage_get_last_block_stored(&mut network, storage)
+        .await
+        .unwrap();
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_kill_storage_node_3_nodes() {
-    let modify_cfg = vec![
-        ("After create block 0", CfgModif::Drop("storage2")),
-        ("After create block 1", CfgModif::Respawn("storage2")),
-        (
-            "After create block 1",
-            CfgModif::HandleEvents(&[("storage2", BLOCK_STORED)]),
-        ),
-    ];
+    //
+    // Assert
+    //
+    let coinbase_addresses: Vec<_> = last_block
+        .mining_transactions
+        .values()
+        .flat_map(|tx| tx.outputs.iter())
+        .map(|out| out.script_public_key.clone())
+        .collect();
+    assert_eq!(coinbase_addresses, vec![Some(reward_address)]);
 
-    let network_config = complete_network_config_with_n_compute_raft(11180, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_dis_and_re_connect_miner_node_3_nodes() {
-    let modify_cfg = vec![
-        ("After create block 0", CfgModif::Disconnect("miner2")),
-        ("After create block 1", CfgModif::Reconnect("miner2")),
-    ];
-    let network_config = complete_network_config_with_n_compute_raft(11190, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
-}
+async fn create_compute_with_mapped_storage_address() {
+    test_step_start();
 
-#[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_dis_and_re_connect_compute_node_3_nodes() {
-    let modify_cfg = vec![
-        ("After create block 0", CfgModif::Disconnect("compute2")),
-        ("After create block 1", CfgModif::Reconnect("compute2")),
-        (
-            "After create block 1",
-            CfgModif::HandleEvents(&[
-                ("compute1", "Sent runtime data to peer"),
-                ("compute3", "Sent runtime data to peer"),
-                ("compute2", "Received runtime data from peer"),
-                ("compute2", "Received runtime data from peer"),
-                ("compute2", "Winning PoW intake open"),
-                ("compute2", "Pipeline halted"),
-                ("compute2", "Transactions committed"),
-                ("compute2", "Block committed"),
-            ]),
-        ),
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config_with_n_compute_raft(11060, 2);
+    network_config.compute_to_storage_mapping =
+        vec![("compute1".to_owned(), "storage2".to_owned())]
+            .into_iter()
+            .collect();
+
+    //
+    // Act
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let storage1 = network.get_address("storage1").await.unwrap();
+    let storage2 = network.get_address("storage2").await.unwrap();
+    let compute1 = network.get_address("compute1").await.unwrap();
+    let compute2 = network.get_address("compute2").await.unwrap();
+    let actual = vec![
+        compute_get_storage_address(&mut network, "compute1").await,
+        compute_get_storage_address(&mut network, "compute2").await,
+    ];
+    let actual_compute = vec![
+        storage_get_compute_address(&mut network, "storage1").await,
+        storage_get_compute_address(&mut network, "storage2").await,
     ];
 
-    let network_config = complete_network_config_with_n_compute_raft(11200, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
+    //
+    // Assert
+    //
+    assert_ne!(storage1, storage2);
+    assert_eq!(actual, vec![storage2, storage2]);
+    assert_ne!(compute1, compute2);
+    assert_eq!(actual_compute, vec![compute1, compute1]);
+
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn full_flow_raft_dis_and_re_connect_storage_node_3_nodes() {
-    let modify_cfg = vec![
-        ("After create block 0", CfgModif::Disconnect("storage2")),
-        ("After create block 1", CfgModif::Reconnect("storage2")),
-        (
-            "After create block 1",
-            CfgModif::HandleEvents(&[("storage2", BLOCK_STORED)]),
-        ),
-    ];
+async fn send_block_to_storage_after_storage_reconnect() {
+    test_step_start();
 
-    let network_config = complete_network_config_with_n_compute_raft(11220, 3);
-    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
-}
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config_with_n_compute_raft(11070, 1);
+    let mut network = Network::create_from_config(&network_config).await;
+    let storage_addr = network.get_address("storage1").await.unwrap();
+    let (mut compute_conn, _, _) = network
+        .compute("compute1")
+        .unwrap()
+        .lock()
+        .await
+        .connect_info_peers();
 
-async fn full_flow_multi_miners(mut network_config: NetworkConfig) {
-    network_config.compute_partition_full_size = 2;
-    network_config.compute_minimum_miner_pool_len = 3;
-    full_flow(network_config).await;
-}
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
 
-async fn full_flow(network_config: NetworkConfig) {
-    full_flow_tls(network_config, CfgNum::All, Vec::new()).await;
-}
+    network
+        .disconnect_nodes_named(&["storage1".to_owned()])
+        .await;
+    while compute_conn
+        .unconnected_peers(&[storage_addr])
+        .await
+        .is_empty()
+    {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
 
-async fn full_flow_no_tls(network_config: NetworkConfig) {
-    full_flow_common(network_config, CfgNum::All, Vec::new()).await;
-}
+    //
+    // Act
+    //
+    let unreachable_result = {
+        let mut c = network.compute("compute1").unwrap().lock().await;
+        c.send_block_to_storage().await
+    };
+    let pending_before = compute_is_block_pending_storage(&mut network, "compute1").await;
 
-async fn full_flow_tls(
-    mut network_config: NetworkConfig,
-    cfg_num: CfgNum,
-    modify_cfg: Vec<(&str, CfgModif)>,
-) {
-    network_config.tls_config = get_test_tls_spec();
-    full_flow_common(network_config, cfg_num, modify_cfg).await;
+    network
+        .re_connect_nodes_named(&["storage1".to_owned()])
+        .await;
+    let mut event_tx = network.get_local_event_tx("compute1").await.unwrap();
+    event_tx
+        .send(
+            LocalEvent::ReconnectionComplete,
+            "reconnection complete test",
+        )
+        .await
+        .unwrap();
+    compute_handle_event(
+        &mut network,
+        "compute1",
+        &["Resent block to storage on reconnection"],
+    )
+    .await;
+    storage_handle_event(&mut network, "storage1", BLOCK_RECEIVED).await;
+    storage_handle_event(&mut network, "storage1", BLOCK_STORED).await;
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+
+    //
+    // Assert
+    //
+    let stored = storage_get_last_block_stored(&mut network, "storage1").await;
+    let pending_after = compute_is_block_pending_storage(&mut network, "compute1").await;
+    assert!(unreachable_result.is_err(), "{unreachable_result:?}");
+    assert!(pending_before);
+    assert_eq!(stored.map(|b| b.block_num), Some(0));
+    assert!(!pending_after);
+
+    test_step_complete(network).await;
 }
 
-async fn full_flow_common(
-    network_config: NetworkConfig,
-    cfg_num: CfgNum,
-    modify_cfg: Vec<(&str, CfgModif)>,
-) {
+#[tokio::test(flavor = "current_thread")]
+async fn resent_block_already_stored_not_stored_twice() {
     test_step_start();
 
     //
     // Arrange
     //
+    let network_config = complete_network_config(11235);
     let mut network = Network::create_from_config(&network_config).await;
-    let initial_utxo_txs = network.collect_initial_uxto_txs();
-    let mining_reward = network.mining_reward();
-    let transactions = valid_transactions(true);
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    let initial_db_count = storage_get_stored_key_values_count(&mut network, "storage1").await;
 
     //
     // Act
     //
-    create_first_block_act(&mut network).await;
-    modify_network(&mut network, "After create block 0", &modify_cfg).await;
-    proof_of_work_act(&mut network, CfgPow::First, cfg_num, false, None).await;
-    send_block_to_storage_act(&mut network, cfg_num).await;
-    let stored0 = storage_get_last_block_stored(&mut network, "storage1").await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    let stored_db_count = storage_get_stored_key_values_count(&mut network, "storage1").await;
 
-    add_transactions_act(&mut network, &transactions).await;
-    create_block_act(&mut network, Cfg::All, cfg_num).await;
-    modify_network(&mut network, "After create block 1", &modify_cfg).await;
-
-    proof_of_work_act(&mut network, CfgPow::Parallel, cfg_num, false, None).await;
-    send_block_to_storage_act(&mut network, cfg_num).await;
-    let stored1 = storage_get_last_block_stored(&mut network, "storage1").await;
-
-    create_block_act(&mut network, Cfg::All, cfg_num).await;
+    compute_send_block_to_storage(&mut network, "compute1").await;
+    storage_handle_event(&mut network, "storage1", "Block received already stored").await;
+    compute_handle_event(&mut network, "compute1", &["Received block stored"]).await;
+    let resent_db_count = storage_get_stored_key_values_count(&mut network, "storage1").await;
 
     //
     // Assert
     //
-    let active_nodes = network.all_active_nodes().clone();
-    let storage_nodes = &active_nodes[&NodeType::Storage];
-    let miner_nodes = &active_nodes[&NodeType::Miner];
-
-    let actual1 = storage_all_get_last_stored_info(&mut network, storage_nodes).await;
-    assert_eq!(equal_first(&actual1), node_all(storage_nodes, true));
-
-    let stored0 = stored0.unwrap();
-    let stored1 = stored1.unwrap();
-    let stored0_mining_tx_len = stored0.mining_transactions.len();
-    let stored1_mining_tx_len = stored1.mining_transactions.len();
-    assert_eq!((stored0_mining_tx_len, stored1_mining_tx_len), (1, 1));
-
-    let actual0_db_count =
-        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
-    let expected_block0_db_count = 1 + initial_utxo_txs.len() + stored0_mining_tx_len;
-    let expected_block1_db_count = 1 + transactions.len() + stored1_mining_tx_len;
-    assert_eq!(
-        actual0_db_count,
-        node_all(
-            storage_nodes,
-            expected_block0_db_count + expected_block1_db_count
-        )
-    );
-
-    let actual_w0 = node_all_combined_get_wallet_info(&mut network, miner_nodes).await;
-    let expected_w0 =
-        node_all_combined_expected_wallet_info(miner_nodes, mining_reward, &[&stored0]);
-    assert_eq!((actual_w0.0, actual_w0.1.len(), actual_w0.2), expected_w0);
+    assert!(stored_db_count > initial_db_count);
+    assert_eq!(resent_db_count, stored_db_count);
 
     test_step_complete(network).await;
 }
 
-async fn modify_network(network: &mut Network, tag: &str, modif_config: &[(&str, CfgModif)]) {
-    for (_tag, modif) in modif_config.iter().filter(|(t, _)| tag == *t) {
-        match modif {
-            CfgModif::Drop(v) => network.close_loops_and_drop_named(&[v.to_string()]).await,
-            CfgModif::Respawn(v) => {
-                let nodes = vec![v.to_string()];
-                network.re_spawn_nodes_named(&nodes).await;
-                network.send_startup_requests_named(&nodes).await;
-            }
-            CfgModif::HandleEvents(es) => {
-                let all_nodes = network.all_active_nodes_name_vec();
-                let all_raisons = network.all_active_nodes_events(|_, n| {
-                    let es_for_t = es.iter().filter(|(ne, _)| *ne == n);
-                    es_for_t.map(|(_, e)| e.to_string()).collect()
-                });
-                node_all_handle_different_event(network, &all_nodes, &all_raisons).await
-            }
-            CfgModif::RestartEventsAll(es) | CfgModif::RestartUpgradeEventsAll(es) => {
-                let all_nodes = network.all_active_nodes_name_vec();
-                let all_raisons = network.all_active_nodes_events(|t, _| {
-                    let es_for_t = es.iter().filter(|(te, _)| *te == t);
-                    es_for_t.map(|(_, e)| e.to_string()).collect()
-                });
-                network.close_loops_and_drop_named(&all_nodes).await;
-                if matches!(modif, CfgModif::RestartUpgradeEventsAll(_)) {
-                    network.upgrade_closed_nodes().await;
-                }
-                network.re_spawn_nodes_named(&all_nodes).await;
-                network.send_startup_requests_named(&all_nodes).await;
-                if !es.is_empty() {
-                    node_all_handle_different_event(network, &all_nodes, &all_raisons).await;
-                }
-            }
-            CfgModif::Disconnect(v) => network.disconnect_nodes_named(&[v.to_string()]).await,
-            CfgModif::Reconnect(v) => {
-                network.re_connect_nodes_named(&[v.to_string()]).await;
-                let mut event_tx = network.get_local_event_tx(v).await.unwrap();
-                event_tx
-                    .send(
-                        LocalEvent::ReconnectionComplete,
-                        "reconnection complete test",
-                    )
-                    .await
-                    .unwrap();
-
-                let event = Some((
-                    v.to_string(),
-                    vec!["Sent startup requests on reconnection".to_string()],
-                ))
-                .into_iter()
-                .collect();
-                node_all_handle_different_event(network, &[v.to_string()], &event).await;
-
-                // Process miner's request at compute node
-                if let Some(miner) = network.miner(v) {
-                    let compute_addr = miner.lock().await.compute_address();
-
-                    let compute_nodes = network.all_active_nodes()[&NodeType::Compute].clone();
-                    for c in compute_nodes {
-                        if network.compute(&c).unwrap().lock().await.local_address() == compute_addr
-                        {
-                            compute_handle_event(
-                                network,
-                                &c,
-                                &["Received partition request successfully"],
-                            )
-                            .await;
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 #[tokio::test(flavor = "current_thread")]
-async fn create_first_block_no_raft() {
-    create_first_block(complete_network_config(10000)).await;
-}
+async fn resent_block_already_stored_flushed_before_ack_real_db() {
+    test_step_start();
 
-#[tokio::test(flavor = "current_thread")]
-async fn create_first_block_raft_1_node() {
-    create_first_block(complete_network_config_with_n_compute_raft(10010, 1)).await;
-}
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11790);
+    network_config.in_memory_db = false;
+    network_config.storage_sync_block_ack = Some(true);
+    remove_all_node_dbs(&network_config);
+    let mut network = Network::create_from_config(&network_config).await;
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    compute_handle_event(&mut network, "compute1", &["Received block stored"]).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn create_first_block_raft_2_nodes() {
-    create_first_block(complete_network_config_with_n_compute_raft(10020, 2)).await;
-}
+    //
+    // Act
+    //
+    compute_send_block_to_storage(&mut network, "compute1").await;
+    storage_handle_event(&mut network, "storage1", "Block received already stored").await;
+    let unflushed_at_ack = storage_get_unflushed_entry_count(&mut network, "storage1").await;
+    compute_handle_event(&mut network, "compute1", &["Received block stored"]).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn create_first_block_raft_3_nodes() {
-    create_first_block(complete_network_config_with_n_compute_raft(10030, 3)).await;
-}
+    //
+    // Assert
+    //
+    assert_eq!(unflushed_at_ack, 0);
 
-#[tokio::test(flavor = "current_thread")]
-async fn create_first_block_raft_15_nodes() {
-    create_first_block(complete_network_config_with_n_compute_raft(10040, 15)).await;
+    test_step_complete(network).await;
 }
 
-async fn create_first_block(network_config: NetworkConfig) {
+#[tokio::test(flavor = "current_thread")]
+async fn sync_block_ack_stored_block_survives_restart_real_db() {
     test_step_start();
 
     //
     // Arrange
     //
+    let mut network_config = complete_network_config(11245);
+    network_config.in_memory_db = false;
+    network_config.storage_sync_block_ack = Some(true);
+    remove_all_node_dbs(&network_config);
     let mut network = Network::create_from_config(&network_config).await;
-    let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let expected_utxo = to_utxo_set(&network.collect_initial_uxto_txs());
+    let storage = "storage1".to_owned();
+
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
 
     //
     // Act
     //
-    create_first_block_act(&mut network).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    let unflushed_at_ack = storage_get_unflushed_entry_count(&mut network, &storage).await;
+    compute_handle_event(&mut network, "compute1", &["Received block stored"]).await;
+    let stored = storage_get_last_block_stored(&mut network, &storage).await;
+    let stored_db_count = storage_get_stored_key_values_count(&mut network, &storage).await;
+
+    network.close_loops_and_drop_named(&[storage.clone()]).await;
+    network.re_spawn_nodes_named(&[storage.clone()]).await;
+    let reopened_db_count = storage_get_stored_key_values_count(&mut network, &storage).await;
+    let reopened_block = {
+        let s = network.storage(&storage).unwrap().lock().await;
+        let block_hash = &stored.as_ref().unwrap().block_hash;
+        s.get_stored_value(block_hash)
+    };
 
     //
     // Assert
     //
-    let utxo_set_after = compute_all_committed_utxo_set(&mut network, compute_nodes).await;
-    assert_eq!(utxo_set_after, node_all(compute_nodes, expected_utxo));
+    assert_eq!(unflushed_at_ack, 0);
+    assert_eq!(stored.map(|b| b.block_num), Some(0));
+    assert_eq!(reopened_db_count, stored_db_count);
+    assert!(reopened_block.is_some());
 
     test_step_complete(network).await;
 }
 
-async fn create_first_block_act(network: &mut Network) {
-    let config = network.config().clone();
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-    let first_request_size = config.compute_minimum_miner_pool_len;
+#[tokio::test(flavor = "current_thread")]
+async fn force_snapshot_at_committed_index_raft_1_node() {
+    test_step_start();
 
-    info!("Test Step Connect nodes");
-    for compute in compute_nodes {
-        let miners = &config.compute_to_miner_mapping[compute];
-        for (idx, miner) in miners.iter().enumerate() {
-            node_send_startup_requests(network, miner).await;
-            let evt = if idx == first_request_size - 1 {
-                "Received first full partition request"
-            } else {
-                "Received partition request successfully"
-            };
-            compute_handle_event(network, compute, &[evt]).await;
-        }
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config_with_n_compute_raft(11255, 1);
+    let mut network = Network::create_from_config(&network_config).await;
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act
+    //
+    for name in ["compute1", "storage1"] {
+        let mut event_tx = network.get_local_event_tx(name).await.unwrap();
+        let event = LocalEvent::ForceSnapshot;
+        event_tx.send(event, "force snapshot test").await.unwrap();
     }
+    compute_handle_event(&mut network, "compute1", &["Snapshot forced"]).await;
+    storage_handle_event(&mut network, "storage1", "Snapshot forced").await;
 
-    info!("Test Step Create first Block");
-    node_all_handle_event(network, compute_nodes, &["First Block committed"]).await;
-}
+    let compute_indexes = {
+        let c = network.compute("compute1").unwrap().lock().await;
+        c.get_snapshot_indexes()
+    };
+    let storage_indexes = {
+        let s = network.storage("storage1").unwrap().lock().await;
+        s.get_snapshot_indexes()
+    };
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_first_block_to_storage_no_raft() {
-    send_first_block_to_storage(complete_network_config(10800)).await;
-}
+    //
+    // Assert
+    //
+    let (compute_committed, compute_snapshot) = compute_indexes;
+    let (storage_committed, storage_snapshot) = storage_indexes;
+    assert_eq!(compute_snapshot, Some(compute_committed));
+    assert_eq!(storage_snapshot, Some(storage_committed));
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_first_block_to_storage_raft_1_node() {
-    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10810, 1)).await;
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn send_first_block_to_storage_raft_2_nodes() {
-    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10820, 2)).await;
-}
+async fn resend_persisted_mined_block_after_compute_restart() {
+    test_step_start();
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_first_block_to_storage_raft_3_nodes() {
-    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10830, 3)).await;
-}
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config_with_n_compute_raft(11080, 1);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute = "compute1".to_owned();
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_first_block_to_storage_raft_majority_3_nodes() {
-    send_first_block_to_storage_common(
-        complete_network_config_with_n_compute_raft(10840, 3),
-        CfgNum::Majority,
-    )
-    .await;
-}
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    let mined_before = compute_mined_block_num(&mut network, &compute).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_first_block_to_storage_raft_15_nodes() {
-    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10850, 15)).await;
-}
+    //
+    // Act
+    //
+    network.close_loops_and_drop_named(&[compute.clone()]).await;
+    network.re_spawn_nodes_named(&[compute.clone()]).await;
+    let mined_reloaded = compute_mined_block_num(&mut network, &compute).await;
 
-async fn send_first_block_to_storage(network_config: NetworkConfig) {
-    send_first_block_to_storage_common(network_config, CfgNum::All).await;
+    compute_resend_trigger_message(&mut network, &compute).await;
+    storage_handle_event(&mut network, "storage1", BLOCK_RECEIVED).await;
+    storage_handle_event(&mut network, "storage1", BLOCK_STORED).await;
+
+    //
+    // Assert
+    //
+    let stored = storage_get_last_block_stored(&mut network, "storage1").await;
+    assert_eq!(mined_before, Some(0));
+    assert_eq!(mined_reloaded, mined_before);
+    assert_eq!(stored.map(|b| b.block_num), Some(0));
+
+    test_step_complete(network).await;
 }
 
-async fn send_first_block_to_storage_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+#[tokio::test(flavor = "current_thread")]
+async fn cancel_coordinated_shutdown_keeps_producing_blocks() {
     test_step_start();
 
     //
     // Arrange
     //
+    let network_config = complete_network_config_with_n_compute_raft(11090, 1);
     let mut network = Network::create_from_config(&network_config).await;
-    let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let storage_nodes = &network_config.nodes[&NodeType::Storage];
-    let initial_utxo_txs = network.collect_initial_uxto_txs();
-    let (expected0, block_info0) = complete_first_block(&initial_utxo_txs).await;
+    let compute = "compute1";
 
-    create_first_block_act(&mut network).await;
-    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
+    node_send_coordinated_shutdown(&mut network, compute, 1).await;
+    compute_handle_event(&mut network, compute, &["Start coordinated shutdown"]).await;
+    let scheduled = compute_get_coordinated_shutdown(&mut network, compute).await;
 
     //
     // Act
     //
-    send_block_to_storage_act(&mut network, cfg_num).await;
+    node_send_cancel_shutdown(&mut network, compute).await;
+    compute_handle_event(&mut network, compute, &["Cancel coordinated shutdown"]).await;
+    let cancelled = compute_get_coordinated_shutdown(&mut network, compute).await;
 
-    //
-    // Assert
-    //
-    let actual0 = storage_all_get_last_stored_info(&mut network, storage_nodes).await;
-    assert_eq!(
-        actual0[0],
-        (
-            Some(expected0.1),
-            Some((expected0.0, 0 /*b_num*/, 1 /*single mining tx*/,))
-        )
-    );
-    assert_eq!(equal_first(&actual0), node_all(storage_nodes, true));
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+    proof_of_work_act(&mut network, CfgPow::Parallel, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
 
-    let actual0_db_count =
-        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
-    assert_eq!(
-        actual0_db_count,
-        node_all(storage_nodes, 1 + initial_utxo_txs.len() + 1)
-    );
+    //
+    // Assert
+    //
+    let stored = storage_get_last_block_stored(&mut network, "storage1").await;
+    let committed = compute_committed_current_block_num(&mut network, compute).await;
+    assert_eq!(scheduled, Some(1));
+    assert_eq!(cancelled, None);
+    assert_eq!(stored.map(|b| (b.block_num, b.shutdown)), Some((1, false)));
+    assert_eq!(committed, Some(2));
 
     test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn add_transactions_no_raft() {
-    add_transactions(complete_network_config(10600)).await;
-}
-
-#[tokio::test(flavor = "current_thread")]
-async fn add_transactions_raft_1_node() {
-    add_transactions(complete_network_config_with_n_compute_raft(10610, 1)).await;
-}
-
-#[tokio::test(flavor = "current_thread")]
-async fn add_transactions_restart_tx_raft_1_node() {
-    let tag = "After add local Transactions";
-    let modify_cfg = vec![
-        (tag, CfgModif::Drop("compute1")),
-        (tag, CfgModif::Respawn("compute1")),
-        (
-            tag,
-            CfgModif::HandleEvents(&[("compute1", "Snapshot applied")]),
-        ),
-    ];
-
-    let network_config = complete_network_config_with_n_compute_raft(10615, 1);
-    add_transactions_common(network_config, &modify_cfg).await;
-}
-
-#[tokio::test(flavor = "current_thread")]
-async fn add_transactions_raft_2_nodes() {
-    add_transactions(complete_network_config_with_n_compute_raft(10620, 2)).await;
-}
-
-#[tokio::test(flavor = "current_thread")]
-async fn add_transactions_raft_3_nodes() {
-    add_transactions(complete_network_config_with_n_compute_raft(10630, 3)).await;
-}
-
-#[tokio::test(flavor = "current_thread")]
-async fn add_transactions_raft_15_nodes() {
-    add_transactions(complete_network_config_with_n_compute_raft(10640, 15)).await;
-}
-
-async fn add_transactions(network_config: NetworkConfig) {
-    add_transactions_common(network_config, &[]).await;
-}
-
-async fn add_transactions_common(network_config: NetworkConfig, modify_cfg: &[(&str, CfgModif)]) {
+async fn detect_block_production_stall() {
     test_step_start();
 
     //
     // Arrange
     //
+    let mut network_config = complete_network_config_with_n_compute_raft(11100, 1);
+    network_config.compute_block_stall_timeout = Some(1000);
     let mut network = Network::create_from_config(&network_config).await;
-    let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let transactions = valid_transactions(true);
-
-    create_first_block_act(&mut network).await;
+    let compute = "compute1";
+    let past_timeout = Duration::from_millis(2000);
 
     //
     // Act
     //
-    add_transactions_act_with(&mut network, &transactions, Cfg::IgnoreWaitTxComplete).await;
-    modify_network(&mut network, "After add local Transactions", modify_cfg).await;
-    add_transactions_act_with(&mut network, &Default::default(), Cfg::All).await;
+    let stalled_initially =
+        compute_check_block_production_stall(&mut network, compute, Duration::ZERO).await;
+    let stalled_past_timeout =
+        compute_check_block_production_stall(&mut network, compute, past_timeout).await;
+    create_first_block_act(&mut network).await;
+    let stalled_after_block = compute_is_block_production_stalled(&mut network, compute).await;
 
     //
     // Assert
     //
-    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
-    assert_eq!(actual[0], transactions);
-    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+    assert!(!stalled_initially);
+    assert!(stalled_past_timeout);
+    assert!(!stalled_after_block);
 
     test_step_complete(network).await;
 }
 
-async fn add_transactions_act(network: &mut Network, txs: &BTreeMap<String, Transaction>) {
-    add_transactions_act_with(network, txs, Cfg::All).await;
+#[tokio::test(flavor = "current_thread")]
+async fn full_flow_multi_miners_raft_1_node() {
+    full_flow_multi_miners(complete_network_config_with_n_compute_miner(
+        11010, true, 1, 3,
+    ))
+    .await;
 }
 
-async fn add_transactions_act_with(
-    network: &mut Network,
-    txs: &BTreeMap<String, Transaction>,
-    cfg: Cfg,
-) {
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-
-    for tx in txs.values() {
-        info!("Test Step Add Transactions");
-        user_send_transaction_to_compute(network, "user1", "compute1", tx).await;
-    }
-    for _tx in txs.values() {
-        compute_handle_event(network, "compute1", &["Transactions added to tx pool"]).await;
-    }
-
-    if cfg != Cfg::IgnoreWaitTxComplete {
-        node_all_handle_event(network, compute_nodes, &["Transactions committed"]).await;
-    }
+#[tokio::test(flavor = "current_thread")]
+async fn full_flow_multi_miners_raft_2_nodes() {
+    full_flow_multi_miners(complete_network_config_with_n_compute_miner(
+        11020, true, 2, 6,
+    ))
+    .await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_block_no_raft() {
-    create_block(complete_network_config(10100)).await;
+async fn full_flow_raft_kill_forever_miner_node_3_nodes() {
+    let modify_cfg = vec![("After create block 0", CfgModif::Drop("miner2"))];
+    let network_config = complete_network_config_with_n_compute_raft(11130, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_block_raft_1_node() {
-    create_block(complete_network_config_with_n_compute_raft(10110, 1)).await;
+async fn full_flow_raft_kill_forever_compute_node_3_nodes() {
+    let modify_cfg = vec![("After create block 0", CfgModif::Drop("compute2"))];
+
+    let network_config = complete_network_config_with_n_compute_raft(11140, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_block_raft_2_nodes() {
-    create_block(complete_network_config_with_n_compute_raft(10120, 2)).await;
+async fn full_flow_raft_kill_forever_storage_node_3_nodes() {
+    let modify_cfg = vec![("After create block 0", CfgModif::Drop("storage2"))];
+
+    let network_config = complete_network_config_with_n_compute_raft(11150, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_block_raft_3_nodes() {
-    create_block(complete_network_config_with_n_compute_raft(10130, 3)).await;
+async fn full_flow_raft_kill_miner_node_3_nodes() {
+    let modify_cfg = vec![
+        ("After create block 0", CfgModif::Drop("miner2")),
+        ("After create block 1", CfgModif::Respawn("miner2")),
+        (
+            "After create block 1",
+            CfgModif::HandleEvents(&[("compute2", "Received partition request successfully")]),
+        ),
+    ];
+    let network_config = complete_network_config_with_n_compute_raft(11160, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_block_raft_majority_3_nodes() {
-    create_block_common(
-        complete_network_config_with_n_compute_raft(10140, 3),
-        CfgNum::Majority,
-    )
-    .await;
+async fn full_flow_raft_kill_compute_node_3_nodes() {
+    let modify_cfg = vec![
+        ("After create block 0", CfgModif::Drop("compute2")),
+        ("After create block 1", CfgModif::Respawn("compute2")),
+        (
+            "After create block 1",
+            CfgModif::HandleEvents(&[
+                ("compute2", "Snapshot applied"),
+                ("compute1", "Sent runtime data to peer"),
+                ("compute3", "Sent runtime data to peer"),
+                ("compute2", "Received runtime data from peer"),
+                ("compute2", "Received runtime data from peer"),
+                ("compute2", "Winning PoW intake open"),
+                ("compute2", "Pipeline halted"),
+                ("compute2", "Transactions committed"),
+                ("compute2", "Block committed"),
+            ]),
+        ),
+    ];
+
+    let network_config = complete_network_config_with_n_compute_raft(11170, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn create_block_raft_15_nodes() {
-    create_block(complete_network_config_with_n_compute_raft(10150, 15)).await;
+async fn full_flow_raft_kill_storage_node_3_nodes() {
+    let modify_cfg = vec![
+        ("After create block 0", CfgModif::Drop("storage2")),
+        ("After create block 1", CfgModif::Respawn("storage2")),
+        (
+            "After create block 1",
+            CfgModif::HandleEvents(&[("storage2", BLOCK_STORED)]),
+        ),
+    ];
+
+    let network_config = complete_network_config_with_n_compute_raft(11180, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
-async fn create_block(network_config: NetworkConfig) {
-    create_block_common(network_config, CfgNum::All).await;
+#[tokio::test(flavor = "current_thread")]
+async fn full_flow_raft_dis_and_re_connect_miner_node_3_nodes() {
+    let modify_cfg = vec![
+        ("After create block 0", CfgModif::Disconnect("miner2")),
+        ("After create block 1", CfgModif::Reconnect("miner2")),
+    ];
+    let network_config = complete_network_config_with_n_compute_raft(11190, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
 }
 
-async fn create_block_common(network_config: NetworkConfig, cfg_num: CfgNum) {
-    test_step_start();
+#[tokio::test(flavor = "current_thread")]
+async fn full_flow_raft_dis_and_re_connect_compute_node_3_nodes() {
+    let modify_cfg = vec![
+        ("After create block 0", CfgModif::Disconnect("compute2")),
+        ("After create block 1", CfgModif::Reconnect("compute2")),
+        (
+            "After create block 1",
+            CfgModif::HandleEvents(&[
+                ("compute1", "Sent runtime data to peer"),
+                ("compute3", "Sent runtime data to peer"),
+                ("compute2", "Received runtime data from peer"),
+                ("compute2", "Received runtime data from peer"),
+                ("compute2", "Winning PoW intake open"),
+                ("compute2", "Pipeline halted"),
+                ("compute2", "Transactions committed"),
+                ("compute2", "Block committed"),
+            ]),
+        ),
+    ];
 
-    //
-    // Arrange
-    //
-    let mut network = Network::create_from_config(&network_config).await;
-    let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let transactions = valid_transactions(true);
-    let transactions_h = transactions.keys().cloned().collect::<Vec<_>>();
-    let transactions_utxo = to_utxo_set(&transactions);
-    let (_, block_info0) = complete_first_block(&network.collect_initial_uxto_txs()).await;
-    let block0_mining_tx = complete_block_mining_txs(&block_info0);
-    let block0_mining_utxo = to_utxo_set(&block0_mining_tx);
+    let network_config = complete_network_config_with_n_compute_raft(11200, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
+}
 
-    let mut left_init_utxo = to_utxo_set(&network.collect_initial_uxto_txs());
-    remove_keys(&mut left_init_utxo, valid_txs_in().keys());
+#[tokio::test(flavor = "current_thread")]
+async fn full_flow_raft_dis_and_re_connect_storage_node_3_nodes() {
+    let modify_cfg = vec![
+        ("After create block 0", CfgModif::Disconnect("storage2")),
+        ("After create block 1", CfgModif::Reconnect("storage2")),
+        (
+            "After create block 1",
+            CfgModif::HandleEvents(&[("storage2", BLOCK_STORED)]),
+        ),
+    ];
 
-    create_first_block_act(&mut network).await;
-    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
-    send_block_to_storage_act(&mut network, CfgNum::All).await;
-    add_transactions_act(&mut network, &transactions).await;
+    let network_config = complete_network_config_with_n_compute_raft(11220, 3);
+    full_flow_tls(network_config, CfgNum::All, modify_cfg).await;
+}
 
-    //
-    // Act
-    //
-    let block_transaction_before =
-        compute_all_current_block_transactions(&mut network, compute_nodes).await;
-
-    create_block_act(&mut network, Cfg::All, cfg_num).await;
-
-    let block_transaction_after =
-        compute_all_current_block_transactions(&mut network, compute_nodes).await;
-    let utxo_set_after = compute_all_committed_utxo_set(&mut network, compute_nodes).await;
-
-    //
-    // Assert
-    //
-    assert_eq!(block_transaction_before, node_all(compute_nodes, None));
-    assert_eq!(
-        block_transaction_after,
-        node_all(compute_nodes, Some(transactions_h))
-    );
-
-    let expected_utxo = merge_txs_3(&left_init_utxo, &transactions_utxo, &block0_mining_utxo);
-    assert_eq!(len_and_map(&utxo_set_after[0]), len_and_map(&expected_utxo));
-    assert_eq!(equal_first(&utxo_set_after), node_all(compute_nodes, true));
-
-    test_step_complete(network).await;
+async fn full_flow_multi_miners(mut network_config: NetworkConfig) {
+    network_config.compute_partition_full_size = 2;
+    network_config.compute_minimum_miner_pool_len = 3;
+    full_flow(network_config).await;
 }
 
-async fn create_block_act(network: &mut Network, cfg: Cfg, cfg_num: CfgNum) {
-    create_block_act_with(network, cfg, cfg_num, 0).await
+async fn full_flow(network_config: NetworkConfig) {
+    full_flow_tls(network_config, CfgNum::All, Vec::new()).await;
 }
 
-async fn create_block_act_with(network: &mut Network, cfg: Cfg, cfg_num: CfgNum, block_num: u64) {
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-    let (msg_c_nodes, msg_s_nodes) = node_combined_select(
-        &network.config().nodes[&NodeType::Compute],
-        &network.config().nodes[&NodeType::Storage],
-        network.dead_nodes(),
-        cfg_num,
-    );
-
-    info!("Test Step Storage signal new block");
-
-    if cfg == Cfg::IgnoreStorage {
-        let block = BlockStoredInfo {
-            block_num,
-            ..Default::default()
-        };
-        let req = ComputeRequest::SendBlockStored(block);
-        compute_all_inject_next_event(network, &msg_s_nodes, &msg_c_nodes, req).await;
-    } else {
-        storage_all_send_stored_block(network, &msg_s_nodes).await;
-    }
-    compute_all_handle_event(network, &msg_c_nodes, "Received block stored").await;
+async fn full_flow_no_tls(network_config: NetworkConfig) {
+    full_flow_common(network_config, CfgNum::All, Vec::new()).await;
+}
 
-    info!("Test Step Generate Block");
-    node_all_handle_event(network, compute_nodes, &["Block committed"]).await;
+async fn full_flow_tls(
+    mut network_config: NetworkConfig,
+    cfg_num: CfgNum,
+    modify_cfg: Vec<(&str, CfgModif)>,
+) {
+    network_config.tls_config = get_test_tls_spec();
+    full_flow_common(network_config, cfg_num, modify_cfg).await;
 }
 
-#[tokio::test(flavor = "current_thread")]
-async fn create_block_with_seed() {
+async fn full_flow_common(
+    network_config: NetworkConfig,
+    cfg_num: CfgNum,
+    modify_cfg: Vec<(&str, CfgModif)>,
+) {
     test_step_start();
 
     //
     // Arrange
     //
-    let network_config = complete_network_config_with_n_compute_raft(11600, 1);
     let mut network = Network::create_from_config(&network_config).await;
-    let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let compute = &compute_nodes[0];
+    let initial_utxo_txs = network.collect_initial_uxto_txs();
+    let mining_reward = network.mining_reward();
     let transactions = valid_transactions(true);
-    let (_, block_info0) = complete_first_block(&network.collect_initial_uxto_txs()).await;
 
     //
     // Act
     //
     create_first_block_act(&mut network).await;
-    let block0 = compute_current_mining_block(&mut network, compute).await;
-    let seed0 = block0.as_ref().map(|b| b.header.seed_value.as_slice());
-    let seed0 = seed0.and_then(|s| std::str::from_utf8(s).ok());
+    modify_network(&mut network, "After create block 0", &modify_cfg).await;
+    proof_of_work_act(&mut network, CfgPow::First, cfg_num, false, None).await;
+    send_block_to_storage_act(&mut network, cfg_num).await;
+    let stored0 = storage_get_last_block_stored(&mut network, "storage1").await;
 
-    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
-    send_block_to_storage_act(&mut network, CfgNum::All).await;
     add_transactions_act(&mut network, &transactions).await;
-    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
-    let block1 = compute_current_mining_block(&mut network, compute).await;
-    let seed1 = block1.as_ref().map(|b| b.header.seed_value.as_slice());
-    let seed1 = seed1.and_then(|s| std::str::from_utf8(s).ok());
+    create_block_act(&mut network, Cfg::All, cfg_num).await;
+    modify_network(&mut network, "After create block 1", &modify_cfg).await;
+
+    proof_of_work_act(&mut network, CfgPow::Parallel, cfg_num, false, None).await;
+    send_block_to_storage_act(&mut network, cfg_num).await;
+    let stored1 = storage_get_last_block_stored(&mut network, "storage1").await;
+
+    create_block_act(&mut network, Cfg::All, cfg_num).await;
 
     //
     // Assert
     //
-    let expected_seed0 = Some("102382207707718734792748219972459444372508601011471438062299221139355828742917-4092482189202844858141461446747443254065713079405017303649880917821984131927979764736076459305831834761744847895656303682167530187457916798745160233343351193");
-    let expected_seed1 = Some("106228469607590785158439105345663906454407776332951582911215086478921447072494-890801478181097874306473746009596725559978631952297198866826970472802729293224119401323816857729365460297789241934487318750450632705046684127753444374170213");
-    assert_eq!((seed0, seed1), (expected_seed0, expected_seed1));
+    let active_nodes = network.all_active_nodes().clone();
+    let storage_nodes = &active_nodes[&NodeType::Storage];
+    let miner_nodes = &active_nodes[&NodeType::Miner];
+
+    let actual1 = storage_all_get_last_stored_info(&mut network, storage_nodes).await;
+    assert_eq!(equal_first(&actual1), node_all(storage_nodes, true));
+
+    let stored0 = stored0.unwrap();
+    let stored1 = stored1.unwrap();
+    let stored0_mining_tx_len = stored0.mining_transactions.len();
+    let stored1_mining_tx_len = stored1.mining_transactions.len();
+    assert_eq!((stored0_mining_tx_len, stored1_mining_tx_len), (1, 1));
+
+    let actual0_db_count =
+        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
+    let expected_block0_db_count = 1 + initial_utxo_txs.len() + stored0_mining_tx_len;
+    let expected_block1_db_count = 1 + transactions.len() + stored1_mining_tx_len;
+    assert_eq!(
+        actual0_db_count,
+        node_all(
+            storage_nodes,
+            expected_block0_db_count + expected_block1_db_count
+        )
+    );
+
+    let actual_w0 = node_all_combined_get_wallet_info(&mut network, miner_nodes).await;
+    let expected_w0 =
+        node_all_combined_expected_wallet_info(miner_nodes, mining_reward, &[&stored0]);
+    assert_eq!((actual_w0.0, actual_w0.1.len(), actual_w0.2), expected_w0);
 
     test_step_complete(network).await;
 }
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_no_raft() {
-    proof_of_work(complete_network_config(10200)).await;
-}
+async fn modify_network(network: &mut Network, tag: &str, modif_config: &[(&str, CfgModif)]) {
+    for (_tag, modif) in modif_config.iter().filter(|(t, _)| tag == *t) {
+        match modif {
+            CfgModif::Drop(v) => network.close_loops_and_drop_named(&[v.to_string()]).await,
+            CfgModif::Respawn(v) => {
+                let nodes = vec![v.to_string()];
+                network.re_spawn_nodes_named(&nodes).await;
+                network.send_startup_requests_named(&nodes).await;
+            }
+            CfgModif::HandleEvents(es) => {
+                let all_nodes = network.all_active_nodes_name_vec();
+                let all_raisons = network.all_active_nodes_events(|_, n| {
+                    let es_for_t = es.iter().filter(|(ne, _)| *ne == n);
+                    es_for_t.map(|(_, e)| e.to_string()).collect()
+                });
+                node_all_handle_different_event(network, &all_nodes, &all_raisons).await
+            }
+            CfgModif::RestartEventsAll(es) | CfgModif::RestartUpgradeEventsAll(es) => {
+                let all_nodes = network.all_active_nodes_name_vec();
+                let all_raisons = network.all_active_nodes_events(|t, _| {
+                    let es_for_t = es.iter().filter(|(te, _)| *te == t);
+                    es_for_t.map(|(_, e)| e.to_string()).collect()
+                });
+                network.close_loops_and_drop_named(&all_nodes).await;
+                if matches!(modif, CfgModif::RestartUpgradeEventsAll(_)) {
+                    network.upgrade_closed_nodes().await;
+                }
+                network.re_spawn_nodes_named(&all_nodes).await;
+                network.send_startup_requests_named(&all_nodes).await;
+                if !es.is_empty() {
+                    node_all_handle_different_event(network, &all_nodes, &all_raisons).await;
+                }
+            }
+            CfgModif::Disconnect(v) => network.disconnect_nodes_named(&[v.to_string()]).await,
+            CfgModif::Reconnect(v) => {
+                network.re_connect_nodes_named(&[v.to_string()]).await;
+                let mut event_tx = network.get_local_event_tx(v).await.unwrap();
+                event_tx
+                    .send(
+                        LocalEvent::ReconnectionComplete,
+                        "reconnection complete test",
+                    )
+                    .await
+                    .unwrap();
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_raft_1_node() {
-    proof_of_work(complete_network_config_with_n_compute_raft(10210, 1)).await;
-}
+                let event = Some((
+                    v.to_string(),
+                    vec!["Sent startup requests on reconnection".to_string()],
+                ))
+                .into_iter()
+                .collect();
+                node_all_handle_different_event(network, &[v.to_string()], &event).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_raft_2_nodes() {
-    proof_of_work(complete_network_config_with_n_compute_raft(10220, 2)).await;
-}
+                // Process miner's request at compute node
+                if let Some(miner) = network.miner(v) {
+                    let compute_addr = miner.lock().await.compute_address();
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_raft_3_nodes() {
-    proof_of_work(complete_network_config_with_n_compute_raft(10230, 3)).await;
+                    let compute_nodes = network.all_active_nodes()[&NodeType::Compute].clone();
+                    for c in compute_nodes {
+                        if network.compute(&c).unwrap().lock().await.local_address() == compute_addr
+                        {
+                            compute_handle_event(
+                                network,
+                                &c,
+                                &["Received partition request successfully"],
+                            )
+                            .await;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_raft_majority_3_nodes() {
-    proof_of_work_common(
-        complete_network_config_with_n_compute_raft(10240, 3),
-        CfgNum::Majority,
-    )
-    .await;
+async fn create_first_block_no_raft() {
+    create_first_block(complete_network_config(10000)).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_multi_no_raft() {
-    let mut cfg = complete_network_config_with_n_compute_miner(10250, false, 1, 3);
-    cfg.compute_partition_full_size = 2;
-    cfg.compute_minimum_miner_pool_len = 3;
-    proof_of_work(cfg).await;
+async fn create_first_block_raft_1_node() {
+    create_first_block(complete_network_config_with_n_compute_raft(10010, 1)).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_multi_raft_1_node() {
-    let mut cfg = complete_network_config_with_n_compute_miner(10260, true, 1, 3);
-    cfg.compute_partition_full_size = 2;
-    cfg.compute_minimum_miner_pool_len = 3;
-    proof_of_work(cfg).await;
+async fn create_first_block_raft_2_nodes() {
+    create_first_block(complete_network_config_with_n_compute_raft(10020, 2)).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn proof_of_work_multi_raft_2_nodes() {
-    let mut cfg = complete_network_config_with_n_compute_miner(10270, true, 2, 6);
-    cfg.compute_partition_full_size = 2;
-    cfg.compute_minimum_miner_pool_len = 3;
-    proof_of_work(cfg).await;
+async fn create_first_block_raft_3_nodes() {
+    create_first_block(complete_network_config_with_n_compute_raft(10030, 3)).await;
 }
 
-async fn proof_of_work(network_config: NetworkConfig) {
-    proof_of_work_common(network_config, CfgNum::All).await;
+#[tokio::test(flavor = "current_thread")]
+async fn create_first_block_raft_15_nodes() {
+    create_first_block(complete_network_config_with_n_compute_raft(10040, 15)).await;
 }
 
-async fn proof_of_work_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+async fn create_first_block(network_config: NetworkConfig) {
     test_step_start();
 
     //
@@ -1141,785 +1183,1913 @@ async fn proof_of_work_common(network_config: NetworkConfig, cfg_num: CfgNum) {
     //
     let mut network = Network::create_from_config(&network_config).await;
     let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let expected_utxo = to_utxo_set(&network.collect_initial_uxto_txs());
 
+    //
+    // Act
+    //
     create_first_block_act(&mut network).await;
-    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
 
     //
-    // Act
+    // Assert
     //
-    let block_before = compute_all_mined_block_num(&mut network, compute_nodes).await;
+    let utxo_set_after = compute_all_committed_utxo_set(&mut network, compute_nodes).await;
+    assert_eq!(utxo_set_after, node_all(compute_nodes, expected_utxo));
 
-    proof_of_work_act(&mut network, CfgPow::First, cfg_num, false, None).await;
-    proof_of_work_send_more_act(&mut network, cfg_num).await;
+    test_step_complete(network).await;
+}
 
-    let block_after = compute_all_mined_block_num(&mut network, compute_nodes).await;
+async fn create_first_block_act(network: &mut Network) {
+    let config = network.config().clone();
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+    let first_request_size = config.compute_minimum_miner_pool_len;
+
+    info!("Test Step Connect nodes");
+    for compute in compute_nodes {
+        let miners = &config.compute_to_miner_mapping[compute];
+        for (idx, miner) in miners.iter().enumerate() {
+            node_send_startup_requests(network, miner).await;
+            let evt = if idx == first_request_size - 1 {
+                "Received first full partition request"
+            } else {
+                "Received partition request successfully"
+            };
+            compute_handle_event(network, compute, &[evt]).await;
+        }
+    }
+
+    info!("Test Step Create first Block");
+    node_all_handle_event(network, compute_nodes, &["First Block committed"]).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_first_block_after_grace_period() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let grace = Duration::from_millis(300);
+    let mut network_config = complete_network_config(11215);
+    network_config.compute_first_block_grace_ms = Some(grace.as_millis() as usize);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute = "compute1";
+    let miner = "miner1";
+
+    //
+    // Act
+    //
+    node_send_startup_requests(&mut network, miner).await;
+    compute_handle_event(
+        &mut network,
+        compute,
+        &["Received first full partition request"],
+    )
+    .await;
+    let pool_reached_at = Instant::now();
+    let block_num_in_grace = compute_committed_current_block_num(&mut network, compute).await;
+
+    compute_handle_event(&mut network, compute, &["First Block committed"]).await;
+    let committed_after = pool_reached_at.elapsed();
 
     //
     // Assert
     //
-    assert_eq!(block_before, node_all(compute_nodes, None));
-    assert_eq!(block_after, node_all(compute_nodes, Some(1)));
+    assert_eq!(block_num_in_grace, None);
+    assert!(committed_after >= grace, "{committed_after:?}");
+    assert_eq!(
+        compute_committed_current_block_num(&mut network, compute).await,
+        Some(0)
+    );
 
     test_step_complete(network).await;
 }
 
-async fn proof_of_work_act(
-    network: &mut Network,
-    cfg_pow: CfgPow,
-    cfg_num: CfgNum,
-    handle_aggregation_tx: bool,
-    prev_mining_reward: Option<TokenAmount>,
-) {
-    proof_of_work_participation_act(network, cfg_num, cfg_pow).await;
-    proof_of_work_block_act(network, cfg_num, handle_aggregation_tx, prev_mining_reward).await;
+#[tokio::test(flavor = "current_thread")]
+async fn send_first_block_to_storage_no_raft() {
+    send_first_block_to_storage(complete_network_config(10800)).await;
 }
 
-async fn proof_of_work_participation_act(network: &mut Network, cfg_num: CfgNum, cfg_pow: CfgPow) {
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-    let c_mined = &node_select(compute_nodes, cfg_num);
-    let active_compute_to_miner_mapping = network.active_compute_to_miner_mapping().clone();
-    const POWS_COMPLETE: [&str; 2] = ["Partition PoW complete", "Block PoW complete"];
+#[tokio::test(flavor = "current_thread")]
+async fn send_first_block_to_storage_raft_1_node() {
+    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10810, 1)).await;
+}
 
-    info!("Test Step Miner block Proof of Work: partition-> rand num -> num pow -> pre-block -> block pow");
-    if cfg_pow == CfgPow::First {
-        for compute in c_mined {
-            let c_miners = &active_compute_to_miner_mapping.get(compute).unwrap();
-            compute_flood_rand_and_block_to_partition(network, compute).await;
-            miner_all_handle_event(network, c_miners, "Received random number successfully").await;
-            for miner in c_miners.iter() {
-                miner_handle_event(network, miner, "Partition PoW complete").await;
-                miner_process_found_partition_pow(network, miner).await;
-                compute_handle_event(network, compute, &["Partition PoW received successfully"])
-                    .await;
-            }
-        }
+#[tokio::test(flavor = "current_thread")]
+async fn send_first_block_to_storage_raft_2_nodes() {
+    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10820, 2)).await;
+}
 
-        node_all_handle_event(network, compute_nodes, &["Winning PoW intake open"]).await;
-    }
+#[tokio::test(flavor = "current_thread")]
+async fn send_first_block_to_storage_raft_3_nodes() {
+    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10830, 3)).await;
 }
 
-async fn proof_of_work_block_act(
-    network: &mut Network,
-    cfg_num: CfgNum,
-    handle_aggregation_tx: bool,
-    prev_mining_reward: Option<TokenAmount>,
-) {
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-    let c_mined = &node_select(compute_nodes, cfg_num);
-    let active_compute_to_miner_mapping = network.active_compute_to_miner_mapping().clone();
+#[tokio::test(flavor = "current_thread")]
+async fn send_first_block_to_storage_raft_majority_3_nodes() {
+    send_first_block_to_storage_common(
+        complete_network_config_with_n_compute_raft(10840, 3),
+        CfgNum::Majority,
+    )
+    .await;
+}
 
-    for compute in c_mined {
-        let c_miners = &active_compute_to_miner_mapping.get(compute).unwrap();
-        let in_miners = compute_get_filtered_participants(network, compute, c_miners).await;
-        let in_miners = &in_miners;
-        compute_flood_rand_and_block_to_partition(network, compute).await;
-        // TODO: Better to validate all miners => needs to have the miner with barrier as well.
+#[tokio::test(flavor = "current_thread")]
+async fn send_first_block_to_storage_raft_15_nodes() {
+    send_first_block_to_storage(complete_network_config_with_n_compute_raft(10850, 15)).await;
+}
 
-        let all_evts = block_and_partition_evt_in_miner_pow(c_miners, in_miners);
-        node_all_handle_different_event(network, c_miners, &all_evts).await;
-        compute_flood_transactions_to_partition(network, compute).await;
-        miner_all_handle_event(network, in_miners, "Block is valid").await;
+async fn send_first_block_to_storage(network_config: NetworkConfig) {
+    send_first_block_to_storage_common(network_config, CfgNum::All).await;
+}
 
-        for miner in c_miners.iter() {
-            miner_process_found_partition_pow(network, miner).await;
-            // `handle_aggregation_tx` indicates that an aggregation tx has been sent by the miner
-            // Compute node needs to handle this special case
-            if handle_aggregation_tx {
-                // Supplied as an array because these Tx event and Partition event can happen in any order
-                let results = &[
-                    "Transactions added to tx pool",
-                    "Transactions committed",
-                    "Partition PoW received successfully",
-                ];
-                compute_handle_event(network, compute, results).await;
-                compute_handle_event(network, compute, results).await;
-                compute_handle_event(network, compute, results).await;
-            } else {
-                // Compute node needs to process the UTXO request sent by the miner
-                if let Some(addr) = miner_has_aggregation_tx_active(network, miner).await {
-                    compute_handle_event(network, compute, &["Received UTXO fetch request"]).await;
+async fn send_first_block_to_storage_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+    test_step_start();
 
-                    // Send the UTXO set to the miner
-                    {
-                        let compute_node = network.compute(compute).unwrap();
-                        compute_node
-                            .lock()
-                            .await
-                            .send_fetched_utxo_set()
-                            .await
-                            .unwrap();
-                    }
+    //
+    // Arrange
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let storage_nodes = &network_config.nodes[&NodeType::Storage];
+    let initial_utxo_txs = network.collect_initial_uxto_txs();
+    let (expected0, block_info0) = complete_first_block(&initial_utxo_txs).await;
 
-                    // Miner handles the UTXO set and updates its balance
-                    miner_handle_event(network, miner, "Received UTXO set").await;
+    create_first_block_act(&mut network).await;
+    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
 
-                    {
-                        let miner_node = network.miner(miner).unwrap();
-                        miner_node.lock().await.update_running_total().await;
+    //
+    // Act
+    //
+    send_block_to_storage_act(&mut network, cfg_num).await;
 
-                        let running_total = miner_node
-                            .lock()
-                            .await
-                            .get_wallet_db()
-                            .get_fund_store()
-                            .running_total()
-                            .clone();
+    //
+    // Assert
+    //
+    let actual0 = storage_all_get_last_stored_info(&mut network, storage_nodes).await;
+    assert_eq!(
+        actual0[0],
+        (
+            Some(expected0.1),
+            Some((expected0.0, 0 /*b_num*/, 1 /*single mining tx*/,))
+        )
+    );
+    assert_eq!(equal_first(&actual0), node_all(storage_nodes, true));
 
-                        let utxo_bal = compute_get_utxo_balance_for_addresses(
-                            network,
-                            compute,
-                            vec![addr.clone()],
-                        )
-                        .await
-                        .get_asset_values()
-                        .tokens;
+    let actual0_db_count =
+        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
+    assert_eq!(
+        actual0_db_count,
+        node_all(storage_nodes, 1 + initial_utxo_txs.len() + 1)
+    );
 
-                        if let Some(amount) = prev_mining_reward {
-                            // Negate the mining reward from the running total as this check
-                            // happens in the next immediate block after the aggregation tx is processed.
-                            let actual_running_total = running_total.tokens - amount;
-                            assert_eq!(utxo_bal, actual_running_total);
-                        }
-                    }
-                }
-                compute_handle_event(network, compute, &["Partition PoW received successfully"])
-                    .await;
-            }
-        }
-        for miner in in_miners {
-            miner_process_found_block_pow(network, miner).await;
-            compute_handle_event(network, compute, &["Received PoW successfully"]).await;
-        }
-    }
-    node_all_handle_event(network, compute_nodes, &["Pipeline halted"]).await;
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn add_transactions_no_raft() {
+    add_transactions(complete_network_config(10600)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn add_transactions_raft_1_node() {
+    add_transactions(complete_network_config_with_n_compute_raft(10610, 1)).await;
 }
 
-async fn proof_of_work_send_more_act(network: &mut Network, cfg_num: CfgNum) {
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-    let c_mined = &node_select(compute_nodes, cfg_num);
-    let active_compute_to_miner_mapping = network.active_compute_to_miner_mapping().clone();
+#[tokio::test(flavor = "current_thread")]
+async fn add_transactions_restart_tx_raft_1_node() {
+    let tag = "After add local Transactions";
+    let modify_cfg = vec![
+        (tag, CfgModif::Drop("compute1")),
+        (tag, CfgModif::Respawn("compute1")),
+        (
+            tag,
+            CfgModif::HandleEvents(&[("compute1", "Snapshot applied")]),
+        ),
+    ];
+
+    let network_config = complete_network_config_with_n_compute_raft(10615, 1);
+    add_transactions_common(network_config, &modify_cfg).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn add_transactions_raft_2_nodes() {
+    add_transactions(complete_network_config_with_n_compute_raft(10620, 2)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn add_transactions_raft_3_nodes() {
+    add_transactions(complete_network_config_with_n_compute_raft(10630, 3)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn add_transactions_raft_15_nodes() {
+    add_transactions(complete_network_config_with_n_compute_raft(10640, 15)).await;
+}
+
+async fn add_transactions(network_config: NetworkConfig) {
+    add_transactions_common(network_config, &[]).await;
+}
+
+async fn add_transactions_common(network_config: NetworkConfig, modify_cfg: &[(&str, CfgModif)]) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let transactions = valid_transactions(true);
+
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act
+    //
+    add_transactions_act_with(&mut network, &transactions, Cfg::IgnoreWaitTxComplete).await;
+    modify_network(&mut network, "After add local Transactions", modify_cfg).await;
+    add_transactions_act_with(&mut network, &Default::default(), Cfg::All).await;
+
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], transactions);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
+}
+
+async fn add_transactions_act(network: &mut Network, txs: &BTreeMap<String, Transaction>) {
+    add_transactions_act_with(network, txs, Cfg::All).await;
+}
+
+async fn add_transactions_act_with(
+    network: &mut Network,
+    txs: &BTreeMap<String, Transaction>,
+    cfg: Cfg,
+) {
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+
+    for tx in txs.values() {
+        info!("Test Step Add Transactions");
+        user_send_transaction_to_compute(network, "user1", "compute1", tx).await;
+    }
+    for _tx in txs.values() {
+        compute_handle_event(network, "compute1", &["Transactions added to tx pool"]).await;
+    }
+
+    if cfg != Cfg::IgnoreWaitTxComplete {
+        node_all_handle_event(network, compute_nodes, &["Transactions committed"]).await;
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_no_raft() {
+    create_block(complete_network_config(10100)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_raft_1_node() {
+    create_block(complete_network_config_with_n_compute_raft(10110, 1)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_raft_2_nodes() {
+    create_block(complete_network_config_with_n_compute_raft(10120, 2)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_raft_3_nodes() {
+    create_block(complete_network_config_with_n_compute_raft(10130, 3)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_raft_majority_3_nodes() {
+    create_block_common(
+        complete_network_config_with_n_compute_raft(10140, 3),
+        CfgNum::Majority,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_raft_15_nodes() {
+    create_block(complete_network_config_with_n_compute_raft(10150, 15)).await;
+}
+
+async fn create_block(network_config: NetworkConfig) {
+    create_block_common(network_config, CfgNum::All).await;
+}
+
+async fn create_block_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let transactions = valid_transactions(true);
+    let transactions_h = transactions.keys().cloned().collect::<Vec<_>>();
+    let transactions_utxo = to_utxo_set(&transactions);
+    let (_, block_info0) = complete_first_block(&network.collect_initial_uxto_txs()).await;
+    let block0_mining_tx = complete_block_mining_txs(&block_info0);
+    let block0_mining_utxo = to_utxo_set(&block0_mining_tx);
+
+    let mut left_init_utxo = to_utxo_set(&network.collect_initial_uxto_txs());
+    remove_keys(&mut left_init_utxo, valid_txs_in().keys());
+
+    create_first_block_act(&mut network).await;
+    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    add_transactions_act(&mut network, &transactions).await;
+
+    //
+    // Act
+    //
+    let block_transaction_before =
+        compute_all_current_block_transactions(&mut network, compute_nodes).await;
+
+    create_block_act(&mut network, Cfg::All, cfg_num).await;
+
+    let block_transaction_after =
+        compute_all_current_block_transactions(&mut network, compute_nodes).await;
+    let utxo_set_after = compute_all_committed_utxo_set(&mut network, compute_nodes).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(block_transaction_before, node_all(compute_nodes, None));
+    assert_eq!(
+        block_transaction_after,
+        node_all(compute_nodes, Some(transactions_h))
+    );
+
+    let expected_utxo = merge_txs_3(&left_init_utxo, &transactions_utxo, &block0_mining_utxo);
+    assert_eq!(len_and_map(&utxo_set_after[0]), len_and_map(&expected_utxo));
+    assert_eq!(equal_first(&utxo_set_after), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
+}
+
+async fn create_block_act(network: &mut Network, cfg: Cfg, cfg_num: CfgNum) {
+    create_block_act_with(network, cfg, cfg_num, 0).await
+}
+
+async fn create_block_act_with(network: &mut Network, cfg: Cfg, cfg_num: CfgNum, block_num: u64) {
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+    let (msg_c_nodes, msg_s_nodes) = node_combined_select(
+        &network.config().nodes[&NodeType::Compute],
+        &network.config().nodes[&NodeType::Storage],
+        network.dead_nodes(),
+        cfg_num,
+    );
+
+    info!("Test Step Storage signal new block");
+
+    if cfg == Cfg::IgnoreStorage {
+        let block = BlockStoredInfo {
+            block_num,
+            ..Default::default()
+        };
+        let req = ComputeRequest::SendBlockStored(block);
+        compute_all_inject_next_event(network, &msg_s_nodes, &msg_c_nodes, req).await;
+    } else {
+        storage_all_send_stored_block(network, &msg_s_nodes).await;
+    }
+    compute_all_handle_event(network, &msg_c_nodes, "Received block stored").await;
+
+    info!("Test Step Generate Block");
+    node_all_handle_event(network, compute_nodes, &["Block committed"]).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn create_block_with_seed() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config_with_n_compute_raft(11600, 1);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let compute = &compute_nodes[0];
+    let transactions = valid_transactions(true);
+    let (_, block_info0) = complete_first_block(&network.collect_initial_uxto_txs()).await;
+
+    //
+    // Act
+    //
+    create_first_block_act(&mut network).await;
+    let block0 = compute_current_mining_block(&mut network, compute).await;
+    let seed0 = block0.as_ref().map(|b| b.header.seed_value.as_slice());
+    let seed0 = seed0.and_then(|s| std::str::from_utf8(s).ok());
+
+    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    add_transactions_act(&mut network, &transactions).await;
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+    let block1 = compute_current_mining_block(&mut network, compute).await;
+    let seed1 = block1.as_ref().map(|b| b.header.seed_value.as_slice());
+    let seed1 = seed1.and_then(|s| std::str::from_utf8(s).ok());
+
+    //
+    // Assert
+    //
+    let expected_seed0 = Some("102382207707718734792748219972459444372508601011471438062299221139355828742917-4092482189202844858141461446747443254065713079405017303649880917821984131927979764736076459305831834761744847895656303682167530187457916798745160233343351193");
+    let expected_seed1 = Some("106228469607590785158439105345663906454407776332951582911215086478921447072494-890801478181097874306473746009596725559978631952297198866826970472802729293224119401323816857729365460297789241934487318750450632705046684127753444374170213");
+    assert_eq!((seed0, seed1), (expected_seed0, expected_seed1));
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_no_raft() {
+    proof_of_work(complete_network_config(10200)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_raft_1_node() {
+    proof_of_work(complete_network_config_with_n_compute_raft(10210, 1)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_raft_2_nodes() {
+    proof_of_work(complete_network_config_with_n_compute_raft(10220, 2)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_raft_3_nodes() {
+    proof_of_work(complete_network_config_with_n_compute_raft(10230, 3)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_raft_majority_3_nodes() {
+    proof_of_work_common(
+        complete_network_config_with_n_compute_raft(10240, 3),
+        CfgNum::Majority,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_multi_no_raft() {
+    let mut cfg = complete_network_config_with_n_compute_miner(10250, false, 1, 3);
+    cfg.compute_partition_full_size = 2;
+    cfg.compute_minimum_miner_pool_len = 3;
+    proof_of_work(cfg).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_multi_raft_1_node() {
+    let mut cfg = complete_network_config_with_n_compute_miner(10260, true, 1, 3);
+    cfg.compute_partition_full_size = 2;
+    cfg.compute_minimum_miner_pool_len = 3;
+    proof_of_work(cfg).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_multi_raft_2_nodes() {
+    let mut cfg = complete_network_config_with_n_compute_miner(10270, true, 2, 6);
+    cfg.compute_partition_full_size = 2;
+    cfg.compute_minimum_miner_pool_len = 3;
+    proof_of_work(cfg).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_external_nonce_forwarded() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config(10290);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute = &network_config.nodes[&NodeType::Compute][0];
+    let miner = &network_config.nodes[&NodeType::Miner][0];
+
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+    proof_of_work_participation_act(&mut network, CfgNum::All, CfgPow::First).await;
+    compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+    miner_handle_event(&mut network, miner, "Pre-block received successfully").await;
+    miner_handle_event(&mut network, miner, "Partition PoW complete").await;
+    miner_handle_event(&mut network, miner, "Block PoW complete").await;
+    compute_flood_transactions_to_partition(&mut network, compute).await;
+    miner_handle_event(&mut network, miner, "Block is valid").await;
+
+    //
+    // Act
+    //
+    let invalid_nonce = miner_find_external_block_pow_nonce(&mut network, miner, false).await;
+    let nonce = miner_find_external_block_pow_nonce(&mut network, miner, true).await;
+    let invalid_response = miner_submit_pow(&mut network, miner, invalid_nonce).await;
+    let response = miner_submit_pow(&mut network, miner, nonce.clone()).await;
+    let repeat_response = miner_submit_pow(&mut network, miner, nonce).await;
+    let local_pow_sent = miner_process_found_block_pow(&mut network, miner).await;
+    compute_handle_event(&mut network, compute, &["Received PoW successfully"]).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(invalid_response.reason, ResponseReason::ExternalPowRejected);
+    assert_eq!(response.reason, ResponseReason::ExternalPowForwarded);
+    assert_eq!(repeat_response.reason, ResponseReason::ExternalPowRejected);
+    assert!(!local_pow_sent);
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_of_work_miner_rejoins_after_missed_round() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config_with_n_compute_miner(10295, false, 1, 2);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute = "compute1";
+    let (in_miner, missed_miner) = ("miner1", "miner2");
+    let miners = &[in_miner.to_owned(), missed_miner.to_owned()];
+
+    create_first_block_act(&mut network).await;
+    compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+    miner_all_handle_event(&mut network, miners, "Received random number successfully").await;
+    miner_all_handle_event(&mut network, miners, "Partition PoW complete").await;
+    miner_process_found_partition_pow(&mut network, in_miner).await;
+    compute_handle_event(
+        &mut network,
+        compute,
+        &["Partition PoW received successfully"],
+    )
+    .await;
+    compute_handle_event(&mut network, compute, &["Winning PoW intake open"]).await;
+
+    //
+    // Act
+    //
+    compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+    miner_handle_event(&mut network, in_miner, "Pre-block received successfully").await;
+    miner_handle_event(
+        &mut network,
+        missed_miner,
+        "Partition request resent after missed round",
+    )
+    .await;
+    compute_handle_event(
+        &mut network,
+        compute,
+        &["Received partition request successfully"],
+    )
+    .await;
+    let participants = compute_get_filtered_participants(&mut network, compute, miners).await;
+    let round_after = miner_get_current_round(&mut network, missed_miner).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(participants, vec![in_miner.to_owned()]);
+    assert_eq!(round_after, Some(0));
+
+    test_step_complete(network).await;
+}
+
+async fn proof_of_work(network_config: NetworkConfig) {
+    proof_of_work_common(network_config, CfgNum::All).await;
+}
+
+async fn proof_of_work_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+
+    //
+    // Act
+    //
+    let block_before = compute_all_mined_block_num(&mut network, compute_nodes).await;
+
+    proof_of_work_act(&mut network, CfgPow::First, cfg_num, false, None).await;
+    proof_of_work_send_more_act(&mut network, cfg_num).await;
+
+    let block_after = compute_all_mined_block_num(&mut network, compute_nodes).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(block_before, node_all(compute_nodes, None));
+    assert_eq!(block_after, node_all(compute_nodes, Some(1)));
+
+    test_step_complete(network).await;
+}
+
+async fn proof_of_work_act(
+    network: &mut Network,
+    cfg_pow: CfgPow,
+    cfg_num: CfgNum,
+    handle_aggregation_tx: bool,
+    prev_mining_reward: Option<TokenAmount>,
+) {
+    proof_of_work_participation_act(network, cfg_num, cfg_pow).await;
+    proof_of_work_block_act(network, cfg_num, handle_aggregation_tx, prev_mining_reward).await;
+}
+
+async fn proof_of_work_participation_act(network: &mut Network, cfg_num: CfgNum, cfg_pow: CfgPow) {
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+    let c_mined = &node_select(compute_nodes, cfg_num);
+    let active_compute_to_miner_mapping = network.active_compute_to_miner_mapping().clone();
+    const POWS_COMPLETE: [&str; 2] = ["Partition PoW complete", "Block PoW complete"];
+
+    info!("Test Step Miner block Proof of Work: partition-> rand num -> num pow -> pre-block -> block pow");
+    if cfg_pow == CfgPow::First {
+        for compute in c_mined {
+            let c_miners = &active_compute_to_miner_mapping.get(compute).unwrap();
+            compute_flood_rand_and_block_to_partition(network, compute).await;
+            miner_all_handle_event(network, c_miners, "Received random number successfully").await;
+            for miner in c_miners.iter() {
+                miner_handle_event(network, miner, "Partition PoW complete").await;
+                miner_process_found_partition_pow(network, miner).await;
+                compute_handle_event(network, compute, &["Partition PoW received successfully"])
+                    .await;
+            }
+        }
+
+        node_all_handle_event(network, compute_nodes, &["Winning PoW intake open"]).await;
+    }
+}
+
+async fn proof_of_work_block_act(
+    network: &mut Network,
+    cfg_num: CfgNum,
+    handle_aggregation_tx: bool,
+    prev_mining_reward: Option<TokenAmount>,
+) {
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+    let c_mined = &node_select(compute_nodes, cfg_num);
+    let active_compute_to_miner_mapping = network.active_compute_to_miner_mapping().clone();
+
+    for compute in c_mined {
+        let c_miners = &active_compute_to_miner_mapping.get(compute).unwrap();
+        let in_miners = compute_get_filtered_participants(network, compute, c_miners).await;
+        let in_miners = &in_miners;
+        compute_flood_rand_and_block_to_partition(network, compute).await;
+        // TODO: Better to validate all miners => needs to have the miner with barrier as well.
+
+        let all_evts = block_and_partition_evt_in_miner_pow(c_miners, in_miners);
+        node_all_handle_different_event(network, c_miners, &all_evts).await;
+        compute_flood_transactions_to_partition(network, compute).await;
+        miner_all_handle_event(network, in_miners, "Block is valid").await;
+
+        for miner in c_miners.iter() {
+            miner_process_found_partition_pow(network, miner).await;
+            // `handle_aggregation_tx` indicates that an aggregation tx has been sent by the miner
+            // Compute node needs to handle this special case
+            if handle_aggregation_tx {
+                // Supplied as an array because these Tx event and Partition event can happen in any order
+                let results = &[
+                    "Transactions added to tx pool",
+                    "Transactions committed",
+                    "Partition PoW received successfully",
+                ];
+                compute_handle_event(network, compute, results).await;
+                compute_handle_event(network, compute, results).await;
+                compute_handle_event(network, compute, results).await;
+            } else {
+                // Compute node needs to process the UTXO request sent by the miner
+                if let Some(addr) = miner_has_aggregation_tx_active(network, miner).await {
+                    compute_handle_event(network, compute, &["Received UTXO fetch request"]).await;
+
+                    // Send the UTXO set to the miner
+                    {
+                        let compute_node = network.compute(compute).unwrap();
+                        compute_node
+                            .lock()
+                            .await
+                            .send_fetched_utxo_set()
+                            .await
+                            .unwrap();
+                    }
+
+                    // Miner handles the UTXO set and updates its balance
+                    miner_handle_event(network, miner, "Received UTXO set").await;
+
+                    {
+                        let miner_node = network.miner(miner).unwrap();
+                        miner_node.lock().await.update_running_total().await;
+
+                        let running_total = miner_node
+                            .lock()
+                            .await
+                            .get_wallet_db()
+                            .get_fund_store()
+                            .running_total()
+                            .clone();
+
+                        let utxo_bal = compute_get_utxo_balance_for_addresses(
+                            network,
+                            compute,
+                            vec![addr.clone()],
+                        )
+                        .await
+                        .get_asset_values()
+                        .tokens;
+
+                        if let Some(amount) = prev_mining_reward {
+                            // Negate the mining reward from the running total as this check
+                            // happens in the next immediate block after the aggregation tx is processed.
+                            let actual_running_total = running_total.tokens - amount;
+                            assert_eq!(utxo_bal, actual_running_total);
+                        }
+                    }
+                }
+                compute_handle_event(network, compute, &["Partition PoW received successfully"])
+                    .await;
+            }
+        }
+        for miner in in_miners {
+            miner_process_found_block_pow(network, miner).await;
+            compute_handle_event(network, compute, &["Received PoW successfully"]).await;
+        }
+    }
+    node_all_handle_event(network, compute_nodes, &["Pipeline halted"]).await;
+}
+
+async fn proof_of_work_send_more_act(network: &mut Network, cfg_num: CfgNum) {
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+    let c_mined = &node_select(compute_nodes, cfg_num);
+    let active_compute_to_miner_mapping = network.active_compute_to_miner_mapping().clone();
+
+    info!("Test Step Miner block Proof of Work to late");
+    for compute in c_mined {
+        let c_miners = active_compute_to_miner_mapping.get(compute).unwrap();
+        let in_miners = compute_get_filtered_participants(network, compute, c_miners).await;
+        for miner in &in_miners {
+            miner_process_found_block_pow(network, miner).await;
+            compute_handle_error(network, compute, &["Not block currently mined"]).await;
+        }
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_winner_no_raft() {
+    proof_winner(complete_network_config(10900)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_winner_raft_1_node() {
+    proof_winner(complete_network_config_with_n_compute_raft(10910, 1)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_winner_multi_no_raft() {
+    proof_winner_multi(complete_network_config_with_n_compute_miner(
+        10920, false, 1, 3,
+    ))
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_winner_multi_raft_1_node() {
+    proof_winner_multi(complete_network_config_with_n_compute_miner(
+        10930, true, 1, 3,
+    ))
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proof_winner_multi_raft_2_nodes() {
+    proof_winner_multi(complete_network_config_with_n_compute_miner(
+        10940, true, 2, 6,
+    ))
+    .await;
+}
+
+async fn proof_winner_multi(mut network_config: NetworkConfig) {
+    network_config.compute_partition_full_size = 2;
+    network_config.compute_minimum_miner_pool_len = 3;
+    proof_winner(network_config).await;
+}
+
+async fn proof_winner(network_config: NetworkConfig) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let miner_nodes = &network_config.nodes[&NodeType::Miner];
+    let mining_reward = network.mining_reward();
+
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act
+    // Does not allow miner reuse.
+    //
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+
+    let info_before = node_all_get_wallet_info(&mut network, miner_nodes).await;
+    proof_winner_act(&mut network).await;
+    let info_after = node_all_get_wallet_info(&mut network, miner_nodes).await;
+    let winner_index = info_after.iter().position(|i| !i.0.is_empty());
+    let winner_index = winner_index.unwrap_or_default();
+
+    //
+    // Assert
+    //
+    let expected_before = node_all(miner_nodes, (AssetValues::default(), 1, 0));
+    let mut expected_after = expected_before.clone();
+    expected_after[winner_index] = (AssetValues::new(mining_reward, Default::default()), 2, 1);
+    assert_eq!(
+        info_before
+            .iter()
+            .map(|i| (i.0.clone(), i.1.len(), i.2.len()))
+            .collect::<Vec<_>>(),
+        expected_before,
+        "Info Before: {info_before:?}"
+    );
+    assert_eq!(
+        info_after
+            .iter()
+            .map(|i| (i.0.clone(), i.1.len(), i.2.len()))
+            .collect::<Vec<_>>(),
+        expected_after,
+        "Info After: {info_after:?}"
+    );
+
+    test_step_complete(network).await;
+}
+
+async fn proof_winner_act(network: &mut Network) {
+    let config = network.config().clone();
+    let active_nodes = network.all_active_nodes().clone();
+    let compute_nodes = &active_nodes[&NodeType::Compute];
+    let miner_nodes = &active_nodes[&NodeType::Miner];
+
+    if miner_nodes.len() < compute_nodes.len() {
+        info!("Test Step Miner winner: Ignored/Miner re-use");
+        return;
+    }
+
+    info!("Test Step Miner winner:");
+    for compute in compute_nodes {
+        let c_miners = &config.compute_to_miner_mapping.get(compute).unwrap();
+        let in_miners = compute_get_filtered_participants(network, compute, c_miners).await;
+        let in_miners = &in_miners;
+
+        compute_flood_rand_and_block_to_partition(network, compute).await;
+        let all_evts = block_and_partition_evt_in_miner_pow(c_miners, in_miners);
+        node_all_handle_different_event(network, c_miners, &all_evts).await;
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_block_to_storage_no_raft() {
+    send_block_to_storage(complete_network_config(10300)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_block_to_storage_raft_1_node() {
+    send_block_to_storage(complete_network_config_with_n_compute_raft(10310, 1)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_block_to_storage_raft_2_nodes() {
+    send_block_to_storage(complete_network_config_with_n_compute_raft(10320, 2)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_block_to_storage_raft_3_nodes() {
+    send_block_to_storage(complete_network_config_with_n_compute_raft(10330, 3)).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_block_to_storage_raft_majority_3_nodes() {
+    send_block_to_storage_common(
+        complete_network_config_with_n_compute_raft(10340, 3),
+        CfgNum::Majority,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn send_block_to_storage_raft_15_nodes() {
+    send_block_to_storage(complete_network_config_with_n_compute_raft(10350, 15)).await;
+}
+
+async fn send_block_to_storage(network_config: NetworkConfig) {
+    send_block_to_storage_common(network_config, CfgNum::All).await;
+}
+
+async fn send_block_to_storage_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let storage_nodes = &network_config.nodes[&NodeType::Storage];
+
+    let transactions = valid_transactions(true);
+    let (_, block_info0) = complete_first_block(&network.collect_initial_uxto_txs()).await;
+    let (expected1, block_info1) = complete_block(1, Some("0"), &transactions).await;
+    let (_expected3, wrong_block3) = complete_block(3, Some("0"), &BTreeMap::new()).await;
+    let block1_mining_tx = complete_block_mining_txs(&block_info1);
+
+    create_first_block_act(&mut network).await;
+    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+
+    compute_all_skip_block_gen(&mut network, compute_nodes, &block_info1).await;
+    compute_all_skip_mining(&mut network, compute_nodes, &block_info1).await;
+
+    let initial_db_count =
+        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
+
+    //
+    // Act
+    //
+    storage_inject_send_block_to_storage(&mut network, "compute1", "storage1", &wrong_block3).await;
+    storage_handle_event(&mut network, "storage1", BLOCK_RECEIVED).await;
+
+    send_block_to_storage_act(&mut network, cfg_num).await;
+
+    //
+    // Assert
+    //
+    let actual1 = storage_all_get_last_stored_info(&mut network, storage_nodes).await;
+    assert_eq!(
+        actual1[0],
+        (
+            Some(expected1.1),
+            Some((expected1.0, 1 /*b_num*/, 1 /*mining txs*/,))
+        )
+    );
+    assert_eq!(equal_first(&actual1), node_all(storage_nodes, true));
+
+    let actual0_db_count =
+        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
+    assert_eq!(
+        substract_vec(&actual0_db_count, &initial_db_count),
+        node_all(
+            storage_nodes,
+            1 + transactions.len() + block1_mining_tx.len()
+        )
+    );
+
+    test_step_complete(network).await;
+}
+
+async fn send_block_to_storage_act(network: &mut Network, cfg_num: CfgNum) {
+    let active_nodes = network.all_active_nodes().clone();
+    let storage_nodes = &active_nodes[&NodeType::Storage];
+
+    let compute_no_mining = network
+        .active_compute_to_miner_mapping()
+        .iter()
+        .filter(|(_, miners)| miners.is_empty())
+        .map(|(c, _)| c.clone());
+    let mut dead_nodes = network.dead_nodes().clone();
+    dead_nodes.extend(compute_no_mining);
+
+    let (msg_c_nodes, msg_s_nodes) = node_combined_select(
+        &network.config().nodes[&NodeType::Compute],
+        &network.config().nodes[&NodeType::Storage],
+        &dead_nodes,
+        cfg_num,
+    );
+
+    info!("Test Step Compute Send block to Storage");
+    compute_all_send_block_to_storage(network, &msg_c_nodes).await;
+    storage_all_handle_event(network, &msg_s_nodes, BLOCK_RECEIVED).await;
+    node_all_handle_event(network, storage_nodes, &[BLOCK_STORED]).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_raft_1_node_with_file_backup() {
+    let mut network_config = complete_network_config_with_n_compute_raft(11300, 1);
+    network_config.in_memory_db = false;
+    network_config.backup_block_modulo = Some(2);
+    remove_all_node_dbs(&network_config);
+    main_loops_raft_1_node_common(network_config, vec![3], TokenAmount(17), 20, 1, 1_000, &[]).await
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_raft_2_node() {
+    let network_config = complete_network_config_with_n_compute_raft(11310, 2);
+    main_loops_raft_1_node_common(network_config, vec![2], TokenAmount(17), 20, 1, 1_000, &[]).await
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_raft_3_node() {
+    let network_config = complete_network_config_with_n_compute_raft(11320, 3);
+    main_loops_raft_1_node_common(network_config, vec![2], TokenAmount(17), 20, 1, 1_000, &[]).await
+}
+
+// Slow: Only run when explicitely specified for performance and large tests
+// `RUST_LOG="info,raft=warn" cargo test --lib --release -- --ignored --nocapture main_loops_many_txs_threaded_raft_1_node`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn main_loops_many_txs_threaded_raft_1_node() {
+    let mut network_config = complete_network_config_with_n_compute_raft(11330, 1);
+    network_config.test_duration_divider = 1;
+
+    // 5_000 TxIn in transactions of 3 TxIn.
+    // 10_000 TxIn available so we can create full transaction batch even if previous block did
+    // not contain all transactions already created.
+    main_loops_raft_1_node_common(
+        network_config,
+        vec![5],
+        TokenAmount(1),
+        1_000_000,
+        10_000,
+        5_000 / 3,
+        &[],
+    )
+    .await
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_restart_raft_1_node() {
+    let network_config = complete_network_config_with_n_compute_raft(11340, 1);
+    let stop_nums = vec![1, 2];
+    let amount = TokenAmount(17);
+    let modify = vec![("Before start 1", CfgModif::RestartEventsAll(&[]))];
+    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_restart_raft_3_node() {
+    let network_config = complete_network_config_with_n_compute_raft(11350, 3);
+    let stop_nums = vec![1, 2];
+    let amount = TokenAmount(17);
+    let modify = vec![("Before start 1", CfgModif::RestartEventsAll(&[]))];
+    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_restart_upgrade_raft_1_node() {
+    let network_config = complete_network_config_with_n_compute_raft(11360, 1);
+    let stop_nums = vec![1, 2];
+    let amount = TokenAmount(17);
+    let modify = vec![("Before start 1", CfgModif::RestartUpgradeEventsAll(&[]))];
+    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn main_loops_few_txs_restart_upgrade_raft_3_node() {
+    let network_config = complete_network_config_with_n_compute_raft(11370, 3);
+    let stop_nums = vec![1, 2];
+    let amount = TokenAmount(17);
+    let modify = vec![("Before start 1", CfgModif::RestartUpgradeEventsAll(&[]))];
+    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
+}
+
+async fn main_loops_raft_1_node_common(
+    mut network_config: NetworkConfig,
+    expected_block_nums: Vec<u64>,
+    initial_amount: TokenAmount,
+    seed_count: i32,
+    seed_wallet_count: i32,
+    tx_max_count: usize,
+    modify_cfg: &[(&str, CfgModif)],
+) {
+    test_step_start();
+
+    //
+    // Arrange
+    // initial_amount is split into TokenAmount(1) TxOuts for the next round.
+    //
+    network_config.compute_seed_utxo =
+        make_compute_seed_utxo(&[(seed_count, "000000")], initial_amount);
+    network_config.user_test_auto_gen_setup = UserAutoGenTxSetup {
+        user_initial_transactions: (0..seed_wallet_count)
+            .map(|i| wallet_seed((i, "000000"), &initial_amount))
+            .collect::<Vec<WalletTxSpec>>(),
+        user_setup_tx_chunk_size: Some(5),
+        user_setup_tx_in_per_tx: Some(3),
+        user_setup_tx_max_count: tx_max_count,
+    };
+
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let storage_nodes = &network_config.nodes[&NodeType::Storage];
+
+    //
+    // Act
+    //
+    let (mut actual_stored, mut expected_stored) = (Vec::new(), Vec::new());
+    let (mut actual_compute, mut expected_compute) = (Vec::new(), Vec::new());
+    for (idx, expected_block_num) in expected_block_nums.iter().copied().enumerate() {
+        let tag = format!("Before start {idx}");
+        modify_network(&mut network, &tag, modify_cfg).await;
+
+        for node_name in compute_nodes {
+            node_send_coordinated_shutdown(&mut network, node_name, expected_block_num).await;
+        }
+
+        let handles = network
+            .spawn_main_node_loops(TIMEOUT_TEST_WAIT_DURATION)
+            .await;
+        node_join_all_checked(handles, &"").await.unwrap();
+
+        actual_stored
+            .push(storage_all_get_last_block_stored_num(&mut network, storage_nodes).await);
+        actual_compute
+            .push(compute_all_committed_current_block_num(&mut network, compute_nodes).await);
+        expected_stored.push(node_all(storage_nodes, Some(expected_block_num)));
+        expected_compute.push(node_all(compute_nodes, Some(expected_block_num)));
+    }
+
+    //
+    // Assert
+    //
+    assert_eq!(actual_stored, expected_stored);
+    assert_eq!(actual_compute, expected_compute);
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn miner_payouts_recorded_for_mined_blocks_raft_2_nodes() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config_with_n_compute_raft(11290, 2);
+    network_config.compute_seed_utxo = make_compute_seed_utxo(&[(20, "000000")], TokenAmount(17));
+    network_config.user_test_auto_gen_setup = UserAutoGenTxSetup {
+        user_initial_transactions: vec![wallet_seed((0, "000000"), &TokenAmount(17))],
+        user_setup_tx_chunk_size: Some(5),
+        user_setup_tx_in_per_tx: Some(3),
+        user_setup_tx_max_count: 1_000,
+    };
+
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    let miner_nodes = &network_config.nodes[&NodeType::Miner];
+    let mut miner_addrs = Vec::new();
+    for name in miner_nodes {
+        miner_addrs.push(network.get_address(name).await.unwrap());
+    }
+
+    //
+    // Act
+    //
+    for node_name in compute_nodes {
+        node_send_coordinated_shutdown(&mut network, node_name, 2).await;
+    }
+
+    let handles = network
+        .spawn_main_node_loops(TIMEOUT_TEST_WAIT_DURATION)
+        .await;
+    node_join_all_checked(handles, &"").await.unwrap();
+
+    let payouts = compute_all_miner_payouts(&mut network, compute_nodes).await;
+
+    //
+    // Assert
+    //
+    let block_nums: Vec<Vec<u64>> = payouts
+        .iter()
+        .map(|ps| ps.iter().map(|p| p.block_num).collect())
+        .collect();
+    assert_eq!(block_nums, node_all(compute_nodes, vec![0, 1, 2]));
+    assert_eq!(equal_first(&payouts), node_all(compute_nodes, true));
+    assert!(payouts[0]
+        .iter()
+        .all(|p| miner_addrs.contains(&p.miner_address) && p.reward > TokenAmount(0)));
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn receive_payment_tx_user() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10400);
+    network_config
+        .nodes_mut(NodeType::User)
+        .push("user2".to_string());
+    network_config.compute_seed_utxo = make_compute_seed_utxo(SEED_UTXO, TokenAmount(11));
+    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
+    let mut network = Network::create_from_config(&network_config).await;
+    let user_nodes = &network_config.nodes[&NodeType::User];
+    let amount = TokenAmount(5);
+    let locktime = Some(5);
+
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act/Assert
+    //
+    let before = node_all_get_wallet_info(&mut network, user_nodes).await;
+
+    node_connect_to(&mut network, "user1", "user2").await;
+
+    // Process requested transactions:
+    user_send_address_request(&mut network, "user1", "user2", amount, locktime).await;
+    user_handle_event(&mut network, "user2", "New address ready to be sent").await;
+
+    user_send_address_to_trading_peer(&mut network, "user2").await;
+    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+
+    user_send_next_payment_to_destinations(&mut network, "user1", "compute1").await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions committed"]).await;
+    user_handle_event(&mut network, "user2", "Payment transaction received").await;
+
+    // Ignore donations:
+    user_send_donation_address_to_peer(&mut network, "user2", "user1").await;
+    user_handle_error(&mut network, "user1", "Ignore unexpected transaction").await;
+
+    let after_payment_user1_user2 = node_all_get_wallet_info(&mut network, user_nodes).await;
 
-    info!("Test Step Miner block Proof of Work to late");
-    for compute in c_mined {
-        let c_miners = active_compute_to_miner_mapping.get(compute).unwrap();
-        let in_miners = compute_get_filtered_participants(network, compute, c_miners).await;
-        for miner in &in_miners {
-            miner_process_found_block_pow(network, miner).await;
-            compute_handle_error(network, compute, &["Not block currently mined"]).await;
-        }
+    // Handle trying to spend locked funds:
+    create_block_act_with(&mut network, Cfg::IgnoreStorage, CfgNum::All, 0).await; // Create next block
+    user_send_address_request(&mut network, "user2", "user1", amount, None).await;
+    user_handle_event(&mut network, "user1", "New address ready to be sent").await;
+
+    user_send_address_to_trading_peer(&mut network, "user1").await;
+    // Insufficient funds due to locked funds:
+    user_handle_event_failure(&mut network, "user2", "Insufficient funds for payment").await;
+
+    // Handle trying to spend unlocked funds:
+    for b_num in 1..10 {
+        // Create next block
+        create_block_act_with(&mut network, Cfg::IgnoreStorage, CfgNum::All, b_num).await;
+        // Update locked coinbase
+        users_filter_locked_coinbase(&mut network, &["user1", "user2"], b_num).await;
     }
-}
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_winner_no_raft() {
-    proof_winner(complete_network_config(10900)).await;
-}
+    user_send_address_request(&mut network, "user2", "user1", amount, None).await;
+    user_handle_event(&mut network, "user1", "New address ready to be sent").await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_winner_raft_1_node() {
-    proof_winner(complete_network_config_with_n_compute_raft(10910, 1)).await;
-}
+    user_send_address_to_trading_peer(&mut network, "user1").await;
+    user_handle_event(&mut network, "user2", "Next payment transaction ready").await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_winner_multi_no_raft() {
-    proof_winner_multi(complete_network_config_with_n_compute_miner(
-        10920, false, 1, 3,
-    ))
-    .await;
-}
+    user_send_next_payment_to_destinations(&mut network, "user2", "compute1").await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions committed"]).await;
+    user_handle_event(&mut network, "user1", "Payment transaction received").await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn proof_winner_multi_raft_1_node() {
-    proof_winner_multi(complete_network_config_with_n_compute_miner(
-        10930, true, 1, 3,
-    ))
-    .await;
+    let after_payment_user2_user1 = node_all_get_wallet_info(&mut network, user_nodes).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        before
+            .iter()
+            .map(|(total, _, _)| total.clone())
+            .collect::<Vec<_>>(),
+        vec![AssetValues::token_u64(11), AssetValues::token_u64(0),]
+    );
+    assert_eq!(
+        after_payment_user1_user2
+            .iter()
+            .map(|(total, _, _)| total.clone())
+            .collect::<Vec<_>>(),
+        vec![AssetValues::token_u64(6), AssetValues::token_u64(5)]
+    );
+    assert_eq!(
+        after_payment_user2_user1
+            .iter()
+            .map(|(total, _, _)| total.clone())
+            .collect::<Vec<_>>(),
+        vec![AssetValues::token_u64(11), AssetValues::token_u64(0),]
+    );
+
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn proof_winner_multi_raft_2_nodes() {
-    proof_winner_multi(complete_network_config_with_n_compute_miner(
-        10940, true, 2, 6,
-    ))
-    .await;
-}
+async fn payment_address_from_public_key() {
+    let (public_key, _) = sign::gen_keypair();
+    let public_key_vec: Vec<u8> = public_key.as_ref().to_vec();
+    let pub_key = PublicKey::from_slice(&public_key_vec).unwrap();
+    assert_eq!(pub_key, public_key);
 
-async fn proof_winner_multi(mut network_config: NetworkConfig) {
-    network_config.compute_partition_full_size = 2;
-    network_config.compute_minimum_miner_pool_len = 3;
-    proof_winner(network_config).await;
+    construct_address(&pub_key);
 }
 
-async fn proof_winner(network_config: NetworkConfig) {
+#[tokio::test(flavor = "current_thread")]
+async fn receive_testnet_donation_payment_tx_user() {
     test_step_start();
 
     //
     // Arrange
     //
+    let mut network_config = complete_network_config(10405);
+    network_config
+        .nodes_mut(NodeType::User)
+        .push("user2".to_string());
+    network_config.user_auto_donate = 5;
+    network_config.compute_seed_utxo = make_compute_seed_utxo(SEED_UTXO, TokenAmount(11));
+    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
     let mut network = Network::create_from_config(&network_config).await;
-    let miner_nodes = &network_config.nodes[&NodeType::Miner];
-    let mining_reward = network.mining_reward();
+    let user_nodes = &network_config.nodes[&NodeType::User];
 
     create_first_block_act(&mut network).await;
 
     //
-    // Act
-    // Does not allow miner reuse.
+    // Act/Assert
     //
-    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
-    send_block_to_storage_act(&mut network, CfgNum::All).await;
-    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+    let before = node_all_get_wallet_info(&mut network, user_nodes).await;
 
-    let info_before = node_all_get_wallet_info(&mut network, miner_nodes).await;
-    proof_winner_act(&mut network).await;
-    let info_after = node_all_get_wallet_info(&mut network, miner_nodes).await;
-    let winner_index = info_after.iter().position(|i| !i.0.is_empty());
-    let winner_index = winner_index.unwrap_or_default();
+    node_connect_to(&mut network, "user1", "user2").await;
+
+    user_send_donation_address_to_peer(&mut network, "user2", "user1").await;
+    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+
+    user_send_next_payment_to_destinations(&mut network, "user1", "compute1").await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    user_handle_event(&mut network, "user2", "Payment transaction received").await;
+
+    let after = node_all_get_wallet_info(&mut network, user_nodes).await;
 
     //
     // Assert
     //
-    let expected_before = node_all(miner_nodes, (AssetValues::default(), 1, 0));
-    let mut expected_after = expected_before.clone();
-    expected_after[winner_index] = (AssetValues::new(mining_reward, Default::default()), 2, 1);
     assert_eq!(
-        info_before
+        before
             .iter()
-            .map(|i| (i.0.clone(), i.1.len(), i.2.len()))
+            .map(|(total, _, _)| total.clone())
             .collect::<Vec<_>>(),
-        expected_before,
-        "Info Before: {info_before:?}"
+        vec![AssetValues::token_u64(11), AssetValues::token_u64(0)]
     );
     assert_eq!(
-        info_after
+        after
             .iter()
-            .map(|i| (i.0.clone(), i.1.len(), i.2.len()))
+            .map(|(total, _, _)| total.clone())
             .collect::<Vec<_>>(),
-        expected_after,
-        "Info After: {info_after:?}"
+        vec![AssetValues::token_u64(6), AssetValues::token_u64(5)]
     );
 
     test_step_complete(network).await;
 }
 
-async fn proof_winner_act(network: &mut Network) {
-    let config = network.config().clone();
-    let active_nodes = network.all_active_nodes().clone();
-    let compute_nodes = &active_nodes[&NodeType::Compute];
-    let miner_nodes = &active_nodes[&NodeType::Miner];
+#[tokio::test(flavor = "current_thread")]
+async fn ignore_testnet_donation_over_limits_user() {
+    test_step_start();
 
-    if miner_nodes.len() < compute_nodes.len() {
-        info!("Test Step Miner winner: Ignored/Miner re-use");
-        return;
-    }
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11760);
+    network_config
+        .nodes_mut(NodeType::User)
+        .extend(vec!["user2".to_string(), "user3".to_string()]);
+    network_config.user_auto_donate = 5;
+    network_config.user_auto_donate_max_per_peer = Some(5);
+    network_config.compute_seed_utxo = make_compute_seed_utxo(SEED_UTXO, TokenAmount(11));
+    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
+    let mut network = Network::create_from_config(&network_config).await;
 
-    info!("Test Step Miner winner:");
-    for compute in compute_nodes {
-        let c_miners = &config.compute_to_miner_mapping.get(compute).unwrap();
-        let in_miners = compute_get_filtered_participants(network, compute, c_miners).await;
-        let in_miners = &in_miners;
+    create_first_block_act(&mut network).await;
+    node_connect_to(&mut network, "user1", "user2").await;
+    node_connect_to(&mut network, "user1", "user3").await;
 
-        compute_flood_rand_and_block_to_partition(network, compute).await;
-        let all_evts = block_and_partition_evt_in_miner_pow(c_miners, in_miners);
-        node_all_handle_different_event(network, c_miners, &all_evts).await;
+    //
+    // Act/Assert
+    //
+    user_send_donation_address_to_peer(&mut network, "user2", "user1").await;
+    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+
+    for _ in 0..3 {
+        user_send_donation_address_to_peer(&mut network, "user2", "user1").await;
+        user_handle_error(&mut network, "user1", "Ignore donation over peer limit").await;
     }
-}
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_block_to_storage_no_raft() {
-    send_block_to_storage(complete_network_config(10300)).await;
-}
+    // Same IP address as user2
+    user_send_donation_address_to_peer(&mut network, "user3", "user1").await;
+    user_handle_error(&mut network, "user1", "Ignore donation over peer limit").await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_block_to_storage_raft_1_node() {
-    send_block_to_storage(complete_network_config_with_n_compute_raft(10310, 1)).await;
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn send_block_to_storage_raft_2_nodes() {
-    send_block_to_storage(complete_network_config_with_n_compute_raft(10320, 2)).await;
-}
+async fn reject_payment_txs() {
+    test_step_start();
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_block_to_storage_raft_3_nodes() {
-    send_block_to_storage(complete_network_config_with_n_compute_raft(10330, 3)).await;
-}
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10410);
+    network_config
+        .nodes_mut(NodeType::User)
+        .push("user2".to_string());
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_block_to_storage_raft_majority_3_nodes() {
-    send_block_to_storage_common(
-        complete_network_config_with_n_compute_raft(10340, 3),
-        CfgNum::Majority,
-    )
-    .await;
-}
+    let valid_txs = valid_transactions(true);
+    let invalid_txs = vec![
+        // New keys not matching utxo_set
+        valid_transactions_with(false, DEFAULT_SEED_AMOUNT, false),
+        // Too much output amount for given inputs
+        valid_transactions_with(true, DEFAULT_SEED_AMOUNT + TokenAmount(1), false),
+        // Too little output amount for given inputs
+        valid_transactions_with(true, DEFAULT_SEED_AMOUNT - TokenAmount(1), false),
+        // Invalid script
+        {
+            let (k, v) = valid_txs.iter().next().unwrap();
+            let (k, mut v) = (k.clone(), v.clone());
+            v.inputs[0].script_signature.stack.push(StackEntry::Num(0));
+            Some((k, v)).into_iter().collect()
+        },
+    ];
+
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act/Assert
+    //
+    for tx in invalid_txs.iter().flat_map(|txs| txs.values()) {
+        user_send_transaction_to_compute(&mut network, "user2", "compute1", tx).await;
+    }
+    for _tx in invalid_txs.iter().flat_map(|txs| txs.values()) {
+        compute_handle_error(
+            &mut network,
+            "compute1",
+            &["No valid transactions provided"],
+        )
+        .await;
+    }
+    add_transactions_act(&mut network, &valid_txs).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn send_block_to_storage_raft_15_nodes() {
-    send_block_to_storage(complete_network_config_with_n_compute_raft(10350, 15)).await;
-}
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
-async fn send_block_to_storage(network_config: NetworkConfig) {
-    send_block_to_storage_common(network_config, CfgNum::All).await;
+    test_step_complete(network).await;
 }
 
-async fn send_block_to_storage_common(network_config: NetworkConfig, cfg_num: CfgNum) {
+#[tokio::test(flavor = "current_thread")]
+async fn reject_future_version_txs() {
     test_step_start();
 
     //
     // Arrange
     //
+    let network_config = complete_network_config(11110);
     let mut network = Network::create_from_config(&network_config).await;
     let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let storage_nodes = &network_config.nodes[&NodeType::Storage];
 
-    let transactions = valid_transactions(true);
-    let (_, block_info0) = complete_first_block(&network.collect_initial_uxto_txs()).await;
-    let (expected1, block_info1) = complete_block(1, Some("0"), &transactions).await;
-    let (_expected3, wrong_block3) = complete_block(3, Some("0"), &BTreeMap::new()).await;
-    let block1_mining_tx = complete_block_mining_txs(&block_info1);
+    let valid_txs = valid_transactions(true);
+    let future_txs: BTreeMap<String, Transaction> = valid_txs
+        .values()
+        .map(|tx| {
+            let mut tx = tx.clone();
+            tx.version = *SUPPORTED_TX_VERSIONS.end() + 1;
+            (construct_tx_hash(&tx), tx)
+        })
+        .collect();
 
     create_first_block_act(&mut network).await;
-    compute_all_skip_mining(&mut network, compute_nodes, &block_info0).await;
-    send_block_to_storage_act(&mut network, CfgNum::All).await;
-
-    compute_all_skip_block_gen(&mut network, compute_nodes, &block_info1).await;
-    compute_all_skip_mining(&mut network, compute_nodes, &block_info1).await;
-
-    let initial_db_count =
-        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
 
     //
     // Act
     //
-    storage_inject_send_block_to_storage(&mut network, "compute1", "storage1", &wrong_block3).await;
-    storage_handle_event(&mut network, "storage1", BLOCK_RECEIVED).await;
-
-    send_block_to_storage_act(&mut network, cfg_num).await;
+    for tx in future_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    for _tx in future_txs.values() {
+        compute_handle_error(
+            &mut network,
+            "compute1",
+            &["Transaction version is not supported"],
+        )
+        .await;
+    }
+    add_transactions_act(&mut network, &valid_txs).await;
 
     //
     // Assert
     //
-    let actual1 = storage_all_get_last_stored_info(&mut network, storage_nodes).await;
-    assert_eq!(
-        actual1[0],
-        (
-            Some(expected1.1),
-            Some((expected1.0, 1 /*b_num*/, 1 /*mining txs*/,))
-        )
-    );
-    assert_eq!(equal_first(&actual1), node_all(storage_nodes, true));
-
-    let actual0_db_count =
-        storage_all_get_stored_key_values_count(&mut network, storage_nodes).await;
-    assert_eq!(
-        substract_vec(&actual0_db_count, &initial_db_count),
-        node_all(
-            storage_nodes,
-            1 + transactions.len() + block1_mining_tx.len()
-        )
-    );
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
     test_step_complete(network).await;
 }
 
-async fn send_block_to_storage_act(network: &mut Network, cfg_num: CfgNum) {
-    let active_nodes = network.all_active_nodes().clone();
-    let storage_nodes = &active_nodes[&NodeType::Storage];
+#[tokio::test(flavor = "current_thread")]
+async fn reject_txs_by_policy() {
+    test_step_start();
 
-    let compute_no_mining = network
-        .active_compute_to_miner_mapping()
-        .iter()
-        .filter(|(_, miners)| miners.is_empty())
-        .map(|(c, _)| c.clone());
-    let mut dead_nodes = network.dead_nodes().clone();
-    dead_nodes.extend(compute_no_mining);
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config(11240);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
-    let (msg_c_nodes, msg_s_nodes) = node_combined_select(
-        &network.config().nodes[&NodeType::Compute],
-        &network.config().nodes[&NodeType::Storage],
-        &dead_nodes,
-        cfg_num,
-    );
+    let blacklisted = VALID_TXS_OUT[0].to_owned();
+    let (rejected_txs, valid_txs): (BTreeMap<_, _>, BTreeMap<_, _>) =
+        valid_transactions(true).into_iter().partition(|(_, tx)| {
+            tx.outputs
+                .iter()
+                .any(|tx_out| tx_out.script_public_key.as_ref() == Some(&blacklisted))
+        });
 
-    info!("Test Step Compute Send block to Storage");
-    compute_all_send_block_to_storage(network, &msg_c_nodes).await;
-    storage_all_handle_event(network, &msg_s_nodes, BLOCK_RECEIVED).await;
-    node_all_handle_event(network, storage_nodes, &[BLOCK_STORED]).await;
-}
+    for compute in compute_nodes {
+        let mut c = network.compute(compute).unwrap().lock().await;
+        c.set_tx_policy(Box::new(BlacklistPolicy(blacklisted.clone())));
+    }
+    create_first_block_act(&mut network).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_raft_1_node_with_file_backup() {
-    let mut network_config = complete_network_config_with_n_compute_raft(11300, 1);
-    network_config.in_memory_db = false;
-    network_config.backup_block_modulo = Some(2);
-    remove_all_node_dbs(&network_config);
-    main_loops_raft_1_node_common(network_config, vec![3], TokenAmount(17), 20, 1, 1_000, &[]).await
-}
+    //
+    // Act/Assert
+    //
+    for tx in rejected_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    for _tx in rejected_txs.values() {
+        compute_handle_error(
+            &mut network,
+            "compute1",
+            &["Transaction rejected by node policy"],
+        )
+        .await;
+    }
+    add_transactions_act(&mut network, &valid_txs).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_raft_2_node() {
-    let network_config = complete_network_config_with_n_compute_raft(11310, 2);
-    main_loops_raft_1_node_common(network_config, vec![2], TokenAmount(17), 20, 1, 1_000, &[]).await
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(rejected_txs.len(), 1);
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_raft_3_node() {
-    let network_config = complete_network_config_with_n_compute_raft(11320, 3);
-    main_loops_raft_1_node_common(network_config, vec![2], TokenAmount(17), 20, 1, 1_000, &[]).await
-}
+async fn accept_txs_at_min_fee() {
+    test_step_start();
 
-// Slow: Only run when explicitely specified for performance and large tests
-// `RUST_LOG="info,raft=warn" cargo test --lib --release -- --ignored --nocapture main_loops_many_txs_threaded_raft_1_node`
-#[tokio::test(flavor = "multi_thread")]
-#[ignore]
-async fn main_loops_many_txs_threaded_raft_1_node() {
-    let mut network_config = complete_network_config_with_n_compute_raft(11330, 1);
-    network_config.test_duration_divider = 1;
+    //
+    // Arrange
+    //
+    let network_config = complete_network_config(11250);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
-    // 5_000 TxIn in transactions of 3 TxIn.
-    // 10_000 TxIn available so we can create full transaction batch even if previous block did
-    // not contain all transactions already created.
-    main_loops_raft_1_node_common(
-        network_config,
-        vec![5],
-        TokenAmount(1),
-        1_000_000,
-        10_000,
-        5_000 / 3,
-        &[],
-    )
-    .await
-}
+    // Valid transactions spend all their inputs: no fee
+    let valid_txs = valid_transactions(true);
+    create_first_block_act(&mut network).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_restart_raft_1_node() {
-    let network_config = complete_network_config_with_n_compute_raft(11340, 1);
-    let stop_nums = vec![1, 2];
-    let amount = TokenAmount(17);
-    let modify = vec![("Before start 1", CfgModif::RestartEventsAll(&[]))];
-    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
-}
+    //
+    // Act
+    //
+    add_transactions_act(&mut network, &valid_txs).await;
 
-#[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_restart_raft_3_node() {
-    let network_config = complete_network_config_with_n_compute_raft(11350, 3);
-    let stop_nums = vec![1, 2];
-    let amount = TokenAmount(17);
-    let modify = vec![("Before start 1", CfgModif::RestartEventsAll(&[]))];
-    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
-}
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
-#[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_restart_upgrade_raft_1_node() {
-    let network_config = complete_network_config_with_n_compute_raft(11360, 1);
-    let stop_nums = vec![1, 2];
-    let amount = TokenAmount(17);
-    let modify = vec![("Before start 1", CfgModif::RestartUpgradeEventsAll(&[]))];
-    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn main_loops_few_txs_restart_upgrade_raft_3_node() {
-    let network_config = complete_network_config_with_n_compute_raft(11370, 3);
-    let stop_nums = vec![1, 2];
-    let amount = TokenAmount(17);
-    let modify = vec![("Before start 1", CfgModif::RestartUpgradeEventsAll(&[]))];
-    main_loops_raft_1_node_common(network_config, stop_nums, amount, 20, 1, 1_000, &modify).await
-}
-
-async fn main_loops_raft_1_node_common(
-    mut network_config: NetworkConfig,
-    expected_block_nums: Vec<u64>,
-    initial_amount: TokenAmount,
-    seed_count: i32,
-    seed_wallet_count: i32,
-    tx_max_count: usize,
-    modify_cfg: &[(&str, CfgModif)],
-) {
+async fn accept_txs_at_non_zero_min_fee() {
     test_step_start();
 
     //
     // Arrange
-    // initial_amount is split into TokenAmount(1) TxOuts for the next round.
     //
-    network_config.compute_seed_utxo =
-        make_compute_seed_utxo(&[(seed_count, "000000")], initial_amount);
-    network_config.user_test_auto_gen_setup = UserAutoGenTxSetup {
-        user_initial_transactions: (0..seed_wallet_count)
-            .map(|i| wallet_seed((i, "000000"), &initial_amount))
-            .collect::<Vec<WalletTxSpec>>(),
-        user_setup_tx_chunk_size: Some(5),
-        user_setup_tx_in_per_tx: Some(3),
-        user_setup_tx_max_count: tx_max_count,
-    };
-
+    let mut network_config = complete_network_config(11305);
+    network_config.compute_min_tx_fee = TokenAmount(1);
     let mut network = Network::create_from_config(&network_config).await;
     let compute_nodes = &network_config.nodes[&NodeType::Compute];
-    let storage_nodes = &network_config.nodes[&NodeType::Storage];
+
+    // Each output is 1 token short of its input: fee of 1 token per input
+    let amount = TokenAmount(DEFAULT_SEED_AMOUNT.0 - 1);
+    let valid_txs = valid_transactions_with(true, amount, false);
+    create_first_block_act(&mut network).await;
 
     //
     // Act
     //
-    let (mut actual_stored, mut expected_stored) = (Vec::new(), Vec::new());
-    let (mut actual_compute, mut expected_compute) = (Vec::new(), Vec::new());
-    for (idx, expected_block_num) in expected_block_nums.iter().copied().enumerate() {
-        let tag = format!("Before start {idx}");
-        modify_network(&mut network, &tag, modify_cfg).await;
-
-        for node_name in compute_nodes {
-            node_send_coordinated_shutdown(&mut network, node_name, expected_block_num).await;
-        }
-
-        let handles = network
-            .spawn_main_node_loops(TIMEOUT_TEST_WAIT_DURATION)
-            .await;
-        node_join_all_checked(handles, &"").await.unwrap();
-
-        actual_stored
-            .push(storage_all_get_last_block_stored_num(&mut network, storage_nodes).await);
-        actual_compute
-            .push(compute_all_committed_current_block_num(&mut network, compute_nodes).await);
-        expected_stored.push(node_all(storage_nodes, Some(expected_block_num)));
-        expected_compute.push(node_all(compute_nodes, Some(expected_block_num)));
-    }
+    add_transactions_act(&mut network, &valid_txs).await;
 
     //
     // Assert
     //
-    assert_eq!(actual_stored, expected_stored);
-    assert_eq!(actual_compute, expected_compute);
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
     test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn receive_payment_tx_user() {
+async fn reject_txs_below_min_fee() {
     test_step_start();
 
     //
     // Arrange
     //
-    let mut network_config = complete_network_config(10400);
-    network_config
-        .nodes_mut(NodeType::User)
-        .push("user2".to_string());
-    network_config.compute_seed_utxo = make_compute_seed_utxo(SEED_UTXO, TokenAmount(11));
-    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
+    let mut network_config = complete_network_config(11260);
+    network_config.compute_min_tx_fee = TokenAmount(1);
     let mut network = Network::create_from_config(&network_config).await;
-    let user_nodes = &network_config.nodes[&NodeType::User];
-    let amount = TokenAmount(5);
-    let locktime = Some(5);
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
+    let valid_txs = valid_transactions(true);
     create_first_block_act(&mut network).await;
 
     //
-    // Act/Assert
+    // Act
     //
-    let before = node_all_get_wallet_info(&mut network, user_nodes).await;
-
-    node_connect_to(&mut network, "user1", "user2").await;
+    for tx in valid_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    for _tx in valid_txs.values() {
+        compute_handle_error(&mut network, "compute1", &["Fee too low"]).await;
+    }
 
-    // Process requested transactions:
-    user_send_address_request(&mut network, "user1", "user2", amount, locktime).await;
-    user_handle_event(&mut network, "user2", "New address ready to be sent").await;
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], Default::default());
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
-    user_send_address_to_trading_peer(&mut network, "user2").await;
-    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+    test_step_complete(network).await;
+}
 
-    user_send_next_payment_to_destinations(&mut network, "user1", "compute1").await;
-    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
-    compute_handle_event(&mut network, "compute1", &["Transactions committed"]).await;
-    user_handle_event(&mut network, "user2", "Payment transaction received").await;
+#[tokio::test(flavor = "current_thread")]
+async fn accept_consolidating_tx_near_utxo_set_cap() {
+    test_step_start();
 
-    // Ignore donations:
-    user_send_donation_address_to_peer(&mut network, "user2", "user1").await;
-    user_handle_error(&mut network, "user1", "Ignore unexpected transaction").await;
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11265);
+    network_config.compute_utxo_set_size_cap = Some(UtxoSetSizeCap {
+        max_size: 6,
+        net_reducing_margin: 2,
+    });
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
-    let after_payment_user1_user2 = node_all_get_wallet_info(&mut network, user_nodes).await;
+    // Spend 2 outputs into 1
+    let (tx_ins, tx_outs) = (&VALID_TXS_IN[1..3], &VALID_TXS_OUT[0..1]);
+    let txs = common_key_transaction(tx_ins, tx_outs, TokenAmount(6));
+    create_first_block_act(&mut network).await;
 
-    // Handle trying to spend locked funds:
-    create_block_act_with(&mut network, Cfg::IgnoreStorage, CfgNum::All, 0).await; // Create next block
-    user_send_address_request(&mut network, "user2", "user1", amount, None).await;
-    user_handle_event(&mut network, "user1", "New address ready to be sent").await;
+    //
+    // Act
+    //
+    add_transactions_act(&mut network, &txs).await;
 
-    user_send_address_to_trading_peer(&mut network, "user1").await;
-    // Insufficient funds due to locked funds:
-    user_handle_event_failure(&mut network, "user2", "Insufficient funds for payment").await;
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
-    // Handle trying to spend unlocked funds:
-    for b_num in 1..10 {
-        // Create next block
-        create_block_act_with(&mut network, Cfg::IgnoreStorage, CfgNum::All, b_num).await;
-        // Update locked coinbase
-        users_filter_locked_coinbase(&mut network, &["user1", "user2"], b_num).await;
-    }
+    test_step_complete(network).await;
+}
 
-    user_send_address_request(&mut network, "user2", "user1", amount, None).await;
-    user_handle_event(&mut network, "user1", "New address ready to be sent").await;
+#[tokio::test(flavor = "current_thread")]
+async fn reject_net_increasing_tx_near_utxo_set_cap() {
+    test_step_start();
 
-    user_send_address_to_trading_peer(&mut network, "user1").await;
-    user_handle_event(&mut network, "user2", "Next payment transaction ready").await;
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11275);
+    network_config.compute_utxo_set_size_cap = Some(UtxoSetSizeCap {
+        max_size: 6,
+        net_reducing_margin: 2,
+    });
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
-    user_send_next_payment_to_destinations(&mut network, "user2", "compute1").await;
-    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
-    compute_handle_event(&mut network, "compute1", &["Transactions committed"]).await;
-    user_handle_event(&mut network, "user1", "Payment transaction received").await;
+    // Spend 2 outputs into 3
+    let (tx_ins, tx_outs) = (&VALID_TXS_IN[1..3], &VALID_TXS_OUT[0..3]);
+    let txs = common_key_transaction(tx_ins, tx_outs, TokenAmount(2));
+    create_first_block_act(&mut network).await;
 
-    let after_payment_user2_user1 = node_all_get_wallet_info(&mut network, user_nodes).await;
+    //
+    // Act
+    //
+    for tx in txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    compute_handle_error(
+        &mut network,
+        "compute1",
+        &["UTXO set near its size cap: transaction must not grow it"],
+    )
+    .await;
 
     //
     // Assert
     //
-    assert_eq!(
-        before
-            .iter()
-            .map(|(total, _, _)| total.clone())
-            .collect::<Vec<_>>(),
-        vec![AssetValues::token_u64(11), AssetValues::token_u64(0),]
-    );
-    assert_eq!(
-        after_payment_user1_user2
-            .iter()
-            .map(|(total, _, _)| total.clone())
-            .collect::<Vec<_>>(),
-        vec![AssetValues::token_u64(6), AssetValues::token_u64(5)]
-    );
-    assert_eq!(
-        after_payment_user2_user1
-            .iter()
-            .map(|(total, _, _)| total.clone())
-            .collect::<Vec<_>>(),
-        vec![AssetValues::token_u64(11), AssetValues::token_u64(0),]
-    );
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], Default::default());
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
     test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn payment_address_from_public_key() {
-    let (public_key, _) = sign::gen_keypair();
-    let public_key_vec: Vec<u8> = public_key.as_ref().to_vec();
-    let pub_key = PublicKey::from_slice(&public_key_vec).unwrap();
-    assert_eq!(pub_key, public_key);
+async fn reject_txs_larger_than_max_block_size() {
+    test_step_start();
 
-    construct_address(&pub_key);
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11780);
+    network_config.compute_max_block_size = Some(1);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+
+    let valid_txs = valid_transactions(true);
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act
+    //
+    for tx in valid_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    for _tx in valid_txs.values() {
+        let reason = "Transaction larger than the maximum block size";
+        compute_handle_error(&mut network, "compute1", &[reason]).await;
+    }
+
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], Default::default());
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn receive_testnet_donation_payment_tx_user() {
+async fn accept_txs_at_dust_threshold() {
     test_step_start();
 
     //
     // Arrange
     //
-    let mut network_config = complete_network_config(10405);
-    network_config
-        .nodes_mut(NodeType::User)
-        .push("user2".to_string());
-    network_config.user_auto_donate = 5;
-    network_config.compute_seed_utxo = make_compute_seed_utxo(SEED_UTXO, TokenAmount(11));
-    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
+    let mut network_config = complete_network_config(11285);
+    network_config.compute_dust_threshold = DEFAULT_SEED_AMOUNT;
     let mut network = Network::create_from_config(&network_config).await;
-    let user_nodes = &network_config.nodes[&NodeType::User];
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
+    // Valid transactions outputs are DEFAULT_SEED_AMOUNT
+    let valid_txs = valid_transactions_with(true, DEFAULT_SEED_AMOUNT, false);
     create_first_block_act(&mut network).await;
 
     //
-    // Act/Assert
+    // Act
     //
-    let before = node_all_get_wallet_info(&mut network, user_nodes).await;
+    add_transactions_act(&mut network, &valid_txs).await;
 
-    node_connect_to(&mut network, "user1", "user2").await;
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
-    user_send_donation_address_to_peer(&mut network, "user2", "user1").await;
-    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+    test_step_complete(network).await;
+}
 
-    user_send_next_payment_to_destinations(&mut network, "user1", "compute1").await;
-    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
-    user_handle_event(&mut network, "user2", "Payment transaction received").await;
+#[tokio::test(flavor = "current_thread")]
+async fn reject_txs_below_dust_threshold() {
+    test_step_start();
 
-    let after = node_all_get_wallet_info(&mut network, user_nodes).await;
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11295);
+    network_config.compute_dust_threshold = DEFAULT_SEED_AMOUNT + TokenAmount(1);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+
+    let valid_txs = valid_transactions_with(true, DEFAULT_SEED_AMOUNT, false);
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act
+    //
+    for tx in valid_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    for _tx in valid_txs.values() {
+        let reason = "Transaction output below dust threshold";
+        compute_handle_error(&mut network, "compute1", &[reason]).await;
+    }
 
     //
     // Assert
     //
-    assert_eq!(
-        before
-            .iter()
-            .map(|(total, _, _)| total.clone())
-            .collect::<Vec<_>>(),
-        vec![AssetValues::token_u64(11), AssetValues::token_u64(0)]
-    );
-    assert_eq!(
-        after
-            .iter()
-            .map(|(total, _, _)| total.clone())
-            .collect::<Vec<_>>(),
-        vec![AssetValues::token_u64(6), AssetValues::token_u64(5)]
-    );
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], Default::default());
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
     test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
-async fn reject_payment_txs() {
+async fn accept_txs_locked_within_max_locktime_offset() {
     test_step_start();
 
     //
     // Arrange
     //
-    let mut network_config = complete_network_config(10410);
-    network_config
-        .nodes_mut(NodeType::User)
-        .push("user2".to_string());
+    let mut network_config = complete_network_config(11325);
+    network_config.compute_max_locktime_offset = Some(10);
     let mut network = Network::create_from_config(&network_config).await;
     let compute_nodes = &network_config.nodes[&NodeType::Compute];
 
-    let valid_txs = valid_transactions(true);
-    let invalid_txs = vec![
-        // New keys not matching utxo_set
-        valid_transactions_with(false, DEFAULT_SEED_AMOUNT, false),
-        // Too much output amount for given inputs
-        valid_transactions_with(true, DEFAULT_SEED_AMOUNT + TokenAmount(1), false),
-        // Too little output amount for given inputs
-        valid_transactions_with(true, DEFAULT_SEED_AMOUNT - TokenAmount(1), false),
-        // Invalid script
-        {
-            let (k, v) = valid_txs.iter().next().unwrap();
-            let (k, mut v) = (k.clone(), v.clone());
-            v.inputs[0].script_signature.stack.push(StackEntry::Num(0));
-            Some((k, v)).into_iter().collect()
-        },
-    ];
+    // Locked up to the maximum offset from the first block
+    let valid_txs = valid_transactions_locked_until(10);
+    create_first_block_act(&mut network).await;
+
+    //
+    // Act
+    //
+    add_transactions_act(&mut network, &valid_txs).await;
+
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert_eq!(actual[0], valid_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn reject_txs_locked_beyond_max_locktime_offset() {
+    test_step_start();
 
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11335);
+    network_config.compute_max_locktime_offset = Some(10);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+
+    // Locked one block past the maximum offset from the first block
+    let valid_txs = valid_transactions_locked_until(11);
     create_first_block_act(&mut network).await;
 
     //
-    // Act/Assert
+    // Act
     //
-    for tx in invalid_txs.iter().flat_map(|txs| txs.values()) {
-        user_send_transaction_to_compute(&mut network, "user2", "compute1", tx).await;
+    for tx in valid_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
     }
-    for _tx in invalid_txs.iter().flat_map(|txs| txs.values()) {
-        compute_handle_error(
-            &mut network,
-            "compute1",
-            &["No valid transactions provided"],
-        )
-        .await;
+    for _tx in valid_txs.values() {
+        let reason = "Transaction locktime beyond maximum offset from current block";
+        compute_handle_error(&mut network, "compute1", &[reason]).await;
     }
-    add_transactions_act(&mut network, &valid_txs).await;
 
     //
     // Assert
     //
     let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
-    assert_eq!(actual[0], valid_txs);
+    assert_eq!(actual[0], Default::default());
     assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
 
     test_step_complete(network).await;
@@ -1993,6 +3163,73 @@ async fn gen_transactions_common(
     test_step_complete(network).await;
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn user_startup_requests_retry_until_compute_reachable() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10445);
+    network_config.user_test_auto_gen_setup = UserAutoGenTxSetup {
+        user_initial_transactions: vec![wallet_seed(VALID_TXS_IN[0], &DEFAULT_SEED_AMOUNT)],
+        user_setup_tx_chunk_size: None,
+        user_setup_tx_in_per_tx: Some(2),
+        user_setup_tx_max_count: 4,
+    };
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_addr = network.get_address("compute1").await.unwrap();
+    let (mut compute_conn, _, _) = network
+        .compute("compute1")
+        .unwrap()
+        .lock()
+        .await
+        .connect_info_peers();
+    let (mut user_conn, _, _) = network
+        .user("user1")
+        .unwrap()
+        .lock()
+        .await
+        .connect_info_peers();
+
+    network
+        .disconnect_nodes_named(&["compute1".to_owned()])
+        .await;
+    while user_conn
+        .unconnected_peers(&[compute_addr])
+        .await
+        .is_empty()
+    {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    //
+    // Act
+    //
+    let reconnect = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        compute_conn.set_pause_listening(false).await;
+        user_conn.connect_to(compute_addr).await
+    });
+    let startup_result = {
+        let mut u = network.user("user1").unwrap().lock().await;
+        u.send_startup_requests().await
+    };
+    let reconnect_result = reconnect.await.unwrap();
+    network
+        .re_connect_nodes_named(&["compute1".to_owned()])
+        .await;
+    compute_handle_event(&mut network, "compute1", &["Received block notification"]).await;
+
+    //
+    // Assert
+    //
+    assert!(reconnect_result.is_ok(), "{reconnect_result:?}");
+    assert!(startup_result.is_ok(), "{startup_result:?}");
+
+    test_step_complete(network).await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn proof_of_work_reject() {
     test_step_start();
@@ -2005,60 +3242,286 @@ async fn proof_of_work_reject() {
 
     let compute = "compute1";
     let miner = "miner1";
-    let block_num = 1;
+    let block_num = 1;
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+    compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+    miner_handle_event(&mut network, miner, "Received random number successfully").await;
+    miner_handle_event(&mut network, miner, "Partition PoW complete").await;
+    miner_process_found_partition_pow(&mut network, miner).await;
+    compute_handle_event(
+        &mut network,
+        compute,
+        &["Partition PoW received successfully"],
+    )
+    .await;
+    compute_handle_event(&mut network, compute, &["Winning PoW intake open"]).await;
+
+    //
+    // Act
+    //
+    {
+        // From node not in partition (user1 is not a miner).
+        let request = ComputeRequest::SendPoW {
+            block_num,
+            nonce: Default::default(),
+            coinbase: Default::default(),
+        };
+        compute_inject_next_event(&mut network, "user1", compute, request).await;
+        compute_handle_error(&mut network, compute, &["Not block currently mined"]).await;
+    }
+    {
+        // For not currently mined block number.
+        let request = ComputeRequest::SendPoW {
+            block_num: block_num + 1,
+            nonce: Default::default(),
+            coinbase: Default::default(),
+        };
+        compute_inject_next_event(&mut network, miner, compute, request).await;
+        compute_handle_error(&mut network, compute, &["Not block currently mined"]).await;
+    }
+    {
+        // For miner in partition and correct block, but wrong nonce/coinbase
+        let request = ComputeRequest::SendPoW {
+            block_num,
+            nonce: Default::default(),
+            coinbase: Default::default(),
+        };
+        compute_inject_next_event(&mut network, miner, compute, request).await;
+        compute_handle_error(&mut network, compute, &["Coinbase transaction invalid"]).await;
+    }
+
+    //
+    // Assert
+    //
+    let block = compute_current_mining_block(&mut network, compute).await;
+    assert!(block.is_some(), "Expect still mining");
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn partition_entry_easy_pow_accepted() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10435);
+    network_config.compute_partition_pow_difficulty = Some(0);
+    let mut network = Network::create_from_config(&network_config).await;
+
+    let compute = "compute1";
+    let miner = "miner1";
+    let block_num = 1;
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+    compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+
+    let rand_num = {
+        let c = network.compute(compute).unwrap().lock().await;
+        c.get_current_random_num().clone()
+    };
+    let miner_addr = network.get_address(miner).await.unwrap();
+    let partition_entry = loop {
+        let entry = ProofOfWork {
+            address: format_parition_pow_address(miner_addr),
+            nonce: generate_pow_nonce(),
+        };
+        if !validate_pow_for_address(&entry, &Some(&rand_num), MINING_DIFFICULTY) {
+            break entry;
+        }
+    };
+
+    //
+    // Act
+    //
+    let request = ComputeRequest::SendPartitionEntry {
+        pow_info: PowInfo {
+            participant_only: false,
+            b_num: block_num,
+        },
+        partition_entry,
+    };
+    compute_inject_next_event(&mut network, miner, compute, request).await;
+
+    //
+    // Assert
+    //
+    compute_handle_event(
+        &mut network,
+        compute,
+        &["Partition PoW received successfully"],
+    )
+    .await;
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn partition_entry_for_rotated_random_num_rejected() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11210);
+    network_config.compute_partition_random_num_rotation = Some(60_000);
+    let mut network = Network::create_from_config(&network_config).await;
+
+    let compute = "compute1";
+    let miner = "miner1";
+    let block_num = 1;
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+    compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+    miner_handle_event(&mut network, miner, "Received random number successfully").await;
+
+    let (old_rand_num, new_rand_num) = {
+        let mut c = network.compute(compute).unwrap().lock().await;
+        let old_rand_num = c.get_current_random_num().clone();
+        c.rotate_partition_random_num().await.unwrap();
+        (old_rand_num, c.get_current_random_num().clone())
+    };
+    let miner_addr = network.get_address(miner).await.unwrap();
+    let partition_entry = loop {
+        let entry = ProofOfWork {
+            address: format_parition_pow_address(miner_addr),
+            nonce: generate_pow_nonce(),
+        };
+        if validate_pow_for_address(&entry, &Some(&old_rand_num), MINING_DIFFICULTY)
+            && !validate_pow_for_address(&entry, &Some(&new_rand_num), MINING_DIFFICULTY)
+        {
+            break entry;
+        }
+    };
+
+    //
+    // Act
+    //
+    let request = ComputeRequest::SendPartitionEntry {
+        pow_info: PowInfo {
+            participant_only: false,
+            b_num: block_num,
+        },
+        partition_entry,
+    };
+    compute_inject_next_event(&mut network, miner, compute, request).await;
+
+    //
+    // Assert
+    //
+    assert_ne!(old_rand_num, new_rand_num);
+    compute_handle_error(&mut network, compute, &["PoW received is invalid"]).await;
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn unresponsive_miner_evicted_from_request_list() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10475);
+    network_config.compute_miner_max_missed_floods = Some(2);
+    let mut network = Network::create_from_config(&network_config).await;
+
+    let compute = "compute1";
+    let miner = "miner1";
+    let miner_addr = network.get_address(miner).await.unwrap();
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+
+    //
+    // Act
+    //
+    let mut in_request_list = Vec::new();
+    for _ in 0..3 {
+        compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+        let c = network.compute(compute).unwrap().lock().await;
+        in_request_list.push(c.get_request_list().contains(&miner_addr));
+    }
+
+    //
+    // Assert
+    //
+    assert_eq!(in_request_list, vec![true, true, false]);
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn miner_sending_any_message_kept_in_request_list() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10485);
+    network_config.compute_miner_max_missed_floods = Some(2);
+    let mut network = Network::create_from_config(&network_config).await;
+
+    let compute = "compute1";
+    let miner = "miner1";
+    let miner_addr = network.get_address(miner).await.unwrap();
+    create_first_block_act(&mut network).await;
+    create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
+
+    //
+    // Act
+    //
+    let mut in_request_list = Vec::new();
+    for _ in 0..3 {
+        compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+        miner_send_request_utxo_set(&mut network, miner).await;
+        compute_handle_event(&mut network, compute, &["Received UTXO fetch request"]).await;
+        let c = network.compute(compute).unwrap().lock().await;
+        in_request_list.push(c.get_request_list().contains(&miner_addr));
+    }
+
+    //
+    // Assert
+    //
+    assert_eq!(in_request_list, vec![true, true, true]);
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn partition_entry_hard_pow_generated_by_miner() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(10455);
+    network_config.compute_partition_pow_difficulty = Some(2);
+    let mut network = Network::create_from_config(&network_config).await;
+
+    let compute = "compute1";
+    let miner = "miner1";
     create_first_block_act(&mut network).await;
     create_block_act(&mut network, Cfg::IgnoreStorage, CfgNum::All).await;
     compute_flood_rand_and_block_to_partition(&mut network, compute).await;
+
+    //
+    // Act
+    //
     miner_handle_event(&mut network, miner, "Received random number successfully").await;
     miner_handle_event(&mut network, miner, "Partition PoW complete").await;
     miner_process_found_partition_pow(&mut network, miner).await;
+
+    //
+    // Assert
+    //
     compute_handle_event(
         &mut network,
         compute,
         &["Partition PoW received successfully"],
     )
     .await;
-    compute_handle_event(&mut network, compute, &["Winning PoW intake open"]).await;
-
-    //
-    // Act
-    //
-    {
-        // From node not in partition (user1 is not a miner).
-        let request = ComputeRequest::SendPoW {
-            block_num,
-            nonce: Default::default(),
-            coinbase: Default::default(),
-        };
-        compute_inject_next_event(&mut network, "user1", compute, request).await;
-        compute_handle_error(&mut network, compute, &["Not block currently mined"]).await;
-    }
-    {
-        // For not currently mined block number.
-        let request = ComputeRequest::SendPoW {
-            block_num: block_num + 1,
-            nonce: Default::default(),
-            coinbase: Default::default(),
-        };
-        compute_inject_next_event(&mut network, miner, compute, request).await;
-        compute_handle_error(&mut network, compute, &["Not block currently mined"]).await;
-    }
-    {
-        // For miner in partition and correct block, but wrong nonce/coinbase
-        let request = ComputeRequest::SendPoW {
-            block_num,
-            nonce: Default::default(),
-            coinbase: Default::default(),
-        };
-        compute_inject_next_event(&mut network, miner, compute, request).await;
-        compute_handle_error(&mut network, compute, &["Coinbase transaction invalid"]).await;
-    }
-
-    //
-    // Assert
-    //
-    let block = compute_current_mining_block(&mut network, compute).await;
-    assert!(block.is_some(), "Expect still mining");
 
     test_step_complete(network).await;
 }
@@ -2580,6 +4043,38 @@ async fn relaunch_with_new_raft_nodes() {
     test_step_complete(network).await;
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn pre_launch_status_complete_after_fetch() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config_with_n_compute_raft(11435, 2);
+    network_config.nodes.insert(NodeType::User, vec![]);
+    let mut network = Network::create_stopped_from_config(&network_config);
+    let compute_nodes = network_config.nodes[&NodeType::Compute].clone();
+    network.pre_launch_nodes_named(&compute_nodes).await;
+
+    //
+    // Act
+    //
+    let before = pre_launch_all_status(&mut network, &compute_nodes).await;
+    let handles = network
+        .spawn_main_node_loops(TIMEOUT_TEST_WAIT_DURATION)
+        .await;
+    node_join_all_checked(handles, &"").await.unwrap();
+    let after = pre_launch_all_status(&mut network, &compute_nodes).await;
+
+    //
+    // Assert
+    //
+    assert_eq!(before, vec![(true, false), (false, false)]);
+    assert_eq!(after, vec![(true, true), (true, true)]);
+
+    test_step_complete(network).await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn request_utxo_set_raft_1_node() {
     test_step_start();
@@ -2778,6 +4273,64 @@ async fn request_utxo_set_and_update_running_total_act(
     user_update_running_total(network, user).await;
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn miner_detects_funds_sent_to_archived_address() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config_with_n_compute_raft(10495, 1);
+    network_config.wallet_max_active_addresses = Some(1);
+    network_config.compute_seed_utxo = make_compute_seed_utxo_with_info(&[(
+        "000000",
+        vec![(SOME_PUB_KEYS[0], DEFAULT_SEED_AMOUNT)],
+    )]);
+    let mut network = Network::create_from_config(&network_config).await;
+    create_first_block_act(&mut network).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+
+    // Funded address unknown to the wallet, archived once a newer one is saved
+    miner_save_address_keys(&mut network, "miner1", 0).await;
+    miner_save_address_keys(&mut network, "miner1", 1).await;
+    let archived_before = miner_get_archived_addresses(&mut network, "miner1").await;
+
+    //
+    // Act
+    //
+    {
+        let mut m = network.miner("miner1").unwrap().lock().await;
+        m.send_archived_addresses_utxo_request().await;
+    }
+    compute_handle_event(&mut network, "compute1", &["Received UTXO fetch request"]).await;
+    compute_send_utxo_set(&mut network, "compute1").await;
+    miner_handle_event(&mut network, "miner1", "Received UTXO set").await;
+    {
+        let mut m = network.miner("miner1").unwrap().lock().await;
+        m.update_running_total().await;
+    }
+    let archived_after = miner_get_archived_addresses(&mut network, "miner1").await;
+    let fund_store = {
+        let m = network.miner("miner1").unwrap().lock().await;
+        m.get_wallet_db().get_fund_store()
+    };
+
+    //
+    // Assert
+    //
+    assert!(archived_before.contains(&SOME_PUB_KEY_ADDRS[0].to_string()));
+    assert!(!archived_after.contains(&SOME_PUB_KEY_ADDRS[0].to_string()));
+    assert_eq!(
+        fund_store
+            .transactions()
+            .get(&OutPoint::new("000000".to_string(), 0)),
+        Some(&Asset::Token(DEFAULT_SEED_AMOUNT))
+    );
+
+    test_step_complete(network).await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 pub async fn create_receipt_asset_raft_1_node() {
     test_step_start();
@@ -2894,6 +4447,90 @@ pub async fn create_receipt_asset_on_compute_raft_1_node() {
     test_step_complete(network).await;
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn create_receipt_asset_by_authorized_minter() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11270);
+    network_config.compute_receipt_minters = Some(vec![SOME_PUB_KEYS[1].to_owned()]);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    create_first_block_act(&mut network).await;
+
+    let (create_txs, signature) = receipt_create_txs_and_signature(1);
+
+    //
+    // Act
+    //
+    let created_on_compute = compute_try_create_receipt_asset_tx(
+        &mut network,
+        "compute1",
+        SOME_PUB_KEYS[1].to_owned(),
+        signature,
+    )
+    .await;
+    add_transactions_act(&mut network, &create_txs).await;
+
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert!(created_on_compute);
+    assert_eq!(actual[0], create_txs);
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn reject_receipt_asset_by_unauthorized_minter() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11280);
+    network_config.compute_receipt_minters = Some(vec![SOME_PUB_KEYS[2].to_owned()]);
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_nodes = &network_config.nodes[&NodeType::Compute];
+    create_first_block_act(&mut network).await;
+
+    let (create_txs, signature) = receipt_create_txs_and_signature(1);
+
+    //
+    // Act
+    //
+    let created_on_compute = compute_try_create_receipt_asset_tx(
+        &mut network,
+        "compute1",
+        SOME_PUB_KEYS[1].to_owned(),
+        signature,
+    )
+    .await;
+    for tx in create_txs.values() {
+        user_send_transaction_to_compute(&mut network, "user1", "compute1", tx).await;
+    }
+    compute_handle_error(
+        &mut network,
+        "compute1",
+        &["No valid transactions provided"],
+    )
+    .await;
+
+    //
+    // Assert
+    //
+    let actual = compute_all_committed_tx_pool(&mut network, compute_nodes).await;
+    assert!(!created_on_compute);
+    assert_eq!(actual[0], Default::default());
+    assert_eq!(equal_first(&actual), node_all(compute_nodes, true));
+
+    test_step_complete(network).await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 pub async fn make_receipt_based_payment_raft_1_node() {
     test_step_start();
@@ -3233,29 +4870,159 @@ async fn compute_create_receipt_asset_tx(
             drs_tx_hash_spec,
             receipt_metadata,
         )
-        .unwrap();
-    c.receive_transactions(vec![tx]);
-}
+        .unwrap();
+    c.receive_transactions(vec![tx]);
+}
+
+async fn compute_try_create_receipt_asset_tx(
+    network: &mut Network,
+    compute: &str,
+    public_key: String,
+    signature: String,
+) -> bool {
+    let mut c = network.compute(compute).unwrap().lock().await;
+    let address = decode_pub_key_as_address(&public_key);
+    c.create_receipt_asset_tx(
+        1,
+        address,
+        public_key,
+        signature,
+        DrsTxHashSpec::Create,
+        None,
+    )
+    .is_ok()
+}
+
+async fn make_receipt_based_payment_act(
+    network: &mut Network,
+    from: &str,
+    to: &str,
+    compute: &str,
+    send_asset: Asset,
+    drs_tx_hash: Option<String>, /* Expected `drs_tx_hash` of `Receipt` asset to receive */
+) {
+    user_send_receipt_based_payment_request(network, from, to, send_asset, drs_tx_hash).await;
+    user_handle_event(network, to, "Received receipt-based payment request").await;
+    user_send_receipt_based_payment_response(network, to).await;
+    user_handle_event(network, from, "Received receipt-based payment response").await;
+    user_send_receipt_based_payment_to_desinations(network, from, compute).await;
+    compute_handle_event(network, compute, &["Transactions added to tx pool"]).await;
+    user_handle_event(network, to, "Payment transaction received").await;
+    user_send_receipt_based_payment_to_desinations(network, to, compute).await;
+    compute_handle_event(network, compute, &["Transactions added to tx pool"]).await;
+    user_handle_event(network, from, "Payment transaction received").await;
+    compute_handle_event(network, compute, &["Transactions committed"]).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn restart_user_resend_outbound_payment() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11770);
+    network_config
+        .nodes_mut(NodeType::User)
+        .push("user2".to_string());
+    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
+    let modify_cfg = vec![
+        ("stop", CfgModif::Drop("user1")),
+        ("start", CfgModif::Respawn("user1")),
+    ];
+    let mut network = Network::create_from_config(&network_config).await;
+    let compute_addr = network.get_address("compute1").await.unwrap();
+    let unconnected_addr = network.get_address("storage1").await.unwrap();
+
+    create_first_block_act(&mut network).await;
+    node_connect_to(&mut network, "user1", "user2").await;
+    user_send_address_request(&mut network, "user1", "user2", TokenAmount(5), None).await;
+    user_handle_event(&mut network, "user2", "New address ready to be sent").await;
+    user_send_address_to_trading_peer(&mut network, "user2").await;
+    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+
+    //
+    // Act
+    //
+    // Payment queued but never sent, as if the node stopped before sending it
+    let send_result = {
+        let mut u = network.user("user1").unwrap().lock().await;
+        u.send_next_payment_to_destinations(unconnected_addr).await
+    };
+
+    modify_network(&mut network, "stop", &modify_cfg).await;
+    modify_network(&mut network, "start", &modify_cfg).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    compute_handle_event(&mut network, "compute1", &["Received block notification"]).await;
+
+    let (queued, acknowledged) = {
+        let mut u = network.user("user1").unwrap().lock().await;
+        let queued = u.get_outbound_payments().await;
+
+        let mut block = Block::new();
+        block.transactions = queued.keys().cloned().collect();
+        u.notified_block_mining(compute_addr, block).await;
+        (queued, u.get_outbound_payments().await)
+    };
+
+    //
+    // Assert
+    //
+    assert!(send_result.is_err());
+    assert_eq!(queued.len(), 1);
+    assert!(acknowledged.is_empty());
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn user_outbound_payment_expired() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config(11785);
+    network_config
+        .nodes_mut(NodeType::User)
+        .push("user2".to_string());
+    network_config.user_wallet_seeds = vec![vec![wallet_seed(VALID_TXS_IN[0], &TokenAmount(11))]];
+    let mut network = Network::create_from_config(&network_config).await;
+    let unconnected_addr = network.get_address("storage1").await.unwrap();
+
+    create_first_block_act(&mut network).await;
+    node_connect_to(&mut network, "user1", "user2").await;
+    user_send_address_request(&mut network, "user1", "user2", TokenAmount(5), None).await;
+    user_handle_event(&mut network, "user2", "New address ready to be sent").await;
+    user_send_address_to_trading_peer(&mut network, "user2").await;
+    user_handle_event(&mut network, "user1", "Next payment transaction ready").await;
+
+    //
+    // Act
+    //
+    let (expired_at_limit, expired_after_limit, remaining) = {
+        let mut u = network.user("user1").unwrap().lock().await;
+        let _ = u.send_next_payment_to_destinations(unconnected_addr).await;
+        let queued = u.get_outbound_payments().await;
+        let queued_at = queued.values().map(|(t, _)| *t).max().unwrap();
+
+        let limit = queued_at + OUTBOUND_PAYMENT_EXPIRY_SECS;
+        let expired_at_limit = u.expire_outbound_payments(limit).await;
+        let expired_after_limit = u.expire_outbound_payments(limit + 1).await;
+        (
+            expired_at_limit,
+            expired_after_limit,
+            u.get_outbound_payments().await,
+        )
+    };
 
-async fn make_receipt_based_payment_act(
-    network: &mut Network,
-    from: &str,
-    to: &str,
-    compute: &str,
-    send_asset: Asset,
-    drs_tx_hash: Option<String>, /* Expected `drs_tx_hash` of `Receipt` asset to receive */
-) {
-    user_send_receipt_based_payment_request(network, from, to, send_asset, drs_tx_hash).await;
-    user_handle_event(network, to, "Received receipt-based payment request").await;
-    user_send_receipt_based_payment_response(network, to).await;
-    user_handle_event(network, from, "Received receipt-based payment response").await;
-    user_send_receipt_based_payment_to_desinations(network, from, compute).await;
-    compute_handle_event(network, compute, &["Transactions added to tx pool"]).await;
-    user_handle_event(network, to, "Payment transaction received").await;
-    user_send_receipt_based_payment_to_desinations(network, to, compute).await;
-    compute_handle_event(network, compute, &["Transactions added to tx pool"]).await;
-    user_handle_event(network, from, "Payment transaction received").await;
-    compute_handle_event(network, compute, &["Transactions committed"]).await;
+    //
+    // Assert
+    //
+    assert_eq!((expired_at_limit, expired_after_limit), (0, 1));
+    assert!(remaining.is_empty());
+
+    test_step_complete(network).await;
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -3382,6 +5149,180 @@ async fn reject_receipt_based_payment() {
     );
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn reject_unbalanced_receipt_based_payment() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config_with_n_compute_raft(11120, 1);
+    network_config.compute_seed_utxo = make_compute_seed_utxo_with_info({
+        &[("000000", vec![(SOME_PUB_KEYS[0], DEFAULT_SEED_AMOUNT)])]
+    });
+    let mut network = Network::create_from_config(&network_config).await;
+    create_first_block_act(&mut network).await;
+    let mut create_receipt_asset_txs = BTreeMap::default();
+    let tx = construct_receipt_create_tx(
+        0,
+        decode_pub_key(SOME_PUB_KEYS[1]).unwrap(),
+        &decode_secret_key(SOME_SEC_KEYS[1]).unwrap(),
+        1,
+        DrsTxHashSpec::Create,
+        None,
+    );
+    let tx_hash = construct_tx_hash(&tx);
+    create_receipt_asset_txs.insert(tx_hash.to_owned(), tx);
+    add_transactions_act(&mut network, &create_receipt_asset_txs).await;
+    proof_of_work_act(&mut network, CfgPow::First, CfgNum::All, false, None).await;
+    send_block_to_storage_act(&mut network, CfgNum::All).await;
+    create_block_act(&mut network, Cfg::All, CfgNum::All).await;
+
+    /* User1 -> User2 */
+    let rb_sender_data = RbSenderData {
+        sender_pub_addr: SOME_PUB_KEY_ADDRS[0].to_owned(),
+        sender_pub_key: SOME_PUB_KEYS[0].to_owned(),
+        sender_sec_key: SOME_SEC_KEYS[0].to_owned(),
+        sender_prev_out: OutPoint::new("000000".to_owned(), 0),
+        sender_amount: DEFAULT_SEED_AMOUNT,
+        sender_half_druid: "sender_druid".to_owned(),
+        sender_expected_drs: Some(tx_hash.to_owned()),
+    };
+
+    let rb_receiver_data = RbReceiverData {
+        receiver_pub_addr: SOME_PUB_KEY_ADDRS[1].to_owned(),
+        receiver_pub_key: SOME_PUB_KEYS[1].to_owned(),
+        receiver_sec_key: SOME_SEC_KEYS[1].to_owned(),
+        receiver_prev_out: OutPoint::new(tx_hash.to_owned(), 0),
+        receiver_half_druid: "receiver_druid".to_owned(),
+    };
+
+    let rb_txs = generate_rb_transactions(rb_sender_data, rb_receiver_data);
+    let rb_send_tx = rb_txs[0].1.clone();
+    let balanced_recv_tx = rb_txs[1].1.clone();
+
+    // Receiver gives its receipt away without expecting anything in return
+    let mut unbalanced_recv_tx = balanced_recv_tx.clone();
+    unbalanced_recv_tx.druid_info.as_mut().unwrap().expectations = Vec::new();
+
+    //
+    // Act
+    //
+    user_send_transaction_to_compute(&mut network, "user1", "compute1", &rb_send_tx).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    user_send_transaction_to_compute(&mut network, "user1", "compute1", &unbalanced_recv_tx).await;
+    compute_handle_event(
+        &mut network,
+        "compute1",
+        &["Some transactions invalid. Adding valid transactions only"],
+    )
+    .await;
+    let local_druid_pool_after_unbalanced =
+        compute_local_druid_pool(&mut network, "compute1").await;
+
+    user_send_transaction_to_compute(&mut network, "user1", "compute1", &rb_send_tx).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    user_send_transaction_to_compute(&mut network, "user1", "compute1", &balanced_recv_tx).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+
+    //
+    // Assert
+    //
+    let actual_pending_druid_pool = compute_pending_druid_pool(&mut network, "compute1").await;
+    let actual_local_druid_pool = compute_local_druid_pool(&mut network, "compute1").await;
+
+    assert_eq!(local_druid_pool_after_unbalanced.len(), 0);
+    assert_eq!(
+        (
+            actual_pending_druid_pool.len(),
+            actual_local_druid_pool.len()
+        ),
+        (0, 1)
+    );
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn cancel_pending_druid_participation() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let mut network_config = complete_network_config_with_n_compute_raft(11230, 1);
+    network_config.compute_seed_utxo = make_compute_seed_utxo_with_info({
+        &[("000000", vec![(SOME_PUB_KEYS[0], DEFAULT_SEED_AMOUNT)])]
+    });
+    let mut network = Network::create_from_config(&network_config).await;
+    create_first_block_act(&mut network).await;
+
+    let rb_sender_data = RbSenderData {
+        sender_pub_addr: SOME_PUB_KEY_ADDRS[0].to_owned(),
+        sender_pub_key: SOME_PUB_KEYS[0].to_owned(),
+        sender_sec_key: SOME_SEC_KEYS[0].to_owned(),
+        sender_prev_out: OutPoint::new("000000".to_owned(), 0),
+        sender_amount: DEFAULT_SEED_AMOUNT,
+        sender_half_druid: "sender_druid".to_owned(),
+        sender_expected_drs: Some("drs_tx_hash".to_owned()),
+    };
+
+    let rb_receiver_data = RbReceiverData {
+        receiver_pub_addr: SOME_PUB_KEY_ADDRS[1].to_owned(),
+        receiver_pub_key: SOME_PUB_KEYS[1].to_owned(),
+        receiver_sec_key: SOME_SEC_KEYS[1].to_owned(),
+        receiver_prev_out: OutPoint::new("000001".to_owned(), 0),
+        receiver_half_druid: "receiver_druid".to_owned(),
+    };
+
+    let rb_txs = generate_rb_transactions(rb_sender_data, rb_receiver_data);
+    let (rb_send_hash, rb_send_tx) = rb_txs[0].clone();
+    let druid = rb_send_tx.druid_info.as_ref().unwrap().druid.clone();
+
+    user_send_transaction_to_compute(&mut network, "user1", "compute1", &rb_send_tx).await;
+    compute_handle_event(&mut network, "compute1", &["Transactions added to tx pool"]).await;
+    let pending_before = compute_pending_druid_pool(&mut network, "compute1").await;
+
+    //
+    // Act
+    //
+    let not_participant = compute_cancel_druid(
+        &mut network,
+        "compute1",
+        &druid,
+        &rb_send_hash,
+        SOME_PUB_KEYS[1],
+        SOME_SEC_KEYS[1],
+    )
+    .await;
+    let cancelled = compute_cancel_druid(
+        &mut network,
+        "compute1",
+        &druid,
+        &rb_send_hash,
+        SOME_PUB_KEYS[0],
+        SOME_SEC_KEYS[0],
+    )
+    .await;
+
+    //
+    // Assert
+    //
+    let pending_after = compute_pending_druid_pool(&mut network, "compute1").await;
+
+    assert!(pending_before.contains_key(&druid));
+    assert_eq!(
+        (not_participant, cancelled),
+        (
+            ResponseReason::DruidParticipantNotFound,
+            ResponseReason::DruidParticipationCancelled
+        )
+    );
+    assert!(!pending_after.contains_key(&druid));
+
+    test_step_complete(network).await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn compute_pause_update_and_resume_raft_3_nodes() {
     test_step_start();
@@ -3396,6 +5337,12 @@ async fn compute_pause_update_and_resume_raft_3_nodes() {
         compute_partition_full_size: network_config.compute_partition_full_size,
         compute_mining_event_timeout: 500,
         compute_miner_whitelist: Default::default(), // No whitelisting
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_dust_threshold: TokenAmount(0),
+        compute_min_block_reward: TokenAmount(0),
+        compute_reward_halving: None,
+        compute_winning_miner_fairness: None,
     };
 
     // This is the configuration we want applied to all compute nodes during runtime
@@ -3403,6 +5350,12 @@ async fn compute_pause_update_and_resume_raft_3_nodes() {
         compute_partition_full_size: 5,
         compute_mining_event_timeout: 10000, // Changed mining event timeout
         compute_miner_whitelist: Default::default(), // No whitelisting
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_dust_threshold: TokenAmount(0),
+        compute_min_block_reward: TokenAmount(0),
+        compute_reward_halving: None,
+        compute_winning_miner_fairness: None,
     };
 
     let compute_ring = &[
@@ -3473,6 +5426,12 @@ async fn compute_activate_miner_api_key_whitelisting_raft_3_nodes() {
         compute_partition_full_size: network_config.compute_partition_full_size,
         compute_mining_event_timeout: 500,
         compute_miner_whitelist: Default::default(), // No whitelisting
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_dust_threshold: TokenAmount(0),
+        compute_min_block_reward: TokenAmount(0),
+        compute_reward_halving: None,
+        compute_winning_miner_fairness: None,
     };
 
     // This is the configuration we want applied to all compute nodes during runtime
@@ -3490,6 +5449,12 @@ async fn compute_activate_miner_api_key_whitelisting_raft_3_nodes() {
             ),
             miner_addresses: None,
         },
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_dust_threshold: TokenAmount(0),
+        compute_min_block_reward: TokenAmount(0),
+        compute_reward_halving: None,
+        compute_winning_miner_fairness: None,
     };
 
     let compute_ring = &[
@@ -3659,6 +5624,16 @@ async fn compute_get_pk_cache(
     c.get_pk_cache()
 }
 
+async fn compute_is_block_pending_storage(network: &mut Network, compute: &str) -> bool {
+    let c = network.compute(compute).unwrap().lock().await;
+    c.is_block_pending_storage()
+}
+
+async fn compute_get_storage_address(network: &mut Network, compute: &str) -> SocketAddr {
+    let c = network.compute(compute).unwrap().lock().await;
+    c.storage_address()
+}
+
 async fn compute_get_shared_config(
     network: &mut Network,
     compute: &str,
@@ -3970,6 +5945,12 @@ async fn node_send_coordinated_shutdown(network: &mut Network, node: &str, at_bl
     event_tx.send(event, "test shutdown").await.unwrap();
 }
 
+async fn node_send_cancel_shutdown(network: &mut Network, node: &str) {
+    let mut event_tx = network.get_local_event_tx(node).await.unwrap();
+    let event = LocalEvent::CancelShutdown;
+    event_tx.send(event, "test cancel shutdown").await.unwrap();
+}
+
 async fn node_send_startup_requests(network: &mut Network, node: &str) {
     network
         .send_startup_requests_named(&[node.to_string()])
@@ -4019,7 +6000,7 @@ async fn compute_handle_event_for_node<E: Future<Output = &'static str> + Unpin>
     let addr = c.local_address();
     match c.handle_next_event(exit).await {
         Some(Ok(Response { success, reason }))
-            if success == success_val && reason_val.contains(&reason) =>
+            if success == success_val && reason_val.contains(&reason.as_str()) =>
         {
             info!("Compute handle_next_event {} success ({})", reason, addr);
         }
@@ -4073,6 +6054,30 @@ async fn compute_all_skip_block_gen(
     }
 }
 
+async fn compute_get_coordinated_shutdown(network: &mut Network, compute: &str) -> Option<u64> {
+    let c = network.compute(compute).unwrap().lock().await;
+    c.get_coordinated_shutdown()
+}
+
+async fn compute_check_block_production_stall(
+    network: &mut Network,
+    compute: &str,
+    advance: Duration,
+) -> bool {
+    let mut c = network.compute(compute).unwrap().lock().await;
+    c.check_block_production_stall(Instant::now() + advance)
+}
+
+async fn compute_is_block_production_stalled(network: &mut Network, compute: &str) -> bool {
+    let c = network.compute(compute).unwrap().lock().await;
+    c.is_block_production_stalled()
+}
+
+async fn compute_resend_trigger_message(network: &mut Network, compute: &str) {
+    let mut c = network.compute(compute).unwrap().lock().await;
+    c.resend_trigger_message().await;
+}
+
 async fn compute_mined_block_num(network: &mut Network, compute: &str) -> Option<u64> {
     let c = network.compute(compute).unwrap().lock().await;
     c.get_current_mined_block()
@@ -4123,6 +6128,18 @@ async fn compute_committed_current_block_num(network: &mut Network, compute: &st
     c.get_committed_current_block_num()
 }
 
+async fn compute_all_miner_payouts(
+    network: &mut Network,
+    compute_group: &[String],
+) -> Vec<Vec<MinerPayout>> {
+    let mut result = Vec::new();
+    for name in compute_group {
+        let c = network.compute(name).unwrap().lock().await;
+        result.push(c.get_miner_payouts());
+    }
+    result
+}
+
 async fn compute_all_committed_current_block_num(
     network: &mut Network,
     compute_group: &[String],
@@ -4197,6 +6214,25 @@ async fn compute_pending_druid_pool(network: &mut Network, compute: &str) -> Dru
     c.get_pending_druid_pool().clone()
 }
 
+async fn compute_cancel_druid(
+    network: &mut Network,
+    compute: &str,
+    druid: &str,
+    tx_hash: &str,
+    pub_key: &str,
+    sec_key: &str,
+) -> ResponseReason {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let expiry = now.as_secs() + 60;
+    let message = druid_cancellation_message(druid, tx_hash, expiry);
+    let sk = decode_secret_key(sec_key).unwrap();
+    let signature = hex::encode(sign::sign_detached(&message, &sk).as_ref());
+
+    let mut c = network.compute(compute).unwrap().lock().await;
+    c.cancel_druid(druid, tx_hash, expiry, pub_key, &signature)
+        .reason
+}
+
 async fn compute_local_druid_pool(
     network: &mut Network,
     compute: &str,
@@ -4214,7 +6250,6 @@ async fn compute_get_utxo_balance_for_user(
     compute_get_utxo_balance_for_addresses(network, compute, addresses)
         .await
         .get_asset_values()
-        .clone()
 }
 
 async fn miner_get_last_aggregation_address(network: &mut Network, miner: &str) -> Option<String> {
@@ -4260,6 +6295,15 @@ async fn compute_inject_next_event(
     c.inject_next_event(from_addr, request).unwrap();
 }
 
+async fn pre_launch_all_status(network: &mut Network, nodes: &[String]) -> Vec<(bool, bool)> {
+    let mut result = Vec::new();
+    for name in nodes {
+        let status = network.pre_launch(name).unwrap().lock().await.status();
+        result.push((status.is_raft_db_fetched(), status.is_complete()));
+    }
+    result
+}
+
 async fn compute_flood_rand_and_block_to_partition(network: &mut Network, compute: &str) {
     let mut c = network.compute(compute).unwrap().lock().await;
     c.flood_rand_and_block_to_partition().await.unwrap();
@@ -4399,11 +6443,21 @@ async fn storage_inject_send_block_to_storage(
     storage_inject_next_event(network, compute, storage, request).await;
 }
 
+async fn storage_get_compute_address(network: &mut Network, storage: &str) -> SocketAddr {
+    let s = network.storage(storage).unwrap().lock().await;
+    s.compute_address()
+}
+
 async fn storage_get_stored_key_values_count(network: &mut Network, storage: &str) -> usize {
     let s = network.storage(storage).unwrap().lock().await;
     s.get_stored_values_count()
 }
 
+async fn storage_get_unflushed_entry_count(network: &mut Network, storage: &str) -> u64 {
+    let s = network.storage(storage).unwrap().lock().await;
+    s.get_unflushed_entry_count()
+}
+
 async fn storage_all_get_stored_key_values_count(
     network: &mut Network,
     storage_group: &[String],
@@ -4566,7 +6620,7 @@ async fn storage_handle_event_for_node<E: Future<Output = &'static str> + Unpin>
     let addr = s.local_address();
     match s.handle_next_event(exit).await {
         Some(Ok(Response { success, reason }))
-            if success == success_val && reason == reason_val =>
+            if success == success_val && reason.as_str() == reason_val =>
         {
             info!(
                 "Storage handle_next_event {} success ({})",
@@ -4634,7 +6688,7 @@ async fn user_handle_event_for_node<E: Future<Output = &'static str> + Unpin>(
     let addr = u.local_address();
     match u.handle_next_event(exit).await {
         Some(Ok(Response { success, reason }))
-            if success == success_val && reason == reason_val =>
+            if success == success_val && reason.as_str() == reason_val =>
         {
             info!("User handle_next_event {} success ({})", reason_val, addr);
         }
@@ -4777,7 +6831,10 @@ async fn user_trigger_update_wallet_from_utxo_set(
     address_list: UtxoFetchType,
 ) {
     let u = network.user(user).unwrap().lock().await;
-    let request = UserRequest::UserApi(UserApiRequest::UpdateWalletFromUtxoSet { address_list });
+    let request = UserRequest::UserApi {
+        request: UserApiRequest::UpdateWalletFromUtxoSet { address_list },
+        correlation_id: String::new(),
+    };
     u.api_inputs()
         .1
         .inject_next_event(u.local_address(), request)
@@ -4885,6 +6942,25 @@ fn miner_blockchain_item_meta(item: &BlockchainItem) -> (char, u64, u32) {
     }
 }
 
+async fn miner_save_address_keys(network: &mut Network, miner: &str, key_idx: usize) {
+    let m = network.miner(miner).unwrap().lock().await;
+    let keys = AddressStore {
+        public_key: decode_pub_key(SOME_PUB_KEYS[key_idx]).unwrap(),
+        secret_key: decode_secret_key(SOME_SEC_KEYS[key_idx]).unwrap(),
+        address_version: None,
+    };
+    let address = SOME_PUB_KEY_ADDRS[key_idx].to_string();
+    m.get_wallet_db()
+        .save_address_to_wallet(address, keys)
+        .await
+        .unwrap();
+}
+
+async fn miner_get_archived_addresses(network: &mut Network, miner: &str) -> Vec<String> {
+    let m = network.miner(miner).unwrap().lock().await;
+    m.get_wallet_db().get_archived_addresses()
+}
+
 async fn miner_handle_event_failure(network: &mut Network, miner: &str, reason_val: &str) {
     let mut m = network.miner(miner).unwrap().lock().await;
     miner_handle_event_for_node(&mut m, false, reason_val, &mut test_timeout()).await;
@@ -4910,7 +6986,7 @@ async fn miner_handle_event_for_node<E: Future<Output = &'static str> + Unpin>(
     let addr = m.local_address();
     match m.handle_next_event(exit).await {
         Some(Ok(Response { success, reason }))
-            if success == success_val && reason == reason_val =>
+            if success == success_val && reason.as_str() == reason_val =>
         {
             info!("Miner handle_next_event {} success ({})", reason_val, addr);
         }
@@ -4947,6 +7023,16 @@ async fn miner_one_handle_event(
     debug!("Stop wait for event");
 }
 
+async fn miner_send_request_utxo_set(network: &mut Network, miner: &str) {
+    let mut m = network.miner(miner).unwrap().lock().await;
+    let request = MineApiRequest::RequestUTXOSet(UtxoFetchType::AnyOf(Vec::new()));
+    let response = m.handle_miner_api(m.local_address(), request).await;
+    assert_eq!(
+        response.map(|r| r.reason),
+        Some(ResponseReason::SentUtxoRequest)
+    );
+}
+
 async fn miner_process_found_partition_pow(network: &mut Network, from_miner: &str) {
     let mut m = network.miner(from_miner).unwrap().lock().await;
     m.process_found_partition_pow().await;
@@ -4960,9 +7046,9 @@ async fn miner_has_aggregation_tx_active(
     m.has_aggregation_tx_active()
 }
 
-async fn miner_process_found_block_pow(network: &mut Network, from_miner: &str) {
+async fn miner_process_found_block_pow(network: &mut Network, from_miner: &str) -> bool {
     let mut m = network.miner(from_miner).unwrap().lock().await;
-    m.process_found_block_pow().await;
+    m.process_found_block_pow().await
 }
 
 async fn miner_set_static_miner_address(network: &mut Network, miner: &str, static_addr: String) {
@@ -4970,6 +7056,36 @@ async fn miner_set_static_miner_address(network: &mut Network, miner: &str, stat
     m.set_static_miner_address(Some(static_addr)).await;
 }
 
+async fn miner_get_current_round(network: &mut Network, miner: &str) -> Option<u64> {
+    let m = network.miner(miner).unwrap().lock().await;
+    m.get_current_round()
+}
+
+async fn miner_find_external_block_pow_nonce(
+    network: &mut Network,
+    miner: &str,
+    valid: bool,
+) -> Vec<u8> {
+    let m = network.miner(miner).unwrap().lock().await;
+    let (_, _, _, _, _, current_block, _) = m.api_inputs();
+    let current_block = serde_json::to_value(current_block.lock().await.clone()).unwrap();
+    let mut header: BlockHeader =
+        serde_json::from_value(current_block["mining_header"].clone()).unwrap();
+    let mining_difficulty: usize =
+        serde_json::from_value(current_block["mining_difficulty"].clone()).unwrap();
+    loop {
+        header.nonce_and_mining_tx_hash.0 = generate_pow_nonce();
+        if validate_pow_block(&header, mining_difficulty) == valid {
+            return header.nonce_and_mining_tx_hash.0;
+        }
+    }
+}
+
+async fn miner_submit_pow(network: &mut Network, miner: &str, nonce: Vec<u8>) -> Response {
+    let mut m = network.miner(miner).unwrap().lock().await;
+    m.handle_submit_pow(nonce).await
+}
+
 async fn miner_set_mining_api_key(network: &mut Network, miner: &str, api_key: Option<String>) {
     let mut m = network.miner(miner).unwrap().lock().await;
     m.set_mining_api_key(api_key);
@@ -5031,6 +7147,44 @@ fn valid_transactions_with(
     transactions
 }
 
+/// Valid transactions with all outputs locked until the given block
+fn valid_transactions_locked_until(locktime: u64) -> BTreeMap<String, Transaction> {
+    valid_transactions_with(true, DEFAULT_SEED_AMOUNT, false)
+        .into_values()
+        .map(|mut tx| {
+            tx.outputs
+                .iter_mut()
+                .for_each(|tx_out| tx_out.locktime = locktime);
+            (construct_tx_hash(&tx), tx)
+        })
+        .collect()
+}
+
+/// Single transaction spending the given seed outputs, all owned by the common key
+fn common_key_transaction(
+    tx_ins: &[(i32, &str)],
+    tx_outs: &[&str],
+    amount: TokenAmount,
+) -> BTreeMap<String, Transaction> {
+    let pk = decode_pub_key(COMMON_PUB_KEY).unwrap();
+    let sk = decode_secret_key(COMMON_SEC_KEY).unwrap();
+    let (t_hash, tx) =
+        create_valid_transaction_with_ins_outs(tx_ins, tx_outs, &pk, &sk, amount, None);
+    std::iter::once((t_hash, tx)).collect()
+}
+
+/// Receipt create transaction for SOME_PUB_KEYS[key_idx] with the signature of its asset
+fn receipt_create_txs_and_signature(key_idx: usize) -> (BTreeMap<String, Transaction>, String) {
+    let public_key = decode_pub_key(SOME_PUB_KEYS[key_idx]).unwrap();
+    let secret_key = decode_secret_key(SOME_SEC_KEYS[key_idx]).unwrap();
+    let tx =
+        construct_receipt_create_tx(0, public_key, &secret_key, 1, DrsTxHashSpec::Create, None);
+    let create_txs = vec![(construct_tx_hash(&tx), tx)].into_iter().collect();
+    let asset_hash = construct_tx_in_signable_asset_hash(&Asset::receipt(1, None, None));
+    let signature = hex::encode(sign::sign_detached(asset_hash.as_bytes(), &secret_key).as_ref());
+    (create_txs, signature)
+}
+
 fn make_compute_seed_utxo(seed: &[(i32, &str)], amount: TokenAmount) -> UtxoSetSpec {
     let seed: Vec<_> = seed
         .iter()
@@ -5078,7 +7232,7 @@ fn block_and_partition_evt_in_miner_pow(
 fn panic_on_timeout<E>(response: &Result<Response, E>, tag: &str) {
     if let Ok(Response {
         success: true,
-        reason: "Test timeout elapsed",
+        reason: ResponseReason::Exit("Test timeout elapsed"),
     }) = response
     {
         panic!("Test timeout elapsed - {}", tag);
@@ -5184,7 +7338,7 @@ async fn construct_mining_common_info(
     addr: String,
 ) -> CommonBlockInfo {
     let block_num = block.header.b_num;
-    let amount = calculate_reward(TokenAmount(0));
+    let amount = calculate_reward(TokenAmount(0), block_num, None);
     let tx = construct_coinbase_tx(block_num, amount, addr);
     let hash = construct_tx_hash(&tx);
     block.header = apply_mining_tx(block.header, Vec::new(), hash.clone());
@@ -5293,7 +7447,20 @@ fn basic_network_config(initial_port: u16) -> NetworkConfig {
         storage_raft: false,
         in_memory_db: true,
         compute_partition_full_size: 1,
+        compute_partition_pow_difficulty: None,
         compute_minimum_miner_pool_len: 1,
+        compute_first_block_grace_ms: None,
+        compute_miner_max_missed_floods: None,
+        compute_block_stall_timeout: None,
+        compute_partition_random_num_rotation: None,
+        compute_close_full_participant_intake: None,
+        compute_winning_miner_fairness: None,
+        compute_min_tx_fee: Default::default(),
+        compute_dust_threshold: Default::default(),
+        compute_max_locktime_offset: None,
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_receipt_minters: None,
         nodes: vec![(NodeType::User, vec!["user1".to_string()])]
             .into_iter()
             .collect(),
@@ -5301,21 +7468,28 @@ fn basic_network_config(initial_port: u16) -> NetworkConfig {
         compute_genesis_tx_in: None,
         user_wallet_seeds: Vec::new(),
         compute_to_miner_mapping: Default::default(),
+        compute_to_storage_mapping: Default::default(),
         test_duration_divider: TEST_DURATION_DIVIDER,
         passphrase: Some("Test Passphrase".to_owned()),
         user_auto_donate: 0,
+        user_auto_donate_max_per_peer: None,
+        user_auto_donate_max_total: None,
+        user_auto_donate_window_secs: None,
         user_test_auto_gen_setup: Default::default(),
         tls_config: Default::default(),
         routes_pow: Default::default(),
         backup_block_modulo: Default::default(),
         utxo_re_align_block_modulo: Default::default(),
         backup_restore: Default::default(),
+        storage_sync_block_ack: Default::default(),
         enable_pipeline_reset: Default::default(),
         static_miner_address: Default::default(),
+        miner_reward_address: Default::default(),
         compute_miner_whitelist: Default::default(),
         mining_api_key: Default::default(),
         peer_limit: 1000,
         address_aggregation_limit: Some(5),
+        wallet_max_active_addresses: Default::default(),
     }
 }
 
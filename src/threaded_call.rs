@@ -1,14 +1,39 @@
-use crate::utils::{MpscTracingSender, StringError};
+use crate::constants::{API_THREADED_CALL_TIMEOUT_MS, THREADED_CALL_CHANNEL_CAPACITY};
+use crate::utils::MpscTracingSender;
+use std::error::Error;
 use std::fmt;
-use tokio::sync::{mpsc, oneshot};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::oneshot;
 use tracing::trace;
 
 pub type ThreadedCall<T> = Box<dyn FnOnce(&mut T) + Send + 'static>;
-pub type ThreadedCallSender<T> = MpscTracingSender<ThreadedCall<T>>;
 pub type ThreadedCallReceiver<T> = mpsc::Receiver<ThreadedCall<T>>;
 
+/// Sender for remote calls, giving up on calls not completed within `timeout`
+pub struct ThreadedCallSender<T: ?Sized> {
+    tx: MpscTracingSender<ThreadedCall<T>>,
+    timeout: Duration,
+}
+
+impl<T: ?Sized> ThreadedCallSender<T> {
+    /// Time a call waits for its result before giving up
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl<T: ?Sized> Clone for ThreadedCallSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            timeout: self.timeout,
+        }
+    }
+}
+
 // Implement blank Debug for ThreadedCallSender
-impl<T> std::fmt::Debug for ThreadedCallSender<T> {
+impl<T: ?Sized> std::fmt::Debug for ThreadedCallSender<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "")
     }
@@ -20,10 +45,40 @@ pub struct ThreadedCallChannel<T: ?Sized> {
     pub rx: ThreadedCallReceiver<T>,
 }
 
+impl<T: ?Sized> ThreadedCallChannel<T> {
+    /// Channel queuing at most `capacity` calls: further calls are rejected as overloaded.
+    /// Callers give up on calls not completed within `timeout`.
+    pub fn new(capacity: usize, timeout: Duration) -> Self {
+        let (tx, rx) = mpsc::channel(capacity);
+        let tx = ThreadedCallSender {
+            tx: tx.into(),
+            timeout,
+        };
+        Self { tx, rx }
+    }
+
+    /// Channel with the configured capacity and timeout, defaults if not set
+    ///
+    /// ### Arguments
+    ///
+    /// * `capacity`   - Configured number of calls queued, must be at least 1
+    /// * `timeout_ms` - Configured time in milliseconds a call waits for its result
+    pub fn with_config(
+        capacity: Option<usize>,
+        timeout_ms: Option<u64>,
+    ) -> Result<Self, &'static str> {
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(API_THREADED_CALL_TIMEOUT_MS));
+        match capacity.unwrap_or(THREADED_CALL_CHANNEL_CAPACITY) {
+            0 => Err("API threaded call capacity must be at least 1"),
+            capacity => Ok(Self::new(capacity, timeout)),
+        }
+    }
+}
+
 impl<T: ?Sized> Default for ThreadedCallChannel<T> {
     fn default() -> Self {
-        let (tx, rx) = mpsc::channel(100);
-        Self { tx: tx.into(), rx }
+        let timeout = Duration::from_millis(API_THREADED_CALL_TIMEOUT_MS);
+        Self::new(THREADED_CALL_CHANNEL_CAPACITY, timeout)
     }
 }
 
@@ -33,32 +88,66 @@ impl<T: ?Sized> fmt::Debug for ThreadedCallChannel<T> {
     }
 }
 
+/// Failure to complete a threaded call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadedCallError {
+    SendFailed,
+    Overloaded,
+    ResultDropped,
+    TimedOut(Duration),
+}
+
+impl fmt::Display for ThreadedCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SendFailed => write!(f, "ThreadedCall fail to send call"),
+            Self::Overloaded => write!(f, "ThreadedCall queue full: node overloaded"),
+            Self::ResultDropped => write!(f, "ThreadedCall fail to get result"),
+            Self::TimedOut(t) => write!(f, "ThreadedCall timed out after {}ms", t.as_millis()),
+        }
+    }
+}
+
+impl Error for ThreadedCallError {}
+
+/// Run `f` on the receiving side of the channel and wait for its result.
+/// Rejects the call if the channel is full rather than queuing it.
+/// Gives up after the sender timeout: a call still queued when the caller gives up is not run.
 pub async fn make_threaded_call<'a, T: ?Sized, R: Send + Sized + 'static>(
     tx: &mut ThreadedCallSender<T>,
     f: impl FnOnce(&mut T) -> R + Send + Sized + 'static,
     tag: &str,
-) -> Result<R, StringError> {
+) -> Result<R, ThreadedCallError> {
+    let timeout = tx.timeout;
     let (r_tx, r_rx) = oneshot::channel::<R>();
-    tx.send(
-        Box::new(move |v| {
-            if r_tx.send(f(v)).is_err() {
-                trace!("ThreadedCall fail to send result");
-            }
-        }),
-        tag,
-    )
-    .await
-    .map_err(|_| StringError("ThreadedCall fail to send call".to_owned()))?;
+    tx.tx
+        .try_send(
+            Box::new(move |v| {
+                if r_tx.is_closed() {
+                    trace!("ThreadedCall cancelled by caller");
+                } else if r_tx.send(f(v)).is_err() {
+                    trace!("ThreadedCall fail to send result");
+                }
+            }),
+            tag,
+        )
+        .map_err(|e| match e {
+            TrySendError::Full(_) => ThreadedCallError::Overloaded,
+            TrySendError::Closed(_) => ThreadedCallError::SendFailed,
+        })?;
 
-    let r = r_rx
+    tokio::time::timeout(timeout, r_rx)
         .await
-        .map_err(|_| StringError("ThreadedCall fail to get result".to_owned()))?;
-    Ok(r)
+        .map_err(|_| ThreadedCallError::TimedOut(timeout))?
+        .map_err(|_| ThreadedCallError::ResultDropped)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::time::Instant;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
 
     struct Callee {
         channel: ThreadedCallChannel<Callee>,
@@ -66,9 +155,9 @@ mod test {
     }
 
     impl Callee {
-        fn new(v: u64) -> Self {
+        fn new(v: u64, timeout: Duration) -> Self {
             Self {
-                channel: Default::default(),
+                channel: ThreadedCallChannel::new(THREADED_CALL_CHANNEL_CAPACITY, timeout),
                 value: v,
             }
         }
@@ -84,7 +173,7 @@ mod test {
 
     #[tokio::test(flavor = "current_thread")]
     async fn threaded_call() {
-        let mut callee = Callee::new(555);
+        let mut callee = Callee::new(555, TIMEOUT);
         let mut tx = callee.channel.tx.clone();
 
         let join_handle = tokio::spawn({
@@ -102,4 +191,65 @@ mod test {
 
         assert_eq!((rg, rs, join_r), (Ok(555), Ok(()), Ok(5)));
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn threaded_call_timeout() {
+        let timeout = Duration::from_millis(100);
+        let mut callee = Callee::new(555, timeout);
+        let mut tx = callee.channel.tx.clone();
+
+        let start = Instant::now();
+        let rs = make_threaded_call(&mut tx, |v| v.call_set(5), "Test set").await;
+        let elapsed = start.elapsed();
+
+        // The timed out call is still queued but must not run
+        let f = callee.channel.rx.recv().await.unwrap();
+        f(&mut callee);
+
+        assert_eq!(rs, Err(ThreadedCallError::TimedOut(timeout)));
+        assert!(elapsed >= timeout && elapsed < TIMEOUT, "{elapsed:?}");
+        assert_eq!(callee.value, 555);
+    }
+
+    #[test]
+    fn threaded_call_channel_with_config() {
+        let with_config = |capacity, timeout_ms| {
+            ThreadedCallChannel::<Callee>::with_config(capacity, timeout_ms)
+                .map(|c| c.tx.timeout().as_millis())
+        };
+
+        assert_eq!(
+            (
+                with_config(None, None),
+                with_config(Some(1), Some(500)),
+                with_config(Some(0), None)
+            ),
+            (
+                Ok(30_000),
+                Ok(500),
+                Err("API threaded call capacity must be at least 1")
+            )
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn threaded_call_overloaded() {
+        let timeout = Duration::from_millis(10);
+        let mut callee = Callee::new(555, timeout);
+        callee.channel = ThreadedCallChannel::new(2, timeout);
+        let mut tx = callee.channel.tx.clone();
+
+        let rs_queued_1 = make_threaded_call(&mut tx, |v| v.call_set(1), "Test 1").await;
+        let rs_queued_2 = make_threaded_call(&mut tx, |v| v.call_set(2), "Test 2").await;
+        let rs_rejected = make_threaded_call(&mut tx, |v| v.call_set(3), "Test 3").await;
+
+        assert_eq!(
+            (rs_queued_1, rs_queued_2, rs_rejected),
+            (
+                Err(ThreadedCallError::TimedOut(timeout)),
+                Err(ThreadedCallError::TimedOut(timeout)),
+                Err(ThreadedCallError::Overloaded)
+            )
+        );
+    }
 }
@@ -1,30 +1,165 @@
 use crate::interfaces::{AddressesWithOutPoints, OutPointData, UtxoSet};
 use crate::utils::{get_pk_with_out_point_cloned, get_pk_with_out_point_from_utxo_set_cloned};
-use naom::primitives::asset::AssetValues;
-use naom::primitives::transaction::{OutPoint, Transaction};
+use naom::primitives::asset::{Asset, AssetValues, TokenAmount};
+use naom::primitives::transaction::{OutPoint, Transaction, TxOut};
 use naom::utils::transaction_utils::get_tx_out_with_out_point_cloned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
-use std::ops::Deref;
+use std::ops::{Bound, Deref};
+
+/// Maximum number of `OutPoint`s returned in a page for an address
+pub const UTXO_PAGE_MAX_LIMIT: usize = 1000;
+
+/// Maximum number of `UtxoSet` entries fetched at once for a snapshot
+pub const UTXO_SNAPSHOT_CHUNK_SIZE: usize = 1000;
+
+/// Type of asset balances are aggregated by
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AssetKey {
+    Token,
+    /// Receipts are only fungible with receipts of the same `drs_tx_hash`
+    Receipt(String),
+}
+
+/// Totals of assets aggregated by asset type
+///
+/// Serialized as `AssetValues` for backward compatibility
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct AssetTotals(BTreeMap<AssetKey, u64>);
+
+impl AssetTotals {
+    /// Add an asset to its type total
+    pub fn update_add(&mut self, asset: &Asset) {
+        if let Some((key, amount)) = asset_key_amount(asset) {
+            *self.0.entry(key).or_insert(0) += amount;
+        }
+    }
+
+    /// Subtract an asset from its type total, dropping totals reaching 0
+    pub fn update_sub(&mut self, asset: &Asset) {
+        if let Some((key, amount)) = asset_key_amount(asset) {
+            if let Some(total) = self.0.get_mut(&key) {
+                *total = total.saturating_sub(amount);
+                if *total == 0 {
+                    self.0.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Add all the totals of another `AssetTotals`
+    pub fn add_totals(&mut self, other: &AssetTotals) {
+        for (key, amount) in &other.0 {
+            *self.0.entry(key.clone()).or_insert(0) += amount;
+        }
+    }
+
+    /// Whether there are no assets
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the total for an asset type
+    pub fn get(&self, key: &AssetKey) -> u64 {
+        self.0.get(key).copied().unwrap_or_default()
+    }
+
+    /// Iterate over the asset types with their totals
+    pub fn iter(&self) -> impl Iterator<Item = (&AssetKey, &u64)> {
+        self.0.iter()
+    }
+
+    /// Get the totals as token and receipt `AssetValues`
+    pub fn to_asset_values(&self) -> AssetValues {
+        let mut values = AssetValues::default();
+        for (key, amount) in &self.0 {
+            match key {
+                AssetKey::Token => values.tokens = TokenAmount(*amount),
+                AssetKey::Receipt(drs_tx_hash) => {
+                    values.receipts.insert(drs_tx_hash.clone(), *amount);
+                }
+            }
+        }
+        values
+    }
+}
+
+/// Get the aggregation type and amount of an asset, if aggregated and not empty
+fn asset_key_amount(asset: &Asset) -> Option<(AssetKey, u64)> {
+    let key_amount = match asset {
+        Asset::Token(tokens) => Some((AssetKey::Token, tokens.0)),
+        Asset::Receipt(receipt) => receipt
+            .drs_tx_hash
+            .clone()
+            .map(|drs_tx_hash| (AssetKey::Receipt(drs_tx_hash), receipt.amount)),
+        Asset::Data(_) => None,
+    };
+    key_amount.filter(|(_, amount)| *amount > 0)
+}
+
+impl Serialize for AssetTotals {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.to_asset_values().serialize(s)
+    }
+}
 
 #[derive(Default, Debug, Clone, Serialize)]
 pub struct TrackedUtxoBalance {
-    total: AssetValues,
+    total: AssetTotals,
     address_list: AddressesWithOutPoints,
 }
 
 impl TrackedUtxoBalance {
-    pub fn get_asset_values(&self) -> &AssetValues {
+    pub fn get_asset_totals(&self) -> &AssetTotals {
         &self.total
     }
+
+    pub fn get_asset_values(&self) -> AssetValues {
+        self.total.to_asset_values()
+    }
+}
+
+/// Page of the `OutPoint`s of an address, ordered by `t_hash` then `n`
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct TrackedUtxoPage {
+    address: String,
+    out_points: Vec<OutPointData>,
+    /// Last `OutPoint` of the page if more are available
+    next_cursor: Option<OutPoint>,
+}
+
+impl TrackedUtxoPage {
+    pub fn get_out_points(&self) -> &[OutPointData] {
+        &self.out_points
+    }
+
+    pub fn get_next_cursor(&self) -> Option<&OutPoint> {
+        self.next_cursor.as_ref()
+    }
 }
-/// Invariant: `pk_cache` contains exactly all relevant mapping for `base`
+
+/// Inconsistency found between the tracking caches and `base`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum UtxoTrackingError {
+    /// `OutPoint` with a `script_public_key` in `base` missing from `pk_cache`
+    Untracked(String, OutPoint),
+    /// `OutPoint` in `pk_cache` not present in `base` for that `script_public_key`
+    Stale(String, OutPoint),
+    /// `pk_cache` entry without any `OutPoint`
+    EmptyEntry(String),
+    /// `balance_cache` entry not matching the `OutPoint` values in `base`
+    BalanceMismatch(String),
+}
+
+/// Invariant: `pk_cache` and `balance_cache` contain exactly all relevant mapping for `base`
 #[derive(Default, Clone, Debug)]
 pub struct TrackedUtxoSet {
     /// The `UtxoSet`
     base: UtxoSet,
     /// Cache mapping of Some `script_public_key` to `OutPoints` present in `base`.
     pk_cache: HashMap<String, BTreeSet<OutPoint>>,
+    /// Cache mapping of Some `script_public_key` to the total of its `OutPoints` present in `base`.
+    balance_cache: HashMap<String, AssetTotals>,
 }
 
 impl TrackedUtxoSet {
@@ -52,9 +187,53 @@ impl TrackedUtxoSet {
         self.pk_cache.remove(entry);
     }
 
-    /// Re-align `pk_cache` to `base`
+    /// Re-align `pk_cache` and `balance_cache` to `base`
     pub fn re_align(&mut self) {
         self.pk_cache = create_pk_cache_from_base(&self.base);
+        self.balance_cache = create_balance_cache_from_base(&self.base);
+    }
+
+    /// Check the invariant between the tracking caches and `base` holds,
+    /// returning all the inconsistencies found otherwise
+    pub fn check_consistency(&self) -> Result<(), Vec<UtxoTrackingError>> {
+        let mut errors = Vec::new();
+
+        for (spk, op) in get_pk_with_out_point_from_utxo_set_cloned(self.base.iter()) {
+            if !self
+                .pk_cache
+                .get(&spk)
+                .map_or(false, |ops| ops.contains(&op))
+            {
+                errors.push(UtxoTrackingError::Untracked(spk, op));
+            }
+        }
+
+        for (spk, ops) in &self.pk_cache {
+            if ops.is_empty() {
+                errors.push(UtxoTrackingError::EmptyEntry(spk.clone()));
+            }
+            for op in ops {
+                let base_spk = self.base.get(op).and_then(|t| t.script_public_key.as_ref());
+                if base_spk != Some(spk) {
+                    errors.push(UtxoTrackingError::Stale(spk.clone(), op.clone()));
+                }
+            }
+        }
+
+        let expected_balance_cache = create_balance_cache_from_base(&self.base);
+        let mut balance_spks: BTreeSet<&String> = self.balance_cache.keys().collect();
+        balance_spks.extend(expected_balance_cache.keys());
+        for spk in balance_spks {
+            if self.balance_cache.get(spk) != expected_balance_cache.get(spk) {
+                errors.push(UtxoTrackingError::BalanceMismatch(spk.clone()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
     /// Get base `UtxoSet` length
@@ -70,7 +249,12 @@ impl TrackedUtxoSet {
     /// Create a new TrackedUtxoSet from `UtxoSet` base
     pub fn new(base: UtxoSet) -> Self {
         let pk_cache = create_pk_cache_from_base(&base);
-        TrackedUtxoSet { base, pk_cache }
+        let balance_cache = create_balance_cache_from_base(&base);
+        TrackedUtxoSet {
+            base,
+            pk_cache,
+            balance_cache,
+        }
     }
 
     // Take ownership of self; return base `UtxoSet`
@@ -83,21 +267,27 @@ impl TrackedUtxoSet {
         self.pk_cache.get(key)
     }
 
-    /// Add base 'UtxoSet' and pk_cache entry concurrently
+    /// Add base 'UtxoSet', pk_cache and balance_cache entry concurrently
     pub fn extend_tracked_utxo_set(&mut self, block_tx: &BTreeMap<String, Transaction>) {
-        self.base
-            .extend(get_tx_out_with_out_point_cloned(block_tx.iter()));
+        for (op, tx_out) in get_tx_out_with_out_point_cloned(block_tx.iter()) {
+            add_to_balance_cache(&mut self.balance_cache, &op, &tx_out);
+            if let Some(old_tx_out) = self.base.insert(op.clone(), tx_out) {
+                sub_from_balance_cache(&mut self.balance_cache, &op, &old_tx_out);
+            }
+        }
         extend_pk_cache_vec(
             &mut self.pk_cache,
             get_pk_with_out_point_cloned(block_tx.iter()),
         );
     }
 
-    /// Remove base 'UtxoSet' and pk_cache entry concurrently
+    /// Remove base 'UtxoSet', pk_cache and balance_cache entry concurrently
     pub fn remove_tracked_utxo_entry<'a>(&mut self, key: &'a OutPoint) -> Option<&'a OutPoint> {
-        self.base.remove(key)?.script_public_key.and_then(|spk| {
+        let tx_out = self.base.remove(key)?;
+        sub_from_balance_cache(&mut self.balance_cache, key, &tx_out);
+        tx_out.script_public_key.and_then(|spk| {
             let pk_cache_entry = self.pk_cache.get_mut(&spk)?;
-            pk_cache_entry.retain(|op| op.t_hash != key.t_hash && op.n != key.n);
+            pk_cache_entry.retain(|op| op != key);
             if pk_cache_entry.is_empty() {
                 self.pk_cache.remove(&spk);
             }
@@ -105,11 +295,86 @@ impl TrackedUtxoSet {
         })
     }
 
+    /// Get the total of the `OutPoint`s for the provided addresses from `balance_cache`,
+    /// without going through the individual `OutPoint`s
+    pub fn get_cached_balance_for_addresses(&self, addresses: &[String]) -> AssetTotals {
+        let addresses: BTreeSet<&String> = addresses.iter().collect();
+        let mut total = AssetTotals::default();
+        for balance in addresses.iter().filter_map(|a| self.balance_cache.get(*a)) {
+            total.add_totals(balance);
+        }
+        total
+    }
+
+    /// Get a page of the `OutPoint`s of an address
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - Address to get the `OutPoint`s for
+    /// * `cursor`  - `OutPoint` the page starts after, from the start if None
+    /// * `limit`   - Maximum number of `OutPoint`s in the page
+    pub fn get_utxo_page_for_address(
+        &self,
+        address: &str,
+        cursor: Option<&OutPoint>,
+        limit: usize,
+    ) -> TrackedUtxoPage {
+        let start = cursor.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut ops = self
+            .get_pk_cache_vec(address)
+            .into_iter()
+            .flat_map(|ops| ops.range((start, Bound::Unbounded)));
+
+        let out_points: Vec<OutPointData> = ops
+            .by_ref()
+            .take(limit)
+            .map(|op| {
+                let asset = self.base.get(op).unwrap().value.clone();
+                OutPointData::new(op.clone(), asset.with_fixed_hash(op))
+            })
+            .collect();
+        let next_cursor = ops
+            .next()
+            .and_then(|_| out_points.last())
+            .map(|data| data.get_out_point().clone());
+
+        TrackedUtxoPage {
+            address: address.to_owned(),
+            out_points,
+            next_cursor,
+        }
+    }
+
     /// Calculates the balance of `OutPoint`s based on provided addresses
     pub fn get_balance_for_addresses(&self, addresses: &[String]) -> TrackedUtxoBalance {
+        self.get_balance_for_addresses_excluding(addresses, &Default::default())
+    }
+
+    /// Calculates the balance of `OutPoint`s based on provided addresses,
+    /// ignoring the excluded `OutPoint`s (e.g. already spent by pending transactions)
+    ///
+    /// The total is taken from `balance_cache`, less the excluded `OutPoint`s of the addresses
+    pub fn get_balance_for_addresses_excluding(
+        &self,
+        addresses: &[String],
+        excluded: &BTreeSet<OutPoint>,
+    ) -> TrackedUtxoBalance {
         let mut address_list = AddressesWithOutPoints::new();
-        let mut total = AssetValues::default();
-        let mut known_op: BTreeSet<OutPoint> = Default::default();
+        let mut total = self.get_cached_balance_for_addresses(addresses);
+        let mut known_op: BTreeSet<OutPoint> = excluded.clone();
+
+        let requested: BTreeSet<&String> = addresses.iter().collect();
+        for op in excluded {
+            if let Some(t_out) = self.base.get(op) {
+                if t_out
+                    .script_public_key
+                    .as_ref()
+                    .map_or(false, |spk| requested.contains(spk))
+                {
+                    total.update_sub(&t_out.value.clone().with_fixed_hash(op));
+                }
+            }
+        }
 
         for address in addresses {
             if let Some(ops) = self.get_pk_cache_vec(address) {
@@ -124,8 +389,7 @@ impl TrackedUtxoSet {
                     address_list
                         .entry(address.clone())
                         .or_insert_with(Vec::new)
-                        .push(OutPointData::new(op.clone(), asset.clone()));
-                    total.update_add(&asset);
+                        .push(OutPointData::new(op.clone(), asset));
                 }
             }
         }
@@ -136,6 +400,45 @@ impl TrackedUtxoSet {
         }
     }
 
+    /// Get a chunk of the `UtxoSet` entries for the provided addresses,
+    /// or of all entries if none provided
+    ///
+    /// ### Arguments
+    ///
+    /// * `addresses` - Addresses to get the entries for
+    /// * `cursor`    - `OutPoint` the chunk starts after, from the start if None
+    /// * `limit`     - Maximum number of entries in the chunk
+    pub fn get_utxo_entries_chunk_for_addresses(
+        &self,
+        addresses: &[String],
+        cursor: Option<&OutPoint>,
+        limit: usize,
+    ) -> Vec<(OutPoint, TxOut)> {
+        let range = (
+            cursor.map_or(Bound::Unbounded, Bound::Excluded),
+            Bound::Unbounded,
+        );
+        if addresses.is_empty() {
+            return self
+                .base
+                .range(range)
+                .take(limit)
+                .map(|(op, t_out)| (op.clone(), t_out.clone()))
+                .collect();
+        }
+
+        let known_op: BTreeSet<&OutPoint> = addresses
+            .iter()
+            .filter_map(|address| self.get_pk_cache_vec(address))
+            .flat_map(|ops| ops.range(range).take(limit))
+            .collect();
+        known_op
+            .into_iter()
+            .take(limit)
+            .map(|op| (op.clone(), self.base.get(op).unwrap().clone()))
+            .collect()
+    }
+
     /// Get all `script_public_key` values from the current UTXO set
     pub fn get_all_addresses(&self) -> Vec<String> {
         self.base
@@ -155,6 +458,46 @@ pub fn create_pk_cache_from_base(base: &UtxoSet) -> HashMap<String, BTreeSet<Out
     pk_cache
 }
 
+/// Create `balance_cache` entries from base `UtxoSet`
+pub fn create_balance_cache_from_base(base: &UtxoSet) -> HashMap<String, AssetTotals> {
+    let mut balance_cache = HashMap::new();
+    for (op, tx_out) in base.iter() {
+        add_to_balance_cache(&mut balance_cache, op, tx_out);
+    }
+    balance_cache
+}
+
+/// Add the value of an `OutPoint` to its `balance_cache` entry
+fn add_to_balance_cache(
+    balance_cache: &mut HashMap<String, AssetTotals>,
+    op: &OutPoint,
+    tx_out: &TxOut,
+) {
+    let asset = tx_out.value.clone().with_fixed_hash(op);
+    if let (Some(spk), Some(_)) = (&tx_out.script_public_key, asset_key_amount(&asset)) {
+        balance_cache
+            .entry(spk.clone())
+            .or_default()
+            .update_add(&asset);
+    }
+}
+
+/// Subtract the value of an `OutPoint` from its `balance_cache` entry
+fn sub_from_balance_cache(
+    balance_cache: &mut HashMap<String, AssetTotals>,
+    op: &OutPoint,
+    tx_out: &TxOut,
+) {
+    if let Some(spk) = &tx_out.script_public_key {
+        if let Some(balance) = balance_cache.get_mut(spk) {
+            balance.update_sub(&tx_out.value.clone().with_fixed_hash(op));
+            if balance.is_empty() {
+                balance_cache.remove(spk);
+            }
+        }
+    }
+}
+
 /// Extend `pk_cache` entries
 pub fn extend_pk_cache_vec<'a>(
     pk_cache: &mut HashMap<String, BTreeSet<OutPoint>>,
@@ -183,6 +526,345 @@ impl<'a> Deserialize<'a> for TrackedUtxoSet {
     fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
         let base: UtxoSet = Deserialize::deserialize(deserializer)?;
         let pk_cache: HashMap<String, BTreeSet<OutPoint>> = create_pk_cache_from_base(&base);
-        Ok(TrackedUtxoSet { base, pk_cache })
+        let balance_cache = create_balance_cache_from_base(&base);
+        Ok(TrackedUtxoSet {
+            base,
+            pk_cache,
+            balance_cache,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn tx_out(address: &str) -> TxOut {
+        TxOut::new_token_amount(address.to_owned(), TokenAmount(1))
+    }
+
+    /// Tracking stays consistent with the base `UtxoSet` when adding and removing entries
+    #[test]
+    fn check_consistency_after_updates() {
+        //
+        // Arrange
+        //
+        let base: UtxoSet = vec![
+            (OutPoint::new("000000".to_owned(), 0), tx_out("addr_1")),
+            (OutPoint::new("000000".to_owned(), 1), tx_out("addr_1")),
+            (OutPoint::new("000001".to_owned(), 0), tx_out("addr_1")),
+            (OutPoint::new("000001".to_owned(), 1), tx_out("addr_2")),
+        ]
+        .into_iter()
+        .collect();
+        let mut utxo_set = TrackedUtxoSet::new(base);
+        let block_tx: BTreeMap<String, Transaction> = vec![(
+            "000002".to_owned(),
+            Transaction {
+                outputs: vec![tx_out("addr_2"), tx_out("addr_3")],
+                ..Transaction::default()
+            },
+        )]
+        .into_iter()
+        .collect();
+
+        //
+        // Act
+        //
+        utxo_set.extend_tracked_utxo_set(&block_tx);
+        utxo_set.remove_tracked_utxo_entry(&OutPoint::new("000000".to_owned(), 0));
+        utxo_set.remove_tracked_utxo_entry(&OutPoint::new("000001".to_owned(), 1));
+        utxo_set.remove_tracked_utxo_entry(&OutPoint::new("000002".to_owned(), 1));
+
+        //
+        // Assert
+        //
+        assert_eq!(utxo_set.check_consistency(), Ok(()));
+        assert_eq!(utxo_set.get_tracked_outpoint_count(), 3);
+        assert_eq!(
+            utxo_set.get_pk_cache_vec("addr_1").map(|ops| ops.len()),
+            Some(2)
+        );
+        assert_eq!(utxo_set.get_pk_cache_vec("addr_3"), None);
+    }
+
+    /// Missing tracking of the base `UtxoSet` entries is reported
+    #[test]
+    fn check_consistency_detects_untracked_entries() {
+        //
+        // Arrange
+        //
+        let op_1 = OutPoint::new("000000".to_owned(), 0);
+        let op_2 = OutPoint::new("000000".to_owned(), 1);
+        let base: UtxoSet = vec![(op_1, tx_out("addr_1")), (op_2.clone(), tx_out("addr_2"))]
+            .into_iter()
+            .collect();
+        let mut utxo_set = TrackedUtxoSet::new(base);
+
+        //
+        // Act
+        //
+        utxo_set.remove_pk_cache_entry("addr_2");
+        let result = utxo_set.check_consistency();
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            result,
+            Err(vec![UtxoTrackingError::Untracked(
+                "addr_2".to_owned(),
+                op_2
+            )])
+        );
+    }
+
+    /// Balances aggregate token and receipt `OutPoint`s by asset type
+    #[test]
+    fn balance_aggregated_by_asset_type() {
+        //
+        // Arrange
+        //
+        let receipt = |amount: u64, drs_tx_hash: Option<&str>| {
+            Asset::receipt(amount, drs_tx_hash.map(|h| h.to_owned()), None)
+        };
+        let base: UtxoSet = vec![
+            (OutPoint::new("000000".to_owned(), 0), tx_out("addr_1")),
+            (
+                OutPoint::new("000000".to_owned(), 1),
+                TxOut::new_token_amount("addr_2".to_owned(), TokenAmount(4)),
+            ),
+            (
+                OutPoint::new("000001".to_owned(), 0),
+                TxOut::new_asset("addr_1".to_owned(), receipt(2, Some("drs_1"))),
+            ),
+            (
+                OutPoint::new("000001".to_owned(), 1),
+                TxOut::new_asset("addr_2".to_owned(), receipt(3, Some("drs_1"))),
+            ),
+            (
+                OutPoint::new("000002".to_owned(), 0),
+                TxOut::new_asset("addr_2".to_owned(), receipt(5, None)),
+            ),
+            (OutPoint::new("000003".to_owned(), 0), tx_out("addr_3")),
+        ]
+        .into_iter()
+        .collect();
+        let utxo_set = TrackedUtxoSet::new(base);
+        let addresses = vec!["addr_1".to_owned(), "addr_2".to_owned()];
+
+        //
+        // Act
+        //
+        let balance = utxo_set.get_balance_for_addresses(&addresses);
+        let totals = balance.get_asset_totals();
+
+        //
+        // Assert
+        //
+        let drs_1 = AssetKey::Receipt("drs_1".to_owned());
+        let create_drs = AssetKey::Receipt("000002".to_owned());
+        assert_eq!(
+            totals.iter().collect::<Vec<_>>(),
+            vec![(&AssetKey::Token, &5), (&drs_1, &5), (&create_drs, &5)]
+        );
+        assert_eq!(
+            serde_json::to_string(totals).unwrap(),
+            "{\"tokens\":5,\"receipts\":{\"000002\":5,\"drs_1\":5}}"
+        );
+    }
+
+    /// Cached balances match a full scan of the `UtxoSet` across many mutations and snapshots,
+    /// including when excluding `OutPoint`s
+    #[test]
+    fn cached_balance_matches_full_scan() {
+        //
+        // Arrange
+        //
+        let addresses: Vec<String> = (0..8).map(|i| format!("addr_{}", i)).collect();
+        let full_scan =
+            |utxo_set: &TrackedUtxoSet, addresses: &[String], excluded: &BTreeSet<OutPoint>| {
+                let mut total = AssetTotals::default();
+                for (op, tx_out) in utxo_set.iter().filter(|(op, _)| !excluded.contains(*op)) {
+                    let spk = tx_out.script_public_key.as_ref();
+                    if spk.map_or(false, |spk| addresses.contains(spk)) {
+                        total.update_add(&tx_out.value.clone().with_fixed_hash(op));
+                    }
+                }
+                total
+            };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut utxo_set = TrackedUtxoSet::default();
+
+        //
+        // Act
+        //
+        let mut mismatches = Vec::new();
+        for b_num in 0..200 {
+            let outputs = (0..rng.gen_range(1, 6))
+                .map(|_| {
+                    let address = addresses[rng.gen_range(0, addresses.len())].clone();
+                    let amount = rng.gen_range(0, 10);
+                    if rng.gen_bool(0.7) {
+                        TxOut::new_token_amount(address, TokenAmount(amount))
+                    } else {
+                        let drs_tx_hash = Some(format!("drs_{}", rng.gen_range(0, 3)))
+                            .filter(|_| rng.gen_bool(0.5));
+                        TxOut::new_asset(address, Asset::receipt(amount, drs_tx_hash, None))
+                    }
+                })
+                .collect();
+            let tx = Transaction {
+                outputs,
+                ..Transaction::default()
+            };
+            let block_tx = vec![(format!("{:06}", b_num), tx)].into_iter().collect();
+            utxo_set.extend_tracked_utxo_set(&block_tx);
+
+            let spent: Vec<OutPoint> = utxo_set
+                .keys()
+                .filter(|_| rng.gen_bool(0.3))
+                .cloned()
+                .collect();
+            for op in &spent {
+                utxo_set.remove_tracked_utxo_entry(op);
+            }
+
+            if b_num % 50 == 0 {
+                utxo_set = bincode::deserialize(&bincode::serialize(&utxo_set).unwrap()).unwrap();
+            }
+
+            let requested = &addresses[..rng.gen_range(1, addresses.len())];
+            if utxo_set.get_cached_balance_for_addresses(requested)
+                != full_scan(&utxo_set, requested, &BTreeSet::new())
+            {
+                mismatches.push(b_num);
+            }
+        }
+
+        //
+        // Assert
+        //
+        assert_eq!(mismatches, Vec::<u64>::new());
+        assert_eq!(utxo_set.check_consistency(), Ok(()));
+        let excluded: BTreeSet<OutPoint> = utxo_set.keys().step_by(2).cloned().collect();
+        for address in &addresses {
+            let requested = vec![address.clone(), address.clone()];
+            assert_eq!(
+                utxo_set
+                    .get_balance_for_addresses_excluding(&requested, &excluded)
+                    .get_asset_totals(),
+                &full_scan(&utxo_set, &requested, &excluded)
+            );
+        }
+    }
+
+    /// Paging through the `OutPoint`s of an address returns all of them once, in order
+    #[test]
+    fn utxo_pages_for_address_cover_all_out_points() {
+        //
+        // Arrange
+        //
+        let mut base: UtxoSet = (0..95)
+            .map(|i| {
+                (
+                    OutPoint::new(format!("{:06}", i / 3), i % 3),
+                    tx_out("addr_1"),
+                )
+            })
+            .collect();
+        base.insert(OutPoint::new("000000".to_owned(), 3), tx_out("addr_2"));
+        let utxo_set = TrackedUtxoSet::new(base);
+
+        //
+        // Act
+        //
+        let mut pages = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = utxo_set.get_utxo_page_for_address("addr_1", cursor.as_ref(), 10);
+            cursor = page.get_next_cursor().cloned();
+            pages.push(page);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let empty_page = utxo_set.get_utxo_page_for_address("addr_3", None, 10);
+
+        //
+        // Assert
+        //
+        let page_lens: Vec<usize> = pages.iter().map(|p| p.get_out_points().len()).collect();
+        let paged_ops: Vec<OutPoint> = pages
+            .iter()
+            .flat_map(|p| p.get_out_points())
+            .map(|data| data.get_out_point().clone())
+            .collect();
+        let expected_ops: Vec<OutPoint> = utxo_set
+            .get_pk_cache_vec("addr_1")
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(page_lens, vec![10, 10, 10, 10, 10, 10, 10, 10, 10, 5]);
+        assert_eq!(paged_ops, expected_ops);
+        assert!(paged_ops.windows(2).all(|w| w[0] < w[1]));
+        assert!(empty_page.get_out_points().is_empty());
+        assert_eq!(empty_page.get_next_cursor(), None);
+    }
+
+    /// Chunks of the `UtxoSet` entries cover all the requested entries once, in order
+    #[test]
+    fn utxo_entries_chunks_cover_all_entries() {
+        //
+        // Arrange
+        //
+        let base: UtxoSet = (0..25)
+            .map(|i| {
+                let address = if i % 5 == 0 { "addr_2" } else { "addr_1" };
+                (OutPoint::new(format!("{:06}", i), 0), tx_out(address))
+            })
+            .collect();
+        let utxo_set = TrackedUtxoSet::new(base.clone());
+        let chunked = |addresses: &[String]| {
+            let mut chunks = Vec::new();
+            let mut cursor: Option<OutPoint> = None;
+            loop {
+                let chunk =
+                    utxo_set.get_utxo_entries_chunk_for_addresses(addresses, cursor.as_ref(), 4);
+                cursor = chunk.last().map(|(op, _)| op.clone());
+                if chunk.is_empty() {
+                    break;
+                }
+                chunks.push(chunk);
+            }
+            chunks
+        };
+
+        //
+        // Act
+        //
+        let all_chunks = chunked(&[]);
+        let addr_chunks = chunked(&["addr_2".to_owned(), "addr_3".to_owned()]);
+
+        //
+        // Assert
+        //
+        let chunk_lens = |chunks: &[Vec<(OutPoint, TxOut)>]| -> Vec<usize> {
+            chunks.iter().map(|c| c.len()).collect()
+        };
+        let all_entries: Vec<_> = all_chunks.iter().flatten().cloned().collect();
+        let addr_ops: Vec<_> = addr_chunks.iter().flatten().map(|(op, _)| op).collect();
+        let expected_addr_ops: Vec<_> = utxo_set
+            .get_pk_cache_vec("addr_2")
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(chunk_lens(&all_chunks), vec![4, 4, 4, 4, 4, 4, 1]);
+        assert_eq!(all_entries, base.into_iter().collect::<Vec<_>>());
+        assert_eq!(chunk_lens(&addr_chunks), vec![4, 1]);
+        assert_eq!(addr_ops, expected_addr_ops);
     }
 }
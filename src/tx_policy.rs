@@ -0,0 +1,35 @@
+use crate::interfaces::UtxoSet;
+use naom::primitives::transaction::Transaction;
+use std::fmt;
+
+/// Outcome of an operator policy check on a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    Accept,
+    Reject(String),
+}
+
+/// Operator policy consulted by the compute node before accepting transactions
+/// that already passed the built-in validation.
+///
+/// Policies are local to the node: they only decide what enters its transaction pool,
+/// not the validity of transactions in blocks from other nodes.
+pub trait TxPolicy: fmt::Debug + Send + Sync {
+    /// Decide whether a transaction is accepted
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx`   - Transaction received by the node
+    /// * `utxo` - Committed UTXO set the transaction inputs are spent from
+    fn accept(&self, tx: &Transaction, utxo: &UtxoSet) -> PolicyDecision;
+}
+
+/// Default policy accepting all transactions
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AcceptAllPolicy;
+
+impl TxPolicy for AcceptAllPolicy {
+    fn accept(&self, _tx: &Transaction, _utxo: &UtxoSet) -> PolicyDecision {
+        PolicyDecision::Accept
+    }
+}
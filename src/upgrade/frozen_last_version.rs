@@ -734,6 +734,10 @@ pub mod convert {
             current_circulation: convert_token_amount(old.current_circulation),
             miner_whitelist: Default::default(), // Will require sensible conversion on next upgrade
             special_handling,
+            dust_threshold: Default::default(),
+            min_block_reward: Default::default(),
+            reward_halving: Default::default(),
+            winning_miner_fairness: Default::default(),
         }
     }
 
@@ -11,8 +11,8 @@ mod tests_last_version_db;
 
 use crate::configurations::{DbMode, ExtraNodeParams, UnicornFixedInfo};
 use crate::constants::{
-    BLOCK_PREPEND, DB_PATH, DB_VERSION_KEY, FUND_KEY, NETWORK_VERSION_SERIALIZED, TX_PREPEND,
-    WALLET_PATH,
+    BLOCK_PREPEND, DB_PATH, DB_VERSION_KEY, FUND_KEY, INDEXED_BLOCK_HASH_PREFIX_KEY,
+    NETWORK_VERSION_SERIALIZED, TX_PREPEND, WALLET_PATH,
 };
 use crate::db_utils::{
     new_db_no_check_version, new_db_with_version, SimpleDb, SimpleDbError, SimpleDbSpec,
@@ -23,10 +23,14 @@ use crate::utils::StringError;
 use crate::{compute, compute_raft, raft_store, storage, storage_raft, user, wallet};
 use bincode::{deserialize, serialize};
 use frozen_last_version as old;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::fmt;
 use tracing::error;
 
+/// Number of blockchain items processed between storage upgrade progress reports
+pub const UPGRADE_PROGRESS_INTERVAL: usize = 1000;
+
 pub const DB_SPEC_INFOS: &[DbSpecInfo] = &[
     DbSpecInfo {
         node_type: "compute",
@@ -77,6 +81,21 @@ pub struct UpgradeCfg {
     pub compute_partition_full_size: usize,
     pub compute_unicorn_fixed_param: UnicornFixedInfo,
     pub passphrase: String,
+    /// Run all upgrade steps without writing the result to the db
+    pub validate_only: bool,
+}
+
+/// Summary of a database content
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DbReport {
+    /// Stored network/db version
+    pub version: Option<Vec<u8>>,
+    /// Number of keys in each open column
+    pub column_key_counts: BTreeMap<String, usize>,
+    /// Number of distinct block keys accross all columns
+    pub block_count: usize,
+    /// Highest indexed block number if any
+    pub last_block_num: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -92,6 +111,7 @@ pub enum UpgradeError {
     DbError(SimpleDbError),
     Serialization(bincode::Error),
     StringError(StringError),
+    ValidationError(&'static str),
     WalletError(wallet::WalletDbError),
 }
 
@@ -102,6 +122,7 @@ impl fmt::Display for UpgradeError {
             Self::DbError(err) => write!(f, "DB error: {err}"),
             Self::Serialization(err) => write!(f, "Serialization error: {err}"),
             Self::StringError(err) => write!(f, "String error: {err}"),
+            Self::ValidationError(err) => write!(f, "Validation error: {err}"),
             Self::WalletError(err) => write!(f, "Wallet error: {err}"),
         }
     }
@@ -114,6 +135,7 @@ impl Error for UpgradeError {
             Self::DbError(ref e) => Some(e),
             Self::Serialization(ref e) => Some(e),
             Self::StringError(ref e) => Some(e),
+            Self::ValidationError(_) => None,
             Self::WalletError(ref e) => Some(e),
         }
     }
@@ -143,6 +165,71 @@ impl From<wallet::WalletDbError> for UpgradeError {
     }
 }
 
+/// Upgrade DB: run the given upgrade against an in memory working copy of the dbs,
+/// and only replace the dbs content once the upgrade and its status are valid.
+/// On failure, the error is returned and the given dbs are left untouched.
+/// With `validate_only`, the working copy is still fully upgraded and validated,
+/// but the given dbs are never replaced.
+pub fn upgrade_transactional(
+    dbs: &mut ExtraNodeParams,
+    upgrade_cfg: &UpgradeCfg,
+    upgrade: impl FnOnce(ExtraNodeParams, &UpgradeCfg) -> Result<(ExtraNodeParams, UpgradeStatus)>,
+) -> Result<UpgradeStatus> {
+    let working = ExtraNodeParams {
+        db: dbs.db.as_ref().map(|db| db.cloned_as_in_memory()),
+        raft_db: dbs.raft_db.as_ref().map(|db| db.cloned_as_in_memory()),
+        wallet_db: dbs.wallet_db.as_ref().map(|db| db.cloned_as_in_memory()),
+        ..Default::default()
+    };
+
+    // The working copy is discarded in validate mode so it can always be written
+    let working_cfg = UpgradeCfg {
+        validate_only: false,
+        ..upgrade_cfg.clone()
+    };
+    let (working, status) = upgrade(working, &working_cfg)?;
+    validate_upgraded_dbs(&working, &status)?;
+    if upgrade_cfg.validate_only {
+        return Ok(status);
+    }
+
+    let to_replace = [
+        (dbs.db.as_mut(), working.db.as_ref()),
+        (dbs.raft_db.as_mut(), working.raft_db.as_ref()),
+        (dbs.wallet_db.as_mut(), working.wallet_db.as_ref()),
+    ];
+    for (db, working_db) in to_replace {
+        if let (Some(db), Some(working_db)) = (db, working_db) {
+            db.replace_content_with(working_db)?;
+        }
+    }
+    Ok(status)
+}
+
+/// Check the upgraded dbs are all at the new version, and the status is consistent
+fn validate_upgraded_dbs(dbs: &ExtraNodeParams, status: &UpgradeStatus) -> Result<()> {
+    for db in [&dbs.db, &dbs.raft_db, &dbs.wallet_db]
+        .into_iter()
+        .flatten()
+    {
+        let version = db.get_cf(DB_COL_DEFAULT, DB_VERSION_KEY)?;
+        if version.as_deref() != Some(NETWORK_VERSION_SERIALIZED) {
+            return Err(UpgradeError::ValidationError(
+                "Upgraded db version mismatch",
+            ));
+        }
+    }
+
+    if let (Some(block), Some(raft_block)) = (status.last_block_num, status.last_raft_block_num) {
+        if raft_block < block {
+            return Err(UpgradeError::ValidationError(
+                "Raft block behind stored block",
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Upgrade DB: New column are added at begining of upgrade and old one removed at the end.
 pub fn get_upgrade_compute_db(
     db_mode: DbMode,
@@ -174,6 +261,9 @@ pub fn upgrade_compute_db(
         (db.batch_writer(), raft_db.batch_writer()),
         upgrade_cfg,
     )?;
+    if upgrade_cfg.validate_only {
+        return Ok((dbs, status));
+    }
     let (batch, raft_batch) = (batch.done(), raft_batch.done());
 
     db.write(batch)?;
@@ -234,7 +324,7 @@ pub fn upgrade_compute_db_batch<'a>(
             .with_unicorn_fixed_param(upgrade_cfg.compute_unicorn_fixed_param.clone())
             .init_block_pipeline_status();
 
-        Ok(serialize(&consensus)?)
+        Ok(compute_raft::serialize_snapshot(&consensus))
     })?;
 
     Ok((batch, raft_batch, status))
@@ -253,6 +343,7 @@ pub fn upgrade_same_version_compute_db(mut dbs: ExtraNodeParams) -> Result<Extra
             || key == compute::USER_NOTIFY_LIST_KEY.as_bytes()
             || key == compute::POW_RANDOM_NUM_KEY.as_bytes()
             || key == compute::POW_PREV_RANDOM_NUM_KEY.as_bytes()
+            || key == compute::MINED_BLOCK_KEY.as_bytes()
         {
             batch.delete_cf(column, &key);
         } else if key == compute::RAFT_KEY_RUN.as_bytes() {
@@ -268,15 +359,17 @@ pub fn upgrade_same_version_compute_db(mut dbs: ExtraNodeParams) -> Result<Extra
     }
 
     clean_same_raft_db(raft_db, &mut raft_batch, |k, v| {
+        let consensus = compute_raft::deserialize_snapshot(&v, &Default::default())
+            .map_err(|e| key_value_error(&format!("ComputeConsensused: {e:?}"), k, &v))?;
         let mut consensus = compute_raft::ComputeConsensused::into_import(
-            tracked_deserialize("ComputeConsensused", k, &v)?,
+            consensus,
             Some(compute_raft::SpecialHandling::FirstUpgradeBlock),
         );
         // Version 0.3.0 coordinated shutdown should never have a block in snapshoot
         consensus.current_block = None;
-        Ok(serialize(&compute_raft::ComputeConsensused::from_import(
-            consensus,
-        ))?)
+        Ok(compute_raft::serialize_snapshot(
+            &compute_raft::ComputeConsensused::from_import(consensus),
+        ))
     })?;
 
     let (batch, raft_batch) = (batch.done(), raft_batch.done());
@@ -297,6 +390,8 @@ pub fn get_upgrade_storage_db(
     let raft_db = new_db_with_version(db_mode, raft_spec, version, old_dbs.raft_db, None)?;
 
     db.upgrade_create_missing_cf(storage::DB_COL_BC_NOW)?;
+    db.upgrade_create_missing_cf(storage::DB_COL_UTXO_CHECKPOINT)?;
+    db.upgrade_create_missing_cf(storage::DB_COL_ADDRESS_TX)?;
     Ok(ExtraNodeParams {
         db: Some(db),
         raft_db: Some(raft_db),
@@ -306,8 +401,18 @@ pub fn get_upgrade_storage_db(
 
 /// Upgrade DB: upgrade ready given db  .
 pub fn upgrade_storage_db(
+    dbs: ExtraNodeParams,
+    upgrade_cfg: &UpgradeCfg,
+) -> Result<(ExtraNodeParams, UpgradeStatus)> {
+    upgrade_storage_db_with_progress(dbs, upgrade_cfg, |_, _| ())
+}
+
+/// Upgrade DB: upgrade ready given db, reporting (processed, total) blockchain items
+/// to `progress` every `UPGRADE_PROGRESS_INTERVAL` items and on completion.
+pub fn upgrade_storage_db_with_progress(
     mut dbs: ExtraNodeParams,
     upgrade_cfg: &UpgradeCfg,
+    progress: impl FnMut(usize, usize),
 ) -> Result<(ExtraNodeParams, UpgradeStatus)> {
     let db = dbs.db.as_mut().unwrap();
     let raft_db = dbs.raft_db.as_mut().unwrap();
@@ -316,7 +421,11 @@ pub fn upgrade_storage_db(
         (db, raft_db),
         (db.batch_writer(), raft_db.batch_writer()),
         upgrade_cfg,
+        progress,
     )?;
+    if upgrade_cfg.validate_only {
+        return Ok((dbs, status));
+    }
     let (batch, raft_batch) = (batch.done(), raft_batch.done());
 
     db.write(batch)?;
@@ -329,6 +438,7 @@ pub fn upgrade_storage_db_batch<'a>(
     (db, raft_db): (&SimpleDb, &SimpleDb),
     (mut batch, mut raft_batch): (SimpleDbWriteBatch<'a>, SimpleDbWriteBatch<'a>),
     upgrade_cfg: &UpgradeCfg,
+    mut progress: impl FnMut(usize, usize),
 ) -> Result<(
     SimpleDbWriteBatch<'a>,
     SimpleDbWriteBatch<'a>,
@@ -351,7 +461,11 @@ pub fn upgrade_storage_db_batch<'a>(
     }
 
     let column = old::storage::DB_COL_BC_V0_6_0;
-    for (key, value) in db.iter_cf_clone(column) {
+    let total = db.count_cf(column);
+    for (processed, (key, value)) in db.iter_cf_clone(column).enumerate() {
+        if processed % UPGRADE_PROGRESS_INTERVAL == 0 {
+            progress(processed, total);
+        }
         if is_transaction_key(&key) {
             let _: old::naom::Transaction = tracked_deserialize("Tx deserialize", &key, &value)?;
         } else if is_block_key(&key) {
@@ -363,6 +477,7 @@ pub fn upgrade_storage_db_batch<'a>(
             return Err(key_value_error("Unexpected key", &key, &value));
         }
     }
+    progress(total, total);
 
     clean_raft_db(raft_db, &mut raft_batch, |k, v| {
         let consensus = old::convert_storage_consensused_to_import(tracked_deserialize(
@@ -483,6 +598,9 @@ pub fn upgrade_wallet_db(
 ) -> Result<(ExtraNodeParams, UpgradeStatus)> {
     let db = dbs.wallet_db.as_mut().unwrap();
     let (batch, status) = upgrade_wallet_db_batch(db, db.batch_writer(), upgrade_cfg)?;
+    if upgrade_cfg.validate_only {
+        return Ok((dbs, status));
+    }
     let batch = batch.done();
     db.write(batch)?;
     Ok((dbs, status))
@@ -497,7 +615,8 @@ pub fn upgrade_wallet_db_batch<'a>(
     batch.put_cf(DB_COL_DEFAULT, DB_VERSION_KEY, NETWORK_VERSION_SERIALIZED);
 
     let passphrase = upgrade_cfg.passphrase.as_bytes();
-    let masterkey = wallet::get_or_save_master_key_store(db, &mut batch, passphrase)?;
+    let masterkey =
+        wallet::get_or_save_master_key_store(db, &mut batch, passphrase, &Default::default())?;
 
     for (key, value) in db.iter_cf_clone(DB_COL_DEFAULT) {
         if key == DB_VERSION_KEY.as_bytes() {
@@ -606,6 +725,37 @@ pub fn dump_db(db: &'_ SimpleDb) -> impl Iterator<Item = String> + '_ {
         .map(|(c, k, v)| format!("\"{c}\", b\"{k}\", b\"{v}\""))
 }
 
+/// Summarize the database content: version, key counts and blocks stored
+pub fn inspect_db(db: &SimpleDb) -> Result<DbReport> {
+    let mut report = DbReport {
+        version: db.get_cf(DB_COL_DEFAULT, DB_VERSION_KEY)?,
+        ..Default::default()
+    };
+
+    let mut block_keys = BTreeSet::new();
+    for (column, items) in db.iter_all_cf_clone() {
+        let mut count = 0;
+        for (key, _) in items {
+            count += 1;
+            if is_block_key(&key) {
+                block_keys.insert(key);
+            } else if let Some(b_num) = indexed_block_num(&key) {
+                report.last_block_num = std::cmp::max(report.last_block_num, Some(b_num));
+            }
+        }
+        report.column_key_counts.insert(column, count);
+    }
+    report.block_count = block_keys.len();
+
+    Ok(report)
+}
+
+/// Block number for an indexed block hash key
+fn indexed_block_num(key: &[u8]) -> Option<u64> {
+    let b_num = key.strip_prefix(INDEXED_BLOCK_HASH_PREFIX_KEY.as_bytes())?;
+    u64::from_str_radix(std::str::from_utf8(b_num).ok()?, 16).ok()
+}
+
 /// Convert to a valid array literal displaying ASCII nicely
 fn to_u8_array_literal(value: &[u8]) -> String {
     let mut result = String::with_capacity(value.len());
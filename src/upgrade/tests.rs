@@ -1,11 +1,11 @@
 use super::tests_last_version_db::{self, DbEntryType};
 use super::{
-    dump_db, get_upgrade_compute_db, get_upgrade_storage_db, get_upgrade_wallet_db, old,
-    upgrade_compute_db, upgrade_storage_db, upgrade_wallet_db, UpgradeCfg, UpgradeError,
-    UpgradeStatus,
+    dump_db, get_upgrade_compute_db, get_upgrade_storage_db, get_upgrade_wallet_db, inspect_db,
+    old, upgrade_compute_db, upgrade_storage_db, upgrade_storage_db_with_progress,
+    upgrade_transactional, upgrade_wallet_db, UpgradeCfg, UpgradeError, UpgradeStatus,
 };
 use crate::configurations::{DbMode, ExtraNodeParams, UserAutoGenTxSetup, WalletTxSpec};
-use crate::constants::{LAST_BLOCK_HASH_KEY, NETWORK_VERSION_SERIALIZED};
+use crate::constants::{DB_VERSION_KEY, LAST_BLOCK_HASH_KEY, NETWORK_VERSION_SERIALIZED};
 use crate::db_utils::{
     new_db, new_db_with_version, SimpleDb, SimpleDbError, SimpleDbSpec, DB_COL_DEFAULT,
 };
@@ -273,6 +273,228 @@ async fn upgrade_common(config: NetworkConfig, name: &str, upgrade_cfg: UpgradeC
     test_step_complete(network).await;
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_validate_only_compute_in_memory() {
+    upgrade_validate_only_common(complete_network_config(20050), "compute1").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_validate_only_storage_in_memory() {
+    upgrade_validate_only_common(complete_network_config(20060), "storage1").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_validate_only_user_in_memory() {
+    upgrade_validate_only_common(complete_network_config(20070), "user1").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_storage_reports_progress_in_memory() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let config = complete_network_config(20080);
+    let network = Network::create_stopped_from_config(&config);
+    let n_info = network.get_node_info("storage1").unwrap().clone();
+    let db = create_old_node_db(&n_info);
+    let db = get_upgrade_node_db(&n_info, in_memory(db)).unwrap();
+    let expected_total = db
+        .db
+        .as_ref()
+        .unwrap()
+        .count_cf(old::storage::DB_COL_BC_V0_6_0);
+
+    //
+    // Act
+    //
+    let mut calls = Vec::new();
+    upgrade_storage_db_with_progress(db, &cfg_upgrade(), |processed, total| {
+        calls.push((processed, total))
+    })
+    .unwrap();
+
+    //
+    // Assert
+    //
+    assert!(expected_total > 0);
+    assert!(calls.iter().all(|(_, total)| *total == expected_total));
+    assert!(calls.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert_eq!(calls.last(), Some(&(expected_total, expected_total)));
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn inspect_old_storage_db_in_memory() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let config = complete_network_config(20160);
+    let network = Network::create_stopped_from_config(&config);
+    let n_info = network.get_node_info("storage1").unwrap().clone();
+    let db = create_old_node_db(&n_info);
+
+    //
+    // Act
+    //
+    let report = inspect_db(db.db.as_ref().unwrap()).unwrap();
+
+    //
+    // Assert
+    //
+    let expected_keys = tests_last_version_db::STORAGE_DB_V0_6_0.len();
+    assert_eq!(
+        report.version.as_deref(),
+        old::constants::NETWORK_VERSION_SERIALIZED
+    );
+    assert_eq!(
+        report.column_key_counts.values().sum::<usize>(),
+        expected_keys
+    );
+    assert_eq!(report.block_count, LAST_BLOCK_STORED_NUM as usize + 1);
+    assert_eq!(report.last_block_num, Some(LAST_BLOCK_STORED_NUM));
+
+    test_step_complete(network).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_failure_rolled_back_compute_in_memory() {
+    upgrade_failure_rolled_back_common(complete_network_config(20090), "compute1").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_failure_rolled_back_storage_in_memory() {
+    upgrade_failure_rolled_back_common(complete_network_config(20150), "storage1").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn upgrade_transactional_validate_only_compute_in_memory() {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let config = complete_network_config(20170);
+    let network = Network::create_stopped_from_config(&config);
+    let n_info = network.get_node_info("compute1").unwrap().clone();
+    let db = create_old_node_db(&n_info);
+    let mut db = get_upgrade_node_db(&n_info, in_memory(db)).unwrap();
+    let versions_before = get_db_versions(&db);
+    let upgrade_cfg = UpgradeCfg {
+        validate_only: true,
+        ..cfg_upgrade()
+    };
+
+    //
+    // Act
+    //
+    let valid = upgrade_transactional(&mut db, &upgrade_cfg, |dbs, upgrade_cfg| {
+        upgrade_node_db(&n_info, dbs, upgrade_cfg)
+    });
+    let not_upgraded = upgrade_transactional(&mut db, &upgrade_cfg, |dbs, _| {
+        Ok((dbs, Default::default()))
+    });
+
+    //
+    // Assert
+    //
+    assert_eq!(
+        valid.map(|s| s.last_raft_block_num).ok(),
+        Some(Some(LAST_BLOCK_STORED_NUM))
+    );
+    assert!(matches!(
+        not_upgraded,
+        Err(UpgradeError::ValidationError(
+            "Upgraded db version mismatch"
+        ))
+    ));
+    assert_eq!(get_db_versions(&db), versions_before);
+
+    test_step_complete(network).await;
+}
+
+async fn upgrade_failure_rolled_back_common(config: NetworkConfig, name: &str) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let network = Network::create_stopped_from_config(&config);
+    let n_info = network.get_node_info(name).unwrap().clone();
+    let db = create_old_node_db(&n_info);
+    let mut db = get_upgrade_node_db(&n_info, in_memory(db)).unwrap();
+    let versions_before = get_db_versions(&db);
+
+    //
+    // Act
+    //
+    let result = upgrade_transactional(&mut db, &cfg_upgrade(), |dbs, upgrade_cfg| {
+        // Fail after the working copy has been fully upgraded
+        let _ = upgrade_node_db(&n_info, dbs, upgrade_cfg)?;
+        Err(UpgradeError::ConfigError("Injected failure"))
+    });
+
+    //
+    // Assert
+    //
+    assert!(matches!(
+        result,
+        Err(UpgradeError::ConfigError("Injected failure"))
+    ));
+    assert_eq!(get_db_versions(&db), versions_before);
+    assert!(open_as_old_node_db(&n_info, in_memory(db)).is_ok());
+
+    test_step_complete(network).await;
+}
+
+async fn upgrade_validate_only_common(config: NetworkConfig, name: &str) {
+    test_step_start();
+
+    //
+    // Arrange
+    //
+    let network = Network::create_stopped_from_config(&config);
+    let n_info = network.get_node_info(name).unwrap().clone();
+    let db = create_old_node_db(&n_info);
+    let upgrade_cfg = UpgradeCfg {
+        validate_only: true,
+        ..cfg_upgrade()
+    };
+
+    let db = get_upgrade_node_db(&n_info, in_memory(db)).unwrap();
+    let versions_before = get_db_versions(&db);
+
+    //
+    // Act
+    //
+    let (db, status) = upgrade_node_db(&n_info, db, &upgrade_cfg).unwrap();
+
+    //
+    // Assert
+    //
+    let versions_after = get_db_versions(&db);
+    let new_version = Some(NETWORK_VERSION_SERIALIZED.to_vec());
+    assert!(!versions_after.is_empty());
+    assert_eq!(versions_after, versions_before);
+    assert!(versions_after.iter().all(|v| *v != new_version));
+
+    let expected_status = match n_info.node_type {
+        NodeType::Compute => (None, Some(LAST_BLOCK_STORED_NUM)),
+        NodeType::Storage => (Some(LAST_BLOCK_STORED_NUM), Some(LAST_BLOCK_STORED_NUM + 1)),
+        NodeType::User | NodeType::Miner => (None, None),
+    };
+    assert_eq!(
+        (status.last_block_num, status.last_raft_block_num),
+        expected_status
+    );
+
+    test_step_complete(network).await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn open_upgrade_started_compute_real_db() {
     let config = real_db(complete_network_config(20100));
@@ -482,7 +704,7 @@ async fn upgrade_restart_network_common(
             actual_count.push(count);
             actual_last_bnum.push(last_bnum);
 
-            let (db, _, _, _, _) = storage.api_inputs();
+            let (db, _, _, _, _, _) = storage.api_inputs();
             let db = db.lock().unwrap();
             info!(
                 "dump_db {}: count:{} b_num:{:?}, \n{}",
@@ -702,7 +924,20 @@ fn complete_network_config(initial_port: u16) -> NetworkConfig {
         storage_raft: true,
         in_memory_db: true,
         compute_partition_full_size: 1,
+        compute_partition_pow_difficulty: None,
         compute_minimum_miner_pool_len: 1,
+        compute_first_block_grace_ms: None,
+        compute_miner_max_missed_floods: None,
+        compute_block_stall_timeout: None,
+        compute_partition_random_num_rotation: None,
+        compute_close_full_participant_intake: None,
+        compute_winning_miner_fairness: None,
+        compute_min_tx_fee: Default::default(),
+        compute_dust_threshold: Default::default(),
+        compute_max_locktime_offset: None,
+        compute_max_block_size: None,
+        compute_utxo_set_size_cap: None,
+        compute_receipt_minters: None,
         nodes: vec![(NodeType::User, vec!["user1".to_string()])]
             .into_iter()
             .collect(),
@@ -710,21 +945,28 @@ fn complete_network_config(initial_port: u16) -> NetworkConfig {
         compute_genesis_tx_in: None,
         user_wallet_seeds: Default::default(),
         compute_to_miner_mapping: Default::default(),
+        compute_to_storage_mapping: Default::default(),
         test_duration_divider: 1,
         passphrase: Some(WALLET_PASSWORD.to_owned()),
         user_auto_donate: 0,
+        user_auto_donate_max_per_peer: None,
+        user_auto_donate_max_total: None,
+        user_auto_donate_window_secs: None,
         user_test_auto_gen_setup: Default::default(),
         tls_config: get_test_tls_spec(),
         routes_pow: Default::default(),
         backup_block_modulo: Default::default(),
         utxo_re_align_block_modulo: Default::default(),
         backup_restore: Default::default(),
+        storage_sync_block_ack: Default::default(),
         enable_pipeline_reset: Default::default(),
         static_miner_address: Default::default(),
+        miner_reward_address: Default::default(),
         mining_api_key: Default::default(),
         compute_miner_whitelist: Default::default(),
         peer_limit: 1000,
         address_aggregation_limit: Some(5),
+        wallet_max_active_addresses: Default::default(),
     }
     .with_groups(1, 1)
 }
@@ -748,6 +990,7 @@ fn cfg_upgrade() -> UpgradeCfg {
         compute_partition_full_size: 1,
         compute_unicorn_fixed_param: get_test_common_unicorn(),
         passphrase: WALLET_PASSWORD.to_owned(),
+        validate_only: false,
     }
 }
 
@@ -814,6 +1057,14 @@ fn get_test_auto_gen_setup(count_override: Option<usize>) -> UserAutoGenTxSetup
     }
 }
 
+fn get_db_versions(dbs: &ExtraNodeParams) -> Vec<Option<Vec<u8>>> {
+    [&dbs.db, &dbs.raft_db, &dbs.wallet_db]
+        .into_iter()
+        .flatten()
+        .map(|db| db.get_cf(DB_COL_DEFAULT, DB_VERSION_KEY).unwrap())
+        .collect()
+}
+
 fn in_memory(dbs: ExtraNodeParams) -> ExtraNodeParams {
     ExtraNodeParams {
         db: dbs.db.and_then(|v| v.in_memory()),
@@ -875,13 +1126,13 @@ async fn raft_node_handle_event(network: &mut Network, node: &str, reason_val: &
     if let Some(n) = network.compute(node) {
         let mut n = n.lock().await;
         match n.handle_next_event(&mut test_timeout()).await {
-            Some(Ok(Response { success, reason })) if success && reason == reason_val => {}
+            Some(Ok(Response { success, reason })) if success && reason.as_str() == reason_val => {}
             other => panic!("Unexpected result: {:?} (expected:{})", other, reason_val),
         }
     } else if let Some(n) = network.storage(node) {
         let mut n = n.lock().await;
         match n.handle_next_event(&mut test_timeout()).await {
-            Some(Ok(Response { success, reason })) if success && reason == reason_val => {}
+            Some(Ok(Response { success, reason })) if success && reason.as_str() == reason_val => {}
             other => panic!("Unexpected result: {:?} (expected:{})", other, reason_val),
         }
     }
@@ -1,21 +1,23 @@
 use crate::comms_handler::{CommsError, Event, Node, TcpTlsConfig};
 use crate::configurations::{ExtraNodeParams, TlsPrivateInfo, UserAutoGenTxSetup, UserNodeConfig};
+use crate::db_utils;
 use crate::interfaces::{
-    ComputeRequest, NodeType, RbPaymentData, RbPaymentRequestData, RbPaymentResponseData, Response,
-    UserApiRequest, UserRequest, UtxoFetchType, UtxoSet,
+    CommsFormat, ComputeRequest, NodeType, RbPaymentData, RbPaymentRequestData,
+    RbPaymentResponseData, Response, ResponseReason, UserApiRequest, UserRequest, UtxoFetchType,
+    UtxoSet,
 };
 use crate::threaded_call::{ThreadedCallChannel, ThreadedCallSender};
 use crate::transaction_gen::{PendingMap, TransactionGen};
 use crate::transactor::Transactor;
 use crate::utils::{
-    generate_half_druid, get_paiments_for_wallet_from_utxo, to_api_keys, to_route_pow_infos,
+    generate_half_druid, get_paiments_for_wallet_from_utxo, to_route_pow_infos, to_signed_api_keys,
     try_send_to_ui, ApiKeys, LocalEvent, LocalEventChannel, LocalEventSender, ResponseResult,
     RoutesPoWInfo,
 };
-use crate::wallet::{AddressStore, WalletDb, WalletDbError};
+use crate::wallet::{AddressStore, KdfParams, PassphraseConfig, WalletDb, WalletDbError, DB_SPEC};
 use crate::Rs2JsMsg;
 use async_trait::async_trait;
-use bincode::deserialize;
+use bincode::{deserialize, serialize};
 use bytes::Bytes;
 use naom::primitives::asset::{Asset, TokenAmount};
 use naom::primitives::block::Block;
@@ -23,12 +25,19 @@ use naom::primitives::druid::DruidExpectation;
 use naom::primitives::transaction::{DrsTxHashSpec, Transaction, TxIn, TxOut};
 use naom::utils::transaction_utils::{
     construct_rb_payments_send_tx, construct_rb_receive_payment_tx, construct_receipt_create_tx,
-    construct_tx_core, construct_tx_ins_address,
+    construct_tx_core, construct_tx_hash, construct_tx_ins_address,
 };
 use serde::Serialize;
 use std::collections::BTreeSet;
 
-use std::{collections::BTreeMap, error::Error, fmt, future::Future, net::SocketAddr};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 use tokio::sync::mpsc;
 use tokio::task;
 use tracing::{debug, error, error_span, info, info_span, trace, warn};
@@ -37,6 +46,22 @@ use tracing_futures::Instrument;
 /// Key for last pow coinbase produced
 pub const TX_GENERATOR_KEY: &str = "TxGeneratorKey";
 
+/// Key for outbound payment transactions not yet acknowledged by compute
+pub const OUTBOUND_PAYMENTS_KEY: &str = "OutboundPaymentsKey";
+
+/// Seconds after which an outbound payment not acknowledged is dropped:
+/// it may have been included in a block while the node was not running
+pub const OUTBOUND_PAYMENT_EXPIRY_SECS: u64 = 24 * 60 * 60;
+
+/// Outbound payment transaction with the time in seconds it was queued at
+pub type OutboundPayment = (u64, Transaction);
+
+/// Maximum number of attempts at sending startup requests
+pub const STARTUP_REQUESTS_MAX_ATTEMPTS: usize = 6;
+
+/// Delay before retrying startup requests, doubled after each failed attempt
+pub const STARTUP_REQUESTS_RETRY_DELAY: Duration = Duration::from_millis(100);
+
 /// Result wrapper for user errors
 pub type Result<T> = std::result::Result<T, UserError>;
 
@@ -47,6 +72,7 @@ pub enum UserError {
     AsyncTask(task::JoinError),
     Serialization(bincode::Error),
     WalletError(WalletDbError),
+    StartupRequestsExhausted(usize, Box<UserError>),
 }
 
 impl fmt::Display for UserError {
@@ -57,6 +83,12 @@ impl fmt::Display for UserError {
             Self::AsyncTask(err) => write!(f, "Async task error: {err}"),
             Self::Serialization(err) => write!(f, "Serialization error: {err}"),
             Self::WalletError(err) => write!(f, "Wallet error: {err}"),
+            Self::StartupRequestsExhausted(attempts, err) => {
+                write!(
+                    f,
+                    "Startup requests failed after {attempts} attempts: {err}"
+                )
+            }
         }
     }
 }
@@ -69,6 +101,7 @@ impl Error for UserError {
             Self::Serialization(ref e) => Some(e),
             Self::AsyncTask(ref e) => Some(e),
             Self::WalletError(ref e) => Some(e),
+            Self::StartupRequestsExhausted(_, ref e) => Some(e.as_ref()),
         }
     }
 }
@@ -119,6 +152,68 @@ pub enum AutoDonate {
     Enabled(TokenAmount),
 }
 
+/// Caps on the amounts auto donated within a window, and the amounts donated in the current one.
+/// Peers are identified by IP address so reconnecting from a new port does not reset their cap.
+#[derive(Debug, Default)]
+pub struct AutoDonateLimits {
+    max_per_peer: Option<u64>,
+    max_total: Option<u64>,
+    window: Option<Duration>,
+    window_start: Option<Instant>,
+    donated_per_peer: BTreeMap<IpAddr, u64>,
+    donated_total: u64,
+}
+
+impl AutoDonateLimits {
+    /// Check a donation of amount to the peer stays within the caps,
+    /// starting a new window first if the current one has elapsed
+    ///
+    /// ### Arguments
+    ///
+    /// * `peer`   - IP address of the peer requesting the donation
+    /// * `amount` - Amount to donate
+    /// * `now`    - Time of the request
+    fn check(
+        &mut self,
+        peer: IpAddr,
+        amount: TokenAmount,
+        now: Instant,
+    ) -> std::result::Result<(), ResponseReason> {
+        let window_start = *self.window_start.get_or_insert(now);
+        if matches!(self.window, Some(window) if now.duration_since(window_start) >= window) {
+            self.window_start = Some(now);
+            self.donated_per_peer.clear();
+            self.donated_total = 0;
+        }
+
+        let peer_donated = self
+            .donated_per_peer
+            .get(&peer)
+            .copied()
+            .unwrap_or_default();
+        if matches!(self.max_per_peer, Some(max) if peer_donated.saturating_add(amount.0) > max) {
+            return Err(ResponseReason::IgnoreDonationOverPeerLimit);
+        }
+        if matches!(self.max_total, Some(max) if self.donated_total.saturating_add(amount.0) > max)
+        {
+            return Err(ResponseReason::IgnoreDonationOverTotalLimit);
+        }
+        Ok(())
+    }
+
+    /// Record a donation of amount to the peer
+    ///
+    /// ### Arguments
+    ///
+    /// * `peer`   - IP address of the peer requesting the donation
+    /// * `amount` - Amount donated
+    fn record(&mut self, peer: IpAddr, amount: TokenAmount) {
+        let peer_donated = self.donated_per_peer.entry(peer).or_default();
+        *peer_donated = peer_donated.saturating_add(amount.0);
+        self.donated_total = self.donated_total.saturating_add(amount.0);
+    }
+}
+
 /// An instance of a UserNode
 #[derive(Debug)]
 pub struct UserNode {
@@ -135,6 +230,7 @@ pub struct UserNode {
     test_auto_gen_tx: Option<AutoGenTx>,
     received_utxo_set: Option<UtxoSet>,
     pending_payments: (BTreeMap<SocketAddr, PendingPayment>, AutoDonate),
+    auto_donate_limits: AutoDonateLimits,
     next_rb_payment_response: Option<(SocketAddr, Option<RbPaymentResponseData>)>,
     next_rb_payment_data: Option<RbPaymentData>,
     next_rb_payment: Option<(Option<SocketAddr>, Transaction)>,
@@ -159,7 +255,7 @@ impl UserNode {
         let api_tls_info = config
             .user_api_use_tls
             .then(|| tcp_tls_config.clone_private_info());
-        let api_keys = to_api_keys(config.api_keys.clone());
+        let api_keys = to_signed_api_keys(config.api_keys.clone(), config.api_signing_keys.clone());
         let api_pow_info = to_route_pow_infos(config.routes_pow.clone());
         let disable_tcp_listener = extra.disable_tcp_listener;
         let node = Node::new(
@@ -170,30 +266,66 @@ impl UserNode {
             false,
         )
         .await?;
+        node.set_comms_format(config.comms_format).await;
+        node.set_compatible_network_versions(config.compatible_network_versions.iter().copied())
+            .await;
 
+        let custom_wallet_spec = extra.custom_wallet_spec.or_else(|| {
+            db_utils::data_dir_db_spec(
+                config.data_dir.as_deref(),
+                &format!("user.{}", addr.port()),
+                &DB_SPEC,
+            )
+        });
+        let passphrase_config = PassphraseConfig {
+            min_length: config.wallet_passphrase_min_length,
+            min_entropy_bits: config.wallet_passphrase_min_entropy_bits,
+            kdf: config
+                .wallet_kdf_iterations
+                .map(|iterations| KdfParams { iterations })
+                .unwrap_or_default(),
+        };
         let wallet_db = match extra.shared_wallet_db {
             Some(shared_db) => shared_db,
-            None => WalletDb::new(
+            None => WalletDb::new_with_passphrase_config_and_tuning(
                 config.user_db_mode,
                 extra.wallet_db.take(),
                 config.passphrase,
-                extra.custom_wallet_spec,
+                custom_wallet_spec,
+                passphrase_config,
+                &config.wallet_db_tuning.clone().unwrap_or_default(),
             )?,
         };
-        let wallet_db = wallet_db.with_seed(config.user_wallet_seeds).await;
+        let wallet_db = wallet_db
+            .with_max_active_addresses(config.wallet_max_active_addresses)
+            .with_encryption_at_rest(config.wallet_encrypted_at_rest.unwrap_or(false))?
+            .with_seed(config.user_wallet_seeds)
+            .await;
 
         let pending_payments = match config.user_auto_donate {
             0 => (Default::default(), AutoDonate::Disabled),
             amount => (Default::default(), AutoDonate::Enabled(TokenAmount(amount))),
         };
 
+        let auto_donate_limits = AutoDonateLimits {
+            max_per_peer: config.user_auto_donate_max_per_peer,
+            max_total: config.user_auto_donate_max_total,
+            window: config.user_auto_donate_window_secs.map(Duration::from_secs),
+            ..Default::default()
+        };
+
         let test_auto_gen_tx = make_transaction_gen(config.user_test_auto_gen_setup);
+        let threaded_calls = ThreadedCallChannel::with_config(
+            config.api_threaded_call_capacity,
+            config.api_threaded_call_timeout_ms,
+        )
+        .map_err(UserError::ConfigError)?;
 
         Ok(UserNode {
             node,
             wallet_db,
             local_events: Default::default(),
-            threaded_calls: Default::default(),
+            threaded_calls,
             ui_feedback_tx: Default::default(),
             compute_addr,
             api_info: (api_addr, api_tls_info, api_keys, api_pow_info),
@@ -203,6 +335,7 @@ impl UserNode {
             test_auto_gen_tx,
             received_utxo_set: None,
             pending_payments,
+            auto_donate_limits,
             next_rb_payment_response: None,
             next_rb_payment_data: None,
             next_rb_payment: None,
@@ -324,10 +457,10 @@ impl UserNode {
         if let Ok(resp) = &response {
             let ui_message = match resp.success {
                 true => Rs2JsMsg::Info {
-                    info: resp.reason.to_owned(),
+                    info: resp.reason.to_string(),
                 },
                 false => Rs2JsMsg::Error {
-                    error: resp.reason.to_owned(),
+                    error: resp.reason.to_string(),
                 },
             };
             try_send_to_ui(self.ui_feedback_tx.as_ref(), ui_message).await;
@@ -336,15 +469,15 @@ impl UserNode {
         match response {
             Ok(Response {
                 success: true,
-                reason: "Sent startup requests on reconnection",
+                reason: ResponseReason::SentStartupRequestsOnReconnection,
             }) => debug!("Sent startup requests on reconnection"),
             Ok(Response {
                 success: false,
-                reason: "Failed to send startup requests on reconnection",
+                reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
             }) => error!("Failed to send startup requests on reconnection"),
             Ok(Response {
                 success: true,
-                reason: "Shutdown",
+                reason: ResponseReason::Shutdown,
             }) => {
                 warn!("Shutdown now");
                 try_send_to_ui(self.ui_feedback_tx.as_ref(), Rs2JsMsg::Exit).await;
@@ -352,19 +485,19 @@ impl UserNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Donation Requested",
+                reason: ResponseReason::DonationRequested,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Request Payment Address",
+                reason: ResponseReason::RequestPaymentAddress,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Payment transaction received",
+                reason: ResponseReason::PaymentTransactionReceived,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Receipt asset create transaction ready",
+                reason: ResponseReason::ReceiptAssetCreateTransactionReady,
             }) => {
                 self.send_next_payment_to_destinations(self.compute_address())
                     .await
@@ -372,7 +505,7 @@ impl UserNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Received receipt-based payment request",
+                reason: ResponseReason::ReceivedReceiptBasedPaymentRequest,
             }) => {
                 self.send_rb_payment_response().await.unwrap();
                 self.send_next_rb_transaction_to_destinations(self.compute_address())
@@ -381,7 +514,7 @@ impl UserNode {
             }
             Ok(Response {
                 success: true,
-                reason: "Received receipt-based payment response",
+                reason: ResponseReason::ReceivedReceiptBasedPaymentResponse,
             }) => {
                 self.send_next_rb_transaction_to_destinations(self.compute_address())
                     .await
@@ -389,26 +522,26 @@ impl UserNode {
             }
             Ok(Response {
                 success: true,
-                reason: "New address ready to be sent",
+                reason: ResponseReason::NewAddressReadyToBeSent,
             }) => {
                 debug!("Sending new payment address");
                 self.send_address_to_trading_peer().await.unwrap();
             }
             Ok(Response {
                 success: true,
-                reason: "New address generated",
+                reason: ResponseReason::NewAddressGenerated,
             }) => {
                 debug!("New address generated");
             }
             Ok(Response {
                 success: true,
-                reason: "Addresses deleted",
+                reason: ResponseReason::AddressesDeleted,
             }) => {
                 debug!("Addresses deleted");
             }
             Ok(Response {
                 success: true,
-                reason: "Next payment transaction ready",
+                reason: ResponseReason::NextPaymentTransactionReady,
             }) => {
                 self.send_next_payment_to_destinations(self.compute_address())
                     .await
@@ -416,59 +549,67 @@ impl UserNode {
             }
             Ok(Response {
                 success: false,
-                reason: "Insufficient funds for payment",
+                reason: ResponseReason::InsufficientFundsForPayment,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Ignore unexpected transaction",
+                reason: ResponseReason::IgnoreUnexpectedTransaction,
+            }) => {}
+            Ok(Response {
+                success: false,
+                reason: ResponseReason::IgnoreDonationOverPeerLimit,
+            }) => {}
+            Ok(Response {
+                success: false,
+                reason: ResponseReason::IgnoreDonationOverTotalLimit,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Block mining notified",
+                reason: ResponseReason::BlockMiningNotified,
             }) => {
                 self.process_mining_notified().await;
             }
             Ok(Response {
                 success: true,
-                reason: "Request UTXO set",
+                reason: ResponseReason::RequestUtxoSet,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Received UTXO set",
+                reason: ResponseReason::ReceivedUtxoSet,
             }) => {
                 self.update_running_total().await;
             }
             Ok(Response {
                 success: true,
-                reason: "Node is connected",
+                reason: ResponseReason::NodeIsConnected,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             }) => {}
             Ok(Response {
                 success: true, // Not always an error
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Connected to compute",
+                reason: ResponseReason::ConnectedToCompute,
             }) => {}
             Ok(Response {
                 success: true,
-                reason: "Disconnected from compute",
+                reason: ResponseReason::DisconnectedFromCompute,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Failed to connect to compute",
+                reason: ResponseReason::FailedToConnectToCompute,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Failed to disconnect from compute",
+                reason: ResponseReason::FailedToDisconnectFromCompute,
             }) => {}
             Ok(Response {
                 success: false,
-                reason: "Already disconnected from compute",
+                reason: ResponseReason::AlreadyDisconnectedFromCompute,
             }) => {}
             Ok(Response {
                 success: true,
@@ -517,20 +658,141 @@ impl UserNode {
                 }
                 reason = &mut *exit => return Some(Ok(Response {
                     success: true,
-                    reason,
+                    reason: ResponseReason::Exit(reason),
                 }))
             }
         }
     }
 
     /// Send initial requests:
-    /// - block notification request (if active)
+    /// - outbound payments not yet acknowledged nor expired (if any)
+    /// - block notification request (if active or awaiting acknowledgement)
+    ///
+    /// Retries with backoff while peers are not reachable, up to a maximum attempt count
     pub async fn send_startup_requests(&mut self) -> Result<()> {
-        if self.is_test_auto_gen_tx_active() {
-            info!("Send startup requests: block notification");
-            return self.send_block_notification_request().await;
+        self.expire_outbound_payments(unix_timestamp_now()).await;
+        let outbound_payments: Vec<_> = self
+            .get_outbound_payments()
+            .await
+            .into_values()
+            .map(|(_, tx)| tx)
+            .collect();
+        if !self.is_test_auto_gen_tx_active() && outbound_payments.is_empty() {
+            return Ok(());
+        }
+
+        let mut attempt = 0;
+        let mut delay = STARTUP_REQUESTS_RETRY_DELAY;
+        loop {
+            attempt += 1;
+            match self.try_send_startup_requests(&outbound_payments).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= STARTUP_REQUESTS_MAX_ATTEMPTS => {
+                    return Err(UserError::StartupRequestsExhausted(attempt, Box::new(e)));
+                }
+                Err(e) => {
+                    warn!("Send startup requests attempt {attempt} failed, retry: {e}");
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+    }
+
+    /// Send initial requests once
+    ///
+    /// ### Arguments
+    ///
+    /// * `outbound_payments` - Outbound payment transactions to re-send
+    async fn try_send_startup_requests(&mut self, outbound_payments: &[Transaction]) -> Result<()> {
+        if !outbound_payments.is_empty() {
+            info!(
+                "Send startup requests: {} outbound payments",
+                outbound_payments.len()
+            );
+            self.send_transactions_to_compute(self.compute_addr, outbound_payments.to_vec())
+                .await?;
+        }
+
+        info!("Send startup requests: block notification");
+        self.send_block_notification_request().await
+    }
+
+    /// Get the outbound payment transactions not yet acknowledged by compute, by hash
+    pub async fn get_outbound_payments(&self) -> BTreeMap<String, OutboundPayment> {
+        match self.wallet_db.get_db_value(OUTBOUND_PAYMENTS_KEY).await {
+            Some(v) => deserialize(&v).unwrap_or_else(|e| {
+                error!("Failed to deserialize outbound payments: {:?}", e);
+                Default::default()
+            }),
+            None => Default::default(),
+        }
+    }
+
+    /// Queue an outbound payment transaction until acknowledged by compute
+    ///
+    /// ### Arguments
+    ///
+    /// * `transaction` - Payment transaction about to be sent
+    async fn queue_outbound_payment(&self, transaction: &Transaction) {
+        let mut outbound_payments = self.get_outbound_payments().await;
+        let queued = (unix_timestamp_now(), transaction.clone());
+        outbound_payments.insert(construct_tx_hash(transaction), queued);
+        self.set_outbound_payments(&outbound_payments).await;
+    }
+
+    /// Drop the outbound payment transactions queued for longer than the expiry,
+    /// returning the number dropped
+    ///
+    /// ### Arguments
+    ///
+    /// * `now` - Current time in seconds since the unix epoch
+    pub async fn expire_outbound_payments(&self, now: u64) -> usize {
+        let mut outbound_payments = self.get_outbound_payments().await;
+        let count = outbound_payments.len();
+        outbound_payments
+            .retain(|_, (queued, _)| now.saturating_sub(*queued) <= OUTBOUND_PAYMENT_EXPIRY_SECS);
+
+        let expired = count - outbound_payments.len();
+        if expired != 0 {
+            warn!("Dropped {} expired outbound payments", expired);
+            self.set_outbound_payments(&outbound_payments).await;
+        }
+        expired
+    }
+
+    /// Remove the outbound payment transactions acknowledged by compute
+    /// with their inclusion in a block
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx_hashes` - Hashes of the transactions included in the block
+    async fn acknowledge_outbound_payments(&self, tx_hashes: &[String]) {
+        let mut outbound_payments = self.get_outbound_payments().await;
+        let count = outbound_payments.len();
+        for tx_hash in tx_hashes {
+            outbound_payments.remove(tx_hash);
+        }
+
+        if outbound_payments.len() != count {
+            self.set_outbound_payments(&outbound_payments).await;
+        }
+    }
+
+    /// Persist the outbound payment transactions
+    ///
+    /// ### Arguments
+    ///
+    /// * `outbound_payments` - Outbound payment transactions by hash
+    async fn set_outbound_payments(&self, outbound_payments: &BTreeMap<String, OutboundPayment>) {
+        if outbound_payments.is_empty() {
+            self.wallet_db.delete_db_value(OUTBOUND_PAYMENTS_KEY).await;
+        } else {
+            let value = serialize(outbound_payments).unwrap();
+            self.wallet_db
+                .set_db_value(OUTBOUND_PAYMENTS_KEY, value)
+                .await;
         }
-        Ok(())
     }
 
     /// Threaded call channel.
@@ -570,15 +832,17 @@ impl UserNode {
                     error!("Failed to send startup requests on reconnect: {}", err);
                     return Some(Response {
                         success: false,
-                        reason: "Failed to send startup requests on reconnection",
+                        reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
                     });
                 }
                 Some(Response {
                     success: true,
-                    reason: "Sent startup requests on reconnection",
+                    reason: ResponseReason::SentStartupRequestsOnReconnection,
                 })
             }
             LocalEvent::CoordinatedShutdown(_) => None,
+            LocalEvent::CancelShutdown => None,
+            LocalEvent::ForceSnapshot => None,
             LocalEvent::Ignore => None,
         }
     }
@@ -590,9 +854,13 @@ impl UserNode {
     /// * `event` - Event object holding the frame to be passed.
     async fn handle_event(&mut self, event: Event) -> Result<Option<Response>> {
         match event {
-            Event::NewFrame { peer, frame } => {
+            Event::NewFrame {
+                peer,
+                frame,
+                format,
+            } => {
                 let peer_span = error_span!("peer", ?peer);
-                self.handle_new_frame(peer, frame)
+                self.handle_new_frame(peer, frame, format)
                     .instrument(peer_span)
                     .await
             }
@@ -605,12 +873,14 @@ impl UserNode {
     ///
     /// * `peer` - Socket Address of the sending peer node.
     /// * `frame` - Byte object holding the frame being handled.
+    /// * `format` - Serialization format of the frame.
     async fn handle_new_frame(
         &mut self,
         peer: SocketAddr,
         frame: Bytes,
+        format: CommsFormat,
     ) -> Result<Option<Response>> {
-        let req = deserialize::<UserRequest>(&frame).map_err(|error| {
+        let req = format.deserialize::<UserRequest>(&frame).map_err(|error| {
             warn!(?error, "frame-deserialize");
             error
         })?;
@@ -633,7 +903,15 @@ impl UserNode {
         trace!("handle_request: {:?}", req);
 
         match req {
-            UserApi(req) => self.handle_api_request(peer, req).await,
+            UserApi {
+                request,
+                correlation_id,
+            } => {
+                let api_span = info_span!("api", %correlation_id);
+                self.handle_api_request(peer, request)
+                    .instrument(api_span)
+                    .await
+            }
             SendUtxoSet { utxo_set } => Some(self.receive_utxo_set(utxo_set)),
             SendAddressRequest => Some(self.receive_payment_address_request(peer)),
             SendPaymentTransaction { transaction } => {
@@ -755,7 +1033,7 @@ impl UserNode {
 
         Response {
             success: true,
-            reason: "Addresses deleted",
+            reason: ResponseReason::AddressesDeleted,
         }
     }
 
@@ -766,7 +1044,7 @@ impl UserNode {
         if join_handles.is_empty() {
             return Response {
                 success: false,
-                reason: "Already disconnected from compute",
+                reason: ResponseReason::AlreadyDisconnectedFromCompute,
             };
         }
         for join_handle in join_handles {
@@ -774,7 +1052,7 @@ impl UserNode {
                 error!("Failed to disconnect from compute: {}", err);
                 return Response {
                     success: false,
-                    reason: "Failed to disconnect from compute",
+                    reason: ResponseReason::FailedToDisconnectFromCompute,
                 };
             }
         }
@@ -787,7 +1065,7 @@ impl UserNode {
         .await;
         Response {
             success: true,
-            reason: "Disconnected from compute",
+            reason: ResponseReason::DisconnectedFromCompute,
         }
     }
 
@@ -798,14 +1076,14 @@ impl UserNode {
             error!("Failed to connect to compute: {e:?}");
             return Response {
                 success: false,
-                reason: "Failed to connect to compute",
+                reason: ResponseReason::FailedToConnectToCompute,
             };
         }
         if let Err(e) = self.send_block_notification_request().await {
             error!("Failed to send startup requests to compute: {e:?}");
             return Response {
                 success: false,
-                reason: "Failed to send startup requests on reconnection",
+                reason: ResponseReason::FailedToSendStartupRequestsOnReconnection,
             };
         }
         try_send_to_ui(
@@ -817,7 +1095,7 @@ impl UserNode {
         .await;
         Response {
             success: true,
-            reason: "Connected to compute",
+            reason: ResponseReason::ConnectedToCompute,
         }
     }
 
@@ -833,7 +1111,7 @@ impl UserNode {
             .await;
             return Response {
                 success: true,
-                reason: "Node is disconnected",
+                reason: ResponseReason::NodeIsDisconnected,
             };
         }
         try_send_to_ui(
@@ -845,7 +1123,7 @@ impl UserNode {
         .await;
         Response {
             success: true,
-            reason: "Node is connected",
+            reason: ResponseReason::NodeIsConnected,
         }
     }
 
@@ -861,7 +1139,7 @@ impl UserNode {
 
         Some(Response {
             success: true,
-            reason: "Shutdown",
+            reason: ResponseReason::Shutdown,
         })
     }
     pub fn get_next_payment_transaction(&self) -> Option<(Option<SocketAddr>, Transaction)> {
@@ -885,6 +1163,7 @@ impl UserNode {
     ) -> Result<()> {
         let (peer, tx) = self.next_payment.take().unwrap();
 
+        self.queue_outbound_payment(&tx).await;
         self.send_transactions_to_compute(compute_peer, vec![tx.clone()])
             .await?;
 
@@ -911,6 +1190,7 @@ impl UserNode {
         compute_peer: SocketAddr,
     ) -> Result<()> {
         let (peer, transaction) = self.next_rb_payment.take().unwrap();
+        self.queue_outbound_payment(&transaction).await;
         let b_num = self.last_block_notified.header.b_num;
         self.wallet_db
             .store_payment_transaction(transaction.clone(), b_num)
@@ -948,7 +1228,7 @@ impl UserNode {
 
         Some(Response {
             success: true,
-            reason: "Request UTXO set",
+            reason: ResponseReason::RequestUtxoSet,
         })
     }
 
@@ -967,7 +1247,7 @@ impl UserNode {
             .ok()?;
         Some(Response {
             success: true,
-            reason: "Donation Requested",
+            reason: ResponseReason::DonationRequested,
         })
     }
 
@@ -989,7 +1269,7 @@ impl UserNode {
             .ok()?;
         Some(Response {
             success: true,
-            reason: "Request Payment Address",
+            reason: ResponseReason::RequestPaymentAddress,
         })
     }
 
@@ -1006,7 +1286,7 @@ impl UserNode {
 
         Response {
             success: true,
-            reason: "Payment transaction received",
+            reason: ResponseReason::PaymentTransactionReceived,
         }
     }
 
@@ -1021,24 +1301,40 @@ impl UserNode {
         peer: SocketAddr,
         address: String,
     ) -> Option<Response> {
-        let (amount, locktime) = match (
+        let (amount, locktime, is_donation) = match (
             self.pending_payments.0.remove(&peer),
             self.pending_payments.1,
         ) {
-            (Some(PendingPayment { amount, locktime }), _) => (amount, locktime),
-            (_, AutoDonate::Enabled(amount)) => (amount, None),
+            (Some(PendingPayment { amount, locktime }), _) => (amount, locktime, false),
+            (_, AutoDonate::Enabled(amount)) => (amount, None, true),
             _ => {
                 return Some(Response {
                     success: false,
-                    reason: "Ignore unexpected transaction",
+                    reason: ResponseReason::IgnoreUnexpectedTransaction,
                 })
             }
         };
 
-        Some(
-            self.make_payment_transactions(Some(peer), address, amount, locktime)
-                .await,
-        )
+        if is_donation {
+            let check = self
+                .auto_donate_limits
+                .check(peer.ip(), amount, Instant::now());
+            if let Err(reason) = check {
+                warn!(?peer, ?amount, "{}", reason);
+                return Some(Response {
+                    success: false,
+                    reason,
+                });
+            }
+        }
+
+        let response = self
+            .make_payment_transactions(Some(peer), address, amount, locktime)
+            .await;
+        if is_donation && response.success {
+            self.auto_donate_limits.record(peer.ip(), amount);
+        }
+        Some(response)
     }
 
     /// Process specified payment with a provided excess address,
@@ -1071,7 +1367,7 @@ impl UserNode {
         } else {
             return Response {
                 success: false,
-                reason: "Insufficient funds for payment",
+                reason: ResponseReason::InsufficientFundsForPayment,
             };
         };
         let payment_tx = construct_tx_core(tx_ins, tx_outs);
@@ -1079,7 +1375,7 @@ impl UserNode {
 
         Response {
             success: true,
-            reason: "Next payment transaction ready",
+            reason: ResponseReason::NextPaymentTransactionReady,
         }
     }
 
@@ -1108,7 +1404,7 @@ impl UserNode {
         } else {
             return Response {
                 success: false,
-                reason: "Insufficient funds for payment",
+                reason: ResponseReason::InsufficientFundsForPayment,
             };
         };
         let payment_tx = construct_tx_core(tx_ins, tx_outs);
@@ -1116,7 +1412,7 @@ impl UserNode {
 
         Response {
             success: true,
-            reason: "Next payment transaction ready",
+            reason: ResponseReason::NextPaymentTransactionReady,
         }
     }
 
@@ -1243,6 +1539,9 @@ impl UserNode {
             self.wallet_db
                 .filter_locked_coinbase(block.header.b_num)
                 .await;
+            self.acknowledge_outbound_payments(&block.transactions)
+                .await;
+            self.expire_outbound_payments(unix_timestamp_now()).await;
             self.last_block_notified = block;
             // Send the block to the UI for realtime feedback
             try_send_to_ui(
@@ -1254,12 +1553,12 @@ impl UserNode {
             .await;
             Response {
                 success: true,
-                reason: "Block mining notified",
+                reason: ResponseReason::BlockMiningNotified,
             }
         } else {
             Response {
                 success: false,
-                reason: "Invalid block mining notifier",
+                reason: ResponseReason::InvalidBlockMiningNotifier,
             }
         }
     }
@@ -1348,7 +1647,7 @@ impl UserNode {
         let _ = self.wallet_db.generate_payment_address().await;
         Response {
             success: true,
-            reason: "New address generated",
+            reason: ResponseReason::NewAddressGenerated,
         }
     }
 
@@ -1370,7 +1669,7 @@ impl UserNode {
 
         Response {
             success: true,
-            reason: "New address ready to be sent",
+            reason: ResponseReason::NewAddressReadyToBeSent,
         }
     }
 
@@ -1467,7 +1766,7 @@ impl UserNode {
         } else {
             return Response {
                 success: false,
-                reason: "Insufficient funds for payment",
+                reason: ResponseReason::InsufficientFundsForPayment,
             };
         };
 
@@ -1483,7 +1782,7 @@ impl UserNode {
 
         Response {
             success: true,
-            reason: "Received receipt-based payment request",
+            reason: ResponseReason::ReceivedReceiptBasedPaymentRequest,
         }
     }
 
@@ -1512,7 +1811,7 @@ impl UserNode {
         }
         Response {
             success: true,
-            reason: "Received receipt-based payment response",
+            reason: ResponseReason::ReceivedReceiptBasedPaymentResponse,
         }
     }
 
@@ -1542,7 +1841,7 @@ impl UserNode {
         self.next_payment = Some((None, receipt_asset_tx));
 
         Response {
-            reason: "Receipt asset create transaction ready",
+            reason: ResponseReason::ReceiptAssetCreateTransactionReady,
             success: true,
         }
     }
@@ -1603,7 +1902,7 @@ impl Transactor for UserNode {
 
         Response {
             success: true,
-            reason: "Received UTXO set",
+            reason: ResponseReason::ReceivedUtxoSet,
         }
     }
     async fn update_running_total(&mut self) {
@@ -1753,3 +2052,49 @@ fn make_transaction_gen(setup: UserAutoGenTxSetup) -> Option<AutoGenTx> {
         None
     }
 }
+
+/// Current time in seconds since the unix epoch
+fn unix_timestamp_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    /// Test the total cap applies across IP addresses until the window elapses
+    #[test]
+    fn auto_donate_limits_reset_after_window() {
+        //
+        // Arrange
+        //
+        let mut limits = AutoDonateLimits {
+            max_per_peer: Some(10),
+            max_total: Some(10),
+            window: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let peer1 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let peer2 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let start = Instant::now();
+
+        //
+        // Act
+        //
+        let first = limits.check(peer1, TokenAmount(10), start);
+        limits.record(peer1, TokenAmount(10));
+        let in_window = limits.check(peer2, TokenAmount(1), start + Duration::from_secs(59));
+        let after_window = limits.check(peer1, TokenAmount(10), start + Duration::from_secs(60));
+
+        //
+        // Assert
+        //
+        assert_eq!(first, Ok(()));
+        assert_eq!(in_window, Err(ResponseReason::IgnoreDonationOverTotalLimit));
+        assert_eq!(after_window, Ok(()));
+    }
+}
@@ -1,16 +1,19 @@
 use crate::comms_handler::Node;
-use crate::configurations::{UnicornFixedInfo, UtxoSetSpec, WalletTxSpec};
+use crate::configurations::{RewardHalvingSchedule, UnicornFixedInfo, UtxoSetSpec, WalletTxSpec};
 use crate::constants::{
     BLOCK_PREPEND, COINBASE_MATURITY, MINING_DIFFICULTY, NETWORK_VERSION, REWARD_ISSUANCE_VAL,
 };
 use crate::interfaces::{
-    BlockchainItem, BlockchainItemMeta, DruidDroplet, PowInfo, ProofOfWork, StoredSerializingBlock,
+    BlockchainItem, BlockchainItemMeta, DruidDroplet, DruidPool, PowInfo, ProofOfWork, Response,
+    ResponseReason, StoredSerializingBlock, UtxoSet,
 };
+use crate::raft_util::{RaftProposalMetrics, RaftProposalMetricsInfo};
+use crate::tracked_utxo::{AssetKey, AssetTotals};
 use crate::wallet::WalletDb;
 use crate::Rs2JsMsg;
 use bincode::serialize;
 use futures::future::join_all;
-use naom::constants::TOTAL_TOKENS;
+use naom::constants::{NETWORK_VERSION_TEMP, NETWORK_VERSION_V0, TOTAL_TOKENS};
 use naom::crypto::sha3_256;
 use naom::crypto::sign_ed25519::{self as sign, PublicKey, SecretKey, Signature};
 use naom::primitives::transaction::DrsTxHashSpec;
@@ -23,7 +26,7 @@ use naom::script::{lang::Script, StackEntry};
 use naom::utils::transaction_utils::{
     construct_address, construct_create_tx, construct_payment_tx_ins, construct_tx_core,
     construct_tx_hash, construct_tx_in_signable_asset_hash, construct_tx_in_signable_hash,
-    get_tx_out_with_out_point, get_tx_out_with_out_point_cloned,
+    construct_tx_ins_address, get_tx_out_with_out_point, get_tx_out_with_out_point_cloned,
 };
 use rand::{self, Rng};
 use std::collections::BTreeMap;
@@ -33,24 +36,40 @@ use std::fs::File;
 use std::future::Future;
 use std::io::Read;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::task;
 use tokio::time::Instant;
-use tracing::{trace, warn};
+use tracing::{trace, warn, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
 
 pub type RoutesPoWInfo = Arc<Mutex<BTreeMap<String, usize>>>;
-pub type ApiKeys = Arc<Mutex<BTreeMap<String, Vec<String>>>>;
+pub type ApiKeys = Arc<Mutex<ApiKeysInfo>>;
 pub type LocalEventSender = MpscTracingSender<LocalEvent>;
 pub type LocalEventReceiver = mpsc::Receiver<LocalEvent>;
 
+/// API keys accepted for each route
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeysInfo {
+    /// Route to the API keys allowed to call it
+    pub route_keys: BTreeMap<String, Vec<String>>,
+    /// API key to the hex encoded public key its requests must be signed with
+    pub signing_keys: BTreeMap<String, String>,
+}
+
 /// Local command event to nodes
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LocalEvent {
     CoordinatedShutdown(u64),
+    CancelShutdown,
     ReconnectionComplete,
-    Exit(&'static str),
+    ForceSnapshot,
+    Exit(ResponseReason),
     Ignore,
 }
 
@@ -107,6 +126,14 @@ impl<T> MpscTracingSender<T> {
             Err(TrySendError::Closed(value)) => Err(SendError(value)),
         }
     }
+
+    pub fn try_send(&mut self, value: T, tag: &str) -> Result<(), mpsc::error::TrySendError<T>> {
+        let result = self.sender.try_send(value);
+        if let Err(mpsc::error::TrySendError::Full(_)) = &result {
+            warn!("send_tracing({}) full: rejected", tag);
+        }
+        result
+    }
 }
 
 /// A running tasks or end result
@@ -146,6 +173,13 @@ impl<T> RunningTaskOrResult<T> {
     pub fn is_active(&self) -> bool {
         matches!(self, Self::Running(_))
     }
+
+    /// Abort the task if running and drop any result
+    pub fn abort(&mut self) {
+        if let Self::Running(task) = std::mem::take(self) {
+            task.abort();
+        }
+    }
 }
 
 /// Channel for low volume local events
@@ -261,6 +295,140 @@ impl UtxoReAlignCheck {
     }
 }
 
+/// Readiness state of a node shared with its API
+#[derive(Debug, Clone, Default)]
+pub struct NodeReadiness {
+    initial_state_applied: Arc<AtomicBool>,
+    block_production_stalled: Arc<AtomicBool>,
+    expected_peers: Vec<SocketAddr>,
+}
+
+impl NodeReadiness {
+    pub fn new(expected_peers: Vec<SocketAddr>) -> Self {
+        Self {
+            initial_state_applied: Default::default(),
+            block_production_stalled: Default::default(),
+            expected_peers,
+        }
+    }
+
+    /// Record whether the initial raft snapshot has been applied
+    pub fn set_initial_state_applied(&self, applied: bool) {
+        self.initial_state_applied.store(applied, Ordering::Relaxed);
+    }
+
+    /// Whether the initial raft snapshot has been applied
+    pub fn is_initial_state_applied(&self) -> bool {
+        self.initial_state_applied.load(Ordering::Relaxed)
+    }
+
+    /// Record whether no block has been committed for longer than allowed
+    pub fn set_block_production_stalled(&self, stalled: bool) {
+        self.block_production_stalled
+            .store(stalled, Ordering::Relaxed);
+    }
+
+    /// Whether no block has been committed for longer than allowed
+    pub fn is_block_production_stalled(&self) -> bool {
+        self.block_production_stalled.load(Ordering::Relaxed)
+    }
+
+    /// Peers the node must be connected to before it is ready
+    pub fn expected_peers(&self) -> &[SocketAddr] {
+        &self.expected_peers
+    }
+}
+
+/// Progress of a pre-launch node, shared with its API
+#[derive(Debug, Clone, Default)]
+pub struct PreLaunchStatus {
+    raft_db_fetched: Arc<AtomicBool>,
+    complete: Arc<AtomicBool>,
+}
+
+impl PreLaunchStatus {
+    /// Record that the raft db has been fetched
+    pub fn set_raft_db_fetched(&self) {
+        self.raft_db_fetched.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the raft db has been fetched
+    pub fn is_raft_db_fetched(&self) -> bool {
+        self.raft_db_fetched.load(Ordering::Relaxed)
+    }
+
+    /// Record that pre-launch is complete and the main node can start
+    pub fn set_complete(&self) {
+        self.complete.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether pre-launch is complete and the main node can start
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Relaxed)
+    }
+}
+
+/// Block numbers stored by the storage raft group and produced by compute,
+/// its proposal metrics and block stored notifications, shared with its API
+#[derive(Debug, Clone)]
+pub struct StorageRaftStatus {
+    stored_block_num: Arc<AtomicU64>,
+    compute_block_num: Arc<AtomicU64>,
+    proposal_metrics: RaftProposalMetrics,
+    block_stored: Arc<watch::Sender<()>>,
+}
+
+impl Default for StorageRaftStatus {
+    fn default() -> Self {
+        Self {
+            stored_block_num: Arc::new(AtomicU64::new(u64::MAX)),
+            compute_block_num: Arc::new(AtomicU64::new(u64::MAX)),
+            proposal_metrics: Default::default(),
+            block_stored: Arc::new(watch::channel(()).0),
+        }
+    }
+}
+
+impl StorageRaftStatus {
+    /// Share the proposal metrics of the storage raft
+    pub fn with_proposal_metrics(mut self, proposal_metrics: RaftProposalMetrics) -> Self {
+        self.proposal_metrics = proposal_metrics;
+        self
+    }
+
+    /// Commit latency and throughput of the storage raft proposals
+    pub fn proposal_metrics(&self) -> RaftProposalMetricsInfo {
+        self.proposal_metrics.info()
+    }
+
+    /// Record the number of the last block stored in consensus, notifying subscribers
+    pub fn set_stored_block_num(&self, b_num: Option<u64>) {
+        let b_num = b_num.unwrap_or(u64::MAX);
+        self.stored_block_num.store(b_num, Ordering::Relaxed);
+        self.block_stored.send_replace(());
+    }
+
+    /// Subscribe to be notified of blocks stored after this call
+    pub fn subscribe_block_stored(&self) -> watch::Receiver<()> {
+        self.block_stored.subscribe()
+    }
+
+    /// Number of the last block stored in consensus, if any
+    pub fn stored_block_num(&self) -> Option<u64> {
+        Some(self.stored_block_num.load(Ordering::Relaxed)).filter(|b| *b != u64::MAX)
+    }
+
+    /// Record the number of the last block received from compute
+    pub fn set_compute_block_num(&self, b_num: u64) {
+        self.compute_block_num.store(b_num, Ordering::Relaxed);
+    }
+
+    /// Number of the last block received from compute, if any
+    pub fn compute_block_num(&self) -> Option<u64> {
+        Some(self.compute_block_num.load(Ordering::Relaxed)).filter(|b| *b != u64::MAX)
+    }
+}
+
 /// Install a global tracing subscriber that listens for events and
 /// filters based on the value of the [`RUST_LOG` environment variable],
 /// if one is not already set.
@@ -278,6 +446,69 @@ pub fn tracing_log_try_init() -> Result<(), Box<dyn Error + Send + Sync + 'stati
     builder.try_init()
 }
 
+/// Handle to change the filter of the global tracing subscriber at runtime
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Install a global tracing subscriber with a filter that can be changed at runtime.
+///
+/// The filter comes from the `RUST_LOG` environment variable if set,
+/// otherwise from the given default directives, and only errors are logged if neither is.
+///
+/// ### Arguments
+///
+/// * `default_dirs` - Default filter directives per module, e.g. "info,znp::compute=debug"
+/// * `json`         - Emit logs as JSON lines instead of the human readable format
+pub fn tracing_log_reloadable_init(
+    default_dirs: Option<&str>,
+    json: bool,
+) -> Result<LogFilterHandle, Box<dyn Error + Send + Sync + 'static>> {
+    let dirs = std::env::var(EnvFilter::DEFAULT_ENV)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .or_else(|| default_dirs.map(str::to_owned))
+        .unwrap_or_default();
+
+    let (filter, handle) = reload::Layer::new(EnvFilter::try_new(dirs)?);
+    let registry = tracing_subscriber::registry().with(filter);
+    if json {
+        registry.with(json_log_layer(std::io::stdout)).try_init()?;
+    } else {
+        registry.with(tracing_subscriber::fmt::layer()).try_init()?;
+    }
+
+    Ok(handle)
+}
+
+/// Log formatting layer writing one JSON object per line, with the timestamp,
+/// level, target, message and event fields at the top level, and the span fields
+///
+/// ### Arguments
+///
+/// * `make_writer` - Creates the writer for each log line
+pub fn json_log_layer<S, W>(make_writer: W) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: MakeWriter + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_writer(make_writer)
+}
+
+/// Replace the active log filter with the given directives
+///
+/// ### Arguments
+///
+/// * `handle` - Handle to the reloadable filter
+/// * `dirs`   - New filter directives per module, e.g. "info,znp::compute=debug"
+pub fn reload_log_filter(handle: &LogFilterHandle, dirs: &str) -> Result<(), StringError> {
+    let filter = EnvFilter::try_new(dirs).map_err(|e| StringError(e.to_string()))?;
+    handle
+        .reload(filter)
+        .map_err(|e| StringError(e.to_string()))
+}
+
 /// Attempts to connect to all peers
 ///
 /// ### Arguments
@@ -416,8 +647,28 @@ pub fn format_parition_pow_address(addr: SocketAddr) -> String {
 /// ### Arguments
 ///
 /// * `current_circulation` - Current circulation of all tokens
-pub fn calculate_reward(current_circulation: TokenAmount) -> TokenAmount {
-    TokenAmount((TOTAL_TOKENS - current_circulation.0) >> REWARD_ISSUANCE_VAL)
+/// * `b_num`               - Block number the reward is for
+/// * `halving`             - Halving schedule to apply if any
+pub fn calculate_reward(
+    current_circulation: TokenAmount,
+    b_num: u64,
+    halving: Option<&RewardHalvingSchedule>,
+) -> TokenAmount {
+    let remaining = TOTAL_TOKENS.saturating_sub(current_circulation.0);
+    let halving = match halving {
+        Some(halving) if halving.halving_interval != 0 => halving,
+        _ => return TokenAmount(remaining >> REWARD_ISSUANCE_VAL),
+    };
+
+    let base_reward = halving
+        .base_reward
+        .map_or(remaining >> REWARD_ISSUANCE_VAL, |base| base.min(remaining));
+    let era = b_num / halving.halving_interval;
+    TokenAmount(
+        base_reward
+            .checked_shr(era.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0),
+    )
 }
 
 /// Gets the total amount of tokens for all present coinbase transactions,
@@ -434,6 +685,91 @@ pub fn get_total_coinbase_tokens(coinbase_tx: &BTreeMap<String, Transaction>) ->
     total
 }
 
+/// Gets the fee paid by a transaction: the tokens of its inputs minus the tokens of its outputs.
+/// Inputs not found in the UTXO set count as zero tokens
+///
+/// ### Arguments
+///
+/// * `tx`       - Transaction to get the fee of
+/// * `utxo_set` - UTXO set the transaction inputs are spent from
+pub fn tx_fee(tx: &Transaction, utxo_set: &UtxoSet) -> TokenAmount {
+    let spent: TokenAmount = tx
+        .inputs
+        .iter()
+        .filter_map(|tx_in| tx_in.previous_out.as_ref())
+        .filter_map(|out_point| utxo_set.get(out_point))
+        .map(|tx_out| tx_out.value.token_amount())
+        .sum();
+    let paid: TokenAmount = tx.outputs.iter().map(|x| x.value.token_amount()).sum();
+    TokenAmount(spent.0.saturating_sub(paid.0))
+}
+
+/// Check if a non-coinbase transaction creates a token output below the dust threshold
+///
+/// ### Arguments
+///
+/// * `tx`             - Transaction to check
+/// * `dust_threshold` - Minimum amount of a token output
+pub fn has_dust_output(tx: &Transaction, dust_threshold: TokenAmount) -> bool {
+    !tx.is_coinbase()
+        && tx.outputs.iter().any(|tx_out| match tx_out.value {
+            Asset::Token(amount) => amount < dust_threshold,
+            _ => false,
+        })
+}
+
+/// Checks the receipts of a transaction are only moved and not created out of thin air:
+/// all the receipt outputs must have a DRS tx hash, and the receipts spent and paid
+/// for each DRS tx hash must match, independently of the tokens.
+/// Inputs not found in the UTXO set make the transaction invalid
+///
+/// ### Arguments
+///
+/// * `tx`       - Transaction to check, not a create transaction
+/// * `utxo_set` - UTXO set the transaction inputs are spent from
+pub fn tx_receipts_are_balanced(tx: &Transaction, utxo_set: &UtxoSet) -> bool {
+    let mut spent = AssetTotals::default();
+    for out_point in tx.inputs.iter().filter_map(|i| i.previous_out.as_ref()) {
+        match utxo_set.get(out_point) {
+            Some(tx_out) => spent.update_add(&tx_out.value.clone().with_fixed_hash(out_point)),
+            None => return false,
+        }
+    }
+
+    let mut paid = AssetTotals::default();
+    for tx_out in &tx.outputs {
+        if let Asset::Receipt(receipt) = &tx_out.value {
+            if receipt.drs_tx_hash.is_none() {
+                return false;
+            }
+        }
+        paid.update_add(&tx_out.value);
+    }
+
+    let receipts = |totals: &AssetTotals| {
+        totals
+            .iter()
+            .filter(|(key, _)| **key != AssetKey::Token)
+            .map(|(key, amount)| (key.clone(), *amount))
+            .collect::<Vec<_>>()
+    };
+    receipts(&spent) == receipts(&paid)
+}
+
+/// Checks all the inputs of a transaction are signed by one of the provided public keys
+///
+/// ### Arguments
+///
+/// * `tx`          - Transaction to check
+/// * `public_keys` - Public keys allowed to sign the transaction
+pub fn tx_signed_by_any(tx: &Transaction, public_keys: &[PublicKey]) -> bool {
+    tx.inputs.iter().all(|tx_in| {
+        public_keys
+            .iter()
+            .any(|pk| (tx_in.script_signature.stack.iter()).any(|e| *e == StackEntry::PubKey(*pk)))
+    })
+}
+
 /// Concatenates a merkle hash and a coinbase hash to produce a single hash output
 ///
 /// ### Arguments
@@ -462,6 +798,82 @@ pub fn generate_random_num(len: usize) -> Vec<u8> {
     (0..len).map(|_| rng.gen_range(1, 200)).collect()
 }
 
+/// Length in hex characters of addresses constructed before the current version
+pub const LEGACY_ADDRESS_LEN: usize = 32;
+
+/// Length in hex characters of addresses constructed for the current version
+pub const ADDRESS_LEN: usize = 64;
+
+/// A validated address, normalized to lower case hex
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CanonicalAddress(String);
+
+impl CanonicalAddress {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for CanonicalAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Reason an address failed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    NotHex,
+    InvalidLength(usize),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotHex => write!(f, "Address is not hexadecimal"),
+            Self::InvalidLength(len) => write!(f, "Address has invalid length {len}"),
+        }
+    }
+}
+
+impl Error for AddressError {}
+
+/// Address lengths accepted for an address version, matching `construct_address_for`
+///
+/// ### Arguments
+///
+/// * `version` - Address version, any supported version if not given
+fn address_lens_for(version: Option<u64>) -> &'static [usize] {
+    match version {
+        Some(NETWORK_VERSION_V0) | Some(NETWORK_VERSION_TEMP) => &[LEGACY_ADDRESS_LEN],
+        Some(_) => &[ADDRESS_LEN],
+        None => &[LEGACY_ADDRESS_LEN, ADDRESS_LEN],
+    }
+}
+
+/// Validate an address for the given version and normalize its casing
+///
+/// ### Arguments
+///
+/// * `addr`    - Address to validate
+/// * `version` - Address version, any supported version if not given
+pub fn validate_address(
+    addr: &str,
+    version: Option<u64>,
+) -> Result<CanonicalAddress, AddressError> {
+    if !addr.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(AddressError::NotHex);
+    }
+    if !address_lens_for(version).contains(&addr.len()) {
+        return Err(AddressError::InvalidLength(addr.len()));
+    }
+    Ok(CanonicalAddress(addr.to_ascii_lowercase()))
+}
+
 /// Generates a ProofOfWork for a given address
 ///
 /// ### Arguments
@@ -469,11 +881,13 @@ pub fn generate_random_num(len: usize) -> Vec<u8> {
 /// * `peer`      - Peer to send PoW to
 /// * `address`   - Given address to generate the ProofOfWork
 /// * `rand_num`  - A random number used to generate the ProofOfWork in an Option<Vec<u8>>
+/// * `difficulty` - Number of leading zero bytes required
 pub fn generate_pow_for_address(
     peer: SocketAddr,
     pow_info: PowInfo,
     address: String,
     rand_num: Option<Vec<u8>>,
+    difficulty: usize,
 ) -> task::JoinHandle<(ProofOfWork, PowInfo, SocketAddr)> {
     task::spawn_blocking(move || {
         let mut pow = ProofOfWork {
@@ -481,7 +895,7 @@ pub fn generate_pow_for_address(
             nonce: generate_pow_nonce(),
         };
 
-        while !validate_pow_for_address(&pow, &rand_num.as_ref()) {
+        while !validate_pow_for_address(&pow, &rand_num.as_ref(), difficulty) {
             pow.nonce = generate_pow_nonce();
         }
 
@@ -490,22 +904,49 @@ pub fn generate_pow_for_address(
 }
 
 /// Validate Proof of Work an address with a random number
-pub fn validate_pow_for_address(pow: &ProofOfWork, rand_num: &Option<&Vec<u8>>) -> bool {
+///
+/// ### Arguments
+///
+/// * `pow`               - The address and nonce to validate
+/// * `rand_num`          - The random number the PoW was generated with
+/// * `mining_difficulty` - Number of leading zero bytes required
+pub fn validate_pow_for_address(
+    pow: &ProofOfWork,
+    rand_num: &Option<&Vec<u8>>,
+    mining_difficulty: usize,
+) -> bool {
     let mut pow_body = pow.address.as_bytes().to_vec();
     pow_body.extend(rand_num.iter().flat_map(|r| r.iter()).copied());
     pow_body.extend(&pow.nonce);
 
-    validate_pow(&pow_body).is_some()
+    validate_pow_for_diff(mining_difficulty, &pow_body).is_some()
+}
+
+/// Get the mining difficulty a block header must reach from its `bits`
+///
+/// Headers created before the difficulty was recorded have `bits` set to 0,
+/// these use the default MINING_DIFFICULTY.
+///
+/// ### Arguments
+///
+/// * `bits`   - The `bits` value of the block header
+pub fn mining_difficulty_from_bits(bits: usize) -> usize {
+    if bits == 0 {
+        MINING_DIFFICULTY
+    } else {
+        bits
+    }
 }
 
 /// Generate Proof of Work for a block with a mining transaction
 ///
 /// ### Arguments
 ///
-/// * `header`   - The header for PoW
+/// * `header`   - The header for PoW, using the difficulty from its `bits`
 pub fn generate_pow_for_block(mut header: BlockHeader) -> BlockHeader {
+    let mining_difficulty = mining_difficulty_from_bits(header.bits);
     header.nonce_and_mining_tx_hash.0 = generate_pow_nonce();
-    while !validate_pow_block(&header) {
+    while !validate_pow_block(&header, mining_difficulty) {
         header.nonce_and_mining_tx_hash.0 = generate_pow_nonce();
     }
     header
@@ -529,9 +970,11 @@ pub fn construct_valid_block_pow_hash(block: &Block) -> Result<String, StringErr
         ));
     }
 
-    let hash_digest = validate_pow_block_hash(&block.header).ok_or_else(|| {
-        StringError("Only block passing validate_pow_block are accepted".to_owned())
-    })?;
+    let mining_difficulty = mining_difficulty_from_bits(block.header.bits);
+    let hash_digest =
+        validate_pow_block_hash(&block.header, mining_difficulty).ok_or_else(|| {
+            StringError("Only block passing validate_pow_block are accepted".to_owned())
+        })?;
 
     let mut hash_digest = hex::encode(hash_digest);
     hash_digest.insert(0, BLOCK_PREPEND as char);
@@ -542,22 +985,24 @@ pub fn construct_valid_block_pow_hash(block: &Block) -> Result<String, StringErr
 ///
 /// ### Arguments
 ///
-/// * `header`   - The header for PoW
-pub fn validate_pow_block(header: &BlockHeader) -> bool {
-    validate_pow_block_hash(header).is_some()
+/// * `header`            - The header for PoW
+/// * `mining_difficulty` - Number of leading zero bytes required
+pub fn validate_pow_block(header: &BlockHeader, mining_difficulty: usize) -> bool {
+    validate_pow_block_hash(header, mining_difficulty).is_some()
 }
 
 /// Validate Proof of Work for a block with a mining transaction returning the PoW hash
 ///
 /// ### Arguments
 ///
-/// * `header`   - The header for PoW
-fn validate_pow_block_hash(header: &BlockHeader) -> Option<Vec<u8>> {
+/// * `header`            - The header for PoW
+/// * `mining_difficulty` - Number of leading zero bytes required
+fn validate_pow_block_hash(header: &BlockHeader, mining_difficulty: usize) -> Option<Vec<u8>> {
     let pow = serialize(header).unwrap();
-    validate_pow(&pow)
+    validate_pow_for_diff(mining_difficulty, &pow)
 }
 
-/// Check the hash of given data reach MINING_DIFFICULTY
+/// Check the hash of given data reach the given mining difficulty
 ///
 /// ### Arguments
 ///
@@ -572,15 +1017,6 @@ pub fn validate_pow_for_diff(mining_difficulty: usize, pow: &[u8]) -> Option<Vec
     }
 }
 
-/// Check the hash of given data reach MINING_DIFFICULTY
-///
-/// ### Arguments
-///
-/// * `pow`    - &u8 proof of work
-fn validate_pow(pow: &[u8]) -> Option<Vec<u8>> {
-    validate_pow_for_diff(MINING_DIFFICULTY, pow)
-}
-
 /// Get the paiment info from the given transactions
 ///
 /// ### Arguments
@@ -690,6 +1126,81 @@ pub fn create_valid_transaction_with_ins_outs(
     (t_hash, payment_tx)
 }
 
+/// Build a payment transaction, signing each input with the key pair owning its previous output.
+/// Offline: neither the UTXO set nor the network is needed.
+///
+/// ### Arguments
+///
+/// * `inputs`   - Previous outputs to spend
+/// * `outputs`  - Outputs to create
+/// * `keypairs` - Public and secret key owning each of the `inputs`, in the same order
+pub fn build_signed_payment(
+    inputs: &[OutPoint],
+    outputs: Vec<TxOut>,
+    keypairs: &[(PublicKey, SecretKey)],
+) -> Result<Transaction, StringError> {
+    if inputs.len() != keypairs.len() {
+        return Err(StringError(format!(
+            "{} inputs signed by {} key pairs",
+            inputs.len(),
+            keypairs.len()
+        )));
+    }
+
+    let tx_in_cons = inputs
+        .iter()
+        .zip(keypairs)
+        .map(|(previous_out, (pub_key, secret_key))| {
+            let signable_h = construct_tx_in_signable_hash(previous_out);
+            TxConstructor {
+                previous_out: previous_out.clone(),
+                signatures: vec![sign::sign_detached(signable_h.as_bytes(), secret_key)],
+                pub_keys: vec![*pub_key],
+                address_version: None,
+            }
+        })
+        .collect();
+
+    Ok(construct_tx_core(
+        construct_payment_tx_ins(tx_in_cons),
+        outputs,
+    ))
+}
+
+/// Check the signature of each input against its signable data, without the UTXO set:
+/// Payment inputs sign their previous output, create transaction inputs the created asset.
+/// Ownership of the previous outputs still needs to be validated against the UTXO set.
+///
+/// ### Arguments
+///
+/// * `tx` - The transaction to check
+pub fn verify_transaction_signatures(tx: &Transaction) -> bool {
+    !tx.inputs.is_empty()
+        && tx.inputs.iter().all(|tx_in| {
+            let expected_signable = match &tx_in.previous_out {
+                Some(previous_out) => construct_tx_in_signable_hash(previous_out),
+                None if tx.is_create_tx() => match tx.outputs.first() {
+                    Some(tx_out) => construct_tx_in_signable_asset_hash(&tx_out.value),
+                    None => return false,
+                },
+                None => return false,
+            };
+
+            let stack = &tx_in.script_signature.stack;
+            stack.windows(3).any(|e| match (&e[0], &e[1], &e[2]) {
+                (
+                    StackEntry::Bytes(signable),
+                    StackEntry::Signature(signature),
+                    StackEntry::PubKey(pub_key),
+                ) => {
+                    *signable == expected_signable
+                        && sign::verify_detached(signature, signable.as_bytes(), pub_key)
+                }
+                _ => false,
+            })
+        })
+}
+
 /// Get the string to display for genesis TxIn
 ///
 /// ### Arguments
@@ -996,7 +1507,7 @@ async fn shutdown_on_path(
             );
 
             let event = if is_now {
-                LocalEvent::Exit("Shutdown")
+                LocalEvent::Exit(ResponseReason::Shutdown)
             } else {
                 LocalEvent::CoordinatedShutdown(block_num)
             };
@@ -1130,7 +1641,18 @@ pub fn construct_coinbase_tx(b_num: u64, amount: TokenAmount, address: String) -
 
 /// Confert to ApiKeys data structure
 pub fn to_api_keys(api_keys: BTreeMap<String, Vec<String>>) -> ApiKeys {
-    Arc::new(Mutex::new(api_keys.into_iter().collect()))
+    to_signed_api_keys(api_keys, Default::default())
+}
+
+/// Confert to ApiKeys data structure with the public keys of API keys that sign requests
+pub fn to_signed_api_keys(
+    api_keys: BTreeMap<String, Vec<String>>,
+    signing_keys: BTreeMap<String, String>,
+) -> ApiKeys {
+    Arc::new(Mutex::new(ApiKeysInfo {
+        route_keys: api_keys,
+        signing_keys,
+    }))
 }
 
 /// Confert to ApiKeys data structure
@@ -1146,6 +1668,141 @@ pub fn check_druid_participants(droplet: &DruidDroplet) -> bool {
         .all(|(_, tx)| tx.druid_info.as_ref().map(|i| i.participants) == Some(droplet.participants))
 }
 
+/// Check the DDE transaction expectations are mutually consistent between participants
+///
+/// Every participant must expect an asset from another participant,
+/// and that participant must pay it with the exact amount and asset type.
+/// Returns the reason the droplet is unbalanced otherwise.
+pub fn check_druid_droplet_balanced(droplet: &DruidDroplet) -> Result<(), String> {
+    let payers: BTreeMap<String, &Transaction> = droplet
+        .txs
+        .values()
+        .map(|tx| (construct_tx_ins_address(&tx.inputs), tx))
+        .collect();
+
+    for (tx_hash, tx) in &droplet.txs {
+        let expectations = match &tx.druid_info {
+            Some(info) if !info.expectations.is_empty() => &info.expectations,
+            _ => return Err(format!("Transaction {tx_hash} expects nothing in return")),
+        };
+
+        let own_addr = construct_tx_ins_address(&tx.inputs);
+        for expectation in expectations {
+            let payer = match payers.get(&expectation.from) {
+                Some(_) if expectation.from == own_addr => {
+                    return Err(format!("Transaction {tx_hash} expects assets from itself"))
+                }
+                Some(payer) => payer,
+                None => {
+                    return Err(format!(
+                        "Transaction {tx_hash} expects assets from a non participant"
+                    ))
+                }
+            };
+
+            let paid = payer.outputs.iter().any(|out| {
+                out.script_public_key.as_ref() == Some(&expectation.to)
+                    && out.value == expectation.asset
+            });
+            if !paid {
+                return Err(format!(
+                    "Transaction {tx_hash} expectation does not match the amount and asset paid"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Domain separator prefixed to the message signed to cancel a DRUID participation
+pub const DRUID_CANCELLATION_DOMAIN: &str = "znp-druid-cancellation";
+
+/// Maximum number of seconds a signed DRUID cancellation can stay valid for
+pub const DRUID_CANCELLATION_MAX_EXPIRY_SECS: u64 = 600;
+
+/// Message signed by a DRUID participant to cancel its transaction
+///
+/// ### Arguments
+///
+/// * `druid`   - DRUID id to cancel the participation in
+/// * `tx_hash` - Hash of the caller transaction in the droplet
+/// * `expiry`  - UNIX timestamp in seconds after which the cancellation is rejected
+pub fn druid_cancellation_message(druid: &str, tx_hash: &str, expiry: u64) -> Vec<u8> {
+    format!("{DRUID_CANCELLATION_DOMAIN}:{druid}:{tx_hash}:{expiry}").into_bytes()
+}
+
+/// Remove the transaction signed by the caller from an incomplete DRUID droplet
+///
+/// The caller proves participation with a signature of `druid_cancellation_message`.
+/// The droplet is removed from the pool once no transaction is left.
+///
+/// ### Arguments
+///
+/// * `druid_pool` - Pool of pending DRUID droplets
+/// * `druid`      - DRUID id to cancel the participation in
+/// * `tx_hash`    - Hash of the caller transaction in the droplet
+/// * `expiry`     - UNIX timestamp in seconds after which the cancellation is rejected,
+///                  at most `DRUID_CANCELLATION_MAX_EXPIRY_SECS` after `now`
+/// * `public_key` - Hex encoded public key of the caller
+/// * `signature`  - Hex encoded signature of the cancellation message by the caller
+/// * `now`        - Current UNIX timestamp in seconds
+pub fn cancel_druid_participation(
+    druid_pool: &mut DruidPool,
+    druid: &str,
+    tx_hash: &str,
+    expiry: u64,
+    public_key: &str,
+    signature: &str,
+    now: u64,
+) -> Response {
+    let fail = |reason| Response {
+        success: false,
+        reason,
+    };
+
+    if expiry < now {
+        return fail(ResponseReason::DruidCancellationExpired);
+    }
+    if expiry > now.saturating_add(DRUID_CANCELLATION_MAX_EXPIRY_SECS) {
+        return fail(ResponseReason::DruidCancellationExpiryTooFar);
+    }
+
+    let message = druid_cancellation_message(druid, tx_hash, expiry);
+    let public_key = match (decode_pub_key(public_key), decode_signature(signature)) {
+        (Ok(pk), Ok(sig)) if sign::verify_detached(&sig, &message, &pk) => pk,
+        _ => return fail(ResponseReason::InvalidDruidCancellationSignature),
+    };
+
+    let droplet = match druid_pool.get_mut(druid) {
+        Some(droplet) if droplet.txs.len() >= droplet.participants => {
+            return fail(ResponseReason::DruidAlreadyExecuting)
+        }
+        Some(droplet) => droplet,
+        None => return fail(ResponseReason::DruidNotPending),
+    };
+
+    let signed_by_caller = |tx: &Transaction| {
+        !tx.inputs.is_empty()
+            && tx.inputs.iter().all(|tx_in| {
+                (tx_in.script_signature.stack.iter()).any(|e| *e == StackEntry::PubKey(public_key))
+            })
+    };
+
+    if !droplet.txs.get(tx_hash).map_or(false, signed_by_caller) {
+        return fail(ResponseReason::DruidParticipantNotFound);
+    }
+    droplet.txs.remove(tx_hash);
+    if droplet.txs.is_empty() {
+        druid_pool.remove(druid);
+    }
+
+    Response {
+        success: true,
+        reason: ResponseReason::DruidParticipationCancelled,
+    }
+}
+
 /// Test UnicornFixedInfo with fast compuation
 pub fn get_test_common_unicorn() -> UnicornFixedInfo {
     UnicornFixedInfo{
@@ -1186,3 +1843,581 @@ pub mod rug_integer {
         Integer::from_str_radix(&value, 16).map_err(serde::de::Error::custom)
     }
 }
+
+/// Serialize a UTXO set as a sequence of pairs: JSON maps only have string keys.
+/// Identical to the map encoding in bincode.
+pub mod utxo_set_pairs {
+    use crate::interfaces::UtxoSet;
+    use naom::primitives::transaction::{OutPoint, TxOut};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serialisation function for UTXO sets
+    pub fn serialize<S>(utxo_set: &UtxoSet, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_seq(utxo_set.iter())
+    }
+
+    /// Deserialisation function for UTXO sets
+    pub fn deserialize<'de, D>(d: D) -> Result<UtxoSet, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let pairs: Vec<(OutPoint, TxOut)> = Deserialize::deserialize(d)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{EventCounter, LogCapture};
+    use naom::utils::script_utils::tx_is_valid;
+    use naom::utils::transaction_utils::{construct_address_for, construct_receipt_create_tx};
+
+    /// Validate a block nonce found at a difficulty fails at the next difficulty
+    #[test]
+    fn validate_pow_block_at_explicit_difficulty() {
+        //
+        // Arrange
+        //
+        let difficulty = 1;
+        let header = loop {
+            let header = generate_pow_for_block(BlockHeader {
+                bits: difficulty,
+                ..Default::default()
+            });
+            let hash = sha3_256::digest(&serialize(&header).unwrap());
+            if hash[difficulty] != 0 {
+                break header;
+            }
+        };
+
+        //
+        // Act
+        //
+        let valid_at_diff = validate_pow_block(&header, difficulty);
+        let valid_above_diff = validate_pow_block(&header, difficulty + 1);
+
+        //
+        // Assert
+        //
+        assert!(valid_at_diff);
+        assert!(!valid_above_diff);
+    }
+
+    /// Validate an address nonce found at a difficulty fails at the next difficulty
+    #[test]
+    fn validate_pow_for_address_at_explicit_difficulty() {
+        //
+        // Arrange
+        //
+        let difficulty = 1;
+        let rand_num = generate_random_num(16);
+        let pow = loop {
+            let pow = ProofOfWork {
+                address: "address".to_owned(),
+                nonce: generate_pow_nonce(),
+            };
+            let mut pow_body = pow.address.as_bytes().to_vec();
+            pow_body.extend(&rand_num);
+            pow_body.extend(&pow.nonce);
+            let hash = sha3_256::digest(&pow_body);
+            if hash[..difficulty].iter().all(|v| *v == 0) && hash[difficulty] != 0 {
+                break pow;
+            }
+        };
+
+        //
+        // Act
+        //
+        let valid_at_diff = validate_pow_for_address(&pow, &Some(&rand_num), difficulty);
+        let valid_above_diff = validate_pow_for_address(&pow, &Some(&rand_num), difficulty + 1);
+
+        //
+        // Assert
+        //
+        assert!(valid_at_diff);
+        assert!(!valid_above_diff);
+    }
+
+    /// Addresses constructed for each supported version validate for that version
+    #[test]
+    fn validate_address_for_each_version() {
+        //
+        // Arrange
+        //
+        let (pk, _) = sign::gen_keypair();
+        let versions = [Some(NETWORK_VERSION_V0), Some(NETWORK_VERSION_TEMP), None];
+        let addresses: Vec<_> = versions
+            .iter()
+            .map(|v| (construct_address_for(&pk, *v), *v))
+            .collect();
+
+        //
+        // Act
+        //
+        let actual: Vec<_> = addresses
+            .iter()
+            .map(|(addr, v)| {
+                let for_version = validate_address(addr, *v).map(|a| a.into_string());
+                let any_version = validate_address(addr, None).map(|a| a.into_string());
+                (for_version, any_version)
+            })
+            .collect();
+
+        //
+        // Assert
+        //
+        let expected: Vec<_> = addresses
+            .iter()
+            .map(|(addr, _)| (Ok(addr.clone()), Ok(addr.clone())))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    /// Malformed addresses are rejected and valid ones normalized to lower case
+    #[test]
+    fn validate_address_malformed_and_casing() {
+        //
+        // Arrange
+        //
+        let legacy = "13bd3351b78beb2d0dadf2058dcc926c";
+        let current = "ca0abdcd2826a77218af0914601ee34c7ff44127aab9d0671267b25a7d36946a";
+
+        //
+        // Act
+        //
+        let upper = validate_address(&legacy.to_uppercase(), None);
+        let legacy_as_current = validate_address(legacy, Some(2));
+        let current_as_legacy = validate_address(current, Some(NETWORK_VERSION_V0));
+        let not_hex = validate_address("13bd3351b78beb2d0dadf2058dcc926z", None);
+        let too_short = validate_address("13bd", None);
+        let empty = validate_address("", None);
+
+        //
+        // Assert
+        //
+        assert_eq!(upper.map(|a| a.into_string()), Ok(legacy.to_owned()));
+        assert_eq!(legacy_as_current, Err(AddressError::InvalidLength(32)));
+        assert_eq!(current_as_legacy, Err(AddressError::InvalidLength(64)));
+        assert_eq!(not_hex, Err(AddressError::NotHex));
+        assert_eq!(too_short, Err(AddressError::InvalidLength(4)));
+        assert_eq!(empty, Err(AddressError::InvalidLength(0)));
+    }
+
+    /// Reward is halved when crossing each halving boundary
+    #[test]
+    fn calculate_reward_halving_boundaries() {
+        //
+        // Arrange
+        //
+        let halving = RewardHalvingSchedule {
+            halving_interval: 10,
+            base_reward: Some(1000),
+        };
+        let circulation = TokenAmount(0);
+
+        //
+        // Act
+        //
+        let rewards: Vec<_> = [0, 9, 10, 19, 20, 10 * 64]
+            .iter()
+            .map(|b_num| calculate_reward(circulation, *b_num, Some(&halving)).0)
+            .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(rewards, vec![1000, 1000, 500, 500, 250, 0]);
+    }
+
+    /// Halving applies to the circulation reward when no base reward is set
+    #[test]
+    fn calculate_reward_halving_from_circulation() {
+        //
+        // Arrange
+        //
+        let halving = RewardHalvingSchedule {
+            halving_interval: 10,
+            base_reward: None,
+        };
+        let circulation = TokenAmount(TOTAL_TOKENS / 2);
+        let no_halving = calculate_reward(circulation, 9, None);
+
+        //
+        // Act
+        //
+        let before = calculate_reward(circulation, 9, Some(&halving));
+        let after = calculate_reward(circulation, 10, Some(&halving));
+
+        //
+        // Assert
+        //
+        assert_eq!(before, no_halving);
+        assert_eq!(after, TokenAmount(no_halving.0 / 2));
+    }
+
+    /// Headers without recorded difficulty use the default one
+    #[test]
+    fn mining_difficulty_from_legacy_bits() {
+        assert_eq!(mining_difficulty_from_bits(0), MINING_DIFFICULTY);
+        assert_eq!(mining_difficulty_from_bits(3), 3);
+    }
+
+    /// Reloading the log filter changes which events are emitted at runtime
+    #[test]
+    fn reload_log_filter_changes_level() {
+        //
+        // Arrange
+        //
+        let counter = EventCounter::default();
+        let (filter, handle) = reload::Layer::new(EnvFilter::new("off"));
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(counter.clone());
+
+        //
+        // Act
+        //
+        let (before, after, invalid) = tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("Filtered out");
+            let before = counter.count();
+            reload_log_filter(&handle, "debug").unwrap();
+            tracing::debug!("Emitted");
+            let invalid = reload_log_filter(&handle, "znp=not_a_level");
+            (before, counter.count(), invalid)
+        });
+
+        //
+        // Assert
+        //
+        assert_eq!((before, after), (0, 1));
+        assert!(invalid.is_err());
+    }
+
+    /// JSON log lines are parseable and hold the event and span fields
+    #[test]
+    fn json_log_layer_emits_json_lines() {
+        //
+        // Arrange
+        //
+        let capture = LogCapture::default();
+        let make_writer = {
+            let capture = capture.clone();
+            move || capture.clone()
+        };
+        let subscriber = tracing_subscriber::registry().with(json_log_layer(make_writer));
+
+        //
+        // Act
+        //
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("block", b_num = 3);
+            let _entered = span.enter();
+            tracing::info!(tx_count = 2, "Block mined");
+        });
+        let lines: Vec<serde_json::Value> = capture
+            .contents()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert!(line["timestamp"].is_string());
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["target"], "znp::utils::test");
+        assert_eq!(line["message"], "Block mined");
+        assert_eq!(line["tx_count"], 2);
+        assert_eq!(line["span"]["name"], "block");
+        assert_eq!(line["span"]["b_num"], 3);
+    }
+
+    /// Fee is the tokens spent from the UTXO set minus the tokens paid out
+    #[test]
+    fn tx_fee_from_spent_inputs() {
+        //
+        // Arrange
+        //
+        let spent = OutPoint::new("000000".to_owned(), 0);
+        let missing = OutPoint::new("000001".to_owned(), 0);
+        let utxo_set: UtxoSet = vec![(
+            spent.clone(),
+            TxOut::new_token_amount(String::new(), TokenAmount(5)),
+        )]
+        .into_iter()
+        .collect();
+        let tx_paying = |previous_outs: &[&OutPoint], amount: u64| Transaction {
+            inputs: previous_outs
+                .iter()
+                .map(|previous_out| TxIn {
+                    previous_out: Some((*previous_out).clone()),
+                    script_signature: Script::new(),
+                })
+                .collect(),
+            outputs: vec![TxOut::new_token_amount(String::new(), TokenAmount(amount))],
+            ..Transaction::default()
+        };
+
+        //
+        // Act
+        //
+        let fees: Vec<_> = vec![
+            tx_paying(&[&spent], 3),
+            tx_paying(&[&spent], 5),
+            tx_paying(&[&spent], 7),
+            tx_paying(&[&spent, &missing], 3),
+            tx_paying(&[], 0),
+        ]
+        .iter()
+        .map(|tx| tx_fee(tx, &utxo_set))
+        .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            fees,
+            vec![
+                TokenAmount(2),
+                TokenAmount(0),
+                TokenAmount(0),
+                TokenAmount(2),
+                TokenAmount(0)
+            ]
+        );
+    }
+
+    /// A payment built offline validates against the UTXO set owned by its signing keys
+    #[test]
+    fn build_signed_payment_validates_against_utxo_set() {
+        //
+        // Arrange
+        //
+        let keypairs = vec![sign::gen_keypair(), sign::gen_keypair()];
+        let inputs = vec![
+            OutPoint::new("000000".to_owned(), 0),
+            OutPoint::new("000001".to_owned(), 1),
+        ];
+        let utxo_set: UtxoSet = inputs
+            .iter()
+            .zip(&keypairs)
+            .map(|(out_point, (pk, _))| {
+                let tx_out = TxOut::new_token_amount(construct_address(pk), TokenAmount(3));
+                (out_point.clone(), tx_out)
+            })
+            .collect();
+        let outputs = vec![TxOut::new_token_amount("000100".to_owned(), TokenAmount(6))];
+        let swapped_keypairs: Vec<_> = keypairs.iter().rev().cloned().collect();
+
+        //
+        // Act
+        //
+        let tx = build_signed_payment(&inputs, outputs.clone(), &keypairs).unwrap();
+        let swapped_tx = build_signed_payment(&inputs, outputs.clone(), &swapped_keypairs).unwrap();
+        let missing_key = build_signed_payment(&inputs, outputs, &keypairs[0..1]);
+
+        //
+        // Assert
+        //
+        assert!(tx_is_valid(&tx, |v| utxo_set.get(v)));
+        assert!(!tx_is_valid(&swapped_tx, |v| utxo_set.get(v)));
+        assert!(missing_key.is_err());
+    }
+
+    /// Signatures are checked against the signed previous outputs and assets
+    #[test]
+    fn verify_transaction_signatures_detects_tampering() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let inputs = vec![OutPoint::new("000000".to_owned(), 0)];
+        let outputs = vec![TxOut::new_token_amount("000100".to_owned(), TokenAmount(3))];
+        let tx = build_signed_payment(&inputs, outputs, &[(pk, sk.clone())]).unwrap();
+        let create_tx = construct_receipt_create_tx(0, pk, &sk, 3, DrsTxHashSpec::Create, None);
+
+        let mut tampered_signature_tx = tx.clone();
+        let other_signature = sign::sign_detached(b"other data", &sk);
+        for entry in tampered_signature_tx.inputs[0]
+            .script_signature
+            .stack
+            .iter_mut()
+        {
+            if let StackEntry::Signature(signature) = entry {
+                *signature = other_signature;
+            }
+        }
+
+        let mut tampered_previous_out_tx = tx.clone();
+        tampered_previous_out_tx.inputs[0].previous_out =
+            Some(OutPoint::new("000000".to_owned(), 1));
+
+        let mut no_output_create_tx = create_tx.clone();
+        no_output_create_tx.outputs.clear();
+
+        //
+        // Act
+        //
+        let verified: Vec<_> = vec![
+            &tx,
+            &create_tx,
+            &tampered_signature_tx,
+            &tampered_previous_out_tx,
+            &no_output_create_tx,
+            &Transaction::default(),
+        ]
+        .into_iter()
+        .map(verify_transaction_signatures)
+        .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(verified, vec![true, true, false, false, false, false]);
+    }
+
+    /// Receipts can only be moved for their own DRS tx hash, not created from tokens
+    #[test]
+    fn tx_receipts_balanced_per_drs_tx_hash() {
+        //
+        // Arrange
+        //
+        let token = OutPoint::new("000000".to_owned(), 0);
+        let created = OutPoint::new("000001".to_owned(), 0);
+        let utxo_set: UtxoSet = vec![
+            (
+                token.clone(),
+                TxOut::new_token_amount(String::new(), TokenAmount(5)),
+            ),
+            (
+                created.clone(),
+                TxOut::new_asset(String::new(), Asset::receipt(2, None, None)),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let receipt = |amount: u64, drs_tx_hash: Option<&str>| {
+            Asset::receipt(amount, drs_tx_hash.map(|h| h.to_owned()), None)
+        };
+        let tx_paying = |previous_outs: &[&OutPoint], assets: Vec<Asset>| Transaction {
+            inputs: previous_outs
+                .iter()
+                .map(|previous_out| TxIn {
+                    previous_out: Some((*previous_out).clone()),
+                    script_signature: Script::new(),
+                })
+                .collect(),
+            outputs: assets
+                .into_iter()
+                .map(|asset| TxOut::new_asset(String::new(), asset))
+                .collect(),
+            ..Transaction::default()
+        };
+
+        //
+        // Act
+        //
+        let balanced: Vec<bool> = vec![
+            tx_paying(&[&token], vec![Asset::token_u64(5)]),
+            tx_paying(&[&created], vec![receipt(2, Some("000001"))]),
+            tx_paying(
+                &[&token, &created],
+                vec![Asset::token_u64(4), receipt(2, Some("000001"))],
+            ),
+            tx_paying(
+                &[&token],
+                vec![Asset::token_u64(2), receipt(3, Some("000001"))],
+            ),
+            tx_paying(&[&created], vec![receipt(2, Some("drs_tx_hash"))]),
+            tx_paying(&[&created], vec![receipt(2, None)]),
+            tx_paying(&[&created], vec![Asset::token_u64(2)]),
+            tx_paying(&[&created], vec![receipt(3, Some("000001"))]),
+        ]
+        .iter()
+        .map(|tx| tx_receipts_are_balanced(tx, &utxo_set))
+        .collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            balanced,
+            vec![true, true, true, false, false, false, false, false]
+        );
+    }
+
+    /// Minted receipts are tracked by their create transaction hash through transfers
+    #[test]
+    fn receipt_drs_tx_hash_preserved_through_transfer() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let create_tx = construct_receipt_create_tx(0, pk, &sk, 3, DrsTxHashSpec::Create, None);
+        let create_tx_hash = construct_tx_hash(&create_tx);
+        let mut utxo_set: UtxoSet =
+            get_tx_out_with_out_point_cloned(std::iter::once((&create_tx_hash, &create_tx)))
+                .collect();
+        let minted = OutPoint::new(create_tx_hash.clone(), 0);
+        let transfer_tx = |asset: Asset| Transaction {
+            inputs: vec![TxIn {
+                previous_out: Some(minted.clone()),
+                script_signature: Script::new(),
+            }],
+            outputs: vec![TxOut::new_asset(String::new(), asset)],
+            ..Transaction::default()
+        };
+
+        //
+        // Act
+        //
+        // The wallet stores the spendable receipt with the lineage fixed from its `OutPoint`
+        let minted_asset = utxo_set[&minted].value.clone().with_fixed_hash(&minted);
+        let transfer = transfer_tx(minted_asset.clone());
+        let altered_transfer = transfer_tx(Asset::receipt(3, Some("other".to_owned()), None));
+        let unfixed_transfer = transfer_tx(utxo_set[&minted].value.clone());
+        let transfer_valid = tx_receipts_are_balanced(&transfer, &utxo_set);
+        let altered_transfer_valid = tx_receipts_are_balanced(&altered_transfer, &utxo_set);
+        let unfixed_transfer_valid = tx_receipts_are_balanced(&unfixed_transfer, &utxo_set);
+
+        let transfer_hash = construct_tx_hash(&transfer);
+        utxo_set.remove(&minted);
+        utxo_set.extend(get_tx_out_with_out_point_cloned(std::iter::once((
+            &transfer_hash,
+            &transfer,
+        ))));
+        let transferred = OutPoint::new(transfer_hash, 0);
+        let transferred_asset = utxo_set[&transferred].value.clone();
+
+        //
+        // Assert
+        //
+        let drs_tx_hash = |asset: &Asset| match asset {
+            Asset::Receipt(receipt) => receipt.drs_tx_hash.clone(),
+            _ => None,
+        };
+        assert_eq!(drs_tx_hash(&minted_asset), Some(create_tx_hash.clone()));
+        assert_eq!(
+            (
+                transfer_valid,
+                altered_transfer_valid,
+                unfixed_transfer_valid
+            ),
+            (true, false, false)
+        );
+        assert_eq!(
+            transferred_asset.with_fixed_hash(&transferred),
+            minted_asset
+        );
+        assert_eq!(
+            drs_tx_hash(&utxo_set[&transferred].value),
+            Some(create_tx_hash)
+        );
+    }
+}
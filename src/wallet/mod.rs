@@ -1,5 +1,7 @@
-use crate::configurations::{DbMode, WalletTxSpec};
-use crate::constants::{FUND_KEY, KNOWN_ADDRESS_KEY, WALLET_PATH};
+use crate::configurations::{DbMode, DbTuning, WalletTxSpec};
+use crate::constants::{
+    DB_VERSION_KEY, FUND_KEY, KNOWN_ADDRESS_KEY, NETWORK_VERSION_SERIALIZED, WALLET_PATH,
+};
 use crate::db_utils::{
     self, CustomDbSpec, SimpleDb, SimpleDbError, SimpleDbSpec, SimpleDbWriteBatch, DB_COL_DEFAULT,
 };
@@ -9,6 +11,7 @@ use bincode::{deserialize, serialize};
 use hex::FromHexError;
 use naom::crypto::pbkdf2 as pwhash;
 use naom::crypto::secretbox_chacha20_poly1305 as secretbox;
+use naom::crypto::sha3_256;
 use naom::crypto::sign_ed25519 as sign;
 use naom::crypto::sign_ed25519::{PublicKey, SecretKey};
 use naom::primitives::asset::{Asset, TokenAmount};
@@ -19,8 +22,8 @@ use naom::utils::transaction_utils::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
-use std::sync::{Arc, Mutex};
-use std::{error, fmt, io};
+use std::sync::{Arc, Mutex, RwLock};
+use std::{error, fmt, io, iter};
 use tokio::sync::Mutex as TokioMutex;
 use tokio::task;
 use tracing::warn;
@@ -33,6 +36,29 @@ pub const LOCKED_COINBASE_KEY: &str = "LockedCoinbaseKey";
 /// Storage key for a &[u8] of the word 'MasterKeyStore'
 pub const MASTER_KEY_STORE_KEY: &str = "MasterKeyStore";
 
+/// Key for known addresses kept out of the active set
+pub const ARCHIVED_ADDRESS_KEY: &str = "ArchivedAddressKey";
+
+/// Key for the order in which known addresses were last used
+pub const ADDRESS_LAST_USED_KEY: &str = "AddressLastUsedKey";
+
+/// Key for the key encrypting all wallet values at rest, itself encrypted with the master key
+pub const AT_REST_KEY_STORE_KEY: &str = "AtRestKeyStore";
+
+/// Key for the key derivation parameters used for the master key store
+pub const MASTER_KEY_KDF_KEY: &str = "MasterKeyKdf";
+
+/// Key and value of a wallet entry encrypted at rest
+type AtRestEntry = (Vec<u8>, Vec<u8>);
+
+/// Keys whose values are never encrypted at rest
+const AT_REST_PLAIN_KEYS: &[&str] = &[
+    DB_VERSION_KEY,
+    MASTER_KEY_STORE_KEY,
+    MASTER_KEY_KDF_KEY,
+    AT_REST_KEY_STORE_KEY,
+];
+
 pub const DB_SPEC: SimpleDbSpec = SimpleDbSpec {
     db_path: WALLET_PATH,
     suffix: "",
@@ -60,6 +86,8 @@ pub enum WalletDbError {
     InsufficientFundsError,
     MasterKeyRetrievalError,
     MasterKeyMissingError,
+    WeakPassphrase(String),
+    WeakKdf(String),
 }
 
 impl fmt::Display for WalletDbError {
@@ -74,6 +102,8 @@ impl fmt::Display for WalletDbError {
             Self::InsufficientFundsError => write!(f, "InsufficientFundsError"),
             Self::MasterKeyRetrievalError => write!(f, "MasterKeyRetrievalError"),
             Self::MasterKeyMissingError => write!(f, "MasterKeyMissingError"),
+            Self::WeakPassphrase(reason) => write!(f, "Weak passphrase: {reason}"),
+            Self::WeakKdf(reason) => write!(f, "Weak key derivation: {reason}"),
         }
     }
 }
@@ -90,6 +120,8 @@ impl error::Error for WalletDbError {
             Self::InsufficientFundsError => None,
             Self::MasterKeyRetrievalError => None,
             Self::MasterKeyMissingError => None,
+            Self::WeakPassphrase(_) => None,
+            Self::WeakKdf(_) => None,
         }
     }
 }
@@ -174,6 +206,70 @@ pub struct MasterKeyStore {
     pub enc_master_key: Vec<u8>,
 }
 
+/// Key derivation parameters the master key store was sealed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub iterations: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            iterations: pwhash::OPSLIMIT_INTERACTIVE,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Check the key derivation cost is not below the interactive minimum
+    pub fn validate(&self) -> Result<()> {
+        if self.iterations < pwhash::OPSLIMIT_INTERACTIVE {
+            return Err(WalletDbError::WeakKdf(format!(
+                "{} iterations, at least {} required",
+                self.iterations,
+                pwhash::OPSLIMIT_INTERACTIVE
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Passphrase strength requirements and key derivation cost for new passphrases
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PassphraseConfig {
+    /// Minimum number of characters, unchecked if None
+    pub min_length: Option<usize>,
+    /// Minimum estimated entropy in bits, unchecked if None
+    pub min_entropy_bits: Option<u32>,
+    /// Key derivation parameters used when sealing the master key
+    pub kdf: KdfParams,
+}
+
+impl PassphraseConfig {
+    /// Check a new passphrase meets the strength requirements
+    ///
+    /// ### Arguments
+    ///
+    /// * `passphrase` - Passphrase to check
+    pub fn validate(&self, passphrase: &[u8]) -> Result<()> {
+        let passphrase = String::from_utf8_lossy(passphrase);
+        let length = passphrase.chars().count();
+        if let Some(min_length) = self.min_length.filter(|min| length < *min) {
+            return Err(WalletDbError::WeakPassphrase(format!(
+                "{length} characters, at least {min_length} required"
+            )));
+        }
+
+        let entropy = passphrase_entropy_bits(&passphrase);
+        if let Some(min_entropy) = self.min_entropy_bits.filter(|min| entropy < *min) {
+            return Err(WalletDbError::WeakPassphrase(format!(
+                "{entropy} bits of entropy, at least {min_entropy} required"
+            )));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WalletDb {
     db: Arc<Mutex<SimpleDb>>,
@@ -182,6 +278,9 @@ pub struct WalletDb {
     locked_coinbase: LockedCoinbaseWithMutex,
     last_generated_address: Option<String>,
     last_locked_coinbase_filter_b_num: Option<u64>,
+    max_active_addresses: Option<usize>,
+    at_rest_key: Arc<RwLock<Option<secretbox::Key>>>,
+    passphrase_config: PassphraseConfig,
 }
 
 impl WalletDb {
@@ -191,11 +290,76 @@ impl WalletDb {
         passphrase: Option<String>,
         custom_db_spec: Option<CustomDbSpec>,
     ) -> Result<Self> {
-        let mut db = db_utils::new_db(db_mode, &DB_SPEC, db, custom_db_spec);
+        Self::new_with_passphrase_config(
+            db_mode,
+            db,
+            passphrase,
+            custom_db_spec,
+            Default::default(),
+        )
+    }
+
+    /// Open or create the wallet, checking the passphrase of a new wallet
+    /// meets the strength requirements and sealing it with the given KDF cost
+    ///
+    /// ### Arguments
+    ///
+    /// * `db_mode`           - Mode for the database
+    /// * `db`                - Optional existing database to use
+    /// * `passphrase`        - Wallet passphrase
+    /// * `custom_db_spec`    - Optional custom database specification
+    /// * `passphrase_config` - Passphrase strength requirements and KDF cost
+    pub fn new_with_passphrase_config(
+        db_mode: DbMode,
+        db: Option<SimpleDb>,
+        passphrase: Option<String>,
+        custom_db_spec: Option<CustomDbSpec>,
+        passphrase_config: PassphraseConfig,
+    ) -> Result<Self> {
+        Self::new_with_passphrase_config_and_tuning(
+            db_mode,
+            db,
+            passphrase,
+            custom_db_spec,
+            passphrase_config,
+            &Default::default(),
+        )
+    }
+
+    /// Open or create the wallet as `new_with_passphrase_config`,
+    /// with the given performance tuning for a file database
+    ///
+    /// ### Arguments
+    ///
+    /// * `db_mode`           - Mode for the database
+    /// * `db`                - Optional existing database to use
+    /// * `passphrase`        - Wallet passphrase
+    /// * `custom_db_spec`    - Optional custom database specification
+    /// * `passphrase_config` - Passphrase strength requirements and KDF cost
+    /// * `tuning`            - Performance tuning for file database
+    pub fn new_with_passphrase_config_and_tuning(
+        db_mode: DbMode,
+        db: Option<SimpleDb>,
+        passphrase: Option<String>,
+        custom_db_spec: Option<CustomDbSpec>,
+        passphrase_config: PassphraseConfig,
+        tuning: &DbTuning,
+    ) -> Result<Self> {
+        passphrase_config.kdf.validate()?;
+        let mut db = db_utils::new_db_with_version_and_tuning(
+            db_mode,
+            &DB_SPEC,
+            Some(NETWORK_VERSION_SERIALIZED),
+            db,
+            custom_db_spec,
+            tuning,
+        )?;
         let mut batch = db.batch_writer();
 
         let passphrase = passphrase.as_deref().unwrap_or("").as_bytes();
-        let masterkey = get_or_save_master_key_store(&db, &mut batch, passphrase)?;
+        let masterkey =
+            get_or_save_master_key_store(&db, &mut batch, passphrase, &passphrase_config)?;
+        let at_rest_key = get_at_rest_key_store(&db, &masterkey)?;
 
         let batch = batch.done();
         db.write(batch).unwrap();
@@ -206,9 +370,58 @@ impl WalletDb {
             ui_feedback_tx: None,
             last_generated_address: None,
             last_locked_coinbase_filter_b_num: None,
+            max_active_addresses: None,
+            at_rest_key: Arc::new(RwLock::new(at_rest_key)),
+            passphrase_config,
         })
     }
 
+    /// Encrypt all wallet values at rest, not only the address keys,
+    /// and store them under keyed hashes of their keys.
+    /// A wallet already encrypted at rest remains so.
+    ///
+    /// ## Arguments
+    /// * `enabled` - Whether to encrypt the wallet values at rest
+    pub fn with_encryption_at_rest(self, enabled: bool) -> Result<Self> {
+        {
+            let mut db = self.db.lock().unwrap();
+            let mut at_rest_key = self.at_rest_key.write().unwrap();
+            if enabled && at_rest_key.is_none() {
+                let new_key = secretbox::gen_key();
+                let mut batch = db.batch_writer();
+                for (db_key, key, value) in get_at_rest_entries(&db, None) {
+                    batch.delete_cf(DB_COL_DEFAULT, db_key);
+                    put_wallet_value(&mut batch, key, value, Some(&new_key));
+                }
+                set_at_rest_key_store(&mut batch, &new_key, &self.encryption_key);
+                let batch = batch.done();
+                db.write(batch)?;
+                *at_rest_key = Some(new_key);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Whether all wallet values are encrypted at rest
+    pub fn is_encrypted_at_rest(&self) -> bool {
+        self.at_rest_key.read().unwrap().is_some()
+    }
+
+    /// Get the key the wallet values are encrypted at rest with, if any
+    fn get_at_rest_key(&self) -> Option<secretbox::Key> {
+        self.at_rest_key.read().unwrap().clone()
+    }
+
+    /// Set the maximum number of active addresses: least recently used
+    /// zero-balance addresses beyond it are archived
+    ///
+    /// ## Arguments
+    /// * `limit` - The maximum number of active addresses, unbounded if None
+    pub fn with_max_active_addresses(mut self, limit: Option<usize>) -> Self {
+        self.max_active_addresses = limit;
+        self
+    }
+
     /// Set the UI feedback channel
     ///
     /// ## Arguments
@@ -286,7 +499,8 @@ impl WalletDb {
         .await?
     }
 
-    /// Re-encrypt the master key with a new passphrase
+    /// Re-encrypt the master key with a new passphrase,
+    /// and all wallet values with a new key if encrypted at rest
     ///
     /// ### Arguments
     ///
@@ -297,24 +511,37 @@ impl WalletDb {
         new_passphrase: String,
     ) -> Result<()> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
+        let passphrase_config = self.passphrase_config;
         task::spawn_blocking(move || {
             let mut db = db.lock().unwrap();
+            let mut at_rest_key = at_rest.write().unwrap();
             let mut batch = db.batch_writer();
             let master_key = get_master_key_store(&db, old_passphrase.as_bytes())?;
-            let salt = pwhash::gen_salt();
-            let nonce = secretbox::gen_nonce();
-            let pass_key = make_key(new_passphrase.as_bytes(), salt);
-            let enc_master_key =
-                secretbox::seal(master_key.as_ref().to_vec(), &nonce, &pass_key).unwrap();
-            let store = serialize(&MasterKeyStore {
-                salt,
-                nonce,
-                enc_master_key,
-            })
-            .unwrap();
-            batch.put_cf(DB_COL_DEFAULT, MASTER_KEY_STORE_KEY, &store);
+            passphrase_config.validate(new_passphrase.as_bytes())?;
+            set_master_key_store(
+                &mut batch,
+                &master_key,
+                new_passphrase.as_bytes(),
+                passphrase_config.kdf,
+            );
+
+            // Re-encrypt all values with a new key if encrypted at rest
+            let new_at_rest_key = at_rest_key.as_ref().map(|old_key| {
+                let new_key = secretbox::gen_key();
+                for (db_key, key, value) in get_at_rest_entries(&db, Some(old_key)) {
+                    batch.delete_cf(DB_COL_DEFAULT, db_key);
+                    put_wallet_value(&mut batch, key, value, Some(&new_key));
+                }
+                set_at_rest_key_store(&mut batch, &new_key, &master_key);
+                new_key
+            });
+
             let batch = batch.done();
             db.write(batch).unwrap();
+            if new_at_rest_key.is_some() {
+                *at_rest_key = new_at_rest_key;
+            }
             Ok(())
         })
         .await?
@@ -396,18 +623,20 @@ impl WalletDb {
     /// * `keys`    - Address-related keys to save
     pub async fn save_address_to_wallet(&self, address: String, keys: AddressStore) -> Result<()> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         let encryption_key = self.encryption_key.clone();
+        let max_active = self.max_active_addresses;
         Ok(task::spawn_blocking(move || {
             // Wallet DB handling
             let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
+            let at_rest_key = at_rest_key.as_ref();
             let mut batch = db.batch_writer();
 
-            let mut address_list = get_known_key_address(&db);
-            address_list.insert(address.clone());
-
             // Save to disk
-            save_address_store_to_wallet(&mut batch, &address, keys, &encryption_key);
-            set_known_key_address(&mut batch, address_list);
+            save_address_store_to_wallet(&mut batch, &address, keys, &encryption_key, at_rest_key);
+            let addresses = iter::once(address).collect();
+            activate_key_addresses(&db, &mut batch, &addresses, max_active, at_rest_key);
             let batch = batch.done();
             db.write(batch).unwrap();
         })
@@ -426,15 +655,17 @@ impl WalletDb {
         keys: Vec<u8>,
     ) -> Result<()> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
+        let max_active = self.max_active_addresses;
         Ok(task::spawn_blocking(move || {
             let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
+            let at_rest_key = at_rest_key.as_ref();
             let mut batch = db.batch_writer();
 
-            let mut address_list = get_known_key_address(&db);
-            address_list.insert(address.clone());
-
-            batch.put_cf(DB_COL_DEFAULT, address, keys);
-            set_known_key_address(&mut batch, address_list);
+            put_wallet_value(&mut batch, &address, keys, at_rest_key);
+            let addresses = iter::once(address).collect();
+            activate_key_addresses(&db, &mut batch, &addresses, max_active, at_rest_key);
 
             let batch = batch.done();
             db.write(batch).unwrap();
@@ -454,12 +685,14 @@ impl WalletDb {
         key_address: String,
     ) -> Result<()> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         Ok(task::spawn_blocking(move || {
             let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
             let mut batch = db.batch_writer();
 
             let store = TransactionStore { key_address };
-            save_transaction_to_wallet(&mut batch, &out_p, &store);
+            save_transaction_to_wallet(&mut batch, &out_p, &store, at_rest_key.as_ref());
 
             let batch = batch.done();
             db.write(batch).unwrap();
@@ -479,17 +712,22 @@ impl WalletDb {
         current_b_num: u64,
     ) -> Result<Vec<(OutPoint, Asset, String, u64)>> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
+        let max_active = self.max_active_addresses;
         let locked_coinbase = self.get_locked_coinbase().await.unwrap_or_default();
         let (result, locked_db) = task::spawn_blocking(move || {
             let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
+            let at_rest_key = at_rest_key.as_ref();
             let mut batch = db.batch_writer();
-            let mut fund_store = get_fund_store(&db);
+            let mut fund_store = get_fund_store(&db, at_rest_key);
             let mut locked_coinbase = locked_coinbase.clone();
-            let addresses = get_known_key_address(&db);
+            let addresses = get_known_key_address(&db, at_rest_key);
+            let archived = get_archived_key_address(&db, at_rest_key);
 
             let usable_payments: Vec<_> = payments
                 .into_iter()
-                .filter(|(_, _, a, _)| addresses.contains(a))
+                .filter(|(_, _, a, _)| addresses.contains(a) || archived.contains(a))
                 .collect();
 
             for (out_p, asset, key_address, locktime) in &usable_payments {
@@ -497,14 +735,24 @@ impl WalletDb {
                 let store = TransactionStore { key_address };
                 let asset_to_store = asset.clone().with_fixed_hash(out_p);
                 fund_store.store_tx(out_p.clone(), asset_to_store);
-                save_transaction_to_wallet(&mut batch, out_p, &store);
+                save_transaction_to_wallet(&mut batch, out_p, &store, at_rest_key);
                 if *locktime > current_b_num {
                     locked_coinbase.insert(out_p.t_hash.clone(), *locktime);
                 }
             }
 
-            set_fund_store(&mut batch, fund_store);
+            set_fund_store(&mut batch, fund_store, at_rest_key);
+
+            let batch = batch.done();
+            db.write(batch).unwrap();
 
+            // Funded addresses are restored to active once their funds are stored
+            let mut batch = db.batch_writer();
+            let received: BTreeSet<_> = usable_payments
+                .iter()
+                .map(|(_, _, a, _)| a.clone())
+                .collect();
+            activate_key_addresses(&db, &mut batch, &received, max_active, at_rest_key);
             let batch = batch.done();
             db.write(batch).unwrap();
             (usable_payments, locked_coinbase)
@@ -639,11 +887,19 @@ impl WalletDb {
         asset_required: Asset,
     ) -> Result<(Vec<TxConstructor>, Asset, Vec<(OutPoint, String)>)> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         let encryption_key = self.encryption_key.clone();
         let locked_coinbase = self.get_locked_coinbase().await;
         task::spawn_blocking(move || {
             let db = db.lock().unwrap();
-            fetch_inputs_for_payment_from_db(&db, asset_required, &encryption_key, &locked_coinbase)
+            let at_rest_key = at_rest.read().unwrap().clone();
+            fetch_inputs_for_payment_from_db(
+                &db,
+                asset_required,
+                &encryption_key,
+                &locked_coinbase,
+                at_rest_key.as_ref(),
+            )
         })
         .await?
     }
@@ -660,15 +916,18 @@ impl WalletDb {
         addresses: BTreeSet<String>,
     ) -> Result<(Vec<TxConstructor>, Asset, Vec<(OutPoint, String)>)> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         let locked_coinbase = self.get_locked_coinbase().await;
         let encryption_key = self.encryption_key.clone();
         task::spawn_blocking(move || {
             let db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
             fetch_inputs_for_payment_from_supplied_input_addrs_db(
                 &db,
                 addresses,
                 &encryption_key,
                 &locked_coinbase,
+                at_rest_key.as_ref(),
             )
         })
         .await?
@@ -686,10 +945,17 @@ impl WalletDb {
         txs: Vec<(OutPoint, Asset)>,
     ) -> Result<(Vec<TxConstructor>, Asset, Vec<(OutPoint, String)>)> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         let encryption_key = self.encryption_key.clone();
         task::spawn_blocking(move || {
             let db = db.lock().unwrap();
-            fetch_inputs_from_supplied_txs_for_payment_from_db(&db, txs, &encryption_key)
+            let at_rest_key = at_rest.read().unwrap().clone();
+            fetch_inputs_from_supplied_txs_for_payment_from_db(
+                &db,
+                txs,
+                &encryption_key,
+                at_rest_key.as_ref(),
+            )
         })
         .await?
     }
@@ -706,15 +972,17 @@ impl WalletDb {
         tx_used: Vec<(OutPoint, String)>,
     ) -> Vec<TxIn> {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         task::spawn_blocking(move || {
             let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
             let mut batch = db.batch_writer();
-            let mut fund_store = get_fund_store(&db);
+            let mut fund_store = get_fund_store(&db, at_rest_key.as_ref());
 
             for (out_p, _) in tx_used {
                 fund_store.spend_tx(&out_p);
             }
-            set_fund_store(&mut batch, fund_store);
+            set_fund_store(&mut batch, fund_store, at_rest_key.as_ref());
             let batch = batch.done();
             db.write(batch).unwrap();
 
@@ -731,9 +999,11 @@ impl WalletDb {
         addresses: Option<BTreeSet<String>>,
     ) -> (BTreeSet<String>, BTreeMap<OutPoint, Asset>) {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         task::spawn_blocking(move || {
             let mut db = db.lock().unwrap();
-            destroy_spent_transactions_and_keys(&mut db, addresses)
+            let at_rest_key = at_rest.read().unwrap().clone();
+            destroy_spent_transactions_and_keys(&mut db, addresses, at_rest_key.as_ref())
         })
         .await
         .unwrap()
@@ -742,19 +1012,27 @@ impl WalletDb {
     /// Get a the serialized value stored at given key
     pub async fn get_db_value(&self, key: &'static str) -> Option<Vec<u8>> {
         let db = self.db.clone();
-        task::spawn_blocking(move || db.lock().unwrap().get_cf(DB_COL_DEFAULT, key).unwrap())
-            .await
-            .unwrap()
+        let at_rest = self.at_rest_key.clone();
+        task::spawn_blocking(move || {
+            let db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
+            get_wallet_value(&db, key, at_rest_key.as_ref()).unwrap()
+        })
+        .await
+        .unwrap()
     }
 
     /// Set a the serialized value stored at given key
     pub async fn set_db_value(&self, key: &'static str, value: Vec<u8>) {
         let db = self.db.clone();
+        let at_rest = self.at_rest_key.clone();
         task::spawn_blocking(move || {
-            db.lock()
-                .unwrap()
-                .put_cf(DB_COL_DEFAULT, key, &value)
-                .unwrap()
+            let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
+            let mut batch = db.batch_writer();
+            put_wallet_value(&mut batch, key, value, at_rest_key.as_ref());
+            let batch = batch.done();
+            db.write(batch).unwrap()
         })
         .await
         .unwrap()
@@ -763,24 +1041,35 @@ impl WalletDb {
     /// Delete value stored at given key
     pub async fn delete_db_value(&self, key: &'static str) {
         let db = self.db.clone();
-        task::spawn_blocking(move || db.lock().unwrap().delete_cf(DB_COL_DEFAULT, key).unwrap())
-            .await
-            .unwrap()
+        let at_rest = self.at_rest_key.clone();
+        task::spawn_blocking(move || {
+            let mut db = db.lock().unwrap();
+            let at_rest_key = at_rest.read().unwrap().clone();
+            let mut batch = db.batch_writer();
+            delete_wallet_value(&mut batch, key, at_rest_key.as_ref());
+            let batch = batch.done();
+            db.write(batch).unwrap()
+        })
+        .await
+        .unwrap()
     }
 
     /// Get the wallet fund store
     pub fn get_fund_store(&self) -> FundStore {
-        get_fund_store(&self.db.lock().unwrap())
+        let db = self.db.lock().unwrap();
+        get_fund_store(&db, self.get_at_rest_key().as_ref())
     }
 
     /// Get the wallet fund store with errors
     pub fn get_fund_store_err(&self) -> Result<FundStore> {
-        get_fund_store_err(&self.db.lock().unwrap())
+        let db = self.db.lock().unwrap();
+        get_fund_store_err(&db, self.get_at_rest_key().as_ref())
     }
 
     /// Get the wallet address
     pub fn get_transaction_store(&self, out_p: &OutPoint) -> TransactionStore {
-        get_transaction_store(&self.db.lock().unwrap(), out_p)
+        let db = self.db.lock().unwrap();
+        get_transaction_store(&db, out_p, self.get_at_rest_key().as_ref())
     }
 
     /// Gets the address store based on a provided key
@@ -789,7 +1078,9 @@ impl WalletDb {
     ///
     ///  * `key_addr` - Key to get the address store for
     pub fn get_address_store(&self, key_addr: &str) -> AddressStore {
-        get_address_store(&self.db.lock().unwrap(), key_addr, &self.encryption_key)
+        let db = self.db.lock().unwrap();
+        let at_rest_key = self.get_at_rest_key();
+        get_address_store(&db, key_addr, &self.encryption_key, at_rest_key.as_ref())
     }
 
     /// Gets the address store based on a provided key, but returns
@@ -799,16 +1090,49 @@ impl WalletDb {
     ///
     ///  * `key_addr` - Key to get the address store for
     pub fn get_address_store_encrypted(&self, key_addr: &str) -> Vec<u8> {
-        get_address_store_encrypted(&self.db.lock().unwrap(), key_addr)
+        let db = self.db.lock().unwrap();
+        get_address_store_encrypted(&db, key_addr, self.get_at_rest_key().as_ref())
     }
 
     /// Get the wallet addresses
     pub fn get_known_addresses(&self) -> Vec<String> {
-        get_known_key_address(&self.db.lock().unwrap())
+        let db = self.db.lock().unwrap();
+        get_known_key_address(&db, self.get_at_rest_key().as_ref())
+            .into_iter()
+            .collect()
+    }
+
+    /// Get the wallet addresses archived out of the active set
+    pub fn get_archived_addresses(&self) -> Vec<String> {
+        let db = self.db.lock().unwrap();
+        get_archived_key_address(&db, self.get_at_rest_key().as_ref())
             .into_iter()
             .collect()
     }
 
+    /// Get all the wallet addresses, active and archived
+    pub fn get_all_addresses(&self) -> Vec<String> {
+        let db = self.db.lock().unwrap();
+        let at_rest_key = self.get_at_rest_key();
+        let mut addresses = get_known_key_address(&db, at_rest_key.as_ref());
+        addresses.extend(get_archived_key_address(&db, at_rest_key.as_ref()));
+        addresses.into_iter().collect()
+    }
+
+    /// Get the active wallet addresses and the archived ones holding funds
+    pub fn get_active_or_funded_addresses(&self) -> Vec<String> {
+        let db = self.db.lock().unwrap();
+        let at_rest_key = self.get_at_rest_key();
+        let archived = get_archived_key_address(&db, at_rest_key.as_ref());
+        let mut addresses = get_known_key_address(&db, at_rest_key.as_ref());
+        addresses.extend(retrieve_non_empty_addresses(
+            archived,
+            &db,
+            at_rest_key.as_ref(),
+        ));
+        addresses.into_iter().collect()
+    }
+
     /// Get the wallet transaction address
     pub fn get_transaction_address(&self, out_p: &OutPoint) -> String {
         self.get_transaction_store(out_p).key_address
@@ -871,31 +1195,116 @@ impl WalletDb {
     }
 }
 
+/// Get the database key a wallet value is stored at.
+/// When encrypted at rest, the key is hashed together with the at rest key
+/// so addresses and outpoints are not visible in the database keys.
+fn wallet_db_key<K: AsRef<[u8]>>(key: K, at_rest_key: Option<&secretbox::Key>) -> Vec<u8> {
+    match at_rest_key {
+        Some(at_rest_key) => {
+            let keyed = [at_rest_key.as_ref(), key.as_ref()].concat();
+            sha3_256::digest(&keyed).to_vec()
+        }
+        None => key.as_ref().to_vec(),
+    }
+}
+
+/// Get a wallet value, decrypting it if the wallet is encrypted at rest
+pub fn get_wallet_value<K: AsRef<[u8]>>(
+    db: &SimpleDb,
+    key: K,
+    at_rest_key: Option<&secretbox::Key>,
+) -> Result<Option<Vec<u8>>> {
+    let value = db.get_cf(DB_COL_DEFAULT, wallet_db_key(&key, at_rest_key))?;
+    Ok(match (at_rest_key, value) {
+        (Some(at_rest_key), Some(value)) => {
+            let entry: AtRestEntry = deserialize(&decrypt_store(value, at_rest_key))?;
+            Some(entry.1)
+        }
+        (_, value) => value,
+    })
+}
+
+/// Put a wallet value, encrypting it with its key if the wallet is encrypted at rest
+pub fn put_wallet_value<K: AsRef<[u8]>>(
+    db: &mut SimpleDbWriteBatch,
+    key: K,
+    value: Vec<u8>,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    let db_key = wallet_db_key(&key, at_rest_key);
+    let value = match at_rest_key {
+        Some(at_rest_key) => {
+            let entry: AtRestEntry = (key.as_ref().to_vec(), value);
+            encrypt_store(serialize(&entry).unwrap(), at_rest_key)
+        }
+        None => value,
+    };
+    db.put_cf(DB_COL_DEFAULT, db_key, value);
+}
+
+/// Delete a wallet value
+pub fn delete_wallet_value<K: AsRef<[u8]>>(
+    db: &mut SimpleDbWriteBatch,
+    key: K,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    db.delete_cf(DB_COL_DEFAULT, wallet_db_key(key, at_rest_key));
+}
+
+/// Get the wallet entries encrypted when the wallet is encrypted at rest
+/// as (database key, key, value), decrypted with the given at rest key
+fn get_at_rest_entries(
+    db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
+) -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    db.iter_cf_clone(DB_COL_DEFAULT)
+        .filter(|(key, _)| !AT_REST_PLAIN_KEYS.iter().any(|k| k.as_bytes() == &key[..]))
+        .map(|(db_key, value)| match at_rest_key {
+            Some(at_rest_key) => {
+                let (key, value): AtRestEntry =
+                    deserialize(&decrypt_store(value, at_rest_key)).unwrap();
+                (db_key, key, value)
+            }
+            None => (db_key.clone(), db_key, value),
+        })
+        .collect()
+}
+
 /// Get the wallet fund store
-pub fn get_fund_store(db: &SimpleDb) -> FundStore {
-    match get_fund_store_err(db) {
+pub fn get_fund_store(db: &SimpleDb, at_rest_key: Option<&secretbox::Key>) -> FundStore {
+    match get_fund_store_err(db, at_rest_key) {
         Ok(v) => v,
         Err(e) => panic!("Failed to access the wallet database with error: {:?}", e),
     }
 }
 
 /// Get the wallet fund store
-pub fn get_fund_store_err(db: &SimpleDb) -> Result<FundStore> {
-    match db.get_cf(DB_COL_DEFAULT, FUND_KEY) {
+pub fn get_fund_store_err(
+    db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
+) -> Result<FundStore> {
+    match get_wallet_value(db, FUND_KEY, at_rest_key) {
         Ok(Some(list)) => Ok(deserialize(&list).unwrap()),
         Ok(None) => Ok(FundStore::default()),
-        Err(e) => Err(e.into()),
+        Err(e) => Err(e),
     }
 }
 
 /// Set the wallet fund store
-pub fn set_fund_store(db: &mut SimpleDbWriteBatch, fund_store: FundStore) {
-    db.put_cf(DB_COL_DEFAULT, FUND_KEY, &serialize(&fund_store).unwrap())
+pub fn set_fund_store(
+    db: &mut SimpleDbWriteBatch,
+    fund_store: FundStore,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    put_wallet_value(db, FUND_KEY, serialize(&fund_store).unwrap(), at_rest_key)
 }
 
 /// Get the wallet known address
-pub fn get_known_key_address(db: &SimpleDb) -> BTreeSet<String> {
-    match db.get_cf(DB_COL_DEFAULT, KNOWN_ADDRESS_KEY) {
+pub fn get_known_key_address(
+    db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
+) -> BTreeSet<String> {
+    match get_wallet_value(db, KNOWN_ADDRESS_KEY, at_rest_key) {
         Ok(Some(list)) => deserialize(&list).unwrap(),
         Ok(None) => Default::default(),
         Err(e) => panic!("Error accessing wallet: {:?}", e),
@@ -903,17 +1312,128 @@ pub fn get_known_key_address(db: &SimpleDb) -> BTreeSet<String> {
 }
 
 /// Set the wallet known address
-pub fn set_known_key_address(db: &mut SimpleDbWriteBatch, address_store: BTreeSet<String>) {
-    db.put_cf(
-        DB_COL_DEFAULT,
-        KNOWN_ADDRESS_KEY,
-        &serialize(&address_store).unwrap(),
-    );
+pub fn set_known_key_address(
+    db: &mut SimpleDbWriteBatch,
+    address_store: BTreeSet<String>,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    let address_store = serialize(&address_store).unwrap();
+    put_wallet_value(db, KNOWN_ADDRESS_KEY, address_store, at_rest_key);
+}
+
+/// Get the wallet archived addresses
+pub fn get_archived_key_address(
+    db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
+) -> BTreeSet<String> {
+    match get_wallet_value(db, ARCHIVED_ADDRESS_KEY, at_rest_key) {
+        Ok(Some(list)) => deserialize(&list).unwrap(),
+        Ok(None) => Default::default(),
+        Err(e) => panic!("Error accessing wallet: {:?}", e),
+    }
+}
+
+/// Set the wallet archived addresses
+pub fn set_archived_key_address(
+    db: &mut SimpleDbWriteBatch,
+    address_store: BTreeSet<String>,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    let address_store = serialize(&address_store).unwrap();
+    put_wallet_value(db, ARCHIVED_ADDRESS_KEY, address_store, at_rest_key);
+}
+
+/// Get the order in which the wallet addresses were last used
+pub fn get_address_last_used(
+    db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
+) -> BTreeMap<String, u64> {
+    match get_wallet_value(db, ADDRESS_LAST_USED_KEY, at_rest_key) {
+        Ok(Some(list)) => deserialize(&list).unwrap(),
+        Ok(None) => Default::default(),
+        Err(e) => panic!("Error accessing wallet: {:?}", e),
+    }
+}
+
+/// Set the order in which the wallet addresses were last used
+pub fn set_address_last_used(
+    db: &mut SimpleDbWriteBatch,
+    last_used: BTreeMap<String, u64>,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    let last_used = serialize(&last_used).unwrap();
+    put_wallet_value(db, ADDRESS_LAST_USED_KEY, last_used, at_rest_key);
+}
+
+/// Make addresses active and most recently used, archiving
+/// unused addresses beyond `max_active`
+pub fn activate_key_addresses(
+    db: &SimpleDb,
+    batch: &mut SimpleDbWriteBatch,
+    addresses: &BTreeSet<String>,
+    max_active: Option<usize>,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    let mut active = get_known_key_address(db, at_rest_key);
+    let mut archived = get_archived_key_address(db, at_rest_key);
+    let mut last_used = get_address_last_used(db, at_rest_key);
+
+    let next_used = last_used.values().max().map_or(0, |v| v + 1);
+    for address in addresses {
+        archived.remove(address);
+        active.insert(address.clone());
+        last_used.insert(address.clone(), next_used);
+    }
+
+    if let Some(max_active) = max_active {
+        archive_unused_addresses(
+            db,
+            &mut active,
+            &mut archived,
+            &last_used,
+            max_active,
+            at_rest_key,
+        );
+    }
+
+    set_known_key_address(batch, active, at_rest_key);
+    set_archived_key_address(batch, archived, at_rest_key);
+    set_address_last_used(batch, last_used, at_rest_key);
+}
+
+/// Archive the least recently used zero-balance addresses
+/// until at most `max_active` addresses remain active
+pub fn archive_unused_addresses(
+    db: &SimpleDb,
+    active: &mut BTreeSet<String>,
+    archived: &mut BTreeSet<String>,
+    last_used: &BTreeMap<String, u64>,
+    max_active: usize,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    if active.len() <= max_active {
+        return;
+    }
+
+    let mut unused: Vec<_> = retrieve_empty_addresses(active.clone(), db, at_rest_key)
+        .into_iter()
+        .collect();
+    unused.sort_by_key(|addr| last_used.get(addr).copied().unwrap_or_default());
+
+    let excess = active.len() - max_active;
+    for address in unused.into_iter().take(excess) {
+        active.remove(&address);
+        archived.insert(address);
+    }
 }
 
 /// Gets the wallet AddressStore in an encrypted state for external storage
-pub fn get_address_store_encrypted(db: &SimpleDb, key_addr: &str) -> Vec<u8> {
-    match db.get_cf(DB_COL_DEFAULT, key_addr) {
+pub fn get_address_store_encrypted(
+    db: &SimpleDb,
+    key_addr: &str,
+    at_rest_key: Option<&secretbox::Key>,
+) -> Vec<u8> {
+    match get_wallet_value(db, key_addr, at_rest_key) {
         Ok(Some(store)) => store,
         Ok(None) => panic!("Key address not present in wallet: {}", key_addr),
         Err(e) => panic!("Error accessing wallet: {:?}", e),
@@ -925,8 +1445,9 @@ pub fn get_address_store(
     db: &SimpleDb,
     key_addr: &str,
     encryption_key: &secretbox::Key,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> AddressStore {
-    match db.get_cf(DB_COL_DEFAULT, key_addr) {
+    match get_wallet_value(db, key_addr, at_rest_key) {
         Ok(Some(store)) => {
             let decrypted = decrypt_store(store, encryption_key);
             deserialize(&decrypted).unwrap()
@@ -937,8 +1458,12 @@ pub fn get_address_store(
 }
 
 /// Delete AddressStore
-pub fn delete_address_store(db: &mut SimpleDbWriteBatch, key_addr: &str) {
-    db.delete_cf(DB_COL_DEFAULT, key_addr);
+pub fn delete_address_store(
+    db: &mut SimpleDbWriteBatch,
+    key_addr: &str,
+    at_rest_key: Option<&secretbox::Key>,
+) {
+    delete_wallet_value(db, key_addr, at_rest_key);
 }
 
 /// Save AddressStore
@@ -947,15 +1472,20 @@ pub fn save_address_store_to_wallet(
     key_addr: &str,
     store: AddressStore,
     encryption_key: &secretbox::Key,
+    at_rest_key: Option<&secretbox::Key>,
 ) {
     let store = serialize(&store).unwrap();
     let input = encrypt_store(store, encryption_key);
-    db.put_cf(DB_COL_DEFAULT, key_addr, &input);
+    put_wallet_value(db, key_addr, input, at_rest_key);
 }
 
 /// Get the wallet transaction store
-pub fn get_transaction_store(db: &SimpleDb, out_p: &OutPoint) -> TransactionStore {
-    match db.get_cf(DB_COL_DEFAULT, serialize(&out_p).unwrap()) {
+pub fn get_transaction_store(
+    db: &SimpleDb,
+    out_p: &OutPoint,
+    at_rest_key: Option<&secretbox::Key>,
+) -> TransactionStore {
+    match get_wallet_value(db, serialize(&out_p).unwrap(), at_rest_key) {
         Ok(Some(store)) => deserialize(&store).unwrap(),
         Ok(None) => panic!("Transaction not present in wallet: {:?}", out_p),
         Err(e) => panic!("Error accessing wallet: {:?}", e),
@@ -963,9 +1493,13 @@ pub fn get_transaction_store(db: &SimpleDb, out_p: &OutPoint) -> TransactionStor
 }
 
 /// Delete transaction store
-pub fn delete_transaction_store(db: &mut SimpleDbWriteBatch, out_p: &OutPoint) {
+pub fn delete_transaction_store(
+    db: &mut SimpleDbWriteBatch,
+    out_p: &OutPoint,
+    at_rest_key: Option<&secretbox::Key>,
+) {
     let key = serialize(&out_p).unwrap();
-    db.delete_cf(DB_COL_DEFAULT, &key);
+    delete_wallet_value(db, key, at_rest_key);
 }
 
 /// Save transaction
@@ -973,21 +1507,34 @@ pub fn save_transaction_to_wallet(
     db: &mut SimpleDbWriteBatch,
     out_p: &OutPoint,
     store: &TransactionStore,
+    at_rest_key: Option<&secretbox::Key>,
 ) {
     let key = serialize(out_p).unwrap();
     let input = serialize(store).unwrap();
-    db.put_cf(DB_COL_DEFAULT, &key, &input);
+    put_wallet_value(db, &key, input, at_rest_key);
 }
 
 // Set a new master key store
 pub fn set_new_master_key_store(
     batch: &mut SimpleDbWriteBatch,
     passphrase: &[u8],
+    kdf: KdfParams,
 ) -> secretbox::Key {
-    let salt = pwhash::gen_salt();
     let master_key = secretbox::gen_key();
+    set_master_key_store(batch, &master_key, passphrase, kdf);
+    master_key
+}
+
+/// Seal the master key with the given passphrase and key derivation parameters
+pub fn set_master_key_store(
+    batch: &mut SimpleDbWriteBatch,
+    master_key: &secretbox::Key,
+    passphrase: &[u8],
+    kdf: KdfParams,
+) {
+    let salt = pwhash::gen_salt();
     let nonce = secretbox::gen_nonce();
-    let pass_key = make_key(passphrase, salt);
+    let pass_key = make_key_with_kdf(passphrase, salt, kdf);
     let enc_master_key = secretbox::seal(master_key.as_ref().to_vec(), &nonce, &pass_key).unwrap();
     let store = serialize(&MasterKeyStore {
         salt,
@@ -996,7 +1543,16 @@ pub fn set_new_master_key_store(
     })
     .unwrap();
     batch.put_cf(DB_COL_DEFAULT, MASTER_KEY_STORE_KEY, &store);
-    master_key
+    batch.put_cf(DB_COL_DEFAULT, MASTER_KEY_KDF_KEY, serialize(&kdf).unwrap());
+}
+
+/// Get the key derivation parameters the master key store was sealed with,
+/// stores sealed before these were recorded use the default parameters
+pub fn get_master_key_kdf(db: &SimpleDb) -> Result<KdfParams> {
+    match db.get_cf(DB_COL_DEFAULT, MASTER_KEY_KDF_KEY)? {
+        Some(kdf) => Ok(deserialize(&kdf)?),
+        None => Ok(Default::default()),
+    }
 }
 
 /// Get master store key with given passphrase
@@ -1004,8 +1560,9 @@ pub fn get_master_key_store(db: &SimpleDb, passphrase: &[u8]) -> Result<secretbo
     let store = db.get_cf(DB_COL_DEFAULT, MASTER_KEY_STORE_KEY)?;
     let store = store.ok_or(WalletDbError::MasterKeyMissingError)?;
     let store: MasterKeyStore = deserialize(&store)?;
+    let kdf = get_master_key_kdf(db)?;
 
-    let pass_key = make_key(passphrase, store.salt);
+    let pass_key = make_key_with_kdf(passphrase, store.salt, kdf);
     let master_key = secretbox::open(store.clone().enc_master_key, &store.nonce, &pass_key)
         .ok_or(WalletDbError::PassphraseError)?;
     let key =
@@ -1013,6 +1570,30 @@ pub fn get_master_key_store(db: &SimpleDb, passphrase: &[u8]) -> Result<secretbo
     Ok(key)
 }
 
+/// Set the key encrypting all wallet values at rest
+pub fn set_at_rest_key_store(
+    batch: &mut SimpleDbWriteBatch,
+    at_rest_key: &secretbox::Key,
+    master_key: &secretbox::Key,
+) {
+    let store = encrypt_store(at_rest_key.as_ref().to_vec(), master_key);
+    batch.put_cf(DB_COL_DEFAULT, AT_REST_KEY_STORE_KEY, &store);
+}
+
+/// Get the key encrypting all wallet values at rest, if the wallet is encrypted at rest
+pub fn get_at_rest_key_store(
+    db: &SimpleDb,
+    master_key: &secretbox::Key,
+) -> Result<Option<secretbox::Key>> {
+    let store = match db.get_cf(DB_COL_DEFAULT, AT_REST_KEY_STORE_KEY)? {
+        Some(store) => store,
+        None => return Ok(None),
+    };
+    let key = decrypt_store(store, master_key);
+    let key = secretbox::Key::from_slice(&key).ok_or(WalletDbError::MasterKeyRetrievalError)?;
+    Ok(Some(key))
+}
+
 /// Get or save master key store
 ///
 /// This function is used during the initial node startup,
@@ -1021,11 +1602,17 @@ pub fn get_or_save_master_key_store(
     db: &SimpleDb,
     batch: &mut SimpleDbWriteBatch,
     passphrase: &[u8],
+    passphrase_config: &PassphraseConfig,
 ) -> Result<secretbox::Key> {
     match get_master_key_store(db, passphrase) {
         Ok(key) => Ok(key),
         Err(WalletDbError::MasterKeyMissingError) => {
-            Ok(set_new_master_key_store(batch, passphrase))
+            passphrase_config.validate(passphrase)?;
+            Ok(set_new_master_key_store(
+                batch,
+                passphrase,
+                passphrase_config.kdf,
+            ))
         }
         Err(e) => Err(e),
     }
@@ -1038,11 +1625,44 @@ pub fn get_or_save_master_key_store(
 /// * `passphrase` - String used as the password when creating the encryption key
 /// * `salt` - Salt value added to the passphrase to ensure safe encryption
 pub fn make_key(passphrase: &[u8], salt: pwhash::Salt) -> secretbox::Key {
+    make_key_with_kdf(passphrase, salt, Default::default())
+}
+
+///Creates a secretbox key with the given key derivation parameters
+///
+/// ### Arguments
+///
+/// * `passphrase` - String used as the password when creating the encryption key
+/// * `salt` - Salt value added to the passphrase to ensure safe encryption
+/// * `kdf` - Key derivation parameters
+pub fn make_key_with_kdf(passphrase: &[u8], salt: pwhash::Salt, kdf: KdfParams) -> secretbox::Key {
     let mut kb = [0; secretbox::KEY_LEN];
-    pwhash::derive_key(&mut kb, passphrase, &salt, pwhash::OPSLIMIT_INTERACTIVE);
+    pwhash::derive_key(&mut kb, passphrase, &salt, kdf.iterations);
     secretbox::Key::from_slice(&kb).unwrap()
 }
 
+/// Estimate the entropy in bits of a passphrase from its length and the
+/// character classes it draws from
+pub fn passphrase_entropy_bits(passphrase: &str) -> u32 {
+    let classes: [(fn(&char) -> bool, u32); 3] = [
+        (char::is_ascii_lowercase, 26),
+        (char::is_ascii_uppercase, 26),
+        (char::is_ascii_digit, 10),
+    ];
+    let mut pool: u32 = classes
+        .iter()
+        .filter(|(in_class, _)| passphrase.chars().any(|c| in_class(&c)))
+        .map(|(_, size)| size)
+        .sum();
+    if passphrase.chars().any(|c| !c.is_ascii_alphanumeric()) {
+        pool += 33;
+    }
+    if pool == 0 {
+        return 0;
+    }
+    (passphrase.chars().count() as f64 * f64::from(pool).log2()) as u32
+}
+
 /// Decrypt a Store value
 pub fn decrypt_store(store: Vec<u8>, encryption_key: &secretbox::Key) -> Vec<u8> {
     let (nonce, output) = store.split_at(secretbox::NONCE_LEN);
@@ -1069,10 +1689,11 @@ pub fn fetch_inputs_for_payment_from_db(
     asset_required: Asset,
     encryption_key: &secretbox::Key,
     locked_coinbase: &LockedCoinbase,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> Result<(Vec<TxConstructor>, Asset, Vec<(OutPoint, String)>)> {
     let mut tx_cons = Vec::new();
     let mut tx_used = Vec::new();
-    let mut fund_store = get_fund_store(db);
+    let mut fund_store = get_fund_store(db, at_rest_key);
     // We need to filter here, because we are fetching inputs for a transaction
     if let Some(count) = fund_store.filter_locked_coinbase(locked_coinbase) {
         warn!("{count} locked coinbase transaction filtered out");
@@ -1085,7 +1706,7 @@ pub fn fetch_inputs_for_payment_from_db(
 
     for (out_p, amount) in fund_store.into_transactions() {
         if amount_made.add_assign(&amount) {
-            let (cons, used) = tx_constructor_from_prev_out(db, out_p, encryption_key);
+            let (cons, used) = tx_constructor_from_prev_out(db, out_p, encryption_key, at_rest_key);
             tx_cons.push(cons);
             tx_used.push(used);
         }
@@ -1105,10 +1726,11 @@ pub fn fetch_inputs_for_payment_from_supplied_input_addrs_db(
     addresses: BTreeSet<String>,
     encryption_key: &secretbox::Key,
     locked_coinbase: &LockedCoinbase,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> Result<(Vec<TxConstructor>, Asset, Vec<(OutPoint, String)>)> {
     // Only use addresses that actually contain assets
-    let addresses_to_use = retrieve_non_empty_addresses(addresses, db);
-    let mut fund_store = get_fund_store(db);
+    let addresses_to_use = retrieve_non_empty_addresses(addresses, db, at_rest_key);
+    let mut fund_store = get_fund_store(db, at_rest_key);
     // We need to filter here because we are fetching inputs for a transaction
     if let Some(count) = fund_store.filter_locked_coinbase(locked_coinbase) {
         warn!("{count} locked coinbase transaction filtered out");
@@ -1119,11 +1741,11 @@ pub fn fetch_inputs_for_payment_from_supplied_input_addrs_db(
     for addr in addresses_to_use {
         fund_store_txs
             .iter()
-            .filter(|(out_p, _)| addr == get_transaction_store(db, out_p).key_address)
+            .filter(|(out_p, _)| addr == get_transaction_store(db, out_p, at_rest_key).key_address)
             .for_each(|(op, asset)| txs_to_use.push((op.clone(), asset.clone())));
     }
 
-    fetch_inputs_from_supplied_txs_for_payment_from_db(db, txs_to_use, encryption_key)
+    fetch_inputs_from_supplied_txs_for_payment_from_db(db, txs_to_use, encryption_key, at_rest_key)
 }
 
 /// Make TxConstructors from stored TxOut
@@ -1133,6 +1755,7 @@ pub fn fetch_inputs_from_supplied_txs_for_payment_from_db(
     db: &SimpleDb,
     addresses: Vec<(OutPoint, Asset)>,
     encryption_key: &secretbox::Key,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> Result<(Vec<TxConstructor>, Asset, Vec<(OutPoint, String)>)> {
     let mut tx_cons = Vec::new();
     let mut tx_used = Vec::new();
@@ -1140,7 +1763,7 @@ pub fn fetch_inputs_from_supplied_txs_for_payment_from_db(
 
     for (out_p, amount) in addresses {
         if amount_made.add_assign(&amount) {
-            let (cons, used) = tx_constructor_from_prev_out(db, out_p, encryption_key);
+            let (cons, used) = tx_constructor_from_prev_out(db, out_p, encryption_key, at_rest_key);
             tx_cons.push(cons);
             tx_used.push(used);
         }
@@ -1154,11 +1777,14 @@ pub fn fetch_inputs_from_supplied_txs_for_payment_from_db(
 pub fn destroy_spent_transactions_and_keys(
     db: &mut SimpleDb,
     addresses: Option<BTreeSet<String>>,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> (BTreeSet<String>, BTreeMap<OutPoint, Asset>) {
-    let empty_addr = retrieve_empty_addresses(addresses.unwrap_or_default(), db);
+    let empty_addr = retrieve_empty_addresses(addresses.unwrap_or_default(), db, at_rest_key);
     let mut batch = db.batch_writer();
-    let mut fund_store = get_fund_store(db);
-    let mut address_store = get_known_key_address(db);
+    let mut fund_store = get_fund_store(db, at_rest_key);
+    let mut address_store = get_known_key_address(db, at_rest_key);
+    let mut archived_store = get_archived_key_address(db, at_rest_key);
+    let mut last_used = get_address_last_used(db, at_rest_key);
 
     //
     // Gather data for update
@@ -1171,12 +1797,12 @@ pub fn destroy_spent_transactions_and_keys(
 
         let unspent_key_addresses: BTreeSet<_> = fund_store_txs
             .keys()
-            .map(|out_p| get_transaction_store(db, out_p).key_address)
+            .map(|out_p| get_transaction_store(db, out_p, at_rest_key).key_address)
             .collect();
 
         let mut spent_addrs: BTreeSet<_> = spent_txs
             .keys()
-            .map(|out_p| get_transaction_store(db, out_p).key_address)
+            .map(|out_p| get_transaction_store(db, out_p, at_rest_key).key_address)
             .filter(|addr| !unspent_key_addresses.contains(addr))
             .collect();
 
@@ -1186,18 +1812,22 @@ pub fn destroy_spent_transactions_and_keys(
 
     for keys_address in &remove_key_addresses {
         address_store.remove(keys_address);
+        archived_store.remove(keys_address);
+        last_used.remove(keys_address);
     }
 
     //
     // Update database
     //
-    set_fund_store(&mut batch, fund_store);
-    set_known_key_address(&mut batch, address_store);
+    set_fund_store(&mut batch, fund_store, at_rest_key);
+    set_known_key_address(&mut batch, address_store, at_rest_key);
+    set_archived_key_address(&mut batch, archived_store, at_rest_key);
+    set_address_last_used(&mut batch, last_used, at_rest_key);
     for keys_address in &remove_key_addresses {
-        delete_address_store(&mut batch, keys_address);
+        delete_address_store(&mut batch, keys_address, at_rest_key);
     }
     for out_p in spent_txs.keys() {
-        delete_transaction_store(&mut batch, out_p);
+        delete_transaction_store(&mut batch, out_p, at_rest_key);
     }
 
     let batch = batch.done();
@@ -1212,17 +1842,19 @@ pub fn destroy_spent_transactions_and_keys(
 /// * `addresses` - The subset of addresses to filter
 /// * `db` - The database to use
 /// * `look_for_empty` - If true, return addresses that DO NOT contain assets
+/// * `at_rest_key` - The key wallet values are encrypted with at rest, if any
 pub fn retrieve_empty_or_non_empty_addresses(
     mut addresses: BTreeSet<String>,
     db: &SimpleDb,
     look_for_empty: bool,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> BTreeSet<String> {
-    let fund_store = get_fund_store(db);
+    let fund_store = get_fund_store(db, at_rest_key);
     let fund_store_txs = fund_store.transactions();
 
     let unspent_key_addresses: BTreeSet<_> = fund_store_txs
         .keys()
-        .map(|out_p| get_transaction_store(db, out_p).key_address)
+        .map(|out_p| get_transaction_store(db, out_p, at_rest_key).key_address)
         .collect();
 
     if look_for_empty {
@@ -1234,16 +1866,21 @@ pub fn retrieve_empty_or_non_empty_addresses(
 }
 
 /// Retrieve addresses from a subset that DO NOT contain assets from the wallet
-pub fn retrieve_empty_addresses(addresses: BTreeSet<String>, db: &SimpleDb) -> BTreeSet<String> {
-    retrieve_empty_or_non_empty_addresses(addresses, db, true)
+pub fn retrieve_empty_addresses(
+    addresses: BTreeSet<String>,
+    db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
+) -> BTreeSet<String> {
+    retrieve_empty_or_non_empty_addresses(addresses, db, true, at_rest_key)
 }
 
 /// Retrieve addresses from a subset that contain assets from the wallet
 pub fn retrieve_non_empty_addresses(
     addresses: BTreeSet<String>,
     db: &SimpleDb,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> BTreeSet<String> {
-    retrieve_empty_or_non_empty_addresses(addresses, db, false)
+    retrieve_empty_or_non_empty_addresses(addresses, db, false, at_rest_key)
 }
 
 /// Make TxConstructor from stored TxOut
@@ -1252,9 +1889,10 @@ pub fn tx_constructor_from_prev_out(
     db: &SimpleDb,
     out_p: OutPoint,
     encryption_key: &secretbox::Key,
+    at_rest_key: Option<&secretbox::Key>,
 ) -> (TxConstructor, (OutPoint, String)) {
-    let key_address = get_transaction_store(db, &out_p).key_address;
-    let needed_store = get_address_store(db, &key_address, encryption_key);
+    let key_address = get_transaction_store(db, &out_p, at_rest_key).key_address;
+    let needed_store = get_address_store(db, &key_address, encryption_key, at_rest_key);
 
     let hash_to_sign = construct_tx_in_signable_hash(&out_p);
     let signature = sign::sign_detached(hash_to_sign.as_bytes(), &needed_store.secret_key);
@@ -1410,4 +2048,253 @@ mod tests {
                 .collect();
         assert_eq!(destroyed_txs, expected_destroyedkeys);
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    /// Archive zero-balance addresses beyond the active limit, keeping funded ones active
+    async fn should_archive_unused_addresses() {
+        //
+        // Arrange
+        //
+        let mut wallet = WalletDb::new(DbMode::InMemory, None, None, None)
+            .unwrap()
+            .with_max_active_addresses(Some(5));
+        let amount = Asset::Token(TokenAmount(3));
+
+        //
+        // Act
+        //
+        let mut funded = Vec::new();
+        for n in 0..3 {
+            let (address, _) = wallet.generate_payment_address().await;
+            let out_p = OutPoint::new(format!("tx_hash_{n}"), 0);
+            wallet
+                .save_usable_payments_to_wallet(
+                    vec![(out_p, amount.clone(), address.clone(), 0)],
+                    0,
+                )
+                .await
+                .unwrap();
+            funded.push(address);
+        }
+        let mut unfunded = Vec::new();
+        for _ in 0..20 {
+            unfunded.push(wallet.generate_payment_address().await.0);
+        }
+        let active_before_restore: BTreeSet<_> = wallet.get_known_addresses().into_iter().collect();
+        let archived_before_restore = wallet.get_archived_addresses();
+
+        let restored = unfunded[0].clone();
+        let out_p = OutPoint::new("tx_hash_restored".to_owned(), 0);
+        wallet
+            .save_usable_payments_to_wallet(vec![(out_p, amount, restored.clone(), 0)], 0)
+            .await
+            .unwrap();
+        let active_after_restore: BTreeSet<_> = wallet.get_known_addresses().into_iter().collect();
+        let archived_after_restore = wallet.get_archived_addresses();
+        let all_after_restore: BTreeSet<_> = wallet.get_all_addresses().into_iter().collect();
+
+        //
+        // Assert
+        //
+        let expected_active: BTreeSet<_> = funded.iter().chain(&unfunded[18..]).cloned().collect();
+        assert_eq!(active_before_restore, expected_active);
+        assert_eq!(archived_before_restore.len(), 18);
+
+        let expected_active: BTreeSet<_> = funded
+            .iter()
+            .chain(&unfunded[19..])
+            .chain(std::iter::once(&restored))
+            .cloned()
+            .collect();
+        assert_eq!(active_after_restore, expected_active);
+        assert_eq!(archived_after_restore.len(), 18);
+        assert!(!archived_after_restore.contains(&restored));
+        let expected_all: BTreeSet<_> = funded.iter().chain(&unfunded).cloned().collect();
+        assert_eq!(all_after_restore, expected_all);
+
+        let archived_keys = wallet.get_address_store(&unfunded[1]);
+        assert_eq!(
+            construct_address_for(&archived_keys.public_key, archived_keys.address_version),
+            unfunded[1]
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn should_encrypt_wallet_at_rest() {
+        //
+        // Arrange
+        //
+        let mut wallet = WalletDb::new(DbMode::InMemory, None, Some("pass".to_owned()), None)
+            .unwrap()
+            .with_encryption_at_rest(true)
+            .unwrap();
+        let out_p = OutPoint::new("tx_hash".to_owned(), 0);
+        let amount = Asset::token_u64(3);
+
+        //
+        // Act
+        //
+        let (address, _) = wallet.generate_payment_address().await;
+        wallet
+            .save_usable_payments_to_wallet(
+                vec![(out_p.clone(), amount.clone(), address.clone(), 0)],
+                0,
+            )
+            .await
+            .unwrap();
+        let fund_store = wallet.get_fund_store();
+        let raw_entries: Vec<_> = (wallet.db.lock().unwrap())
+            .iter_cf_clone(DB_COL_DEFAULT)
+            .collect();
+
+        wallet
+            .change_wallet_passphrase("pass".to_owned(), "new_pass".to_owned())
+            .await
+            .unwrap();
+        let fund_store_after_change = wallet.get_fund_store();
+
+        let db = wallet.take_closed_persistent_store().await;
+        let wrong_pass_result = WalletDb::new(
+            DbMode::InMemory,
+            db.cloned_in_memory(),
+            Some("pass".to_owned()),
+            None,
+        );
+        let reopened = WalletDb::new(
+            DbMode::InMemory,
+            Some(db),
+            Some("new_pass".to_owned()),
+            None,
+        )
+        .unwrap();
+
+        //
+        // Assert
+        //
+        let expected_txs: BTreeMap<_, _> = std::iter::once((out_p.clone(), amount)).collect();
+        assert_eq!(fund_store.transactions(), &expected_txs);
+        let plain_keys = [
+            FUND_KEY.as_bytes().to_vec(),
+            address.as_bytes().to_vec(),
+            serialize(&out_p).unwrap(),
+        ];
+        assert!(raw_entries.iter().all(|(k, _)| !plain_keys.contains(k)));
+        assert!(raw_entries
+            .iter()
+            .all(|(_, v)| *v != serialize(&fund_store).unwrap()));
+        assert_eq!(fund_store_after_change.transactions(), &expected_txs);
+        assert!(matches!(
+            wrong_pass_result,
+            Err(WalletDbError::PassphraseError)
+        ));
+        assert!(reopened.is_encrypted_at_rest());
+        assert_eq!(reopened.get_fund_store().transactions(), &expected_txs);
+    }
+
+    #[test]
+    fn should_reject_weak_passphrase() {
+        //
+        // Arrange
+        //
+        let passphrase_config = PassphraseConfig {
+            min_length: Some(12),
+            min_entropy_bits: Some(60),
+            ..Default::default()
+        };
+        let new_wallet = |passphrase: &str| {
+            WalletDb::new_with_passphrase_config(
+                DbMode::InMemory,
+                None,
+                Some(passphrase.to_owned()),
+                None,
+                passphrase_config,
+            )
+        };
+
+        //
+        // Act
+        //
+        let too_short = new_wallet("Sh0rt!");
+        let low_entropy = new_wallet("aaaaaaaaaaaa");
+        let strong = new_wallet("Correct-Horse-42");
+
+        //
+        // Assert
+        //
+        assert!(matches!(too_short, Err(WalletDbError::WeakPassphrase(_))));
+        assert!(matches!(low_entropy, Err(WalletDbError::WeakPassphrase(_))));
+        assert!(strong.is_ok());
+    }
+
+    #[test]
+    fn should_reject_weak_kdf() {
+        //
+        // Arrange
+        //
+        let new_wallet = |iterations: u32| {
+            let passphrase_config = PassphraseConfig {
+                kdf: KdfParams { iterations },
+                ..Default::default()
+            };
+            WalletDb::new_with_passphrase_config(
+                DbMode::InMemory,
+                None,
+                Some("pass".to_owned()),
+                None,
+                passphrase_config,
+            )
+        };
+
+        //
+        // Act
+        //
+        let zero = new_wallet(0);
+        let below_interactive = new_wallet(KdfParams::default().iterations - 1);
+        let interactive = new_wallet(KdfParams::default().iterations);
+
+        //
+        // Assert
+        //
+        assert!(matches!(zero, Err(WalletDbError::WeakKdf(_))));
+        assert!(matches!(below_interactive, Err(WalletDbError::WeakKdf(_))));
+        assert!(interactive.is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn should_round_trip_with_raised_kdf_cost() {
+        //
+        // Arrange
+        //
+        let kdf = KdfParams {
+            iterations: KdfParams::default().iterations * 2,
+        };
+        let passphrase_config = PassphraseConfig {
+            kdf,
+            ..Default::default()
+        };
+        let mut wallet = WalletDb::new_with_passphrase_config(
+            DbMode::InMemory,
+            None,
+            Some("pass".to_owned()),
+            None,
+            passphrase_config,
+        )
+        .unwrap();
+        let (address, _) = wallet.generate_payment_address().await;
+
+        //
+        // Act
+        //
+        let stored_kdf = get_master_key_kdf(&wallet.db.lock().unwrap()).unwrap();
+        let db = wallet.take_closed_persistent_store().await;
+        let reopened = WalletDb::new(DbMode::InMemory, Some(db), Some("pass".to_owned()), None);
+
+        //
+        // Assert
+        //
+        assert_eq!(stored_kdf, kdf);
+        let reopened = reopened.unwrap();
+        assert!(reopened.test_passphrase("pass".to_owned()).await.is_ok());
+        assert_eq!(reopened.get_known_addresses(), vec![address]);
+    }
 }